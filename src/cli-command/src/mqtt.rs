@@ -17,22 +17,37 @@
 use common_base::enum_type::sort_type::SortType;
 use common_base::tools::unique_id;
 use grpc_clients::mqtt::admin::call::{
-    mqtt_broker_bind_schema, mqtt_broker_cluster_status, mqtt_broker_create_acl,
-    mqtt_broker_create_blacklist, mqtt_broker_create_connector, mqtt_broker_create_schema,
-    mqtt_broker_create_user, mqtt_broker_delete_acl, mqtt_broker_delete_auto_subscribe_rule,
-    mqtt_broker_delete_blacklist, mqtt_broker_delete_connector, mqtt_broker_delete_schema,
-    mqtt_broker_delete_user, mqtt_broker_enable_flapping_detect, mqtt_broker_enable_slow_subscribe,
-    mqtt_broker_list_acl, mqtt_broker_list_auto_subscribe_rule, mqtt_broker_list_bind_schema,
-    mqtt_broker_list_blacklist, mqtt_broker_list_connection, mqtt_broker_list_connector,
-    mqtt_broker_list_schema, mqtt_broker_list_slow_subscribe, mqtt_broker_list_topic,
-    mqtt_broker_list_user, mqtt_broker_set_auto_subscribe_rule, mqtt_broker_unbind_schema,
-    mqtt_broker_update_connector, mqtt_broker_update_schema,
+    mqtt_broker_bind_schema, mqtt_broker_cancel_delay_message, mqtt_broker_clean_session,
+    mqtt_broker_clear_alarm, mqtt_broker_cluster_status, mqtt_broker_create_acl,
+    mqtt_broker_create_blacklist, mqtt_broker_create_connector, mqtt_broker_create_rule,
+    mqtt_broker_create_schema, mqtt_broker_create_tenant, mqtt_broker_create_user,
+    mqtt_broker_delete_acl, mqtt_broker_delete_auto_subscribe_rule, mqtt_broker_delete_blacklist,
+    mqtt_broker_delete_connector, mqtt_broker_delete_retain_message, mqtt_broker_delete_rule,
+    mqtt_broker_delete_schema, mqtt_broker_delete_tenant, mqtt_broker_delete_user,
+    mqtt_broker_enable_flapping_detect, mqtt_broker_enable_slow_subscribe,
+    mqtt_broker_get_retain_message, mqtt_broker_inspect_client, mqtt_broker_inspect_delay_message,
+    mqtt_broker_kick_client, mqtt_broker_list_acl, mqtt_broker_list_alarm,
+    mqtt_broker_list_auto_subscribe_rule, mqtt_broker_list_bind_schema, mqtt_broker_list_blacklist,
+    mqtt_broker_list_client_trace, mqtt_broker_list_connection, mqtt_broker_list_connector,
+    mqtt_broker_list_delay_message, mqtt_broker_list_retain_message, mqtt_broker_list_rule,
+    mqtt_broker_list_schema, mqtt_broker_list_schema_version, mqtt_broker_list_session,
+    mqtt_broker_list_slow_subscribe, mqtt_broker_list_subscribe, mqtt_broker_list_tenant,
+    mqtt_broker_list_topic, mqtt_broker_list_user, mqtt_broker_peek_message,
+    mqtt_broker_publish_message, mqtt_broker_reload_config, mqtt_broker_reload_tls_cert,
+    mqtt_broker_set_auto_subscribe_rule, mqtt_broker_set_client_trace,
+    mqtt_broker_set_tenant_config, mqtt_broker_unbind_schema, mqtt_broker_update_connector,
+    mqtt_broker_update_rule, mqtt_broker_update_schema, mqtt_broker_update_tenant,
 };
 use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::alarm::MqttAlarm;
 use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
 use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+use metadata_struct::mqtt::rule::MqttRule;
+use metadata_struct::mqtt::session::MqttSession;
+use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
+use metadata_struct::mqtt::tenant::MqttTenant;
 use metadata_struct::mqtt::user::MqttUser;
-use metadata_struct::schema::SchemaData;
+use metadata_struct::schema::{SchemaData, SchemaResourceBind};
 use paho_mqtt::{DisconnectOptionsBuilder, MessageBuilder, Properties, PropertyCode, ReasonCode};
 use prettytable::{row, Table};
 use protocol::broker_mqtt::broker_mqtt_admin::{
@@ -41,10 +56,20 @@
     EnableFlappingDetectRequest, EnableSlowSubscribeRequest, ListAclRequest,
     ListAutoSubscribeRuleRequest, ListBlacklistRequest, ListConnectionRequest,
     ListSlowSubscribeRequest, ListTopicRequest, ListUserRequest, MqttBindSchemaRequest,
-    MqttCreateConnectorRequest, MqttCreateSchemaRequest, MqttDeleteConnectorRequest,
-    MqttDeleteSchemaRequest, MqttListBindSchemaRequest, MqttListConnectorRequest,
-    MqttListSchemaRequest, MqttUnbindSchemaRequest, MqttUpdateConnectorRequest,
-    MqttUpdateSchemaRequest, SetAutoSubscribeRuleRequest,
+    MqttCancelDelayMessageRequest, MqttCleanSessionRequest, MqttClearAlarmRequest,
+    MqttCreateConnectorRequest, MqttCreateRuleRequest, MqttCreateSchemaRequest,
+    MqttCreateTenantRequest, MqttDeleteConnectorRequest, MqttDeleteRetainMessageRequest,
+    MqttDeleteRuleRequest, MqttDeleteSchemaRequest, MqttDeleteTenantRequest,
+    MqttGetRetainMessageRequest, MqttInspectClientRequest, MqttInspectDelayMessageRequest,
+    MqttKickClientRequest, MqttListAlarmRequest, MqttListBindSchemaRequest,
+    MqttListClientTraceRequest, MqttListConnectorRequest, MqttListDelayMessageRequest,
+    MqttListRetainMessageRequest, MqttListRuleRequest, MqttListSchemaRequest,
+    MqttListSchemaVersionRequest, MqttListSessionRequest, MqttListSubscribeRequest,
+    MqttListTenantRequest, MqttPeekMessageRequest, MqttPublishMessageRequest,
+    MqttReloadConfigRequest, MqttReloadTlsCertRequest, MqttSetClientTraceRequest,
+    MqttSetTenantConfigRequest, MqttUnbindSchemaRequest, MqttUpdateConnectorRequest,
+    MqttUpdateRuleRequest, MqttUpdateSchemaRequest, MqttUpdateTenantRequest,
+    SetAutoSubscribeRuleRequest,
 };
 use std::str::FromStr;
 use std::sync::Arc;
@@ -64,9 +89,11 @@ pub struct MqttCliCommandParam {
 pub enum MqttActionType {
     // cluster status
     Status,
+    ReloadConfig,
+    ReloadTlsCert,
 
     // user admin
-    ListUser,
+    ListUser(ListUserRequest),
     CreateUser(CreateUserRequest),
     DeleteUser(DeleteUserRequest),
 
@@ -81,7 +108,7 @@ pub enum MqttActionType {
     DeleteBlacklist(DeleteBlacklistRequest),
 
     // connection
-    ListConnection,
+    ListConnection(ListConnectionRequest),
 
     // observability: slow-sub
     EnableSlowSubscribe(EnableSlowSubscribeRequest),
@@ -104,8 +131,44 @@ pub enum MqttActionType {
     UpdateConnector(MqttUpdateConnectorRequest),
     DeleteConnector(MqttDeleteConnectorRequest),
 
+    // rule
+    ListRule(MqttListRuleRequest),
+    CreateRule(MqttCreateRuleRequest),
+    UpdateRule(MqttUpdateRuleRequest),
+    DeleteRule(MqttDeleteRuleRequest),
+
+    // delay message
+    ListDelayMessage(MqttListDelayMessageRequest),
+    InspectDelayMessage(MqttInspectDelayMessageRequest),
+    CancelDelayMessage(MqttCancelDelayMessageRequest),
+
+    // message
+    PublishMessage(MqttPublishMessageRequest),
+    PeekMessage(MqttPeekMessageRequest),
+
+    // retain message
+    ListRetainMessage(MqttListRetainMessageRequest),
+    GetRetainMessage(MqttGetRetainMessageRequest),
+    DeleteRetainMessage(MqttDeleteRetainMessageRequest),
+
+    // alarm
+    ListAlarm(MqttListAlarmRequest),
+    ClearAlarm(MqttClearAlarmRequest),
+
+    // trace
+    SetClientTrace(MqttSetClientTraceRequest),
+    ListClientTrace(MqttListClientTraceRequest),
+
+    // client & session
+    ListSession(MqttListSessionRequest),
+    ListSubscribe(MqttListSubscribeRequest),
+    InspectClient(MqttInspectClientRequest),
+    KickClient(MqttKickClientRequest),
+    CleanSession(MqttCleanSessionRequest),
+
     // schema
     ListSchema(MqttListSchemaRequest),
+    ListSchemaVersion(MqttListSchemaVersionRequest),
     CreateSchema(MqttCreateSchemaRequest),
     UpdateSchema(MqttUpdateSchemaRequest),
     DeleteSchema(MqttDeleteSchemaRequest),
@@ -117,6 +180,13 @@ pub enum MqttActionType {
     ListAutoSubscribeRule(ListAutoSubscribeRuleRequest),
     SetAutoSubscribeRule(SetAutoSubscribeRuleRequest),
     DeleteAutoSubscribeRule(DeleteAutoSubscribeRuleRequest),
+
+    // tenant
+    ListTenant(MqttListTenantRequest),
+    CreateTenant(MqttCreateTenantRequest),
+    UpdateTenant(MqttUpdateTenantRequest),
+    DeleteTenant(MqttDeleteTenantRequest),
+    SetTenantConfig(MqttSetTenantConfigRequest),
 }
 
 pub struct MqttBrokerCommand {}
@@ -139,9 +209,16 @@ pub async fn start(&self, params: MqttCliCommandParam) {
             MqttActionType::Status => {
                 self.status(&client_pool, params.clone()).await;
             }
+            MqttActionType::ReloadConfig => {
+                self.reload_config(&client_pool, params.clone()).await;
+            }
+            MqttActionType::ReloadTlsCert => {
+                self.reload_tls_cert(&client_pool, params.clone()).await;
+            }
             // user admin
-            MqttActionType::ListUser => {
-                self.list_user(&client_pool, params.clone()).await;
+            MqttActionType::ListUser(ref request) => {
+                self.list_user(&client_pool, params.clone(), request.clone())
+                    .await;
             }
             MqttActionType::CreateUser(ref request) => {
                 self.create_user(&client_pool, params.clone(), request.clone())
@@ -176,8 +253,9 @@ pub async fn start(&self, params: MqttCliCommandParam) {
                     .await;
             }
             // connection
-            MqttActionType::ListConnection => {
-                self.list_connections(&client_pool, params.clone()).await;
+            MqttActionType::ListConnection(ref request) => {
+                self.list_connections(&client_pool, params.clone(), request.clone())
+                    .await;
             }
             MqttActionType::EnableSlowSubscribe(ref request) => {
                 self.enable_slow_subscribe(&client_pool, params.clone(), *request)
@@ -218,11 +296,113 @@ pub async fn start(&self, params: MqttCliCommandParam) {
                     .await;
             }
 
+            // rule
+            MqttActionType::ListRule(ref request) => {
+                self.list_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::CreateRule(ref request) => {
+                self.create_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::UpdateRule(ref request) => {
+                self.update_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::DeleteRule(ref request) => {
+                self.delete_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
+            // delay message
+            MqttActionType::ListDelayMessage(ref request) => {
+                self.list_delay_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::InspectDelayMessage(ref request) => {
+                self.inspect_delay_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::CancelDelayMessage(ref request) => {
+                self.cancel_delay_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
+            // message
+            MqttActionType::PublishMessage(ref request) => {
+                self.publish_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::PeekMessage(ref request) => {
+                self.peek_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
+            // retain message
+            MqttActionType::ListRetainMessage(ref request) => {
+                self.list_retain_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::GetRetainMessage(ref request) => {
+                self.get_retain_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::DeleteRetainMessage(ref request) => {
+                self.delete_retain_message(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
+            // alarm
+            MqttActionType::ListAlarm(ref request) => {
+                self.list_alarm(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ClearAlarm(ref request) => {
+                self.clear_alarm(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
+            // trace
+            MqttActionType::SetClientTrace(ref request) => {
+                self.set_client_trace(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListClientTrace(ref request) => {
+                self.list_client_trace(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
+            // client & session
+            MqttActionType::ListSession(ref request) => {
+                self.list_session(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListSubscribe(ref request) => {
+                self.list_subscribe(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::InspectClient(ref request) => {
+                self.inspect_client(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::KickClient(ref request) => {
+                self.kick_client(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::CleanSession(ref request) => {
+                self.clean_session(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+
             // schema
             MqttActionType::ListSchema(ref request) => {
                 self.list_schema(&client_pool, params.clone(), request.clone())
                     .await;
             }
+            MqttActionType::ListSchemaVersion(ref request) => {
+                self.list_schema_version(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
             MqttActionType::CreateSchema(ref request) => {
                 self.create_schema(&client_pool, params.clone(), request.clone())
                     .await;
@@ -261,6 +441,27 @@ pub async fn start(&self, params: MqttCliCommandParam) {
                 self.delete_auto_subscribe_rule(&client_pool, params.clone(), request.clone())
                     .await;
             }
+            // tenant
+            MqttActionType::ListTenant(ref request) => {
+                self.list_tenant(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::CreateTenant(ref request) => {
+                self.create_tenant(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::UpdateTenant(ref request) => {
+                self.update_tenant(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::DeleteTenant(ref request) => {
+                self.delete_tenant(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::SetTenantConfig(ref request) => {
+                self.set_tenant_config(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
         }
     }
     async fn publish(&self, params: MqttCliCommandParam, args: PublishArgsRequest) {
@@ -413,6 +614,44 @@ async fn status(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
         }
     }
 
+    async fn reload_config(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
+        let request = MqttReloadConfigRequest {};
+        match mqtt_broker_reload_config(client_pool, &grpc_addr(params.server), request).await {
+            Ok(data) => {
+                println!("config reloaded, applied settings:");
+                for path in data.applied {
+                    println!("- {}", path);
+                }
+                if !data.restart_required.is_empty() {
+                    println!("changed settings that need a restart:");
+                    for path in data.restart_required {
+                        println!("- {}", path);
+                    }
+                }
+            }
+            Err(e) => {
+                println!("MQTT broker reload config exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn reload_tls_cert(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
+        let request = MqttReloadTlsCertRequest {};
+        match mqtt_broker_reload_tls_cert(client_pool, &grpc_addr(params.server), request).await {
+            Ok(data) => {
+                println!("TLS certificates reloaded");
+                for server_name in data.server_names {
+                    println!("- sni: {}", server_name);
+                }
+            }
+            Err(e) => {
+                println!("MQTT broker reload tls cert exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
     async fn create_user(
         &self,
         client_pool: &ClientPool,
@@ -447,9 +686,13 @@ async fn delete_user(
         }
     }
 
-    async fn list_user(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
-        let request = ListUserRequest {};
-        match mqtt_broker_list_user(client_pool, &grpc_addr(params.server), request).await {
+    async fn list_user(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: ListUserRequest,
+    ) {
+        match mqtt_broker_list_user(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(data) => {
                 // format table
                 let mut table = Table::new();
@@ -602,9 +845,14 @@ async fn list_blacklist(&self, client_pool: &ClientPool, params: MqttCliCommandP
         }
     }
 
-    async fn list_connections(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
-        let request = ListConnectionRequest {};
-        match mqtt_broker_list_connection(client_pool, &grpc_addr(params.server), request).await {
+    async fn list_connections(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: ListConnectionRequest,
+    ) {
+        match mqtt_broker_list_connection(client_pool, &grpc_addr(params.server), cli_request).await
+        {
             Ok(data) => {
                 let mut table = Table::new();
 
@@ -807,6 +1055,11 @@ async fn list_connectors(
                     "topic id",
                     "status",
                     "broker id",
+                    "delivered",
+                    "failed",
+                    "lag(s)",
+                    "committed offset",
+                    "last error",
                     "create time",
                     "update time",
                 ]);
@@ -821,6 +1074,11 @@ async fn list_connectors(
                         connector.topic_id,
                         connector.status,
                         connector.broker_id.unwrap_or(0),
+                        connector.stats.delivered_count,
+                        connector.stats.failed_count,
+                        connector.stats.lag,
+                        connector.stats.committed_offset,
+                        connector.stats.last_error.unwrap_or_default(),
                         connector.create_time,
                         connector.update_time
                     ]);
@@ -893,256 +1151,1048 @@ async fn update_connector(
         }
     }
 
-    // ------------------ schema ----------------
-    async fn list_schema(
+    // ------------------ rule ----------------
+    async fn list_rule(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttListSchemaRequest,
+        cli_request: MqttListRuleRequest,
     ) {
-        match mqtt_broker_list_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+        match mqtt_broker_list_rule(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(data) => {
-                println!("schema list result:");
-                for mqtt_schema in data.schemas {
-                    let schema = serde_json::from_slice::<SchemaData>(&mqtt_schema).unwrap();
-                    println!(
-                        concat!(
-                            "cluster name: {}\n",
-                            "schema name: {}\n",
-                            "schema type: {}\n",
-                            "schema desc: {}\n",
-                            "schema: {}\n"
-                        ),
-                        schema.cluster_name,
-                        schema.name,
-                        schema.schema_type,
-                        schema.desc,
-                        schema.schema
-                    );
+                println!("rule list result:");
+                let mut table = Table::new();
+
+                table.add_row(row![
+                    "rule name",
+                    "sql",
+                    "actions",
+                    "enable",
+                    "desc",
+                    "create time",
+                    "update time",
+                ]);
+
+                for raw in data.rules {
+                    match MqttRule::decode(&raw) {
+                        Ok(rule) => {
+                            table.add_row(row![
+                                rule.rule_name,
+                                rule.sql,
+                                serde_json::to_string(&rule.actions).unwrap_or_default(),
+                                rule.enable,
+                                rule.desc,
+                                rule.create_time,
+                                rule.update_time
+                            ]);
+                        }
+                        Err(e) => {
+                            error_info(e.to_string());
+                        }
+                    }
                 }
+
+                // output cmd
+                table.printstd()
             }
             Err(e) => {
-                println!("MQTT broker list schema exception");
+                println!("MQTT broker list rule exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn create_schema(
+    async fn create_rule(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttCreateSchemaRequest,
+        cli_request: MqttCreateRuleRequest,
     ) {
-        match mqtt_broker_create_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+        match mqtt_broker_create_rule(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(_) => {
                 println!("Created successfully!")
             }
             Err(e) => {
-                println!("MQTT broker create schema exception");
+                println!("MQTT broker create rule exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn update_schema(
+    async fn update_rule(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttUpdateSchemaRequest,
+        cli_request: MqttUpdateRuleRequest,
     ) {
-        match mqtt_broker_update_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+        match mqtt_broker_update_rule(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(_) => {
                 println!("Updated successfully!")
             }
             Err(e) => {
-                println!("MQTT broker update schema exception");
+                println!("MQTT broker update rule exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn delete_schema(
+    async fn delete_rule(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttDeleteSchemaRequest,
+        cli_request: MqttDeleteRuleRequest,
     ) {
-        match mqtt_broker_delete_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+        match mqtt_broker_delete_rule(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(_) => {
                 println!("Deleted successfully!")
             }
             Err(e) => {
-                println!("MQTT broker delete schema exception");
+                println!("MQTT broker delete rule exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn bind_schema(
+    // ------------------ tenant ----------------
+    async fn list_tenant(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttBindSchemaRequest,
+        cli_request: MqttListTenantRequest,
     ) {
-        match mqtt_broker_bind_schema(client_pool, &grpc_addr(params.server), cli_request).await {
-            Ok(_) => {
-                println!("Created successfully!")
+        match mqtt_broker_list_tenant(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(data) => {
+                println!("tenant list result:");
+                let mut table = Table::new();
+
+                table.add_row(row![
+                    "tenant name",
+                    "desc",
+                    "max connections",
+                    "max sessions",
+                    "max topics",
+                    "max message rate",
+                    "max storage bytes",
+                    "connections",
+                    "sessions",
+                    "topics",
+                    "storage bytes",
+                ]);
+
+                for raw in data.tenants {
+                    match MqttTenant::decode(&raw.tenant) {
+                        Ok(tenant) => {
+                            table.add_row(row![
+                                tenant.tenant_name,
+                                tenant.desc,
+                                tenant.quota.max_connections,
+                                tenant.quota.max_sessions,
+                                tenant.quota.max_topics,
+                                tenant.quota.max_message_rate,
+                                tenant.quota.max_storage_bytes,
+                                raw.connection_num,
+                                raw.session_num,
+                                raw.topic_num,
+                                raw.storage_bytes
+                            ]);
+                        }
+                        Err(e) => {
+                            error_info(e.to_string());
+                        }
+                    }
+                }
+
+                // output cmd
+                table.printstd()
             }
             Err(e) => {
-                println!("MQTT broker create schema exception");
+                println!("MQTT broker list tenant exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn unbind_schema(
+    async fn create_tenant(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttUnbindSchemaRequest,
+        cli_request: MqttCreateTenantRequest,
     ) {
-        match mqtt_broker_unbind_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+        match mqtt_broker_create_tenant(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(_) => {
-                println!("Deleted successfully!")
+                println!("Created successfully!")
             }
             Err(e) => {
-                println!("MQTT broker delete schema exception");
+                println!("MQTT broker create tenant exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn list_bind_schema(
+    async fn update_tenant(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: MqttListBindSchemaRequest,
+        cli_request: MqttUpdateTenantRequest,
     ) {
-        match mqtt_broker_list_bind_schema(client_pool, &grpc_addr(params.server), cli_request)
-            .await
-        {
-            Ok(data) => {
-                println!("bind schema list result:");
-                for mqtt_schema in data.schema_binds {
-                    let schema = serde_json::from_slice::<SchemaData>(&mqtt_schema).unwrap();
-                    println!(
-                        concat!(
-                            "cluster name: {}\n",
-                            "schema name: {}\n",
-                            "schema type: {}\n",
-                            "schema desc: {}\n",
-                            "schema: {}\n"
-                        ),
-                        schema.cluster_name,
-                        schema.name,
-                        schema.schema_type,
-                        schema.desc,
-                        schema.schema
-                    );
-                }
+        match mqtt_broker_update_tenant(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Updated successfully!")
             }
             Err(e) => {
-                println!("MQTT broker list bind schema exception");
+                println!("MQTT broker update tenant exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    // ------------------ auto subscribe ----------------
-    async fn set_auto_subscribe_rule(
+    async fn delete_tenant(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: SetAutoSubscribeRuleRequest,
+        cli_request: MqttDeleteTenantRequest,
     ) {
-        match mqtt_broker_set_auto_subscribe_rule(
-            client_pool,
-            &grpc_addr(params.server),
-            cli_request,
-        )
-        .await
-        {
+        match mqtt_broker_delete_tenant(client_pool, &grpc_addr(params.server), cli_request).await {
             Ok(_) => {
-                println!("Created successfully!")
+                println!("Deleted successfully!")
             }
             Err(e) => {
-                println!("MQTT broker set auto subscribe rule normal exception");
+                println!("MQTT broker delete tenant exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn delete_auto_subscribe_rule(
+    async fn set_tenant_config(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: DeleteAutoSubscribeRuleRequest,
+        cli_request: MqttSetTenantConfigRequest,
     ) {
-        match mqtt_broker_delete_auto_subscribe_rule(
-            client_pool,
-            &grpc_addr(params.server),
-            cli_request,
-        )
-        .await
+        match mqtt_broker_set_tenant_config(client_pool, &grpc_addr(params.server), cli_request)
+            .await
         {
             Ok(_) => {
-                println!("Deleted successfully!");
+                println!("Set tenant config successfully!")
             }
             Err(e) => {
-                println!("MQTT broker delete auto subscribe rule normal exception");
+                println!("MQTT broker set tenant config exception");
                 error_info(e.to_string());
             }
         }
     }
 
-    async fn list_auto_subscribe_rule(
+    // ------------------ delay message ----------------
+    async fn list_delay_message(
         &self,
         client_pool: &ClientPool,
         params: MqttCliCommandParam,
-        cli_request: ListAutoSubscribeRuleRequest,
+        cli_request: MqttListDelayMessageRequest,
     ) {
-        let _ = cli_request;
-        let request = ListAutoSubscribeRuleRequest {};
-        match mqtt_broker_list_auto_subscribe_rule(client_pool, &grpc_addr(params.server), request)
+        match mqtt_broker_list_delay_message(client_pool, &grpc_addr(params.server), cli_request)
             .await
         {
             Ok(data) => {
-                // format table
+                println!("delay message list result:");
                 let mut table = Table::new();
+
                 table.add_row(row![
-                    "topic",
-                    "qos",
-                    "no_local",
-                    "retain_as_published",
-                    "retained_handling",
+                    "delay id",
+                    "target topic",
+                    "due timestamp",
+                    "create time",
+                    "shard name",
+                    "offset",
                 ]);
-                for rule in data.auto_subscribe_rules {
-                    let mqtt_auto_subscribe_rule =
-                        match serde_json::from_slice::<MqttAutoSubscribeRule>(rule.as_slice()) {
-                            Ok(rule) => rule,
-                            Err(e) => {
-                                error_info(e.to_string());
-                                continue;
-                            }
-                        };
+
+                for raw in data.delay_messages {
                     table.add_row(row![
-                        mqtt_auto_subscribe_rule.topic,
-                        Into::<u8>::into(mqtt_auto_subscribe_rule.qos),
-                        mqtt_auto_subscribe_rule.no_local,
-                        mqtt_auto_subscribe_rule.retain_as_published,
-                        Into::<u8>::into(mqtt_auto_subscribe_rule.retained_handling)
+                        raw.delay_id,
+                        raw.target_topic,
+                        raw.due_timestamp,
+                        raw.create_time,
+                        raw.shard_name,
+                        raw.offset
                     ]);
                 }
+
                 // output cmd
                 table.printstd()
             }
             Err(e) => {
-                println!("MQTT broker list auto subscribe rule exception");
+                println!("MQTT broker list delay message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn inspect_delay_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttInspectDelayMessageRequest,
+    ) {
+        match mqtt_broker_inspect_delay_message(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                let mut table = Table::new();
+                if let Some(raw) = data.delay_message {
+                    table.add_row(row!["delay id", raw.delay_id]);
+                    table.add_row(row!["target topic", raw.target_topic]);
+                    table.add_row(row!["due timestamp", raw.due_timestamp]);
+                    table.add_row(row!["create time", raw.create_time]);
+                    table.add_row(row!["shard name", raw.shard_name]);
+                    table.add_row(row!["offset", raw.offset]);
+                }
+                table.add_row(row!["client id", data.client_id]);
+                table.add_row(row!["qos", data.qos]);
+                table.add_row(row![
+                    "payload",
+                    String::from_utf8_lossy(&data.payload).to_string()
+                ]);
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker inspect delay message exception");
                 error_info(e.to_string());
             }
         }
     }
+
+    async fn cancel_delay_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttCancelDelayMessageRequest,
+    ) {
+        match mqtt_broker_cancel_delay_message(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(_) => {
+                println!("Cancelled successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker cancel delay message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ message ----------------
+    async fn publish_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttPublishMessageRequest,
+    ) {
+        match mqtt_broker_publish_message(client_pool, &grpc_addr(params.server), cli_request).await
+        {
+            Ok(data) => {
+                println!(
+                    "Published to topic {} successfully! offset: {}",
+                    data.topic_name, data.offset
+                )
+            }
+            Err(e) => {
+                println!("MQTT broker publish message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn peek_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttPeekMessageRequest,
+    ) {
+        match mqtt_broker_peek_message(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(data) => {
+                println!("peek message result:");
+                let mut table = Table::new();
+
+                table.add_row(row![
+                    "offset",
+                    "client id",
+                    "qos",
+                    "retain",
+                    "create time",
+                    "content type",
+                    "user properties",
+                    "payload",
+                ]);
+
+                for raw in data.messages {
+                    table.add_row(row![
+                        raw.offset,
+                        raw.client_id,
+                        raw.qos,
+                        raw.retain,
+                        raw.create_time,
+                        raw.content_type,
+                        raw.user_properties.join(","),
+                        String::from_utf8_lossy(&raw.payload).to_string()
+                    ]);
+                }
+
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker peek message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ retain message ----------------
+    async fn list_retain_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListRetainMessageRequest,
+    ) {
+        match mqtt_broker_list_retain_message(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                println!("retain message list result:");
+                let mut table = Table::new();
+
+                table.add_row(row![
+                    "topic name",
+                    "client id",
+                    "qos",
+                    "payload size",
+                    "create time",
+                    "expired at",
+                    "offset",
+                ]);
+
+                for raw in data.retain_messages {
+                    table.add_row(row![
+                        raw.topic_name,
+                        raw.client_id,
+                        raw.qos,
+                        raw.payload_size,
+                        raw.create_time,
+                        raw.expired_at,
+                        raw.offset
+                    ]);
+                }
+
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list retain message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn get_retain_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttGetRetainMessageRequest,
+    ) {
+        match mqtt_broker_get_retain_message(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                let mut table = Table::new();
+                if let Some(raw) = data.retain_message {
+                    table.add_row(row!["topic name", raw.topic_name]);
+                    table.add_row(row!["client id", raw.client_id]);
+                    table.add_row(row!["qos", raw.qos]);
+                    table.add_row(row!["payload size", raw.payload_size]);
+                    table.add_row(row!["create time", raw.create_time]);
+                    table.add_row(row!["expired at", raw.expired_at]);
+                    table.add_row(row!["offset", raw.offset]);
+                }
+                table.add_row(row!["content type", data.content_type]);
+                table.add_row(row!["user properties", data.user_properties.join(",")]);
+                table.add_row(row![
+                    "payload",
+                    String::from_utf8_lossy(&data.payload).to_string()
+                ]);
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker get retain message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_retain_message(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttDeleteRetainMessageRequest,
+    ) {
+        match mqtt_broker_delete_retain_message(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                println!(
+                    "Deleted {} retained messages successfully!",
+                    data.deleted_num
+                )
+            }
+            Err(e) => {
+                println!("MQTT broker delete retain message exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ alarm ----------------
+    async fn list_alarm(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListAlarmRequest,
+    ) {
+        match mqtt_broker_list_alarm(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(data) => {
+                println!("alarm list result:");
+                let mut table = Table::new();
+
+                table.add_row(row![
+                    "alarm id",
+                    "name",
+                    "broker id",
+                    "resource",
+                    "message",
+                    "activate at",
+                    "deactivate at",
+                ]);
+
+                for raw in data.alarms {
+                    let alarm = serde_json::from_slice::<MqttAlarm>(&raw).unwrap();
+                    table.add_row(row![
+                        alarm.alarm_id,
+                        alarm.name,
+                        alarm.broker_id,
+                        alarm.resource,
+                        alarm.message,
+                        alarm.activate_at,
+                        if alarm.is_active() {
+                            "-".to_string()
+                        } else {
+                            alarm.deactivate_at.to_string()
+                        }
+                    ]);
+                }
+
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list alarm exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn clear_alarm(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttClearAlarmRequest,
+    ) {
+        match mqtt_broker_clear_alarm(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(data) => {
+                println!("Cleared {} alarms successfully!", data.cleared)
+            }
+            Err(e) => {
+                println!("MQTT broker clear alarm exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ trace ----------------
+    async fn set_client_trace(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttSetClientTraceRequest,
+    ) {
+        let enable = cli_request.enable;
+        match mqtt_broker_set_client_trace(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(_) => {
+                if enable {
+                    println!("Client trace enabled successfully!")
+                } else {
+                    println!("Client trace disabled successfully!")
+                }
+            }
+            Err(e) => {
+                println!("MQTT broker set client trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_client_trace(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListClientTraceRequest,
+    ) {
+        match mqtt_broker_list_client_trace(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                println!("traced client list result:");
+                let mut table = Table::new();
+                table.add_row(row!["client id"]);
+                for client_id in data.client_ids {
+                    table.add_row(row![client_id]);
+                }
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list client trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ client & session ----------------
+    async fn list_session(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListSessionRequest,
+    ) {
+        match mqtt_broker_list_session(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(data) => {
+                println!("session list result:");
+                let mut table = Table::new();
+                table.add_row(row![
+                    "client_id",
+                    "broker_id",
+                    "connection_id",
+                    "session_expiry",
+                    "create_time",
+                    "reconnect_time",
+                    "distinct_time"
+                ]);
+                for raw in data.sessions {
+                    let session = match serde_json::from_slice::<MqttSession>(&raw) {
+                        Ok(session) => session,
+                        Err(e) => {
+                            error_info(e.to_string());
+                            continue;
+                        }
+                    };
+                    table.add_row(row![
+                        session.client_id,
+                        option_to_string(session.broker_id),
+                        option_to_string(session.connection_id),
+                        session.session_expiry,
+                        session.create_time,
+                        option_to_string(session.reconnect_time),
+                        option_to_string(session.distinct_time)
+                    ]);
+                }
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list session exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_subscribe(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListSubscribeRequest,
+    ) {
+        match mqtt_broker_list_subscribe(client_pool, &grpc_addr(params.server), cli_request).await
+        {
+            Ok(data) => {
+                println!("subscribe list result:");
+                let mut table = Table::new();
+                table.add_row(row!["client_id", "path", "broker_id", "qos", "no_local"]);
+                for raw in data.subscribes {
+                    let subscribe = match serde_json::from_slice::<MqttSubscribe>(&raw) {
+                        Ok(subscribe) => subscribe,
+                        Err(e) => {
+                            error_info(e.to_string());
+                            continue;
+                        }
+                    };
+                    table.add_row(row![
+                        subscribe.client_id,
+                        subscribe.path,
+                        subscribe.broker_id,
+                        format!("{:?}", subscribe.filter.qos),
+                        subscribe.filter.nolocal
+                    ]);
+                }
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list subscribe exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn inspect_client(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttInspectClientRequest,
+    ) {
+        match mqtt_broker_inspect_client(client_pool, &grpc_addr(params.server), cli_request).await
+        {
+            Ok(data) => match serde_json::from_slice::<serde_json::Value>(&data.detail) {
+                Ok(detail) => {
+                    println!("client detail:");
+                    println!("{}", serde_json::to_string_pretty(&detail).unwrap());
+                }
+                Err(e) => error_info(e.to_string()),
+            },
+            Err(e) => {
+                println!("MQTT broker inspect client exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn kick_client(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttKickClientRequest,
+    ) {
+        match mqtt_broker_kick_client(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => println!("Client kicked successfully!"),
+            Err(e) => {
+                println!("MQTT broker kick client exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn clean_session(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttCleanSessionRequest,
+    ) {
+        match mqtt_broker_clean_session(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => println!("Session cleaned successfully!"),
+            Err(e) => {
+                println!("MQTT broker clean session exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ schema ----------------
+    async fn list_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListSchemaRequest,
+    ) {
+        match mqtt_broker_list_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(data) => {
+                println!("schema list result:");
+                for mqtt_schema in data.schemas {
+                    let schema = serde_json::from_slice::<SchemaData>(&mqtt_schema).unwrap();
+                    print_schema(&schema);
+                }
+            }
+            Err(e) => {
+                println!("MQTT broker list schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_schema_version(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListSchemaVersionRequest,
+    ) {
+        match mqtt_broker_list_schema_version(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                println!("schema version list result:");
+                for mqtt_schema in data.schemas {
+                    let schema = serde_json::from_slice::<SchemaData>(&mqtt_schema).unwrap();
+                    print_schema(&schema);
+                }
+            }
+            Err(e) => {
+                println!("MQTT broker list schema version exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn create_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttCreateSchemaRequest,
+    ) {
+        match mqtt_broker_create_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker create schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn update_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttUpdateSchemaRequest,
+    ) {
+        match mqtt_broker_update_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Updated successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker update schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttDeleteSchemaRequest,
+    ) {
+        match mqtt_broker_delete_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Deleted successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker delete schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn bind_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttBindSchemaRequest,
+    ) {
+        match mqtt_broker_bind_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker create schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn unbind_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttUnbindSchemaRequest,
+    ) {
+        match mqtt_broker_unbind_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Deleted successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker delete schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_bind_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: MqttListBindSchemaRequest,
+    ) {
+        match mqtt_broker_list_bind_schema(client_pool, &grpc_addr(params.server), cli_request)
+            .await
+        {
+            Ok(data) => {
+                println!("bind schema list result:");
+                for mqtt_schema_bind in data.schema_binds {
+                    let bind =
+                        serde_json::from_slice::<SchemaResourceBind>(&mqtt_schema_bind).unwrap();
+                    println!(
+                        concat!(
+                            "cluster name: {}\n",
+                            "schema name: {}\n",
+                            "resource name: {}\n",
+                            "schema version: {}\n"
+                        ),
+                        bind.cluster_name,
+                        bind.schema_name,
+                        bind.resource_name,
+                        match bind.schema_version {
+                            Some(version) => version.to_string(),
+                            None => "latest".to_string(),
+                        }
+                    );
+                }
+            }
+            Err(e) => {
+                println!("MQTT broker list bind schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------------ auto subscribe ----------------
+    async fn set_auto_subscribe_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: SetAutoSubscribeRuleRequest,
+    ) {
+        match mqtt_broker_set_auto_subscribe_rule(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker set auto subscribe rule normal exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_auto_subscribe_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: DeleteAutoSubscribeRuleRequest,
+    ) {
+        match mqtt_broker_delete_auto_subscribe_rule(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("MQTT broker delete auto subscribe rule normal exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_auto_subscribe_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: ListAutoSubscribeRuleRequest,
+    ) {
+        let _ = cli_request;
+        let request = ListAutoSubscribeRuleRequest {};
+        match mqtt_broker_list_auto_subscribe_rule(client_pool, &grpc_addr(params.server), request)
+            .await
+        {
+            Ok(data) => {
+                // format table
+                let mut table = Table::new();
+                table.add_row(row![
+                    "topic",
+                    "qos",
+                    "no_local",
+                    "retain_as_published",
+                    "retained_handling",
+                ]);
+                for rule in data.auto_subscribe_rules {
+                    let mqtt_auto_subscribe_rule =
+                        match serde_json::from_slice::<MqttAutoSubscribeRule>(rule.as_slice()) {
+                            Ok(rule) => rule,
+                            Err(e) => {
+                                error_info(e.to_string());
+                                continue;
+                            }
+                        };
+                    table.add_row(row![
+                        mqtt_auto_subscribe_rule.topic,
+                        Into::<u8>::into(mqtt_auto_subscribe_rule.qos),
+                        mqtt_auto_subscribe_rule.no_local,
+                        mqtt_auto_subscribe_rule.retain_as_published,
+                        Into::<u8>::into(mqtt_auto_subscribe_rule.retained_handling)
+                    ]);
+                }
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list auto subscribe rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+}
+
+fn option_to_string<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_schema(schema: &SchemaData) {
+    println!(
+        concat!(
+            "cluster name: {}\n",
+            "schema name: {}\n",
+            "schema type: {}\n",
+            "schema version: {}\n",
+            "schema compatibility: {}\n",
+            "schema desc: {}\n",
+            "schema: {}\n"
+        ),
+        schema.cluster_name,
+        schema.name,
+        schema.schema_type,
+        schema.version,
+        schema.compatibility,
+        schema.desc,
+        schema.schema
+    );
 }
 
 #[cfg(test)]
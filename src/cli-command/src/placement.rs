@@ -14,18 +14,25 @@
 
 use std::sync::Arc;
 
-use grpc_clients::placement::inner::call::cluster_status;
+use grpc_clients::placement::inner::call::{backup, cluster_status, restore};
 use grpc_clients::placement::openraft::call::{
     placement_openraft_add_learner, placement_openraft_change_membership,
+    placement_openraft_snapshot_status, placement_openraft_trigger_snapshot,
 };
 use grpc_clients::pool::ClientPool;
-use protocol::placement_center::placement_center_inner::ClusterStatusRequest;
+use protocol::placement_center::placement_center_inner::{
+    BackupRequest, ClusterStatusRequest, RestoreRequest,
+};
 use protocol::placement_center::placement_center_openraft::{
-    AddLearnerRequest, ChangeMembershipRequest,
+    AddLearnerRequest, ChangeMembershipRequest, SnapshotStatusRequest, TriggerSnapshotRequest,
 };
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use crate::{error_info, grpc_addr};
 
+const RESTORE_CHUNK_SIZE: usize = 1024 * 1024;
+
 #[derive(Clone)]
 pub struct PlacementCliCommandParam {
     pub server: String,
@@ -37,6 +44,16 @@ pub enum PlacementActionType {
     Status,
     AddLearner(AddLearnerRequest),
     ChangeMembership(ChangeMembershipRequest),
+    TriggerSnapshot(TriggerSnapshotRequest),
+    SnapshotStatus,
+    Backup {
+        request: BackupRequest,
+        output: String,
+    },
+    Restore {
+        input: String,
+        cluster_name: String,
+    },
 }
 
 pub struct PlacementCenterCommand {}
@@ -66,6 +83,27 @@ pub async fn start(&self, params: PlacementCliCommandParam) {
                 self.change_membership(&client_pool, params.clone(), request.clone())
                     .await;
             }
+            PlacementActionType::TriggerSnapshot(ref request) => {
+                self.trigger_snapshot(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            PlacementActionType::SnapshotStatus => {
+                self.snapshot_status(&client_pool, params).await;
+            }
+            PlacementActionType::Backup {
+                ref request,
+                ref output,
+            } => {
+                self.backup(&client_pool, params.clone(), request.clone(), output)
+                    .await;
+            }
+            PlacementActionType::Restore {
+                ref input,
+                ref cluster_name,
+            } => {
+                self.restore(&client_pool, params.clone(), input, cluster_name)
+                    .await;
+            }
         }
     }
 
@@ -123,4 +161,140 @@ async fn change_membership(
             }
         }
     }
+
+    async fn trigger_snapshot(
+        &self,
+        client_pool: &ClientPool,
+        params: PlacementCliCommandParam,
+        cli_request: TriggerSnapshotRequest,
+    ) {
+        match placement_openraft_trigger_snapshot(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Snapshot triggered, use snapshot-status to follow its progress");
+            }
+            Err(e) => {
+                println!("Placement center trigger snapshot normal exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn snapshot_status(&self, client_pool: &ClientPool, params: PlacementCliCommandParam) {
+        let request = SnapshotStatusRequest {};
+        match placement_openraft_snapshot_status(client_pool, &grpc_addr(params.server), request)
+            .await
+        {
+            Ok(reply) => {
+                println!("{}", reply.content);
+            }
+            Err(e) => {
+                println!("Placement center snapshot status normal exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn backup(
+        &self,
+        client_pool: &ClientPool,
+        params: PlacementCliCommandParam,
+        cli_request: BackupRequest,
+        output: &str,
+    ) {
+        let mut file = match File::create(output).await {
+            Ok(file) => file,
+            Err(e) => {
+                println!("Failed to create backup file {}", output);
+                error_info(e.to_string());
+                return;
+            }
+        };
+
+        let mut stream = match backup(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Placement center backup normal exception");
+                error_info(e.to_string());
+                return;
+            }
+        };
+
+        let mut size = 0;
+        loop {
+            match stream.message().await {
+                Ok(Some(reply)) => {
+                    if let Err(e) = file.write_all(&reply.data).await {
+                        println!("Failed to write backup file {}", output);
+                        error_info(e.to_string());
+                        return;
+                    }
+                    size += reply.data.len();
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    println!("Placement center backup normal exception");
+                    error_info(e.to_string());
+                    return;
+                }
+            }
+        }
+
+        if let Err(e) = file.sync_all().await {
+            error_info(e.to_string());
+            return;
+        }
+        println!("Backup written to {}, size: {} bytes", output, size);
+    }
+
+    async fn restore(
+        &self,
+        client_pool: &ClientPool,
+        params: PlacementCliCommandParam,
+        input: &str,
+        cluster_name: &str,
+    ) {
+        let file = match File::open(input).await {
+            Ok(file) => file,
+            Err(e) => {
+                println!("Failed to open backup file {}", input);
+                error_info(e.to_string());
+                return;
+            }
+        };
+
+        // The cluster name only needs to travel with the first chunk
+        let requests =
+            futures::stream::unfold(Some((file, cluster_name.to_string())), |state| async move {
+                let (mut file, cluster_name) = state?;
+                let mut data = vec![0u8; RESTORE_CHUNK_SIZE];
+                match file.read(&mut data).await {
+                    Ok(0) => None,
+                    Ok(len) => {
+                        data.truncate(len);
+                        let request = RestoreRequest { data, cluster_name };
+                        Some((request, Some((file, String::new()))))
+                    }
+                    Err(e) => {
+                        error_info(e.to_string());
+                        None
+                    }
+                }
+            });
+
+        match restore(client_pool, &grpc_addr(params.server), requests).await {
+            Ok(reply) => {
+                println!("Restore finished, {} records imported", reply.records);
+            }
+            Err(e) => {
+                println!("Placement center restore normal exception");
+                error_info(e.to_string());
+            }
+        }
+    }
 }
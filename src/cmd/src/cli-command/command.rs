@@ -20,21 +20,36 @@
     PlacementActionType, PlacementCenterCommand, PlacementCliCommandParam,
 };
 use mqtt::admin::{
-    process_auto_subscribe_args, BindSchemaArgs, CreateConnectorArgs, CreateSchemaArgs,
-    DeleteConnectorArgs, DeleteSchemaArgs, ListBindSchemaArgs, ListConnectorArgs, ListSchemaArgs,
-    MqttAutoSubscribeRuleCommand, UnbindSchemaArgs, UpdateConnectorArgs, UpdateSchemaArgs,
+    process_auto_subscribe_args, process_publish_message_args, process_set_tenant_config_args,
+    BindSchemaArgs, ClearAlarmArgs, ClientArgs, ClientTraceArgs, CreateConnectorArgs,
+    CreateRuleArgs, CreateSchemaArgs, DelayMessageArgs, DeleteConnectorArgs,
+    DeleteRetainMessageArgs, DeleteRuleArgs, DeleteSchemaArgs, DeleteTenantArgs,
+    GetRetainMessageArgs, ListAlarmArgs, ListBindSchemaArgs, ListConnectorArgs,
+    ListDelayMessageArgs, ListRetainMessageArgs, ListRuleArgs, ListSchemaArgs,
+    ListSchemaVersionArgs, ListSubscribeArgs, ListTenantArgs, MqttAutoSubscribeRuleCommand,
+    PeekMessageArgs, PublishMessageArgs, SetTenantConfigArgs, TenantArgs, TenantFilterArgs,
+    UnbindSchemaArgs, UpdateConnectorArgs, UpdateSchemaArgs,
 };
 use mqtt::publish::process_subscribe_args;
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    EnableFlappingDetectRequest, ListTopicRequest, MqttBindSchemaRequest,
-    MqttCreateConnectorRequest, MqttCreateSchemaRequest, MqttDeleteConnectorRequest,
-    MqttDeleteSchemaRequest, MqttListBindSchemaRequest, MqttListConnectorRequest,
-    MqttListSchemaRequest, MqttUnbindSchemaRequest, MqttUpdateConnectorRequest,
-    MqttUpdateSchemaRequest,
+    EnableFlappingDetectRequest, ListConnectionRequest, ListTopicRequest, MqttBindSchemaRequest,
+    MqttCancelDelayMessageRequest, MqttCleanSessionRequest, MqttClearAlarmRequest,
+    MqttCreateConnectorRequest, MqttCreateRuleRequest, MqttCreateSchemaRequest,
+    MqttCreateTenantRequest, MqttDeleteConnectorRequest, MqttDeleteRetainMessageRequest,
+    MqttDeleteRuleRequest, MqttDeleteSchemaRequest, MqttDeleteTenantRequest,
+    MqttGetRetainMessageRequest, MqttInspectClientRequest, MqttInspectDelayMessageRequest,
+    MqttKickClientRequest, MqttListAlarmRequest, MqttListBindSchemaRequest,
+    MqttListClientTraceRequest, MqttListConnectorRequest, MqttListDelayMessageRequest,
+    MqttListRetainMessageRequest, MqttListRuleRequest, MqttListSchemaRequest,
+    MqttListSchemaVersionRequest, MqttListSessionRequest, MqttListSubscribeRequest,
+    MqttListTenantRequest, MqttPeekMessageRequest, MqttSetClientTraceRequest,
+    MqttUnbindSchemaRequest, MqttUpdateConnectorRequest, MqttUpdateRuleRequest,
+    MqttUpdateSchemaRequest, MqttUpdateTenantRequest,
 };
 
+use protocol::placement_center::placement_center_inner::BackupRequest;
 use protocol::placement_center::placement_center_openraft::{
-    AddLearnerRequest, ChangeMembershipRequest, Node,
+    AddLearnerRequest, ChangeMembershipRequest, Node, TriggerSnapshotRequest,
 };
 
 use crate::mqtt::admin::{
@@ -85,6 +100,10 @@ struct MqttArgs {
 enum MQTTAction {
     // cluster status
     Status,
+    // reload the broker config file
+    ReloadConfig,
+    // reload the TLS certificates from disk
+    ReloadTlsCert,
     // user admin
     User(UserArgs),
     // access control list admin
@@ -93,7 +112,7 @@ enum MQTTAction {
     Blacklist(BlacklistArgs),
 
     // Connections
-    ListConnection,
+    ListConnection(TenantFilterArgs),
 
     // flapping detect feat
     FlappingDetect(FlappingDetectArgs),
@@ -112,8 +131,45 @@ enum MQTTAction {
     UpdateConnector(UpdateConnectorArgs),
     DeleteConnector(DeleteConnectorArgs),
 
+    // rule
+    ListRule(ListRuleArgs),
+    CreateRule(CreateRuleArgs),
+    UpdateRule(CreateRuleArgs),
+    DeleteRule(DeleteRuleArgs),
+
+    // delay message
+    ListDelayMessage(ListDelayMessageArgs),
+    InspectDelayMessage(DelayMessageArgs),
+    CancelDelayMessage(DelayMessageArgs),
+
+    // message
+    PublishMessage(PublishMessageArgs),
+    PeekMessage(PeekMessageArgs),
+
+    // retain message
+    ListRetainMessage(ListRetainMessageArgs),
+    GetRetainMessage(GetRetainMessageArgs),
+    DeleteRetainMessage(DeleteRetainMessageArgs),
+
+    // alarm
+    ListAlarm(ListAlarmArgs),
+    ClearAlarm(ClearAlarmArgs),
+
+    // trace
+    EnableClientTrace(ClientTraceArgs),
+    DisableClientTrace(ClientTraceArgs),
+    ListClientTrace,
+
+    // client & session
+    ListSession(TenantFilterArgs),
+    ListSubscribe(ListSubscribeArgs),
+    InspectClient(ClientArgs),
+    KickClient(ClientArgs),
+    CleanSession(ClientArgs),
+
     // schema
     ListSchema(ListSchemaArgs),
+    ListSchemaVersion(ListSchemaVersionArgs),
     CreateSchema(CreateSchemaArgs),
     UpdateSchema(UpdateSchemaArgs),
     DeleteSchema(DeleteSchemaArgs),
@@ -123,6 +179,13 @@ enum MQTTAction {
 
     //auto subscribe
     AutoSubscribeRule(MqttAutoSubscribeRuleCommand),
+
+    // tenant
+    ListTenant(ListTenantArgs),
+    CreateTenant(TenantArgs),
+    UpdateTenant(TenantArgs),
+    DeleteTenant(DeleteTenantArgs),
+    SetTenantConfig(SetTenantConfigArgs),
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -141,6 +204,9 @@ struct ListTopicArgs {
 
     #[arg(short, long, default_value = "e")]
     match_option: MatchOption,
+
+    #[arg(long, default_value = "")]
+    tenant: String,
 }
 
 #[derive(clap::Args, Debug)]
@@ -159,6 +225,10 @@ enum PlacementAction {
     Status,
     AddLearner(AddLearnerArgs),
     ChangeMembership(ChangeMembershipArgs),
+    TriggerSnapshot(TriggerSnapshotArgs),
+    SnapshotStatus,
+    Backup(BackupArgs),
+    Restore(RestoreArgs),
 }
 
 #[derive(clap::Args, Debug)]
@@ -186,6 +256,38 @@ struct ChangeMembershipArgs {
     retain: bool,
 }
 
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ",  about="action: build a raft snapshot on the node and optionally purge the logs it covers", long_about = None)]
+#[command(next_line_help = true)]
+struct TriggerSnapshotArgs {
+    #[arg(short, long, default_value_t = false)]
+    purge_log: bool,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ",  about="action: write a backup of the placement center metadata to a file", long_about = None)]
+#[command(next_line_help = true)]
+struct BackupArgs {
+    #[arg(short, long, required = true)]
+    output: String,
+
+    /// Only export the metadata of this broker cluster
+    #[arg(short, long)]
+    cluster_name: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ",  about="action: replace the placement center data with a backup file, keys missing from the backup are removed", long_about = None)]
+#[command(next_line_help = true)]
+struct RestoreArgs {
+    #[arg(short, long, required = true)]
+    input: String,
+
+    /// Only import the metadata of this broker cluster
+    #[arg(short, long)]
+    cluster_name: Option<String>,
+}
+
 #[derive(clap::Args, Debug)]
 #[command(author="RobustMQ", about="Command line tool for journal engine", long_about = None)]
 #[command(next_line_help = true)]
@@ -215,13 +317,19 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
         action: match args.action {
             // cluster status
             MQTTAction::Status => MqttActionType::Status,
+            MQTTAction::ReloadConfig => MqttActionType::ReloadConfig,
+            MQTTAction::ReloadTlsCert => MqttActionType::ReloadTlsCert,
             // user admin
             MQTTAction::User(args) => process_user_args(args),
             // access control list admin
             MQTTAction::Acl(args) => process_acl_args(args),
             // blacklist admin
             MQTTAction::Blacklist(args) => process_blacklist_args(args),
-            MQTTAction::ListConnection => MqttActionType::ListConnection,
+            MQTTAction::ListConnection(args) => {
+                MqttActionType::ListConnection(ListConnectionRequest {
+                    tenant: args.tenant,
+                })
+            }
             MQTTAction::ListTopic(args) => MqttActionType::ListTopic(ListTopicRequest {
                 topic_name: args.topic_name,
                 match_option: match args.match_option {
@@ -229,6 +337,7 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
                     MatchOption::P => 1,
                     MatchOption::S => 2,
                 },
+                tenant: args.tenant,
             }),
             MQTTAction::SlowSub(args) => process_slow_sub_args(args),
             MQTTAction::FlappingDetect(args) => {
@@ -264,15 +373,119 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
                     connector_name: args.connector_name,
                 })
             }
+            MQTTAction::ListRule(args) => MqttActionType::ListRule(MqttListRuleRequest {
+                rule_name: args.rule_name,
+            }),
+            MQTTAction::CreateRule(args) => MqttActionType::CreateRule(MqttCreateRuleRequest {
+                rule_name: args.rule_name,
+                sql: args.sql,
+                actions: args.actions,
+                enable: args.enable,
+                desc: args.desc,
+            }),
+            MQTTAction::UpdateRule(args) => MqttActionType::UpdateRule(MqttUpdateRuleRequest {
+                rule_name: args.rule_name,
+                sql: args.sql,
+                actions: args.actions,
+                enable: args.enable,
+                desc: args.desc,
+            }),
+            MQTTAction::DeleteRule(args) => MqttActionType::DeleteRule(MqttDeleteRuleRequest {
+                rule_name: args.rule_name,
+            }),
+            MQTTAction::ListDelayMessage(args) => {
+                MqttActionType::ListDelayMessage(MqttListDelayMessageRequest {
+                    target_topic: args.target_topic,
+                })
+            }
+            MQTTAction::InspectDelayMessage(args) => {
+                MqttActionType::InspectDelayMessage(MqttInspectDelayMessageRequest {
+                    delay_id: args.delay_id,
+                })
+            }
+            MQTTAction::CancelDelayMessage(args) => {
+                MqttActionType::CancelDelayMessage(MqttCancelDelayMessageRequest {
+                    delay_id: args.delay_id,
+                })
+            }
+            MQTTAction::PublishMessage(args) => process_publish_message_args(args),
+            MQTTAction::PeekMessage(args) => MqttActionType::PeekMessage(MqttPeekMessageRequest {
+                topic_name: args.topic_name,
+                num: args.num,
+            }),
+            MQTTAction::ListRetainMessage(args) => {
+                MqttActionType::ListRetainMessage(MqttListRetainMessageRequest {
+                    topic_filter: args.topic_filter,
+                })
+            }
+            MQTTAction::GetRetainMessage(args) => {
+                MqttActionType::GetRetainMessage(MqttGetRetainMessageRequest {
+                    topic_name: args.topic_name,
+                })
+            }
+            MQTTAction::DeleteRetainMessage(args) => {
+                MqttActionType::DeleteRetainMessage(MqttDeleteRetainMessageRequest {
+                    topic_filter: args.topic_filter,
+                })
+            }
+            MQTTAction::ListAlarm(args) => MqttActionType::ListAlarm(MqttListAlarmRequest {
+                active_only: args.active_only,
+            }),
+            MQTTAction::ClearAlarm(args) => MqttActionType::ClearAlarm(MqttClearAlarmRequest {
+                alarm_id: args.alarm_id,
+            }),
+            MQTTAction::EnableClientTrace(args) => {
+                MqttActionType::SetClientTrace(MqttSetClientTraceRequest {
+                    client_id: args.client_id,
+                    enable: true,
+                })
+            }
+            MQTTAction::DisableClientTrace(args) => {
+                MqttActionType::SetClientTrace(MqttSetClientTraceRequest {
+                    client_id: args.client_id,
+                    enable: false,
+                })
+            }
+            MQTTAction::ListClientTrace => {
+                MqttActionType::ListClientTrace(MqttListClientTraceRequest {})
+            }
+            MQTTAction::ListSession(args) => MqttActionType::ListSession(MqttListSessionRequest {
+                tenant: args.tenant,
+            }),
+            MQTTAction::ListSubscribe(args) => {
+                MqttActionType::ListSubscribe(MqttListSubscribeRequest {
+                    client_id: args.client_id,
+                })
+            }
+            MQTTAction::InspectClient(args) => {
+                MqttActionType::InspectClient(MqttInspectClientRequest {
+                    client_id: args.client_id,
+                })
+            }
+            MQTTAction::KickClient(args) => MqttActionType::KickClient(MqttKickClientRequest {
+                client_id: args.client_id,
+            }),
+            MQTTAction::CleanSession(args) => {
+                MqttActionType::CleanSession(MqttCleanSessionRequest {
+                    client_id: args.client_id,
+                })
+            }
             MQTTAction::ListSchema(args) => MqttActionType::ListSchema(MqttListSchemaRequest {
                 schema_name: args.schema_name,
             }),
+            MQTTAction::ListSchemaVersion(args) => {
+                MqttActionType::ListSchemaVersion(MqttListSchemaVersionRequest {
+                    schema_name: args.schema_name,
+                    version: args.version,
+                })
+            }
             MQTTAction::CreateSchema(args) => {
                 MqttActionType::CreateSchema(MqttCreateSchemaRequest {
                     schema_name: args.schema_name,
                     schema_type: args.schema_type,
                     schema: args.schema,
                     desc: args.desc,
+                    compatibility: args.compatibility,
                 })
             }
             MQTTAction::UpdateSchema(args) => {
@@ -281,6 +494,7 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
                     schema_type: args.schema_type,
                     schema: args.schema,
                     desc: args.desc,
+                    compatibility: args.compatibility.unwrap_or_default(),
                 })
             }
             MQTTAction::DeleteSchema(args) => {
@@ -297,6 +511,7 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
             MQTTAction::BindSchema(args) => MqttActionType::BindSchema(MqttBindSchemaRequest {
                 schema_name: args.schema_name,
                 resource_name: args.resource_name,
+                schema_version: args.schema_version,
             }),
             MQTTAction::UnbindSchema(args) => {
                 MqttActionType::UnbindSchema(MqttUnbindSchemaRequest {
@@ -305,6 +520,38 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
                 })
             }
             MQTTAction::AutoSubscribeRule(args) => process_auto_subscribe_args(args),
+            // tenant
+            MQTTAction::ListTenant(args) => MqttActionType::ListTenant(MqttListTenantRequest {
+                tenant_name: args.tenant_name,
+            }),
+            MQTTAction::CreateTenant(args) => {
+                MqttActionType::CreateTenant(MqttCreateTenantRequest {
+                    tenant_name: args.tenant_name,
+                    desc: args.desc,
+                    max_connections: args.max_connections,
+                    max_sessions: args.max_sessions,
+                    max_topics: args.max_topics,
+                    max_message_rate: args.max_message_rate,
+                    max_storage_bytes: args.max_storage_bytes,
+                })
+            }
+            MQTTAction::UpdateTenant(args) => {
+                MqttActionType::UpdateTenant(MqttUpdateTenantRequest {
+                    tenant_name: args.tenant_name,
+                    desc: args.desc,
+                    max_connections: args.max_connections,
+                    max_sessions: args.max_sessions,
+                    max_topics: args.max_topics,
+                    max_message_rate: args.max_message_rate,
+                    max_storage_bytes: args.max_storage_bytes,
+                })
+            }
+            MQTTAction::DeleteTenant(args) => {
+                MqttActionType::DeleteTenant(MqttDeleteTenantRequest {
+                    tenant_name: args.tenant_name,
+                })
+            }
+            MQTTAction::SetTenantConfig(args) => process_set_tenant_config_args(args),
         },
     };
     cmd.start(params).await;
@@ -331,6 +578,22 @@ async fn handle_placement(args: PlacementArgs, cmd: PlacementCenterCommand) {
                     retain: arg.retain,
                 })
             }
+            PlacementAction::TriggerSnapshot(arg) => {
+                PlacementActionType::TriggerSnapshot(TriggerSnapshotRequest {
+                    purge_log: arg.purge_log,
+                })
+            }
+            PlacementAction::SnapshotStatus => PlacementActionType::SnapshotStatus,
+            PlacementAction::Backup(arg) => PlacementActionType::Backup {
+                request: BackupRequest {
+                    cluster_name: arg.cluster_name.unwrap_or_default(),
+                },
+                output: arg.output,
+            },
+            PlacementAction::Restore(arg) => PlacementActionType::Restore {
+                input: arg.input,
+                cluster_name: arg.cluster_name.unwrap_or_default(),
+            },
         },
     };
     cmd.start(params).await;
@@ -22,7 +22,8 @@
 use protocol::broker_mqtt::broker_mqtt_admin::{
     CreateAclRequest, CreateBlacklistRequest, CreateUserRequest, DeleteAclRequest,
     DeleteAutoSubscribeRuleRequest, DeleteBlacklistRequest, DeleteUserRequest,
-    ListAutoSubscribeRuleRequest, SetAutoSubscribeRuleRequest,
+    ListAutoSubscribeRuleRequest, ListUserRequest, MqttPublishMessageRequest,
+    MqttSetTenantConfigRequest, SetAutoSubscribeRuleRequest,
 };
 use protocol::broker_mqtt::broker_mqtt_admin::{
     EnableSlowSubscribeRequest, ListSlowSubscribeRequest,
@@ -39,7 +40,7 @@ pub(crate) struct UserArgs {
 #[derive(Debug, clap::Subcommand)]
 pub enum UserActionType {
     #[command(author="RobustMQ", about="action: list users", long_about = None)]
-    List,
+    List(TenantFilterArgs),
     #[command(author="RobustMQ", about="action: create user", long_about = None)]
     Create(CreateUserArgs),
     #[command(author="RobustMQ", about="action: delete user", long_about = None)]
@@ -67,6 +68,14 @@ pub(crate) struct DeleteUserArgs {
     pub(crate) username: String,
 }
 
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub(crate) struct TenantFilterArgs {
+    #[arg(long, default_value = "")]
+    #[arg(help = "only list the resources of this tenant")]
+    pub(crate) tenant: String,
+}
+
 #[derive(clap::Args, Debug)]
 #[command(author="RobustMQ", about="related operations of access control list, such as listing, creating, and deleting", long_about = None)]
 #[command(next_line_help = true)]
@@ -250,6 +259,178 @@ pub(crate) struct DeleteConnectorArgs {
     pub(crate) connector_name: String,
 }
 
+// rule
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListRuleArgs {
+    #[arg(default_value = "")]
+    #[arg(help = "list only this rule, lists all rules when empty")]
+    pub(crate) rule_name: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct CreateRuleArgs {
+    pub(crate) rule_name: String,
+    #[arg(
+        help = r#"e.g. SELECT payload.temp AS t, clientid FROM "sensors/#" WHERE payload.temp > 40"#
+    )]
+    pub(crate) sql: String,
+    #[arg(
+        help = r#"JSON array, e.g. [{"Republish":{"topic":"alerts","qos":1}},{"Connector":{"connector_name":"c1"}}]"#
+    )]
+    pub(crate) actions: String,
+    #[arg(short, long, default_value = "")]
+    pub(crate) desc: String,
+    #[arg(short, long, default_value_t = true, action = ArgAction::Set)]
+    pub(crate) enable: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteRuleArgs {
+    pub(crate) rule_name: String,
+}
+
+// delay message
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListDelayMessageArgs {
+    #[arg(default_value = "")]
+    #[arg(help = "list only messages for this target topic, lists all when empty")]
+    pub(crate) target_topic: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DelayMessageArgs {
+    pub(crate) delay_id: String,
+}
+
+// message
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct PublishMessageArgs {
+    pub(crate) topic_name: String,
+    pub(crate) payload: String,
+    #[arg(short, long, default_value_t = 0)]
+    pub(crate) qos: u32,
+    #[arg(short, long, default_value_t = false)]
+    pub(crate) retain: bool,
+    #[arg(long, default_value = "")]
+    #[arg(help = "client id recorded as the publisher, defaults to admin")]
+    pub(crate) client_id: String,
+    #[arg(long, default_value = "")]
+    #[arg(help = "user the ACL check is evaluated for")]
+    pub(crate) username: String,
+    #[arg(long, default_value_t = false)]
+    #[arg(help = "skip the ACL check")]
+    pub(crate) bypass_acl: bool,
+    #[arg(long, default_value = "")]
+    #[arg(help = "tenant the message is published into")]
+    pub(crate) tenant: String,
+    #[arg(long)]
+    pub(crate) payload_format_indicator: Option<u32>,
+    #[arg(long, default_value_t = 0)]
+    #[arg(help = "unit is seconds")]
+    pub(crate) message_expiry_interval: u32,
+    #[arg(long, default_value = "")]
+    pub(crate) response_topic: String,
+    #[arg(long, default_value = "")]
+    pub(crate) correlation_data: String,
+    #[arg(long, default_value = "")]
+    pub(crate) content_type: String,
+    #[arg(long = "user-property")]
+    #[arg(help = "user property in key=value form, can be repeated")]
+    pub(crate) user_properties: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct PeekMessageArgs {
+    pub(crate) topic_name: String,
+    #[arg(short, long, default_value_t = 10)]
+    #[arg(help = "number of latest messages to read, at most 100")]
+    pub(crate) num: u32,
+}
+
+// retain message
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListRetainMessageArgs {
+    #[arg(default_value = "")]
+    #[arg(help = "list only messages matching this topic filter, lists all when empty")]
+    pub(crate) topic_filter: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct GetRetainMessageArgs {
+    pub(crate) topic_name: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteRetainMessageArgs {
+    #[arg(help = "delete the retained messages of all topics matching this topic filter")]
+    pub(crate) topic_filter: String,
+}
+
+// alarm
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListAlarmArgs {
+    #[arg(short, long, default_value_t = false)]
+    #[arg(help = "list only alarms that are still active")]
+    pub(crate) active_only: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ClearAlarmArgs {
+    #[arg(default_value = "")]
+    #[arg(help = "clear only this deactivated alarm, clears all deactivated alarms when empty")]
+    pub(crate) alarm_id: String,
+}
+
+// trace
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ClientTraceArgs {
+    #[arg(help = "client id whose publishes start a new trace")]
+    pub(crate) client_id: String,
+}
+
+// client & session
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ClientArgs {
+    pub(crate) client_id: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListSubscribeArgs {
+    #[arg(default_value = "")]
+    #[arg(help = "only list subscriptions of this client, lists all when empty")]
+    pub(crate) client_id: String,
+}
+
 // schema
 #[derive(Debug, Parser)]
 #[command(author="RobustMQ", about="", long_about = None)]
@@ -258,6 +439,16 @@ pub(crate) struct ListSchemaArgs {
     pub(crate) schema_name: String,
 }
 
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListSchemaVersionArgs {
+    pub(crate) schema_name: String,
+    #[arg(short, long, default_value_t = 0)]
+    #[arg(help = "list only this version, 0 lists all versions")]
+    pub(crate) version: u64,
+}
+
 #[derive(Debug, Parser)]
 #[command(author="RobustMQ", about="", long_about = None)]
 #[command(next_line_help = true)]
@@ -266,6 +457,9 @@ pub(crate) struct CreateSchemaArgs {
     pub(crate) schema_type: String,
     pub(crate) schema: String,
     pub(crate) desc: String,
+    #[arg(short, long, default_value = "none")]
+    #[arg(help = "compatibility with the previous version: none, backward, forward, full")]
+    pub(crate) compatibility: String,
 }
 
 #[derive(Debug, Parser)]
@@ -276,6 +470,11 @@ pub(crate) struct UpdateSchemaArgs {
     pub(crate) schema_type: String,
     pub(crate) schema: String,
     pub(crate) desc: String,
+    #[arg(short, long)]
+    #[arg(
+        help = "change the compatibility level for later updates: none, backward, forward, full. The stored level is kept when omitted"
+    )]
+    pub(crate) compatibility: Option<String>,
 }
 
 #[derive(Debug, Parser)]
@@ -299,6 +498,9 @@ pub(crate) struct ListBindSchemaArgs {
 pub(crate) struct BindSchemaArgs {
     pub(crate) schema_name: String,
     pub(crate) resource_name: String,
+    #[arg(short = 'v', long, default_value_t = 0)]
+    #[arg(help = "pin the binding to a schema version, 0 follows the latest version")]
+    pub(crate) schema_version: u64,
 }
 
 #[derive(Debug, Parser)]
@@ -332,7 +534,9 @@ pub fn process_slow_sub_args(args: SlowSubArgs) -> MqttActionType {
 pub fn process_user_args(args: UserArgs) -> MqttActionType {
     match args.action {
         Some(user_action) => match user_action {
-            UserActionType::List => MqttActionType::ListUser,
+            UserActionType::List(arg) => {
+                MqttActionType::ListUser(ListUserRequest { tenant: arg.tenant })
+            }
             UserActionType::Create(arg) => MqttActionType::CreateUser(CreateUserRequest {
                 username: arg.username,
                 password: arg.password,
@@ -429,6 +633,108 @@ pub(crate) struct DeleteAutoSubscribeRuleArgs {
     pub(crate) topic: String,
 }
 
+// tenant
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: list tenants", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListTenantArgs {
+    #[arg(short, long, default_value = "")]
+    pub(crate) tenant_name: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: create or update tenant, a quota of 0 means unlimited", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct TenantArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) tenant_name: String,
+    #[arg(short, long, default_value = "")]
+    pub(crate) desc: String,
+    #[arg(long, default_value_t = 0)]
+    pub(crate) max_connections: u64,
+    #[arg(long, default_value_t = 0)]
+    pub(crate) max_sessions: u64,
+    #[arg(long, default_value_t = 0)]
+    pub(crate) max_topics: u64,
+    #[arg(long, default_value_t = 0)]
+    #[arg(help = "messages per second")]
+    pub(crate) max_message_rate: u64,
+    #[arg(long, default_value_t = 0)]
+    pub(crate) max_storage_bytes: u64,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: delete tenant", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteTenantArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) tenant_name: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: set how connections are mapped to tenants", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct SetTenantConfigArgs {
+    #[arg(long, required = true, value_parser = BoolishValueParser::new())]
+    pub(crate) enable: bool,
+    #[arg(long, default_value = "username")]
+    #[arg(help = "username, cert_field or listener")]
+    pub(crate) source: String,
+    #[arg(long, default_value = "@")]
+    pub(crate) username_separator: String,
+    #[arg(long, default_value = "O")]
+    #[arg(help = "certificate subject field holding the tenant: CN, O or OU")]
+    pub(crate) cert_field: String,
+    #[arg(long)]
+    #[arg(help = "listener=tenant, can be repeated")]
+    pub(crate) listener_tenant: Vec<String>,
+}
+
+pub fn process_set_tenant_config_args(args: SetTenantConfigArgs) -> MqttActionType {
+    let listener_tenants = args
+        .listener_tenant
+        .iter()
+        .filter_map(|raw| {
+            raw.split_once('=')
+                .map(|(listener, tenant)| (listener.to_string(), tenant.to_string()))
+        })
+        .collect();
+    MqttActionType::SetTenantConfig(MqttSetTenantConfigRequest {
+        enable: args.enable,
+        source: args.source,
+        username_separator: args.username_separator,
+        cert_field: args.cert_field,
+        listener_tenants,
+    })
+}
+
+pub fn process_publish_message_args(args: PublishMessageArgs) -> MqttActionType {
+    let user_properties = args
+        .user_properties
+        .iter()
+        .filter_map(|raw| {
+            raw.split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+        })
+        .collect();
+    MqttActionType::PublishMessage(MqttPublishMessageRequest {
+        topic_name: args.topic_name,
+        payload: args.payload.into_bytes(),
+        qos: args.qos,
+        retain: args.retain,
+        client_id: args.client_id,
+        username: args.username,
+        bypass_acl: args.bypass_acl,
+        tenant: args.tenant,
+        payload_format_indicator: args.payload_format_indicator,
+        message_expiry_interval: args.message_expiry_interval,
+        response_topic: args.response_topic,
+        correlation_data: args.correlation_data.into_bytes(),
+        content_type: args.content_type,
+        user_properties,
+    })
+}
+
 pub fn process_auto_subscribe_args(args: MqttAutoSubscribeRuleCommand) -> MqttActionType {
     match args.action {
         Some(auto_subscribe_action) => match auto_subscribe_action {
@@ -533,4 +839,31 @@ async fn test_process_slow_sub_args_function_filed_is_enable_none_2() {
             action_type
         )
     }
+
+    #[test]
+    fn test_process_publish_message_args_user_properties() {
+        let args = PublishMessageArgs {
+            topic_name: "t/1".to_string(),
+            payload: "hello".to_string(),
+            qos: 1,
+            retain: false,
+            client_id: "".to_string(),
+            username: "".to_string(),
+            bypass_acl: true,
+            tenant: "".to_string(),
+            payload_format_indicator: None,
+            message_expiry_interval: 0,
+            response_topic: "".to_string(),
+            correlation_data: "".to_string(),
+            content_type: "".to_string(),
+            user_properties: vec!["k1=v1".to_string(), "invalid".to_string()],
+        };
+
+        let MqttActionType::PublishMessage(request) = process_publish_message_args(args) else {
+            panic!("unexpected action type");
+        };
+        assert_eq!(request.payload, b"hello".to_vec());
+        assert_eq!(request.user_properties.len(), 1);
+        assert_eq!(request.user_properties.get("k1"), Some(&"v1".to_string()));
+    }
 }
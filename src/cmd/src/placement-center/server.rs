@@ -16,6 +16,7 @@
 use common_base::config::placement_center::init_placement_center_conf_by_path;
 use common_base::config::DEFAULT_PLACEMENT_CENTER_CONFIG;
 use common_base::logs::init_placement_center_log;
+use log::info;
 use placement_center::PlacementCenter;
 use tokio::sync::broadcast;
 
@@ -26,6 +27,14 @@ struct ArgsParams {
     /// MetaService Indicates the path of the configuration file
     #[arg(short, long, default_value_t=String::from(DEFAULT_PLACEMENT_CENTER_CONFIG))]
     conf: String,
+
+    /// Bootstrap a new single node cluster from a backup file before starting
+    #[arg(long)]
+    restore: Option<String>,
+
+    /// Only restore the metadata of this broker cluster from the backup file
+    #[arg(long, requires = "restore")]
+    restore_cluster_name: Option<String>,
 }
 #[tokio::main]
 async fn main() {
@@ -34,5 +43,11 @@ async fn main() {
     init_placement_center_log();
     let (stop_send, _) = broadcast::channel(2);
     let mut pc = PlacementCenter::new();
+    if let Some(path) = args.restore {
+        match pc.restore_from_backup(&path, args.restore_cluster_name.as_deref()) {
+            Ok(records) => info!("Restored {} records from backup {}", records, path),
+            Err(e) => panic!("Failed to restore backup {}, {}", path, e),
+        }
+    }
     pc.start(stop_send).await;
 }
@@ -12,23 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
 
 use super::common::{
-    default_prometheus, override_default_by_env, Auth, Log, Prometheus, Storage, Telemetry,
+    default_prometheus, default_telemetry, override_default_by_env, Auth, Log, Prometheus, Storage,
+    Telemetry,
 };
 use super::default_mqtt::{
-    default_auth, default_grpc_port, default_log, default_mqtt_cluster_dynamic_feature,
+    default_auth, default_busy_queue_len, default_cpu_high_watermark, default_grpc_port,
+    default_http_api, default_log, default_long_schedule_ms, default_memory_high_watermark,
+    default_mqtt_cluster_dynamic_alarm, default_mqtt_cluster_dynamic_feature,
     default_mqtt_cluster_dynamic_flapping_detect, default_mqtt_cluster_dynamic_network,
-    default_mqtt_cluster_dynamic_protocol, default_mqtt_cluster_dynamic_security,
-    default_mqtt_cluster_dynamic_slow_sub, default_network, default_network_quic_port,
+    default_mqtt_cluster_dynamic_protocol, default_mqtt_cluster_dynamic_retain_message,
+    default_mqtt_cluster_dynamic_security, default_mqtt_cluster_dynamic_slow_sub,
+    default_mqtt_cluster_dynamic_tenant, default_network, default_network_quic_port,
     default_network_tcp_port, default_network_tcps_port, default_network_websocket_port,
     default_network_websockets_port, default_offline_message, default_placement_center,
-    default_storage, default_system, default_tcp_thread, default_telemetry,
+    default_storage, default_sys_interval_secs, default_sysmon_interval_secs, default_system,
+    default_system_monitor, default_system_monitor_enable, default_tcp_thread,
 };
-use crate::tools::{read_file, try_create_fold};
+use super::reload::{
+    apply_common_reload, reload_config, validate_log_config, ConfigReloadReport,
+    COMMON_RELOADABLE_CONFIG,
+};
+use crate::error::common::CommonError;
+use crate::tools::{file_exists, read_file, try_create_fold};
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct BrokerMqttConfig {
@@ -56,6 +68,10 @@ pub struct BrokerMqttConfig {
     pub telemetry: Telemetry,
     #[serde(default = "default_prometheus")]
     pub prometheus: Prometheus,
+    #[serde(default = "default_system_monitor")]
+    pub system_monitor: SystemMonitor,
+    #[serde(default = "default_http_api")]
+    pub http_api: HttpApi,
 
     #[serde(default = "default_mqtt_cluster_dynamic_slow_sub")]
     pub cluster_dynamic_config_slow_sub: MqttClusterDynamicSlowSub,
@@ -69,6 +85,12 @@ pub struct BrokerMqttConfig {
     pub cluster_dynamic_config_security: MqttClusterDynamicConfigSecurity,
     #[serde(default = "default_mqtt_cluster_dynamic_network")]
     pub cluster_dynamic_config_network: MqttClusterDynamicConfigNetwork,
+    #[serde(default = "default_mqtt_cluster_dynamic_alarm")]
+    pub cluster_dynamic_config_alarm: MqttClusterDynamicAlarm,
+    #[serde(default = "default_mqtt_cluster_dynamic_retain_message")]
+    pub cluster_dynamic_config_retain_message: MqttClusterDynamicRetainMessage,
+    #[serde(default = "default_mqtt_cluster_dynamic_tenant")]
+    pub cluster_dynamic_config_tenant: MqttClusterDynamicTenant,
 }
 
 // MQTT cluster protocol related dynamic configuration
@@ -152,6 +174,40 @@ pub fn encode(&self) -> Vec<u8> {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicAlarm {
+    pub enable: bool,
+    pub check_interval_secs: u64,
+    pub connection_high_watermark: u64,
+    pub storage_write_failures: u64,
+    pub flapping_detect_bans: u64,
+    pub schema_validation_failures: u64,
+    pub slow_subscriptions: u64,
+}
+
+// 0 means there is no limit on the number of retained messages
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicRetainMessage {
+    pub max_retain_message_num: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicTenant {
+    pub enable: bool,
+    pub source: ConfigTenantSource,
+    pub username_separator: String,
+    pub cert_field: String,
+    pub listener_tenants: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default, Clone)]
+pub enum ConfigTenantSource {
+    #[default]
+    Username,
+    CertField,
+    Listener,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct MqttClusterDynamicOfflineMessage {
     pub enable: bool,
@@ -180,6 +236,20 @@ pub struct Network {
     pub tls_cert: String,
     #[serde(default)]
     pub tls_key: String,
+    // Certificates served by SNI server name, other handshakes get tls_cert
+    #[serde(default)]
+    pub tls_sni_certs: Vec<TlsSniCert>,
+    // CA used to verify client certificates, clients without a certificate are still accepted
+    #[serde(default)]
+    pub tls_client_ca: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct TlsSniCert {
+    // Exact host name or a wildcard such as *.example.com
+    pub server_name: String,
+    pub tls_cert: String,
+    pub tls_key: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -212,6 +282,24 @@ pub struct System {
     pub default_password: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct SystemMonitor {
+    #[serde(default = "default_system_monitor_enable")]
+    pub enable: bool,
+    #[serde(default = "default_sys_interval_secs")]
+    pub sys_interval_secs: u64,
+    #[serde(default = "default_sysmon_interval_secs")]
+    pub sysmon_interval_secs: u64,
+    #[serde(default = "default_cpu_high_watermark")]
+    pub cpu_high_watermark: f32,
+    #[serde(default = "default_memory_high_watermark")]
+    pub memory_high_watermark: f32,
+    #[serde(default = "default_long_schedule_ms")]
+    pub long_schedule_ms: u64,
+    #[serde(default = "default_busy_queue_len")]
+    pub busy_queue_len: usize,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct OfflineMessage {
     #[serde(default)]
@@ -222,21 +310,41 @@ pub struct OfflineMessage {
     pub max_messages_num: u32,
 }
 
-static BROKER_MQTT_CONF: OnceLock<BrokerMqttConfig> = OnceLock::new();
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct HttpApi {
+    #[serde(default)]
+    pub enable: bool,
+    pub port: u32,
+    // Accepted as `Authorization: Bearer <key>` or `X-API-Key: <key>`
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
 
-pub fn init_broker_mqtt_conf_by_path(config_path: &str) -> &'static BrokerMqttConfig {
+// Swapped as a whole on reload. Readers keep the snapshot they loaded until
+// they drop it, and the old config is freed with the last snapshot.
+static BROKER_MQTT_CONF: OnceLock<ArcSwap<BrokerMqttConfig>> = OnceLock::new();
+static BROKER_MQTT_CONF_PATH: OnceLock<String> = OnceLock::new();
+
+pub const BROKER_MQTT_SERVICE_NAME: &str = "mqtt-broker";
+
+// Settings that can change while the broker is running
+pub const BROKER_MQTT_RELOADABLE_CONFIG: [&str; 8] = [
+    "network.tls_cert",
+    "network.tls_key",
+    "network.tls_sni_certs",
+    "system_monitor.cpu_high_watermark",
+    "system_monitor.memory_high_watermark",
+    "system_monitor.long_schedule_ms",
+    "system_monitor.busy_queue_len",
+    "cluster_dynamic_config_network",
+];
+
+pub fn init_broker_mqtt_conf_by_path(config_path: &str) -> Arc<BrokerMqttConfig> {
     // n.b. static items do not call [`Drop`] on program termination, so if
     // [`DeepThought`] impls Drop, that will not be used for this instance.
-    BROKER_MQTT_CONF.get_or_init(|| {
-        let content = match read_file(config_path) {
-            Ok(data) => data,
-            Err(e) => {
-                panic!("{}", e.to_string())
-            }
-        };
-        let new_content = override_default_by_env(content, "MQTT_SERVER");
-        let config: BrokerMqttConfig = match toml::from_str(&new_content) {
-            Ok(da) => da,
+    let lock = BROKER_MQTT_CONF.get_or_init(|| {
+        let config = match parse_broker_mqtt_conf(config_path) {
+            Ok(config) => config,
             Err(e) => {
                 panic!("{}", e)
             }
@@ -247,29 +355,96 @@ pub fn init_broker_mqtt_conf_by_path(config_path: &str) -> &'static BrokerMqttCo
                 panic!("{}", e);
             }
         }
-        config
-    })
+        let _ = BROKER_MQTT_CONF_PATH.set(config_path.to_string());
+        ArcSwap::from_pointee(config)
+    });
+    lock.load_full()
 }
 
-pub fn init_broker_mqtt_conf_by_config(config: BrokerMqttConfig) -> &'static BrokerMqttConfig {
+pub fn init_broker_mqtt_conf_by_config(config: BrokerMqttConfig) -> Arc<BrokerMqttConfig> {
     // n.b. static items do not call [`Drop`] on program termination, so if
     // [`DeepThought`] impls Drop, that will not be used for this instance.
-    BROKER_MQTT_CONF.get_or_init(|| config)
+    let lock = BROKER_MQTT_CONF.get_or_init(|| ArcSwap::from_pointee(config));
+    lock.load_full()
 }
 
-pub fn broker_mqtt_conf() -> &'static BrokerMqttConfig {
+pub fn broker_mqtt_conf() -> Arc<BrokerMqttConfig> {
     match BROKER_MQTT_CONF.get() {
-        Some(config) => config,
+        Some(config) => config.load_full(),
         None => {
             panic!("MQTT Broker configuration is not initialized, check the configuration file.");
         }
     }
 }
 
+pub fn broker_mqtt_conf_path() -> Option<String> {
+    BROKER_MQTT_CONF_PATH.get().cloned()
+}
+
+fn parse_broker_mqtt_conf(config_path: &str) -> Result<BrokerMqttConfig, CommonError> {
+    let content = read_file(config_path)?;
+    let new_content = override_default_by_env(content, "MQTT_SERVER");
+    toml::from_str(&new_content).map_err(|e| CommonError::CommonError(e.to_string()))
+}
+
+fn validate_broker_mqtt_conf(config: &BrokerMqttConfig) -> Result<(), CommonError> {
+    validate_log_config(&config.log)?;
+    let mut tls_files = vec![
+        &config.network.tls_cert,
+        &config.network.tls_key,
+        &config.network.tls_client_ca,
+    ];
+    for sni_cert in config.network.tls_sni_certs.iter() {
+        tls_files.push(&sni_cert.tls_cert);
+        tls_files.push(&sni_cert.tls_key);
+    }
+    for path in tls_files {
+        if !path.is_empty() && !file_exists(path) {
+            return Err(CommonError::CommonError(format!(
+                "TLS file {} does not exist",
+                path
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Re-reads the config file and applies the settings that can change safely.
+// An invalid file leaves the running config untouched.
+pub fn reload_broker_mqtt_conf() -> Result<ConfigReloadReport, CommonError> {
+    let (Some(config_path), Some(conf)) = (BROKER_MQTT_CONF_PATH.get(), BROKER_MQTT_CONF.get())
+    else {
+        return Err(CommonError::CommonError(
+            "MQTT Broker configuration was not loaded from a file".to_string(),
+        ));
+    };
+    let mut reloadable = COMMON_RELOADABLE_CONFIG.to_vec();
+    reloadable.extend(BROKER_MQTT_RELOADABLE_CONFIG);
+    reload_config(
+        conf,
+        || {
+            let config = parse_broker_mqtt_conf(config_path)?;
+            validate_broker_mqtt_conf(&config)?;
+            Ok(config)
+        },
+        &reloadable,
+        |report, config| {
+            apply_common_reload(
+                report,
+                &config.log,
+                &config.prometheus,
+                Some(&config.telemetry),
+                BROKER_MQTT_SERVICE_NAME,
+            )
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         broker_mqtt_conf, init_broker_mqtt_conf_by_path, override_default_by_env, BrokerMqttConfig,
+        ConfigTenantSource, SystemMonitor,
     };
     use crate::tools::read_file;
 
@@ -331,6 +506,38 @@ fn config_default_test() {
         assert_eq!(config.auth.storage_type, "placement".to_string());
         assert_eq!(config.auth.journal_addr, "".to_string());
         assert_eq!(config.auth.mysql_addr, "".to_string());
+
+        assert!(config.system_monitor.enable);
+        assert_eq!(config.system_monitor.sys_interval_secs, 60);
+        assert_eq!(config.system_monitor.sysmon_interval_secs, 10);
+        assert_eq!(config.system_monitor.busy_queue_len, 800);
+
+        assert!(!config.http_api.enable);
+        assert_eq!(config.http_api.port, 8080);
+        assert!(config.http_api.api_keys.is_empty());
+        assert_eq!(
+            config
+                .cluster_dynamic_config_retain_message
+                .max_retain_message_num,
+            100000
+        );
+        assert!(!config.cluster_dynamic_config_tenant.enable);
+        assert_eq!(
+            config.cluster_dynamic_config_tenant.source,
+            ConfigTenantSource::Username
+        );
+        assert_eq!(config.cluster_dynamic_config_tenant.username_separator, "/");
+    }
+
+    #[test]
+    fn system_monitor_partial_section_test() {
+        let config: SystemMonitor = toml::from_str("cpu_high_watermark = 90.0").unwrap();
+        assert!(config.enable);
+        assert_eq!(config.cpu_high_watermark, 90.0);
+        assert_eq!(config.memory_high_watermark, 80.0);
+        assert_eq!(config.sysmon_interval_secs, 10);
+        assert_eq!(config.long_schedule_ms, 240);
+        assert_eq!(config.busy_queue_len, 800);
     }
 
     #[test]
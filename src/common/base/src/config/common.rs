@@ -41,8 +41,12 @@ pub struct Prometheus {
     pub push_gateway_server: String,
     #[serde(default)]
     pub interval: u32,
+    // One `Name: value` pair per line, sent as HTTP headers in push mode.
     #[serde(default)]
     pub header: String,
+    // Comma separated `key=value` pairs, used as push gateway grouping labels.
+    #[serde(default)]
+    pub labels: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
@@ -75,6 +79,7 @@ pub fn default_prometheus() -> Prometheus {
         push_gateway_server: "".to_string(),
         interval: 10,
         header: "".to_string(),
+        labels: "".to_string(),
     }
 }
 
@@ -155,3 +160,11 @@ fn override_default_by_env() {
         );
     }
 }
+
+pub fn default_telemetry() -> Telemetry {
+    Telemetry {
+        enable: false,
+        exporter_endpoint: "grpc://127.0.0.1:4317".to_string(),
+        exporter_type: "otlp".to_string(),
+    }
+}
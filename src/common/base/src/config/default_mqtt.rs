@@ -12,13 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use super::broker_mqtt::{
-    ConfigAvailableFlag, MqttClusterDynamicConfigFeature, MqttClusterDynamicConfigNetwork,
+    ConfigAvailableFlag, ConfigTenantSource, HttpApi, MqttClusterDynamicAlarm,
+    MqttClusterDynamicConfigFeature, MqttClusterDynamicConfigNetwork,
     MqttClusterDynamicConfigProtocol, MqttClusterDynamicConfigSecurity,
-    MqttClusterDynamicFlappingDetect, MqttClusterDynamicSlowSub, Network, OfflineMessage, System,
-    TcpThread,
+    MqttClusterDynamicFlappingDetect, MqttClusterDynamicRetainMessage, MqttClusterDynamicSlowSub,
+    MqttClusterDynamicTenant, Network, OfflineMessage, System, SystemMonitor, TcpThread,
 };
-use super::common::{Auth, Log, Storage, Telemetry};
+use super::common::{Auth, Log, Storage};
 
 pub fn default_grpc_port() -> u32 {
     9981
@@ -37,6 +40,8 @@ pub fn default_network() -> Network {
         quic_port: default_network_quic_port(),
         tls_cert: "".to_string(),
         tls_key: "".to_string(),
+        tls_sni_certs: Vec::new(),
+        tls_client_ca: "".to_string(),
     }
 }
 pub fn default_network_tcp_port() -> u32 {
@@ -76,6 +81,54 @@ pub fn default_system() -> System {
     }
 }
 
+pub fn default_system_monitor() -> SystemMonitor {
+    SystemMonitor {
+        enable: default_system_monitor_enable(),
+        sys_interval_secs: default_sys_interval_secs(),
+        sysmon_interval_secs: default_sysmon_interval_secs(),
+        cpu_high_watermark: default_cpu_high_watermark(),
+        memory_high_watermark: default_memory_high_watermark(),
+        long_schedule_ms: default_long_schedule_ms(),
+        busy_queue_len: default_busy_queue_len(),
+    }
+}
+
+pub fn default_system_monitor_enable() -> bool {
+    true
+}
+
+pub fn default_sys_interval_secs() -> u64 {
+    60
+}
+
+pub fn default_sysmon_interval_secs() -> u64 {
+    10
+}
+
+pub fn default_cpu_high_watermark() -> f32 {
+    80.0
+}
+
+pub fn default_memory_high_watermark() -> f32 {
+    80.0
+}
+
+pub fn default_long_schedule_ms() -> u64 {
+    240
+}
+
+pub fn default_busy_queue_len() -> usize {
+    800
+}
+
+pub fn default_http_api() -> HttpApi {
+    HttpApi {
+        enable: false,
+        port: 8080,
+        api_keys: Vec::new(),
+    }
+}
+
 pub fn default_storage() -> Storage {
     Storage {
         storage_type: "memory".to_string(),
@@ -109,14 +162,6 @@ pub fn default_auth() -> Auth {
     }
 }
 
-pub fn default_telemetry() -> Telemetry {
-    Telemetry {
-        enable: false,
-        exporter_endpoint: "grpc://127.0.0.1:4317".to_string(),
-        exporter_type: "otlp".to_string(),
-    }
-}
-
 pub fn default_mqtt_cluster_dynamic_feature() -> MqttClusterDynamicConfigFeature {
     MqttClusterDynamicConfigFeature {
         retain_available: ConfigAvailableFlag::Enable,
@@ -166,6 +211,34 @@ pub fn default_mqtt_cluster_dynamic_flapping_detect() -> MqttClusterDynamicFlapp
     }
 }
 
+pub fn default_mqtt_cluster_dynamic_alarm() -> MqttClusterDynamicAlarm {
+    MqttClusterDynamicAlarm {
+        enable: true,
+        check_interval_secs: 30,
+        connection_high_watermark: 10000,
+        storage_write_failures: 10,
+        flapping_detect_bans: 10,
+        schema_validation_failures: 100,
+        slow_subscriptions: 100,
+    }
+}
+
+pub fn default_mqtt_cluster_dynamic_retain_message() -> MqttClusterDynamicRetainMessage {
+    MqttClusterDynamicRetainMessage {
+        max_retain_message_num: 100000,
+    }
+}
+
+pub fn default_mqtt_cluster_dynamic_tenant() -> MqttClusterDynamicTenant {
+    MqttClusterDynamicTenant {
+        enable: false,
+        source: ConfigTenantSource::Username,
+        username_separator: "/".to_string(),
+        cert_field: "O".to_string(),
+        listener_tenants: HashMap::new(),
+    }
+}
+
 pub fn default_mqtt_cluster_dynamic_network() -> MqttClusterDynamicConfigNetwork {
     MqttClusterDynamicConfigNetwork {
         tcp_max_connection_num: 1000,
@@ -15,7 +15,7 @@
 use toml::Table;
 
 use super::common::Log;
-use super::placement_center::{Heartbeat, Network, Node, Rocksdb, System};
+use super::placement_center::{Heartbeat, Network, Node, Raft, Rocksdb, System};
 
 pub fn default_cluster_name() -> String {
     "placement-center".to_string()
@@ -107,3 +107,23 @@ pub fn default_heartbeat_timeout_ms() -> u64 {
 pub fn default_heartbeat_check_time_ms() -> u64 {
     1000
 }
+
+pub fn default_raft() -> Raft {
+    Raft {
+        snapshot_max_chunk_size: default_snapshot_max_chunk_size(),
+        snapshot_logs_since_last: default_snapshot_logs_since_last(),
+        max_in_snapshot_log_to_keep: default_max_in_snapshot_log_to_keep(),
+    }
+}
+
+pub fn default_snapshot_max_chunk_size() -> u64 {
+    3 * 1024 * 1024
+}
+
+pub fn default_snapshot_logs_since_last() -> u64 {
+    5000
+}
+
+pub fn default_max_in_snapshot_log_to_keep() -> u64 {
+    1000
+}
@@ -12,19 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
-use serde::Deserialize;
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
 
-use super::common::{default_prometheus, Log, Prometheus};
+use super::common::{default_prometheus, default_telemetry, Log, Prometheus, Telemetry};
 use super::default_journal_server::{
     default_enable_auto_create_shard, default_grpc_port, default_local_ip, default_log,
     default_max_segment_size, default_network, default_network_tcp_port, default_network_tcps_port,
     default_shard, default_shard_replica_num, default_storage, default_system, default_tcp_thread,
 };
+use super::reload::{
+    apply_common_reload, reload_config, validate_log_config, ConfigReloadReport,
+    COMMON_RELOADABLE_CONFIG,
+};
+use crate::error::common::CommonError;
 use crate::tools::{read_file, try_create_fold};
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct JournalServerConfig {
     pub cluster_name: String,
     pub node_id: u64,
@@ -44,9 +50,11 @@ pub struct JournalServerConfig {
     pub prometheus: Prometheus,
     #[serde(default = "default_log")]
     pub log: Log,
+    #[serde(default = "default_telemetry")]
+    pub telemetry: Telemetry,
 }
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Network {
     #[serde(default = "default_local_ip")]
     pub local_ip: String,
@@ -62,20 +70,20 @@ pub struct Network {
     pub tls_key: String,
 }
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct System {
     #[serde(default)]
     pub runtime_work_threads: usize,
 }
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Storage {
     #[serde(default)]
     pub data_path: Vec<String>,
     pub rocksdb_max_open_files: Option<i32>,
 }
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Shard {
     #[serde(default = "default_enable_auto_create_shard")]
     pub enable_auto_create_shard: bool,
@@ -85,7 +93,7 @@ pub struct Shard {
     pub max_segment_size: u32,
 }
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct TcpThread {
     #[serde(default)]
     pub accept_thread_num: usize,
@@ -101,19 +109,23 @@ pub struct TcpThread {
     pub response_queue_size: usize,
 }
 
-static STORAGE_ENGINE_CONFIG: OnceLock<JournalServerConfig> = OnceLock::new();
+// Swapped as a whole on reload. Readers keep the snapshot they loaded until
+// they drop it, and the old config is freed with the last snapshot.
+static STORAGE_ENGINE_CONFIG: OnceLock<ArcSwap<JournalServerConfig>> = OnceLock::new();
+static STORAGE_ENGINE_CONFIG_PATH: OnceLock<String> = OnceLock::new();
+
+pub const JOURNAL_SERVER_SERVICE_NAME: &str = "journal-server";
 
-pub fn init_journal_server_conf_by_path(config_path: &str) -> &'static JournalServerConfig {
+pub fn init_journal_server_conf_by_path(config_path: &str) -> Arc<JournalServerConfig> {
     // n.b. static items do not call [`Drop`] on program termination, so if
     // [`DeepThought`] impls Drop, that will not be used for this instance.
-    STORAGE_ENGINE_CONFIG.get_or_init(|| {
-        let content = match read_file(config_path) {
-            Ok(data) => data,
+    let lock = STORAGE_ENGINE_CONFIG.get_or_init(|| {
+        let pc_config = match parse_journal_server_conf(config_path) {
+            Ok(config) => config,
             Err(e) => {
                 panic!("{}", e.to_string());
             }
         };
-        let pc_config: JournalServerConfig = toml::from_str(&content).unwrap();
         for fold in pc_config.storage.data_path.clone() {
             match try_create_fold(&fold) {
                 Ok(()) => {}
@@ -129,21 +141,22 @@ pub fn init_journal_server_conf_by_path(config_path: &str) -> &'static JournalSe
                 panic!("{}", e);
             }
         }
-        pc_config
-    })
+        let _ = STORAGE_ENGINE_CONFIG_PATH.set(config_path.to_string());
+        ArcSwap::from_pointee(pc_config)
+    });
+    lock.load_full()
 }
 
-pub fn init_journal_server_conf_by_config(
-    config: JournalServerConfig,
-) -> &'static JournalServerConfig {
+pub fn init_journal_server_conf_by_config(config: JournalServerConfig) -> Arc<JournalServerConfig> {
     // n.b. static items do not call [`Drop`] on program termination, so if
     // [`DeepThought`] impls Drop, that will not be used for this instance.
-    STORAGE_ENGINE_CONFIG.get_or_init(|| config)
+    let lock = STORAGE_ENGINE_CONFIG.get_or_init(|| ArcSwap::from_pointee(config));
+    lock.load_full()
 }
 
-pub fn journal_server_conf() -> &'static JournalServerConfig {
+pub fn journal_server_conf() -> Arc<JournalServerConfig> {
     match STORAGE_ENGINE_CONFIG.get() {
-        Some(config) => config,
+        Some(config) => config.load_full(),
         None => {
             panic!(
                 "Placement center configuration is not initialized, check the configuration file."
@@ -152,6 +165,46 @@ pub fn journal_server_conf() -> &'static JournalServerConfig {
     }
 }
 
+pub fn journal_server_conf_path() -> Option<String> {
+    STORAGE_ENGINE_CONFIG_PATH.get().cloned()
+}
+
+fn parse_journal_server_conf(config_path: &str) -> Result<JournalServerConfig, CommonError> {
+    let content = read_file(config_path)?;
+    toml::from_str(&content).map_err(|e| CommonError::CommonError(e.to_string()))
+}
+
+// Re-reads the config file and applies the settings that can change safely.
+// An invalid file leaves the running config untouched.
+pub fn reload_journal_server_conf() -> Result<ConfigReloadReport, CommonError> {
+    let (Some(config_path), Some(conf)) = (
+        STORAGE_ENGINE_CONFIG_PATH.get(),
+        STORAGE_ENGINE_CONFIG.get(),
+    ) else {
+        return Err(CommonError::CommonError(
+            "Journal server configuration was not loaded from a file".to_string(),
+        ));
+    };
+    reload_config(
+        conf,
+        || {
+            let config = parse_journal_server_conf(config_path)?;
+            validate_log_config(&config.log)?;
+            Ok(config)
+        },
+        &COMMON_RELOADABLE_CONFIG,
+        |report, config| {
+            apply_common_reload(
+                report,
+                &config.log,
+                &config.prometheus,
+                Some(&config.telemetry),
+                JOURNAL_SERVER_SERVICE_NAME,
+            )
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::init_journal_server_conf_by_path;
@@ -13,19 +13,27 @@
 // limitations under the License.
 
 use std::env::temp_dir;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
 use toml::map::Map;
 use toml::{Table, Value};
 
-use super::common::{override_default_by_env, Log};
+use super::common::{default_prometheus, override_default_by_env, Log, Prometheus};
 use super::default_placement_center::{
     default_cluster_name, default_data_path, default_grpc_port, default_heartbeat,
     default_heartbeat_check_time_ms, default_heartbeat_timeout_ms, default_http_port,
-    default_local_ip, default_log, default_max_open_files, default_network, default_node,
-    default_node_id, default_nodes, default_rocksdb, default_runtime_work_threads, default_system,
+    default_local_ip, default_log, default_max_in_snapshot_log_to_keep, default_max_open_files,
+    default_network, default_node, default_node_id, default_nodes, default_raft, default_rocksdb,
+    default_runtime_work_threads, default_snapshot_logs_since_last,
+    default_snapshot_max_chunk_size, default_system,
 };
+use super::reload::{
+    apply_common_reload, reload_config, validate_log_config, ConfigReloadReport,
+    COMMON_RELOADABLE_CONFIG,
+};
+use crate::error::common::CommonError;
 use crate::tools::{read_file, try_create_fold};
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -42,8 +50,12 @@ pub struct PlacementCenterConfig {
     pub heartbeat: Heartbeat,
     #[serde(default = "default_rocksdb")]
     pub rocksdb: Rocksdb,
+    #[serde(default = "default_raft")]
+    pub raft: Raft,
     #[serde(default = "default_log")]
     pub log: Log,
+    #[serde(default = "default_prometheus")]
+    pub prometheus: Prometheus,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -86,20 +98,42 @@ pub struct Rocksdb {
     pub max_open_files: Option<i32>,
 }
 
-static PLACEMENT_CENTER_CONF: OnceLock<PlacementCenterConfig> = OnceLock::new();
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Raft {
+    // Size in bytes of each chunk a snapshot is split into when sent to a follower
+    #[serde(default = "default_snapshot_max_chunk_size")]
+    pub snapshot_max_chunk_size: u64,
+    // Build a snapshot once this many logs have been applied since the last one
+    #[serde(default = "default_snapshot_logs_since_last")]
+    pub snapshot_logs_since_last: u64,
+    // Logs covered by a snapshot that are kept before being purged
+    #[serde(default = "default_max_in_snapshot_log_to_keep")]
+    pub max_in_snapshot_log_to_keep: u64,
+}
+
+impl Default for Raft {
+    fn default() -> Self {
+        default_raft()
+    }
+}
+
+// Swapped as a whole on reload. Readers keep the snapshot they loaded until
+// they drop it, and the old config is freed with the last snapshot.
+static PLACEMENT_CENTER_CONF: OnceLock<ArcSwap<PlacementCenterConfig>> = OnceLock::new();
+static PLACEMENT_CENTER_CONF_PATH: OnceLock<String> = OnceLock::new();
+
+pub const PLACEMENT_CENTER_SERVICE_NAME: &str = "placement-center";
 
-pub fn init_placement_center_conf_by_path(config_path: &str) -> &'static PlacementCenterConfig {
+pub fn init_placement_center_conf_by_path(config_path: &str) -> Arc<PlacementCenterConfig> {
     // n.b. static items do not call [`Drop`] on program termination, so if
     // [`DeepThought`] impls Drop, that will not be used for this instance.
-    PLACEMENT_CENTER_CONF.get_or_init(|| {
-        let content = match read_file(config_path) {
-            Ok(data) => data,
+    let lock = PLACEMENT_CENTER_CONF.get_or_init(|| {
+        let pc_config = match parse_placement_center_conf(config_path) {
+            Ok(config) => config,
             Err(e) => {
                 panic!("{}", e.to_string());
             }
         };
-        let new_content = override_default_by_env(content, "PLACEMENT_CENTER");
-        let pc_config: PlacementCenterConfig = toml::from_str(&new_content).unwrap();
         match try_create_fold(&pc_config.rocksdb.data_path) {
             Ok(()) => {}
             Err(e) => {
@@ -112,13 +146,15 @@ pub fn init_placement_center_conf_by_path(config_path: &str) -> &'static Placeme
                 panic!("{}", e);
             }
         }
-        pc_config
-    })
+        let _ = PLACEMENT_CENTER_CONF_PATH.set(config_path.to_string());
+        ArcSwap::from_pointee(pc_config)
+    });
+    lock.load_full()
 }
 
 pub fn init_placement_center_conf_by_config(
     config: PlacementCenterConfig,
-) -> &'static PlacementCenterConfig {
+) -> Arc<PlacementCenterConfig> {
     // n.b. static items do not call [`Drop`] on program termination, so if
     // [`DeepThought`] impls Drop, that will not be used for this instance.
     match try_create_fold(&config.rocksdb.data_path) {
@@ -133,12 +169,13 @@ pub fn init_placement_center_conf_by_config(
             panic!("{}", e);
         }
     }
-    PLACEMENT_CENTER_CONF.get_or_init(|| config)
+    let lock = PLACEMENT_CENTER_CONF.get_or_init(|| ArcSwap::from_pointee(config));
+    lock.load_full()
 }
 
-pub fn placement_center_conf() -> &'static PlacementCenterConfig {
+pub fn placement_center_conf() -> Arc<PlacementCenterConfig> {
     match PLACEMENT_CENTER_CONF.get() {
-        Some(config) => config,
+        Some(config) => config.load_full(),
         None => {
             panic!(
                 "Placement center configuration is not initialized, check the configuration file."
@@ -147,6 +184,47 @@ pub fn placement_center_conf() -> &'static PlacementCenterConfig {
     }
 }
 
+pub fn placement_center_conf_path() -> Option<String> {
+    PLACEMENT_CENTER_CONF_PATH.get().cloned()
+}
+
+fn parse_placement_center_conf(config_path: &str) -> Result<PlacementCenterConfig, CommonError> {
+    let content = read_file(config_path)?;
+    let new_content = override_default_by_env(content, "PLACEMENT_CENTER");
+    toml::from_str(&new_content).map_err(|e| CommonError::CommonError(e.to_string()))
+}
+
+// Re-reads the config file and applies the settings that can change safely.
+// An invalid file leaves the running config untouched.
+pub fn reload_placement_center_conf() -> Result<ConfigReloadReport, CommonError> {
+    let (Some(config_path), Some(conf)) = (
+        PLACEMENT_CENTER_CONF_PATH.get(),
+        PLACEMENT_CENTER_CONF.get(),
+    ) else {
+        return Err(CommonError::CommonError(
+            "Placement center configuration was not loaded from a file".to_string(),
+        ));
+    };
+    reload_config(
+        conf,
+        || {
+            let config = parse_placement_center_conf(config_path)?;
+            validate_log_config(&config.log)?;
+            Ok(config)
+        },
+        &COMMON_RELOADABLE_CONFIG,
+        |report, config| {
+            apply_common_reload(
+                report,
+                &config.log,
+                &config.prometheus,
+                None,
+                PLACEMENT_CENTER_SERVICE_NAME,
+            )
+        },
+    )
+}
+
 pub fn placement_center_test_conf() -> PlacementCenterConfig {
     let rocksdb = Rocksdb {
         data_path: temp_dir().to_str().unwrap().to_string(),
@@ -185,7 +263,7 @@ fn meta_default() {
             "{ 1 = \"127.0.0.1:1228\" , 2 = \"127.0.0.1:1227\" }",
         );
         init_placement_center_conf_by_path(&path);
-        let config: &PlacementCenterConfig = placement_center_conf();
+        let config = placement_center_conf();
         println!("{:?}", config);
         assert_eq!(config.cluster_name, "placement-test");
         assert_eq!(config.node.node_id, 2);
@@ -214,5 +292,10 @@ fn meta_default() {
         assert_eq!(config.rocksdb.max_open_files, Some(10000_i32));
         assert_eq!(config.heartbeat.heartbeat_timeout_ms, 5000);
         assert_eq!(config.heartbeat.heartbeat_check_time_ms, 1000);
+        assert_eq!(config.raft.snapshot_max_chunk_size, 3 * 1024 * 1024);
+        assert_eq!(config.raft.snapshot_logs_since_last, 5000);
+        assert!(!config.prometheus.enable);
+        assert_eq!(config.prometheus.model, "pull");
+        assert_eq!(config.prometheus.port, 9091);
     }
 }
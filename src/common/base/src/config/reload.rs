@@ -0,0 +1,435 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime};
+
+use arc_swap::ArcSwap;
+use log::{error, info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::select;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+use super::common::{Log, Prometheus, Telemetry};
+use crate::error::common::CommonError;
+use crate::logs::reload_log;
+use crate::metrics::push::update_push_gateway_conf;
+use crate::telemetry::trace::reload_tracer_provider;
+
+// Settings shared by every server that can change without a restart.
+pub const COMMON_RELOADABLE_CONFIG: [&str; 6] = [
+    "log",
+    "telemetry",
+    "prometheus.push_gateway_server",
+    "prometheus.interval",
+    "prometheus.header",
+    "prometheus.labels",
+];
+
+const CONFIG_FILE_CHECK_INTERVAL_SECS: u64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ConfigReloadReport {
+    // Changed settings that are in effect now
+    pub applied: Vec<String>,
+    // Changed settings that only take effect after a restart
+    pub restart_required: Vec<String>,
+}
+
+impl ConfigReloadReport {
+    pub fn is_applied(&self, prefix: &str) -> bool {
+        self.applied.iter().any(|path| path_match(path, prefix))
+    }
+}
+
+static CONFIG_RELOAD_SENDER: LazyLock<broadcast::Sender<ConfigReloadReport>> =
+    LazyLock::new(|| broadcast::channel(8).0);
+
+// Components that cache settings at start subscribe to learn about reloads.
+pub fn subscribe_config_reload() -> broadcast::Receiver<ConfigReloadReport> {
+    CONFIG_RELOAD_SENDER.subscribe()
+}
+
+pub fn publish_config_reload(report: &ConfigReloadReport) {
+    // No receiver is not an error, nothing cached the changed settings.
+    let _ = CONFIG_RELOAD_SENDER.send(report.clone());
+}
+
+fn path_match(path: &str, prefix: &str) -> bool {
+    path == prefix || path.starts_with(&format!("{}.", prefix))
+}
+
+fn is_reloadable(path: &str, reloadable: &[&str]) -> bool {
+    reloadable.iter().any(|prefix| path_match(path, prefix))
+}
+
+// Dotted paths of the leaves that differ between two serialized configs.
+pub fn changed_paths(current: &Value, new: &Value) -> Vec<String> {
+    let mut results = Vec::new();
+    collect_changed_paths("", current, new, &mut results);
+    results
+}
+
+fn collect_changed_paths(prefix: &str, current: &Value, new: &Value, results: &mut Vec<String>) {
+    match (current, new) {
+        (Value::Object(current_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = current_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_changed_paths(
+                    &path,
+                    current_map.get(key).unwrap_or(&Value::Null),
+                    new_map.get(key).unwrap_or(&Value::Null),
+                    results,
+                );
+            }
+        }
+        (current, new) => {
+            if current != new {
+                results.push(prefix.to_string());
+            }
+        }
+    }
+}
+
+fn set_path(target: &mut Value, path: &str, value: Value) {
+    let mut node = target;
+    let levels: Vec<&str> = path.split('.').collect();
+    for (i, level) in levels.iter().enumerate() {
+        let Value::Object(map) = node else {
+            return;
+        };
+        if i == levels.len() - 1 {
+            map.insert(level.to_string(), value);
+            return;
+        }
+        node = map
+            .entry(level.to_string())
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+}
+
+fn get_path<'a>(source: &'a Value, path: &str) -> &'a Value {
+    let mut node = source;
+    for level in path.split('.') {
+        node = node.get(level).unwrap_or(&Value::Null);
+    }
+    node
+}
+
+// Builds the config that takes effect now: the current config with only the
+// reloadable settings taken from `new`. Everything else that changed is
+// reported as needing a restart.
+pub fn merge_reloadable_config<T>(
+    current: &T,
+    new: &T,
+    reloadable: &[&str],
+) -> Result<(T, ConfigReloadReport), CommonError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let current_value = serde_json::to_value(current)?;
+    let new_value = serde_json::to_value(new)?;
+
+    let mut merged = current_value.clone();
+    let mut report = ConfigReloadReport::default();
+    for path in changed_paths(&current_value, &new_value) {
+        if is_reloadable(&path, reloadable) {
+            set_path(&mut merged, &path, get_path(&new_value, &path).clone());
+            report.applied.push(path);
+        } else {
+            report.restart_required.push(path);
+        }
+    }
+
+    Ok((serde_json::from_value(merged)?, report))
+}
+
+// The reload every server runs: load and check the new file, take over only
+// the reloadable settings, apply them and swap the config in for readers.
+pub fn reload_config<T, L, A>(
+    conf: &ArcSwap<T>,
+    load: L,
+    reloadable: &[&str],
+    apply: A,
+) -> Result<ConfigReloadReport, CommonError>
+where
+    T: Serialize + DeserializeOwned,
+    L: FnOnce() -> Result<T, CommonError>,
+    A: FnOnce(&ConfigReloadReport, &T) -> Result<(), CommonError>,
+{
+    let new_config = load()?;
+    let (config, report) = merge_reloadable_config(conf.load().as_ref(), &new_config, reloadable)?;
+    if report.applied.is_empty() {
+        return Ok(report);
+    }
+
+    apply(&report, &config)?;
+    conf.store(Arc::new(config));
+    publish_config_reload(&report);
+    Ok(report)
+}
+
+pub fn validate_log_config(log: &Log) -> Result<(), CommonError> {
+    if !std::path::Path::new(&log.log_config).exists() {
+        return Err(CommonError::CommonError(format!(
+            "Logging configuration file {} does not exist",
+            log.log_config
+        )));
+    }
+    Ok(())
+}
+
+// Applies the settings every server handles the same way.
+pub fn apply_common_reload(
+    report: &ConfigReloadReport,
+    log: &Log,
+    prometheus: &Prometheus,
+    telemetry: Option<&Telemetry>,
+    service_name: &str,
+) -> Result<(), CommonError> {
+    if report.is_applied("log") {
+        reload_log(&log.log_config, &log.log_path)?;
+    }
+    if report.is_applied("prometheus") {
+        update_push_gateway_conf(prometheus.clone());
+    }
+    if let Some(telemetry) = telemetry {
+        if report.is_applied("telemetry") {
+            reload_tracer_provider(telemetry, service_name);
+        }
+    }
+    Ok(())
+}
+
+pub fn file_modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(unix)]
+struct HangupSignal(Signal);
+
+#[cfg(unix)]
+impl HangupSignal {
+    fn new() -> std::io::Result<Self> {
+        signal(SignalKind::hangup()).map(HangupSignal)
+    }
+
+    async fn recv(&mut self) {
+        self.0.recv().await;
+    }
+}
+
+// There is no SIGHUP outside unix, only file changes trigger a reload there.
+#[cfg(not(unix))]
+struct HangupSignal;
+
+#[cfg(not(unix))]
+impl HangupSignal {
+    fn new() -> std::io::Result<Self> {
+        Ok(HangupSignal)
+    }
+
+    async fn recv(&mut self) {
+        std::future::pending::<()>().await;
+    }
+}
+
+// Reloads the configuration on SIGHUP and whenever the config file changes.
+pub async fn start_config_reload_watcher(
+    config_path: String,
+    reload: fn() -> Result<ConfigReloadReport, CommonError>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let mut hangup = match HangupSignal::new() {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!("Failed to listen for SIGHUP, error message:{}", e);
+            return;
+        }
+    };
+    let mut stop_rx = stop_send.subscribe();
+    let mut last_modified = file_modified_time(&config_path);
+
+    loop {
+        select! {
+            val = stop_rx.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        info!("{}", "Config reload watcher stopped successfully.");
+                        break;
+                    }
+                }
+            }
+            _ = hangup.recv() => {
+                info!("SIGHUP received, reloading config file {}", config_path);
+                last_modified = file_modified_time(&config_path);
+                log_reload_result(reload());
+            }
+            _ = sleep(Duration::from_secs(CONFIG_FILE_CHECK_INTERVAL_SECS)) => {
+                let modified = file_modified_time(&config_path);
+                if modified.is_some() && modified != last_modified {
+                    info!("Config file {} changed, reloading", config_path);
+                    last_modified = modified;
+                    log_reload_result(reload());
+                }
+            }
+        }
+    }
+}
+
+fn log_reload_result(result: Result<ConfigReloadReport, CommonError>) {
+    match result {
+        Ok(report) => {
+            info!("Config reloaded, applied settings: {:?}", report.applied);
+            if !report.restart_required.is_empty() {
+                warn!(
+                    "Changed settings that need a restart to take effect: {:?}",
+                    report.restart_required
+                );
+            }
+        }
+        Err(e) => {
+            error!(
+                "Config reload failed, the running config is unchanged. error message:{}",
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use arc_swap::ArcSwap;
+
+    use super::{changed_paths, merge_reloadable_config, reload_config, ConfigReloadReport};
+    use crate::error::common::CommonError;
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    struct Inner {
+        a: u32,
+        b: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    struct Conf {
+        port: u32,
+        inner: Inner,
+        list: Vec<String>,
+    }
+
+    #[test]
+    fn changed_paths_test() {
+        let current = json!({"a": 1, "b": {"c": "x", "d": [1]}});
+        let new = json!({"a": 1, "b": {"c": "y", "d": [1, 2]}, "e": true});
+        assert_eq!(
+            changed_paths(&current, &new),
+            vec!["b.c".to_string(), "b.d".to_string(), "e".to_string()]
+        );
+        assert!(changed_paths(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn merge_reloadable_config_test() {
+        let current = Conf {
+            port: 1883,
+            inner: Inner {
+                a: 1,
+                b: "x".to_string(),
+            },
+            list: vec![],
+        };
+        let new = Conf {
+            port: 1884,
+            inner: Inner {
+                a: 2,
+                b: "y".to_string(),
+            },
+            list: vec!["z".to_string()],
+        };
+
+        let (merged, report) =
+            merge_reloadable_config(&current, &new, &["inner.a", "list"]).unwrap();
+        assert_eq!(merged.port, 1883);
+        assert_eq!(merged.inner.a, 2);
+        assert_eq!(merged.inner.b, "x");
+        assert_eq!(merged.list, vec!["z".to_string()]);
+        assert_eq!(
+            report,
+            ConfigReloadReport {
+                applied: vec!["inner.a".to_string(), "list".to_string()],
+                restart_required: vec!["inner.b".to_string(), "port".to_string()],
+            }
+        );
+        assert!(report.is_applied("inner"));
+        assert!(!report.is_applied("in"));
+    }
+
+    #[test]
+    fn reload_config_test() {
+        let current = Conf {
+            port: 1883,
+            inner: Inner {
+                a: 1,
+                b: "x".to_string(),
+            },
+            list: vec![],
+        };
+        let conf = ArcSwap::from_pointee(current.clone());
+
+        let result = reload_config(
+            &conf,
+            || Err(CommonError::CommonError("invalid file".to_string())),
+            &["inner"],
+            |_, _| Ok(()),
+        );
+        assert!(result.is_err());
+        assert_eq!(*conf.load_full(), current);
+
+        let mut new = current.clone();
+        new.port = 1884;
+        new.inner.a = 2;
+        let mut applied = None;
+        let report = reload_config(
+            &conf,
+            || Ok(new),
+            &["inner"],
+            |report, config: &Conf| {
+                applied = Some((report.applied.clone(), config.inner.a));
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(report.applied, vec!["inner.a".to_string()]);
+        assert_eq!(report.restart_required, vec!["port".to_string()]);
+        assert_eq!(applied, Some((vec!["inner.a".to_string()], 2)));
+        assert_eq!(conf.load().port, 1883);
+        assert_eq!(conf.load().inner.a, 2);
+    }
+}
@@ -12,11 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::OnceLock;
+
+use log4rs::config::RawConfig;
+use log4rs::Handle;
+
 use crate::config::broker_mqtt::broker_mqtt_conf;
 use crate::config::journal_server::journal_server_conf;
 use crate::config::placement_center::placement_center_conf;
+use crate::error::common::CommonError;
 use crate::tools::{file_exists, read_file, try_create_fold};
 
+static LOG_HANDLE: OnceLock<Handle> = OnceLock::new();
+
 pub fn init_placement_center_log() {
     let conf = placement_center_conf();
     init_log(&conf.log.log_config, &conf.log.log_path);
@@ -33,43 +41,66 @@ pub fn init_journal_server_log() {
 }
 
 pub fn init_log(log_config_file: &String, log_path: &String) {
+    let config = match build_log_config(log_config_file, log_path) {
+        Ok(config) => config,
+        Err(e) => {
+            panic!("{}", e.to_string());
+        }
+    };
+    match log4rs::init_config(config) {
+        Ok(handle) => {
+            let _ = LOG_HANDLE.set(handle);
+        }
+        Err(e) => {
+            panic!("{}", e.to_string());
+        }
+    }
+}
+
+// Swaps the logger config in place, log records in flight are not lost.
+pub fn reload_log(log_config_file: &String, log_path: &String) -> Result<(), CommonError> {
+    let config = build_log_config(log_config_file, log_path)?;
+    let Some(handle) = LOG_HANDLE.get() else {
+        return Err(CommonError::CommonError(
+            "Logging is not initialized".to_string(),
+        ));
+    };
+    handle.set_config(config);
+    Ok(())
+}
+
+fn build_log_config(
+    log_config_file: &String,
+    log_path: &String,
+) -> Result<log4rs::Config, CommonError> {
     if !file_exists(log_config_file) {
-        panic!(
+        return Err(CommonError::CommonError(format!(
             "Logging configuration file {} does not exist",
             log_config_file
-        );
+        )));
     }
 
-    let content = match read_file(log_config_file) {
-        Ok(data) => data,
-        Err(e) => {
-            panic!("{}", e.to_string());
-        }
-    };
+    let content = read_file(log_config_file)?;
 
-    match try_create_fold(log_path) {
-        Ok(()) => {}
-        Err(_) => {
-            panic!("Failed to initialize log directory {}", log_path);
-        }
+    if try_create_fold(log_path).is_err() {
+        return Err(CommonError::CommonError(format!(
+            "Failed to initialize log directory {}",
+            log_path
+        )));
     }
 
     let config_content = content.replace("{$path}", log_path);
-    let config = match serde_yaml::from_str(&config_content) {
+    let raw_config: RawConfig = match serde_yaml::from_str(&config_content) {
         Ok(data) => data,
         Err(e) => {
-            panic!(
+            return Err(CommonError::CommonError(format!(
                 "Failed to parse the contents of the config file {} with error message :{}",
                 log_config_file, e
-            );
+            )));
         }
     };
-    match log4rs::init_raw_config(config) {
-        Ok(_) => {}
-        Err(e) => {
-            panic!("{}", e.to_string());
-        }
-    }
+    log4rs::config::create_raw_config(raw_config)
+        .map_err(|e| CommonError::CommonError(e.to_string()))
 }
 
 #[cfg(test)]
@@ -13,12 +13,15 @@
 // limitations under the License.
 
 pub mod broker;
+pub mod push;
 pub mod registry;
 use axum::routing::get;
 use axum::Router;
 use log::info;
 use prometheus_client::encoding::text::encode;
 
+use crate::config::common::Prometheus;
+
 // lazy_static::lazy_static! {
 //     static ref APP_VERSION: IntGaugeVec =
 //         prometheus::register_int_gauge_vec!("app_version", "app version", &["short_version", "version"]).unwrap();
@@ -35,6 +38,15 @@ pub fn dump_metrics() -> String {
     buffer
 }
 
+// `model = "push"` pushes to the configured gateway, anything else serves `/metrics`.
+pub async fn start_prometheus_export(conf: Prometheus, job: &str) {
+    if conf.model == "push" {
+        push::start_push_gateway(conf, job).await;
+    } else {
+        register_prometheus_export(conf.port).await;
+    }
+}
+
 pub async fn register_prometheus_export(port: u32) {
     let ip = format!("0.0.0.0:{}", port);
     let route = Router::new().route("/metrics", get(route_metrics));
@@ -0,0 +1,219 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use log::{error, info};
+use reqwest::Client;
+use tokio::time::sleep;
+
+use super::dump_metrics;
+use crate::config::common::Prometheus;
+use crate::error::common::CommonError;
+use crate::tools::get_local_ip;
+
+// Push settings that can change while the push loop is running
+static PUSH_GATEWAY_CONF: RwLock<Option<Prometheus>> = RwLock::new(None);
+
+pub fn update_push_gateway_conf(conf: Prometheus) {
+    *PUSH_GATEWAY_CONF.write().unwrap() = Some(conf);
+}
+
+fn current_push_gateway_conf() -> Option<Prometheus> {
+    PUSH_GATEWAY_CONF.read().unwrap().clone()
+}
+
+pub async fn start_push_gateway(conf: Prometheus, job: &str) {
+    update_push_gateway_conf(conf.clone());
+    info!(
+        "Prometheus push gateway started successfully, pushing to {} every {}s",
+        conf.push_gateway_server, conf.interval
+    );
+    let client = Client::new();
+    loop {
+        let conf = current_push_gateway_conf().unwrap_or(conf.clone());
+        let url = build_push_url(
+            &conf.push_gateway_server,
+            job,
+            &parse_key_values(&conf.labels),
+        );
+        let headers = parse_headers(&conf.header);
+        if let Err(e) = push_metrics(&client, &url, &headers).await {
+            error!("Failed to push metrics to {}, error message: {}", url, e);
+        }
+        sleep(Duration::from_secs(conf.interval.max(1) as u64)).await;
+    }
+}
+
+async fn push_metrics(
+    client: &Client,
+    url: &str,
+    headers: &[(String, String)],
+) -> Result<(), CommonError> {
+    // PUT replaces the whole group, every push carries the full registry.
+    let mut request = client
+        .put(url)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(dump_metrics());
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CommonError::CommonError(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(CommonError::CommonError(format!(
+            "push gateway responded with status {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+pub fn parse_key_values(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+// One `Name: value` header per line, so header values may contain commas
+pub fn parse_headers(raw: &str) -> Vec<(String, String)> {
+    raw.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some((name.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+// Nodes of the same job are kept apart by the `instance` label, which
+// defaults to the local ip when not configured.
+pub fn build_push_url(server: &str, job: &str, labels: &[(String, String)]) -> String {
+    let server = server.trim_end_matches('/');
+    let mut url = if server.starts_with("http://") || server.starts_with("https://") {
+        server.to_string()
+    } else {
+        format!("http://{}", server)
+    };
+    url.push_str(&format!("/metrics/job/{}", job));
+    for (key, value) in labels {
+        url.push_str(&label_path(key, value));
+    }
+    if !labels.iter().any(|(key, _)| key == "instance") {
+        url.push_str(&format!("/instance/{}", get_local_ip()));
+    }
+    url
+}
+
+// The push gateway can not take a `/` inside a path segment, not even percent
+// encoded, so values outside the URL safe characters are sent in its
+// `@base64` form instead.
+fn label_path(key: &str, value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'));
+    if plain {
+        format!("/{}/{}", key, value)
+    } else {
+        format!("/{}@base64/{}", key, base64_url_encode(value.as_bytes()))
+    }
+}
+
+fn base64_url_encode(data: &[u8]) -> String {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    if data.is_empty() {
+        return "=".to_string();
+    }
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | ((*b as u32) << (16 - i * 8)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(CHARS[((n >> (18 - i * 6)) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_key_values_test() {
+        assert!(parse_key_values("").is_empty());
+        assert_eq!(
+            parse_key_values("Authorization=Basic dXNlcg==, =x,region = eu"),
+            vec![
+                ("Authorization".to_string(), "Basic dXNlcg==".to_string()),
+                ("region".to_string(), "eu".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_headers_test() {
+        assert!(parse_headers("").is_empty());
+        assert_eq!(
+            parse_headers("Authorization: Basic dXNlcg==\nAccept: text/plain, */*\n: x\nbroken"),
+            vec![
+                ("Authorization".to_string(), "Basic dXNlcg==".to_string()),
+                ("Accept".to_string(), "text/plain, */*".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_push_url_test() {
+        let labels = vec![("instance".to_string(), "node-1".to_string())];
+        assert_eq!(
+            build_push_url("127.0.0.1:9091/", "mqtt-broker", &labels),
+            "http://127.0.0.1:9091/metrics/job/mqtt-broker/instance/node-1"
+        );
+        assert!(build_push_url("https://gw", "journal-server", &[])
+            .starts_with("https://gw/metrics/job/journal-server/instance/"));
+
+        let labels = vec![
+            ("instance".to_string(), "node-1".to_string()),
+            ("path".to_string(), "/var/tmp".to_string()),
+            ("zone".to_string(), "".to_string()),
+            ("name".to_string(), "a b?".to_string()),
+        ];
+        assert_eq!(
+            build_push_url("127.0.0.1:9091", "mqtt-broker", &labels),
+            "http://127.0.0.1:9091/metrics/job/mqtt-broker/instance/node-1\
+             /path@base64/L3Zhci90bXA=/zone@base64/=/name@base64/YSBiPw=="
+        );
+    }
+}
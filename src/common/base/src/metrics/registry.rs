@@ -16,6 +16,7 @@
 use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::Histogram;
 use prometheus_client::registry::Registry;
 use std::fmt::Debug;
 use std::sync::{Arc, RwLock};
@@ -27,6 +28,8 @@
 
 pub type FamilyCounter<L> = Arc<RwLock<Family<L, Counter>>>;
 
+pub type FamilyHistogram<L> = Arc<RwLock<Family<L, Histogram, fn() -> Histogram>>>;
+
 static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::default()));
 
 pub fn default() -> MutexGuard<'static, Registry> {
@@ -53,6 +56,21 @@ macro_rules! register_counter_metric {
     };
 }
 
+// `$buckets` must not capture anything, the constructor is a plain fn pointer.
+#[macro_export]
+macro_rules! register_histogram_metric {
+    ($name:ident, $metric_name:expr, $help:expr,$label:ty,$buckets:expr) => {
+        static $name: std::sync::LazyLock<common_base::metrics::registry::FamilyHistogram<$label>> =
+            std::sync::LazyLock::new(|| {
+                common_base::metrics::registry::register_histogram_family(
+                    $metric_name,
+                    $help,
+                    || prometheus_client::metrics::histogram::Histogram::new($buckets),
+                )
+            });
+    };
+}
+
 #[macro_export]
 macro_rules! gauge_metric_inc {
     ($family:ident,$label:ident) => {{
@@ -91,6 +109,25 @@ macro_rules! counter_metric_inc {
     }};
 }
 
+#[macro_export]
+macro_rules! counter_metric_inc_by {
+    ($family:ident,$label:ident,$v:expr) => {{
+        let family = $family.clone();
+        let mut found = false;
+        {
+            let family_r = family.read().unwrap();
+            if let Some(counter) = family_r.get(&$label) {
+                counter.inc_by($v);
+                found = true;
+            };
+        }
+        if !found {
+            let family_w = family.write().unwrap();
+            family_w.get_or_create(&$label).inc_by($v);
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! gauge_metric_inc_by {
     ($family:ident,$label:ident,$v:expr) => {{
@@ -110,6 +147,25 @@ macro_rules! gauge_metric_inc_by {
     }};
 }
 
+#[macro_export]
+macro_rules! gauge_metric_set {
+    ($family:ident,$label:ident,$v:expr) => {{
+        let family = $family.clone();
+        let mut found = false;
+        {
+            let family_r = family.read().unwrap();
+            if let Some(gauge) = family_r.get(&$label) {
+                gauge.set($v);
+                found = true;
+            };
+        }
+        if !found {
+            let family_w = family.write().unwrap();
+            family_w.get_or_create(&$label).set($v);
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! gauge_metric_get {
     ($family:ident,$label:ident, $res:ident) => {{
@@ -148,6 +204,25 @@ macro_rules! counter_metric_get {
     }};
 }
 
+#[macro_export]
+macro_rules! histogram_metric_observe {
+    ($family:ident,$label:ident,$v:expr) => {{
+        let family = $family.clone();
+        let mut found = false;
+        {
+            let family_r = family.read().unwrap();
+            if let Some(histogram) = family_r.get(&$label) {
+                histogram.observe($v);
+                found = true;
+            };
+        }
+        if !found {
+            let family_w = family.write().unwrap();
+            family_w.get_or_create(&$label).observe($v);
+        }
+    }};
+}
+
 /// Register a `Family<Gauge>` and wrap it in `Arc<RwLock<...>>`
 pub fn register_int_gauge_family<L>(name: &str, help: &str) -> Arc<RwLock<Family<L, Gauge>>>
 where
@@ -168,6 +243,25 @@ pub fn register_int_counter_family<L>(name: &str, help: &str) -> Arc<RwLock<Fami
     Arc::new(RwLock::new(family))
 }
 
+/// Register a `Family<Histogram>` and wrap it in `Arc<RwLock<...>>`
+pub fn register_histogram_family<L>(
+    name: &str,
+    help: &str,
+    constructor: fn() -> Histogram,
+) -> FamilyHistogram<L>
+where
+    L: EncodeLabelSet + Eq + Clone + Hash + Debug + Sync + Send + 'static,
+{
+    let family = Family::<L, Histogram, fn() -> Histogram>::new_with_constructor(constructor);
+    default().register(name, help, family.clone());
+    Arc::new(RwLock::new(family))
+}
+
+/// Millisecond buckets from 1ms to ~16s.
+pub fn latency_ms_buckets() -> impl Iterator<Item = f64> {
+    prometheus_client::metrics::histogram::exponential_buckets(1.0, 2.0, 15)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -249,4 +343,23 @@ async fn test_counter() {
 
         assert!(!buffer.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_histogram() {
+        let family = register_histogram_family::<ClientConnectionLabels>(
+            "client_connect_latency",
+            "client connect latency",
+            || Histogram::new(latency_ms_buckets()),
+        );
+        let label = ClientConnectionLabels {
+            client_id: "client-0".to_string(),
+        };
+        family.read().unwrap().get_or_create(&label).observe(3.0);
+        family.read().unwrap().get_or_create(&label).observe(300.0);
+
+        let mut buffer = String::new();
+        let re = default();
+        encode(&mut buffer, &re).unwrap();
+        assert!(buffer.contains("client_connect_latency_count{client_id=\"client-0\"} 2"));
+    }
 }
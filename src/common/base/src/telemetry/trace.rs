@@ -12,11 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::config::broker_mqtt::BrokerMqttConfig;
-use opentelemetry::{global, propagation::Extractor, trace::noop::NoopTracerProvider};
+use crate::config::common::Telemetry;
+use log::warn;
+use opentelemetry::{
+    global,
+    propagation::{Extractor, Injector},
+    trace::{noop::NoopTracerProvider, SpanKind, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
 use opentelemetry_otlp::{SpanExporter, WithExportConfig};
 use opentelemetry_sdk::{propagation::TraceContextPropagator, trace as sdktrace, Resource};
-use std::{collections::HashMap, sync::OnceLock};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{LazyLock, RwLock},
+};
 
 // Prepare to support three types
 // 1. For performance, turn it off
@@ -25,56 +34,168 @@
 #[derive(Debug)]
 pub enum TraceExporterProvider {
     Noop(NoopTracerProvider),
-    Stdout(NoopTracerProvider),
+    Stdout(sdktrace::SdkTracerProvider),
     Otlp(sdktrace::SdkTracerProvider),
 }
 
-static GLOBAL_PROVIDER: OnceLock<TraceExporterProvider> = OnceLock::new();
+static GLOBAL_PROVIDER: RwLock<Option<TraceExporterProvider>> = RwLock::new(None);
 
-pub async fn init_tracer_provider(broker_config: &'static BrokerMqttConfig) {
-    if !broker_config.telemetry.enable {
+// Clients whose publishes start a new trace even without an incoming traceparent
+static TRACED_CLIENTS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+pub async fn init_tracer_provider(telemetry: &Telemetry, service_name: &str) {
+    set_tracer_provider(telemetry, service_name);
+}
+
+// Replaces the tracer provider, spans of the old provider are flushed on shutdown.
+pub fn reload_tracer_provider(telemetry: &Telemetry, service_name: &str) {
+    if let Some(provider) = set_tracer_provider(telemetry, service_name) {
+        shutdown_provider(provider);
+    }
+}
+
+fn set_tracer_provider(telemetry: &Telemetry, service_name: &str) -> Option<TraceExporterProvider> {
+    if !telemetry.enable {
         global::set_tracer_provider(NoopTracerProvider::new());
-        return;
+        return GLOBAL_PROVIDER.write().unwrap().take();
     }
-    match broker_config.telemetry.exporter_type.as_str() {
+    let resource = Resource::builder()
+        .with_service_name(service_name.to_string())
+        .build();
+    let provider = match telemetry.exporter_type.as_str() {
         "otlp" => {
             let exporter = SpanExporter::builder()
                 .with_tonic()
-                .with_endpoint(broker_config.telemetry.exporter_endpoint.as_str())
+                .with_endpoint(telemetry.exporter_endpoint.as_str())
                 .build()
                 .unwrap();
-            global::set_text_map_propagator(TraceContextPropagator::new());
             let provider = sdktrace::SdkTracerProvider::builder()
                 .with_batch_exporter(exporter)
-                .with_resource(Resource::builder().with_service_name("robustmq").build())
+                .with_resource(resource)
                 .build();
-            GLOBAL_PROVIDER
-                .set(TraceExporterProvider::Otlp(provider.clone()))
-                .unwrap();
-            global::set_tracer_provider(provider.clone());
+            TraceExporterProvider::Otlp(provider)
+        }
+        "stdout" => {
+            let provider = sdktrace::SdkTracerProvider::builder()
+                .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
+                .with_resource(resource)
+                .build();
+            TraceExporterProvider::Stdout(provider)
         }
         _ => {
             global::set_tracer_provider(NoopTracerProvider::new());
+            return GLOBAL_PROVIDER.write().unwrap().take();
+        }
+    };
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    match &provider {
+        TraceExporterProvider::Otlp(p) | TraceExporterProvider::Stdout(p) => {
+            global::set_tracer_provider(p.clone());
+        }
+        TraceExporterProvider::Noop(p) => {
+            global::set_tracer_provider(p.clone());
+        }
+    }
+    GLOBAL_PROVIDER.write().unwrap().replace(provider)
+}
+
+fn shutdown_provider(provider: TraceExporterProvider) {
+    match provider {
+        TraceExporterProvider::Otlp(provider) | TraceExporterProvider::Stdout(provider) => {
+            if let Err(e) = provider.shutdown() {
+                warn!("Failed to shut down tracer provider, error message: {}", e);
+            }
+        }
+        TraceExporterProvider::Noop(_provider) => {
+            // Ignore
         }
     }
 }
 
 pub async fn stop_tracer_provider() {
-    if let Some(provider) = GLOBAL_PROVIDER.get() {
+    if let Some(provider) = GLOBAL_PROVIDER.write().unwrap().take() {
         match provider {
-            TraceExporterProvider::Otlp(provider) => {
+            TraceExporterProvider::Otlp(provider) | TraceExporterProvider::Stdout(provider) => {
                 provider.shutdown().unwrap();
             }
             TraceExporterProvider::Noop(_provider) => {
                 // Ignore
             }
-            TraceExporterProvider::Stdout(_provider) => {
-                // Ignore
-            }
         }
     }
 }
 
+pub fn enable_client_trace(client_id: &str) {
+    TRACED_CLIENTS
+        .write()
+        .unwrap()
+        .insert(client_id.to_string());
+}
+
+pub fn disable_client_trace(client_id: &str) {
+    TRACED_CLIENTS.write().unwrap().remove(client_id);
+}
+
+pub fn is_client_traced(client_id: &str) -> bool {
+    TRACED_CLIENTS.read().unwrap().contains(client_id)
+}
+
+pub fn list_traced_clients() -> Vec<String> {
+    TRACED_CLIENTS.read().unwrap().iter().cloned().collect()
+}
+
+/// Rebuild the remote context carried in W3C trace context headers.
+pub fn extract_context(carrier: &HashMap<String, String>) -> Context {
+    let context = CustomContext {
+        inner: carrier.clone(),
+    };
+    global::get_text_map_propagator(|prop| prop.extract(&context))
+}
+
+/// Serialize the span of `cx` as W3C trace context headers, empty when `cx` carries no span.
+pub fn inject_context(cx: &Context) -> HashMap<String, String> {
+    let mut context = CustomContext::new();
+    if cx.span().span_context().is_valid() {
+        global::get_text_map_propagator(|prop| prop.inject_context(cx, &mut context));
+    }
+    context.inner
+}
+
+/// Replace the trace context entries of MQTT user properties with `headers`.
+pub fn merge_trace_context(
+    user_properties: &mut Vec<(String, String)>,
+    headers: HashMap<String, String>,
+) {
+    if headers.is_empty() {
+        return;
+    }
+    user_properties.retain(|(k, _)| !headers.contains_key(k));
+    user_properties.extend(headers);
+}
+
+/// Start a span as a child of `parent`. Without a remote parent a new trace is only
+/// started when tracing has been enabled for `client_id`, otherwise `None` is returned.
+pub fn start_span(
+    tracer_name: &'static str,
+    span_name: &'static str,
+    kind: SpanKind,
+    parent: &Context,
+    client_id: &str,
+) -> Option<Context> {
+    if !parent.span().span_context().is_valid() && !is_client_traced(client_id) {
+        return None;
+    }
+    let tracer = global::tracer(tracer_name);
+    let mut builder = tracer.span_builder(span_name).with_kind(kind);
+    if !client_id.is_empty() {
+        builder = builder.with_attributes(vec![KeyValue::new("client_id", client_id.to_string())]);
+    }
+    let span = builder.start_with_context(&tracer, parent);
+    Some(parent.with_span(span))
+}
+
 pub struct CustomContext {
     pub inner: HashMap<String, String>,
 }
@@ -94,6 +215,12 @@ fn keys(&self) -> Vec<&str> {
     }
 }
 
+impl Injector for CustomContext {
+    fn set(&mut self, key: &str, value: String) {
+        self.inner.insert(key.to_string(), value);
+    }
+}
+
 impl CustomContext {
     pub fn new() -> Self {
         CustomContext {
@@ -112,16 +239,25 @@ fn default() -> Self {
 mod tests {
     use crate::config::broker_mqtt::broker_mqtt_conf;
     use crate::config::broker_mqtt::init_broker_mqtt_conf_by_path;
+    use opentelemetry::global;
+    use opentelemetry::trace::{SpanKind, TraceContextExt};
+    use opentelemetry::Context;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
     use std::path::PathBuf;
 
-    use super::init_tracer_provider;
+    use super::{
+        disable_client_trace, enable_client_trace, extract_context, init_tracer_provider,
+        inject_context, is_client_traced, start_span,
+    };
 
     #[test]
     fn telemetry_config_test() {
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         path.push("../../../example/mqtt-cluster/mqtt-server/node-1.toml");
         init_broker_mqtt_conf_by_path(path.to_str().unwrap());
-        let telemetry_config = &broker_mqtt_conf().telemetry;
+        let config = broker_mqtt_conf();
+        let telemetry_config = &config.telemetry;
         assert!(!telemetry_config.enable);
         assert_eq!(
             telemetry_config.exporter_endpoint,
@@ -135,6 +271,48 @@ async fn telemetry_test_init() {
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         path.push("../../../example/mqtt-cluster/mqtt-server/node-1.toml");
         init_broker_mqtt_conf_by_path(path.to_str().unwrap());
-        init_tracer_provider(broker_mqtt_conf()).await
+        init_tracer_provider(&broker_mqtt_conf().telemetry, "robustmq").await
+    }
+
+    #[test]
+    fn client_trace_toggle_test() {
+        let client_id = "trace-toggle-client";
+        assert!(!is_client_traced(client_id));
+        assert!(start_span("test", "test", SpanKind::Server, &Context::new(), client_id).is_none());
+
+        enable_client_trace(client_id);
+        assert!(is_client_traced(client_id));
+
+        disable_client_trace(client_id);
+        assert!(!is_client_traced(client_id));
+    }
+
+    #[test]
+    fn context_propagation_test() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        global::set_tracer_provider(SdkTracerProvider::builder().build());
+
+        assert!(inject_context(&Context::new()).is_empty());
+
+        let client_id = "trace-propagation-client";
+        enable_client_trace(client_id);
+        let cx = start_span("test", "root", SpanKind::Server, &Context::new(), client_id).unwrap();
+        disable_client_trace(client_id);
+
+        let headers = inject_context(&cx);
+        assert!(headers.contains_key("traceparent"));
+
+        let remote = extract_context(&headers);
+        assert_eq!(
+            remote.span().span_context().trace_id(),
+            cx.span().span_context().trace_id()
+        );
+
+        // a remote parent is always continued, regardless of client sampling
+        let child = start_span("test", "child", SpanKind::Consumer, &remote, "").unwrap();
+        assert_eq!(
+            child.span().span_context().trace_id(),
+            cx.span().span_context().trace_id()
+        );
     }
 }
@@ -14,6 +14,7 @@
 
 use common_base::{tools::now_second, utils::crc::calc_crc32};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Header {
@@ -21,6 +22,8 @@ pub struct Header {
     pub value: String,
 }
 
+const TIMESTAMP_MS_HEADER: &str = "timestamp_ms";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Record {
     pub offset: Option<u64>,
@@ -70,6 +73,40 @@ pub fn set_header(&mut self, headers: Vec<Header>) {
         self.header = headers;
     }
 
+    pub fn header_map(&self) -> HashMap<String, String> {
+        self.header
+            .iter()
+            .map(|h| (h.name.clone(), h.value.clone()))
+            .collect()
+    }
+
+    /// Add or overwrite headers by name, keeping the others untouched.
+    pub fn merge_header(&mut self, headers: HashMap<String, String>) {
+        for (name, value) in headers {
+            if let Some(h) = self.header.iter_mut().find(|h| h.name == name) {
+                h.value = value;
+            } else {
+                self.header.push(Header { name, value });
+            }
+        }
+    }
+
+    /// `timestamp` only has second precision, the millisecond build time is kept in a header.
+    pub fn set_timestamp_ms(&mut self, timestamp_ms: u128) {
+        self.merge_header(HashMap::from([(
+            TIMESTAMP_MS_HEADER.to_string(),
+            timestamp_ms.to_string(),
+        )]));
+    }
+
+    pub fn timestamp_ms(&self) -> u128 {
+        self.header
+            .iter()
+            .find(|h| h.name == TIMESTAMP_MS_HEADER)
+            .and_then(|h| h.value.parse().ok())
+            .unwrap_or(self.timestamp as u128 * 1000)
+    }
+
     pub fn set_key(&mut self, key: String) {
         self.key = key;
     }
@@ -79,3 +116,17 @@ pub fn crc32_check(&self) -> bool {
         crc_num == self.crc_num
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Record;
+
+    #[test]
+    fn timestamp_ms_test() {
+        let mut record = Record::build_str("data".to_string());
+        assert_eq!(record.timestamp_ms(), record.timestamp as u128 * 1000);
+
+        record.set_timestamp_ms(1_700_000_000_123);
+        assert_eq!(record.timestamp_ms(), 1_700_000_000_123);
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct MqttAlarm {
+    pub cluster_name: String,
+    pub broker_id: u64,
+    pub alarm_id: String,
+    pub name: String,
+    // The connector, port or client the alarm is about, empty for broker wide alarms
+    pub resource: String,
+    pub message: String,
+    pub activate_at: u64,
+    // 0 while the alarm is still active
+    pub deactivate_at: u64,
+}
+
+impl MqttAlarm {
+    pub fn is_active(&self) -> bool {
+        self.deactivate_at == 0
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(data)
+    }
+}
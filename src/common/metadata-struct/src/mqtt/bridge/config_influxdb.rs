@@ -0,0 +1,79 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum InfluxDbVersion {
+    // /write with database, username and password
+    V1,
+    // /api/v2/write with org, bucket and token
+    #[default]
+    V2,
+}
+
+// measurement, tag values and field values are templates, see bridge/placeholder.rs
+// in the broker for the supported placeholders. Field values that render as true/false
+// are booleans and numbers are floats, unless the template ends with an `i` suffix
+// (e.g. "${payload.count}i") to write an integer. Anything else is a string.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InfluxDbConnectorConfig {
+    // e.g. http://127.0.0.1:8086
+    pub url: String,
+    #[serde(default)]
+    pub version: InfluxDbVersion,
+    #[serde(default)]
+    pub database: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub org: String,
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default)]
+    pub token: String,
+    pub measurement: String,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    pub fields: BTreeMap<String, String>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for InfluxDbConnectorConfig {
+    fn default() -> Self {
+        InfluxDbConnectorConfig {
+            url: String::new(),
+            version: InfluxDbVersion::default(),
+            database: String::new(),
+            username: String::new(),
+            password: String::new(),
+            org: String::new(),
+            bucket: String::new(),
+            token: String::new(),
+            measurement: String::new(),
+            tags: BTreeMap::new(),
+            fields: BTreeMap::new(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
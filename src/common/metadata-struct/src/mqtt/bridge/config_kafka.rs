@@ -14,9 +14,94 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum KafkaDeliveryErrorPolicy {
+    // Keep retrying the batch; nothing is committed until Kafka accepts it.
+    #[default]
+    Retry,
+    // Log the failed record and move on.
+    Skip,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct KafkaSaslConfig {
+    // PLAIN, SCRAM-SHA-256 or SCRAM-SHA-512
+    pub mechanism: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct KafkaTlsConfig {
+    pub ca_path: String,
+    #[serde(default)]
+    pub cert_path: Option<String>,
+    #[serde(default)]
+    pub key_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct KafkaConnectorConfig {
     pub bootstrap_servers: String,
     pub topic: String,
+    // Message key template, ${clientid} and ${topic} are replaced with the MQTT
+    // client id and topic name. An empty key sends records without a key.
+    #[serde(default)]
     pub key: String,
+    #[serde(default)]
+    pub sasl: Option<KafkaSaslConfig>,
+    #[serde(default)]
+    pub tls: Option<KafkaTlsConfig>,
+    // 0, 1 or all
+    #[serde(default = "default_acks")]
+    pub acks: String,
+    // none, gzip, snappy, lz4 or zstd
+    #[serde(default = "default_compression")]
+    pub compression: String,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u64,
+    #[serde(default = "default_linger_ms")]
+    pub linger_ms: u64,
+    #[serde(default = "default_message_timeout_ms")]
+    pub message_timeout_ms: u64,
+    #[serde(default)]
+    pub delivery_error_policy: KafkaDeliveryErrorPolicy,
+}
+
+impl Default for KafkaConnectorConfig {
+    fn default() -> Self {
+        KafkaConnectorConfig {
+            bootstrap_servers: String::new(),
+            topic: String::new(),
+            key: String::new(),
+            sasl: None,
+            tls: None,
+            acks: default_acks(),
+            compression: default_compression(),
+            batch_size: default_batch_size(),
+            linger_ms: default_linger_ms(),
+            message_timeout_ms: default_message_timeout_ms(),
+            delivery_error_policy: KafkaDeliveryErrorPolicy::default(),
+        }
+    }
+}
+
+fn default_acks() -> String {
+    "all".to_string()
+}
+
+fn default_compression() -> String {
+    "none".to_string()
+}
+
+fn default_batch_size() -> u64 {
+    16384
+}
+
+fn default_linger_ms() -> u64 {
+    5
+}
+
+fn default_message_timeout_ms() -> u64 {
+    30000
 }
@@ -14,7 +14,54 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum LocalFileFormat {
+    // One JSON object per line with the message metadata and payload.
+    #[default]
+    JsonLines,
+    // timestamp, clientid, topic, qos, retain, payload with a header row.
+    Csv,
+    // The payload followed by a newline.
+    Raw,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct LocalFileConnectorConfig {
+    // The file currently being written, rotated files are kept next to it.
     pub local_file_path: String,
+    #[serde(default)]
+    pub format: LocalFileFormat,
+    // Rotate once the active file reaches this size, 0 disables it.
+    #[serde(default)]
+    pub rotate_size_bytes: u64,
+    // Rotate once the active file is this old, 0 disables it.
+    #[serde(default)]
+    pub rotate_interval_secs: u64,
+    // Name of a rotated file, supports ${name}, ${datetime} and ${timestamp}.
+    #[serde(default = "default_rotated_file_template")]
+    pub rotated_file_template: String,
+    // gzip rotated files.
+    #[serde(default)]
+    pub gzip: bool,
+    // Number of rotated files to keep, 0 keeps all of them.
+    #[serde(default)]
+    pub retention_count: usize,
+}
+
+impl Default for LocalFileConnectorConfig {
+    fn default() -> Self {
+        LocalFileConnectorConfig {
+            local_file_path: String::new(),
+            format: LocalFileFormat::default(),
+            rotate_size_bytes: 0,
+            rotate_interval_secs: 0,
+            rotated_file_template: default_rotated_file_template(),
+            gzip: false,
+            retention_count: 0,
+        }
+    }
+}
+
+fn default_rotated_file_template() -> String {
+    "${name}.${datetime}".to_string()
 }
@@ -0,0 +1,95 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum MqttBridgeDirection {
+    // Forward the connector topic to the remote broker.
+    #[default]
+    Egress,
+    // Subscribe on the remote broker and publish locally.
+    Ingress,
+    Both,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct MqttBridgeTlsConfig {
+    pub ca_path: String,
+    #[serde(default)]
+    pub cert_path: Option<String>,
+    #[serde(default)]
+    pub key_path: Option<String>,
+    #[serde(default = "default_true")]
+    pub verify: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MqttBridgeConnectorConfig {
+    // e.g. tcp://127.0.0.1:1883 or ssl://127.0.0.1:8883
+    pub server: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub direction: MqttBridgeDirection,
+    // Prepended to the local topic name when forwarding to the remote broker.
+    #[serde(default)]
+    pub remote_topic_prefix: String,
+    // Remote topic filters subscribed for ingress.
+    #[serde(default)]
+    pub subscribe_topics: Vec<String>,
+    // Prepended to the remote topic name when publishing locally.
+    #[serde(default)]
+    pub local_topic_prefix: String,
+    // Messages above this QoS are downgraded in both directions.
+    #[serde(default = "default_max_qos")]
+    pub max_qos: u8,
+    #[serde(default = "default_keep_alive")]
+    pub keep_alive_secs: u64,
+    #[serde(default)]
+    pub tls: Option<MqttBridgeTlsConfig>,
+}
+
+impl Default for MqttBridgeConnectorConfig {
+    fn default() -> Self {
+        MqttBridgeConnectorConfig {
+            server: String::new(),
+            client_id: String::new(),
+            username: String::new(),
+            password: String::new(),
+            direction: MqttBridgeDirection::default(),
+            remote_topic_prefix: String::new(),
+            subscribe_topics: Vec::new(),
+            local_topic_prefix: String::new(),
+            max_qos: default_max_qos(),
+            keep_alive_secs: default_keep_alive(),
+            tls: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_qos() -> u8 {
+    1
+}
+
+fn default_keep_alive() -> u64 {
+    60
+}
@@ -0,0 +1,68 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum RedisCommandType {
+    #[default]
+    Set,
+    HSet,
+    XAdd,
+    Publish,
+}
+
+// key, field and value are templates, see bridge/placeholder.rs in the broker
+// for the supported placeholders.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RedisConnectorConfig {
+    // e.g. redis://127.0.0.1:6379/0
+    pub url: String,
+    pub command: RedisCommandType,
+    // The key for SET/HSET, the stream for XADD and the channel for PUBLISH.
+    pub key: String,
+    // The hash field for HSET and the entry field for XADD.
+    #[serde(default = "default_field")]
+    pub field: String,
+    #[serde(default = "default_value")]
+    pub value: String,
+    // Expiry for SET.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    // Approximate MAXLEN for XADD.
+    #[serde(default)]
+    pub stream_max_len: Option<usize>,
+}
+
+impl Default for RedisConnectorConfig {
+    fn default() -> Self {
+        RedisConnectorConfig {
+            url: String::new(),
+            command: RedisCommandType::default(),
+            key: String::new(),
+            field: default_field(),
+            value: default_value(),
+            ttl_secs: None,
+            stream_max_len: None,
+        }
+    }
+}
+
+fn default_field() -> String {
+    "payload".to_string()
+}
+
+fn default_value() -> String {
+    "${payload}".to_string()
+}
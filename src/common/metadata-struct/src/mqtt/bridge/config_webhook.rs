@@ -0,0 +1,98 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WebHookConnectorConfig {
+    pub url: String,
+    // POST, PUT or PATCH
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // ${payload}, ${topic} and ${clientid} are replaced per message. When empty the
+    // message is sent as a JSON object with those three fields.
+    #[serde(default)]
+    pub body_template: String,
+    // Messages per request. Batched requests carry a JSON array of the rendered
+    // bodies, so the template has to render JSON when this is above 1.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    // Maximum number of requests in flight.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    // Retries for 5xx responses and transport errors.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    // Doubled after every retry, up to max_backoff_ms.
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    // Messages that fail permanently are written here. Without it they are
+    // dropped and counted in connector_messages_dropped.
+    #[serde(default)]
+    pub dead_letter_topic: Option<String>,
+}
+
+impl Default for WebHookConnectorConfig {
+    fn default() -> Self {
+        WebHookConnectorConfig {
+            url: String::new(),
+            method: default_method(),
+            headers: HashMap::new(),
+            body_template: String::new(),
+            batch_size: default_batch_size(),
+            concurrency: default_concurrency(),
+            max_retries: default_max_retries(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            timeout_ms: default_timeout_ms(),
+            dead_letter_topic: None,
+        }
+    }
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+fn default_batch_size() -> usize {
+    1
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    200
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30000
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
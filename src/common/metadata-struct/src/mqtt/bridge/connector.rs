@@ -14,7 +14,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::{connector_type::ConnectorType, status::MQTTStatus};
+use super::{
+    connector_type::ConnectorType,
+    status::{MQTTConnectorStats, MQTTStatus},
+};
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct MQTTConnector {
@@ -27,6 +30,8 @@ pub struct MQTTConnector {
     pub broker_id: Option<u64>,
     pub create_time: u64,
     pub update_time: u64,
+    #[serde(default)]
+    pub stats: MQTTConnectorStats,
 }
 
 impl MQTTConnector {
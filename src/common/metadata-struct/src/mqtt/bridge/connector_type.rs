@@ -21,6 +21,11 @@ pub enum ConnectorType {
     #[default]
     Kafka,
     LocalFile,
+    Mqtt,
+    WebHook,
+    Rdb,
+    Redis,
+    InfluxDb,
 }
 
 impl Display for ConnectorType {
@@ -12,8 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod config_influxdb;
 pub mod config_kafka;
 pub mod config_local_file;
+pub mod config_mqtt;
+pub mod config_rdb;
+pub mod config_redis;
+pub mod config_webhook;
 pub mod connector;
 pub mod connector_type;
 pub mod status;
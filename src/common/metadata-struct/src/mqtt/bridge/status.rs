@@ -28,3 +28,15 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct MQTTConnectorStats {
+    // records acknowledged by the sink
+    pub delivered_count: u64,
+    // failed sink writes, each of them is retried
+    pub failed_count: u64,
+    // seconds between now and the oldest record not yet delivered
+    pub lag: u64,
+    pub committed_offset: u64,
+    pub last_error: Option<String>,
+}
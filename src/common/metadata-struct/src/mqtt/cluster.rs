@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use protocol::mqtt::common::QoS;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +24,9 @@
 pub const DEFAULT_DYNAMIC_CONFIG_FEATURE: &str = "feature";
 pub const DEFAULT_DYNAMIC_CONFIG_SECURITY: &str = "security";
 pub const DEFAULT_DYNAMIC_CONFIG_NETWORK: &str = "network";
+pub const DEFAULT_DYNAMIC_CONFIG_ALARM: &str = "alarm";
+pub const DEFAULT_DYNAMIC_CONFIG_RETAIN_MESSAGE: &str = "retain_message";
+pub const DEFAULT_DYNAMIC_CONFIG_TENANT: &str = "tenant";
 
 // Dynamic configuration of MQTT cluster latitude
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -33,6 +38,10 @@ pub struct MqttClusterDynamicConfig {
     pub slow: MqttClusterDynamicSlowSub,
     pub flapping_detect: MqttClusterDynamicFlappingDetect,
     pub offline_message: MqttClusterDynamicOfflineMessage,
+    pub alarm: MqttClusterDynamicAlarm,
+    pub retain_message: MqttClusterDynamicRetainMessage,
+    #[serde(default)]
+    pub tenant: MqttClusterDynamicTenant,
 }
 
 // MQTT cluster protocol related dynamic configuration
@@ -79,6 +88,12 @@ pub struct MqttClusterDynamicConfigNetwork {
     pub response_try_mut_sleep_time_ms: u64,
 }
 
+impl MqttClusterDynamicConfigNetwork {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
 // MQTT cluster Feature related dynamic configuration
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct MqttClusterDynamicConfigFeature {
@@ -116,6 +131,61 @@ pub fn encode(&self) -> Vec<u8> {
     }
 }
 
+// Thresholds are counted per alarm check interval, 0 disables the alarm
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicAlarm {
+    pub enable: bool,
+    pub check_interval_secs: u64,
+    pub connection_high_watermark: u64,
+    pub storage_write_failures: u64,
+    pub flapping_detect_bans: u64,
+    pub schema_validation_failures: u64,
+    pub slow_subscriptions: u64,
+}
+
+impl MqttClusterDynamicAlarm {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
+// 0 means there is no limit on the number of retained messages in the cluster
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicRetainMessage {
+    pub max_retain_message_num: u64,
+}
+
+impl MqttClusterDynamicRetainMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
+// Where the tenant of a connection is taken from
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicTenant {
+    pub enable: bool,
+    pub source: TenantSource,
+    pub username_separator: String,
+    pub cert_field: String,
+    // Listener name(tcp, tcps, websocket, websockets, quic) to tenant name
+    pub listener_tenants: HashMap<String, String>,
+}
+
+impl MqttClusterDynamicTenant {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default, Clone, Debug)]
+pub enum TenantSource {
+    #[default]
+    Username,
+    CertField,
+    Listener,
+}
+
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct MqttClusterDynamicOfflineMessage {
     pub enable: bool,
@@ -31,6 +31,9 @@ pub struct MQTTConnection {
     pub source_ip_addr: String,
     //
     pub login_user: String,
+    // Tenant the connection belongs to, empty when multi-tenancy is disabled
+    #[serde(default)]
+    pub tenant: String,
     // When the client does not report a heartbeat, the maximum survival time of the connection,
     pub keep_alive: u16,
     // Records the Topic alias information for the connection dimension
@@ -14,7 +14,7 @@
 
 use bytes::Bytes;
 use common_base::error::common::CommonError;
-use common_base::tools::now_second;
+use common_base::tools::{now_mills, now_second};
 use log::error;
 use protocol::mqtt::common::{Publish, PublishProperties, QoS};
 use serde::{Deserialize, Serialize};
@@ -110,7 +110,11 @@ pub fn build_record(
         let msg =
             MqttMessage::build_message(client_id, publish, publish_properties, expiry_interval);
         match serde_json::to_vec(&msg) {
-            Ok(data) => Some(Record::build_byte(data)),
+            Ok(data) => {
+                let mut record = Record::build_byte(data);
+                record.set_timestamp_ms(now_mills());
+                Some(record)
+            }
 
             Err(e) => {
                 error!("Message encoding failed, error message :{}", e.to_string());
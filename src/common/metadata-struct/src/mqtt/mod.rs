@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod alarm;
 pub mod auto_subscribe_rule;
 pub mod bridge;
 pub mod cluster;
@@ -19,8 +20,10 @@
 pub mod lastwill;
 pub mod message;
 pub mod node_extend;
+pub mod rule;
 pub mod session;
 pub mod subscribe_data;
+pub mod tenant;
 pub mod topic;
 pub mod topic_rewrite_rule;
 pub mod user;
@@ -0,0 +1,45 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct MqttRule {
+    pub cluster_name: String,
+    pub rule_name: String,
+    pub sql: String,
+    pub actions: Vec<MqttRuleAction>,
+    pub enable: bool,
+    pub desc: String,
+    pub create_time: u64,
+    pub update_time: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum MqttRuleAction {
+    // Publish the rule output to a topic
+    Republish { topic: String, qos: u8 },
+    // Deliver the rule output to a connector
+    Connector { connector_name: String },
+}
+
+impl MqttRule {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(data)
+    }
+}
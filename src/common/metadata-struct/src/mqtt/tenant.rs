@@ -0,0 +1,46 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct MqttTenant {
+    pub cluster_name: String,
+    pub tenant_name: String,
+    pub desc: String,
+    pub quota: MqttTenantQuota,
+    pub create_time: u64,
+    pub update_time: u64,
+}
+
+// A value of 0 means the dimension is not limited
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct MqttTenantQuota {
+    pub max_connections: u64,
+    pub max_sessions: u64,
+    pub max_topics: u64,
+    // Messages per second published by all clients of the tenant
+    pub max_message_rate: u64,
+    pub max_storage_bytes: u64,
+}
+
+impl MqttTenant {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(data)
+    }
+}
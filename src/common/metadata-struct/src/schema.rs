@@ -23,6 +23,10 @@ pub struct SchemaData {
     pub schema_type: SchemaType,
     pub desc: String,
     pub schema: String,
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub compatibility: SchemaCompatibility,
 }
 
 impl SchemaData {
@@ -36,6 +40,9 @@ pub struct SchemaResourceBind {
     pub cluster_name: String,
     pub schema_name: String,
     pub resource_name: String,
+    // None means the binding follows the latest version of the schema
+    #[serde(default)]
+    pub schema_version: Option<u64>,
 }
 
 impl SchemaResourceBind {
@@ -60,3 +67,23 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         }
     }
 }
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum SchemaCompatibility {
+    #[default]
+    NONE,
+    BACKWARD,
+    FORWARD,
+    FULL,
+}
+
+impl Display for SchemaCompatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaCompatibility::NONE => write!(f, "none"),
+            SchemaCompatibility::BACKWARD => write!(f, "backward"),
+            SchemaCompatibility::FORWARD => write!(f, "forward"),
+            SchemaCompatibility::FULL => write!(f, "full"),
+        }
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use common_base::error::common::CommonError;
-use mysql::Pool;
+use mysql::{Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts};
 
 pub fn build_mysql_conn_pool(addr: &str) -> Result<Pool, CommonError> {
     match Pool::new(addr) {
@@ -21,3 +21,15 @@ pub fn build_mysql_conn_pool(addr: &str) -> Result<Pool, CommonError> {
         Err(e) => Err(CommonError::CommonError(e.to_string())),
     }
 }
+
+pub fn build_mysql_conn_pool_with_size(addr: &str, max_size: usize) -> Result<Pool, CommonError> {
+    let opts = Opts::from_url(addr).map_err(|e| CommonError::CommonError(e.to_string()))?;
+    let constraints = PoolConstraints::new(1, max_size.max(1))
+        .ok_or_else(|| CommonError::CommonError(format!("invalid mysql pool size {}", max_size)))?;
+    let opts =
+        OptsBuilder::from_opts(opts).pool_opts(PoolOpts::default().with_constraints(constraints));
+    match Pool::new(opts) {
+        Ok(pool) => Ok(pool),
+        Err(e) => Err(CommonError::CommonError(e.to_string())),
+    }
+}
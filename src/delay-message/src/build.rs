@@ -12,15 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common_base::tools::now_second;
 use log::error;
 use metadata_struct::adapter::read_config::ReadConfig;
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use storage_adapter::storage::StorageAdapter;
 use tokio::time::sleep;
 
-use crate::{DelayMessageManager, DelayMessageRecord};
+use crate::{completed_offset, DelayMessageManager, DelayMessageRecord};
 
+// Re-arms the messages of a shard that were neither delivered nor cancelled,
+// starting at the committed watermark. Messages that are already due are
+// delivered right away.
 pub async fn build_delay_queue<S>(
     message_storage_adapter: Arc<S>,
     delay_message_manager: Arc<DelayMessageManager<S>>,
@@ -31,9 +33,23 @@ pub async fn build_delay_queue<S>(
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
-    let mut offset = 0;
+    let mut offset = loop {
+        match delay_message_manager.get_committed_offset(shard_no).await {
+            Ok(offset) => break offset,
+            Err(e) => {
+                error!(
+                    "Reading the committed offset of shard {} failed with error {:?}",
+                    shard_name, e
+                );
+                sleep(Duration::from_secs(1)).await;
+            }
+        }
+    };
+    delay_message_manager.start_rebuild(shard_no, offset);
 
-    loop {
+    let mut delay_messages = Vec::new();
+    let mut completed = HashSet::new();
+    'read: loop {
         let data = match message_storage_adapter
             .read_by_offset(
                 namespace.to_owned(),
@@ -54,24 +70,37 @@ pub async fn build_delay_queue<S>(
             break;
         }
         for record in data {
-            offset = record.offset.unwrap();
+            let record_offset = record.offset.unwrap_or(offset);
+            offset = record_offset + 1;
 
-            if record.delay_timestamp < now_second() {
-                continue;
+            // Written after startup, these are already in the queue.
+            if delay_message_manager.rebuild_reached_live(shard_no, record_offset) {
+                break 'read;
             }
+            delay_message_manager.mark_seen(shard_no, record_offset);
 
-            let delay_message_record = DelayMessageRecord {
-                shard_name: shard_name.to_owned(),
-                offset,
-                delay_timestamp: record.delay_timestamp,
-            };
-
-            if let Err(e) = delay_message_manager
-                .send_to_delay_queue(shard_no, delay_message_record)
-                .await
+            if let Some(completed_offset) = completed_offset(&record) {
+                completed.insert(completed_offset);
+                continue;
+            }
+            if let Some(delay_message) =
+                DelayMessageRecord::from_record(shard_no, &shard_name, &record)
             {
-                error!("While building the deferred message index, sending a message to the DelayQueue failed with error message :{:?}", e);
+                delay_messages.push(delay_message);
             }
         }
     }
+
+    for delay_message in delay_messages {
+        if !completed.contains(&delay_message.offset) {
+            delay_message_manager.add_pending(delay_message);
+        }
+    }
+
+    if let Err(e) = delay_message_manager.finish_rebuild(shard_no).await {
+        error!(
+            "Committing the watermark of shard {} failed with error {:?}",
+            shard_name, e
+        );
+    }
 }
@@ -14,29 +14,94 @@
 
 use build::build_delay_queue;
 use common_base::error::common::CommonError;
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap};
 use log::{debug, info};
-use metadata_struct::adapter::{read_config::ReadConfig, record::Record};
+use metadata_struct::adapter::{
+    read_config::ReadConfig,
+    record::{Header, Record},
+};
 use std::{
+    collections::{BTreeSet, HashMap},
     sync::{atomic::AtomicU64, Arc},
-    time::Duration,
 };
 use storage_adapter::storage::{ShardInfo, StorageAdapter};
-use tokio::{
-    select,
-    sync::broadcast,
-    time::{sleep, Instant},
-};
-use tokio_util::time::DelayQueue;
+use tokio::sync::{broadcast, mpsc};
 
 pub mod build;
 pub mod pop;
 
-#[derive(Clone)]
+pub const DELAY_TARGET_TOPIC_HEADER: &str = "$delay-target-topic";
+pub const DELAY_TARGET_SHARD_HEADER: &str = "$delay-target-shard";
+// Only set when the target shard lives outside the namespace of the manager.
+pub const DELAY_TARGET_NAMESPACE_HEADER: &str = "$delay-target-namespace";
+// Written into the delay shard once a message is delivered or cancelled, the
+// value is the offset of that message.
+const DELAY_COMPLETED_HEADER: &str = "$delay-completed";
+
+const DELAY_MESSAGE_SHARD_NAME_PREFIX: &str = "$delay-message-shard-";
+const DELAY_MESSAGE_GROUP_PREFIX: &str = "$delay-message-group-";
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct DelayMessageRecord {
-    shard_name: String,
-    offset: u64,
-    delay_timestamp: u64,
+    pub delay_id: String,
+    pub shard_no: u64,
+    pub shard_name: String,
+    pub offset: u64,
+    pub target_topic_name: String,
+    pub target_shard_name: String,
+    pub target_namespace: Option<String>,
+    // Absolute due time in seconds.
+    pub due_timestamp: u64,
+    pub create_time: u64,
+}
+
+impl DelayMessageRecord {
+    // Returns None for records that are not delayed messages, e.g. completion markers.
+    pub fn from_record(shard_no: u64, shard_name: &str, record: &Record) -> Option<Self> {
+        let offset = record.offset?;
+        Some(DelayMessageRecord {
+            delay_id: build_delay_id(shard_no, offset),
+            shard_no,
+            shard_name: shard_name.to_owned(),
+            offset,
+            target_topic_name: header_value(record, DELAY_TARGET_TOPIC_HEADER)?,
+            target_shard_name: header_value(record, DELAY_TARGET_SHARD_HEADER)?,
+            target_namespace: header_value(record, DELAY_TARGET_NAMESPACE_HEADER),
+            due_timestamp: record.delay_timestamp,
+            create_time: record.timestamp,
+        })
+    }
+}
+
+pub(crate) enum DelayQueueCommand {
+    Insert(String, u64),
+    Cancel(String),
+}
+
+// Tracks which offsets of a delay shard are still pending. Everything below the
+// watermark has been delivered or cancelled, so the rebuild after a restart
+// starts reading there.
+#[derive(Default)]
+struct ShardWatermark {
+    pending: BTreeSet<u64>,
+    next_offset: u64,
+    committed_offset: u64,
+    // The first offset written by this process, the rebuild stops there.
+    live_start_offset: Option<u64>,
+    rebuilt: bool,
+}
+
+impl ShardWatermark {
+    fn seen(&mut self, offset: u64) {
+        self.next_offset = self.next_offset.max(offset + 1);
+    }
+
+    fn watermark(&self) -> Option<u64> {
+        if !self.rebuilt {
+            return None;
+        }
+        Some(self.pending.first().copied().unwrap_or(self.next_offset))
+    }
 }
 
 pub struct DelayMessageManager<S> {
@@ -44,11 +109,13 @@ pub struct DelayMessageManager<S> {
     shard_num: u64,
     message_storage_adapter: Arc<S>,
     incr_no: AtomicU64,
-    delay_queue_list: DashMap<u64, DelayQueue<DelayMessageRecord>>,
+    pending: DashMap<String, DelayMessageRecord>,
+    watermarks: DashMap<u64, ShardWatermark>,
+    delay_queue_sender: DashMap<u64, mpsc::UnboundedSender<DelayQueueCommand>>,
+    delay_queue_receiver: DashMap<u64, mpsc::UnboundedReceiver<DelayQueueCommand>>,
     delay_queue_pop_thread: DashMap<u64, broadcast::Sender<bool>>,
 }
 
-const DELAY_MESSAGE_SHARD_NAME_PREFIX: &str = "$delay-message-shard-";
 impl<S> DelayMessageManager<S>
 where
     S: StorageAdapter + Sync + Send + 'static + Clone,
@@ -59,7 +126,10 @@ pub fn new(namespace: String, shard_num: u64, message_storage_adapter: Arc<S>) -
             shard_num,
             message_storage_adapter,
             incr_no: AtomicU64::new(0),
-            delay_queue_list: DashMap::with_capacity(2),
+            pending: DashMap::with_capacity(256),
+            watermarks: DashMap::with_capacity(2),
+            delay_queue_sender: DashMap::with_capacity(2),
+            delay_queue_receiver: DashMap::with_capacity(2),
             delay_queue_pop_thread: DashMap::with_capacity(2),
         }
     }
@@ -71,6 +141,10 @@ pub async fn init(&self) -> Result<(), CommonError> {
         Ok(())
     }
 
+    pub fn get_shard_num(&self) -> u64 {
+        self.shard_num
+    }
+
     async fn try_init_shard(&self) -> Result<(), CommonError> {
         for i in 0..self.shard_num {
             let shard_name = self.get_delay_message_shard_name(i);
@@ -94,28 +168,125 @@ async fn try_init_shard(&self) -> Result<(), CommonError> {
 
     async fn init_delay_queue(&self) -> Result<(), CommonError> {
         for shard_no in 0..self.shard_num {
-            let delay_queue = DelayQueue::new();
-            self.delay_queue_list.insert(shard_no, delay_queue);
+            let (sender, receiver) = mpsc::unbounded_channel();
+            self.delay_queue_sender.insert(shard_no, sender);
+            self.delay_queue_receiver.insert(shard_no, receiver);
+            self.watermarks.insert(shard_no, ShardWatermark::default());
         }
         Ok(())
     }
 
-    pub async fn send_delay_message(&self, data: Record) -> Result<(), CommonError> {
+    // Stores the message in a delay shard and returns its delay id. The message
+    // is written to target_shard_name once due_timestamp (in seconds) is reached.
+    pub async fn send_delay_message(
+        &self,
+        target_topic_name: &str,
+        target_shard_name: &str,
+        due_timestamp: u64,
+        data: Record,
+    ) -> Result<String, CommonError> {
+        self.send_delay_message_to_namespace(
+            None,
+            target_topic_name,
+            target_shard_name,
+            due_timestamp,
+            data,
+        )
+        .await
+    }
+
+    pub async fn send_delay_message_to_namespace(
+        &self,
+        target_namespace: Option<&str>,
+        target_topic_name: &str,
+        target_shard_name: &str,
+        due_timestamp: u64,
+        mut data: Record,
+    ) -> Result<String, CommonError> {
+        data.delay_timestamp = due_timestamp;
+        if let Some(target_namespace) = target_namespace {
+            data.header.push(Header {
+                name: DELAY_TARGET_NAMESPACE_HEADER.to_string(),
+                value: target_namespace.to_owned(),
+            });
+        }
+        data.header.push(Header {
+            name: DELAY_TARGET_TOPIC_HEADER.to_string(),
+            value: target_topic_name.to_owned(),
+        });
+        data.header.push(Header {
+            name: DELAY_TARGET_SHARD_HEADER.to_string(),
+            value: target_shard_name.to_owned(),
+        });
+
         let shard_no = self.get_target_shard_no();
-        let namespace = self.namespace.clone();
         let shard_name = self.get_delay_message_shard_name(shard_no);
         let offset = self
             .message_storage_adapter
-            .write(namespace, shard_name.clone(), data.clone())
+            .write(self.namespace.clone(), shard_name.clone(), data.clone())
             .await?;
+        if let Some(mut watermark) = self.watermarks.get_mut(&shard_no) {
+            watermark.live_start_offset.get_or_insert(offset);
+        }
 
         let delay_message_record = DelayMessageRecord {
+            delay_id: build_delay_id(shard_no, offset),
+            shard_no,
             shard_name,
             offset,
-            delay_timestamp: data.delay_timestamp,
+            target_topic_name: target_topic_name.to_owned(),
+            target_shard_name: target_shard_name.to_owned(),
+            target_namespace: target_namespace.map(|namespace| namespace.to_owned()),
+            due_timestamp,
+            create_time: data.timestamp,
         };
-        self.send_to_delay_queue(shard_no, delay_message_record)
-            .await?;
+        let delay_id = delay_message_record.delay_id.clone();
+        self.add_pending(delay_message_record);
+        Ok(delay_id)
+    }
+
+    pub fn list_delay_messages(&self) -> Vec<DelayMessageRecord> {
+        let mut results: Vec<DelayMessageRecord> =
+            self.pending.iter().map(|raw| raw.value().clone()).collect();
+        results.sort_by(|a, b| {
+            (a.due_timestamp, a.shard_no, a.offset).cmp(&(b.due_timestamp, b.shard_no, b.offset))
+        });
+        results
+    }
+
+    pub fn get_delay_message(&self, delay_id: &str) -> Option<DelayMessageRecord> {
+        self.pending.get(delay_id).map(|raw| raw.value().clone())
+    }
+
+    // Reads the stored message of a pending delayed message.
+    pub async fn read_delay_message(
+        &self,
+        delay_message: &DelayMessageRecord,
+    ) -> Result<Option<Record>, CommonError> {
+        pop::read_offset_data(
+            &self.message_storage_adapter,
+            &self.namespace,
+            &delay_message.shard_name,
+            delay_message.offset,
+        )
+        .await
+    }
+
+    pub async fn cancel_delay_message(&self, delay_id: &str) -> Result<(), CommonError> {
+        let Some(delay_message) = self.take_pending(delay_id) else {
+            return Err(CommonError::CommonError(format!(
+                "Delay message {} does not exist or is being delivered",
+                delay_id
+            )));
+        };
+
+        if let Err(e) = self.complete_delay_message(&delay_message).await {
+            self.add_pending(delay_message);
+            return Err(e);
+        }
+        if let Some(sender) = self.delay_queue_sender.get(&delay_message.shard_no) {
+            let _ = sender.send(DelayQueueCommand::Cancel(delay_id.to_owned()));
+        }
         Ok(())
     }
 
@@ -132,20 +303,147 @@ pub async fn stop(&self) -> Result<(), CommonError> {
         Ok(())
     }
 
-    async fn send_to_delay_queue(
+    // Messages already known, e.g. written while the shard was being rebuilt,
+    // are ignored.
+    pub(crate) fn add_pending(&self, delay_message: DelayMessageRecord) {
+        match self.pending.entry(delay_message.delay_id.clone()) {
+            Entry::Occupied(_) => return,
+            Entry::Vacant(entry) => {
+                entry.insert(delay_message.clone());
+            }
+        }
+
+        if let Some(mut watermark) = self.watermarks.get_mut(&delay_message.shard_no) {
+            watermark.pending.insert(delay_message.offset);
+            watermark.seen(delay_message.offset);
+        }
+
+        if let Some(sender) = self.delay_queue_sender.get(&delay_message.shard_no) {
+            let _ = sender.send(DelayQueueCommand::Insert(
+                delay_message.delay_id,
+                delay_message.due_timestamp,
+            ));
+        }
+    }
+
+    // Claims a pending message for delivery or cancellation. Only one caller gets
+    // it, so a message cancelled just as it becomes due is not delivered as well.
+    pub(crate) fn take_pending(&self, delay_id: &str) -> Option<DelayMessageRecord> {
+        self.pending.remove(delay_id).map(|(_, record)| record)
+    }
+
+    // Puts a message whose delivery failed back into the queue, it stays listed
+    // and can still be cancelled until the next attempt at retry_timestamp.
+    pub(crate) fn retry_delay_message(
         &self,
-        shard_no: u64,
-        delay_message_record: DelayMessageRecord,
+        delay_message: DelayMessageRecord,
+        retry_timestamp: u64,
+    ) {
+        let delay_id = delay_message.delay_id.clone();
+        let shard_no = delay_message.shard_no;
+        self.pending.insert(delay_id.clone(), delay_message);
+        if let Some(sender) = self.delay_queue_sender.get(&shard_no) {
+            let _ = sender.send(DelayQueueCommand::Insert(delay_id, retry_timestamp));
+        }
+    }
+
+    // Called once a message was delivered or cancelled. The marker keeps it from
+    // being re-armed after a restart when it sits above the watermark.
+    pub(crate) async fn complete_delay_message(
+        &self,
+        delay_message: &DelayMessageRecord,
     ) -> Result<(), CommonError> {
-        if let Some(mut delay_queue) = self.delay_queue_list.get_mut(&shard_no) {
-            delay_queue.insert_at(
-                delay_message_record.clone(),
-                Instant::now() + Duration::from_secs(delay_message_record.delay_timestamp),
-            );
+        let mut marker = Record::build_byte(Vec::new());
+        marker.set_header(vec![Header {
+            name: DELAY_COMPLETED_HEADER.to_string(),
+            value: delay_message.offset.to_string(),
+        }]);
+        let offset = self
+            .message_storage_adapter
+            .write(
+                self.namespace.clone(),
+                delay_message.shard_name.clone(),
+                marker,
+            )
+            .await?;
+
+        self.pending.remove(&delay_message.delay_id);
+        if let Some(mut watermark) = self.watermarks.get_mut(&delay_message.shard_no) {
+            watermark.pending.remove(&delay_message.offset);
+            watermark.seen(offset);
+        }
+        self.commit_watermark(delay_message.shard_no).await
+    }
+
+    async fn commit_watermark(&self, shard_no: u64) -> Result<(), CommonError> {
+        let offset = match self.watermarks.get(&shard_no) {
+            Some(watermark) => match watermark.watermark() {
+                Some(offset) if offset > watermark.committed_offset => offset,
+                _ => return Ok(()),
+            },
+            None => return Ok(()),
+        };
+
+        let mut offset_data = HashMap::new();
+        offset_data.insert(self.get_delay_message_shard_name(shard_no), offset);
+        self.message_storage_adapter
+            .commit_offset(
+                self.get_delay_message_group_name(shard_no),
+                self.namespace.clone(),
+                offset_data,
+            )
+            .await?;
+
+        if let Some(mut watermark) = self.watermarks.get_mut(&shard_no) {
+            watermark.committed_offset = watermark.committed_offset.max(offset);
         }
         Ok(())
     }
 
+    pub(crate) async fn get_committed_offset(&self, shard_no: u64) -> Result<u64, CommonError> {
+        let offsets = self
+            .message_storage_adapter
+            .get_offset_by_group(self.get_delay_message_group_name(shard_no))
+            .await?;
+        Ok(offsets.first().map(|offset| offset.offset).unwrap_or(0))
+    }
+
+    pub(crate) fn start_rebuild(&self, shard_no: u64, committed_offset: u64) {
+        if let Some(mut watermark) = self.watermarks.get_mut(&shard_no) {
+            watermark.committed_offset = committed_offset;
+            watermark.next_offset = watermark.next_offset.max(committed_offset);
+        }
+    }
+
+    pub(crate) fn rebuild_reached_live(&self, shard_no: u64, offset: u64) -> bool {
+        self.watermarks
+            .get(&shard_no)
+            .and_then(|watermark| watermark.live_start_offset)
+            .is_some_and(|live_start_offset| offset >= live_start_offset)
+    }
+
+    pub(crate) fn mark_seen(&self, shard_no: u64, offset: u64) {
+        if let Some(mut watermark) = self.watermarks.get_mut(&shard_no) {
+            watermark.seen(offset);
+        }
+    }
+
+    pub(crate) async fn finish_rebuild(&self, shard_no: u64) -> Result<(), CommonError> {
+        if let Some(mut watermark) = self.watermarks.get_mut(&shard_no) {
+            watermark.rebuilt = true;
+        }
+        self.commit_watermark(shard_no).await
+    }
+
+    fn take_delay_queue_receiver(
+        &self,
+        shard_no: u64,
+    ) -> Option<mpsc::UnboundedReceiver<DelayQueueCommand>> {
+        self.delay_queue_receiver
+            .remove(&shard_no)
+            .map(|(_, receiver)| receiver)
+    }
+
     fn get_target_shard_no(&self) -> u64 {
         self.incr_no
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
@@ -155,6 +453,27 @@ fn get_target_shard_no(&self) -> u64 {
     fn get_delay_message_shard_name(&self, no: u64) -> String {
         format!("{}{}", DELAY_MESSAGE_SHARD_NAME_PREFIX, no)
     }
+
+    fn get_delay_message_group_name(&self, no: u64) -> String {
+        format!("{}{}-{}", DELAY_MESSAGE_GROUP_PREFIX, self.namespace, no)
+    }
+}
+
+pub fn build_delay_id(shard_no: u64, offset: u64) -> String {
+    format!("{}-{}", shard_no, offset)
+}
+
+fn header_value(record: &Record, name: &str) -> Option<String> {
+    record
+        .header
+        .iter()
+        .find(|header| header.name == name)
+        .map(|header| header.value.clone())
+}
+
+// The offset of the message a completion marker refers to.
+pub(crate) fn completed_offset(record: &Record) -> Option<u64> {
+    header_value(record, DELAY_COMPLETED_HEADER)?.parse().ok()
 }
 
 pub async fn start_build_delay_queue<S>(
@@ -198,6 +517,14 @@ pub async fn start_delay_message_pop<S>(
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
     for shard_no in 0..shard_num {
+        let Some(command_recv) = delay_message_manager.take_delay_queue_receiver(shard_no) else {
+            debug!(
+                "Delay queue pop thread for shard {} already started",
+                shard_no
+            );
+            continue;
+        };
+
         let new_delay_message_manager = delay_message_manager.clone();
         let new_message_storage_adapter = message_storage_adapter.clone();
         let new_namespace = namespace.to_owned();
@@ -206,27 +533,182 @@ pub async fn start_delay_message_pop<S>(
         delay_message_manager.add_delay_queue_pop_thread(shard_no, stop_send.clone());
 
         tokio::spawn(async move {
-            loop {
-                let mut recv = stop_send.subscribe();
-                select! {
-                    val = recv.recv() =>{
-                        if let Ok(flag) = val {
-                            if flag {
-                                debug!("{}","Heartbeat reporting thread exited successfully");
-                                break;
-                            }
-                        }
-                    }
-                    _ =  pop::pop_delay_queue(
-                        &new_namespace,
-                        &new_message_storage_adapter,
-                        &new_delay_message_manager,
-                        shard_no,
-                    ) => {
-                        sleep(Duration::from_millis(100)).await;
-                    }
-                }
-            }
+            pop::pop_delay_queue(
+                &new_namespace,
+                &new_message_storage_adapter,
+                &new_delay_message_manager,
+                command_recv,
+                stop_send,
+            )
+            .await;
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common_base::tools::now_second;
+    use storage_adapter::memory::MemoryStorageAdapter;
+    use tokio::time::sleep;
+
+    use super::*;
+
+    async fn start_manager(
+        storage: &Arc<MemoryStorageAdapter>,
+    ) -> Arc<DelayMessageManager<MemoryStorageAdapter>> {
+        let manager = Arc::new(DelayMessageManager::new(
+            "ns".to_string(),
+            2,
+            storage.clone(),
+        ));
+        manager.init().await.unwrap();
+        start_build_delay_queue(
+            "ns".to_string(),
+            manager.clone(),
+            storage.clone(),
+            manager.get_shard_num(),
+        )
+        .await;
+        start_delay_message_pop(
+            "ns".to_string(),
+            storage.clone(),
+            manager.clone(),
+            manager.get_shard_num(),
+        )
+        .await;
+        manager
+    }
+
+    async fn read_target(storage: &Arc<MemoryStorageAdapter>, expect: usize) -> Vec<Record> {
+        for _ in 0..50 {
+            let records = storage
+                .read_by_offset("ns".to_string(), "target".to_string(), 0, ReadConfig::new())
+                .await
+                .unwrap();
+            if records.len() >= expect {
+                return records;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        panic!("expected {} delivered messages", expect);
+    }
+
+    #[tokio::test]
+    async fn deliver_and_cancel_test() {
+        let storage = Arc::new(MemoryStorageAdapter::new());
+        let manager = start_manager(&storage).await;
+
+        let due = manager
+            .send_delay_message(
+                "a/b",
+                "target",
+                now_second(),
+                Record::build_str("m1".into()),
+            )
+            .await
+            .unwrap();
+        let later = manager
+            .send_delay_message(
+                "a/b",
+                "target",
+                now_second() + 3600,
+                Record::build_str("m2".into()),
+            )
+            .await
+            .unwrap();
+
+        let records = read_target(&storage, 1).await;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].data, b"m1".to_vec());
+        assert!(records[0].header.is_empty());
+        assert_eq!(records[0].delay_timestamp, 0);
+
+        sleep(Duration::from_millis(100)).await;
+        assert!(manager.get_delay_message(&due).is_none());
+        let pending = manager.list_delay_messages();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].delay_id, later);
+        assert_eq!(pending[0].target_topic_name, "a/b");
+
+        manager.cancel_delay_message(&later).await.unwrap();
+        assert!(manager.list_delay_messages().is_empty());
+        assert!(manager.cancel_delay_message(&later).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel_claimed_test() {
+        let storage = Arc::new(MemoryStorageAdapter::new());
+        let manager = start_manager(&storage).await;
+
+        let delay_id = manager
+            .send_delay_message(
+                "a/b",
+                "target",
+                now_second() + 3600,
+                Record::build_str("m1".into()),
+            )
+            .await
+            .unwrap();
+
+        // Claimed for delivery, a concurrent cancel must not succeed as well.
+        let claimed = manager.take_pending(&delay_id).unwrap();
+        assert!(manager.take_pending(&delay_id).is_none());
+        assert!(manager.cancel_delay_message(&delay_id).await.is_err());
+
+        // A failed delivery hands it back, so it can be cancelled again.
+        manager.retry_delay_message(claimed, now_second() + 3600);
+        assert_eq!(manager.list_delay_messages().len(), 1);
+        manager.cancel_delay_message(&delay_id).await.unwrap();
+        assert!(manager.list_delay_messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rebuild_test() {
+        let storage = Arc::new(MemoryStorageAdapter::new());
+        let manager = start_manager(&storage).await;
+
+        let kept = manager
+            .send_delay_message(
+                "a/b",
+                "target",
+                now_second() + 3600,
+                Record::build_str("m1".into()),
+            )
+            .await
+            .unwrap();
+        let cancelled = manager
+            .send_delay_message(
+                "a/b",
+                "target",
+                now_second() + 3600,
+                Record::build_str("m2".into()),
+            )
+            .await
+            .unwrap();
+        manager
+            .send_delay_message(
+                "a/b",
+                "target",
+                now_second(),
+                Record::build_str("m3".into()),
+            )
+            .await
+            .unwrap();
+        manager.cancel_delay_message(&cancelled).await.unwrap();
+        read_target(&storage, 1).await;
+        sleep(Duration::from_millis(100)).await;
+        manager.stop().await.unwrap();
+
+        let restarted = start_manager(&storage).await;
+        sleep(Duration::from_millis(500)).await;
+        let pending = restarted.list_delay_messages();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].delay_id, kept);
+        assert!(pending[0].due_timestamp > now_second());
+
+        // m3 was delivered before the restart and is not delivered again.
+        assert_eq!(read_target(&storage, 1).await.len(), 1);
+    }
+}
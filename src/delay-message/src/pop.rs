@@ -12,70 +12,156 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use crate::DelayMessageManager;
+use crate::{
+    DelayMessageManager, DelayMessageRecord, DelayQueueCommand, DELAY_TARGET_NAMESPACE_HEADER,
+    DELAY_TARGET_SHARD_HEADER, DELAY_TARGET_TOPIC_HEADER,
+};
 use common_base::error::common::CommonError;
+use common_base::tools::now_second;
 use futures::StreamExt;
-use log::error;
+use log::{debug, error};
 use metadata_struct::adapter::{read_config::ReadConfig, record::Record};
 use storage_adapter::storage::StorageAdapter;
+use tokio::{
+    select,
+    sync::{broadcast, mpsc},
+    time::Instant,
+};
+use tokio_util::time::DelayQueue;
 
+const DELIVERY_ATTEMPTS: u32 = 30;
+const DELIVERY_RETRY_INTERVAL_SECS: u64 = 60;
+
+// The queue is owned by this loop, inserts and cancels arrive over the channel,
+// so a long wait for the next due message never blocks senders.
 pub async fn pop_delay_queue<S>(
     namespace: &str,
     message_storage_adapter: &Arc<S>,
     delay_message_manager: &Arc<DelayMessageManager<S>>,
-    shard_no: u64,
+    mut command_recv: mpsc::UnboundedReceiver<DelayQueueCommand>,
+    stop_send: broadcast::Sender<bool>,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
-    if let Some(mut delay_queue) = delay_message_manager.delay_queue_list.get_mut(&shard_no) {
-        while let Some(expired) = delay_queue.next().await {
-            let delay_message = expired.into_inner();
-            let raw_message_storage_adapter = message_storage_adapter.clone();
-            let raw_namespace = namespace.to_owned();
-            tokio::spawn(async move {
-                send_delay_message_to_shard(
-                    &raw_message_storage_adapter,
-                    &raw_namespace,
-                    &delay_message.shard_name,
-                    delay_message.offset,
-                )
-                .await;
-            });
+    let mut delay_queue = DelayQueue::new();
+    let mut keys = HashMap::new();
+    let mut stop_recv = stop_send.subscribe();
+    loop {
+        select! {
+            val = stop_recv.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        debug!("{}", "Delay message pop thread exited successfully");
+                        break;
+                    }
+                }
+            }
+            command = command_recv.recv() => {
+                match command {
+                    Some(DelayQueueCommand::Insert(delay_id, due_timestamp)) => {
+                        if !keys.contains_key(&delay_id) {
+                            let key = delay_queue.insert_at(delay_id.clone(), due_instant(due_timestamp));
+                            keys.insert(delay_id, key);
+                        }
+                    }
+                    Some(DelayQueueCommand::Cancel(delay_id)) => {
+                        if let Some(key) = keys.remove(&delay_id) {
+                            delay_queue.remove(&key);
+                        }
+                    }
+                    None => break,
+                }
+            }
+            Some(expired) = delay_queue.next(), if !delay_queue.is_empty() => {
+                let delay_id = expired.into_inner();
+                keys.remove(&delay_id);
+                // Cancelled in the meantime.
+                let Some(delay_message) = delay_message_manager.take_pending(&delay_id) else {
+                    continue;
+                };
+
+                let raw_message_storage_adapter = message_storage_adapter.clone();
+                let raw_delay_message_manager = delay_message_manager.clone();
+                let raw_namespace = namespace.to_owned();
+                tokio::spawn(async move {
+                    send_delay_message_to_target(
+                        &raw_message_storage_adapter,
+                        &raw_delay_message_manager,
+                        &raw_namespace,
+                        &delay_message,
+                    )
+                    .await;
+                });
+            }
         }
     }
 }
 
-async fn send_delay_message_to_shard<S>(
+fn due_instant(due_timestamp: u64) -> Instant {
+    Instant::now() + Duration::from_secs(due_timestamp.saturating_sub(now_second()))
+}
+
+async fn send_delay_message_to_target<S>(
     message_storage_adapter: &Arc<S>,
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
     namespace: &str,
-    shard_name: &str,
-    offset: u64,
+    delay_message: &DelayMessageRecord,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
     let mut times = 0;
     loop {
-        if times > 1000 {
-            error!("send_delay_message_to_shard failed, times: {},namespace:{},shard_name:{},offset:{}", times, namespace, shard_name, offset);
-            break;
+        if times >= DELIVERY_ATTEMPTS {
+            error!(
+                "Failed to deliver delay message {} to {} after {} attempts, retrying in {}s",
+                delay_message.delay_id,
+                delay_message.target_topic_name,
+                times,
+                DELIVERY_RETRY_INTERVAL_SECS
+            );
+            delay_message_manager.retry_delay_message(
+                delay_message.clone(),
+                now_second() + DELIVERY_RETRY_INTERVAL_SECS,
+            );
+            return;
         }
 
         times += 1;
-        let record =
-            match read_offset_data(message_storage_adapter, namespace, shard_name, offset).await {
-                Ok(Some(record)) => record,
-                Ok(None) => break,
-                Err(e) => {
-                    error!("read_offset_data failed, err: {:?}", e);
-                    tokio::time::sleep(Duration::from_millis(1000)).await;
-                    continue;
-                }
-            };
+        let record = match read_offset_data(
+            message_storage_adapter,
+            namespace,
+            &delay_message.shard_name,
+            delay_message.offset,
+        )
+        .await
+        {
+            Ok(Some(record)) => record,
+            Ok(None) => {
+                error!(
+                    "Delay message {} no longer exists in shard {}, skipping it",
+                    delay_message.delay_id, delay_message.shard_name
+                );
+                break;
+            }
+            Err(e) => {
+                error!("read_offset_data failed, err: {:?}", e);
+                tokio::time::sleep(Duration::from_millis(1000)).await;
+                continue;
+            }
+        };
 
+        let target_namespace = delay_message
+            .target_namespace
+            .clone()
+            .unwrap_or(namespace.to_owned());
         match message_storage_adapter
-            .write(namespace.to_owned(), shard_name.to_owned(), record)
+            .write(
+                target_namespace,
+                delay_message.target_shard_name.to_owned(),
+                build_target_record(record),
+            )
             .await
         {
             Ok(_) => {
@@ -88,9 +174,30 @@ async fn send_delay_message_to_shard<S>(
             }
         }
     }
+
+    if let Err(e) = delay_message_manager
+        .complete_delay_message(delay_message)
+        .await
+    {
+        error!(
+            "Failed to commit the delivery of delay message {}, error message: {:?}",
+            delay_message.delay_id, e
+        );
+    }
+}
+
+fn build_target_record(mut record: Record) -> Record {
+    record.offset = None;
+    record.delay_timestamp = 0;
+    record.header.retain(|header| {
+        header.name != DELAY_TARGET_TOPIC_HEADER
+            && header.name != DELAY_TARGET_SHARD_HEADER
+            && header.name != DELAY_TARGET_NAMESPACE_HEADER
+    });
+    record
 }
 
-async fn read_offset_data<S>(
+pub(crate) async fn read_offset_data<S>(
     message_storage_adapter: &Arc<S>,
     namespace: &str,
     shard_name: &str,
@@ -113,7 +220,7 @@ async fn read_offset_data<S>(
         .await?;
 
     for record in results {
-        if record.offset.unwrap() == offset {
+        if record.offset == Some(offset) {
             return Ok(Some(record));
         }
     }
@@ -122,6 +229,31 @@ async fn read_offset_data<S>(
 
 #[cfg(test)]
 mod test {
+    use metadata_struct::adapter::record::Header;
+
+    use super::*;
+
     #[test]
-    pub fn read_offset_data_test() {}
+    pub fn build_target_record_test() {
+        let mut record = Record::build_str("payload".to_string());
+        record.offset = Some(3);
+        record.delay_timestamp = 100;
+        record.set_header(vec![
+            Header {
+                name: DELAY_TARGET_TOPIC_HEADER.to_string(),
+                value: "a/b".to_string(),
+            },
+            Header {
+                name: "other".to_string(),
+                value: "1".to_string(),
+            },
+        ]);
+
+        let record = build_target_record(record);
+        assert_eq!(record.offset, None);
+        assert_eq!(record.delay_timestamp, 0);
+        assert_eq!(record.header.len(), 1);
+        assert_eq!(record.header[0].name, "other");
+        assert!(record.crc32_check());
+    }
 }
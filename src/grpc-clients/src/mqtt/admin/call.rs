@@ -25,13 +25,30 @@
     ListBlacklistReply, ListBlacklistRequest, ListConnectionReply, ListConnectionRequest,
     ListSlowSubscribeReply, ListSlowSubscribeRequest, ListTopicReply, ListTopicRequest,
     ListUserReply, ListUserRequest, MqttBindSchemaReply, MqttBindSchemaRequest,
-    MqttCreateConnectorReply, MqttCreateConnectorRequest, MqttCreateSchemaReply,
-    MqttCreateSchemaRequest, MqttDeleteConnectorReply, MqttDeleteConnectorRequest,
-    MqttDeleteSchemaReply, MqttDeleteSchemaRequest, MqttListBindSchemaReply,
-    MqttListBindSchemaRequest, MqttListConnectorReply, MqttListConnectorRequest,
-    MqttListSchemaReply, MqttListSchemaRequest, MqttUnbindSchemaReply, MqttUnbindSchemaRequest,
-    MqttUpdateConnectorReply, MqttUpdateConnectorRequest, MqttUpdateSchemaReply,
-    MqttUpdateSchemaRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
+    MqttCancelDelayMessageReply, MqttCancelDelayMessageRequest, MqttCleanSessionReply,
+    MqttCleanSessionRequest, MqttClearAlarmReply, MqttClearAlarmRequest, MqttCreateConnectorReply,
+    MqttCreateConnectorRequest, MqttCreateRuleReply, MqttCreateRuleRequest, MqttCreateSchemaReply,
+    MqttCreateSchemaRequest, MqttCreateTenantReply, MqttCreateTenantRequest,
+    MqttDeleteConnectorReply, MqttDeleteConnectorRequest, MqttDeleteRetainMessageReply,
+    MqttDeleteRetainMessageRequest, MqttDeleteRuleReply, MqttDeleteRuleRequest,
+    MqttDeleteSchemaReply, MqttDeleteSchemaRequest, MqttDeleteTenantReply, MqttDeleteTenantRequest,
+    MqttGetRetainMessageReply, MqttGetRetainMessageRequest, MqttInspectClientReply,
+    MqttInspectClientRequest, MqttInspectDelayMessageReply, MqttInspectDelayMessageRequest,
+    MqttKickClientReply, MqttKickClientRequest, MqttListAlarmReply, MqttListAlarmRequest,
+    MqttListBindSchemaReply, MqttListBindSchemaRequest, MqttListClientTraceReply,
+    MqttListClientTraceRequest, MqttListConnectorReply, MqttListConnectorRequest,
+    MqttListDelayMessageReply, MqttListDelayMessageRequest, MqttListRetainMessageReply,
+    MqttListRetainMessageRequest, MqttListRuleReply, MqttListRuleRequest, MqttListSchemaReply,
+    MqttListSchemaRequest, MqttListSchemaVersionReply, MqttListSchemaVersionRequest,
+    MqttListSessionReply, MqttListSessionRequest, MqttListSubscribeReply, MqttListSubscribeRequest,
+    MqttListTenantReply, MqttListTenantRequest, MqttPeekMessageReply, MqttPeekMessageRequest,
+    MqttPublishMessageReply, MqttPublishMessageRequest, MqttReloadConfigReply,
+    MqttReloadConfigRequest, MqttReloadTlsCertReply, MqttReloadTlsCertRequest,
+    MqttSetClientTraceReply, MqttSetClientTraceRequest, MqttSetTenantConfigReply,
+    MqttSetTenantConfigRequest, MqttUnbindSchemaReply, MqttUnbindSchemaRequest,
+    MqttUpdateConnectorReply, MqttUpdateConnectorRequest, MqttUpdateRuleReply,
+    MqttUpdateRuleRequest, MqttUpdateSchemaReply, MqttUpdateSchemaRequest, MqttUpdateTenantReply,
+    MqttUpdateTenantRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
 };
 
 use crate::pool::ClientPool;
@@ -56,6 +73,20 @@ pub async fn $fn_name(
     ClusterStatus
 );
 
+generate_mqtt_admin_service_call!(
+    mqtt_broker_reload_config,
+    MqttReloadConfigRequest,
+    MqttReloadConfigReply,
+    MqttReloadConfig
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_reload_tls_cert,
+    MqttReloadTlsCertRequest,
+    MqttReloadTlsCertReply,
+    MqttReloadTlsCert
+);
+
 // ------ user -------
 generate_mqtt_admin_service_call!(
     mqtt_broker_list_user,
@@ -197,6 +228,160 @@ pub async fn $fn_name(
     MqttDeleteConnector
 );
 
+// rule command line CRUD
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_rule,
+    MqttListRuleRequest,
+    MqttListRuleReply,
+    MqttListRule
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_create_rule,
+    MqttCreateRuleRequest,
+    MqttCreateRuleReply,
+    MqttCreateRule
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_update_rule,
+    MqttUpdateRuleRequest,
+    MqttUpdateRuleReply,
+    MqttUpdateRule
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_delete_rule,
+    MqttDeleteRuleRequest,
+    MqttDeleteRuleReply,
+    MqttDeleteRule
+);
+
+// delay message command line
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_delay_message,
+    MqttListDelayMessageRequest,
+    MqttListDelayMessageReply,
+    MqttListDelayMessage
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_inspect_delay_message,
+    MqttInspectDelayMessageRequest,
+    MqttInspectDelayMessageReply,
+    MqttInspectDelayMessage
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_cancel_delay_message,
+    MqttCancelDelayMessageRequest,
+    MqttCancelDelayMessageReply,
+    MqttCancelDelayMessage
+);
+
+// alarm command line
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_alarm,
+    MqttListAlarmRequest,
+    MqttListAlarmReply,
+    MqttListAlarm
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_clear_alarm,
+    MqttClearAlarmRequest,
+    MqttClearAlarmReply,
+    MqttClearAlarm
+);
+
+// trace command line
+generate_mqtt_admin_service_call!(
+    mqtt_broker_set_client_trace,
+    MqttSetClientTraceRequest,
+    MqttSetClientTraceReply,
+    MqttSetClientTrace
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_client_trace,
+    MqttListClientTraceRequest,
+    MqttListClientTraceReply,
+    MqttListClientTrace
+);
+
+// client and session command line
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_session,
+    MqttListSessionRequest,
+    MqttListSessionReply,
+    MqttListSession
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_subscribe,
+    MqttListSubscribeRequest,
+    MqttListSubscribeReply,
+    MqttListSubscribe
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_inspect_client,
+    MqttInspectClientRequest,
+    MqttInspectClientReply,
+    MqttInspectClient
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_kick_client,
+    MqttKickClientRequest,
+    MqttKickClientReply,
+    MqttKickClient
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_clean_session,
+    MqttCleanSessionRequest,
+    MqttCleanSessionReply,
+    MqttCleanSession
+);
+
+// message command line
+generate_mqtt_admin_service_call!(
+    mqtt_broker_publish_message,
+    MqttPublishMessageRequest,
+    MqttPublishMessageReply,
+    MqttPublishMessage
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_peek_message,
+    MqttPeekMessageRequest,
+    MqttPeekMessageReply,
+    MqttPeekMessage
+);
+
+// retain message command line
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_retain_message,
+    MqttListRetainMessageRequest,
+    MqttListRetainMessageReply,
+    MqttListRetainMessage
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_get_retain_message,
+    MqttGetRetainMessageRequest,
+    MqttGetRetainMessageReply,
+    MqttGetRetainMessage
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_delete_retain_message,
+    MqttDeleteRetainMessageRequest,
+    MqttDeleteRetainMessageReply,
+    MqttDeleteRetainMessage
+);
+
 // schema command line CRUD
 generate_mqtt_admin_service_call!(
     mqtt_broker_list_schema,
@@ -205,6 +390,13 @@ pub async fn $fn_name(
     MqttListSchema
 );
 
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_schema_version,
+    MqttListSchemaVersionRequest,
+    MqttListSchemaVersionReply,
+    MqttListSchemaVersion
+);
+
 generate_mqtt_admin_service_call!(
     mqtt_broker_create_schema,
     MqttCreateSchemaRequest,
@@ -267,3 +459,39 @@ pub async fn $fn_name(
     ListAutoSubscribeRuleReply,
     ListAutoSubscribeRule
 );
+
+// tenant command line CRUD
+generate_mqtt_admin_service_call!(
+    mqtt_broker_list_tenant,
+    MqttListTenantRequest,
+    MqttListTenantReply,
+    MqttListTenant
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_create_tenant,
+    MqttCreateTenantRequest,
+    MqttCreateTenantReply,
+    MqttCreateTenant
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_update_tenant,
+    MqttUpdateTenantRequest,
+    MqttUpdateTenantReply,
+    MqttUpdateTenant
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_delete_tenant,
+    MqttDeleteTenantRequest,
+    MqttDeleteTenantReply,
+    MqttDeleteTenant
+);
+
+generate_mqtt_admin_service_call!(
+    mqtt_broker_set_tenant_config,
+    MqttSetTenantConfigRequest,
+    MqttSetTenantConfigReply,
+    MqttSetTenantConfig
+);
@@ -18,10 +18,26 @@
 use protocol::broker_mqtt::broker_mqtt_admin::{
     ClusterStatusReply, ClusterStatusRequest, DeleteAutoSubscribeRuleReply,
     DeleteAutoSubscribeRuleRequest, ListAutoSubscribeRuleReply, ListAutoSubscribeRuleRequest,
-    MqttCreateConnectorReply, MqttCreateConnectorRequest, MqttDeleteConnectorReply,
-    MqttDeleteConnectorRequest, MqttListConnectorReply, MqttListConnectorRequest,
-    MqttUpdateConnectorReply, MqttUpdateConnectorRequest, SetAutoSubscribeRuleReply,
-    SetAutoSubscribeRuleRequest,
+    MqttCancelDelayMessageReply, MqttCancelDelayMessageRequest, MqttCleanSessionReply,
+    MqttCleanSessionRequest, MqttClearAlarmReply, MqttClearAlarmRequest, MqttCreateConnectorReply,
+    MqttCreateConnectorRequest, MqttCreateRuleReply, MqttCreateRuleRequest, MqttCreateTenantReply,
+    MqttCreateTenantRequest, MqttDeleteConnectorReply, MqttDeleteConnectorRequest,
+    MqttDeleteRetainMessageReply, MqttDeleteRetainMessageRequest, MqttDeleteRuleReply,
+    MqttDeleteRuleRequest, MqttDeleteTenantReply, MqttDeleteTenantRequest,
+    MqttGetRetainMessageReply, MqttGetRetainMessageRequest, MqttInspectClientReply,
+    MqttInspectClientRequest, MqttInspectDelayMessageReply, MqttInspectDelayMessageRequest,
+    MqttKickClientReply, MqttKickClientRequest, MqttListAlarmReply, MqttListAlarmRequest,
+    MqttListClientTraceReply, MqttListClientTraceRequest, MqttListConnectorReply,
+    MqttListConnectorRequest, MqttListDelayMessageReply, MqttListDelayMessageRequest,
+    MqttListRetainMessageReply, MqttListRetainMessageRequest, MqttListRuleReply,
+    MqttListRuleRequest, MqttListSessionReply, MqttListSessionRequest, MqttListSubscribeReply,
+    MqttListSubscribeRequest, MqttListTenantReply, MqttListTenantRequest, MqttPeekMessageReply,
+    MqttPeekMessageRequest, MqttPublishMessageReply, MqttPublishMessageRequest,
+    MqttReloadConfigReply, MqttReloadConfigRequest, MqttReloadTlsCertReply,
+    MqttReloadTlsCertRequest, MqttSetClientTraceReply, MqttSetClientTraceRequest,
+    MqttSetTenantConfigReply, MqttSetTenantConfigRequest, MqttUpdateConnectorReply,
+    MqttUpdateConnectorRequest, MqttUpdateRuleReply, MqttUpdateRuleRequest, MqttUpdateTenantReply,
+    MqttUpdateTenantRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
 };
 use protocol::broker_mqtt::broker_mqtt_admin::{
     CreateAclReply, CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest,
@@ -34,7 +50,8 @@
     ListSlowSubscribeRequest, ListTopicReply, ListTopicRequest, ListUserReply, ListUserRequest,
     MqttBindSchemaReply, MqttBindSchemaRequest, MqttCreateSchemaReply, MqttCreateSchemaRequest,
     MqttDeleteSchemaReply, MqttDeleteSchemaRequest, MqttListBindSchemaReply,
-    MqttListBindSchemaRequest, MqttListSchemaReply, MqttListSchemaRequest, MqttUnbindSchemaReply,
+    MqttListBindSchemaRequest, MqttListSchemaReply, MqttListSchemaRequest,
+    MqttListSchemaVersionReply, MqttListSchemaVersionRequest, MqttUnbindSchemaReply,
     MqttUnbindSchemaRequest, MqttUpdateSchemaReply, MqttUpdateSchemaRequest,
 };
 use tonic::transport::Channel;
@@ -86,6 +103,22 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     cluster_status
 );
 
+impl_retriable_request!(
+    MqttReloadConfigRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttReloadConfigReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_reload_config
+);
+
+impl_retriable_request!(
+    MqttReloadTlsCertRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttReloadTlsCertReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_reload_tls_cert
+);
+
 impl_retriable_request!(
     ListUserRequest,
     MqttBrokerAdminServiceClient<Channel>,
@@ -247,6 +280,181 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     mqtt_broker_delete_connector
 );
 
+// rule command line CRUD
+impl_retriable_request!(
+    MqttListRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_rule
+);
+
+impl_retriable_request!(
+    MqttCreateRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttCreateRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_create_rule
+);
+
+impl_retriable_request!(
+    MqttUpdateRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttUpdateRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_update_rule
+);
+
+impl_retriable_request!(
+    MqttDeleteRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttDeleteRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_delete_rule
+);
+
+// delay message command line
+impl_retriable_request!(
+    MqttListDelayMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListDelayMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_delay_message
+);
+
+impl_retriable_request!(
+    MqttInspectDelayMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttInspectDelayMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_inspect_delay_message
+);
+
+impl_retriable_request!(
+    MqttCancelDelayMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttCancelDelayMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_cancel_delay_message
+);
+
+// alarm command line
+impl_retriable_request!(
+    MqttListAlarmRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListAlarmReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_alarm
+);
+
+impl_retriable_request!(
+    MqttClearAlarmRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttClearAlarmReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_clear_alarm
+);
+
+// trace command line
+impl_retriable_request!(
+    MqttSetClientTraceRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttSetClientTraceReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_set_client_trace
+);
+
+impl_retriable_request!(
+    MqttListClientTraceRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListClientTraceReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_client_trace
+);
+
+// client and session command line
+impl_retriable_request!(
+    MqttListSessionRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListSessionReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_session
+);
+
+impl_retriable_request!(
+    MqttListSubscribeRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListSubscribeReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_subscribe
+);
+
+impl_retriable_request!(
+    MqttInspectClientRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttInspectClientReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_inspect_client
+);
+
+impl_retriable_request!(
+    MqttKickClientRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttKickClientReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_kick_client
+);
+
+impl_retriable_request!(
+    MqttCleanSessionRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttCleanSessionReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_clean_session
+);
+
+// message command line
+impl_retriable_request!(
+    MqttPublishMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttPublishMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_publish_message
+);
+
+impl_retriable_request!(
+    MqttPeekMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttPeekMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_peek_message
+);
+
+// retain message command line
+impl_retriable_request!(
+    MqttListRetainMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListRetainMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_retain_message
+);
+
+impl_retriable_request!(
+    MqttGetRetainMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttGetRetainMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_get_retain_message
+);
+
+impl_retriable_request!(
+    MqttDeleteRetainMessageRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttDeleteRetainMessageReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_delete_retain_message
+);
+
 // schema command line CRUD
 impl_retriable_request!(
     MqttListSchemaRequest,
@@ -256,6 +464,14 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     mqtt_broker_list_schema
 );
 
+impl_retriable_request!(
+    MqttListSchemaVersionRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListSchemaVersionReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_schema_version
+);
+
 impl_retriable_request!(
     MqttCreateSchemaRequest,
     MqttBrokerAdminServiceClient<Channel>,
@@ -327,3 +543,44 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     mqtt_broker_admin_services_client,
     mqtt_broker_delete_auto_subscribe_rule
 );
+
+// tenant command line CRUD
+impl_retriable_request!(
+    MqttListTenantRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttListTenantReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_tenant
+);
+
+impl_retriable_request!(
+    MqttCreateTenantRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttCreateTenantReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_create_tenant
+);
+
+impl_retriable_request!(
+    MqttUpdateTenantRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttUpdateTenantReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_update_tenant
+);
+
+impl_retriable_request!(
+    MqttDeleteTenantRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttDeleteTenantReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_delete_tenant
+);
+
+impl_retriable_request!(
+    MqttSetTenantConfigRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    MqttSetTenantConfigReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_set_tenant_config
+);
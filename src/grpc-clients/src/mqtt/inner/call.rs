@@ -14,7 +14,8 @@
 
 use common_base::error::common::CommonError;
 use protocol::broker_mqtt::broker_mqtt_inner::{
-    DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
+    DeleteSessionReply, DeleteSessionRequest, GetClientDetailReply, GetClientDetailRequest,
+    KickClientReply, KickClientRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
     UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 
@@ -52,3 +53,17 @@ pub async fn $fn_name(
     SendLastWillMessageReply,
     SendLastWillMessage
 );
+
+generate_mqtt_inner_service_call!(
+    broker_mqtt_get_client_detail,
+    GetClientDetailRequest,
+    GetClientDetailReply,
+    GetClientDetail
+);
+
+generate_mqtt_inner_service_call!(
+    broker_mqtt_kick_client,
+    KickClientRequest,
+    KickClientReply,
+    KickClient
+);
@@ -16,7 +16,8 @@
 use mobc::Manager;
 use protocol::broker_mqtt::broker_mqtt_inner::mqtt_broker_inner_service_client::MqttBrokerInnerServiceClient;
 use protocol::broker_mqtt::broker_mqtt_inner::{
-    DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
+    DeleteSessionReply, DeleteSessionRequest, GetClientDetailReply, GetClientDetailRequest,
+    KickClientReply, KickClientRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
     UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 use tonic::transport::Channel;
@@ -83,3 +84,19 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     mqtt_broker_mqtt_services_client,
     send_last_will_message
 );
+
+impl_retriable_request!(
+    GetClientDetailRequest,
+    MqttBrokerInnerServiceClient<Channel>,
+    GetClientDetailReply,
+    mqtt_broker_mqtt_services_client,
+    get_client_detail
+);
+
+impl_retriable_request!(
+    KickClientRequest,
+    MqttBrokerInnerServiceClient<Channel>,
+    KickClientReply,
+    mqtt_broker_mqtt_services_client,
+    kick_client
+);
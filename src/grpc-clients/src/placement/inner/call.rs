@@ -14,17 +14,20 @@
 
 use common_base::error::common::CommonError;
 use protocol::placement_center::placement_center_inner::{
-    BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest,
-    CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
-    DeleteResourceConfigReply, DeleteResourceConfigRequest, DeleteSchemaReply, DeleteSchemaRequest,
-    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, GetOffsetDataReply,
-    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, HeartbeatReply,
-    HeartbeatRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
-    ListSchemaRequest, NodeListReply, NodeListRequest, RegisterNodeReply, RegisterNodeRequest,
-    SaveOffsetDataReply, SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
-    SetResourceConfigReply, SetResourceConfigRequest, UnBindSchemaReply, UnBindSchemaRequest,
-    UnRegisterNodeReply, UnRegisterNodeRequest, UpdateSchemaReply, UpdateSchemaRequest,
+    BackupReply, BackupRequest, BindSchemaReply, BindSchemaRequest, ClusterStatusReply,
+    ClusterStatusRequest, CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply,
+    DeleteIdempotentDataRequest, DeleteResourceConfigReply, DeleteResourceConfigRequest,
+    DeleteSchemaReply, DeleteSchemaRequest, ExistsIdempotentDataReply, ExistsIdempotentDataRequest,
+    GetOffsetDataReply, GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest,
+    GetSchemaReply, GetSchemaRequest, HeartbeatReply, HeartbeatRequest, ListBindSchemaReply,
+    ListBindSchemaRequest, ListSchemaReply, ListSchemaRequest, ListSchemaVersionReply,
+    ListSchemaVersionRequest, NodeListReply, NodeListRequest, RegisterNodeReply,
+    RegisterNodeRequest, RestoreReply, RestoreRequest, SaveOffsetDataReply, SaveOffsetDataRequest,
+    SetIdempotentDataReply, SetIdempotentDataRequest, SetResourceConfigReply,
+    SetResourceConfigRequest, UnBindSchemaReply, UnBindSchemaRequest, UnRegisterNodeReply,
+    UnRegisterNodeRequest, UpdateSchemaReply, UpdateSchemaRequest,
 };
+use tonic::{IntoStreamingRequest, Streaming};
 
 use crate::pool::ClientPool;
 
@@ -107,6 +110,15 @@ pub async fn $fn_name(
 
 generate_placement_service_call!(list_schema, ListSchemaRequest, ListSchemaReply, ListSchema);
 
+generate_placement_service_call!(get_schema, GetSchemaRequest, GetSchemaReply, GetSchema);
+
+generate_placement_service_call!(
+    list_schema_version,
+    ListSchemaVersionRequest,
+    ListSchemaVersionReply,
+    ListSchemaVersion
+);
+
 generate_placement_service_call!(
     create_schema,
     CreateSchemaRequest,
@@ -150,3 +162,55 @@ pub async fn $fn_name(
     GetOffsetDataReply,
     GetOffsetData
 );
+
+/// Backup is a server streaming call, the archive chunks are returned as they are read.
+/// Any placement center node can serve it from its own state machine.
+pub async fn backup(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: BackupRequest,
+) -> Result<Streaming<BackupReply>, CommonError> {
+    let mut last_err = CommonError::CommonError("Call address list cannot be empty".to_string());
+    for addr in addrs {
+        let mut client = match client_pool
+            .placement_center_inner_services_client(addr.as_ref())
+            .await
+        {
+            Ok(client) => client,
+            Err(e) => {
+                last_err = e;
+                continue;
+            }
+        };
+        match client.backup(request.clone()).await {
+            Ok(reply) => return Ok(reply.into_inner()),
+            Err(e) => last_err = e.into(),
+        }
+    }
+    Err(last_err)
+}
+
+/// Restore consumes the request stream, so it is sent once to the first reachable address,
+/// which has to be the raft leader for the import to be committed.
+pub async fn restore(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: impl IntoStreamingRequest<Message = RestoreRequest>,
+) -> Result<RestoreReply, CommonError> {
+    let mut last_err = CommonError::CommonError("Call address list cannot be empty".to_string());
+    for addr in addrs {
+        match client_pool
+            .placement_center_inner_services_client(addr.as_ref())
+            .await
+        {
+            Ok(mut client) => {
+                return match client.restore(request).await {
+                    Ok(reply) => Ok(reply.into_inner()),
+                    Err(e) => Err(e.into()),
+                };
+            }
+            Err(e) => last_err = e,
+        }
+    }
+    Err(last_err)
+}
@@ -20,10 +20,11 @@
     CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
     DeleteResourceConfigReply, DeleteResourceConfigRequest, DeleteSchemaReply, DeleteSchemaRequest,
     ExistsIdempotentDataReply, ExistsIdempotentDataRequest, GetOffsetDataReply,
-    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, HeartbeatReply,
-    HeartbeatRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
-    ListSchemaRequest, NodeListReply, NodeListRequest, RegisterNodeReply, RegisterNodeRequest,
-    SaveOffsetDataReply, SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
+    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, GetSchemaReply,
+    GetSchemaRequest, HeartbeatReply, HeartbeatRequest, ListBindSchemaReply, ListBindSchemaRequest,
+    ListSchemaReply, ListSchemaRequest, ListSchemaVersionReply, ListSchemaVersionRequest,
+    NodeListReply, NodeListRequest, RegisterNodeReply, RegisterNodeRequest, SaveOffsetDataReply,
+    SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
     SetResourceConfigReply, SetResourceConfigRequest, UnBindSchemaReply, UnBindSchemaRequest,
     UnRegisterNodeReply, UnRegisterNodeRequest, UpdateSchemaReply, UpdateSchemaRequest,
 };
@@ -194,6 +195,24 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     true
 );
 
+impl_retriable_request!(
+    GetSchemaRequest,
+    PlacementCenterServiceClient<Channel>,
+    GetSchemaReply,
+    placement_center_inner_services_client,
+    get_schema,
+    true
+);
+
+impl_retriable_request!(
+    ListSchemaVersionRequest,
+    PlacementCenterServiceClient<Channel>,
+    ListSchemaVersionReply,
+    placement_center_inner_services_client,
+    list_schema_version,
+    true
+);
+
 impl_retriable_request!(
     CreateSchemaRequest,
     PlacementCenterServiceClient<Channel>,
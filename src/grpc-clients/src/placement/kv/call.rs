@@ -13,10 +13,15 @@
 // limitations under the License.
 
 use common_base::error::common::CommonError;
+use protocol::placement_center::placement_center_kv::request_op::Op;
 use protocol::placement_center::placement_center_kv::{
-    DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    Compare, CompareResult, CompareTarget, DeleteReply, DeleteRequest, ExistsReply, ExistsRequest,
+    GetPrefixReply, GetPrefixRequest, GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest,
+    LeaseKeepAliveReply, LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest,
+    ListShardReply, ListShardRequest, RequestOp, SetReply, SetRequest, TxnReply, TxnRequest,
+    WatchReply, WatchRequest,
 };
+use tonic::Streaming;
 
 use crate::pool::ClientPool;
 
@@ -48,3 +53,88 @@ pub async fn $fn_name(
     GetPrefixReply,
     GetPrefix
 );
+generate_kv_service_call!(
+    placement_lease_grant,
+    LeaseGrantRequest,
+    LeaseGrantReply,
+    LeaseGrant
+);
+generate_kv_service_call!(
+    placement_lease_keep_alive,
+    LeaseKeepAliveRequest,
+    LeaseKeepAliveReply,
+    LeaseKeepAlive
+);
+generate_kv_service_call!(
+    placement_lease_revoke,
+    LeaseRevokeRequest,
+    LeaseRevokeReply,
+    LeaseRevoke
+);
+generate_kv_service_call!(placement_txn, TxnRequest, TxnReply, Txn);
+
+/// Set `key` to `value` only if its current value equals `expect`, or only if the key
+/// does not exist when `expect` is `None`. Returns whether the value was swapped.
+pub async fn placement_compare_and_swap(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    key: String,
+    expect: Option<String>,
+    value: String,
+) -> Result<bool, CommonError> {
+    let compare = match expect {
+        Some(expect) => Compare {
+            key: key.clone(),
+            target: CompareTarget::Value.into(),
+            result: CompareResult::Equal.into(),
+            value: expect,
+            number: 0,
+        },
+        None => Compare {
+            key: key.clone(),
+            target: CompareTarget::Version.into(),
+            result: CompareResult::Equal.into(),
+            value: String::new(),
+            number: 0,
+        },
+    };
+    let request = TxnRequest {
+        compares: vec![compare],
+        success: vec![RequestOp {
+            op: Some(Op::Put(SetRequest {
+                key,
+                value,
+                lease_id: 0,
+            })),
+        }],
+        failure: Vec::new(),
+    };
+    Ok(placement_txn(client_pool, addrs, request).await?.succeeded)
+}
+
+/// Watch is a server streaming call, so it is not retried through the leader like the
+/// unary calls. Every placement center node applies the raft log and can serve it.
+pub async fn placement_watch(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: WatchRequest,
+) -> Result<Streaming<WatchReply>, CommonError> {
+    let mut last_err = CommonError::CommonError("Call address list cannot be empty".to_string());
+    for addr in addrs {
+        let mut client = match client_pool
+            .placement_center_kv_services_client(addr.as_ref())
+            .await
+        {
+            Ok(client) => client,
+            Err(e) => {
+                last_err = e;
+                continue;
+            }
+        };
+        match client.watch(request.clone()).await {
+            Ok(reply) => return Ok(reply.into_inner()),
+            Err(e) => last_err = e.into(),
+        }
+    }
+    Err(last_err)
+}
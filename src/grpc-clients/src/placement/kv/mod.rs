@@ -17,7 +17,9 @@
 use protocol::placement_center::placement_center_kv::kv_service_client::KvServiceClient;
 use protocol::placement_center::placement_center_kv::{
     DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest, ListShardReply, ListShardRequest,
+    SetReply, SetRequest, TxnReply, TxnRequest,
 };
 use tonic::transport::Channel;
 
@@ -115,5 +117,41 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     true
 );
 
+impl_retriable_request!(
+    LeaseGrantRequest,
+    KvServiceClient<Channel>,
+    LeaseGrantReply,
+    placement_center_kv_services_client,
+    lease_grant,
+    true
+);
+
+impl_retriable_request!(
+    LeaseKeepAliveRequest,
+    KvServiceClient<Channel>,
+    LeaseKeepAliveReply,
+    placement_center_kv_services_client,
+    lease_keep_alive,
+    true
+);
+
+impl_retriable_request!(
+    LeaseRevokeRequest,
+    KvServiceClient<Channel>,
+    LeaseRevokeReply,
+    placement_center_kv_services_client,
+    lease_revoke,
+    true
+);
+
+impl_retriable_request!(
+    TxnRequest,
+    KvServiceClient<Channel>,
+    TxnReply,
+    placement_center_kv_services_client,
+    txn,
+    true
+);
+
 #[cfg(test)]
 mod tests {}
@@ -16,22 +16,26 @@
 use protocol::placement_center::placement_center_mqtt::{
     ConnectorHeartbeatReply, ConnectorHeartbeatRequest, CreateAclReply, CreateAclRequest,
     CreateBlacklistReply, CreateBlacklistRequest, CreateConnectorReply, CreateConnectorRequest,
-    CreateSessionReply, CreateSessionRequest, CreateTopicReply, CreateTopicRequest,
+    CreateRuleReply, CreateRuleRequest, CreateSessionReply, CreateSessionRequest,
+    CreateTenantReply, CreateTenantRequest, CreateTopicReply, CreateTopicRequest,
     CreateTopicRewriteRuleReply, CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest,
-    DeleteAclReply, DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
-    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteConnectorReply, DeleteConnectorRequest,
-    DeleteSessionReply, DeleteSessionRequest, DeleteSubscribeReply, DeleteSubscribeRequest,
-    DeleteTopicReply, DeleteTopicRequest, DeleteTopicRewriteRuleReply,
-    DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply,
-    GetShareSubLeaderRequest, ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply,
+    DeleteAclReply, DeleteAclRequest, DeleteAlarmReply, DeleteAlarmRequest,
+    DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest, DeleteBlacklistReply,
+    DeleteBlacklistRequest, DeleteConnectorReply, DeleteConnectorRequest, DeleteRuleReply,
+    DeleteRuleRequest, DeleteSessionReply, DeleteSessionRequest, DeleteSubscribeReply,
+    DeleteSubscribeRequest, DeleteTenantReply, DeleteTenantRequest, DeleteTopicReply,
+    DeleteTopicRequest, DeleteTopicRewriteRuleReply, DeleteTopicRewriteRuleRequest,
+    DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply, GetShareSubLeaderRequest,
+    ListAclReply, ListAclRequest, ListAlarmReply, ListAlarmRequest, ListAutoSubscribeRuleReply,
     ListAutoSubscribeRuleRequest, ListBlacklistReply, ListBlacklistRequest, ListConnectorReply,
-    ListConnectorRequest, ListSessionReply, ListSessionRequest, ListSubscribeReply,
-    ListSubscribeRequest, ListTopicReply, ListTopicRequest, ListTopicRewriteRuleReply,
-    ListTopicRewriteRuleRequest, ListUserReply, ListUserRequest, SaveLastWillMessageReply,
-    SaveLastWillMessageRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
-    SetSubscribeReply, SetSubscribeRequest, SetTopicRetainMessageReply,
-    SetTopicRetainMessageRequest, UpdateConnectorReply, UpdateConnectorRequest, UpdateSessionReply,
-    UpdateSessionRequest,
+    ListConnectorRequest, ListRuleReply, ListRuleRequest, ListSessionReply, ListSessionRequest,
+    ListSubscribeReply, ListSubscribeRequest, ListTenantReply, ListTenantRequest, ListTopicReply,
+    ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply,
+    ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest, SetAlarmReply,
+    SetAlarmRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetSubscribeReply,
+    SetSubscribeRequest, SetTopicRetainMessageReply, SetTopicRetainMessageRequest,
+    UpdateConnectorReply, UpdateConnectorRequest, UpdateRuleReply, UpdateRuleRequest,
+    UpdateSessionReply, UpdateSessionRequest, UpdateTenantReply, UpdateTenantRequest,
 };
 
 use crate::pool::ClientPool;
@@ -222,6 +226,62 @@ pub async fn $fn_name(
     ConnectorHeartbeat
 );
 
+generate_mqtt_service_call!(
+    placement_create_rule,
+    CreateRuleRequest,
+    CreateRuleReply,
+    CreateRule
+);
+
+generate_mqtt_service_call!(
+    placement_update_rule,
+    UpdateRuleRequest,
+    UpdateRuleReply,
+    UpdateRule
+);
+
+generate_mqtt_service_call!(
+    placement_delete_rule,
+    DeleteRuleRequest,
+    DeleteRuleReply,
+    DeleteRule
+);
+
+generate_mqtt_service_call!(
+    placement_list_rule,
+    ListRuleRequest,
+    ListRuleReply,
+    ListRule
+);
+
+generate_mqtt_service_call!(
+    placement_create_tenant,
+    CreateTenantRequest,
+    CreateTenantReply,
+    CreateTenant
+);
+
+generate_mqtt_service_call!(
+    placement_update_tenant,
+    UpdateTenantRequest,
+    UpdateTenantReply,
+    UpdateTenant
+);
+
+generate_mqtt_service_call!(
+    placement_delete_tenant,
+    DeleteTenantRequest,
+    DeleteTenantReply,
+    DeleteTenant
+);
+
+generate_mqtt_service_call!(
+    placement_list_tenant,
+    ListTenantRequest,
+    ListTenantReply,
+    ListTenant
+);
+
 generate_mqtt_service_call!(
     placement_list_auto_subscribe_rule,
     ListAutoSubscribeRuleRequest,
@@ -240,3 +300,24 @@ pub async fn $fn_name(
     DeleteAutoSubscribeRuleReply,
     DeleteAutoSubscribeRule
 );
+
+generate_mqtt_service_call!(
+    placement_set_alarm,
+    SetAlarmRequest,
+    SetAlarmReply,
+    SetAlarm
+);
+
+generate_mqtt_service_call!(
+    placement_list_alarm,
+    ListAlarmRequest,
+    ListAlarmReply,
+    ListAlarm
+);
+
+generate_mqtt_service_call!(
+    placement_delete_alarm,
+    DeleteAlarmRequest,
+    DeleteAlarmReply,
+    DeleteAlarm
+);
@@ -18,22 +18,26 @@
 use protocol::placement_center::placement_center_mqtt::{
     ConnectorHeartbeatReply, ConnectorHeartbeatRequest, CreateAclReply, CreateAclRequest,
     CreateBlacklistReply, CreateBlacklistRequest, CreateConnectorReply, CreateConnectorRequest,
-    CreateSessionReply, CreateSessionRequest, CreateTopicReply, CreateTopicRequest,
+    CreateRuleReply, CreateRuleRequest, CreateSessionReply, CreateSessionRequest,
+    CreateTenantReply, CreateTenantRequest, CreateTopicReply, CreateTopicRequest,
     CreateTopicRewriteRuleReply, CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest,
-    DeleteAclReply, DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
-    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteConnectorReply, DeleteConnectorRequest,
-    DeleteSessionReply, DeleteSessionRequest, DeleteSubscribeReply, DeleteSubscribeRequest,
-    DeleteTopicReply, DeleteTopicRequest, DeleteTopicRewriteRuleReply,
-    DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply,
-    GetShareSubLeaderRequest, ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply,
+    DeleteAclReply, DeleteAclRequest, DeleteAlarmReply, DeleteAlarmRequest,
+    DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest, DeleteBlacklistReply,
+    DeleteBlacklistRequest, DeleteConnectorReply, DeleteConnectorRequest, DeleteRuleReply,
+    DeleteRuleRequest, DeleteSessionReply, DeleteSessionRequest, DeleteSubscribeReply,
+    DeleteSubscribeRequest, DeleteTenantReply, DeleteTenantRequest, DeleteTopicReply,
+    DeleteTopicRequest, DeleteTopicRewriteRuleReply, DeleteTopicRewriteRuleRequest,
+    DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply, GetShareSubLeaderRequest,
+    ListAclReply, ListAclRequest, ListAlarmReply, ListAlarmRequest, ListAutoSubscribeRuleReply,
     ListAutoSubscribeRuleRequest, ListBlacklistReply, ListBlacklistRequest, ListConnectorReply,
-    ListConnectorRequest, ListSessionReply, ListSessionRequest, ListSubscribeReply,
-    ListSubscribeRequest, ListTopicReply, ListTopicRequest, ListTopicRewriteRuleReply,
-    ListTopicRewriteRuleRequest, ListUserReply, ListUserRequest, SaveLastWillMessageReply,
-    SaveLastWillMessageRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
-    SetSubscribeReply, SetSubscribeRequest, SetTopicRetainMessageReply,
-    SetTopicRetainMessageRequest, UpdateConnectorReply, UpdateConnectorRequest, UpdateSessionReply,
-    UpdateSessionRequest,
+    ListConnectorRequest, ListRuleReply, ListRuleRequest, ListSessionReply, ListSessionRequest,
+    ListSubscribeReply, ListSubscribeRequest, ListTenantReply, ListTenantRequest, ListTopicReply,
+    ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply,
+    ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest, SetAlarmReply,
+    SetAlarmRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetSubscribeReply,
+    SetSubscribeRequest, SetTopicRetainMessageReply, SetTopicRetainMessageRequest,
+    UpdateConnectorReply, UpdateConnectorRequest, UpdateRuleReply, UpdateRuleRequest,
+    UpdateSessionReply, UpdateSessionRequest, UpdateTenantReply, UpdateTenantRequest,
 };
 use tonic::transport::Channel;
 
@@ -347,6 +351,78 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     true
 );
 
+impl_retriable_request!(
+    CreateRuleRequest,
+    MqttServiceClient<Channel>,
+    CreateRuleReply,
+    placement_center_mqtt_services_client,
+    create_rule,
+    true
+);
+
+impl_retriable_request!(
+    UpdateRuleRequest,
+    MqttServiceClient<Channel>,
+    UpdateRuleReply,
+    placement_center_mqtt_services_client,
+    update_rule,
+    true
+);
+
+impl_retriable_request!(
+    DeleteRuleRequest,
+    MqttServiceClient<Channel>,
+    DeleteRuleReply,
+    placement_center_mqtt_services_client,
+    delete_rule,
+    true
+);
+
+impl_retriable_request!(
+    ListRuleRequest,
+    MqttServiceClient<Channel>,
+    ListRuleReply,
+    placement_center_mqtt_services_client,
+    list_rule,
+    true
+);
+
+impl_retriable_request!(
+    CreateTenantRequest,
+    MqttServiceClient<Channel>,
+    CreateTenantReply,
+    placement_center_mqtt_services_client,
+    create_tenant,
+    true
+);
+
+impl_retriable_request!(
+    UpdateTenantRequest,
+    MqttServiceClient<Channel>,
+    UpdateTenantReply,
+    placement_center_mqtt_services_client,
+    update_tenant,
+    true
+);
+
+impl_retriable_request!(
+    DeleteTenantRequest,
+    MqttServiceClient<Channel>,
+    DeleteTenantReply,
+    placement_center_mqtt_services_client,
+    delete_tenant,
+    true
+);
+
+impl_retriable_request!(
+    ListTenantRequest,
+    MqttServiceClient<Channel>,
+    ListTenantReply,
+    placement_center_mqtt_services_client,
+    list_tenant,
+    true
+);
+
 impl_retriable_request!(
     ListAutoSubscribeRuleRequest,
     MqttServiceClient<Channel>,
@@ -373,3 +449,30 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     delete_auto_subscribe_rule,
     true
 );
+
+impl_retriable_request!(
+    SetAlarmRequest,
+    MqttServiceClient<Channel>,
+    SetAlarmReply,
+    placement_center_mqtt_services_client,
+    set_alarm,
+    true
+);
+
+impl_retriable_request!(
+    ListAlarmRequest,
+    MqttServiceClient<Channel>,
+    ListAlarmReply,
+    placement_center_mqtt_services_client,
+    list_alarm,
+    true
+);
+
+impl_retriable_request!(
+    DeleteAlarmRequest,
+    MqttServiceClient<Channel>,
+    DeleteAlarmReply,
+    placement_center_mqtt_services_client,
+    delete_alarm,
+    true
+);
@@ -15,7 +15,8 @@
 use common_base::error::common::CommonError;
 use protocol::placement_center::placement_center_openraft::{
     AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, SnapshotStatusReply,
+    SnapshotStatusRequest, TriggerSnapshotReply, TriggerSnapshotRequest, VoteReply, VoteRequest,
 };
 
 use crate::pool::ClientPool;
@@ -57,3 +58,15 @@ pub async fn $fn_name(
     ChangeMembershipReply,
     ChangeMembership
 );
+generate_openraft_service_call!(
+    placement_openraft_trigger_snapshot,
+    TriggerSnapshotRequest,
+    TriggerSnapshotReply,
+    TriggerSnapshot
+);
+generate_openraft_service_call!(
+    placement_openraft_snapshot_status,
+    SnapshotStatusRequest,
+    SnapshotStatusReply,
+    SnapshotStatus
+);
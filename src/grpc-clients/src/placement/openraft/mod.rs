@@ -17,7 +17,8 @@
 use protocol::placement_center::placement_center_openraft::open_raft_service_client::OpenRaftServiceClient;
 use protocol::placement_center::placement_center_openraft::{
     AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, SnapshotStatusReply,
+    SnapshotStatusRequest, TriggerSnapshotReply, TriggerSnapshotRequest, VoteReply, VoteRequest,
 };
 use tonic::transport::Channel;
 
@@ -101,3 +102,20 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     change_membership,
     true
 );
+
+// Snapshots are per node, so these are sent to the given address instead of the leader
+impl_retriable_request!(
+    TriggerSnapshotRequest,
+    OpenRaftServiceClient<Channel>,
+    TriggerSnapshotReply,
+    placement_center_openraft_services_client,
+    trigger_snapshot
+);
+
+impl_retriable_request!(
+    SnapshotStatusRequest,
+    OpenRaftServiceClient<Channel>,
+    SnapshotStatusReply,
+    placement_center_openraft_services_client,
+    snapshot_status
+);
@@ -20,7 +20,8 @@ mod tests {
         mqtt_broker_cluster_status, mqtt_broker_create_connector, mqtt_broker_create_schema,
         mqtt_broker_create_user, mqtt_broker_delete_connector, mqtt_broker_delete_schema,
         mqtt_broker_delete_user, mqtt_broker_list_connector, mqtt_broker_list_schema,
-        mqtt_broker_list_user, mqtt_broker_update_connector, mqtt_broker_update_schema,
+        mqtt_broker_list_schema_version, mqtt_broker_list_user, mqtt_broker_update_connector,
+        mqtt_broker_update_schema,
     };
     use grpc_clients::pool::ClientPool;
     use metadata_struct::mqtt::bridge::config_kafka::KafkaConnectorConfig;
@@ -33,7 +34,8 @@ mod tests {
         ClusterStatusRequest, CreateUserRequest, DeleteUserRequest, ListUserRequest,
         MqttConnectorType, MqttCreateConnectorRequest, MqttCreateSchemaRequest,
         MqttDeleteConnectorRequest, MqttDeleteSchemaRequest, MqttListConnectorRequest,
-        MqttListSchemaRequest, MqttUpdateConnectorRequest, MqttUpdateSchemaRequest,
+        MqttListSchemaRequest, MqttListSchemaVersionRequest, MqttUpdateConnectorRequest,
+        MqttUpdateSchemaRequest,
     };
 
     use crate::common::get_mqtt_broker_addr;
@@ -74,7 +76,7 @@ async fn user_test() {
             }
         }
 
-        match mqtt_broker_list_user(&client_pool, &addrs, ListUserRequest {}).await {
+        match mqtt_broker_list_user(&client_pool, &addrs, ListUserRequest::default()).await {
             Ok(data) => {
                 let mut flag = false;
                 for raw in data.users {
@@ -105,7 +107,7 @@ async fn user_test() {
             }
         }
 
-        match mqtt_broker_list_user(&client_pool, &addrs, ListUserRequest {}).await {
+        match mqtt_broker_list_user(&client_pool, &addrs, ListUserRequest::default()).await {
             Ok(data) => {
                 let mut flag = true;
                 for raw in data.users {
@@ -148,6 +150,7 @@ async fn schema_test() {
             schema_type: "json".to_string(),
             schema: schema_data.clone(),
             desc: "Old schema".to_string(),
+            compatibility: "none".to_string(),
         };
 
         match mqtt_broker_create_schema(&client_pool, &addrs, create_request).await {
@@ -194,6 +197,7 @@ async fn schema_test() {
             schema_type: "avro".to_string(),
             schema: schema_data.clone(),
             desc: "New schema".to_string(),
+            compatibility: "none".to_string(),
         };
 
         match mqtt_broker_update_schema(&client_pool, &addrs, update_request).await {
@@ -214,6 +218,7 @@ async fn schema_test() {
                 assert_eq!(schema.schema_type, SchemaType::AVRO);
                 assert_eq!(schema.schema, schema_data);
                 assert_eq!(schema.desc, "New schema".to_string());
+                assert_eq!(schema.version, 2);
             }
 
             Err(e) => {
@@ -221,6 +226,27 @@ async fn schema_test() {
             }
         }
 
+        // both versions are kept in the history
+        let list_version_request = MqttListSchemaVersionRequest {
+            schema_name: schema_name.clone(),
+            version: 0,
+        };
+
+        match mqtt_broker_list_schema_version(&client_pool, &addrs, list_version_request).await {
+            Ok(reply) => {
+                let versions: Vec<u64> = reply
+                    .schemas
+                    .iter()
+                    .map(|raw| serde_json::from_slice::<SchemaData>(raw).unwrap().version)
+                    .collect();
+                assert_eq!(versions, vec![1, 2]);
+            }
+
+            Err(e) => {
+                panic!("list schema version failed: {}", e);
+            }
+        }
+
         // delete schema
         let delete_request = MqttDeleteSchemaRequest {
             schema_name: schema_name.clone(),
@@ -257,6 +283,7 @@ async fn connector_test() {
             connector_type: MqttConnectorType::File as i32,
             config: serde_json::to_string(&LocalFileConnectorConfig {
                 local_file_path: "/tmp/test".to_string(),
+                ..Default::default()
             })
             .unwrap(),
             topic_id: "test-topic-1".to_string(),
@@ -293,6 +320,7 @@ async fn connector_test() {
             &connector.config,
             &serde_json::to_string(&LocalFileConnectorConfig {
                 local_file_path: "/tmp/test".to_string(),
+                ..Default::default()
             })
             .unwrap()
         );
@@ -304,6 +332,7 @@ async fn connector_test() {
             bootstrap_servers: "localhost:9092".to_string(),
             topic: "test-topic".to_string(),
             key: "test-key".to_string(),
+            ..Default::default()
         })
         .unwrap();
         connector.topic_id = "test-topic-2".to_string();
@@ -342,6 +371,7 @@ async fn connector_test() {
                 bootstrap_servers: "localhost:9092".to_string(),
                 topic: "test-topic".to_string(),
                 key: "test-key".to_string(),
+                ..Default::default()
             })
             .unwrap()
         );
@@ -17,11 +17,12 @@ mod tests {
     use std::sync::Arc;
 
     use grpc_clients::placement::kv::call::{
-        placement_delete, placement_exists, placement_get, placement_set,
+        placement_compare_and_swap, placement_delete, placement_exists, placement_get,
+        placement_lease_grant, placement_lease_revoke, placement_set,
     };
     use grpc_clients::pool::ClientPool;
     use protocol::placement_center::placement_center_kv::{
-        DeleteRequest, ExistsRequest, GetRequest, SetRequest,
+        DeleteRequest, ExistsRequest, GetRequest, LeaseGrantRequest, LeaseRevokeRequest, SetRequest,
     };
 
     use crate::common::get_placement_addr;
@@ -36,6 +37,7 @@ async fn kv_test() {
         let request = SetRequest {
             key: key.clone(),
             value: value.clone(),
+            lease_id: 0,
         };
         match placement_set(&client_pool, &addrs, request).await {
             Ok(_) => {}
@@ -47,6 +49,7 @@ async fn kv_test() {
         let request_key_empty = SetRequest {
             key: "".to_string(),
             value: value.clone(),
+            lease_id: 0,
         };
         let err = placement_set(&client_pool, &addrs, request_key_empty)
             .await
@@ -56,6 +59,7 @@ async fn kv_test() {
         let request_value_empty = SetRequest {
             key: key.clone(),
             value: "".to_string(),
+            lease_id: 0,
         };
         let err = placement_set(&client_pool, &addrs, request_value_empty)
             .await
@@ -100,4 +104,62 @@ async fn kv_test() {
             }
         }
     }
+
+    #[tokio::test]
+    async fn kv_cas_and_lease_test() {
+        let client_pool: Arc<ClientPool> = Arc::new(ClientPool::new(1));
+        let addrs = vec![get_placement_addr()];
+        let key = "test-cas-key".to_string();
+
+        let swapped =
+            placement_compare_and_swap(&client_pool, &addrs, key.clone(), None, "v1".to_string())
+                .await
+                .unwrap();
+        assert!(swapped);
+
+        let swapped =
+            placement_compare_and_swap(&client_pool, &addrs, key.clone(), None, "v2".to_string())
+                .await
+                .unwrap();
+        assert!(!swapped);
+
+        let swapped = placement_compare_and_swap(
+            &client_pool,
+            &addrs,
+            key.clone(),
+            Some("v1".to_string()),
+            "v2".to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(swapped);
+
+        let lease = placement_lease_grant(&client_pool, &addrs, LeaseGrantRequest { ttl: 30 })
+            .await
+            .unwrap();
+        let request = SetRequest {
+            key: key.clone(),
+            value: "v3".to_string(),
+            lease_id: lease.lease_id,
+        };
+        placement_set(&client_pool, &addrs, request).await.unwrap();
+
+        let reply = placement_get(&client_pool, &addrs, GetRequest { key: key.clone() })
+            .await
+            .unwrap();
+        assert_eq!(reply.lease_id, lease.lease_id);
+        assert_eq!(reply.version, 3);
+
+        let request = LeaseRevokeRequest {
+            lease_id: lease.lease_id,
+        };
+        placement_lease_revoke(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+
+        let reply = placement_exists(&client_pool, &addrs, ExistsRequest { key })
+            .await
+            .unwrap();
+        assert!(!reply.flag);
+    }
 }
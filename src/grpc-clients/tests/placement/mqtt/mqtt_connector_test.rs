@@ -62,6 +62,7 @@ async fn connector_test() {
             connector_type: ConnectorType::LocalFile,
             config: serde_json::to_string(&LocalFileConnectorConfig {
                 local_file_path: "/tmp/test".to_string(),
+                ..Default::default()
             })
             .unwrap(),
             topic_id: "test_topic-1".to_string(),
@@ -109,6 +110,7 @@ async fn connector_test() {
             bootstrap_servers: "localhost:9092".to_string(),
             topic: "test_topic".to_string(),
             key: "test_key".to_string(),
+            ..Default::default()
         })
         .unwrap();
         connector.topic_id = "test_topic-2".to_string();
@@ -20,7 +20,7 @@ mod test {
         placement::inner::call::{create_schema, delete_schema, list_schema, update_schema},
         pool::ClientPool,
     };
-    use metadata_struct::schema::{SchemaData, SchemaType};
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
     use protocol::placement_center::placement_center_inner::{
         CreateSchemaRequest, DeleteSchemaRequest, ListSchemaRequest, UpdateSchemaRequest,
     };
@@ -33,6 +33,7 @@ fn check_schema_equal(left: &SchemaData, right: &SchemaData) {
         assert_eq!(left.schema_type, right.schema_type);
         assert_eq!(left.schema, right.schema);
         assert_eq!(left.desc, right.desc);
+        assert_eq!(left.version, right.version);
     }
 
     #[tokio::test]
@@ -61,6 +62,8 @@ async fn schema_test() {
             }"#
             .to_string(),
             desc: "Old schema".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
         };
 
         let create_request = CreateSchemaRequest {
@@ -107,6 +110,7 @@ async fn schema_test() {
         }"#
         .to_string();
         schema_data.desc = "New schema".to_string();
+        schema_data.version = 2;
 
         let update_request = UpdateSchemaRequest {
             cluster_name: cluster_name.clone(),
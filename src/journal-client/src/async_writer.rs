@@ -339,6 +339,7 @@ fn build_send_data(
                     key: raw.key.to_owned(),
                     value: raw.content.to_owned(),
                     tags: raw.tags.to_owned(),
+                    headers: raw.headers.to_owned(),
                 });
                 if let Some(mut data_mut) = data_pkgs.get_mut(&msg.sender_pkg_id) {
                     data_mut.push(pkid);
@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
 use common_base::utils::crc::calc_crc32;
 use metadata_struct::adapter::read_config::ReadConfig;
-use metadata_struct::adapter::record::Record;
+use metadata_struct::adapter::record::{Header, Record};
 use metadata_struct::journal::shard::JournalShard;
 use protocol::journal_server::journal_engine::{
     CreateShardReqBody, DeleteShardReqBody, GetClusterMetadataNode, GetShardMetadataRespShard,
@@ -41,6 +42,7 @@ pub struct JournalClientWriteData {
     pub key: String,
     pub content: Vec<u8>,
     pub tags: Vec<String>,
+    pub headers: HashMap<String, String>,
 }
 
 #[derive(Clone)]
@@ -191,7 +193,11 @@ pub async fn read_by_offset(
                 key: raw.key,
                 data: raw.value.clone(),
                 tags: raw.tags,
-                header: Vec::new(),
+                header: raw
+                    .headers
+                    .into_iter()
+                    .map(|(name, value)| Header { name, value })
+                    .collect(),
                 timestamp: raw.timestamp,
                 delay_timestamp: 0,
                 crc_num: calc_crc32(&raw.value),
@@ -248,7 +254,11 @@ pub async fn read_by_key(
                 key: raw.key,
                 data: raw.value.clone(),
                 tags: raw.tags,
-                header: Vec::new(),
+                header: raw
+                    .headers
+                    .into_iter()
+                    .map(|(name, value)| Header { name, value })
+                    .collect(),
                 timestamp: raw.timestamp,
                 delay_timestamp: 0,
                 crc_num: calc_crc32(&raw.value),
@@ -287,7 +297,11 @@ pub async fn read_by_tag(
                 key: raw.key,
                 data: raw.value.clone(),
                 tags: raw.tags,
-                header: Vec::new(),
+                header: raw
+                    .headers
+                    .into_iter()
+                    .map(|(name, value)| Header { name, value })
+                    .collect(),
                 timestamp: raw.timestamp,
                 delay_timestamp: 0,
                 crc_num: calc_crc32(&raw.value),
@@ -103,7 +103,9 @@ async fn report_report0(client_pool: Arc<ClientPool>) {
         }
         Err(e) => {
             if e.to_string().contains("Node") && e.to_string().contains("does not exist") {
-                if let Err(e) = register_journal_node(client_pool.clone(), config.clone()).await {
+                if let Err(e) =
+                    register_journal_node(client_pool.clone(), config.as_ref().clone()).await
+                {
                     error!("{}", e);
                 }
             } else {
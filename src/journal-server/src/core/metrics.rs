@@ -0,0 +1,59 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::metrics::registry::latency_ms_buckets;
+use prometheus_client::encoding::EncodeLabelSet;
+
+use crate::segment::SegmentIdentity;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+pub struct ShardLabel {
+    pub namespace: String,
+    pub shard_name: String,
+}
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+pub struct SegmentLabel {
+    pub segment: String,
+}
+
+common_base::register_histogram_metric!(
+    SEGMENT_WRITE_LATENCY_MS,
+    "segment_write_latency_ms",
+    "Latency of writing a batch of records to a segment file in milliseconds",
+    ShardLabel,
+    latency_ms_buckets()
+);
+
+common_base::register_gauge_metric!(
+    INDEX_BUILD_LAG,
+    "index_build_lag",
+    "Number of records written to a segment but not indexed yet",
+    SegmentLabel
+);
+
+pub fn metrics_segment_write_ms(segment_iden: &SegmentIdentity, ms: f64) {
+    let label = ShardLabel {
+        namespace: segment_iden.namespace.clone(),
+        shard_name: segment_iden.shard_name.clone(),
+    };
+    common_base::histogram_metric_observe!(SEGMENT_WRITE_LATENCY_MS, label, ms)
+}
+
+pub fn metrics_index_build_lag(segment_iden: &SegmentIdentity, lag: u64) {
+    let label = SegmentLabel {
+        segment: segment_iden.name(),
+    };
+    common_base::gauge_metric_set!(INDEX_BUILD_LAG, label, lag as i64)
+}
@@ -17,6 +17,7 @@
 pub mod cluster_config;
 pub mod consts;
 pub mod error;
+pub mod metrics;
 pub mod notification;
 pub mod segment;
 pub mod segment_meta;
@@ -33,6 +33,7 @@
 use crate::core::cache::CacheManager;
 use crate::core::consts::{BUILD_INDE_PER_RECORD_NUM, DB_COLUMN_FAMILY_INDEX};
 use crate::core::error::JournalServerError;
+use crate::core::metrics::metrics_index_build_lag;
 use crate::index::IndexData;
 use crate::segment::file::{open_segment_write, ReadData};
 use crate::segment::manager::SegmentFileManager;
@@ -86,6 +87,7 @@ pub async fn try_trigger_build_index(
 
     start_segment_build_index_thread(
         cache_manager.clone(),
+        segment_file_manager.clone(),
         rocksdb_engine_handler.clone(),
         segment_iden.clone(),
         segment_file_meta.start_offset as u64,
@@ -104,6 +106,7 @@ pub async fn try_trigger_build_index(
 
 async fn start_segment_build_index_thread(
     cache_manager: Arc<CacheManager>,
+    segment_file_manager: Arc<SegmentFileManager>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
     segment_iden: SegmentIdentity,
     start_offset: u64,
@@ -179,6 +182,11 @@ async fn start_segment_build_index_thread(
                                 continue;
                             }
 
+                            if let Some(segment_file) = segment_file_manager.get_segment_file(&segment_iden) {
+                                let end_offset = segment_file.end_offset.max(0) as u64;
+                                metrics_index_build_lag(&segment_iden, end_offset.saturating_sub(last_build_offset));
+                            }
+
 
                         }
                         Err(e) => {
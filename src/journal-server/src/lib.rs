@@ -22,9 +22,13 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use common_base::config::journal_server::{journal_server_conf, JournalServerConfig};
-use common_base::metrics::register_prometheus_export;
+use common_base::config::journal_server::{
+    journal_server_conf, journal_server_conf_path, reload_journal_server_conf, JournalServerConfig,
+};
+use common_base::config::reload::start_config_reload_watcher;
+use common_base::metrics::start_prometheus_export;
 use common_base::runtime::create_runtime;
+use common_base::telemetry::trace::{init_tracer_provider, stop_tracer_provider};
 use grpc_clients::pool::ClientPool;
 use index::engine::{column_family_list, storage_data_fold};
 use log::{error, info};
@@ -62,7 +66,7 @@ pub struct JournalServer {
 
 impl JournalServer {
     pub fn new(stop_send: broadcast::Sender<bool>) -> Self {
-        let config = journal_server_conf().clone();
+        let config = journal_server_conf().as_ref().clone();
         let server_runtime = create_runtime(
             "storage-engine-server-runtime",
             config.system.runtime_work_threads,
@@ -101,6 +105,8 @@ pub fn start(&self) {
 
         self.start_prometheus();
 
+        self.start_tracer_provider();
+
         self.init_node();
 
         self.start_daemon_thread();
@@ -146,13 +152,20 @@ fn start_tcp_server(&self) {
 
     fn start_prometheus(&self) {
         if self.config.prometheus.enable {
-            let prometheus_port = self.config.prometheus.port;
+            let prometheus = self.config.prometheus.clone();
             self.server_runtime.spawn(async move {
-                register_prometheus_export(prometheus_port).await;
+                start_prometheus_export(prometheus, "journal-server").await;
             });
         }
     }
 
+    fn start_tracer_provider(&self) {
+        let telemetry = self.config.telemetry.clone();
+        self.server_runtime.spawn(async move {
+            init_tracer_provider(&telemetry, "journal-server").await;
+        });
+    }
+
     fn start_daemon_thread(&self) {
         let client_pool = self.client_pool.clone();
         let stop_sx = self.stop_send.clone();
@@ -164,6 +177,13 @@ fn start_daemon_thread(&self) {
         self.daemon_runtime
             .spawn(async move { report_monitor(client_pool, stop_sx).await });
 
+        if let Some(config_path) = journal_server_conf_path() {
+            let stop_sx = self.stop_send.clone();
+            self.daemon_runtime.spawn(async move {
+                start_config_reload_watcher(config_path, reload_journal_server_conf, stop_sx).await;
+            });
+        }
+
         let segment_scroll = SegmentScrollManager::new(
             self.cache_manager.clone(),
             self.client_pool.clone(),
@@ -184,6 +204,7 @@ fn waiting_stop(&self) {
                         "When ctrl + c is received, the service starts to stop"
                     );
                     self.stop_server().await;
+                    stop_tracer_provider().await;
                     break;
                 }
             }
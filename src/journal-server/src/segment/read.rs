@@ -134,6 +134,7 @@ pub async fn read_data_req(
                 value: record.content,
                 tags: record.tags,
                 timestamp: record.create_time,
+                headers: record.headers,
             });
         }
         shard_message.messages = record_message;
@@ -14,16 +14,19 @@
 
 use crate::core::cache::CacheManager;
 use crate::core::error::{get_journal_server_code, JournalServerError};
+use crate::core::metrics::metrics_segment_write_ms;
 use crate::core::segment_meta::{update_meta_end_timestamp, update_meta_start_timestamp};
 use crate::core::segment_status::sealup_segment;
 use crate::index::build::try_trigger_build_index;
 use crate::segment::file::{open_segment_write, SegmentFile};
 use crate::segment::manager::SegmentFileManager;
 use crate::segment::SegmentIdentity;
+use common_base::telemetry::trace::{extract_context, start_span};
 use common_base::tools::now_second;
 use grpc_clients::pool::ClientPool;
 use log::{error, warn};
 use metadata_struct::journal::segment::SegmentStatus;
+use opentelemetry::trace::{SpanKind, TraceContextExt};
 use protocol::journal_server::journal_engine::{
     WriteReqBody, WriteRespMessage, WriteRespMessageStatus,
 };
@@ -31,7 +34,7 @@
 use rocksdb_engine::RocksDBEngine;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::select;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{broadcast, oneshot};
@@ -82,7 +85,18 @@ pub async fn write_data_req(
         );
 
         let mut record_list = Vec::new();
+        let mut trace_list = Vec::new();
         for message in shard_data.messages.iter() {
+            if let Some(cx) = start_span(
+                "robustmq/journal",
+                "journal/write",
+                SpanKind::Server,
+                &extract_context(&message.headers),
+                "",
+            ) {
+                trace_list.push(cx);
+            }
+
             // todo data validator
             let record = JournalRecord {
                 content: message.value.clone(),
@@ -95,19 +109,24 @@ pub async fn write_data_req(
                 pkid: message.pkid,
                 producer_id: "".to_string(),
                 offset: -1,
+                headers: message.headers.clone(),
             };
             record_list.push(record);
         }
 
-        let resp = match write_data(
+        let write_result = write_data(
             cache_manager,
             rocksdb_engine_handler,
             segment_file_manager,
             &segment_iden,
             record_list.clone(),
         )
-        .await
-        {
+        .await;
+        for cx in trace_list {
+            cx.span().end();
+        }
+
+        let resp = match write_result {
             Ok(resp) => resp,
             Err(e) => {
                 // if this write filled up the segment, we need to seal up the segment and update end timestamp
@@ -416,7 +435,10 @@ async fn batch_write0(
     }
 
     // batch write data
-    match segment_write.write(&records).await {
+    let start = Instant::now();
+    let result = segment_write.write(&records).await;
+    metrics_segment_write_ms(segment_iden, start.elapsed().as_secs_f64() * 1000.0);
+    match result {
         Ok(_) => {
             let record = records.last().unwrap();
             segment_file_manager.update_end_offset(segment_iden, record.offset)?;
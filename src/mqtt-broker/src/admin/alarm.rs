@@ -0,0 +1,63 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttClearAlarmReply, MqttClearAlarmRequest, MqttListAlarmReply, MqttListAlarmRequest,
+};
+use tonic::{Request, Response, Status};
+
+use crate::storage::alarm::AlarmStorage;
+
+pub async fn list_alarm_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttListAlarmRequest>,
+) -> Result<Response<MqttListAlarmReply>, Status> {
+    let req = request.into_inner();
+    let storage = AlarmStorage::new(client_pool.clone());
+    let mut list = storage.list_alarm().await?;
+    list.retain(|alarm| !req.active_only || alarm.is_active());
+    list.sort_by(|a, b| b.activate_at.cmp(&a.activate_at));
+    let alarms = list.iter().map(|alarm| alarm.encode()).collect();
+    Ok(Response::new(MqttListAlarmReply { alarms }))
+}
+
+// Only deactivated alarms can be cleared, an empty alarm_id clears all of them.
+pub async fn clear_alarm_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttClearAlarmRequest>,
+) -> Result<Response<MqttClearAlarmReply>, Status> {
+    let req = request.into_inner();
+    let storage = AlarmStorage::new(client_pool.clone());
+    let mut cleared = 0;
+    for alarm in storage.list_alarm().await? {
+        if !req.alarm_id.is_empty() && alarm.alarm_id != req.alarm_id {
+            continue;
+        }
+        if alarm.is_active() {
+            if !req.alarm_id.is_empty() {
+                return Err(Status::cancelled(format!(
+                    "Alarm {} is still active and cannot be cleared",
+                    req.alarm_id
+                )));
+            }
+            continue;
+        }
+        storage.delete_alarm(&alarm.alarm_id).await?;
+        cleared += 1;
+    }
+    Ok(Response::new(MqttClearAlarmReply { cleared }))
+}
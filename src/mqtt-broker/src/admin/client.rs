@@ -0,0 +1,397 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use axum::extract::ws::Message;
+use bytes::BytesMut;
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::mqtt::inner::call::{broker_mqtt_get_client_detail, broker_mqtt_kick_client};
+use grpc_clients::placement::mqtt::call::{placement_delete_subscribe, placement_list_subscribe};
+use grpc_clients::pool::ClientPool;
+use log::{info, warn};
+use metadata_struct::mqtt::session::MqttSession;
+use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttCleanSessionReply, MqttCleanSessionRequest, MqttInspectClientReply,
+    MqttInspectClientRequest, MqttKickClientReply, MqttKickClientRequest, MqttListSessionReply,
+    MqttListSessionRequest, MqttListSubscribeReply, MqttListSubscribeRequest,
+};
+use protocol::broker_mqtt::broker_mqtt_inner::{GetClientDetailRequest, KickClientRequest};
+use protocol::mqtt::codec::{MqttCodec, MqttPacketWrapper};
+use protocol::mqtt::common::DisconnectReasonCode;
+use protocol::placement_center::placement_center_mqtt::{
+    DeleteSubscribeRequest, ListSubscribeRequest,
+};
+use serde::{Deserialize, Serialize};
+use tonic::{Request, Response, Status};
+
+use crate::handler::cache::CacheManager;
+use crate::handler::connection::disconnect_connection;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::response::response_packet_mqtt_distinct_by_reason;
+use crate::handler::tenant::is_tenant_client_id;
+use crate::server::connection_manager::ConnectionManager;
+use crate::storage::cluster::ClusterStorage;
+use crate::storage::session::SessionStorage;
+use crate::subscribe::subscribe_manager::SubscribeManager;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ClientDetail {
+    pub client_id: String,
+    pub online: bool,
+    pub broker_id: Option<u64>,
+    pub connect_id: Option<u64>,
+    pub protocol: String,
+    pub connection_type: String,
+    pub source_addr: String,
+    pub login_user: String,
+    pub keep_alive: u16,
+    pub connected_at: u64,
+    // QoS 1/2 messages still waiting for an acknowledgement, per direction.
+    pub inflight_recv: isize,
+    pub inflight_send: isize,
+    pub session: Option<MqttSession>,
+    pub subscriptions: Vec<String>,
+}
+
+pub async fn list_session_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttListSessionRequest>,
+) -> Result<Response<MqttListSessionReply>, Status> {
+    let req = request.into_inner();
+    let session_storage = SessionStorage::new(client_pool.clone());
+    let mut sessions = Vec::new();
+    for (client_id, session) in session_storage.list_session().await? {
+        if !req.tenant.is_empty() && !is_tenant_client_id(&req.tenant, &client_id) {
+            continue;
+        }
+        sessions.push(serde_json::to_vec(&session).map_err(|e| Status::internal(e.to_string()))?);
+    }
+    Ok(Response::new(MqttListSessionReply { sessions }))
+}
+
+pub async fn list_subscribe_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttListSubscribeRequest>,
+) -> Result<Response<MqttListSubscribeReply>, Status> {
+    let req = request.into_inner();
+    let subscribes = list_cluster_subscribe(client_pool, &req.client_id)
+        .await?
+        .iter()
+        .map(|sub| sub.encode())
+        .collect();
+    Ok(Response::new(MqttListSubscribeReply { subscribes }))
+}
+
+pub async fn inspect_client_by_req(
+    client_pool: &Arc<ClientPool>,
+    cache_manager: &Arc<CacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    request: Request<MqttInspectClientRequest>,
+) -> Result<Response<MqttInspectClientReply>, Status> {
+    let req = request.into_inner();
+    let session = get_cluster_session(client_pool, &req.client_id).await?;
+
+    let detail = match owner_broker_id(&session) {
+        Some(broker_id) if broker_id == broker_mqtt_conf().broker_id => {
+            serialize_detail(&local_client_detail(
+                cache_manager,
+                connection_manager,
+                subscribe_manager,
+                &req.client_id,
+            ))?
+        }
+        Some(broker_id) => {
+            let addr = broker_inner_addr(client_pool, broker_id).await?;
+            let request = GetClientDetailRequest {
+                cluster_name: broker_mqtt_conf().cluster_name.clone(),
+                client_id: req.client_id.clone(),
+            };
+            broker_mqtt_get_client_detail(client_pool, &[addr], request)
+                .await?
+                .detail
+        }
+        None => {
+            let subscriptions = list_cluster_subscribe(client_pool, &req.client_id)
+                .await?
+                .into_iter()
+                .map(|sub| sub.path)
+                .collect();
+            serialize_detail(&ClientDetail {
+                client_id: req.client_id.clone(),
+                session: Some(session),
+                subscriptions,
+                ..Default::default()
+            })?
+        }
+    };
+    Ok(Response::new(MqttInspectClientReply { detail }))
+}
+
+pub async fn kick_client_by_req(
+    client_pool: &Arc<ClientPool>,
+    cache_manager: &Arc<CacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    request: Request<MqttKickClientRequest>,
+) -> Result<Response<MqttKickClientReply>, Status> {
+    let req = request.into_inner();
+    let session = get_cluster_session(client_pool, &req.client_id).await?;
+
+    match owner_broker_id(&session) {
+        Some(broker_id) if broker_id == broker_mqtt_conf().broker_id => {
+            kick_local_client(
+                cache_manager,
+                client_pool,
+                connection_manager,
+                subscribe_manager,
+                &req.client_id,
+            )
+            .await?;
+        }
+        Some(broker_id) => {
+            let addr = broker_inner_addr(client_pool, broker_id).await?;
+            let request = KickClientRequest {
+                cluster_name: broker_mqtt_conf().cluster_name.clone(),
+                client_id: req.client_id.clone(),
+            };
+            broker_mqtt_kick_client(client_pool, &[addr], request).await?;
+        }
+        None => return Err(MqttBrokerError::ClientNotOnline(req.client_id).into()),
+    }
+    Ok(Response::new(MqttKickClientReply::default()))
+}
+
+// Sessions held by a live broker cannot be cleaned, otherwise it would keep
+// serving a client whose session no longer exists. A session whose owner has
+// left the cluster is stale and can be removed.
+pub async fn clean_session_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttCleanSessionRequest>,
+) -> Result<Response<MqttCleanSessionReply>, Status> {
+    let req = request.into_inner();
+    let session = get_cluster_session(client_pool, &req.client_id).await?;
+    if let Some(broker_id) = owner_broker_id(&session) {
+        if broker_is_alive(client_pool, broker_id).await? {
+            return Err(MqttBrokerError::ClientIsOnline(req.client_id).into());
+        }
+    }
+
+    let conf = broker_mqtt_conf();
+    let request = DeleteSubscribeRequest {
+        cluster_name: conf.cluster_name.clone(),
+        client_id: req.client_id.clone(),
+        path: "".to_string(),
+    };
+    placement_delete_subscribe(client_pool, &conf.placement_center, request).await?;
+
+    let session_storage = SessionStorage::new(client_pool.clone());
+    session_storage
+        .delete_session(req.client_id.clone())
+        .await?;
+    info!("Session of client {} was cleaned by admin", req.client_id);
+    Ok(Response::new(MqttCleanSessionReply::default()))
+}
+
+pub fn local_client_detail(
+    cache_manager: &Arc<CacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    client_id: &str,
+) -> ClientDetail {
+    let session = cache_manager.get_session_info(client_id);
+    let mut detail = ClientDetail {
+        client_id: client_id.to_string(),
+        broker_id: Some(broker_mqtt_conf().broker_id),
+        subscriptions: subscribe_manager
+            .subscribe_list
+            .iter()
+            .filter(|entry| entry.value().client_id == client_id)
+            .map(|entry| entry.value().path.clone())
+            .collect(),
+        ..Default::default()
+    };
+
+    let connect_id = session.as_ref().and_then(|session| session.connection_id);
+    detail.session = session;
+    let Some(connect_id) = connect_id else {
+        return detail;
+    };
+
+    detail.connect_id = Some(connect_id);
+    if let Some(connection) = cache_manager.get_connection(connect_id) {
+        detail.online = true;
+        detail.login_user = connection.login_user.clone();
+        detail.keep_alive = connection.keep_alive;
+        detail.connected_at = connection.create_time;
+        detail.inflight_recv = connection.get_recv_qos_message();
+        detail.inflight_send = connection.get_send_qos_message();
+    }
+    if let Some(network) = connection_manager.get_connect(connect_id) {
+        detail.connection_type = network.connection_type.to_string();
+        detail.source_addr = network.addr.to_string();
+        if let Some(protocol) = network.protocol {
+            detail.protocol = protocol.into();
+        }
+    }
+    detail
+}
+
+pub async fn kick_local_client(
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+    connection_manager: &Arc<ConnectionManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    client_id: &str,
+) -> Result<(), MqttBrokerError> {
+    let Some(connect_id) = cache_manager
+        .get_session_info(client_id)
+        .and_then(|session| session.connection_id)
+    else {
+        return Err(MqttBrokerError::ClientNotOnline(client_id.to_string()));
+    };
+
+    // Only MQTT 5 has a server-sent DISCONNECT, older clients just see the
+    // socket close.
+    if let Some(network) = connection_manager.get_connect(connect_id) {
+        if let Some(protocol) = network.protocol.clone().filter(|p| p.is_mqtt5()) {
+            let wrap = MqttPacketWrapper {
+                protocol_version: protocol.clone().into(),
+                packet: response_packet_mqtt_distinct_by_reason(
+                    &protocol,
+                    Some(DisconnectReasonCode::AdministrativeAction),
+                ),
+            };
+            let result = if network.is_tcp() {
+                connection_manager.write_tcp_frame(connect_id, wrap).await
+            } else {
+                let mut codec = MqttCodec::new(Some(protocol.into()));
+                let mut buff = BytesMut::new();
+                match codec.encode_data(wrap.clone(), &mut buff) {
+                    Ok(()) => {
+                        connection_manager
+                            .write_websocket_frame(connect_id, wrap, Message::Binary(buff.to_vec()))
+                            .await
+                    }
+                    Err(e) => Err(MqttBrokerError::CommonError(e.to_string())),
+                }
+            };
+            // The connection is closed below whether or not the DISCONNECT got through.
+            if let Err(e) = result {
+                warn!("Failed to send DISCONNECT to client {}: {}", client_id, e);
+            }
+        }
+    }
+
+    disconnect_connection(
+        client_id,
+        connect_id,
+        cache_manager,
+        client_pool,
+        connection_manager,
+        subscribe_manager,
+        false,
+    )
+    .await?;
+    info!("Client {} was disconnected by admin", client_id);
+    Ok(())
+}
+
+fn owner_broker_id(session: &MqttSession) -> Option<u64> {
+    session.connection_id?;
+    session.broker_id
+}
+
+async fn get_cluster_session(
+    client_pool: &Arc<ClientPool>,
+    client_id: &str,
+) -> Result<MqttSession, Status> {
+    if client_id.is_empty() {
+        return Err(Status::invalid_argument("client_id cannot be empty"));
+    }
+    let session_storage = SessionStorage::new(client_pool.clone());
+    match session_storage.get_session(client_id.to_string()).await? {
+        Some(session) => Ok(session),
+        None => Err(Status::not_found(
+            MqttBrokerError::SessionDoesNotExist.to_string(),
+        )),
+    }
+}
+
+async fn list_cluster_subscribe(
+    client_pool: &Arc<ClientPool>,
+    client_id: &str,
+) -> Result<Vec<MqttSubscribe>, Status> {
+    let conf = broker_mqtt_conf();
+    let request = ListSubscribeRequest {
+        cluster_name: conf.cluster_name.clone(),
+    };
+    let reply = placement_list_subscribe(client_pool, &conf.placement_center, request).await?;
+    Ok(reply
+        .subscribes
+        .iter()
+        .filter_map(|raw| serde_json::from_slice::<MqttSubscribe>(raw).ok())
+        .filter(|sub| client_id.is_empty() || sub.client_id == client_id)
+        .collect())
+}
+
+async fn broker_inner_addr(
+    client_pool: &Arc<ClientPool>,
+    broker_id: u64,
+) -> Result<String, Status> {
+    let cluster_storage = ClusterStorage::new(client_pool.clone());
+    cluster_storage
+        .node_list()
+        .await?
+        .into_iter()
+        .find(|node| node.node_id == broker_id)
+        .map(|node| node.node_inner_addr)
+        .ok_or_else(|| MqttBrokerError::BrokerNodeNotFound(broker_id).into())
+}
+
+async fn broker_is_alive(client_pool: &Arc<ClientPool>, broker_id: u64) -> Result<bool, Status> {
+    let cluster_storage = ClusterStorage::new(client_pool.clone());
+    Ok(cluster_storage
+        .node_list()
+        .await?
+        .iter()
+        .any(|node| node.node_id == broker_id))
+}
+
+fn serialize_detail(detail: &ClientDetail) -> Result<Vec<u8>, Status> {
+    serde_json::to_vec(detail).map_err(|e| Status::internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use metadata_struct::mqtt::session::MqttSession;
+
+    use super::owner_broker_id;
+
+    #[test]
+    fn owner_broker_id_test() {
+        let mut session = MqttSession::new("c1".to_string(), 60, false, None);
+        assert_eq!(owner_broker_id(&session), None);
+
+        session.update_broker_id(Some(2));
+        assert_eq!(owner_broker_id(&session), None);
+
+        session.update_connnction_id(Some(10));
+        assert_eq!(owner_broker_id(&session), Some(2));
+
+        session.update_broker_id(None);
+        assert_eq!(owner_broker_id(&session), None);
+    }
+}
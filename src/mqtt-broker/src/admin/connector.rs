@@ -12,23 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::bridge::file::render_rotated_file_name;
+use crate::bridge::rdb::template::InsertTemplate;
 use crate::handler::error::MqttBrokerError;
 use crate::storage::connector::ConnectorStorage;
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use common_base::tools::now_second;
 use grpc_clients::placement::mqtt::call::placement_list_connector;
 use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::bridge::config_influxdb::{InfluxDbConnectorConfig, InfluxDbVersion};
 use metadata_struct::mqtt::bridge::config_kafka::KafkaConnectorConfig;
 use metadata_struct::mqtt::bridge::config_local_file::LocalFileConnectorConfig;
+use metadata_struct::mqtt::bridge::config_mqtt::{MqttBridgeConnectorConfig, MqttBridgeDirection};
+use metadata_struct::mqtt::bridge::config_rdb::RdbConnectorConfig;
+use metadata_struct::mqtt::bridge::config_redis::RedisConnectorConfig;
+use metadata_struct::mqtt::bridge::config_webhook::WebHookConnectorConfig;
 use metadata_struct::mqtt::bridge::connector::MQTTConnector;
 use metadata_struct::mqtt::bridge::connector_type::ConnectorType;
-use metadata_struct::mqtt::bridge::status::MQTTStatus;
+use metadata_struct::mqtt::bridge::status::{MQTTConnectorStats, MQTTStatus};
 use protocol::broker_mqtt::broker_mqtt_admin::{
     MqttConnectorType, MqttCreateConnectorReply, MqttCreateConnectorRequest,
     MqttDeleteConnectorReply, MqttDeleteConnectorRequest, MqttListConnectorReply,
     MqttListConnectorRequest, MqttUpdateConnectorReply, MqttUpdateConnectorRequest,
 };
 use protocol::placement_center::placement_center_mqtt::ListConnectorRequest;
+use std::path::Path;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
@@ -71,6 +79,7 @@ pub async fn create_connector_by_req(
         broker_id: None,
         create_time: now_second(),
         update_time: now_second(),
+        stats: MQTTConnectorStats::default(),
     };
     if let Err(e) = storage.create_connector(connector).await {
         return Err(Status::cancelled(e.to_string()));
@@ -118,10 +127,131 @@ fn connector_config_validator(
 ) -> Result<(), MqttBrokerError> {
     match connector_type {
         ConnectorType::LocalFile => {
-            let _file_config: LocalFileConnectorConfig = serde_json::from_str(config)?;
+            let file_config: LocalFileConnectorConfig = serde_json::from_str(config)?;
+            let path = Path::new(&file_config.local_file_path);
+            let Some(active_file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid local file path {}",
+                    file_config.local_file_path
+                )));
+            };
+            let rotated_file_name =
+                render_rotated_file_name(&file_config.rotated_file_template, active_file_name);
+            if rotated_file_name.is_empty()
+                || rotated_file_name.contains('/')
+                || rotated_file_name == active_file_name
+            {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid rotated file template {}",
+                    file_config.rotated_file_template
+                )));
+            }
         }
         ConnectorType::Kafka => {
-            let _kafka_config: KafkaConnectorConfig = serde_json::from_str(config)?;
+            let kafka_config: KafkaConnectorConfig = serde_json::from_str(config)?;
+            if kafka_config.bootstrap_servers.is_empty() || kafka_config.topic.is_empty() {
+                return Err(MqttBrokerError::CommonError(
+                    "kafka connector needs bootstrap_servers and a topic".to_string(),
+                ));
+            }
+            if !["0", "1", "all", "-1"].contains(&kafka_config.acks.as_str()) {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid kafka acks {}",
+                    kafka_config.acks
+                )));
+            }
+            if !["none", "gzip", "snappy", "lz4", "zstd"]
+                .contains(&kafka_config.compression.as_str())
+            {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid kafka compression {}",
+                    kafka_config.compression
+                )));
+            }
+        }
+        ConnectorType::Mqtt => {
+            let mqtt_config: MqttBridgeConnectorConfig = serde_json::from_str(config)?;
+            if mqtt_config.server.is_empty() || mqtt_config.client_id.is_empty() {
+                return Err(MqttBrokerError::CommonError(
+                    "mqtt bridge needs a server and a client_id".to_string(),
+                ));
+            }
+            if mqtt_config.max_qos > 2 {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid mqtt bridge max_qos {}",
+                    mqtt_config.max_qos
+                )));
+            }
+            if mqtt_config.direction != MqttBridgeDirection::Egress
+                && mqtt_config.subscribe_topics.is_empty()
+            {
+                return Err(MqttBrokerError::CommonError(
+                    "mqtt bridge ingress needs at least one subscribe topic".to_string(),
+                ));
+            }
+        }
+        ConnectorType::WebHook => {
+            let webhook_config: WebHookConnectorConfig = serde_json::from_str(config)?;
+            if !webhook_config.url.starts_with("http://")
+                && !webhook_config.url.starts_with("https://")
+            {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid webhook url {}",
+                    webhook_config.url
+                )));
+            }
+            if !["POST", "PUT", "PATCH"].contains(&webhook_config.method.to_uppercase().as_str()) {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "invalid webhook method {}",
+                    webhook_config.method
+                )));
+            }
+        }
+        ConnectorType::Rdb => {
+            let rdb_config: RdbConnectorConfig = serde_json::from_str(config)?;
+            let template = InsertTemplate::parse(&rdb_config.sql_template)?;
+            if !rdb_config.upsert_keys.is_empty() && template.has_suffix() {
+                return Err(MqttBrokerError::RdbTemplateError(
+                    "upsert_keys cannot be combined with a custom conflict clause".to_string(),
+                ));
+            }
+            for key in rdb_config.upsert_keys.iter() {
+                if !template.columns().contains(key) {
+                    return Err(MqttBrokerError::RdbTemplateError(format!(
+                        "upsert key {} is not an inserted column",
+                        key
+                    )));
+                }
+            }
+        }
+        ConnectorType::Redis => {
+            let redis_config: RedisConnectorConfig = serde_json::from_str(config)?;
+            if redis_config.url.is_empty() || redis_config.key.is_empty() {
+                return Err(MqttBrokerError::CommonError(
+                    "redis connector needs a url and a key".to_string(),
+                ));
+            }
+        }
+        ConnectorType::InfluxDb => {
+            let influxdb_config: InfluxDbConnectorConfig = serde_json::from_str(config)?;
+            if influxdb_config.url.is_empty()
+                || influxdb_config.measurement.is_empty()
+                || influxdb_config.fields.is_empty()
+            {
+                return Err(MqttBrokerError::CommonError(
+                    "influxdb connector needs a url, a measurement and at least one field"
+                        .to_string(),
+                ));
+            }
+            let target_missing = match influxdb_config.version {
+                InfluxDbVersion::V1 => influxdb_config.database.is_empty(),
+                InfluxDbVersion::V2 => influxdb_config.bucket.is_empty(),
+            };
+            if target_missing {
+                return Err(MqttBrokerError::CommonError(
+                    "influxdb v1 needs a database and v2 needs a bucket".to_string(),
+                ));
+            }
         }
     }
     Ok(())
@@ -131,5 +261,10 @@ fn parse_mqtt_connector_type(connector_type: MqttConnectorType) -> ConnectorType
     match connector_type {
         MqttConnectorType::File => ConnectorType::LocalFile,
         MqttConnectorType::Kafka => ConnectorType::Kafka,
+        MqttConnectorType::Mqtt => ConnectorType::Mqtt,
+        MqttConnectorType::WebHook => ConnectorType::WebHook,
+        MqttConnectorType::Rdb => ConnectorType::Rdb,
+        MqttConnectorType::Redis => ConnectorType::Redis,
+        MqttConnectorType::InfluxDb => ConnectorType::InfluxDb,
     }
 }
@@ -0,0 +1,103 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use delay_message::{DelayMessageManager, DelayMessageRecord};
+use metadata_struct::mqtt::message::MqttMessage;
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttCancelDelayMessageReply, MqttCancelDelayMessageRequest, MqttDelayMessageRaw,
+    MqttInspectDelayMessageReply, MqttInspectDelayMessageRequest, MqttListDelayMessageReply,
+    MqttListDelayMessageRequest,
+};
+use storage_adapter::storage::StorageAdapter;
+use tonic::{Request, Response, Status};
+
+pub async fn list_delay_message_by_req<S>(
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
+    request: Request<MqttListDelayMessageRequest>,
+) -> Result<Response<MqttListDelayMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    let delay_messages = delay_message_manager
+        .list_delay_messages()
+        .iter()
+        .filter(|raw| req.target_topic.is_empty() || raw.target_topic_name == req.target_topic)
+        .map(encode_delay_message)
+        .collect();
+    Ok(Response::new(MqttListDelayMessageReply { delay_messages }))
+}
+
+pub async fn inspect_delay_message_by_req<S>(
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
+    request: Request<MqttInspectDelayMessageRequest>,
+) -> Result<Response<MqttInspectDelayMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    let Some(delay_message) = delay_message_manager.get_delay_message(&req.delay_id) else {
+        return Err(Status::cancelled(format!(
+            "Delay message {} does not exist",
+            req.delay_id
+        )));
+    };
+
+    let mut reply = MqttInspectDelayMessageReply {
+        delay_message: Some(encode_delay_message(&delay_message)),
+        ..Default::default()
+    };
+    let record = delay_message_manager
+        .read_delay_message(&delay_message)
+        .await
+        .map_err(|e| Status::cancelled(e.to_string()))?;
+    if let Some(record) = record {
+        let message =
+            MqttMessage::decode_record(record).map_err(|e| Status::cancelled(e.to_string()))?;
+        reply.client_id = message.client_id;
+        reply.qos = message.qos as u32;
+        reply.payload = message.payload.to_vec();
+    }
+    Ok(Response::new(reply))
+}
+
+pub async fn cancel_delay_message_by_req<S>(
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
+    request: Request<MqttCancelDelayMessageRequest>,
+) -> Result<Response<MqttCancelDelayMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    if let Err(e) = delay_message_manager
+        .cancel_delay_message(&req.delay_id)
+        .await
+    {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(MqttCancelDelayMessageReply::default()))
+}
+
+fn encode_delay_message(delay_message: &DelayMessageRecord) -> MqttDelayMessageRaw {
+    MqttDelayMessageRaw {
+        delay_id: delay_message.delay_id.clone(),
+        target_topic: delay_message.target_topic_name.clone(),
+        due_timestamp: delay_message.due_timestamp,
+        create_time: delay_message.create_time,
+        shard_name: delay_message.shard_name.clone(),
+        offset: delay_message.offset,
+    }
+}
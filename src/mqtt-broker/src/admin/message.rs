@@ -0,0 +1,257 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use bytes::Bytes;
+use delay_message::DelayMessageManager;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::acl::mqtt_acl::MqttAclAction;
+use metadata_struct::mqtt::connection::MQTTConnection;
+use metadata_struct::mqtt::message::MqttMessage;
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttPeekMessageRaw, MqttPeekMessageReply, MqttPeekMessageRequest, MqttPublishMessageReply,
+    MqttPublishMessageRequest,
+};
+use protocol::mqtt::common::{qos, Publish, PublishProperties};
+use schema_register::schema::SchemaRegisterManager;
+use storage_adapter::storage::StorageAdapter;
+use tonic::{Request, Response, Status};
+
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::publish::publish_message;
+use crate::handler::topic::get_topic_name;
+use crate::rule_engine::RuleEngine;
+use crate::security::acl::auth::is_allow_acl;
+use crate::storage::message::MessageStorage;
+use crate::subscribe::subscribe_manager::SubscribeManager;
+
+const ADMIN_PUBLISH_CLIENT_ID: &str = "admin";
+const DEFAULT_PEEK_MESSAGE_NUM: u32 = 10;
+const MAX_PEEK_MESSAGE_NUM: u32 = 100;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn publish_message_by_req<S>(
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+    message_storage_adapter: &Arc<S>,
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
+    rule_engine: &Arc<RuleEngine<S>>,
+    request: Request<MqttPublishMessageRequest>,
+) -> Result<Response<MqttPublishMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    match admin_publish_message(
+        cache_manager,
+        client_pool,
+        message_storage_adapter,
+        delay_message_manager,
+        subscribe_manager,
+        schema_manager,
+        rule_engine,
+        req,
+    )
+    .await
+    {
+        Ok(reply) => Ok(Response::new(reply)),
+        Err(e) => Err(Status::cancelled(e.to_string())),
+    }
+}
+
+// Runs a message through the same steps as an MQTT PUBLISH, with an ACL check
+// that can be bypassed.
+#[allow(clippy::too_many_arguments)]
+async fn admin_publish_message<S>(
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+    message_storage_adapter: &Arc<S>,
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
+    rule_engine: &Arc<RuleEngine<S>>,
+    req: MqttPublishMessageRequest,
+) -> Result<MqttPublishMessageReply, MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let client_id = if req.client_id.is_empty() {
+        ADMIN_PUBLISH_CLIENT_ID.to_string()
+    } else {
+        req.client_id.clone()
+    };
+
+    let Some(publish_qos) = qos(req.qos as u8) else {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Invalid qos {}",
+            req.qos
+        )));
+    };
+
+    let publish = Publish {
+        dup: false,
+        qos: publish_qos,
+        pkid: 0,
+        retain: req.retain,
+        topic: Bytes::from(req.topic_name.clone()),
+        payload: Bytes::from(req.payload.clone()),
+    };
+    let publish_properties = build_publish_properties(&req);
+
+    let topic_name = get_topic_name(0, cache_manager, &publish, &None)?;
+
+    if !req.bypass_acl {
+        let connection = MQTTConnection {
+            client_id: client_id.clone(),
+            login_user: req.username.clone(),
+            tenant: req.tenant.clone(),
+            ..Default::default()
+        };
+        if !is_allow_acl(
+            cache_manager,
+            &connection,
+            &topic_name,
+            MqttAclAction::Publish,
+            publish.retain,
+            publish.qos,
+        ) {
+            return Err(MqttBrokerError::PublishNotAuthorized(client_id, topic_name));
+        }
+    }
+
+    if !req.tenant.is_empty() && cache_manager.get_tenant(&req.tenant).is_none() {
+        return Err(MqttBrokerError::TenantNotFound(req.tenant.clone()));
+    }
+
+    let result = publish_message(
+        cache_manager,
+        client_pool,
+        message_storage_adapter,
+        delay_message_manager,
+        subscribe_manager,
+        schema_manager,
+        rule_engine,
+        &req.tenant,
+        &client_id,
+        &topic_name,
+        &publish,
+        &publish_properties,
+    )
+    .await?;
+
+    Ok(MqttPublishMessageReply {
+        topic_name: result.topic_name,
+        offset: result.offset.unwrap_or_default(),
+    })
+}
+
+fn build_publish_properties(req: &MqttPublishMessageRequest) -> Option<PublishProperties> {
+    let properties = PublishProperties {
+        payload_format_indicator: req.payload_format_indicator.map(|v| v as u8),
+        message_expiry_interval: if req.message_expiry_interval > 0 {
+            Some(req.message_expiry_interval)
+        } else {
+            None
+        },
+        topic_alias: None,
+        response_topic: if req.response_topic.is_empty() {
+            None
+        } else {
+            Some(req.response_topic.clone())
+        },
+        correlation_data: if req.correlation_data.is_empty() {
+            None
+        } else {
+            Some(Bytes::from(req.correlation_data.clone()))
+        },
+        user_properties: req
+            .user_properties
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        subscription_identifiers: Vec::new(),
+        content_type: if req.content_type.is_empty() {
+            None
+        } else {
+            Some(req.content_type.clone())
+        },
+    };
+    Some(properties)
+}
+
+pub async fn peek_message_by_req<S>(
+    cache_manager: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    request: Request<MqttPeekMessageRequest>,
+) -> Result<Response<MqttPeekMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    match peek_message(cache_manager, message_storage_adapter, &req).await {
+        Ok(messages) => Ok(Response::new(MqttPeekMessageReply { messages })),
+        Err(e) => Err(Status::cancelled(e.to_string())),
+    }
+}
+
+async fn peek_message<S>(
+    cache_manager: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    req: &MqttPeekMessageRequest,
+) -> Result<Vec<MqttPeekMessageRaw>, MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let Some(topic) = cache_manager.get_topic_by_name(&req.topic_name) else {
+        return Err(MqttBrokerError::TopicDoesNotExist(req.topic_name.clone()));
+    };
+
+    let num = match req.num {
+        0 => DEFAULT_PEEK_MESSAGE_NUM,
+        num => num.min(MAX_PEEK_MESSAGE_NUM),
+    } as u64;
+
+    let message_storage =
+        MessageStorage::new_by_topic(message_storage_adapter.clone(), &topic.topic_name);
+    let end_offset = message_storage
+        .get_topic_end_offset(&topic.topic_id)
+        .await?;
+    let records = message_storage
+        .read_topic_message(&topic.topic_id, end_offset.saturating_sub(num), num)
+        .await?;
+
+    let mut messages = Vec::with_capacity(records.len());
+    for record in records {
+        let offset = record.offset.unwrap_or_default();
+        let message = MqttMessage::decode_record(record)?;
+        messages.push(MqttPeekMessageRaw {
+            offset,
+            client_id: message.client_id,
+            qos: u8::from(message.qos) as u32,
+            retain: message.retain,
+            payload: message.payload.to_vec(),
+            create_time: message.create_time,
+            content_type: message.content_type.unwrap_or_default(),
+            user_properties: message
+                .user_properties
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect(),
+        });
+    }
+    Ok(messages)
+}
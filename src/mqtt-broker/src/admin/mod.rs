@@ -13,24 +13,34 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod alarm;
+pub mod client;
 pub mod connector;
+pub mod delay_message;
+pub mod message;
+pub mod retain;
+pub mod rule;
 pub mod subscribe;
+pub mod tenant;
 pub mod topic;
+pub mod trace;
 pub mod user;
 
 use crate::handler::cache::CacheManager;
 use crate::handler::flapping_detect::enable_flapping_detect;
 use crate::observability::slow::sub::{enable_slow_sub, read_slow_sub_record, SlowSubData};
+use crate::server::cert_resolver::TlsCertResolver;
 use crate::server::connection_manager::ConnectionManager;
 use crate::{handler::error::MqttBrokerError, storage::cluster::ClusterStorage};
-use common_base::config::broker_mqtt::broker_mqtt_conf;
+use common_base::config::broker_mqtt::{broker_mqtt_conf, reload_broker_mqtt_conf};
 use common_base::tools::serialize_value;
 use common_base::utils::file_utils::get_project_root;
 use grpc_clients::pool::ClientPool;
 use protocol::broker_mqtt::broker_mqtt_admin::{
     ClusterStatusReply, EnableFlappingDetectReply, EnableFlappingDetectRequest,
     EnableSlowSubScribeReply, EnableSlowSubscribeRequest, ListConnectionRaw, ListConnectionReply,
-    ListSlowSubScribeRaw, ListSlowSubscribeReply, ListSlowSubscribeRequest,
+    ListConnectionRequest, ListSlowSubScribeRaw, ListSlowSubscribeReply, ListSlowSubscribeRequest,
+    MqttReloadConfigReply, MqttReloadTlsCertReply,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
@@ -52,6 +62,23 @@ pub async fn cluster_status_by_req(
     })
 }
 
+pub fn reload_config_by_req() -> Result<MqttReloadConfigReply, MqttBrokerError> {
+    let report = reload_broker_mqtt_conf()?;
+    Ok(MqttReloadConfigReply {
+        applied: report.applied,
+        restart_required: report.restart_required,
+    })
+}
+
+pub fn reload_tls_cert_by_req(
+    tls_cert_resolver: &Arc<TlsCertResolver>,
+) -> Result<MqttReloadTlsCertReply, MqttBrokerError> {
+    tls_cert_resolver.reload(&broker_mqtt_conf().network)?;
+    Ok(MqttReloadTlsCertReply {
+        server_names: tls_cert_resolver.server_names(),
+    })
+}
+
 pub async fn enable_flapping_detect_by_req(
     cache_manager: &Arc<CacheManager>,
     request: Request<EnableFlappingDetectRequest>,
@@ -69,11 +96,16 @@ pub async fn enable_flapping_detect_by_req(
 pub fn list_connection_by_req(
     connection_manager: &Arc<ConnectionManager>,
     cache_manager: &Arc<CacheManager>,
+    request: Request<ListConnectionRequest>,
 ) -> Result<Response<ListConnectionReply>, Status> {
+    let req = request.into_inner();
     let mut reply = ListConnectionReply::default();
     let mut list_connection_raw: Vec<ListConnectionRaw> = Vec::new();
     for (key, value) in connection_manager.list_connect() {
         if let Some(mqtt_value) = cache_manager.connection_info.clone().get(&key) {
+            if !req.tenant.is_empty() && mqtt_value.tenant != req.tenant {
+                continue;
+            }
             let mqtt_info = serialize_value(mqtt_value.value())?;
             let raw = ListConnectionRaw {
                 connection_id: value.connection_id,
@@ -0,0 +1,133 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttDeleteRetainMessageReply, MqttDeleteRetainMessageRequest, MqttGetRetainMessageReply,
+    MqttGetRetainMessageRequest, MqttListRetainMessageReply, MqttListRetainMessageRequest,
+    MqttRetainMessageRaw,
+};
+use storage_adapter::storage::StorageAdapter;
+use tonic::{Request, Response, Status};
+
+use crate::handler::cache::CacheManager;
+use crate::handler::retain_index::RetainIndexEntry;
+use crate::storage::retain::RetainMessageStorage;
+
+pub async fn list_retain_message_by_req(
+    cache_manager: &Arc<CacheManager>,
+    request: Request<MqttListRetainMessageRequest>,
+) -> Result<Response<MqttListRetainMessageReply>, Status> {
+    let req = request.into_inner();
+    let index = &cache_manager.retain_message_index;
+    let retain_messages = if req.topic_filter.is_empty() {
+        index
+            .list()
+            .iter()
+            .map(|(topic_name, entry)| encode_retain_message(topic_name, entry))
+            .collect()
+    } else {
+        index
+            .match_filter(&req.topic_filter)
+            .iter()
+            .filter_map(|topic_name| {
+                index
+                    .get(topic_name)
+                    .map(|entry| encode_retain_message(topic_name, &entry))
+            })
+            .collect()
+    };
+    Ok(Response::new(MqttListRetainMessageReply {
+        retain_messages,
+    }))
+}
+
+pub async fn get_retain_message_by_req<S>(
+    cache_manager: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    request: Request<MqttGetRetainMessageRequest>,
+) -> Result<Response<MqttGetRetainMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    let Some(entry) = cache_manager.retain_message_index.get(&req.topic_name) else {
+        return Err(Status::cancelled(format!(
+            "Topic {} has no retained message",
+            req.topic_name
+        )));
+    };
+
+    let retain_storage = RetainMessageStorage::new(message_storage_adapter.clone());
+    let message = retain_storage
+        .get(entry.offset)
+        .await
+        .map_err(|e| Status::cancelled(e.to_string()))?;
+
+    let mut reply = MqttGetRetainMessageReply {
+        retain_message: Some(encode_retain_message(&req.topic_name, &entry)),
+        ..Default::default()
+    };
+    if let Some(message) = message {
+        reply.payload = message.payload.to_vec();
+        reply.content_type = message.content_type.unwrap_or_default();
+        reply.user_properties = message
+            .user_properties
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+    }
+    Ok(Response::new(reply))
+}
+
+pub async fn delete_retain_message_by_req<S>(
+    cache_manager: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    request: Request<MqttDeleteRetainMessageRequest>,
+) -> Result<Response<MqttDeleteRetainMessageReply>, Status>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let req = request.into_inner();
+    if req.topic_filter.is_empty() {
+        return Err(Status::cancelled(
+            "topic_filter cannot be empty".to_string(),
+        ));
+    }
+
+    let index = &cache_manager.retain_message_index;
+    let retain_storage = RetainMessageStorage::new(message_storage_adapter.clone());
+    let mut deleted_num = 0;
+    for topic_name in index.match_filter(&req.topic_filter) {
+        let offset = retain_storage
+            .delete(&topic_name)
+            .await
+            .map_err(|e| Status::cancelled(e.to_string()))?;
+        index.delete(&topic_name, offset);
+        deleted_num += 1;
+    }
+    Ok(Response::new(MqttDeleteRetainMessageReply { deleted_num }))
+}
+
+fn encode_retain_message(topic_name: &str, entry: &RetainIndexEntry) -> MqttRetainMessageRaw {
+    MqttRetainMessageRaw {
+        topic_name: topic_name.to_string(),
+        client_id: entry.client_id.clone(),
+        qos: entry.qos as u32,
+        payload_size: entry.payload_size as u64,
+        create_time: entry.create_time,
+        expired_at: entry.expired_at,
+        offset: entry.offset,
+    }
+}
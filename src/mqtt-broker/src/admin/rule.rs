@@ -0,0 +1,161 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use common_base::tools::now_second;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::rule::{MqttRule, MqttRuleAction};
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttCreateRuleReply, MqttCreateRuleRequest, MqttDeleteRuleReply, MqttDeleteRuleRequest,
+    MqttListRuleReply, MqttListRuleRequest, MqttUpdateRuleReply, MqttUpdateRuleRequest,
+};
+use tonic::{Request, Response, Status};
+
+use crate::handler::error::MqttBrokerError;
+use crate::rule_engine::sql::parse_rule_sql;
+use crate::storage::connector::ConnectorStorage;
+use crate::storage::rule::RuleStorage;
+
+pub async fn list_rule_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttListRuleRequest>,
+) -> Result<Response<MqttListRuleReply>, Status> {
+    let req = request.into_inner();
+    let storage = RuleStorage::new(client_pool.clone());
+    let rules = storage
+        .list_rule(&req.rule_name)
+        .await?
+        .iter()
+        .map(|rule| rule.encode())
+        .collect();
+    Ok(Response::new(MqttListRuleReply { rules }))
+}
+
+pub async fn create_rule_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttCreateRuleRequest>,
+) -> Result<Response<MqttCreateRuleReply>, Status> {
+    let req = request.into_inner();
+    let actions = match rule_validator(client_pool, &req.sql, &req.actions).await {
+        Ok(actions) => actions,
+        Err(e) => return Err(Status::cancelled(e.to_string())),
+    };
+
+    let config = broker_mqtt_conf();
+    let rule = MqttRule {
+        cluster_name: config.cluster_name.clone(),
+        rule_name: req.rule_name.clone(),
+        sql: req.sql.clone(),
+        actions,
+        enable: req.enable,
+        desc: req.desc.clone(),
+        create_time: now_second(),
+        update_time: now_second(),
+    };
+    let storage = RuleStorage::new(client_pool.clone());
+    if let Err(e) = storage.create_rule(rule).await {
+        return Err(Status::cancelled(e.to_string()));
+    };
+    Ok(Response::new(MqttCreateRuleReply::default()))
+}
+
+pub async fn update_rule_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttUpdateRuleRequest>,
+) -> Result<Response<MqttUpdateRuleReply>, Status> {
+    let req = request.into_inner();
+    let actions = match rule_validator(client_pool, &req.sql, &req.actions).await {
+        Ok(actions) => actions,
+        Err(e) => return Err(Status::cancelled(e.to_string())),
+    };
+
+    let storage = RuleStorage::new(client_pool.clone());
+    let mut rule = match storage.list_rule(&req.rule_name).await?.pop() {
+        Some(rule) => rule,
+        None => {
+            return Err(Status::cancelled(format!(
+                "Rule {} does not exist",
+                req.rule_name
+            )))
+        }
+    };
+    rule.sql = req.sql.clone();
+    rule.actions = actions;
+    rule.enable = req.enable;
+    rule.desc = req.desc.clone();
+    rule.update_time = now_second();
+
+    if let Err(e) = storage.update_rule(rule).await {
+        return Err(Status::cancelled(e.to_string()));
+    };
+    Ok(Response::new(MqttUpdateRuleReply::default()))
+}
+
+pub async fn delete_rule_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttDeleteRuleRequest>,
+) -> Result<Response<MqttDeleteRuleReply>, Status> {
+    let req = request.into_inner();
+    let storage = RuleStorage::new(client_pool.clone());
+    if let Err(e) = storage.delete_rule(&req.rule_name).await {
+        return Err(Status::cancelled(e.to_string()));
+    };
+    Ok(Response::new(MqttDeleteRuleReply::default()))
+}
+
+async fn rule_validator(
+    client_pool: &Arc<ClientPool>,
+    sql: &str,
+    actions: &str,
+) -> Result<Vec<MqttRuleAction>, MqttBrokerError> {
+    parse_rule_sql(sql)?;
+
+    let actions: Vec<MqttRuleAction> = serde_json::from_str(actions)?;
+    if actions.is_empty() {
+        return Err(MqttBrokerError::CommonError(
+            "a rule needs at least one action".to_string(),
+        ));
+    }
+
+    let connector_storage = ConnectorStorage::new(client_pool.clone());
+    for action in actions.iter() {
+        match action {
+            MqttRuleAction::Republish { topic, qos } => {
+                if topic.is_empty() {
+                    return Err(MqttBrokerError::TopicNameIsEmpty);
+                }
+                if *qos > 2 {
+                    return Err(MqttBrokerError::CommonError(format!(
+                        "invalid republish qos {}",
+                        qos
+                    )));
+                }
+            }
+            MqttRuleAction::Connector { connector_name } => {
+                if connector_storage
+                    .list_connector(connector_name)
+                    .await?
+                    .is_empty()
+                {
+                    return Err(MqttBrokerError::ConnectorNotFound(
+                        connector_name.to_owned(),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(actions)
+}
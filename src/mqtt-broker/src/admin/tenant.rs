@@ -0,0 +1,161 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use common_base::tools::now_second;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::cluster::{MqttClusterDynamicTenant, TenantSource};
+use metadata_struct::mqtt::tenant::{MqttTenant, MqttTenantQuota};
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttCreateTenantReply, MqttCreateTenantRequest, MqttDeleteTenantReply, MqttDeleteTenantRequest,
+    MqttListTenantReply, MqttListTenantRequest, MqttSetTenantConfigReply,
+    MqttSetTenantConfigRequest, MqttTenantRaw, MqttUpdateTenantReply, MqttUpdateTenantRequest,
+};
+use tonic::{Request, Response, Status};
+
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::tenant::tenant_name_validator;
+use crate::storage::tenant::TenantStorage;
+
+// Usage counts cover the whole cluster as of the last tenant usage sync
+pub async fn list_tenant_by_req(
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttListTenantRequest>,
+) -> Result<Response<MqttListTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = TenantStorage::new(client_pool.clone());
+    let tenants = storage
+        .list_tenant(&req.tenant_name)
+        .await?
+        .into_iter()
+        .map(|tenant| {
+            let usage = cache_manager.get_tenant_usage(&tenant.tenant_name);
+            MqttTenantRaw {
+                connection_num: cache_manager.tenant_cluster_connection_num(&tenant.tenant_name),
+                session_num: cache_manager.tenant_session_num(&tenant.tenant_name),
+                topic_num: cache_manager.tenant_topic_num(&tenant.tenant_name),
+                storage_bytes: usage.storage_bytes,
+                tenant: tenant.encode(),
+            }
+        })
+        .collect();
+    Ok(Response::new(MqttListTenantReply { tenants }))
+}
+
+pub async fn create_tenant_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttCreateTenantRequest>,
+) -> Result<Response<MqttCreateTenantReply>, Status> {
+    let req = request.into_inner();
+    if !tenant_name_validator(&req.tenant_name) {
+        return Err(MqttBrokerError::TenantNameInvalid(req.tenant_name).into());
+    }
+
+    let config = broker_mqtt_conf();
+    let tenant = MqttTenant {
+        cluster_name: config.cluster_name.clone(),
+        tenant_name: req.tenant_name.clone(),
+        desc: req.desc.clone(),
+        quota: MqttTenantQuota {
+            max_connections: req.max_connections,
+            max_sessions: req.max_sessions,
+            max_topics: req.max_topics,
+            max_message_rate: req.max_message_rate,
+            max_storage_bytes: req.max_storage_bytes,
+        },
+        create_time: now_second(),
+        update_time: now_second(),
+    };
+    let storage = TenantStorage::new(client_pool.clone());
+    if let Err(e) = storage.create_tenant(tenant).await {
+        return Err(Status::cancelled(e.to_string()));
+    };
+    Ok(Response::new(MqttCreateTenantReply::default()))
+}
+
+pub async fn update_tenant_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttUpdateTenantRequest>,
+) -> Result<Response<MqttUpdateTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = TenantStorage::new(client_pool.clone());
+    let mut tenant = match storage.list_tenant(&req.tenant_name).await?.pop() {
+        Some(tenant) => tenant,
+        None => return Err(MqttBrokerError::TenantNotFound(req.tenant_name).into()),
+    };
+    tenant.desc = req.desc.clone();
+    tenant.quota = MqttTenantQuota {
+        max_connections: req.max_connections,
+        max_sessions: req.max_sessions,
+        max_topics: req.max_topics,
+        max_message_rate: req.max_message_rate,
+        max_storage_bytes: req.max_storage_bytes,
+    };
+    tenant.update_time = now_second();
+
+    if let Err(e) = storage.update_tenant(tenant).await {
+        return Err(Status::cancelled(e.to_string()));
+    };
+    Ok(Response::new(MqttUpdateTenantReply::default()))
+}
+
+// Clients, sessions and topics of the tenant are kept, new connections of the tenant are refused
+pub async fn delete_tenant_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttDeleteTenantRequest>,
+) -> Result<Response<MqttDeleteTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = TenantStorage::new(client_pool.clone());
+    if let Err(e) = storage.delete_tenant(&req.tenant_name).await {
+        return Err(Status::cancelled(e.to_string()));
+    };
+    Ok(Response::new(MqttDeleteTenantReply::default()))
+}
+
+pub async fn set_tenant_config_by_req(
+    cache_manager: &Arc<CacheManager>,
+    request: Request<MqttSetTenantConfigRequest>,
+) -> Result<Response<MqttSetTenantConfigReply>, Status> {
+    let req = request.into_inner();
+    let source = match req.source.as_str() {
+        "username" => TenantSource::Username,
+        "cert_field" => TenantSource::CertField,
+        "listener" => TenantSource::Listener,
+        source => {
+            return Err(Status::cancelled(format!(
+                "Invalid tenant source {}, expected username, cert_field or listener",
+                source
+            )))
+        }
+    };
+    for tenant in req.listener_tenants.values() {
+        if !tenant_name_validator(tenant) {
+            return Err(MqttBrokerError::TenantNameInvalid(tenant.clone()).into());
+        }
+    }
+
+    let tenant = MqttClusterDynamicTenant {
+        enable: req.enable,
+        source,
+        username_separator: req.username_separator.clone(),
+        cert_field: req.cert_field.clone(),
+        listener_tenants: req.listener_tenants,
+    };
+    cache_manager.set_tenant_config(tenant).await?;
+    Ok(Response::new(MqttSetTenantConfigReply::default()))
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::handler::cache::CacheManager;
+use crate::handler::tenant::{tenant_of_topic, tenant_topic_name};
 use crate::storage::topic::TopicStorage;
 use common_base::{config::broker_mqtt::broker_mqtt_conf, tools::now_mills};
 use grpc_clients::pool::ClientPool;
@@ -29,41 +30,57 @@ pub fn list_topic_by_req(
     request: Request<ListTopicRequest>,
 ) -> Result<Response<ListTopicReply>, Status> {
     let req = request.into_inner();
-    let topic_query_result: Vec<MqttTopic> = match req.match_option {
+    // Exact and prefix matches are done on the name inside the tenant namespace
+    let topic_name = if req.match_option == 2 {
+        req.topic_name.clone()
+    } else {
+        tenant_topic_name(&req.tenant, &req.topic_name)
+    };
+    let topics = search_topic(cache_manager, &topic_name, req.match_option)
+        .into_iter()
+        .filter(|topic| req.tenant.is_empty() || tenant_of_topic(&topic.topic_name) == req.tenant)
+        .take(10)
+        .collect();
+    Ok(Response::new(ListTopicReply { topics }))
+}
+
+// match_option: 0 exact, 1 prefix, 2 contains
+pub fn search_topic(
+    cache_manager: &Arc<CacheManager>,
+    topic_name: &str,
+    match_option: i32,
+) -> Vec<MqttTopic> {
+    match match_option {
         0 => cache_manager
-            .get_topic_by_name(&req.topic_name)
+            .get_topic_by_name(topic_name)
             .into_iter()
-            .take(10)
             .map(|entry| MqttTopic {
                 topic_id: entry.topic_id.clone(),
                 topic_name: entry.topic_name.clone(),
                 cluster_name: entry.cluster_name.clone(),
-                is_contain_retain_message: entry.retain_message.is_some(),
+                is_contain_retain_message: cache_manager
+                    .retain_message_index
+                    .contains(&entry.topic_name),
             })
             .collect(),
         option => cache_manager
             .topic_info
             .iter()
             .filter(|entry| match option {
-                1 => entry.value().topic_name.starts_with(&req.topic_name),
-                2 => entry.value().topic_name.contains(&req.topic_name),
+                1 => entry.value().topic_name.starts_with(topic_name),
+                2 => entry.value().topic_name.contains(topic_name),
                 _ => false,
             })
-            .take(10)
             .map(|entry| MqttTopic {
                 topic_id: entry.value().topic_id.clone(),
                 topic_name: entry.value().topic_name.clone(),
                 cluster_name: entry.value().cluster_name.clone(),
-                is_contain_retain_message: entry.value().retain_message.is_some(),
+                is_contain_retain_message: cache_manager
+                    .retain_message_index
+                    .contains(&entry.value().topic_name),
             })
             .collect(),
-    };
-
-    let reply = ListTopicReply {
-        topics: topic_query_result,
-    };
-
-    Ok(Response::new(reply))
+    }
 }
 
 pub async fn delete_topic_rewrite_rule_by_req(
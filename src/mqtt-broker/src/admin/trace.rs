@@ -0,0 +1,45 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use common_base::telemetry::trace::{
+    disable_client_trace, enable_client_trace, list_traced_clients,
+};
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    MqttListClientTraceReply, MqttListClientTraceRequest, MqttSetClientTraceReply,
+    MqttSetClientTraceRequest,
+};
+use tonic::{Request, Response, Status};
+
+// Sampling is kept per node, so it only applies to clients connected to this broker.
+pub async fn set_client_trace_by_req(
+    request: Request<MqttSetClientTraceRequest>,
+) -> Result<Response<MqttSetClientTraceReply>, Status> {
+    let req = request.into_inner();
+    if req.client_id.is_empty() {
+        return Err(Status::invalid_argument("client_id cannot be empty"));
+    }
+    if req.enable {
+        enable_client_trace(&req.client_id);
+    } else {
+        disable_client_trace(&req.client_id);
+    }
+    Ok(Response::new(MqttSetClientTraceReply::default()))
+}
+
+pub async fn list_client_trace_by_req(
+    _request: Request<MqttListClientTraceRequest>,
+) -> Result<Response<MqttListClientTraceReply>, Status> {
+    let mut client_ids = list_traced_clients();
+    client_ids.sort();
+    Ok(Response::new(MqttListClientTraceReply { client_ids }))
+}
@@ -18,6 +18,7 @@
 use metadata_struct::mqtt::user::MqttUser;
 use protocol::broker_mqtt::broker_mqtt_admin::{
     CreateUserReply, CreateUserRequest, DeleteUserReply, DeleteUserRequest, ListUserReply,
+    ListUserRequest,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
@@ -55,16 +56,27 @@ pub async fn delete_user_by_req(
     }
 }
 
+// With a tenant, only the users whose username carries the tenant prefix are listed
 pub async fn list_user_by_req(
     cache_manager: &Arc<CacheManager>,
     client_pool: &Arc<ClientPool>,
+    request: Request<ListUserRequest>,
 ) -> Result<Response<ListUserReply>, Status> {
+    let req = request.into_inner();
+    let separator = cache_manager.get_tenant_config().username_separator;
     let mut reply = ListUserReply::default();
     let auth_driver = AuthDriver::new(cache_manager.clone(), client_pool.clone());
     match auth_driver.read_all_user().await {
         Ok(data) => {
             let mut users = Vec::new();
             for ele in data {
+                if !req.tenant.is_empty()
+                    && (separator.is_empty()
+                        || ele.1.username.split_once(&separator).map(|(t, _)| t)
+                            != Some(req.tenant.as_str()))
+                {
+                    continue;
+                }
                 users.push(ele.1.encode());
             }
             reply.users = users;
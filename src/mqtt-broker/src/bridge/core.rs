@@ -12,19 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::handler::cache::CacheManager;
 use crate::handler::error::MqttBrokerError;
+use crate::observability::metrics::connector::metrics_connector_lag;
+use crate::observability::warn::{record_connector_down, record_connector_up};
+use crate::rule_engine::rule_connector_shard_name;
+use crate::storage::message::MessageStorage;
 use axum::async_trait;
 use common_base::config::broker_mqtt::broker_mqtt_conf;
+use common_base::tools::now_second;
+use grpc_clients::pool::ClientPool;
 use log::{error, info};
+use metadata_struct::adapter::record::Record;
 use metadata_struct::mqtt::bridge::{
-    config_local_file::LocalFileConnectorConfig, connector::MQTTConnector,
-    connector_type::ConnectorType, status::MQTTStatus,
+    config_influxdb::InfluxDbConnectorConfig,
+    config_kafka::KafkaConnectorConfig,
+    config_local_file::LocalFileConnectorConfig,
+    config_mqtt::{MqttBridgeConnectorConfig, MqttBridgeDirection},
+    config_rdb::RdbConnectorConfig,
+    config_redis::RedisConnectorConfig,
+    config_webhook::WebHookConnectorConfig,
+    connector::MQTTConnector,
+    connector_type::ConnectorType,
+    status::MQTTStatus,
 };
-use std::{sync::Arc, time::Duration};
+use metadata_struct::mqtt::rule::MqttRuleAction;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use storage_adapter::storage::StorageAdapter;
 use tokio::{select, sync::broadcast, time::sleep};
 
-use super::{file::FileBridgePlugin, manager::ConnectorManager};
+use super::{
+    file::FileBridgePlugin, influxdb::InfluxDbBridgePlugin, kafka::KafkaBridgePlugin,
+    manager::ConnectorManager, mqtt::MqttBridgePlugin, rdb::RdbBridgePlugin,
+    redis::RedisBridgePlugin, webhook::WebHookBridgePlugin,
+};
 
 pub struct BridgePluginReadConfig {
     pub topic_id: String,
@@ -37,14 +58,231 @@ pub struct BridgePluginThread {
     pub stop_send: broadcast::Sender<bool>,
 }
 
+// A sink for connector records. Reading, retrying and offset commits are handled
+// by `run_bridge_plugin`, so a batch is only committed after `append` succeeded.
 #[async_trait]
 pub trait BridgePlugin {
-    async fn exec(&self, config: BridgePluginReadConfig) -> Result<(), MqttBrokerError>;
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError>;
+}
+
+pub(crate) const RETRY_BACKOFF_MIN_MS: u64 = 100;
+pub(crate) const RETRY_BACKOFF_MAX_MS: u64 = 10_000;
+
+pub async fn run_bridge_plugin<S, P>(
+    plugin: &P,
+    connector_manager: &Arc<ConnectorManager>,
+    cache_manager: &Arc<CacheManager>,
+    message_storage: &Arc<S>,
+    connector_name: &str,
+    config: BridgePluginReadConfig,
+    stop_send: broadcast::Sender<bool>,
+) -> Result<(), MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+    P: BridgePlugin + Sync,
+{
+    // (shard_name, next offset to read)
+    let mut offsets = HashMap::new();
+    let mut backoff_ms = RETRY_BACKOFF_MIN_MS;
+    let mut recv = stop_send.subscribe();
+
+    loop {
+        select! {
+            val = recv.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        info!("Connector {} thread exited successfully", connector_name);
+                        record_connector_up(connector_name);
+                        break;
+                    }
+                }
+            }
+
+            val = deliver_batches(
+                plugin,
+                connector_manager,
+                cache_manager,
+                message_storage,
+                connector_name,
+                &config,
+                &mut offsets,
+            ) => {
+                match val {
+                    Ok(true) => {
+                        if backoff_ms > RETRY_BACKOFF_MIN_MS {
+                            record_connector_up(connector_name);
+                        }
+                        backoff_ms = RETRY_BACKOFF_MIN_MS;
+                    }
+                    Ok(false) => {
+                        sleep(Duration::from_millis(RETRY_BACKOFF_MIN_MS)).await;
+                    }
+                    Err(e) => {
+                        error!(
+                            "Connector {} failed to deliver records, retrying in {}ms, error message: {}",
+                            connector_name, backoff_ms, e
+                        );
+                        connector_manager.update_connector_stats(connector_name, |stats| {
+                            stats.failed_count += 1;
+                            stats.last_error = Some(e.to_string());
+                        });
+                        record_connector_down(connector_name, e.to_string());
+                        sleep(Duration::from_millis(backoff_ms)).await;
+                        backoff_ms = (backoff_ms * 2).min(RETRY_BACKOFF_MAX_MS);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Delivers one batch from the connector topic and from every rule result shard of
+// the connector, returns whether any record was delivered.
+async fn deliver_batches<S, P>(
+    plugin: &P,
+    connector_manager: &Arc<ConnectorManager>,
+    cache_manager: &Arc<CacheManager>,
+    storage_adapter: &Arc<S>,
+    connector_name: &str,
+    config: &BridgePluginReadConfig,
+    offsets: &mut HashMap<String, u64>,
+) -> Result<bool, MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+    P: BridgePlugin + Sync,
+{
+    let mut delivered = false;
+    for (group_name, shard_name) in connector_shards(cache_manager, connector_name, config) {
+        let message_storage = shard_message_storage(cache_manager, storage_adapter, &shard_name);
+        let offset = match offsets.get(&shard_name) {
+            Some(offset) => *offset,
+            None => message_storage.get_group_offset(&group_name).await?,
+        };
+        let next_offset = deliver_batch(
+            plugin,
+            connector_manager,
+            &message_storage,
+            connector_name,
+            &group_name,
+            &shard_name,
+            config.record_num,
+            offset,
+        )
+        .await?;
+        if next_offset.is_some() {
+            delivered = true;
+        }
+        offsets.insert(shard_name, next_offset.unwrap_or(offset));
+    }
+    Ok(delivered)
+}
+
+// (group_name, shard_name) pairs read by a connector, the connector topic uses the
+// connector name as its group so that existing offsets are kept.
+fn connector_shards(
+    cache_manager: &Arc<CacheManager>,
+    connector_name: &str,
+    config: &BridgePluginReadConfig,
+) -> Vec<(String, String)> {
+    let mut shards = vec![(connector_name.to_string(), config.topic_id.clone())];
+    for raw in cache_manager.rule_info.iter() {
+        let is_target = raw.rule.actions.iter().any(|action| {
+            matches!(action, MqttRuleAction::Connector { connector_name: name } if name == connector_name)
+        });
+        if is_target {
+            shards.push((
+                format!("{}_{}", connector_name, raw.rule.rule_name),
+                rule_connector_shard_name(&raw.rule.rule_name),
+            ));
+        }
+    }
+    shards
+}
+
+// A connector topic of a tenant is read from the storage namespace of the tenant, rule
+// result shards are not topics and stay in the namespace of the cluster.
+fn shard_message_storage<S>(
+    cache_manager: &Arc<CacheManager>,
+    storage_adapter: &Arc<S>,
+    shard_name: &str,
+) -> MessageStorage<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    match cache_manager.topic_name_by_id(shard_name) {
+        Some(topic_name) => MessageStorage::new_by_topic(storage_adapter.clone(), &topic_name),
+        None => MessageStorage::new(storage_adapter.clone()),
+    }
+}
+
+// Returns the next offset to read once a batch was written and committed,
+// or None when there was nothing to read.
+#[allow(clippy::too_many_arguments)]
+async fn deliver_batch<S, P>(
+    plugin: &P,
+    connector_manager: &Arc<ConnectorManager>,
+    message_storage: &MessageStorage<S>,
+    connector_name: &str,
+    group_name: &str,
+    shard_name: &str,
+    record_num: u64,
+    offset: u64,
+) -> Result<Option<u64>, MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+    P: BridgePlugin + Sync,
+{
+    let records = message_storage
+        .read_topic_message(shard_name, offset, record_num)
+        .await?;
+    connector_manager.report_heartbeat(connector_name);
+
+    // lag is tracked for the connector topic only
+    let is_connector_topic = group_name == connector_name;
+    let Some(first) = records.first() else {
+        if is_connector_topic {
+            connector_manager.update_connector_stats(connector_name, |stats| stats.lag = 0);
+            metrics_connector_lag(connector_name, 0);
+        }
+        return Ok(None);
+    };
+
+    if is_connector_topic {
+        let lag = now_second().saturating_sub(first.timestamp);
+        connector_manager.update_connector_stats(connector_name, |stats| stats.lag = lag);
+        metrics_connector_lag(connector_name, lag);
+    }
+
+    plugin.append(&records).await?;
+
+    let next_offset = next_read_offset(offset, &records);
+    message_storage
+        .commit_group_offset(group_name, shard_name, next_offset)
+        .await?;
+
+    connector_manager.update_connector_stats(connector_name, |stats| {
+        stats.delivered_count += records.len() as u64;
+        if is_connector_topic {
+            stats.committed_offset = next_offset;
+        }
+        stats.last_error = None;
+    });
+    Ok(Some(next_offset))
+}
+
+fn next_read_offset(offset: u64, records: &[Record]) -> u64 {
+    match records.last().and_then(|record| record.offset) {
+        Some(last) => last + 1,
+        None => offset + records.len() as u64,
+    }
 }
 
 pub async fn start_connector_thread<S>(
     message_storage: Arc<S>,
     connector_manager: Arc<ConnectorManager>,
+    cache_manager: Arc<CacheManager>,
+    client_pool: Arc<ClientPool>,
     stop_send: broadcast::Sender<bool>,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
@@ -63,6 +301,8 @@ pub async fn start_connector_thread<S>(
             _ = check_connector(
                 &message_storage,
                 &connector_manager,
+                &cache_manager,
+                &client_pool,
             ) => {
                 sleep(Duration::from_secs(1)).await;
             }
@@ -70,8 +310,12 @@ pub async fn start_connector_thread<S>(
     }
 }
 
-async fn check_connector<S>(message_storage: &Arc<S>, connector_manager: &Arc<ConnectorManager>)
-where
+async fn check_connector<S>(
+    message_storage: &Arc<S>,
+    connector_manager: &Arc<ConnectorManager>,
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
     let config = broker_mqtt_conf();
@@ -104,6 +348,8 @@ async fn check_connector<S>(message_storage: &Arc<S>, connector_manager: &Arc<Co
         start_thread(
             connector_manager.clone(),
             message_storage.clone(),
+            cache_manager.clone(),
+            client_pool.clone(),
             raw.clone(),
             thread,
         );
@@ -139,6 +385,8 @@ async fn check_connector<S>(message_storage: &Arc<S>, connector_manager: &Arc<Co
 fn start_thread<S>(
     connector_manager: Arc<ConnectorManager>,
     message_storage: Arc<S>,
+    cache_manager: Arc<CacheManager>,
+    client_pool: Arc<ClientPool>,
     connector: MQTTConnector,
     thread: BridgePluginThread,
 ) where
@@ -152,36 +400,348 @@ fn start_thread<S>(
                 ) {
                     Ok(config) => config,
                     Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
                         error!("Failed to parse LocalFileConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
                         return;
                     }
                 };
 
-                let bridge = FileBridgePlugin::new(
-                    connector_manager.clone(),
-                    message_storage.clone(),
-                    connector.connector_name.clone(),
-                    local_file_config,
+                let bridge = FileBridgePlugin::new(local_file_config);
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                if let Err(e) = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
+                        topic_id: connector.topic_id,
+                        record_num: 100,
+                    },
+                    thread.stop_send,
+                )
+                .await
+                {
+                    connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
+                    error!(
+                        "Failed to start FileBridgePlugin with error message: {:?}",
+                        e
+                    );
+                }
+            }
+            ConnectorType::Kafka => {
+                let kafka_config = match serde_json::from_str::<KafkaConnectorConfig>(
+                    &connector.config,
+                ) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!("Failed to parse KafkaConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
+                        return;
+                    }
+                };
+
+                let bridge = match KafkaBridgePlugin::new(kafka_config) {
+                    Ok(bridge) => bridge,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!(
+                            "Failed to create KafkaBridgePlugin with error message: {:?}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                if let Err(e) = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
+                        topic_id: connector.topic_id,
+                        record_num: 100,
+                    },
+                    thread.stop_send,
+                )
+                .await
+                {
+                    connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
+                    error!(
+                        "Failed to start KafkaBridgePlugin with error message: {:?}",
+                        e
+                    );
+                }
+            }
+            ConnectorType::Mqtt => {
+                let mqtt_config = match serde_json::from_str::<MqttBridgeConnectorConfig>(
+                    &connector.config,
+                ) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!("Failed to parse MqttBridgeConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
+                        return;
+                    }
+                };
+
+                let bridge = match MqttBridgePlugin::new(mqtt_config) {
+                    Ok(bridge) => bridge,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!(
+                            "Failed to create MqttBridgePlugin with error message: {:?}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                let egress = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
+                        topic_id: connector.topic_id.clone(),
+                        record_num: 100,
+                    },
+                    thread.stop_send.clone(),
+                );
+                let ingress = bridge.run_ingress(
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &client_pool,
+                    &connector.connector_name,
                     thread.stop_send.clone(),
                 );
+                let result = match bridge.direction() {
+                    MqttBridgeDirection::Egress => egress.await,
+                    MqttBridgeDirection::Ingress => ingress.await,
+                    MqttBridgeDirection::Both => {
+                        let (egress, ingress) = tokio::join!(egress, ingress);
+                        egress.and(ingress)
+                    }
+                };
+                bridge.close().await;
 
-                connector_manager.add_connector_thread(&connector.connector_name, thread);
+                if let Err(e) = result {
+                    connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
+                    error!(
+                        "Failed to start MqttBridgePlugin with error message: {:?}",
+                        e
+                    );
+                }
+            }
+            ConnectorType::WebHook => {
+                let webhook_config = match serde_json::from_str::<WebHookConnectorConfig>(
+                    &connector.config,
+                ) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!("Failed to parse WebHookConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
+                        return;
+                    }
+                };
 
-                if let Err(e) = bridge
-                    .exec(BridgePluginReadConfig {
+                let bridge = match WebHookBridgePlugin::new(
+                    connector.connector_name.clone(),
+                    webhook_config,
+                    cache_manager.clone(),
+                    message_storage.clone(),
+                    client_pool,
+                ) {
+                    Ok(bridge) => bridge,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!(
+                            "Failed to create WebHookBridgePlugin with error message: {:?}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                if let Err(e) = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
                         topic_id: connector.topic_id,
                         record_num: 100,
-                    })
-                    .await
+                    },
+                    thread.stop_send,
+                )
+                .await
                 {
                     connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
                     error!(
-                        "Failed to start FileBridgePlugin with error message: {:?}",
+                        "Failed to start WebHookBridgePlugin with error message: {:?}",
+                        e
+                    );
+                }
+            }
+            ConnectorType::Rdb => {
+                let rdb_config = match serde_json::from_str::<RdbConnectorConfig>(&connector.config)
+                {
+                    Ok(config) => config,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!("Failed to parse RdbConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
+                        return;
+                    }
+                };
+
+                let bridge = match RdbBridgePlugin::new(rdb_config) {
+                    Ok(bridge) => bridge,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!(
+                            "Failed to create RdbBridgePlugin with error message: {:?}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                if let Err(e) = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
+                        topic_id: connector.topic_id,
+                        record_num: 100,
+                    },
+                    thread.stop_send,
+                )
+                .await
+                {
+                    connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
+                    error!(
+                        "Failed to start RdbBridgePlugin with error message: {:?}",
+                        e
+                    );
+                }
+            }
+            ConnectorType::Redis => {
+                let redis_config = match serde_json::from_str::<RedisConnectorConfig>(
+                    &connector.config,
+                ) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!("Failed to parse RedisConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
+                        return;
+                    }
+                };
+
+                let bridge = match RedisBridgePlugin::new(redis_config) {
+                    Ok(bridge) => bridge,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!(
+                            "Failed to create RedisBridgePlugin with error message: {:?}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                if let Err(e) = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
+                        topic_id: connector.topic_id,
+                        record_num: 100,
+                    },
+                    thread.stop_send,
+                )
+                .await
+                {
+                    connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
+                    error!(
+                        "Failed to start RedisBridgePlugin with error message: {:?}",
+                        e
+                    );
+                }
+            }
+            ConnectorType::InfluxDb => {
+                let influxdb_config = match serde_json::from_str::<InfluxDbConnectorConfig>(
+                    &connector.config,
+                ) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!("Failed to parse InfluxDbConnectorConfig file with error message :{}, configuration contents: {}", e, connector.config);
+                        return;
+                    }
+                };
+
+                let bridge = match InfluxDbBridgePlugin::new(influxdb_config) {
+                    Ok(bridge) => bridge,
+                    Err(e) => {
+                        record_connector_down(&connector.connector_name, e.to_string());
+                        error!(
+                            "Failed to create InfluxDbBridgePlugin with error message: {:?}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                connector_manager.add_connector_thread(&connector.connector_name, thread.clone());
+
+                if let Err(e) = run_bridge_plugin(
+                    &bridge,
+                    &connector_manager,
+                    &cache_manager,
+                    &message_storage,
+                    &connector.connector_name,
+                    BridgePluginReadConfig {
+                        topic_id: connector.topic_id,
+                        record_num: 100,
+                    },
+                    thread.stop_send,
+                )
+                .await
+                {
+                    connector_manager.remove_connector_thread(&connector.connector_name);
+                    record_connector_down(&connector.connector_name, e.to_string());
+                    error!(
+                        "Failed to start InfluxDbBridgePlugin with error message: {:?}",
                         e
                     );
                 }
             }
-            ConnectorType::Kafka => {}
         }
     });
 }
@@ -190,3 +750,24 @@ fn stop_thread(thread: BridgePluginThread) -> Result<(), MqttBrokerError> {
     thread.stop_send.send(true)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use metadata_struct::adapter::record::Record;
+
+    use super::next_read_offset;
+
+    #[test]
+    fn next_read_offset_test() {
+        assert_eq!(next_read_offset(5, &[]), 5);
+
+        let mut records = vec![
+            Record::build_str("a".to_string()),
+            Record::build_str("b".to_string()),
+        ];
+        assert_eq!(next_read_offset(5, &records), 7);
+
+        records[1].offset = Some(10);
+        assert_eq!(next_read_offset(5, &records), 11);
+    }
+}
@@ -12,111 +12,367 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{sync::Arc, time::Duration};
-
-use super::core::{BridgePlugin, BridgePluginReadConfig};
-use super::manager::ConnectorManager;
-use crate::{handler::error::MqttBrokerError, storage::message::MessageStorage};
+use super::core::BridgePlugin;
+use crate::handler::error::MqttBrokerError;
 use axum::async_trait;
-use log::error;
+use chrono::Local;
+use common_base::tools::now_second;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::warn;
 use metadata_struct::{
-    adapter::record::Record, mqtt::bridge::config_local_file::LocalFileConnectorConfig,
+    adapter::record::Record,
+    mqtt::bridge::config_local_file::{LocalFileConnectorConfig, LocalFileFormat},
+    mqtt::message::MqttMessage,
 };
-use storage_adapter::storage::StorageAdapter;
-use tokio::fs::File;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
 use tokio::io::{AsyncWriteExt, BufWriter};
-use tokio::{fs::OpenOptions, select, sync::broadcast, time::sleep};
+use tokio::sync::Mutex;
+
+const CSV_HEADER: [&str; 6] = ["timestamp", "clientid", "topic", "qos", "retain", "payload"];
 
-pub struct FileBridgePlugin<S> {
-    connector_manager: Arc<ConnectorManager>,
-    message_storage: Arc<S>,
-    connector_name: String,
+struct ActiveFile {
+    writer: BufWriter<File>,
+    size: u64,
+    opened_at: u64,
+}
+
+pub struct FileBridgePlugin {
     config: LocalFileConnectorConfig,
-    stop_send: broadcast::Sender<bool>,
+    active: Mutex<Option<ActiveFile>>,
 }
 
-impl<S> FileBridgePlugin<S>
-where
-    S: StorageAdapter + Sync + Send + 'static + Clone,
-{
-    pub fn new(
-        connector_manager: Arc<ConnectorManager>,
-        message_storage: Arc<S>,
-        connector_name: String,
-        config: LocalFileConnectorConfig,
-        stop_send: broadcast::Sender<bool>,
-    ) -> Self {
+impl FileBridgePlugin {
+    pub fn new(config: LocalFileConnectorConfig) -> Self {
         FileBridgePlugin {
-            connector_manager,
-            message_storage,
-            connector_name,
             config,
-            stop_send,
+            active: Mutex::new(None),
+        }
+    }
+
+    async fn open(&self) -> Result<ActiveFile, MqttBrokerError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.local_file_path)
+            .await?;
+        let mut size = file.metadata().await?.len();
+        let mut writer = BufWriter::new(file);
+        if size == 0 && self.config.format == LocalFileFormat::Csv {
+            let header = encode_csv_row(&CSV_HEADER)?;
+            writer.write_all(&header).await?;
+            size += header.len() as u64;
         }
+        Ok(ActiveFile {
+            writer,
+            size,
+            opened_at: now_second(),
+        })
     }
 
-    pub async fn append(
+    fn rotation_due(&self, file: &ActiveFile) -> bool {
+        (self.config.rotate_size_bytes > 0 && file.size >= self.config.rotate_size_bytes)
+            || (self.config.rotate_interval_secs > 0
+                && now_second().saturating_sub(file.opened_at) >= self.config.rotate_interval_secs)
+    }
+
+    async fn write_records(
         &self,
-        records: &Vec<Record>,
-        writer: &mut BufWriter<File>,
+        file: &mut ActiveFile,
+        records: &[Record],
     ) -> Result<(), MqttBrokerError> {
         for record in records {
-            let data = serde_json::to_string(record)?;
-            writer.write_all(data.as_ref()).await?;
+            let message = MqttMessage::decode_record(record.clone())?;
+            let data = encode_message(&self.config.format, &message)?;
+            file.writer.write_all(&data).await?;
+            file.size += data.len() as u64;
+        }
+        file.writer.flush().await?;
+        Ok(())
+    }
+
+    // A failed rotation only delays it, the data is already on disk and the
+    // active file is reopened in append mode on the next batch.
+    async fn rotate(&self, active: &mut Option<ActiveFile>) {
+        *active = None;
+        if let Err(e) = self.rotate_file().await {
+            warn!(
+                "Failed to rotate local file {}, error message: {}",
+                self.config.local_file_path, e
+            );
+        }
+    }
+
+    async fn rotate_file(&self) -> Result<(), MqttBrokerError> {
+        let path = Path::new(&self.config.local_file_path);
+        let target = rotated_path(path, &self.config.rotated_file_template).await?;
+        fs::rename(path, &target).await?;
+
+        if self.config.gzip {
+            tokio::task::spawn_blocking(move || gzip_file(&target))
+                .await
+                .map_err(|e| MqttBrokerError::CommonError(e.to_string()))??;
+        }
+
+        if self.config.retention_count > 0 {
+            remove_expired_files(
+                path,
+                &self.config.rotated_file_template,
+                self.config.retention_count,
+            )
+            .await?;
         }
-        writer.flush().await?;
         Ok(())
     }
 }
 
 #[async_trait]
-impl<S> BridgePlugin for FileBridgePlugin<S>
-where
-    S: StorageAdapter + Sync + Send + 'static + Clone,
-{
-    async fn exec(&self, config: BridgePluginReadConfig) -> Result<(), MqttBrokerError> {
-        let message_storage = MessageStorage::new(self.message_storage.clone());
-        let group_name = self.connector_name.clone();
-        let offset = message_storage.get_group_offset(&group_name).await?;
-        let mut recv = self.stop_send.subscribe();
-        let file = OpenOptions::new()
-            .append(true)
-            .open(self.config.local_file_path.clone())
-            .await?;
-        let mut writer = tokio::io::BufWriter::new(file);
-
-        loop {
-            select! {
-                val = recv.recv() =>{
-                    if let Ok(flag) = val {
-                        if flag {
-                            break;
-                        }
-                    }
-                },
-
-                val = message_storage.read_topic_message(&config.topic_id, offset, config.record_num) => {
-                    match val {
-                        Ok(data) => {
-                            self.connector_manager.report_heartbeat(&self.connector_name);
-                            if data.is_empty() {
-                                sleep(Duration::from_millis(100)).await;
-                                continue;
-                            }
-
-                            if let Err(e) = self.append(&data,&mut writer).await{
-                                error!("Connector {} failed to write data to {}, error message :{}", self.connector_name,self.config.local_file_path, e);
-                                sleep(Duration::from_millis(100)).await;
-                            }
-                        },
-                        Err(e) => {
-                            error!("Connector {} failed to read Topic {} data with error message :{}", self.connector_name,config.topic_id,e);
-                            sleep(Duration::from_millis(100)).await;
-                        }
-                    }
-                }
-            }
+impl BridgePlugin for FileBridgePlugin {
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        let mut active = self.active.lock().await;
+        if active.as_ref().is_some_and(|file| self.rotation_due(file)) {
+            self.rotate(&mut active).await;
+        }
+
+        if active.is_none() {
+            *active = Some(self.open().await?);
+        }
+
+        let result = self.write_records(active.as_mut().unwrap(), records).await;
+        if result.is_err() {
+            // reopen the file on the next attempt, the buffered data is rewritten by the retry
+            *active = None;
+            return result;
+        }
+
+        // Size based rotation happens after the batch that crosses the limit.
+        if active.as_ref().is_some_and(|file| self.rotation_due(file)) {
+            self.rotate(&mut active).await;
         }
         Ok(())
     }
 }
+
+fn encode_message(
+    format: &LocalFileFormat,
+    message: &MqttMessage,
+) -> Result<Vec<u8>, MqttBrokerError> {
+    match format {
+        LocalFileFormat::JsonLines => {
+            let payload = serde_json::from_slice::<Value>(&message.payload).unwrap_or_else(|_| {
+                Value::String(String::from_utf8_lossy(&message.payload).to_string())
+            });
+            let mut line = serde_json::to_vec(&json!({
+                "timestamp": message.create_time,
+                "clientid": message.client_id,
+                "topic": String::from_utf8_lossy(&message.topic),
+                "qos": message.qos as u8,
+                "retain": message.retain,
+                "payload": payload,
+            }))?;
+            line.push(b'\n');
+            Ok(line)
+        }
+        LocalFileFormat::Csv => encode_csv_row(&[
+            message.create_time.to_string().as_str(),
+            message.client_id.as_str(),
+            &String::from_utf8_lossy(&message.topic),
+            (message.qos as u8).to_string().as_str(),
+            message.retain.to_string().as_str(),
+            &String::from_utf8_lossy(&message.payload),
+        ]),
+        LocalFileFormat::Raw => {
+            let mut line = message.payload.to_vec();
+            line.push(b'\n');
+            Ok(line)
+        }
+    }
+}
+
+fn encode_csv_row(fields: &[&str]) -> Result<Vec<u8>, MqttBrokerError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(fields)
+        .map_err(|e| MqttBrokerError::CommonError(e.to_string()))?;
+    writer
+        .into_inner()
+        .map_err(|e| MqttBrokerError::CommonError(e.to_string()))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub fn render_rotated_file_name(template: &str, active_file_name: &str) -> String {
+    template
+        .replace("${name}", active_file_name)
+        .replace(
+            "${datetime}",
+            &Local::now().format("%Y%m%d%H%M%S").to_string(),
+        )
+        .replace("${timestamp}", &now_second().to_string())
+}
+
+// Rotations within the same second get a .1, .2, ... suffix.
+async fn rotated_path(path: &Path, template: &str) -> Result<PathBuf, MqttBrokerError> {
+    let name = render_rotated_file_name(template, &file_name(path));
+    let base = path.with_file_name(&name);
+    let mut candidate = base.clone();
+    let mut index = 0;
+    while fs::try_exists(&candidate).await? || fs::try_exists(gzip_path(&candidate)).await? {
+        index += 1;
+        candidate = path.with_file_name(format!("{}.{}", name, index));
+    }
+    Ok(candidate)
+}
+
+fn gzip_path(path: &Path) -> PathBuf {
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    PathBuf::from(gz)
+}
+
+fn gzip_file(path: &Path) -> Result<(), MqttBrokerError> {
+    let mut input = std::fs::File::open(path)?;
+    let output = std::fs::File::create(gzip_path(path))?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+fn rotated_file_regex(template: &str, active_file_name: &str) -> Result<Regex, MqttBrokerError> {
+    let pattern = regex::escape(template)
+        .replace(&regex::escape("${name}"), &regex::escape(active_file_name))
+        .replace(&regex::escape("${datetime}"), r"\d{14}")
+        .replace(&regex::escape("${timestamp}"), r"\d+");
+    Regex::new(&format!(r"^{}(\.\d+)?(\.gz)?$", pattern))
+        .map_err(|e| MqttBrokerError::CommonError(e.to_string()))
+}
+
+async fn remove_expired_files(
+    path: &Path,
+    template: &str,
+    retention_count: usize,
+) -> Result<(), MqttBrokerError> {
+    let active_file_name = file_name(path);
+    let pattern = rotated_file_regex(template, &active_file_name)?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut rotated = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name != active_file_name && pattern.is_match(&name) {
+            rotated.push((entry.metadata().await?.modified()?, entry.path()));
+        }
+    }
+
+    rotated.sort();
+    let expired = rotated.len().saturating_sub(retention_count);
+    for (_, path) in rotated.into_iter().take(expired) {
+        fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use bytes::Bytes;
+    use common_base::utils::file_utils::test_temp_dir;
+    use flate2::read::GzDecoder;
+    use protocol::mqtt::common::QoS;
+
+    use super::*;
+
+    fn build_message(payload: &str) -> MqttMessage {
+        MqttMessage {
+            client_id: "c1".to_string(),
+            qos: QoS::AtLeastOnce,
+            topic: Bytes::from("sensors/1"),
+            payload: Bytes::from(payload.to_string()),
+            create_time: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn encode_message_test() {
+        let message = build_message(r#"{"temp": 21}"#);
+        let line = encode_message(&LocalFileFormat::JsonLines, &message).unwrap();
+        assert_eq!(line.last(), Some(&b'\n'));
+        let json: Value = serde_json::from_slice(&line).unwrap();
+        assert_eq!(json["payload"]["temp"], 21);
+        assert_eq!(json["clientid"], "c1");
+        assert_eq!(json["qos"], 1);
+
+        let message = build_message(r#"a,"b""#);
+        let line = encode_message(&LocalFileFormat::Csv, &message).unwrap();
+        assert_eq!(
+            String::from_utf8(line).unwrap(),
+            "100,c1,sensors/1,1,false,\"a,\"\"b\"\"\"\n"
+        );
+
+        let line = encode_message(&LocalFileFormat::Raw, &message).unwrap();
+        assert_eq!(line, b"a,\"b\"\n".to_vec());
+    }
+
+    #[test]
+    fn rotated_file_regex_test() {
+        let regex = rotated_file_regex("${name}.${datetime}", "data.log").unwrap();
+        assert!(regex.is_match("data.log.20240101120000"));
+        assert!(regex.is_match("data.log.20240101120000.2.gz"));
+        assert!(!regex.is_match("data.log"));
+        assert!(!regex.is_match("dataxlog.20240101120000"));
+
+        let name = render_rotated_file_name("archive-${timestamp}-${name}", "data.log");
+        let regex = rotated_file_regex("archive-${timestamp}-${name}", "data.log").unwrap();
+        assert!(regex.is_match(&name));
+    }
+
+    #[tokio::test]
+    async fn rotate_test() {
+        let dir = test_temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = format!("{}data.log", dir);
+        let bridge = FileBridgePlugin::new(LocalFileConnectorConfig {
+            local_file_path: path.clone(),
+            format: LocalFileFormat::Raw,
+            rotate_size_bytes: 1,
+            gzip: true,
+            retention_count: 2,
+            ..Default::default()
+        });
+
+        for i in 0..4 {
+            let record = Record::build_byte(build_message(&format!("m{}", i)).encode());
+            bridge.append(&[record]).await.unwrap();
+        }
+
+        assert!(!Path::new(&path).exists());
+        let mut contents = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let entry = entry.unwrap();
+            assert!(entry.file_name().to_string_lossy().ends_with(".gz"));
+            let mut decoder = GzDecoder::new(std::fs::File::open(entry.path()).unwrap());
+            let mut content = String::new();
+            decoder.read_to_string(&mut content).unwrap();
+            contents.push(content);
+        }
+        contents.sort();
+        assert_eq!(contents, vec!["m2\n".to_string(), "m3\n".to_string()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -59,10 +59,15 @@ async fn report_heartbeat(
     let mut heatbeats = Vec::new();
 
     for (connector_name, heartbeat_time) in connector_manager.connector_heartbeat.clone() {
+        let stats = match connector_manager.get_connector_stats(&connector_name) {
+            Some(stats) => serde_json::to_string(&stats).unwrap_or_default(),
+            None => String::new(),
+        };
         heatbeats.push(ConnectorHeartbeatRaw {
             connector_name,
             heartbeat_time,
             broker_id: conf.broker_id,
+            stats,
         });
     }
 
@@ -0,0 +1,270 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use axum::async_trait;
+use log::warn;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::bridge::config_influxdb::{InfluxDbConnectorConfig, InfluxDbVersion};
+use metadata_struct::mqtt::message::MqttMessage;
+
+use super::core::BridgePlugin;
+use super::placeholder::render_placeholders;
+use crate::handler::error::MqttBrokerError;
+
+pub struct InfluxDbBridgePlugin {
+    config: InfluxDbConnectorConfig,
+    client: reqwest::Client,
+}
+
+impl InfluxDbBridgePlugin {
+    pub fn new(config: InfluxDbConnectorConfig) -> Result<Self, MqttBrokerError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()?;
+        Ok(InfluxDbBridgePlugin { config, client })
+    }
+
+    fn build_request(&self, body: String) -> reqwest::RequestBuilder {
+        let url = self.config.url.trim_end_matches('/');
+        match self.config.version {
+            InfluxDbVersion::V1 => {
+                let mut request = self
+                    .client
+                    .post(format!("{}/write", url))
+                    .query(&[("db", self.config.database.as_str()), ("precision", "s")]);
+                if !self.config.username.is_empty() {
+                    request = request.query(&[
+                        ("u", self.config.username.as_str()),
+                        ("p", self.config.password.as_str()),
+                    ]);
+                }
+                request.body(body)
+            }
+            InfluxDbVersion::V2 => self
+                .client
+                .post(format!("{}/api/v2/write", url))
+                .query(&[
+                    ("org", self.config.org.as_str()),
+                    ("bucket", self.config.bucket.as_str()),
+                    ("precision", "s"),
+                ])
+                .header("Authorization", format!("Token {}", self.config.token))
+                .body(body),
+        }
+    }
+}
+
+#[async_trait]
+impl BridgePlugin for InfluxDbBridgePlugin {
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        let mut lines = Vec::with_capacity(records.len());
+        for record in records {
+            let message = MqttMessage::decode_record(record.clone())?;
+            match build_line(&self.config, &message) {
+                Some(line) => lines.push(line),
+                None => warn!(
+                    "Skipping message from {} on {}, no field could be rendered",
+                    message.client_id,
+                    String::from_utf8_lossy(&message.topic)
+                ),
+            }
+        }
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let response = self.build_request(lines.join("\n")).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(MqttBrokerError::CommonError(format!(
+                "influxdb write failed with {}: {}",
+                status, body
+            )));
+        }
+        Ok(())
+    }
+}
+
+// Renders one point in line protocol, or None when every field rendered empty.
+fn build_line(config: &InfluxDbConnectorConfig, message: &MqttMessage) -> Option<String> {
+    let fields: Vec<String> = config
+        .fields
+        .iter()
+        .filter_map(|(key, template)| {
+            render_field(template, message).map(|value| format!("{}={}", escape_key(key), value))
+        })
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+
+    let mut line = escape_measurement(&render_placeholders(&config.measurement, message));
+    for (key, template) in config.tags.iter() {
+        let value = render_placeholders(template, message);
+        if value.is_empty() {
+            continue;
+        }
+        line.push_str(&format!(",{}={}", escape_key(key), escape_key(&value)));
+    }
+    line.push(' ');
+    line.push_str(&fields.join(","));
+    line.push_str(&format!(" {}", message.create_time));
+    Some(line)
+}
+
+// A template ending in "}i" renders an integer field, values that are not integers are skipped.
+fn render_field(template: &str, message: &MqttMessage) -> Option<String> {
+    if let Some(template) = template.strip_suffix('i').filter(|t| t.ends_with('}')) {
+        let value = render_placeholders(template, message);
+        return value.parse::<i64>().ok().map(|v| format!("{}i", v));
+    }
+
+    let value = render_placeholders(template, message);
+    if value.is_empty() {
+        return None;
+    }
+    if value == "true" || value == "false" || value.parse::<f64>().is_ok() {
+        return Some(value);
+    }
+    Some(format!(
+        "\"{}\"",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+fn escape_measurement(value: &str) -> String {
+    value.replace(',', "\\,").replace(' ', "\\ ")
+}
+
+fn escape_key(value: &str) -> String {
+    value
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use axum::extract::{Query, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use bytes::Bytes;
+    use tokio::net::TcpListener;
+    use tokio::sync::Mutex;
+
+    use super::*;
+
+    fn build_message(client_id: &str, payload: &str, create_time: u64) -> MqttMessage {
+        MqttMessage {
+            client_id: client_id.to_string(),
+            topic: Bytes::from("sensors/room 1"),
+            payload: Bytes::from(payload.to_string()),
+            create_time,
+            ..Default::default()
+        }
+    }
+
+    fn build_config(url: String) -> InfluxDbConnectorConfig {
+        InfluxDbConnectorConfig {
+            url,
+            org: "robustmq".to_string(),
+            bucket: "mqtt".to_string(),
+            token: "secret".to_string(),
+            measurement: "readings".to_string(),
+            tags: BTreeMap::from([
+                ("device".to_string(), "${clientid}".to_string()),
+                ("topic".to_string(), "${topic}".to_string()),
+            ]),
+            fields: BTreeMap::from([
+                ("temp".to_string(), "${payload.temp}".to_string()),
+                ("count".to_string(), "${payload.count}i".to_string()),
+                ("ok".to_string(), "${payload.ok}".to_string()),
+                ("note".to_string(), "${payload.note}".to_string()),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn build_line_test() {
+        let config = build_config(String::new());
+        let message = build_message(
+            "c1",
+            r#"{"temp": 21.5, "count": 3, "ok": true, "note": "say \"hi\""}"#,
+            100,
+        );
+        assert_eq!(
+            build_line(&config, &message).unwrap(),
+            r#"readings,device=c1,topic=sensors/room\ 1 count=3i,note="say \"hi\"",ok=true,temp=21.5 100"#
+        );
+
+        let message = build_message("c1", "not json", 100);
+        assert_eq!(build_line(&config, &message), None);
+    }
+
+    type Writes = Arc<Mutex<Vec<(BTreeMap<String, String>, String, String)>>>;
+
+    async fn write_handler(
+        State(writes): State<Writes>,
+        Query(query): Query<BTreeMap<String, String>>,
+        headers: HeaderMap,
+        body: String,
+    ) -> StatusCode {
+        let auth = headers
+            .get("Authorization")
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        writes.lock().await.push((query, auth, body));
+        StatusCode::NO_CONTENT
+    }
+
+    #[tokio::test]
+    async fn influxdb_stand_in_test() {
+        let writes = Writes::default();
+        let router = Router::new()
+            .route("/api/v2/write", post(write_handler))
+            .with_state(writes.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        let plugin = InfluxDbBridgePlugin::new(build_config(url)).unwrap();
+        let records = vec![
+            Record::build_byte(build_message("c1", r#"{"temp": 20}"#, 100).encode()),
+            Record::build_byte(build_message("c2", r#"{"temp": 21}"#, 101).encode()),
+        ];
+        plugin.append(&records).await.unwrap();
+
+        let writes = writes.lock().await;
+        assert_eq!(writes.len(), 1);
+        let (query, auth, body) = &writes[0];
+        assert_eq!(query.get("bucket").unwrap(), "mqtt");
+        assert_eq!(query.get("precision").unwrap(), "s");
+        assert_eq!(auth, "Token secret");
+        assert_eq!(
+            body,
+            "readings,device=c1,topic=sensors/room\\ 1 temp=20 100\n\
+             readings,device=c2,topic=sensors/room\\ 1 temp=21 101"
+        );
+    }
+}
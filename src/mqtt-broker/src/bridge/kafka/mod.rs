@@ -12,122 +12,220 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{sync::Arc, time::Duration};
+use std::time::Duration;
 
 use axum::async_trait;
-use log::{error, info};
-use metadata_struct::{adapter::record::Record, mqtt::bridge::config_kafka::KafkaConnectorConfig};
-use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
-use storage_adapter::storage::StorageAdapter;
-use tokio::{select, sync::broadcast, time::sleep};
-
-use crate::{handler::error::MqttBrokerError, storage::message::MessageStorage};
-
-use super::{
-    core::{BridgePlugin, BridgePluginReadConfig},
-    manager::ConnectorManager,
-};
-
-pub struct KafkaBridgePlugin<S> {
-    connector_manager: Arc<ConnectorManager>,
-    message_storage: Arc<S>,
-    connector_name: String,
+use futures::future::join_all;
+use log::error;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::bridge::config_kafka::{KafkaConnectorConfig, KafkaDeliveryErrorPolicy};
+use metadata_struct::mqtt::message::MqttMessage;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+use crate::handler::error::MqttBrokerError;
+
+use super::core::BridgePlugin;
+
+pub struct KafkaBridgePlugin {
     config: KafkaConnectorConfig,
-    stop_send: broadcast::Sender<bool>,
+    producer: FutureProducer,
 }
 
-impl<S> KafkaBridgePlugin<S>
-where
-    S: StorageAdapter + Sync + Send + 'static + Clone,
-{
-    pub fn new(
-        connector_manager: Arc<ConnectorManager>,
-        message_storage: Arc<S>,
-        connector_name: String,
-        config: KafkaConnectorConfig,
-        stop_send: broadcast::Sender<bool>,
-    ) -> Self {
-        KafkaBridgePlugin {
-            connector_manager,
-            message_storage,
-            connector_name,
-            config,
-            stop_send,
-        }
+impl KafkaBridgePlugin {
+    pub fn new(config: KafkaConnectorConfig) -> Result<Self, MqttBrokerError> {
+        let producer: FutureProducer = build_client_config(&config).create()?;
+        Ok(KafkaBridgePlugin { config, producer })
     }
+}
 
-    pub async fn append(
-        &self,
-        records: &Vec<Record>,
-        producer: FutureProducer,
-    ) -> Result<(), MqttBrokerError> {
+#[async_trait]
+impl BridgePlugin for KafkaBridgePlugin {
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        let mut messages = Vec::with_capacity(records.len());
         for record in records {
-            let data = serde_json::to_string(record)?;
-            producer
-                .send(
-                    FutureRecord::to(self.config.topic.as_str())
-                        .key(self.config.key.as_str())
-                        .payload(&data),
-                    Duration::from_secs(0),
-                )
-                .await
-                .map_err(|(e, _)| e)?;
+            let message = MqttMessage::decode_record(record.clone())?;
+            let key = render_key(&self.config.key, &message);
+            messages.push((key, message));
         }
 
-        producer.flush(Duration::from_secs(0))?;
-        Ok(())
-    }
-}
+        // Everything is handed to the producer first so that it can batch.
+        let deliveries = messages.iter().map(|(key, message)| {
+            let mut headers = OwnedHeaders::new_with_capacity(message.user_properties.len());
+            for (name, value) in message.user_properties.iter() {
+                headers = headers.insert(Header {
+                    key: name.as_str(),
+                    value: Some(value.as_str()),
+                });
+            }
 
-#[async_trait]
-impl<S> BridgePlugin for KafkaBridgePlugin<S>
-where
-    S: StorageAdapter + Sync + Send + 'static + Clone,
-{
-    async fn exec(&self, config: BridgePluginReadConfig) -> Result<(), MqttBrokerError> {
-        let message_storage = MessageStorage::new(self.message_storage.clone());
-        let group_name = self.connector_name.clone();
-        let offset = message_storage.get_group_offset(&group_name).await?;
-        let mut recv = self.stop_send.subscribe();
-        let producer: FutureProducer = rdkafka::ClientConfig::new()
-            .set("bootstrap.servers", self.config.bootstrap_servers.as_str())
-            .set("message.timeout.ms", "5000")
-            .create()?;
-
-        loop {
-            select! {
-                val = recv.recv() =>{
-                    if let Ok(flag) = val {
-                        if flag {
-                            info!("{}","Connector thread exited successfully");
-                            break;
-                        }
-                    }
-                }
+            let mut kafka_record = FutureRecord::<str, [u8]>::to(&self.config.topic)
+                .payload(&message.payload)
+                .headers(headers);
+            if !key.is_empty() {
+                kafka_record = kafka_record.key(key.as_str());
+            }
+            self.producer.send(
+                kafka_record,
+                Duration::from_millis(self.config.message_timeout_ms),
+            )
+        });
 
-                val = message_storage.read_topic_message(&config.topic_id, offset, config.record_num) => {
-                    match val {
-                        Ok(data) => {
-                            self.connector_manager.report_heartbeat(&self.connector_name);
-                            if data.is_empty() {
-                                tokio::time::sleep(Duration::from_millis(100)).await;
-                                continue;
-                            }
-
-                            if let Err(e) = self.append(&data, producer.clone()).await{
-                                error!("Connector {} failed to write data to kafka topic {}, error message: {}", self.connector_name, self.config.topic, e);
-                                sleep(Duration::from_millis(100)).await;
-                            }
-                        },
-                        Err(e) => {
-                            error!("Connector {} failed to read Topic {} data with error message :{}", self.connector_name,config.topic_id,e);
-                            sleep(Duration::from_millis(100)).await;
-                        }
+        for result in join_all(deliveries).await {
+            if let Err((e, _)) = result {
+                match self.config.delivery_error_policy {
+                    KafkaDeliveryErrorPolicy::Retry => return Err(e.into()),
+                    KafkaDeliveryErrorPolicy::Skip => {
+                        error!(
+                            "Skipping record that could not be delivered to kafka topic {}, error message: {}",
+                            self.config.topic, e
+                        );
                     }
                 }
             }
         }
-
         Ok(())
     }
 }
+
+fn build_client_config(config: &KafkaConnectorConfig) -> ClientConfig {
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", config.bootstrap_servers.as_str())
+        .set("acks", config.acks.as_str())
+        .set("compression.type", config.compression.as_str())
+        .set("batch.size", config.batch_size.to_string())
+        .set("linger.ms", config.linger_ms.to_string())
+        .set("message.timeout.ms", config.message_timeout_ms.to_string());
+
+    let security_protocol = match (&config.sasl, &config.tls) {
+        (Some(_), Some(_)) => "SASL_SSL",
+        (Some(_), None) => "SASL_PLAINTEXT",
+        (None, Some(_)) => "SSL",
+        (None, None) => "PLAINTEXT",
+    };
+    client_config.set("security.protocol", security_protocol);
+
+    if let Some(sasl) = &config.sasl {
+        client_config
+            .set("sasl.mechanisms", sasl.mechanism.as_str())
+            .set("sasl.username", sasl.username.as_str())
+            .set("sasl.password", sasl.password.as_str());
+    }
+
+    if let Some(tls) = &config.tls {
+        client_config.set("ssl.ca.location", tls.ca_path.as_str());
+        if let Some(cert_path) = &tls.cert_path {
+            client_config.set("ssl.certificate.location", cert_path.as_str());
+        }
+        if let Some(key_path) = &tls.key_path {
+            client_config.set("ssl.key.location", key_path.as_str());
+        }
+    }
+    client_config
+}
+
+fn render_key(template: &str, message: &MqttMessage) -> String {
+    template
+        .replace("${clientid}", &message.client_id)
+        .replace("${topic}", &String::from_utf8_lossy(&message.topic))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use rdkafka::consumer::{BaseConsumer, Consumer};
+    use rdkafka::message::Headers;
+    use rdkafka::mocking::MockCluster;
+    use rdkafka::Message;
+
+    use super::*;
+
+    fn build_record(client_id: &str, topic: &str, payload: &str) -> Record {
+        let message = MqttMessage {
+            client_id: client_id.to_string(),
+            topic: Bytes::from(topic.to_string()),
+            payload: Bytes::from(payload.to_string()),
+            user_properties: vec![("site".to_string(), "a".to_string())],
+            ..Default::default()
+        };
+        Record::build_byte(message.encode())
+    }
+
+    #[test]
+    fn render_key_test() {
+        let message = MqttMessage {
+            client_id: "c1".to_string(),
+            topic: Bytes::from("sensors/1"),
+            ..Default::default()
+        };
+        assert_eq!(render_key("${clientid}-${topic}", &message), "c1-sensors/1");
+        assert_eq!(render_key("fixed", &message), "fixed");
+    }
+
+    #[test]
+    fn build_client_config_test() {
+        let config = KafkaConnectorConfig {
+            bootstrap_servers: "localhost:9092".to_string(),
+            sasl: Some(Default::default()),
+            tls: Some(Default::default()),
+            ..Default::default()
+        };
+        let client_config = build_client_config(&config);
+        assert_eq!(client_config.get("security.protocol"), Some("SASL_SSL"));
+        assert_eq!(client_config.get("acks"), Some("all"));
+    }
+
+    #[tokio::test]
+    async fn mock_cluster_append_test() {
+        let cluster = MockCluster::new(1).unwrap();
+        cluster.create_topic("mqtt", 1, 1).unwrap();
+
+        let config = KafkaConnectorConfig {
+            bootstrap_servers: cluster.bootstrap_servers(),
+            topic: "mqtt".to_string(),
+            key: "${clientid}".to_string(),
+            ..Default::default()
+        };
+        let plugin = KafkaBridgePlugin::new(config).unwrap();
+        plugin
+            .append(&[
+                build_record("c1", "sensors/1", "m1"),
+                build_record("c2", "sensors/2", "m2"),
+            ])
+            .await
+            .unwrap();
+
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", cluster.bootstrap_servers())
+            .set("group.id", "mock_cluster_append_test")
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .unwrap();
+        consumer.subscribe(&["mqtt"]).unwrap();
+
+        let mut received = Vec::new();
+        for _ in 0..50 {
+            if let Some(Ok(message)) = consumer.poll(Duration::from_millis(200)) {
+                let header = message.headers().unwrap().get(0);
+                assert_eq!(header.key, "site");
+                assert_eq!(header.value, Some("a".as_bytes()));
+                received.push((
+                    String::from_utf8(message.key().unwrap().to_vec()).unwrap(),
+                    String::from_utf8(message.payload().unwrap().to_vec()).unwrap(),
+                ));
+            }
+            if received.len() == 2 {
+                break;
+            }
+        }
+        assert_eq!(
+            received,
+            vec![
+                ("c1".to_string(), "m1".to_string()),
+                ("c2".to_string(), "m2".to_string())
+            ]
+        );
+    }
+}
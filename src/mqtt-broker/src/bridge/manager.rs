@@ -15,6 +15,7 @@
 use common_base::tools::now_second;
 use dashmap::DashMap;
 use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+use metadata_struct::mqtt::bridge::status::MQTTConnectorStats;
 
 use super::core::BridgePluginThread;
 
@@ -41,8 +42,13 @@ pub fn new() -> Self {
 
     // Connector
     pub fn add_connector(&self, connector: &MQTTConnector) {
+        let mut connector = connector.clone();
+        // delivery stats are tracked by the running thread, keep them across metadata updates
+        if let Some(current) = self.connector_list.get(&connector.connector_name) {
+            connector.stats = current.stats.clone();
+        }
         self.connector_list
-            .insert(connector.connector_name.clone(), connector.clone());
+            .insert(connector.connector_name.clone(), connector);
     }
 
     pub fn get_connector(&self, connector_name: &str) -> Option<MQTTConnector> {
@@ -65,6 +71,21 @@ pub fn remove_connector(&self, connector_name: &str) {
         self.connector_list.remove(connector_name);
     }
 
+    pub fn update_connector_stats<F>(&self, connector_name: &str, f: F)
+    where
+        F: FnOnce(&mut MQTTConnectorStats),
+    {
+        if let Some(mut connector) = self.connector_list.get_mut(connector_name) {
+            f(&mut connector.stats);
+        }
+    }
+
+    pub fn get_connector_stats(&self, connector_name: &str) -> Option<MQTTConnectorStats> {
+        self.connector_list
+            .get(connector_name)
+            .map(|connector| connector.stats.clone())
+    }
+
     // Connector Thread
     pub fn add_connector_thread(&self, connector_name: &str, thread: BridgePluginThread) {
         self.connector_thread
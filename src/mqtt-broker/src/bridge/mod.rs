@@ -15,5 +15,11 @@
 pub mod core;
 pub mod file;
 pub mod heartbeat;
+pub mod influxdb;
 pub mod kafka;
 pub mod manager;
+pub mod mqtt;
+pub mod placeholder;
+pub mod rdb;
+pub mod redis;
+pub mod webhook;
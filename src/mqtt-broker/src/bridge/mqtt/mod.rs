@@ -0,0 +1,368 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::async_trait;
+use bytes::Bytes;
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::pool::ClientPool;
+use log::{error, info, warn};
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::bridge::config_mqtt::{MqttBridgeConnectorConfig, MqttBridgeDirection};
+use metadata_struct::mqtt::message::MqttMessage;
+use paho_mqtt::{
+    AsyncClient, AsyncReceiver, ConnectOptions, ConnectOptionsBuilder, CreateOptionsBuilder,
+    Message, MessageBuilder, Properties, PropertyCode, SslOptionsBuilder,
+};
+use protocol::mqtt::common::{qos, Publish, PublishProperties};
+use storage_adapter::storage::StorageAdapter;
+use tokio::sync::{broadcast, Mutex};
+use tokio::{select, time::sleep};
+
+use super::core::{BridgePlugin, RETRY_BACKOFF_MAX_MS, RETRY_BACKOFF_MIN_MS};
+use super::manager::ConnectorManager;
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::topic::try_init_topic;
+use crate::storage::message::MessageStorage;
+
+// User property stamped on every bridged message, carrying the cluster (egress)
+// or remote server (ingress) the message came from. Messages that already carry
+// it are never forwarded again, which keeps two bridged sites from looping.
+pub const BRIDGE_ORIGIN_PROPERTY: &str = "robustmq-bridge-origin";
+
+pub struct MqttBridgePlugin {
+    config: MqttBridgeConnectorConfig,
+    client: AsyncClient,
+    stream: Mutex<Option<AsyncReceiver<Option<Message>>>>,
+    connect_lock: Mutex<()>,
+}
+
+impl MqttBridgePlugin {
+    pub fn new(config: MqttBridgeConnectorConfig) -> Result<Self, MqttBrokerError> {
+        let create_opts = CreateOptionsBuilder::new()
+            .server_uri(config.server.as_str())
+            .client_id(config.client_id.as_str())
+            .finalize();
+        let mut client = AsyncClient::new(create_opts)?;
+        // The stream has to be created before the first connect.
+        let stream = client.get_stream(1024);
+        Ok(MqttBridgePlugin {
+            config,
+            client,
+            stream: Mutex::new(Some(stream)),
+            connect_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn direction(&self) -> &MqttBridgeDirection {
+        &self.config.direction
+    }
+
+    pub async fn close(&self) {
+        if self.client.is_connected() {
+            if let Err(e) = self.client.disconnect(None).await {
+                warn!(
+                    "Failed to disconnect mqtt bridge from {}, error message: {}",
+                    self.config.server, e
+                );
+            }
+        }
+    }
+
+    // Subscribes on the remote broker and writes everything it receives into the
+    // local topics until the stop signal arrives, reconnecting with backoff.
+    pub async fn run_ingress<S>(
+        &self,
+        connector_manager: &Arc<ConnectorManager>,
+        cache_manager: &Arc<CacheManager>,
+        message_storage_adapter: &Arc<S>,
+        client_pool: &Arc<ClientPool>,
+        connector_name: &str,
+        stop_send: broadcast::Sender<bool>,
+    ) -> Result<(), MqttBrokerError>
+    where
+        S: StorageAdapter + Sync + Send + 'static + Clone,
+    {
+        let Some(stream) = self.stream.lock().await.take() else {
+            return Err(MqttBrokerError::CommonError(format!(
+                "mqtt bridge {} ingress is already running",
+                connector_name
+            )));
+        };
+        let mut recv = stop_send.subscribe();
+        let mut backoff_ms = RETRY_BACKOFF_MIN_MS;
+
+        loop {
+            if !self.client.is_connected() {
+                if let Err(e) = self.ensure_connected().await {
+                    warn!(
+                        "Connector {} failed to connect to {}, retrying in {}ms, error message: {}",
+                        connector_name, self.config.server, backoff_ms, e
+                    );
+                    connector_manager.update_connector_stats(connector_name, |stats| {
+                        stats.failed_count += 1;
+                        stats.last_error = Some(e.to_string());
+                    });
+                    select! {
+                        val = recv.recv() => {
+                            if let Ok(true) = val {
+                                break;
+                            }
+                        }
+                        _ = sleep(Duration::from_millis(backoff_ms)) => {}
+                    }
+                    backoff_ms = (backoff_ms * 2).min(RETRY_BACKOFF_MAX_MS);
+                    continue;
+                }
+                backoff_ms = RETRY_BACKOFF_MIN_MS;
+            }
+
+            select! {
+                val = recv.recv() => {
+                    if let Ok(true) = val {
+                        info!("Connector {} ingress exited successfully", connector_name);
+                        break;
+                    }
+                }
+
+                _ = sleep(Duration::from_secs(1)) => {
+                    connector_manager.report_heartbeat(connector_name);
+                }
+
+                val = stream.recv() => {
+                    match val {
+                        Ok(Some(message)) => {
+                            connector_manager.report_heartbeat(connector_name);
+                            match self
+                                .publish_local(
+                                    message,
+                                    cache_manager,
+                                    message_storage_adapter,
+                                    client_pool,
+                                )
+                                .await
+                            {
+                                Ok(true) => {
+                                    connector_manager.update_connector_stats(connector_name, |stats| {
+                                        stats.delivered_count += 1;
+                                        stats.last_error = None;
+                                    });
+                                }
+                                Ok(false) => {}
+                                Err(e) => {
+                                    error!(
+                                        "Connector {} failed to publish bridged message locally, error message: {}",
+                                        connector_name, e
+                                    );
+                                    connector_manager.update_connector_stats(connector_name, |stats| {
+                                        stats.failed_count += 1;
+                                        stats.last_error = Some(e.to_string());
+                                    });
+                                }
+                            }
+                        }
+                        Ok(None) => {
+                            warn!(
+                                "Connector {} lost connection to {}",
+                                connector_name, self.config.server
+                            );
+                        }
+                        Err(e) => {
+                            return Err(MqttBrokerError::CommonError(e.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Returns false when the message was dropped by loop prevention. Anything that
+    // already carries an origin was bridged before, by us or by another site.
+    async fn publish_local<S>(
+        &self,
+        message: Message,
+        cache_manager: &Arc<CacheManager>,
+        message_storage_adapter: &Arc<S>,
+        client_pool: &Arc<ClientPool>,
+    ) -> Result<bool, MqttBrokerError>
+    where
+        S: StorageAdapter + Sync + Send + 'static + Clone,
+    {
+        let mut user_properties: Vec<(String, String)> = message.properties().user_iter().collect();
+        if bridge_origin(&user_properties).is_some() {
+            return Ok(false);
+        }
+        user_properties.push((
+            BRIDGE_ORIGIN_PROPERTY.to_string(),
+            self.config.server.clone(),
+        ));
+
+        let topic_name = map_topic(&self.config.local_topic_prefix, message.topic());
+        let topic = try_init_topic(
+            &topic_name,
+            cache_manager,
+            message_storage_adapter,
+            client_pool,
+        )
+        .await?;
+
+        let publish = Publish {
+            dup: false,
+            qos: qos(downgrade_qos(message.qos() as u8, self.config.max_qos)).unwrap_or_default(),
+            pkid: 0,
+            retain: message.retained(),
+            topic: Bytes::from(topic_name),
+            payload: Bytes::copy_from_slice(message.payload()),
+        };
+        let properties = Some(PublishProperties {
+            user_properties,
+            ..Default::default()
+        });
+        if let Some(record) =
+            MqttMessage::build_record(&self.config.client_id, &publish, &properties, 0)
+        {
+            let message_storage =
+                MessageStorage::new_by_topic(message_storage_adapter.clone(), &topic.topic_name);
+            message_storage
+                .append_topic_message(&topic.topic_id, vec![record])
+                .await?;
+        }
+        Ok(true)
+    }
+
+    async fn ensure_connected(&self) -> Result<(), MqttBrokerError> {
+        let _guard = self.connect_lock.lock().await;
+        if self.client.is_connected() {
+            return Ok(());
+        }
+
+        self.client.connect(self.build_connect_options()?).await?;
+        if self.config.direction != MqttBridgeDirection::Egress
+            && !self.config.subscribe_topics.is_empty()
+        {
+            let qos = vec![self.config.max_qos as i32; self.config.subscribe_topics.len()];
+            self.client
+                .subscribe_many(&self.config.subscribe_topics, &qos)
+                .await?;
+        }
+        info!("Mqtt bridge connected to {}", self.config.server);
+        Ok(())
+    }
+
+    fn build_connect_options(&self) -> Result<ConnectOptions, MqttBrokerError> {
+        let mut builder = ConnectOptionsBuilder::new_v5();
+        builder
+            .keep_alive_interval(Duration::from_secs(self.config.keep_alive_secs))
+            .connect_timeout(Duration::from_secs(30))
+            .clean_start(true);
+        if !self.config.username.is_empty() {
+            builder
+                .user_name(self.config.username.as_str())
+                .password(self.config.password.as_str());
+        }
+
+        if let Some(tls) = &self.config.tls {
+            let mut ssl = SslOptionsBuilder::new();
+            ssl.trust_store(&tls.ca_path)?;
+            if let Some(cert_path) = &tls.cert_path {
+                ssl.key_store(cert_path)?;
+            }
+            if let Some(key_path) = &tls.key_path {
+                ssl.private_key(key_path)?;
+            }
+            ssl.enable_server_cert_auth(tls.verify).verify(tls.verify);
+            builder.ssl_options(ssl.finalize());
+        }
+        Ok(builder.finalize())
+    }
+}
+
+#[async_trait]
+impl BridgePlugin for MqttBridgePlugin {
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        self.ensure_connected().await?;
+
+        let origin = broker_mqtt_conf().cluster_name.clone();
+        for record in records {
+            let message = MqttMessage::decode_record(record.clone())?;
+            if bridge_origin(&message.user_properties).is_some() {
+                continue;
+            }
+
+            let mut props = Properties::new();
+            for (key, value) in message.user_properties.iter() {
+                props.push_string_pair(PropertyCode::UserProperty, key, value)?;
+            }
+            props.push_string_pair(PropertyCode::UserProperty, BRIDGE_ORIGIN_PROPERTY, &origin)?;
+
+            let topic = map_topic(
+                &self.config.remote_topic_prefix,
+                &String::from_utf8_lossy(&message.topic),
+            );
+            let remote_message = MessageBuilder::new()
+                .topic(topic)
+                .payload(message.payload.to_vec())
+                .qos(downgrade_qos(message.qos as u8, self.config.max_qos) as i32)
+                .retained(message.retain)
+                .properties(props)
+                .finalize();
+            self.client.publish(remote_message).await?;
+        }
+        Ok(())
+    }
+}
+
+pub fn map_topic(prefix: &str, topic_name: &str) -> String {
+    format!("{}{}", prefix, topic_name)
+}
+
+pub fn downgrade_qos(qos: u8, max_qos: u8) -> u8 {
+    qos.min(max_qos)
+}
+
+fn bridge_origin(user_properties: &[(String, String)]) -> Option<&str> {
+    user_properties
+        .iter()
+        .find(|(key, _)| key == BRIDGE_ORIGIN_PROPERTY)
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_mapping_test() {
+        assert_eq!(map_topic("site-a/", "sensors/1"), "site-a/sensors/1");
+        assert_eq!(map_topic("", "sensors/1"), "sensors/1");
+
+        assert_eq!(downgrade_qos(2, 1), 1);
+        assert_eq!(downgrade_qos(0, 1), 0);
+        assert_eq!(downgrade_qos(2, 2), 2);
+    }
+
+    #[test]
+    fn bridge_origin_test() {
+        let properties = vec![
+            ("site".to_string(), "a".to_string()),
+            (BRIDGE_ORIGIN_PROPERTY.to_string(), "cluster-a".to_string()),
+        ];
+        assert_eq!(bridge_origin(&properties), Some("cluster-a"));
+        assert_eq!(bridge_origin(&properties[..1]), None);
+    }
+}
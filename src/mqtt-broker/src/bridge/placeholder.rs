@@ -0,0 +1,93 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use metadata_struct::mqtt::message::MqttMessage;
+use serde_json::Value;
+
+// Expands ${topic}, ${clientid}, ${timestamp}, ${payload} and ${payload.<field>}
+// in a single pass, so placeholders inside the substituted values are left alone.
+// Unknown placeholders are kept as they are and missing payload fields render empty.
+pub fn render_placeholders(template: &str, message: &MqttMessage) -> String {
+    let mut payload_json = None;
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + len];
+        match name {
+            "topic" => result.push_str(&String::from_utf8_lossy(&message.topic)),
+            "clientid" => result.push_str(&message.client_id),
+            "timestamp" => result.push_str(&message.create_time.to_string()),
+            "payload" => result.push_str(&String::from_utf8_lossy(&message.payload)),
+            _ => match name.strip_prefix("payload.") {
+                Some(path) => {
+                    let json = payload_json.get_or_insert_with(|| {
+                        serde_json::from_slice::<Value>(&message.payload).unwrap_or_default()
+                    });
+                    result.push_str(&payload_field(json, path));
+                }
+                None => result.push_str(&rest[start..start + len + 1]),
+            },
+        }
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn payload_field(json: &Value, path: &str) -> String {
+    let mut current = json;
+    for segment in path.split('.') {
+        match current.get(segment) {
+            Some(value) => current = value,
+            None => return String::new(),
+        }
+    }
+    match current {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn render_placeholders_test() {
+        let message = MqttMessage {
+            client_id: "c1".to_string(),
+            topic: Bytes::from("sensors/1"),
+            payload: Bytes::from(r#"{"temp": 21.5, "meta": {"site": "a"}, "note": "${topic}"}"#),
+            create_time: 100,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_placeholders(
+                "${clientid}:${topic}:${timestamp}:${payload.temp}:${payload.meta.site}",
+                &message
+            ),
+            "c1:sensors/1:100:21.5:a"
+        );
+        assert_eq!(render_placeholders("${payload.note}", &message), "${topic}");
+        assert_eq!(render_placeholders("${payload.missing}", &message), "");
+        assert_eq!(render_placeholders("${other} ${", &message), "${other} ${");
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use mysql::prelude::Queryable;
+use mysql::{Params, Pool, Value};
+
+use super::template::RdbValue;
+use crate::handler::error::MqttBrokerError;
+
+pub fn execute(pool: &Pool, sql: &str, values: Vec<RdbValue>) -> Result<(), MqttBrokerError> {
+    let mut conn = pool.get_conn()?;
+    let params: Vec<Value> = values.into_iter().map(to_mysql_value).collect();
+    conn.exec_drop(sql, Params::Positional(params))?;
+    Ok(())
+}
+
+fn to_mysql_value(value: RdbValue) -> Value {
+    match value {
+        RdbValue::Null => Value::NULL,
+        RdbValue::Bool(b) => Value::Int(b as i64),
+        RdbValue::Int(i) => Value::Int(i),
+        RdbValue::Float(f) => Value::Double(f),
+        RdbValue::Text(s) => Value::Bytes(s.into_bytes()),
+    }
+}
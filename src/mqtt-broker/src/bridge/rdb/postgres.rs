@@ -0,0 +1,87 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use r2d2_postgres::postgres::types::{ToSql, Type};
+use third_driver::postgres::PostgresPool;
+
+use super::template::RdbValue;
+use crate::handler::error::MqttBrokerError;
+
+type PostgresParam = Box<dyn ToSql + Sync>;
+
+pub fn execute(
+    pool: &PostgresPool,
+    sql: &str,
+    values: Vec<RdbValue>,
+) -> Result<(), MqttBrokerError> {
+    let mut client = pool.get()?;
+    let statement = client.prepare(sql)?;
+    // Postgres checks parameter types strictly, so values are converted to the
+    // types the server inferred for the statement.
+    let params = statement
+        .params()
+        .iter()
+        .zip(values.iter())
+        .map(|(ty, value)| to_postgres_param(value, ty))
+        .collect::<Result<Vec<PostgresParam>, MqttBrokerError>>()?;
+    let refs: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param.as_ref()).collect();
+    client.execute(&statement, &refs)?;
+    Ok(())
+}
+
+fn to_postgres_param(value: &RdbValue, ty: &Type) -> Result<PostgresParam, MqttBrokerError> {
+    let param: PostgresParam = if *ty == Type::BOOL {
+        Box::new(convert(value, ty, RdbValue::as_bool)?)
+    } else if *ty == Type::INT2 {
+        Box::new(convert(value, ty, |v| v.as_i64().map(|i| i as i16))?)
+    } else if *ty == Type::INT4 {
+        Box::new(convert(value, ty, |v| v.as_i64().map(|i| i as i32))?)
+    } else if *ty == Type::INT8 {
+        Box::new(convert(value, ty, RdbValue::as_i64)?)
+    } else if *ty == Type::FLOAT4 {
+        Box::new(convert(value, ty, |v| v.as_f64().map(|f| f as f32))?)
+    } else if *ty == Type::FLOAT8 {
+        Box::new(convert(value, ty, RdbValue::as_f64)?)
+    } else if *ty == Type::JSON || *ty == Type::JSONB {
+        Box::new(convert(value, ty, RdbValue::as_json)?)
+    } else if *ty == Type::TIMESTAMP || *ty == Type::TIMESTAMPTZ {
+        let time: Option<SystemTime> = convert(value, ty, |v| {
+            v.as_i64()
+                .map(|secs| UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64))
+        })?;
+        Box::new(time)
+    } else {
+        Box::new(value.as_text())
+    };
+    Ok(param)
+}
+
+fn convert<T>(
+    value: &RdbValue,
+    ty: &Type,
+    f: impl Fn(&RdbValue) -> Option<T>,
+) -> Result<Option<T>, MqttBrokerError> {
+    if *value == RdbValue::Null {
+        return Ok(None);
+    }
+    match f(value) {
+        Some(v) => Ok(Some(v)),
+        None => Err(MqttBrokerError::RdbTemplateError(format!(
+            "cannot bind {:?} to a {} column",
+            value, ty
+        ))),
+    }
+}
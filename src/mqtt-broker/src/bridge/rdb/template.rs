@@ -0,0 +1,503 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use metadata_struct::mqtt::bridge::config_rdb::RdbBackend;
+use metadata_struct::mqtt::message::MqttMessage;
+use serde_json::Value;
+
+use crate::handler::error::MqttBrokerError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Binding {
+    Topic,
+    ClientId,
+    Timestamp,
+    Payload,
+    PayloadField(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RowPart {
+    Text(String),
+    Bind(Binding),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RdbValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl RdbValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            RdbValue::Bool(b) => Some(*b),
+            RdbValue::Int(i) => Some(*i != 0),
+            RdbValue::Text(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            RdbValue::Int(i) => Some(*i),
+            RdbValue::Float(f) if f.fract() == 0.0 => Some(*f as i64),
+            RdbValue::Bool(b) => Some(*b as i64),
+            RdbValue::Text(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            RdbValue::Int(i) => Some(*i as f64),
+            RdbValue::Float(f) => Some(*f),
+            RdbValue::Text(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<String> {
+        match self {
+            RdbValue::Null => None,
+            RdbValue::Bool(b) => Some(b.to_string()),
+            RdbValue::Int(i) => Some(i.to_string()),
+            RdbValue::Float(f) => Some(f.to_string()),
+            RdbValue::Text(s) => Some(s.clone()),
+        }
+    }
+
+    pub fn as_json(&self) -> Option<Value> {
+        match self {
+            RdbValue::Null => None,
+            RdbValue::Bool(b) => Some(Value::from(*b)),
+            RdbValue::Int(i) => Some(Value::from(*i)),
+            RdbValue::Float(f) => Some(Value::from(*f)),
+            RdbValue::Text(s) => {
+                Some(serde_json::from_str(s).unwrap_or_else(|_| Value::String(s.clone())))
+            }
+        }
+    }
+}
+
+// A parsed single-row INSERT statement that can be expanded to a multi-row,
+// optionally upserting, statement with backend specific placeholders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertTemplate {
+    prefix: String,
+    columns: Vec<String>,
+    row: Vec<RowPart>,
+    // Positions of the bound values used by the expression of each column
+    column_binds: Vec<Vec<usize>>,
+    suffix: String,
+}
+
+impl InsertTemplate {
+    pub fn parse(sql: &str) -> Result<Self, MqttBrokerError> {
+        let sql = sql.trim().trim_end_matches(';');
+        let upper = sql.to_uppercase();
+        if !upper.starts_with("INSERT") {
+            return Err(template_error("the template must be an INSERT statement"));
+        }
+        let Some(values_idx) = upper.find("VALUES") else {
+            return Err(template_error("the template has no VALUES clause"));
+        };
+        let prefix = sql[..values_idx + "VALUES".len()].to_string();
+
+        let columns = match (prefix.find('('), prefix.rfind(')')) {
+            (Some(start), Some(end)) if start < end => prefix[start + 1..end]
+                .split(',')
+                .map(|column| column.trim().trim_matches(['`', '"']).to_string())
+                .collect(),
+            _ => return Err(template_error("the template must list its columns")),
+        };
+
+        let rest = sql[values_idx + "VALUES".len()..].trim_start();
+        if !rest.starts_with('(') {
+            return Err(template_error("VALUES must be followed by a row"));
+        }
+        let mut depth = 0;
+        let mut row_end = None;
+        for (i, c) in rest.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        row_end = Some(i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(row_end) = row_end else {
+            return Err(template_error("unbalanced parentheses in VALUES"));
+        };
+
+        let row = parse_row(&rest[..=row_end])?;
+        let column_binds = column_binds(&row);
+        if column_binds.len() != columns.len() {
+            return Err(template_error(
+                "the number of values does not match the number of columns",
+            ));
+        }
+
+        Ok(InsertTemplate {
+            prefix,
+            columns,
+            row,
+            column_binds,
+            suffix: rest[row_end + 1..].trim().to_string(),
+        })
+    }
+
+    pub fn build_sql(&self, backend: &RdbBackend, rows: usize, upsert_keys: &[String]) -> String {
+        let mut sql = self.prefix.clone();
+        let mut index = 0;
+        for n in 0..rows {
+            sql.push_str(if n == 0 { " " } else { ", " });
+            for part in self.row.iter() {
+                match part {
+                    RowPart::Text(text) => sql.push_str(text),
+                    RowPart::Bind(_) => {
+                        index += 1;
+                        match backend {
+                            RdbBackend::MySql => sql.push('?'),
+                            RdbBackend::Postgres => sql.push_str(&format!("${}", index)),
+                        }
+                    }
+                }
+            }
+        }
+
+        if !upsert_keys.is_empty() {
+            let updates: Vec<&String> = self
+                .columns
+                .iter()
+                .filter(|column| !upsert_keys.contains(column))
+                .collect();
+            match backend {
+                RdbBackend::MySql => {
+                    // MySQL resolves conflicts on any unique key, so the key columns are only
+                    // used to decide which columns to leave alone. Without any other column
+                    // the key is assigned to itself, which keeps the existing row.
+                    let updates: Vec<String> = if updates.is_empty() {
+                        vec![format!("{} = {}", upsert_keys[0], upsert_keys[0])]
+                    } else {
+                        updates
+                            .iter()
+                            .map(|column| format!("{} = VALUES({})", column, column))
+                            .collect()
+                    };
+                    sql.push_str(&format!(" ON DUPLICATE KEY UPDATE {}", updates.join(", ")));
+                }
+                RdbBackend::Postgres => {
+                    sql.push_str(&format!(" ON CONFLICT ({}) DO", upsert_keys.join(", ")));
+                    if updates.is_empty() {
+                        sql.push_str(" NOTHING");
+                    } else {
+                        let updates: Vec<String> = updates
+                            .iter()
+                            .map(|column| format!("{} = EXCLUDED.{}", column, column))
+                            .collect();
+                        sql.push_str(&format!(" UPDATE SET {}", updates.join(", ")));
+                    }
+                }
+            }
+        }
+
+        if !self.suffix.is_empty() {
+            sql.push(' ');
+            sql.push_str(&self.suffix);
+        }
+        sql
+    }
+
+    pub fn bind_row(&self, message: &MqttMessage) -> Vec<RdbValue> {
+        let payload_json = serde_json::from_slice::<Value>(&message.payload).ok();
+        self.row
+            .iter()
+            .filter_map(|part| match part {
+                RowPart::Bind(binding) => Some(bind(binding, message, &payload_json)),
+                RowPart::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Keeps only the last row of every upsert key. A statement can not update the same
+    /// row twice on Postgres, and on MySQL the last row wins anyway.
+    pub fn dedup_rows(
+        &self,
+        rows: Vec<Vec<RdbValue>>,
+        upsert_keys: &[String],
+    ) -> Vec<Vec<RdbValue>> {
+        if upsert_keys.is_empty() {
+            return rows;
+        }
+
+        let binds: Vec<usize> = self
+            .columns
+            .iter()
+            .zip(self.column_binds.iter())
+            .filter(|(column, _)| upsert_keys.contains(column))
+            .flat_map(|(_, binds)| binds.iter().copied())
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut results: Vec<Vec<RdbValue>> = rows
+            .into_iter()
+            .rev()
+            .filter(|row| {
+                let key: Vec<String> = binds.iter().map(|i| format!("{:?}", row[*i])).collect();
+                seen.insert(key)
+            })
+            .collect();
+        results.reverse();
+        results
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn has_suffix(&self) -> bool {
+        !self.suffix.is_empty()
+    }
+}
+
+fn parse_row(row: &str) -> Result<Vec<RowPart>, MqttBrokerError> {
+    let mut parts = Vec::new();
+    let mut rest = row;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            return Err(template_error("unterminated placeholder"));
+        };
+        if start > 0 {
+            parts.push(RowPart::Text(rest[..start].to_string()));
+        }
+        let name = &rest[start + 2..start + len];
+        let binding = match name {
+            "topic" => Binding::Topic,
+            "clientid" => Binding::ClientId,
+            "timestamp" => Binding::Timestamp,
+            "payload" => Binding::Payload,
+            _ => match name.strip_prefix("payload.") {
+                Some(path) if !path.is_empty() => {
+                    Binding::PayloadField(path.split('.').map(|s| s.to_string()).collect())
+                }
+                _ => {
+                    return Err(template_error(&format!(
+                        "unknown placeholder ${{{}}}",
+                        name
+                    )))
+                }
+            },
+        };
+        parts.push(RowPart::Bind(binding));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(RowPart::Text(rest.to_string()));
+    }
+    Ok(parts)
+}
+
+// Splits the row on the commas between column expressions, ignoring the ones nested in
+// function calls or string literals.
+fn column_binds(row: &[RowPart]) -> Vec<Vec<usize>> {
+    let mut columns = vec![Vec::new()];
+    let mut depth = 0;
+    let mut quote = None;
+    let mut index = 0;
+    for part in row {
+        match part {
+            RowPart::Bind(_) => {
+                if let Some(binds) = columns.last_mut() {
+                    binds.push(index);
+                }
+                index += 1;
+            }
+            RowPart::Text(text) => {
+                for c in text.chars() {
+                    match (quote, c) {
+                        (Some(q), c) if c == q => quote = None,
+                        (Some(_), _) => {}
+                        (None, '\'' | '"' | '`') => quote = Some(c),
+                        (None, '(') => depth += 1,
+                        (None, ')') => depth -= 1,
+                        (None, ',') if depth == 1 => columns.push(Vec::new()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    columns
+}
+
+fn bind(binding: &Binding, message: &MqttMessage, payload_json: &Option<Value>) -> RdbValue {
+    match binding {
+        Binding::Topic => RdbValue::Text(String::from_utf8_lossy(&message.topic).to_string()),
+        Binding::ClientId => RdbValue::Text(message.client_id.clone()),
+        Binding::Timestamp => RdbValue::Int(message.create_time as i64),
+        Binding::Payload => RdbValue::Text(String::from_utf8_lossy(&message.payload).to_string()),
+        Binding::PayloadField(path) => {
+            let mut current = match payload_json {
+                Some(value) => value,
+                None => return RdbValue::Null,
+            };
+            for segment in path {
+                match current.get(segment) {
+                    Some(value) => current = value,
+                    None => return RdbValue::Null,
+                }
+            }
+            match current {
+                Value::Null => RdbValue::Null,
+                Value::Bool(b) => RdbValue::Bool(*b),
+                Value::Number(n) => match n.as_i64() {
+                    Some(i) => RdbValue::Int(i),
+                    None => RdbValue::Float(n.as_f64().unwrap_or_default()),
+                },
+                Value::String(s) => RdbValue::Text(s.clone()),
+                other => RdbValue::Text(other.to_string()),
+            }
+        }
+    }
+}
+
+fn template_error(message: &str) -> MqttBrokerError {
+    MqttBrokerError::RdbTemplateError(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    const TEMPLATE: &str = "INSERT INTO readings (topic, device, temp, ts) \
+        VALUES (${topic}, ${clientid}, ${payload.sensor.temp}, FROM_UNIXTIME(${timestamp}));";
+
+    #[test]
+    fn build_sql_test() {
+        let template = InsertTemplate::parse(TEMPLATE).unwrap();
+        assert_eq!(template.columns(), ["topic", "device", "temp", "ts"]);
+
+        assert_eq!(
+            template.build_sql(&RdbBackend::MySql, 2, &[]),
+            "INSERT INTO readings (topic, device, temp, ts) VALUES (?, ?, ?, FROM_UNIXTIME(?)), \
+             (?, ?, ?, FROM_UNIXTIME(?))"
+        );
+        assert_eq!(
+            template.build_sql(&RdbBackend::MySql, 1, &["topic".to_string()]),
+            "INSERT INTO readings (topic, device, temp, ts) VALUES (?, ?, ?, FROM_UNIXTIME(?)) \
+             ON DUPLICATE KEY UPDATE device = VALUES(device), temp = VALUES(temp), ts = VALUES(ts)"
+        );
+        assert_eq!(
+            template.build_sql(
+                &RdbBackend::Postgres,
+                2,
+                &["topic".to_string(), "device".to_string()]
+            ),
+            "INSERT INTO readings (topic, device, temp, ts) VALUES ($1, $2, $3, FROM_UNIXTIME($4)), \
+             ($5, $6, $7, FROM_UNIXTIME($8)) ON CONFLICT (topic, device) DO UPDATE SET \
+             temp = EXCLUDED.temp, ts = EXCLUDED.ts"
+        );
+
+        let template =
+            InsertTemplate::parse("INSERT INTO t (a, b) VALUES (${topic}, ${clientid})").unwrap();
+        let keys = ["a".to_string(), "b".to_string()];
+        assert_eq!(
+            template.build_sql(&RdbBackend::MySql, 2, &keys),
+            "INSERT INTO t (a, b) VALUES (?, ?), (?, ?) ON DUPLICATE KEY UPDATE a = a"
+        );
+        assert_eq!(
+            template.build_sql(&RdbBackend::Postgres, 1, &keys),
+            "INSERT INTO t (a, b) VALUES ($1, $2) ON CONFLICT (a, b) DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn dedup_rows_test() {
+        let template = InsertTemplate::parse(
+            "INSERT INTO t (id, note, v) VALUES (CONCAT(${topic}, '-', ${clientid}), 'a,b', ${payload})",
+        )
+        .unwrap();
+        let row = |topic: &str, client_id: &str, payload: &str| {
+            vec![
+                RdbValue::Text(topic.to_string()),
+                RdbValue::Text(client_id.to_string()),
+                RdbValue::Text(payload.to_string()),
+            ]
+        };
+        let rows = vec![
+            row("t1", "c1", "1"),
+            row("t1", "c2", "2"),
+            row("t1", "c1", "3"),
+        ];
+
+        assert_eq!(template.dedup_rows(rows.clone(), &[]), rows);
+        assert_eq!(
+            template.dedup_rows(rows.clone(), &["id".to_string()]),
+            vec![row("t1", "c2", "2"), row("t1", "c1", "3")]
+        );
+        assert_eq!(
+            template.dedup_rows(rows, &["note".to_string()]),
+            vec![row("t1", "c1", "3")]
+        );
+    }
+
+    #[test]
+    fn bind_row_test() {
+        let template = InsertTemplate::parse(TEMPLATE).unwrap();
+        let message = MqttMessage {
+            client_id: "c1".to_string(),
+            topic: Bytes::from("sensors/1"),
+            payload: Bytes::from(r#"{"sensor": {"temp": 21.5}}"#),
+            create_time: 100,
+            ..Default::default()
+        };
+        assert_eq!(
+            template.bind_row(&message),
+            vec![
+                RdbValue::Text("sensors/1".to_string()),
+                RdbValue::Text("c1".to_string()),
+                RdbValue::Float(21.5),
+                RdbValue::Int(100),
+            ]
+        );
+
+        let message = MqttMessage {
+            payload: Bytes::from("not json"),
+            ..Default::default()
+        };
+        assert_eq!(template.bind_row(&message)[2], RdbValue::Null);
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert!(InsertTemplate::parse("SELECT 1").is_err());
+        assert!(InsertTemplate::parse("INSERT INTO t VALUES (${topic})").is_err());
+        assert!(InsertTemplate::parse("INSERT INTO t (a) VALUES (${unknown})").is_err());
+        assert!(InsertTemplate::parse("INSERT INTO t (a) VALUES (${topic}").is_err());
+        assert!(InsertTemplate::parse("INSERT INTO t (a, b) VALUES (${topic})").is_err());
+    }
+}
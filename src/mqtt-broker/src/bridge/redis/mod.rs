@@ -0,0 +1,264 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::async_trait;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::bridge::config_redis::{RedisCommandType, RedisConnectorConfig};
+use metadata_struct::mqtt::message::MqttMessage;
+use redis::aio::ConnectionManager;
+use redis::{Cmd, Pipeline};
+use tokio::sync::Mutex;
+
+use super::core::BridgePlugin;
+use super::placeholder::render_placeholders;
+use crate::handler::error::MqttBrokerError;
+
+pub struct RedisBridgePlugin {
+    config: RedisConnectorConfig,
+    client: redis::Client,
+    // The manager reconnects by itself once it exists; it is created lazily so
+    // that the connector also starts while redis is unreachable.
+    connection: Mutex<Option<ConnectionManager>>,
+}
+
+impl RedisBridgePlugin {
+    pub fn new(config: RedisConnectorConfig) -> Result<Self, MqttBrokerError> {
+        let client = redis::Client::open(config.url.as_str())?;
+        Ok(RedisBridgePlugin {
+            config,
+            client,
+            connection: Mutex::new(None),
+        })
+    }
+
+    async fn get_connection(&self) -> Result<ConnectionManager, MqttBrokerError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(connection) = connection.as_ref() {
+            return Ok(connection.clone());
+        }
+        let manager = ConnectionManager::new(self.client.clone()).await?;
+        *connection = Some(manager.clone());
+        Ok(manager)
+    }
+}
+
+#[async_trait]
+impl BridgePlugin for RedisBridgePlugin {
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        let mut pipe = Pipeline::with_capacity(records.len());
+        for record in records {
+            let message = MqttMessage::decode_record(record.clone())?;
+            pipe.add_command(build_command(&self.config, &message))
+                .ignore();
+        }
+
+        let mut connection = self.get_connection().await?;
+        let _: () = pipe.query_async(&mut connection).await?;
+        Ok(())
+    }
+}
+
+fn build_command(config: &RedisConnectorConfig, message: &MqttMessage) -> Cmd {
+    let key = render_placeholders(&config.key, message);
+    let value = render_placeholders(&config.value, message);
+    match config.command {
+        RedisCommandType::Set => {
+            let mut cmd = redis::cmd("SET");
+            cmd.arg(key).arg(value);
+            if let Some(ttl) = config.ttl_secs {
+                cmd.arg("EX").arg(ttl);
+            }
+            cmd
+        }
+        RedisCommandType::HSet => {
+            let mut cmd = redis::cmd("HSET");
+            cmd.arg(key)
+                .arg(render_placeholders(&config.field, message))
+                .arg(value);
+            cmd
+        }
+        RedisCommandType::XAdd => {
+            let mut cmd = redis::cmd("XADD");
+            cmd.arg(key);
+            if let Some(max_len) = config.stream_max_len {
+                cmd.arg("MAXLEN").arg("~").arg(max_len);
+            }
+            cmd.arg("*")
+                .arg(render_placeholders(&config.field, message))
+                .arg(value);
+            cmd
+        }
+        RedisCommandType::Publish => {
+            let mut cmd = redis::cmd("PUBLISH");
+            cmd.arg(key).arg(value);
+            cmd
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn build_message(client_id: &str, payload: &str) -> MqttMessage {
+        MqttMessage {
+            client_id: client_id.to_string(),
+            topic: Bytes::from("sensors/1"),
+            payload: Bytes::from(payload.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn command_args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8(bytes.to_vec()).unwrap(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn build_command_test() {
+        let message = build_message("c1", r#"{"temp": 21}"#);
+
+        let config = RedisConnectorConfig {
+            command: RedisCommandType::Set,
+            key: "device:${clientid}".to_string(),
+            ttl_secs: Some(60),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_args(&build_command(&config, &message)),
+            vec!["SET", "device:c1", r#"{"temp": 21}"#, "EX", "60"]
+        );
+
+        let config = RedisConnectorConfig {
+            command: RedisCommandType::HSet,
+            key: "latest".to_string(),
+            field: "${clientid}".to_string(),
+            value: "${payload.temp}".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_args(&build_command(&config, &message)),
+            vec!["HSET", "latest", "c1", "21"]
+        );
+
+        let config = RedisConnectorConfig {
+            command: RedisCommandType::XAdd,
+            key: "stream:${topic}".to_string(),
+            stream_max_len: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_args(&build_command(&config, &message)),
+            vec![
+                "XADD",
+                "stream:sensors/1",
+                "MAXLEN",
+                "~",
+                "1000",
+                "*",
+                "payload",
+                r#"{"temp": 21}"#
+            ]
+        );
+    }
+
+    // Speaks just enough RESP to answer every command with +OK and records what
+    // it received.
+    async fn start_redis_stand_in() -> (String, Arc<Mutex<Vec<Vec<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let commands = Arc::new(Mutex::new(Vec::new()));
+        let received = commands.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let received = received.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut reader = BufReader::new(read);
+                    let mut line = String::new();
+                    loop {
+                        line.clear();
+                        if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                            break;
+                        }
+                        let argc: usize = line.trim_start_matches('*').trim().parse().unwrap();
+                        let mut args = Vec::with_capacity(argc);
+                        for _ in 0..argc {
+                            line.clear();
+                            reader.read_line(&mut line).await.unwrap();
+                            let len: usize = line.trim_start_matches('$').trim().parse().unwrap();
+                            let mut buf = vec![0; len + 2];
+                            reader.read_exact(&mut buf).await.unwrap();
+                            buf.truncate(len);
+                            args.push(String::from_utf8(buf).unwrap());
+                        }
+                        received.lock().await.push(args);
+                        write.write_all(b"+OK\r\n").await.unwrap();
+                    }
+                });
+            }
+        });
+        (format!("redis://{}", addr), commands)
+    }
+
+    #[tokio::test]
+    async fn redis_stand_in_test() {
+        let (url, commands) = start_redis_stand_in().await;
+        let plugin = RedisBridgePlugin::new(RedisConnectorConfig {
+            url,
+            command: RedisCommandType::Publish,
+            key: "mqtt/${topic}".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let records: Vec<Record> = ["m1", "m2"]
+            .iter()
+            .map(|payload| Record::build_byte(build_message("c1", payload).encode()))
+            .collect();
+        plugin.append(&records).await.unwrap();
+
+        let commands = commands.lock().await;
+        let published: Vec<&Vec<String>> = commands
+            .iter()
+            .filter(|args| args[0] == "PUBLISH")
+            .collect();
+        assert_eq!(
+            published,
+            vec![
+                &vec![
+                    "PUBLISH".to_string(),
+                    "mqtt/sensors/1".to_string(),
+                    "m1".to_string()
+                ],
+                &vec![
+                    "PUBLISH".to_string(),
+                    "mqtt/sensors/1".to_string(),
+                    "m2".to_string()
+                ],
+            ]
+        );
+    }
+}
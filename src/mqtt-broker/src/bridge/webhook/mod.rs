@@ -0,0 +1,526 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::async_trait;
+use bytes::Bytes;
+use futures::stream::{self, StreamExt};
+use grpc_clients::pool::ClientPool;
+use log::warn;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::bridge::config_webhook::WebHookConnectorConfig;
+use metadata_struct::mqtt::message::MqttMessage;
+use reqwest::Method;
+use serde_json::{json, Value};
+use storage_adapter::storage::StorageAdapter;
+use tokio::time::sleep;
+
+use super::core::BridgePlugin;
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::topic::try_init_topic;
+use crate::observability::metrics::connector::incr_connector_messages_dropped;
+use crate::storage::message::MessageStorage;
+
+pub const DEAD_LETTER_SOURCE_PROPERTY: &str = "dead-letter-source-topic";
+pub const DEAD_LETTER_REASON_PROPERTY: &str = "dead-letter-reason";
+
+enum DeliveryError {
+    Retryable(String),
+    Permanent(String),
+}
+
+pub struct WebHookBridgePlugin<S> {
+    connector_name: String,
+    config: WebHookConnectorConfig,
+    method: Method,
+    client: reqwest::Client,
+    cache_manager: Arc<CacheManager>,
+    message_storage_adapter: Arc<S>,
+    client_pool: Arc<ClientPool>,
+}
+
+impl<S> WebHookBridgePlugin<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    pub fn new(
+        connector_name: String,
+        config: WebHookConnectorConfig,
+        cache_manager: Arc<CacheManager>,
+        message_storage_adapter: Arc<S>,
+        client_pool: Arc<ClientPool>,
+    ) -> Result<Self, MqttBrokerError> {
+        let method = Method::from_bytes(config.method.to_uppercase().as_bytes())
+            .map_err(|e| MqttBrokerError::CommonError(e.to_string()))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()?;
+        Ok(WebHookBridgePlugin {
+            connector_name,
+            config,
+            method,
+            client,
+            cache_manager,
+            message_storage_adapter,
+            client_pool,
+        })
+    }
+
+    async fn deliver(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        let mut messages = Vec::with_capacity(records.len());
+        for record in records {
+            messages.push(MqttMessage::decode_record(record.clone())?);
+        }
+        let body = build_body(&self.config, &messages);
+
+        let mut backoff_ms = self.config.retry_backoff_ms;
+        let mut attempt = 0;
+        loop {
+            let reason = match self.send(body.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(DeliveryError::Permanent(reason)) => {
+                    return self.dead_letter(messages, reason).await
+                }
+                Err(DeliveryError::Retryable(reason)) => reason,
+            };
+
+            if attempt >= self.config.max_retries {
+                return self.dead_letter(messages, reason).await;
+            }
+            attempt += 1;
+            warn!(
+                "Webhook {} delivery failed, retrying in {}ms ({}/{}), error message: {}",
+                self.config.url, backoff_ms, attempt, self.config.max_retries, reason
+            );
+            sleep(Duration::from_millis(backoff_ms)).await;
+            backoff_ms = (backoff_ms * 2).min(self.config.max_backoff_ms);
+        }
+    }
+
+    async fn send(&self, body: String) -> Result<(), DeliveryError> {
+        let mut request = self
+            .client
+            .request(self.method.clone(), &self.config.url)
+            .body(body);
+        for (key, value) in self.config.headers.iter() {
+            request = request.header(key, value);
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    Ok(())
+                } else if status.is_server_error() {
+                    Err(DeliveryError::Retryable(format!(
+                        "{} responded with {}",
+                        self.config.url, status
+                    )))
+                } else {
+                    Err(DeliveryError::Permanent(format!(
+                        "{} responded with {}",
+                        self.config.url, status
+                    )))
+                }
+            }
+            Err(e) => Err(DeliveryError::Retryable(e.to_string())),
+        }
+    }
+
+    // Without a dead-letter topic the messages are dropped, otherwise the batch
+    // would never be committed and the connector would retry it forever.
+    async fn dead_letter(
+        &self,
+        messages: Vec<MqttMessage>,
+        reason: String,
+    ) -> Result<(), MqttBrokerError> {
+        let Some(topic_name) = &self.config.dead_letter_topic else {
+            warn!(
+                "Dropping {} messages of connector {}, no dead-letter topic is configured, error message: {}",
+                messages.len(),
+                self.connector_name,
+                reason
+            );
+            incr_connector_messages_dropped(&self.connector_name, messages.len() as u64);
+            return Ok(());
+        };
+
+        warn!(
+            "Moving {} messages to dead-letter topic {}, error message: {}",
+            messages.len(),
+            topic_name,
+            reason
+        );
+        let topic = try_init_topic(
+            topic_name,
+            &self.cache_manager,
+            &self.message_storage_adapter,
+            &self.client_pool,
+        )
+        .await?;
+
+        let records = messages
+            .into_iter()
+            .map(|mut message| {
+                let source = String::from_utf8_lossy(&message.topic).to_string();
+                message
+                    .user_properties
+                    .push((DEAD_LETTER_SOURCE_PROPERTY.to_string(), source));
+                message
+                    .user_properties
+                    .push((DEAD_LETTER_REASON_PROPERTY.to_string(), reason.clone()));
+                message.topic = Bytes::from(topic_name.clone());
+                Record::build_byte(message.encode())
+            })
+            .collect();
+
+        let message_storage =
+            MessageStorage::new_by_topic(self.message_storage_adapter.clone(), &topic.topic_name);
+        message_storage
+            .append_topic_message(&topic.topic_id, records)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S> BridgePlugin for WebHookBridgePlugin<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    async fn append(&self, records: &[Record]) -> Result<(), MqttBrokerError> {
+        let results: Vec<Result<(), MqttBrokerError>> =
+            stream::iter(records.chunks(self.config.batch_size.max(1)))
+                .map(|chunk| self.deliver(chunk))
+                .buffer_unordered(self.config.concurrency.max(1))
+                .collect()
+                .await;
+        results.into_iter().collect()
+    }
+}
+
+fn render_body(template: &str, message: &MqttMessage) -> String {
+    let payload = String::from_utf8_lossy(&message.payload);
+    let topic = String::from_utf8_lossy(&message.topic);
+    if template.is_empty() {
+        return json!({
+            "payload": payload,
+            "topic": topic,
+            "clientid": message.client_id,
+        })
+        .to_string();
+    }
+
+    if is_json_template(template) {
+        return render_json_body(template, message);
+    }
+
+    // The payload goes last so that placeholders inside it are left alone.
+    template
+        .replace("${topic}", &topic)
+        .replace("${clientid}", &message.client_id)
+        .replace("${payload}", &payload)
+}
+
+fn is_json_template(template: &str) -> bool {
+    template.trim_start().starts_with(['{', '['])
+}
+
+// Values are escaped for the place they land in the JSON template: inside a string
+// literal as string content, elsewhere as a JSON string, except for a payload that
+// is valid JSON which is inserted as it is.
+fn render_json_body(template: &str, message: &MqttMessage) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut index = 0;
+    while index < template.len() {
+        let rest = &template[index..];
+        if let Some(len) = rest.strip_prefix("${").and_then(|s| s.find('}')) {
+            let name = &rest[2..len + 2];
+            let value = match name {
+                "topic" => Some(String::from_utf8_lossy(&message.topic).to_string()),
+                "clientid" => Some(message.client_id.clone()),
+                "payload" => Some(String::from_utf8_lossy(&message.payload).to_string()),
+                _ => None,
+            };
+            if let Some(value) = value {
+                let quoted = Value::String(value.clone()).to_string();
+                if in_string {
+                    result.push_str(&quoted[1..quoted.len() - 1]);
+                } else if name == "payload" && serde_json::from_str::<Value>(&value).is_ok() {
+                    result.push_str(&value);
+                } else {
+                    result.push_str(&quoted);
+                }
+                index += len + 3;
+                continue;
+            }
+        }
+
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        if escaped {
+            escaped = false;
+        } else if in_string && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            in_string = !in_string;
+        }
+        result.push(c);
+        index += c.len_utf8();
+    }
+    result
+}
+
+fn build_body(config: &WebHookConnectorConfig, messages: &[MqttMessage]) -> String {
+    let bodies: Vec<String> = messages
+        .iter()
+        .map(|message| render_body(&config.body_template, message))
+        .collect();
+    if config.batch_size > 1 {
+        format!("[{}]", bodies.join(","))
+    } else {
+        bodies.concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::Router;
+    use common_base::config::broker_mqtt::init_broker_mqtt_conf_by_path;
+    use metadata_struct::mqtt::topic::MqttTopic;
+    use storage_adapter::memory::MemoryStorageAdapter;
+    use tokio::net::TcpListener;
+    use tokio::sync::Mutex;
+
+    use super::*;
+    use crate::observability::metrics::connector::get_connector_messages_dropped;
+
+    fn build_message(client_id: &str, topic: &str, payload: &str) -> MqttMessage {
+        MqttMessage {
+            client_id: client_id.to_string(),
+            topic: Bytes::from(topic.to_string()),
+            payload: Bytes::from(payload.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn render_body_test() {
+        let message = build_message("c1", "sensors/1", r#"{"temp": 20}"#);
+        assert_eq!(
+            render_body(
+                r#"{"device": "${clientid}", "topic": "${topic}", "data": ${payload}}"#,
+                &message
+            ),
+            r#"{"device": "c1", "topic": "sensors/1", "data": {"temp": 20}}"#
+        );
+
+        let body: serde_json::Value = serde_json::from_str(&render_body("", &message)).unwrap();
+        assert_eq!(body["clientid"], "c1");
+        assert_eq!(body["payload"], r#"{"temp": 20}"#);
+
+        let config = WebHookConnectorConfig {
+            body_template: "${payload}".to_string(),
+            batch_size: 2,
+            ..Default::default()
+        };
+        let messages = vec![build_message("c1", "t", "1"), build_message("c2", "t", "2")];
+        assert_eq!(build_body(&config, &messages), "[1,2]");
+    }
+
+    #[test]
+    fn render_json_body_test() {
+        let template = r#"{"device": "${clientid}", "topic": "${topic}", "data": ${payload}, "raw": "${payload}"}"#;
+
+        let message = build_message(r#"c"1"#, r"sensors\1", r#"{"note": "a \"b\""}"#);
+        let body: serde_json::Value =
+            serde_json::from_str(&render_body(template, &message)).unwrap();
+        assert_eq!(body["device"], r#"c"1"#);
+        assert_eq!(body["topic"], r"sensors\1");
+        assert_eq!(body["data"]["note"], r#"a "b""#);
+        assert_eq!(body["raw"], r#"{"note": "a \"b\""}"#);
+
+        // a payload that is not JSON is sent as a string
+        let message = build_message("c1", "sensors/1", r#"say "hi""#);
+        let body: serde_json::Value =
+            serde_json::from_str(&render_body(template, &message)).unwrap();
+        assert_eq!(body["data"], r#"say "hi""#);
+        assert_eq!(body["raw"], r#"say "hi""#);
+
+        // placeholders inside the payload are left alone
+        let message = build_message("c1", "sensors/1", r#""${topic}""#);
+        let body: serde_json::Value =
+            serde_json::from_str(&render_body(template, &message)).unwrap();
+        assert_eq!(body["data"], "${topic}");
+    }
+
+    #[derive(Clone, Default)]
+    struct Endpoint {
+        calls: Arc<AtomicUsize>,
+        bodies: Arc<Mutex<Vec<String>>>,
+    }
+
+    // Fails the first call with a 500 and accepts everything after it.
+    async fn flaky_handler(State(endpoint): State<Endpoint>, body: String) -> StatusCode {
+        if endpoint.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+        endpoint.bodies.lock().await.push(body);
+        StatusCode::OK
+    }
+
+    async fn rejecting_handler() -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    async fn start_endpoint(router: Router) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    fn build_plugin(config: WebHookConnectorConfig) -> WebHookBridgePlugin<MemoryStorageAdapter> {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(
+            client_pool.clone(),
+            "test-cluster".to_string(),
+        ));
+        WebHookBridgePlugin::new(
+            "webhook-test".to_string(),
+            config,
+            cache_manager,
+            Arc::new(MemoryStorageAdapter::new()),
+            client_pool,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retry_on_server_error_test() {
+        let endpoint = Endpoint::default();
+        let router = Router::new()
+            .route("/hook", post(flaky_handler))
+            .with_state(endpoint.clone());
+        let url = format!("{}/hook", start_endpoint(router).await);
+
+        let plugin = build_plugin(WebHookConnectorConfig {
+            url,
+            body_template: "${topic}:${payload}".to_string(),
+            retry_backoff_ms: 10,
+            ..Default::default()
+        });
+        let records = vec![Record::build_byte(
+            build_message("c1", "sensors/1", "20").encode(),
+        )];
+        plugin.append(&records).await.unwrap();
+
+        assert_eq!(endpoint.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(*endpoint.bodies.lock().await, vec!["sensors/1:20"]);
+    }
+
+    #[tokio::test]
+    async fn permanent_failure_test() {
+        let router = Router::new().route("/hook", post(rejecting_handler));
+        let url = format!("{}/hook", start_endpoint(router).await);
+
+        let plugin = build_plugin(WebHookConnectorConfig {
+            url,
+            retry_backoff_ms: 10,
+            ..Default::default()
+        });
+        let records = vec![Record::build_byte(
+            build_message("c1", "sensors/1", "20").encode(),
+        )];
+        plugin.append(&records).await.unwrap();
+        assert_eq!(get_connector_messages_dropped("webhook-test"), 1);
+    }
+    #[tokio::test]
+    async fn dead_letter_test() {
+        let path = format!(
+            "{}/../../config/mqtt-server.toml",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        init_broker_mqtt_conf_by_path(&path);
+
+        let router = Router::new().route("/hook", post(rejecting_handler));
+        let url = format!("{}/hook", start_endpoint(router).await);
+
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(
+            client_pool.clone(),
+            "test-cluster".to_string(),
+        ));
+        let dead_letter_topic = MqttTopic::new(
+            "dead-letter-id".to_string(),
+            "test-cluster".to_string(),
+            "dead-letter".to_string(),
+        );
+        cache_manager.add_topic("dead-letter", &dead_letter_topic);
+        let storage_adapter = Arc::new(MemoryStorageAdapter::new());
+        let plugin = WebHookBridgePlugin::new(
+            "webhook-dead-letter-test".to_string(),
+            WebHookConnectorConfig {
+                url,
+                retry_backoff_ms: 10,
+                dead_letter_topic: Some("dead-letter".to_string()),
+                ..Default::default()
+            },
+            cache_manager,
+            storage_adapter.clone(),
+            client_pool,
+        )
+        .unwrap();
+
+        let records = vec![Record::build_byte(
+            build_message("c1", "sensors/1", "20").encode(),
+        )];
+        plugin.append(&records).await.unwrap();
+        assert_eq!(
+            get_connector_messages_dropped("webhook-dead-letter-test"),
+            0
+        );
+
+        let message_storage = MessageStorage::new_by_topic(storage_adapter, "dead-letter");
+        let records = message_storage
+            .read_topic_message("dead-letter-id", 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        let message = MqttMessage::decode_record(records[0].clone()).unwrap();
+        assert_eq!(message.topic, Bytes::from("dead-letter"));
+        assert_eq!(message.payload, Bytes::from("20"));
+        assert!(message.user_properties.contains(&(
+            DEAD_LETTER_SOURCE_PROPERTY.to_string(),
+            "sensors/1".to_string()
+        )));
+        assert!(message
+            .user_properties
+            .iter()
+            .any(|(key, _)| key == DEAD_LETTER_REASON_PROPERTY));
+    }
+}
@@ -21,7 +21,9 @@
 use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
 use metadata_struct::mqtt::cluster::MqttClusterDynamicConfig;
 use metadata_struct::mqtt::connection::MQTTConnection;
+use metadata_struct::mqtt::rule::MqttRule;
 use metadata_struct::mqtt::session::MqttSession;
+use metadata_struct::mqtt::tenant::MqttTenant;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
 use metadata_struct::mqtt::user::MqttUser;
@@ -33,6 +35,13 @@
 use tokio::sync::broadcast::Sender;
 use tokio::time::sleep;
 
+use super::retain_index::RetainMessageIndex;
+use super::tenant::TenantUsage;
+use crate::observability::metrics::tenant::{
+    record_tenant_connection_dec, record_tenant_connection_inc,
+};
+use crate::rule_engine::sql::parse_rule_sql;
+use crate::rule_engine::RuleRuntime;
 use crate::security::acl::metadata::AclMetadata;
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -133,6 +142,18 @@ pub struct CacheManager {
 
     // All auto subscribe rule
     pub auto_subscribe_rule: DashMap<String, MqttAutoSubscribeRule>,
+
+    // (rule_name, RuleRuntime)
+    pub rule_info: DashMap<String, RuleRuntime>,
+
+    // Topic filter index of the retained messages
+    pub retain_message_index: RetainMessageIndex,
+
+    // (tenant_name, Tenant)
+    pub tenant_info: DashMap<String, MqttTenant>,
+
+    // (tenant_name, TenantUsage)
+    pub tenant_usage: DashMap<String, TenantUsage>,
 }
 
 impl CacheManager {
@@ -153,6 +174,10 @@ pub fn new(client_pool: Arc<ClientPool>, cluster_name: String) -> Self {
             acl_metadata: AclMetadata::new(),
             topic_rewrite_rule: DashMap::with_capacity(8),
             auto_subscribe_rule: DashMap::with_capacity(8),
+            rule_info: DashMap::with_capacity(8),
+            retain_message_index: RetainMessageIndex::new(),
+            tenant_info: DashMap::with_capacity(2),
+            tenant_usage: DashMap::with_capacity(2),
         }
     }
 
@@ -213,12 +238,19 @@ pub fn retain_users(&self, usernames: HashSet<String>) {
     pub fn add_connection(&self, connect_id: u64, conn: MQTTConnection) {
         if let Some(mut session) = self.session_info.get_mut(&conn.client_id) {
             session.connection_id = Some(connect_id);
+            if !conn.tenant.is_empty() {
+                record_tenant_connection_inc(&conn.tenant);
+            }
             self.connection_info.insert(connect_id, conn);
         }
     }
 
     pub fn remove_connection(&self, connect_id: u64) {
-        self.connection_info.remove(&connect_id);
+        if let Some((_, conn)) = self.connection_info.remove(&connect_id) {
+            if !conn.tenant.is_empty() {
+                record_tenant_connection_dec(&conn.tenant);
+            }
+        }
     }
 
     pub fn get_connect_id(&self, client_id: &str) -> Option<u64> {
@@ -267,12 +299,6 @@ pub fn get_topic_by_name(&self, topic_name: &str) -> Option<MqttTopic> {
         None
     }
 
-    pub fn update_topic_retain_message(&self, topic_name: &str, retain_message: Option<Vec<u8>>) {
-        if let Some(mut topic) = self.topic_info.get_mut(topic_name) {
-            topic.retain_message = retain_message;
-        }
-    }
-
     // topic rewrite rule
     pub fn add_topic_rewrite_rule(&self, topic_rewrite_rule: MqttTopicRewriteRule) {
         let key = self.topic_rewrite_rule_key(
@@ -288,6 +314,27 @@ pub fn delete_topic_rewrite_rule(&self, cluster: &str, action: &str, source_topi
         self.topic_rewrite_rule.remove(&key);
     }
 
+    // rule engine
+    pub fn add_rule(&self, rule: MqttRule) {
+        match parse_rule_sql(&rule.sql) {
+            Ok(statement) => {
+                self.rule_info
+                    .insert(rule.rule_name.clone(), RuleRuntime { rule, statement });
+            }
+            Err(e) => {
+                warn!(
+                    "rule {} failed to compile and was skipped, error: {}",
+                    rule.rule_name, e
+                );
+                self.rule_info.remove(&rule.rule_name);
+            }
+        }
+    }
+
+    pub fn delete_rule(&self, rule_name: &str) {
+        self.rule_info.remove(rule_name);
+    }
+
     pub fn login_success(&self, connect_id: u64, user_name: String) {
         if let Some(mut conn) = self.connection_info.get_mut(&connect_id) {
             conn.login_success(user_name)
@@ -15,22 +15,28 @@
 use crate::bridge::manager::ConnectorManager;
 use crate::storage::auto_subscribe::AutoSubscribeStorage;
 use crate::storage::connector::ConnectorStorage;
+use crate::storage::rule::RuleStorage;
+use crate::storage::tenant::TenantStorage;
 use crate::storage::topic::TopicStorage;
 use crate::{security::AuthDriver, subscribe::subscribe_manager::SubscribeManager};
 use common_base::config::broker_mqtt::broker_mqtt_conf;
-use grpc_clients::placement::inner::call::list_schema;
+use grpc_clients::placement::inner::call::{list_bind_schema, list_schema, list_schema_version};
 use grpc_clients::pool::ClientPool;
 use log::error;
 use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+use metadata_struct::mqtt::rule::MqttRule;
 use metadata_struct::mqtt::session::MqttSession;
 use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
+use metadata_struct::mqtt::tenant::MqttTenant;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::user::MqttUser;
 use metadata_struct::schema::{SchemaData, SchemaResourceBind};
 use protocol::broker_mqtt::broker_mqtt_inner::{
     MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType, UpdateMqttCacheRequest,
 };
-use protocol::placement_center::placement_center_inner::ListSchemaRequest;
+use protocol::placement_center::placement_center_inner::{
+    ListBindSchemaRequest, ListSchemaRequest, ListSchemaVersionRequest,
+};
 use schema_register::schema::SchemaRegisterManager;
 use std::sync::Arc;
 
@@ -130,6 +136,30 @@ pub async fn load_metadata_cache(
         connector_manager.add_connector(connector);
     }
 
+    // load all rules
+    let rule_storage = RuleStorage::new(client_pool.clone());
+    let rules = match rule_storage.list_all_rules().await {
+        Ok(list) => list,
+        Err(e) => {
+            panic!("Failed to load the rule list with error message:{}", e);
+        }
+    };
+    for rule in rules {
+        cache_manager.add_rule(rule);
+    }
+
+    // load all tenants
+    let tenant_storage = TenantStorage::new(client_pool.clone());
+    let tenants = match tenant_storage.list_all_tenants().await {
+        Ok(list) => list,
+        Err(e) => {
+            panic!("Failed to load the tenant list with error message:{}", e);
+        }
+    };
+    for tenant in tenants {
+        cache_manager.add_tenant(tenant);
+    }
+
     // load all schemas
     let config = broker_mqtt_conf();
     let request = ListSchemaRequest {
@@ -155,6 +185,64 @@ pub async fn load_metadata_cache(
         }
     }
 
+    // load all schema binds, and the schema versions pinned by them
+    let request = ListBindSchemaRequest {
+        cluster_name: config.cluster_name.clone(),
+        schema_name: "".to_owned(),
+        resource_name: "".to_owned(),
+    };
+
+    let schema_binds = match list_bind_schema(client_pool, &config.placement_center, request).await
+    {
+        Ok(reply) => reply.schema_binds,
+        Err(e) => {
+            panic!(
+                "Failed to load the schema bind list with error message:{}",
+                e
+            );
+        }
+    };
+
+    for raw in schema_binds {
+        let schema_bind = match serde_json::from_slice::<SchemaResourceBind>(raw.as_slice()) {
+            Ok(schema_bind) => schema_bind,
+            Err(e) => {
+                error!("{}", e);
+                continue;
+            }
+        };
+
+        if let Some(version) = schema_bind.schema_version {
+            if schema_manager
+                .get_schema_by_version(&schema_bind.schema_name, version)
+                .is_none()
+            {
+                let request = ListSchemaVersionRequest {
+                    cluster_name: config.cluster_name.clone(),
+                    schema_name: schema_bind.schema_name.clone(),
+                    version,
+                };
+                match list_schema_version(client_pool, &config.placement_center, request).await {
+                    Ok(reply) => {
+                        for raw in reply.schemas {
+                            match serde_json::from_slice::<SchemaData>(raw.as_slice()) {
+                                Ok(schema) => schema_manager.add_schema(schema),
+                                Err(e) => error!("{}", e),
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        panic!(
+                            "Failed to load the schema version list with error message:{}",
+                            e
+                        );
+                    }
+                }
+            }
+        }
+        schema_manager.add_schema_resource(&schema_bind);
+    }
+
     // load all auto subscribe rule
     let auto_subscribe_storage = AutoSubscribeStorage::new(client_pool.clone());
     let auto_subscribe_rules = match auto_subscribe_storage.list_auto_subscribe_rule().await {
@@ -294,6 +382,50 @@ pub async fn update_cache_metadata(
                 }
             }
         },
+        MqttBrokerUpdateCacheResourceType::Rule => match request.action_type() {
+            MqttBrokerUpdateCacheActionType::Set => {
+                match serde_json::from_str::<MqttRule>(&request.data) {
+                    Ok(rule) => {
+                        cache_manager.add_rule(rule);
+                    }
+                    Err(e) => {
+                        error!("{}", e);
+                    }
+                }
+            }
+            MqttBrokerUpdateCacheActionType::Delete => {
+                match serde_json::from_str::<MqttRule>(&request.data) {
+                    Ok(rule) => {
+                        cache_manager.delete_rule(&rule.rule_name);
+                    }
+                    Err(e) => {
+                        error!("{}", e);
+                    }
+                }
+            }
+        },
+        MqttBrokerUpdateCacheResourceType::Tenant => match request.action_type() {
+            MqttBrokerUpdateCacheActionType::Set => {
+                match serde_json::from_str::<MqttTenant>(&request.data) {
+                    Ok(tenant) => {
+                        cache_manager.add_tenant(tenant);
+                    }
+                    Err(e) => {
+                        error!("{}", e);
+                    }
+                }
+            }
+            MqttBrokerUpdateCacheActionType::Delete => {
+                match serde_json::from_str::<MqttTenant>(&request.data) {
+                    Ok(tenant) => {
+                        cache_manager.delete_tenant(&tenant.tenant_name);
+                    }
+                    Err(e) => {
+                        error!("{}", e);
+                    }
+                }
+            }
+        },
         MqttBrokerUpdateCacheResourceType::Schema => match request.action_type() {
             MqttBrokerUpdateCacheActionType::Set => {
                 match serde_json::from_str::<SchemaData>(&request.data) {
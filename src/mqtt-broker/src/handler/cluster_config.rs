@@ -12,23 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::handler::cache::CacheManager;
 use crate::handler::error::MqttBrokerError;
 use crate::storage::cluster::ClusterStorage;
-use common_base::config::broker_mqtt::{broker_mqtt_conf, ConfigAvailableFlag};
+use common_base::config::broker_mqtt::{
+    broker_mqtt_conf, BrokerMqttConfig, ConfigAvailableFlag, ConfigTenantSource,
+};
+use common_base::config::reload::subscribe_config_reload;
 use grpc_clients::pool::ClientPool;
+use log::error;
 use metadata_struct::mqtt::cluster::{
-    AvailableFlag, MqttClusterDynamicConfig, MqttClusterDynamicConfigFeature,
-    MqttClusterDynamicConfigNetwork, MqttClusterDynamicConfigProtocol,
-    MqttClusterDynamicConfigSecurity, MqttClusterDynamicFlappingDetect,
-    MqttClusterDynamicOfflineMessage, MqttClusterDynamicSlowSub, DEFAULT_DYNAMIC_CONFIG_FEATURE,
+    AvailableFlag, MqttClusterDynamicAlarm, MqttClusterDynamicConfig,
+    MqttClusterDynamicConfigFeature, MqttClusterDynamicConfigNetwork,
+    MqttClusterDynamicConfigProtocol, MqttClusterDynamicConfigSecurity,
+    MqttClusterDynamicFlappingDetect, MqttClusterDynamicOfflineMessage,
+    MqttClusterDynamicRetainMessage, MqttClusterDynamicSlowSub, MqttClusterDynamicTenant,
+    TenantSource, DEFAULT_DYNAMIC_CONFIG_ALARM, DEFAULT_DYNAMIC_CONFIG_FEATURE,
     DEFAULT_DYNAMIC_CONFIG_FLAPPING_DETECT, DEFAULT_DYNAMIC_CONFIG_NETWORK,
     DEFAULT_DYNAMIC_CONFIG_OFFLINE_MESSAGE, DEFAULT_DYNAMIC_CONFIG_PROTOCOL,
-    DEFAULT_DYNAMIC_CONFIG_SLOW_SUB,
+    DEFAULT_DYNAMIC_CONFIG_RETAIN_MESSAGE, DEFAULT_DYNAMIC_CONFIG_SLOW_SUB,
+    DEFAULT_DYNAMIC_CONFIG_TENANT,
 };
 use protocol::mqtt::common::{qos, QoS};
+use tokio::select;
+use tokio::sync::broadcast;
 
 /// This section primarily implements cache management for cluster-related configuration operations.
 /// Through this implementation, we can retrieve configuration information within the cluster
@@ -73,6 +83,84 @@ pub fn get_slow_sub_config(&self) -> MqttClusterDynamicSlowSub {
         self.get_cluster_info().slow
     }
 
+    pub async fn set_alarm_config(
+        &self,
+        alarm: MqttClusterDynamicAlarm,
+    ) -> Result<(), MqttBrokerError> {
+        if let Some(mut config) = self.cluster_info.get_mut(&self.cluster_name) {
+            config.alarm = alarm.clone();
+        }
+
+        self.save_dynamic_config(DEFAULT_DYNAMIC_CONFIG_ALARM, alarm.encode())
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn get_alarm_config(&self) -> MqttClusterDynamicAlarm {
+        self.get_cluster_info().alarm
+    }
+
+    pub async fn set_retain_message_config(
+        &self,
+        retain_message: MqttClusterDynamicRetainMessage,
+    ) -> Result<(), MqttBrokerError> {
+        if let Some(mut config) = self.cluster_info.get_mut(&self.cluster_name) {
+            config.retain_message = retain_message.clone();
+        }
+
+        self.save_dynamic_config(
+            DEFAULT_DYNAMIC_CONFIG_RETAIN_MESSAGE,
+            retain_message.encode(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub fn get_retain_message_config(&self) -> MqttClusterDynamicRetainMessage {
+        self.get_cluster_info().retain_message
+    }
+
+    pub async fn set_tenant_config(
+        &self,
+        tenant: MqttClusterDynamicTenant,
+    ) -> Result<(), MqttBrokerError> {
+        if let Some(mut config) = self.cluster_info.get_mut(&self.cluster_name) {
+            config.tenant = tenant.clone();
+        }
+
+        self.save_dynamic_config(DEFAULT_DYNAMIC_CONFIG_TENANT, tenant.encode())
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn get_tenant_config(&self) -> MqttClusterDynamicTenant {
+        self.get_cluster_info().tenant
+    }
+
+    pub async fn set_network_config(
+        &self,
+        network: MqttClusterDynamicConfigNetwork,
+    ) -> Result<(), MqttBrokerError> {
+        if let Some(mut config) = self.cluster_info.get_mut(&self.cluster_name) {
+            config.network = network.clone();
+        }
+
+        self.save_dynamic_config(DEFAULT_DYNAMIC_CONFIG_NETWORK, network.encode())
+            .await?;
+
+        Ok(())
+    }
+
+    // A reloaded config file wins over the network limits stored in the
+    // placement center, so they are written back there as well.
+    pub async fn reload_network_config(&self) -> Result<(), MqttBrokerError> {
+        let network = build_network_by_conf(&broker_mqtt_conf());
+        self.set_network_config(network).await
+    }
+
     pub fn set_cluster_info(&self, cluster: MqttClusterDynamicConfig) {
         self.cluster_info.insert(self.cluster_name.clone(), cluster);
     }
@@ -95,6 +183,36 @@ async fn save_dynamic_config(
     }
 }
 
+pub async fn apply_cluster_config_reload(
+    cache_manager: Arc<CacheManager>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let mut stop_rx = stop_send.subscribe();
+    let mut reload_rx = subscribe_config_reload();
+    loop {
+        select! {
+            val = stop_rx.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        break;
+                    }
+                }
+            }
+            val = reload_rx.recv() => {
+                let Ok(report) = val else {
+                    continue;
+                };
+                if !report.is_applied("cluster_dynamic_config_network") {
+                    continue;
+                }
+                if let Err(e) = cache_manager.reload_network_config().await {
+                    error!("Failed to apply reloaded network config, error message:{}", e);
+                }
+            }
+        }
+    }
+}
+
 pub fn build_default_cluster_config() -> MqttClusterDynamicConfig {
     MqttClusterDynamicConfig {
         protocol: MqttClusterDynamicConfigProtocol {
@@ -140,6 +258,25 @@ pub fn build_default_cluster_config() -> MqttClusterDynamicConfig {
             ban_time: 5,
         },
         offline_message: MqttClusterDynamicOfflineMessage { enable: true },
+        alarm: MqttClusterDynamicAlarm {
+            enable: true,
+            check_interval_secs: 30,
+            connection_high_watermark: 10000,
+            storage_write_failures: 10,
+            flapping_detect_bans: 10,
+            schema_validation_failures: 100,
+            slow_subscriptions: 100,
+        },
+        retain_message: MqttClusterDynamicRetainMessage {
+            max_retain_message_num: 100000,
+        },
+        tenant: MqttClusterDynamicTenant {
+            enable: false,
+            source: TenantSource::Username,
+            username_separator: "/".to_string(),
+            cert_field: "O".to_string(),
+            listener_tenants: HashMap::new(),
+        },
     }
 }
 
@@ -154,6 +291,9 @@ pub async fn build_cluster_config(
         slow: build_slow_sub(client_pool).await?,
         flapping_detect: build_flapping_detect(client_pool).await?,
         offline_message: build_offline_message(client_pool).await?,
+        alarm: build_alarm(client_pool).await?,
+        retain_message: build_retain_message(client_pool).await?,
+        tenant: build_tenant(client_pool).await?,
     })
 }
 
@@ -265,7 +405,11 @@ async fn build_network(
         let cluster = serde_json::from_slice::<MqttClusterDynamicConfigNetwork>(&data)?;
         return Ok(cluster);
     }
-    Ok(MqttClusterDynamicConfigNetwork {
+    Ok(build_network_by_conf(&conf))
+}
+
+fn build_network_by_conf(conf: &BrokerMqttConfig) -> MqttClusterDynamicConfigNetwork {
+    MqttClusterDynamicConfigNetwork {
         tcp_max_connection_num: conf.cluster_dynamic_config_network.tcp_max_connection_num,
         tcps_max_connection_num: conf.cluster_dynamic_config_network.tcps_max_connection_num,
         websocket_max_connection_num: conf
@@ -280,7 +424,7 @@ async fn build_network(
         response_try_mut_sleep_time_ms: conf
             .cluster_dynamic_config_network
             .response_try_mut_sleep_time_ms,
-    })
+    }
 }
 
 async fn build_slow_sub(
@@ -343,3 +487,76 @@ async fn build_offline_message(
         enable: conf.offline_messages.enable,
     })
 }
+
+async fn build_alarm(
+    client_pool: &Arc<ClientPool>,
+) -> Result<MqttClusterDynamicAlarm, MqttBrokerError> {
+    let conf = broker_mqtt_conf();
+    let cluster_storage = ClusterStorage::new(client_pool.clone());
+    let data = cluster_storage
+        .get_dynamic_config(&conf.cluster_name, DEFAULT_DYNAMIC_CONFIG_ALARM)
+        .await?;
+    if !data.is_empty() {
+        let cluster = serde_json::from_slice::<MqttClusterDynamicAlarm>(&data)?;
+        return Ok(cluster);
+    }
+    let alarm = &conf.cluster_dynamic_config_alarm;
+    Ok(MqttClusterDynamicAlarm {
+        enable: alarm.enable,
+        check_interval_secs: alarm.check_interval_secs,
+        connection_high_watermark: alarm.connection_high_watermark,
+        storage_write_failures: alarm.storage_write_failures,
+        flapping_detect_bans: alarm.flapping_detect_bans,
+        schema_validation_failures: alarm.schema_validation_failures,
+        slow_subscriptions: alarm.slow_subscriptions,
+    })
+}
+
+async fn build_retain_message(
+    client_pool: &Arc<ClientPool>,
+) -> Result<MqttClusterDynamicRetainMessage, MqttBrokerError> {
+    let conf = broker_mqtt_conf();
+    let cluster_storage = ClusterStorage::new(client_pool.clone());
+    let data = cluster_storage
+        .get_dynamic_config(&conf.cluster_name, DEFAULT_DYNAMIC_CONFIG_RETAIN_MESSAGE)
+        .await?;
+    if !data.is_empty() {
+        let cluster = serde_json::from_slice::<MqttClusterDynamicRetainMessage>(&data)?;
+        return Ok(cluster);
+    }
+    Ok(MqttClusterDynamicRetainMessage {
+        max_retain_message_num: conf
+            .cluster_dynamic_config_retain_message
+            .max_retain_message_num,
+    })
+}
+
+async fn build_tenant(
+    client_pool: &Arc<ClientPool>,
+) -> Result<MqttClusterDynamicTenant, MqttBrokerError> {
+    let conf = broker_mqtt_conf();
+    let cluster_storage = ClusterStorage::new(client_pool.clone());
+    let data = cluster_storage
+        .get_dynamic_config(&conf.cluster_name, DEFAULT_DYNAMIC_CONFIG_TENANT)
+        .await?;
+    if !data.is_empty() {
+        let cluster = serde_json::from_slice::<MqttClusterDynamicTenant>(&data)?;
+        return Ok(cluster);
+    }
+    let tenant = &conf.cluster_dynamic_config_tenant;
+    Ok(MqttClusterDynamicTenant {
+        enable: tenant.enable,
+        source: to_tenant_source(tenant.source.clone()),
+        username_separator: tenant.username_separator.clone(),
+        cert_field: tenant.cert_field.clone(),
+        listener_tenants: tenant.listener_tenants.clone(),
+    })
+}
+
+fn to_tenant_source(source: ConfigTenantSource) -> TenantSource {
+    match source {
+        ConfigTenantSource::Username => TenantSource::Username,
+        ConfigTenantSource::CertField => TenantSource::CertField,
+        ConfigTenantSource::Listener => TenantSource::Listener,
+    }
+}
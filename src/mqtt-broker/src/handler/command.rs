@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Instant;
 
 use super::flow_control::is_qos_message;
 use super::mqtt::MqttService;
@@ -21,16 +23,17 @@
 use crate::handler::response::{
     response_packet_mqtt_connect_fail, response_packet_mqtt_distinct_by_reason,
 };
+use crate::observability::metrics::publish::{metrics_inflight_window, metrics_publish_latency};
+use crate::rule_engine::RuleEngine;
 use crate::security::AuthDriver;
 use crate::server::connection::NetworkConnection;
 use crate::server::connection_manager::ConnectionManager;
 use crate::subscribe::subscribe_manager::SubscribeManager;
-use common_base::telemetry::trace::CustomContext;
+use common_base::telemetry::trace::{extract_context, start_span};
 use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
 use log::info;
-use opentelemetry::global;
-use opentelemetry::trace::{Span, SpanKind, Tracer};
+use opentelemetry::trace::{FutureExt, SpanKind, TraceContextExt};
 use protocol::mqtt::common::{
     is_mqtt3, is_mqtt4, is_mqtt5, ConnectReturnCode, DisconnectReasonCode, MqttPacket, MqttProtocol,
 };
@@ -55,6 +58,7 @@ pub fn new(
         cache_manager: Arc<CacheManager>,
         message_storage_adapter: Arc<S>,
         delay_message_manager: Arc<DelayMessageManager<S>>,
+        rule_engine: Arc<RuleEngine<S>>,
         subscribe_manager: Arc<SubscribeManager>,
         client_pool: Arc<ClientPool>,
         connection_manager: Arc<ConnectionManager>,
@@ -67,6 +71,7 @@ pub fn new(
             connection_manager.clone(),
             message_storage_adapter.clone(),
             delay_message_manager.clone(),
+            rule_engine.clone(),
             subscribe_manager.clone(),
             schema_manager.clone(),
             client_pool.clone(),
@@ -78,6 +83,7 @@ pub fn new(
             connection_manager.clone(),
             message_storage_adapter.clone(),
             delay_message_manager.clone(),
+            rule_engine.clone(),
             subscribe_manager.clone(),
             schema_manager.clone(),
             client_pool.clone(),
@@ -89,6 +95,7 @@ pub fn new(
             connection_manager.clone(),
             message_storage_adapter.clone(),
             delay_message_manager.clone(),
+            rule_engine.clone(),
             subscribe_manager.clone(),
             schema_manager.clone(),
             client_pool.clone(),
@@ -201,18 +208,6 @@ pub async fn apply(
             }
 
             MqttPacket::Publish(publish, publish_properties) => {
-                let mut context = CustomContext::default();
-                if let Some(ref p) = publish_properties {
-                    p.user_properties.iter().for_each(|(k, v)| {
-                        context.inner.insert(k.clone(), v.clone());
-                    });
-                };
-                let parent_cx = global::get_text_map_propagator(|prop| prop.extract(&context));
-                let tracer = global::tracer("robustmq/publish");
-                let mut span = tracer
-                    .span_builder("command/publish")
-                    .with_kind(SpanKind::Server)
-                    .start_with_context(&tracer, &parent_cx);
                 let connection = if let Some(se) = self
                     .metadata_cache
                     .connection_info
@@ -226,37 +221,59 @@ pub async fn apply(
                     ));
                 };
 
+                let mut carrier = HashMap::new();
+                if let Some(ref p) = publish_properties {
+                    carrier.extend(p.user_properties.iter().cloned());
+                };
+                let trace_cx = start_span(
+                    "robustmq/publish",
+                    "command/publish",
+                    SpanKind::Server,
+                    &extract_context(&carrier),
+                    &connection.client_id,
+                );
+
                 if is_qos_message(publish.qos) {
                     connection.recv_qos_message_incr();
+                    metrics_inflight_window(connection.get_recv_qos_message());
                 }
 
-                let resp = if tcp_connection.is_mqtt3() {
-                    self.mqtt3_service
-                        .publish(
-                            tcp_connection.connection_id,
-                            publish.clone(),
-                            publish_properties,
-                        )
-                        .await
-                } else if tcp_connection.is_mqtt4() {
-                    self.mqtt4_service
-                        .publish(
-                            tcp_connection.connection_id,
-                            publish.clone(),
-                            publish_properties,
-                        )
-                        .await
-                } else if tcp_connection.is_mqtt5() {
-                    self.mqtt5_service
-                        .publish(
-                            tcp_connection.connection_id,
-                            publish.clone(),
-                            publish_properties,
-                        )
-                        .await
+                let start = Instant::now();
+                let publish_fut = async {
+                    if tcp_connection.is_mqtt3() {
+                        self.mqtt3_service
+                            .publish(
+                                tcp_connection.connection_id,
+                                publish.clone(),
+                                publish_properties,
+                            )
+                            .await
+                    } else if tcp_connection.is_mqtt4() {
+                        self.mqtt4_service
+                            .publish(
+                                tcp_connection.connection_id,
+                                publish.clone(),
+                                publish_properties,
+                            )
+                            .await
+                    } else if tcp_connection.is_mqtt5() {
+                        self.mqtt5_service
+                            .publish(
+                                tcp_connection.connection_id,
+                                publish.clone(),
+                                publish_properties,
+                            )
+                            .await
+                    } else {
+                        None
+                    }
+                };
+                let resp = if let Some(cx) = &trace_cx {
+                    publish_fut.with_context(cx.clone()).await
                 } else {
-                    None
+                    publish_fut.await
                 };
+                metrics_publish_latency(publish.qos, start.elapsed().as_secs_f64() * 1000.0);
 
                 if let Some(pack) = resp.clone() {
                     if let MqttPacket::PubRec(_, _) = pack {
@@ -265,11 +282,15 @@ pub async fn apply(
                         connection.recv_qos_message_decr();
                     }
                 }
-                // span add_event
-                span.add_event(
-                    format!("connection_id: {:?}", tcp_connection.connection_id),
-                    vec![],
-                );
+
+                if let Some(cx) = trace_cx {
+                    let span = cx.span();
+                    span.add_event(
+                        format!("connection_id: {:?}", tcp_connection.connection_id),
+                        vec![],
+                    );
+                    span.end();
+                }
                 return resp;
             }
 
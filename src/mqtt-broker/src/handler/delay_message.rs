@@ -12,39 +12,55 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_base::tools::now_second;
+
 use super::error::MqttBrokerError;
 
-const DELAY_PUBLISH_MESSAGE_PREFIXED: &str = "$delayed";
+const DELAY_PUBLISH_MESSAGE_PREFIXED: &str = "$delayed/";
+const DELAY_ABSOLUTE_TIME_PREFIXED: &str = "@";
 
+#[derive(Debug, PartialEq)]
 pub struct DelayPublishTopic {
-    pub topic: String,
-    pub delay_timestamp: u64,
+    pub target_topic_name: String,
+    pub due_timestamp: u64,
 }
 
 pub fn is_delay_message(topic: &str) -> bool {
     topic.starts_with(DELAY_PUBLISH_MESSAGE_PREFIXED)
 }
 
+// $delayed/{seconds}/{topic} delays by a number of seconds, $delayed/@{epoch}/{topic}
+// delivers at an absolute unix time in seconds. A due time in the past is delivered
+// right away.
 pub fn decode_delay_topic(topic: &str) -> Result<Option<DelayPublishTopic>, MqttBrokerError> {
-    if !is_delay_message(topic) {
-        return Ok(None);
-    }
-    let mut str_slice: Vec<&str> = topic.split("/").collect();
-    if str_slice.len() < 3 {
+    let Some(rest) = topic.strip_prefix(DELAY_PUBLISH_MESSAGE_PREFIXED) else {
         return Ok(None);
+    };
+    let Some((delay, target_topic_name)) = rest.split_once('/') else {
+        return Err(MqttBrokerError::DelayPublishDecodeTopicNameFail(
+            topic.to_owned(),
+        ));
+    };
+    if target_topic_name.is_empty() {
+        return Err(MqttBrokerError::DelayPublishDecodeTopicNameFail(
+            topic.to_owned(),
+        ));
     }
-    let delay_timestamp = str_slice[1].parse::<u64>()?;
-    str_slice.remove(0);
-    str_slice.remove(0);
-    let msg = DelayPublishTopic {
-        topic: format!("/{}", str_slice.join("/")),
-        delay_timestamp,
+
+    let due_timestamp = match delay.strip_prefix(DELAY_ABSOLUTE_TIME_PREFIXED) {
+        Some(epoch) => epoch.parse::<u64>()?,
+        None => now_second() + delay.parse::<u64>()?,
     };
-    Ok(Some(msg))
+    Ok(Some(DelayPublishTopic {
+        target_topic_name: target_topic_name.to_owned(),
+        due_timestamp,
+    }))
 }
 
 #[cfg(test)]
 mod test {
+    use common_base::tools::now_second;
+
     #[test]
     pub fn is_delay_message_test() {
         let topic_name = "$delayed/60/a/b";
@@ -55,6 +71,9 @@ pub fn is_delay_message_test() {
 
         let topic_name = "/a/b";
         assert!(!super::is_delay_message(topic_name));
+
+        let topic_name = "$delayedx/60/a/b";
+        assert!(!super::is_delay_message(topic_name));
     }
 
     #[test]
@@ -63,7 +82,22 @@ pub fn decode_delay_message_test() {
         let msg = super::decode_delay_topic(topic_name).unwrap();
         assert!(msg.is_some());
         let msg = msg.unwrap();
-        assert_eq!(msg.topic, "/a/b");
-        assert_eq!(msg.delay_timestamp, 60);
+        assert_eq!(msg.target_topic_name, "a/b");
+        assert!(msg.due_timestamp >= now_second() + 59 && msg.due_timestamp <= now_second() + 60);
+
+        let topic_name = "$delayed/@1893456000/a/b";
+        let msg = super::decode_delay_topic(topic_name).unwrap().unwrap();
+        assert_eq!(msg.target_topic_name, "a/b");
+        assert_eq!(msg.due_timestamp, 1893456000);
+
+        let topic_name = "$delayed/5000000/a/b";
+        let msg = super::decode_delay_topic(topic_name).unwrap().unwrap();
+        assert!(msg.due_timestamp >= now_second() + 4999999);
+
+        assert!(super::decode_delay_topic("a/b").unwrap().is_none());
+        assert!(super::decode_delay_topic("$delayed/60").is_err());
+        assert!(super::decode_delay_topic("$delayed/60/").is_err());
+        assert!(super::decode_delay_topic("$delayed/x/a").is_err());
+        assert!(super::decode_delay_topic("$delayed/@/a").is_err());
     }
 }
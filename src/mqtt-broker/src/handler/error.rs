@@ -110,8 +110,68 @@ pub enum MqttBrokerError {
     #[error("Invalid schema type {0}")]
     InvalidSchemaType(String),
 
+    #[error("Invalid schema compatibility {0}")]
+    InvalidSchemaCompatibility(String),
+
+    #[error("Schema {0} not found")]
+    SchemaNotFound(String),
+
     #[error("kafka error: {0}")]
     KafkaError(#[from] KafkaError),
+
+    #[error("Rule SQL parse error: {0}")]
+    RuleSqlParseError(String),
+
+    #[error("Rule evaluation failed: {0}")]
+    RuleEvalError(String),
+
+    #[error("Connector {0} does not exist")]
+    ConnectorNotFound(String),
+
+    #[error("mqtt bridge error: {0}")]
+    PahoMqttError(#[from] paho_mqtt::Error),
+
+    #[error("http request error: {0}")]
+    ReqwestError(#[from] reqwest::Error),
+
+    #[error("{0}")]
+    PostgresError(#[from] r2d2_postgres::postgres::Error),
+
+    #[error("{0}")]
+    R2d2Error(#[from] r2d2_postgres::r2d2::Error),
+
+    #[error("Invalid SQL template: {0}")]
+    RdbTemplateError(String),
+
+    #[error("{0}")]
+    RedisError(#[from] redis::RedisError),
+
+    #[error("Client {0} is not connected")]
+    ClientNotOnline(String),
+
+    #[error("Client {0} is still connected, disconnect it before cleaning its session")]
+    ClientIsOnline(String),
+
+    #[error("Broker node {0} does not exist")]
+    BrokerNodeNotFound(u64),
+
+    #[error("Client {0} is not allowed to publish to topic {1}")]
+    PublishNotAuthorized(String, String),
+
+    #[error("Failed to load TLS certificate {0}: {1}")]
+    TlsCertLoadFailed(String, String),
+
+    #[error("Tenant {0} does not exist")]
+    TenantNotFound(String),
+
+    #[error("Tenant {0} has exceeded its {1} quota")]
+    TenantQuotaExceeded(String, String),
+
+    #[error("Tenant {0} has been existed")]
+    TenantAlreadyExist(String),
+
+    #[error("Tenant name {0} is invalid, only letters, digits and underscores are allowed")]
+    TenantNameInvalid(String),
 }
 
 impl From<MqttBrokerError> for Status {
@@ -15,6 +15,7 @@
 use crate::handler::cache::CacheManager;
 use crate::handler::error::MqttBrokerError;
 use crate::observability::metrics::event_metrics;
+use crate::observability::warn::record_flapping_detect_ban;
 use common_base::enum_type::time_unit_enum::TimeUnit;
 use common_base::tools::{convert_seconds, now_second};
 use log::{debug, error, info};
@@ -148,6 +149,7 @@ fn add_blacklist_4_connection_jitter(
     };
 
     cache_manager.add_blacklist(client_id_blacklist);
+    record_flapping_detect_ban();
 }
 
 fn is_within_window_time(
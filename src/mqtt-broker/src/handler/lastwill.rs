@@ -60,7 +60,7 @@ pub async fn send_last_will_message<S>(
 
     save_retain_message(
         cache_manager,
-        client_pool,
+        &message_storage_adapter,
         topic_name,
         client_id,
         &publish,
@@ -69,7 +69,8 @@ pub async fn send_last_will_message<S>(
     .await?;
 
     // Persisting stores message data
-    let message_storage = MessageStorage::new(message_storage_adapter.clone());
+    let message_storage =
+        MessageStorage::new_by_topic(message_storage_adapter.clone(), &topic.topic_name);
 
     let message_expire = build_message_expire(cache_manager, &publish_properties);
     if let Some(record) =
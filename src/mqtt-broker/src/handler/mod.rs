@@ -30,13 +30,17 @@
 pub mod mqtt;
 pub mod offline_message;
 pub mod pkid;
+pub mod publish;
 pub mod response;
 pub mod retain;
+pub mod retain_index;
 pub mod session;
 pub mod sub_auto;
 pub mod sub_exclusive;
 pub mod sub_parse_topic;
 pub mod subscribe;
+pub mod tenant;
+pub mod tenant_usage;
 pub mod topic;
 mod topic_rewrite;
 pub mod unsubscribe;
@@ -15,6 +15,7 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use bytes::Bytes;
 use common_base::tools::now_second;
 use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
@@ -31,10 +32,15 @@
 use storage_adapter::storage::StorageAdapter;
 
 use super::connection::{disconnect_connection, is_delete_session};
-use super::offline_message::save_message;
+use super::error::MqttBrokerError;
+use super::publish::publish_message;
 use super::retain::{is_new_sub, try_send_retain_message};
 use super::sub_auto::start_auto_subscribe;
 use super::subscribe::save_subscribe;
+use super::tenant::{
+    is_reserved_client_id, is_tenant_topic, resolve_tenant, tenant_client_id, tenant_subscribe,
+    tenant_topic_name,
+};
 use super::unsubscribe::remove_subscribe;
 use crate::handler::cache::{
     CacheManager, ConnectionLiveTime, QosAckPackageData, QosAckPackageType,
@@ -52,9 +58,8 @@
     response_packet_mqtt_pubrel_success, response_packet_mqtt_suback,
     response_packet_mqtt_unsuback,
 };
-use crate::handler::retain::save_retain_message;
 use crate::handler::session::{build_session, save_session};
-use crate::handler::topic::{get_topic_name, try_init_topic};
+use crate::handler::topic::get_topic_name;
 use crate::handler::topic_rewrite::{process_sub_topic_rewrite, process_unsub_topic_rewrite};
 use crate::handler::validator::{
     connect_validator, publish_validator, subscribe_validator, un_subscribe_validator,
@@ -63,6 +68,7 @@
     st_report_connected_event, st_report_disconnected_event, st_report_subscribed_event,
     st_report_unsubscribed_event,
 };
+use crate::rule_engine::RuleEngine;
 use crate::security::AuthDriver;
 use crate::server::connection_manager::ConnectionManager;
 use crate::subscribe::sub_common::{min_qos, path_contain_sub};
@@ -75,6 +81,7 @@ pub struct MqttService<S> {
     connection_manager: Arc<ConnectionManager>,
     message_storage_adapter: Arc<S>,
     delay_message_manager: Arc<DelayMessageManager<S>>,
+    rule_engine: Arc<RuleEngine<S>>,
     subscribe_manager: Arc<SubscribeManager>,
     schema_manager: Arc<SchemaRegisterManager>,
     client_pool: Arc<ClientPool>,
@@ -92,6 +99,7 @@ pub fn new(
         connection_manager: Arc<ConnectionManager>,
         message_storage_adapter: Arc<S>,
         delay_message_manager: Arc<DelayMessageManager<S>>,
+        rule_engine: Arc<RuleEngine<S>>,
         subscribe_manager: Arc<SubscribeManager>,
         schema_manager: Arc<SchemaRegisterManager>,
         client_pool: Arc<ClientPool>,
@@ -103,6 +111,7 @@ pub fn new(
             connection_manager,
             message_storage_adapter,
             delay_message_manager,
+            rule_engine,
             subscribe_manager,
             client_pool,
             auth_driver,
@@ -136,9 +145,38 @@ pub async fn connect(
             return res;
         }
 
+        // tenant of the client, its client id and last will live in the tenant namespace
+        let tenant = resolve_tenant(
+            &cluster.tenant,
+            login,
+            &self.connection_manager.get_connect(connect_id),
+        );
+        if !tenant.is_empty() && self.cache_manager.get_tenant(&tenant).is_none() {
+            return response_packet_mqtt_connect_fail(
+                &self.protocol,
+                ConnectReturnCode::NotAuthorized,
+                &connect_properties,
+                Some(MqttBrokerError::TenantNotFound(tenant).to_string()),
+            );
+        }
+        let last_will = last_will.map(|mut will| {
+            let topic = String::from_utf8_lossy(&will.topic).to_string();
+            will.topic = Bytes::from(tenant_topic_name(&tenant, &topic));
+            will
+        });
+
         // blacklist check
-        let (client_id, new_client_id) = get_client_id(&connect.client_id);
-        let connection = build_connection(
+        let (raw_client_id, new_client_id) = get_client_id(&connect.client_id);
+        if is_reserved_client_id(&cluster.tenant, &tenant, &raw_client_id) {
+            return response_packet_mqtt_connect_fail(
+                &self.protocol,
+                ConnectReturnCode::ClientIdentifierNotValid,
+                &connect_properties,
+                None,
+            );
+        }
+        let client_id = tenant_client_id(&tenant, &raw_client_id);
+        let mut connection = build_connection(
             connect_id,
             client_id.clone(),
             &cluster,
@@ -146,6 +184,7 @@ pub async fn connect(
             &connect_properties,
             &addr,
         );
+        connection.tenant = tenant.clone();
 
         if self.auth_driver.allow_connect(&connection).await {
             return response_packet_mqtt_connect_fail(
@@ -182,9 +221,29 @@ pub async fn connect(
             }
         }
 
+        // tenant quota check
+        let quota_res = self
+            .cache_manager
+            .check_tenant_connection_quota(&tenant)
+            .and_then(|_| {
+                if self.cache_manager.get_session_info(&client_id).is_none() {
+                    self.cache_manager.check_tenant_session_quota(&tenant)
+                } else {
+                    Ok(())
+                }
+            });
+        if let Err(e) = quota_res {
+            return response_packet_mqtt_connect_fail(
+                &self.protocol,
+                ConnectReturnCode::QuotaExceeded,
+                &connect_properties,
+                Some(e.to_string()),
+            );
+        }
+
         // flapping detect check
         if cluster.flapping_detect.enable {
-            check_flapping_detect(connect.client_id.clone(), &self.cache_manager);
+            check_flapping_detect(client_id.clone(), &self.cache_manager);
         }
 
         let (session, new_session) = match build_session(
@@ -245,6 +304,7 @@ pub async fn connect(
 
         if let Err(e) = start_auto_subscribe(
             client_id.clone(),
+            &tenant,
             login,
             &self.protocol,
             &self.client_pool,
@@ -288,7 +348,7 @@ pub async fn connect(
         response_packet_mqtt_connect_success(
             &self.protocol,
             &cluster,
-            client_id,
+            raw_client_id,
             new_client_id,
             session.session_expiry as u32,
             new_session,
@@ -331,7 +391,7 @@ pub async fn publish(
 
         let is_puback = publish.qos != QoS::ExactlyOnce;
 
-        let topic_name = match get_topic_name(
+        let client_topic_name = match get_topic_name(
             connect_id,
             &self.cache_manager,
             &publish,
@@ -359,9 +419,31 @@ pub async fn publish(
             }
         };
 
+        // The tenant namespaces are only reachable through the mount of the client's tenant
+        if is_tenant_topic(&client_topic_name) {
+            let e = MqttBrokerError::TopicNameIncorrectlyFormatted(client_topic_name.clone());
+            if is_puback {
+                return Some(response_packet_mqtt_puback_fail(
+                    &self.protocol,
+                    &connection,
+                    publish.pkid,
+                    PubAckReason::TopicNameInvalid,
+                    Some(e.to_string()),
+                ));
+            } else {
+                return Some(response_packet_mqtt_pubrec_fail(
+                    &self.protocol,
+                    &connection,
+                    publish.pkid,
+                    PubRecReason::TopicNameInvalid,
+                    Some(e.to_string()),
+                ));
+            }
+        }
+
         if !self
             .auth_driver
-            .allow_publish(&connection, &topic_name, publish.retain, publish.qos)
+            .allow_publish(&connection, &client_topic_name, publish.retain, publish.qos)
             .await
         {
             if is_puback {
@@ -383,116 +465,47 @@ pub async fn publish(
             }
         }
 
-        let topic = match try_init_topic(
-            &topic_name,
-            &self.cache_manager,
-            &self.message_storage_adapter,
-            &self.client_pool,
-        )
-        .await
-        {
-            Ok(tp) => tp,
-            Err(e) => {
-                if is_puback {
-                    return Some(response_packet_mqtt_puback_fail(
-                        &self.protocol,
-                        &connection,
-                        publish.pkid,
-                        PubAckReason::UnspecifiedError,
-                        Some(e.to_string()),
-                    ));
-                } else {
-                    return Some(response_packet_mqtt_pubrec_fail(
-                        &self.protocol,
-                        &connection,
-                        publish.pkid,
-                        PubRecReason::UnspecifiedError,
-                        Some(e.to_string()),
-                    ));
-                }
-            }
-        };
-
-        if self.schema_manager.is_check_schema(&topic_name) {
-            if let Err(e) = self.schema_manager.validate(&topic_name, &publish.payload) {
-                if is_puback {
-                    return Some(response_packet_mqtt_puback_fail(
-                        &self.protocol,
-                        &connection,
-                        publish.pkid,
-                        PubAckReason::UnspecifiedError,
-                        Some(e.to_string()),
-                    ));
-                } else {
-                    return Some(response_packet_mqtt_pubrec_fail(
-                        &self.protocol,
-                        &connection,
-                        publish.pkid,
-                        PubRecReason::UnspecifiedError,
-                        Some(e.to_string()),
-                    ));
-                }
-            }
-        }
-
         let client_id = connection.client_id.clone();
-
-        // Persisting retain message data
-        match save_retain_message(
+        let result = match publish_message(
             &self.cache_manager,
             &self.client_pool,
-            topic_name.clone(),
+            &self.message_storage_adapter,
+            &self.delay_message_manager,
+            &self.subscribe_manager,
+            &self.schema_manager,
+            &self.rule_engine,
+            &connection.tenant,
             &client_id,
+            &client_topic_name,
             &publish,
             &publish_properties,
         )
         .await
         {
-            Ok(()) => {}
+            Ok(result) => result,
             Err(e) => {
-                if is_puback {
-                    return Some(response_packet_mqtt_puback_fail(
-                        &self.protocol,
-                        &connection,
-                        publish.pkid,
+                let (ack_reason, rec_reason) = match &e {
+                    MqttBrokerError::DelayPublishDecodeTopicNameFail(_) => (
+                        PubAckReason::TopicNameInvalid,
+                        PubRecReason::TopicNameInvalid,
+                    ),
+                    MqttBrokerError::TenantQuotaExceeded(_, _) => {
+                        if publish.qos == QoS::AtMostOnce {
+                            return None;
+                        }
+                        (PubAckReason::QuotaExceeded, PubRecReason::QuotaExceeded)
+                    }
+                    _ => (
                         PubAckReason::UnspecifiedError,
-                        Some(e.to_string()),
-                    ));
-                } else {
-                    return Some(response_packet_mqtt_pubrec_fail(
-                        &self.protocol,
-                        &connection,
-                        publish.pkid,
                         PubRecReason::UnspecifiedError,
-                        Some(e.to_string()),
-                    ));
-                }
-            }
-        }
-
-        // Persisting stores message data
-        let offset = match save_message(
-            &self.message_storage_adapter,
-            &self.delay_message_manager,
-            &self.cache_manager,
-            &publish,
-            &publish_properties,
-            &self.subscribe_manager,
-            &client_id,
-            &topic,
-        )
-        .await
-        {
-            Ok(da) => {
-                format!("{:?}", da)
-            }
-            Err(e) => {
+                    ),
+                };
                 if is_puback {
                     return Some(response_packet_mqtt_puback_fail(
                         &self.protocol,
                         &connection,
                         publish.pkid,
-                        PubAckReason::UnspecifiedError,
+                        ack_reason,
                         Some(e.to_string()),
                     ));
                 } else {
@@ -500,17 +513,19 @@ pub async fn publish(
                         &self.protocol,
                         &connection,
                         publish.pkid,
-                        PubRecReason::UnspecifiedError,
+                        rec_reason,
                         Some(e.to_string()),
                     ));
                 }
             }
         };
+        let topic_name = result.topic_name;
+        let offset = format!("{:?}", result.offset);
 
         let user_properties: Vec<(String, String)> = vec![("offset".to_string(), offset)];
 
         self.cache_manager
-            .add_topic_alias(connect_id, &topic_name, &publish_properties);
+            .add_topic_alias(connect_id, &client_topic_name, &publish_properties);
 
         match publish.qos {
             QoS::AtMostOnce => None,
@@ -756,14 +771,21 @@ pub async fn subscribe(
             &self.subscribe_manager,
             &connection,
             &subscribe,
+            &subscribe_properties,
         )
         .await
         {
             return packet;
         }
 
-        let new_subs = is_new_sub(&connection.client_id, &subscribe, &self.subscribe_manager).await;
+        let new_subs = is_new_sub(
+            &connection.client_id,
+            &tenant_subscribe(&connection.tenant, &subscribe),
+            &self.subscribe_manager,
+        )
+        .await;
         process_sub_topic_rewrite(&mut subscribe, &self.cache_manager.topic_rewrite_rule);
+        let subscribe = tenant_subscribe(&connection.tenant, &subscribe);
         if let Err(e) = save_subscribe(
             &connection.client_id,
             &self.protocol,
@@ -800,7 +822,7 @@ pub async fn subscribe(
             connection.client_id.clone(),
             subscribe.clone(),
             subscribe_properties.clone(),
-            self.client_pool.clone(),
+            self.message_storage_adapter.clone(),
             self.cache_manager.clone(),
             self.connection_manager.clone(),
             new_subs,
@@ -878,6 +900,9 @@ pub async fn un_subscribe(
         }
 
         process_unsub_topic_rewrite(&mut un_subscribe, &self.cache_manager.topic_rewrite_rule);
+        for filter in un_subscribe.filters.iter_mut() {
+            *filter = tenant_topic_name(&connection.tenant, filter);
+        }
 
         if let Err(e) = remove_subscribe(
             &connection.client_id,
@@ -15,17 +15,20 @@
 use std::sync::Arc;
 
 use super::{
-    cache::CacheManager,
-    delay_message::{decode_delay_topic, is_delay_message},
-    error::MqttBrokerError,
-    message::build_message_expire,
+    cache::CacheManager, delay_message::DelayPublishTopic, error::MqttBrokerError,
+    message::build_message_expire, tenant::split_tenant_mount,
 };
 use crate::{
     observability::metrics::packets::record_messages_dropped_no_subscribers_metrics,
-    storage::message::MessageStorage, subscribe::subscribe_manager::SubscribeManager,
+    storage::message::{tenant_storage_namespace, MessageStorage},
+    subscribe::subscribe_manager::SubscribeManager,
 };
+use bytes::Bytes;
+use common_base::telemetry::trace::inject_context;
 use delay_message::DelayMessageManager;
+use metadata_struct::adapter::record::Record;
 use metadata_struct::mqtt::{message::MqttMessage, topic::MqttTopic};
+use opentelemetry::Context;
 use protocol::mqtt::common::{Publish, PublishProperties};
 use storage_adapter::storage::StorageAdapter;
 
@@ -43,6 +46,7 @@ pub async fn save_message<S>(
     subscribe_manager: &Arc<SubscribeManager>,
     client_id: &str,
     topic: &MqttTopic,
+    delay_publish: &Option<DelayPublishTopic>,
 ) -> Result<Option<String>, MqttBrokerError>
 where
     S: StorageAdapter + Sync + Send + 'static + Clone,
@@ -52,6 +56,28 @@ pub async fn save_message<S>(
         return Ok(None);
     }
 
+    // Subscribers of the target topic are looked up when the message is due.
+    if let Some(delay_publish) = delay_publish {
+        let message_expire = build_message_expire(cache_manager, publish_properties);
+        let mut message =
+            MqttMessage::build_message(client_id, publish, publish_properties, message_expire);
+        message.topic = Bytes::from(delay_publish.target_topic_name.clone());
+        let mut record = Record::build_byte(message.encode());
+        record.merge_header(inject_context(&Context::current()));
+        let target_namespace = split_tenant_mount(&delay_publish.target_topic_name)
+            .map(|(tenant, _)| tenant_storage_namespace(&tenant));
+        delay_message_manager
+            .send_delay_message_to_namespace(
+                target_namespace.as_deref(),
+                &delay_publish.target_topic_name,
+                &topic.topic_id,
+                delay_publish.due_timestamp,
+                record,
+            )
+            .await?;
+        return Ok(None);
+    }
+
     // todo new topic subscribe will be handler later for this function
     // it will cause the message not to be saved in cache
     // for this problem is subscribe function didn't handle the new topic
@@ -65,26 +91,16 @@ pub async fn save_message<S>(
         return Ok(None);
     }
 
-    let message_storage = MessageStorage::new(message_storage_adapter.clone());
+    let message_storage =
+        MessageStorage::new_by_topic(message_storage_adapter.clone(), &topic.topic_name);
     let message_expire = build_message_expire(cache_manager, publish_properties);
     let offset = if let Some(record) =
         MqttMessage::build_record(client_id, publish, publish_properties, message_expire)
     {
-        if is_delay_message(&topic.topic_name) {
-            let delay_topic = decode_delay_topic(&topic.topic_name)?;
-            if delay_topic.is_none() {
-                return Err(MqttBrokerError::DelayPublishDecodeTopicNameFail(
-                    topic.topic_name.clone(),
-                ));
-            }
-            delay_message_manager.send_delay_message(record).await?;
-            return Ok(None);
-        } else {
-            let offsets = message_storage
-                .append_topic_message(&topic.topic_id, vec![record])
-                .await?;
-            Some(format!("{:?}", offsets))
-        }
+        let offsets = message_storage
+            .append_topic_message(&topic.topic_id, vec![record])
+            .await?;
+        Some(format!("{:?}", offsets))
     } else {
         None
     };
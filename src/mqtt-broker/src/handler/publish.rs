@@ -0,0 +1,117 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use delay_message::DelayMessageManager;
+use grpc_clients::pool::ClientPool;
+use protocol::mqtt::common::{Publish, PublishProperties};
+use schema_register::schema::SchemaRegisterManager;
+use storage_adapter::storage::StorageAdapter;
+
+use super::cache::CacheManager;
+use super::delay_message::decode_delay_topic;
+use super::error::MqttBrokerError;
+use super::offline_message::save_message;
+use super::retain::save_retain_message;
+use super::tenant::tenant_topic_name;
+use super::topic::try_init_topic;
+use crate::observability::warn::record_schema_validation_failure;
+use crate::rule_engine::RuleEngine;
+use crate::subscribe::subscribe_manager::SubscribeManager;
+
+pub struct PublishResult {
+    // The topic name with the tenant mount.
+    pub topic_name: String,
+    pub offset: Option<String>,
+}
+
+// Shared by MQTT PUBLISH and the admin publish API. The caller resolves the
+// topic name and checks the ACL, everything after that happens here.
+#[allow(clippy::too_many_arguments)]
+pub async fn publish_message<S>(
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+    message_storage_adapter: &Arc<S>,
+    delay_message_manager: &Arc<DelayMessageManager<S>>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
+    rule_engine: &Arc<RuleEngine<S>>,
+    tenant: &str,
+    client_id: &str,
+    client_topic_name: &str,
+    publish: &Publish,
+    publish_properties: &Option<PublishProperties>,
+) -> Result<PublishResult, MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    // Delayed messages are stored for, and checked against, their target topic.
+    let mut delay_publish = decode_delay_topic(client_topic_name)?;
+    let topic_name = tenant_topic_name(tenant, client_topic_name);
+    if let Some(delay_publish) = delay_publish.as_mut() {
+        delay_publish.target_topic_name =
+            tenant_topic_name(tenant, &delay_publish.target_topic_name);
+    }
+    let storage_topic_name = match &delay_publish {
+        Some(delay_publish) => delay_publish.target_topic_name.clone(),
+        None => topic_name.clone(),
+    };
+
+    cache_manager.check_tenant_publish(tenant, &storage_topic_name, publish.payload.len())?;
+
+    let topic = try_init_topic(
+        &storage_topic_name,
+        cache_manager,
+        message_storage_adapter,
+        client_pool,
+    )
+    .await?;
+
+    if schema_manager.is_check_schema(&storage_topic_name) {
+        if let Err(e) = schema_manager.validate(&storage_topic_name, &publish.payload) {
+            record_schema_validation_failure();
+            return Err(e.into());
+        }
+    }
+
+    // Delayed messages are not retained
+    if delay_publish.is_none() {
+        save_retain_message(
+            cache_manager,
+            message_storage_adapter,
+            topic_name.clone(),
+            client_id,
+            publish,
+            publish_properties,
+        )
+        .await?;
+    }
+
+    let offset = save_message(
+        message_storage_adapter,
+        delay_message_manager,
+        cache_manager,
+        publish,
+        publish_properties,
+        subscribe_manager,
+        client_id,
+        &topic,
+        &delay_publish,
+    )
+    .await?;
+
+    rule_engine.submit(client_id, &topic_name, publish, publish_properties);
+
+    Ok(PublishResult { topic_name, offset })
+}
@@ -12,33 +12,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
 use common_base::tools::now_second;
 use dashmap::DashMap;
 use grpc_clients::pool::ClientPool;
-use log::info;
+use log::{debug, error, info, warn};
+use metadata_struct::adapter::read_config::ReadConfig;
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::{
     MqttProtocol, Publish, PublishProperties, QoS, RetainForwardRule, Subscribe,
     SubscribeProperties,
 };
+use storage_adapter::storage::StorageAdapter;
+use tokio::select;
 use tokio::sync::broadcast::{self};
+use tokio::time::sleep;
 
 use super::cache::{CacheManager, QosAckPacketInfo};
 use super::constant::{SUB_RETAIN_MESSAGE_PUSH_FLAG, SUB_RETAIN_MESSAGE_PUSH_FLAG_VALUE};
 use super::error::MqttBrokerError;
 use super::message::build_message_expire;
+use super::retain_index::RetainIndexEntry;
+use super::tenant::tenant_topic_unmount;
 use crate::observability::metrics::packets::{
     record_retain_recv_metrics, record_retain_sent_metrics,
 };
 use crate::server::connection_manager::ConnectionManager;
+use crate::storage::retain::RetainMessageStorage;
 use crate::storage::topic::TopicStorage;
 use crate::subscribe::exclusive_push::{
     exclusive_publish_message_qos1, exclusive_publish_message_qos2,
 };
-use crate::subscribe::sub_common::{get_sub_topic_id_list, min_qos, publish_message_qos};
+use crate::subscribe::sub_common::{
+    decode_queue_info, decode_share_info, is_queue_sub, is_share_sub, min_qos, publish_message_qos,
+    strip_transcode_prefix,
+};
 use crate::subscribe::subscribe_manager::SubscribeManager;
 use crate::subscribe::subscriber::SubPublishParam;
 use crate::subscribe::subscriber::Subscriber;
@@ -58,51 +71,88 @@ pub async fn is_new_sub(
     results
 }
 
-pub async fn save_retain_message(
+pub async fn save_retain_message<S>(
     cache_manager: &Arc<CacheManager>,
-    client_pool: &Arc<ClientPool>,
+    message_storage_adapter: &Arc<S>,
     topic_name: String,
     client_id: &str,
     publish: &Publish,
     publish_properties: &Option<PublishProperties>,
-) -> Result<(), MqttBrokerError> {
+) -> Result<(), MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
     if !publish.retain {
         return Ok(());
     }
 
-    let topic_storage = TopicStorage::new(client_pool.clone());
+    let retain_storage = RetainMessageStorage::new(message_storage_adapter.clone());
+    let index = &cache_manager.retain_message_index;
 
     if publish.payload.is_empty() {
-        topic_storage
-            .delete_retain_message(topic_name.clone())
-            .await?;
-        cache_manager.update_topic_retain_message(&topic_name, Some(Vec::new()));
-    } else {
-        record_retain_recv_metrics(publish.qos);
-        let message_expire = build_message_expire(cache_manager, publish_properties);
-        let retain_message =
-            MqttMessage::build_message(client_id, publish, publish_properties, message_expire);
-        topic_storage
-            .set_retain_message(topic_name.clone(), &retain_message, message_expire)
-            .await?;
+        let offset = retain_storage.delete(&topic_name).await?;
+        index.delete(&topic_name, offset);
+        return Ok(());
+    }
 
-        cache_manager.update_topic_retain_message(&topic_name, Some(retain_message.encode()));
+    let max_num = cache_manager
+        .get_retain_message_config()
+        .max_retain_message_num;
+    if max_num > 0 && !index.contains(&topic_name) && index.len() as u64 >= max_num {
+        warn!(
+            "The number of retained messages has reached the limit {}, the retained message of topic {} is discarded",
+            max_num, topic_name
+        );
+        return Ok(());
     }
 
+    record_retain_recv_metrics(publish.qos);
+    let message_expire = build_message_expire(cache_manager, publish_properties);
+    let retain_message =
+        MqttMessage::build_message(client_id, publish, publish_properties, message_expire);
+    let offset = retain_storage.set(&topic_name, &retain_message).await?;
+    index.set(&topic_name, build_index_entry(offset, &retain_message));
+
     Ok(())
 }
 
+fn build_index_entry(offset: u64, message: &MqttMessage) -> RetainIndexEntry {
+    RetainIndexEntry {
+        offset,
+        client_id: message.client_id.clone(),
+        qos: message.qos.into(),
+        payload_size: message.payload.len(),
+        create_time: message.create_time,
+        expired_at: message.expiry_interval,
+    }
+}
+
+// Topic filter used to look up retained messages, the subscription prefixes are
+// stripped the same way as when matching published messages.
+fn retain_filter_path(path: &str) -> String {
+    let path = strip_transcode_prefix(path);
+    if is_share_sub(&path) {
+        return decode_share_info(&path).1;
+    }
+    if is_queue_sub(&path) {
+        return decode_queue_info(&path);
+    }
+    path
+}
+
 #[allow(clippy::too_many_arguments)]
-pub async fn try_send_retain_message(
+pub async fn try_send_retain_message<S>(
     protocol: MqttProtocol,
     client_id: String,
     subscribe: Subscribe,
     subscribe_properties: Option<SubscribeProperties>,
-    client_pool: Arc<ClientPool>,
+    message_storage_adapter: Arc<S>,
     cache_manager: Arc<CacheManager>,
     connection_manager: Arc<ConnectionManager>,
     is_new_subs: DashMap<String, bool>,
-) {
+) where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
     tokio::spawn(async move {
         let (stop_sx, _) = broadcast::channel(1);
         if let Err(e) = send_retain_message(
@@ -110,7 +160,7 @@ pub async fn try_send_retain_message(
             &client_id,
             &subscribe,
             &subscribe_properties,
-            &client_pool,
+            &message_storage_adapter,
             &cache_manager,
             &connection_manager,
             &stop_sx,
@@ -124,17 +174,20 @@ pub async fn try_send_retain_message(
 }
 
 #[allow(clippy::too_many_arguments)]
-async fn send_retain_message(
+async fn send_retain_message<S>(
     protocol: &MqttProtocol,
     client_id: &String,
     subscribe: &Subscribe,
     subscribe_properties: &Option<SubscribeProperties>,
-    client_pool: &Arc<ClientPool>,
+    message_storage_adapter: &Arc<S>,
     cache_manager: &Arc<CacheManager>,
     connection_manager: &Arc<ConnectionManager>,
     stop_sx: &broadcast::Sender<bool>,
     is_new_subs: &DashMap<String, bool>,
-) -> Result<(), MqttBrokerError> {
+) -> Result<(), MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
     let mut sub_ids = Vec::new();
     if let Some(properties) = subscribe_properties {
         if let Some(id) = properties.subscription_identifier {
@@ -157,17 +210,21 @@ async fn send_retain_message(
             return Ok(());
         }
 
-        let topic_id_list = get_sub_topic_id_list(cache_manager, &filter.path).await;
-        let topic_storage = TopicStorage::new(client_pool.clone());
+        let filter_path = retain_filter_path(&filter.path);
+
+        let retain_storage = RetainMessageStorage::new(message_storage_adapter.clone());
         let cluster = cache_manager.get_cluster_info();
-        for topic_id in topic_id_list.iter() {
-            let topic_name = if let Some(topic_name) = cache_manager.topic_name_by_id(topic_id) {
-                topic_name
-            } else {
-                continue;
+        let now = now_second();
+        for topic_name in cache_manager
+            .retain_message_index
+            .match_filter(&filter_path)
+        {
+            let entry = match cache_manager.retain_message_index.get(&topic_name) {
+                Some(entry) if !entry.is_expired(now) => entry,
+                _ => continue,
             };
 
-            let msg = if let Some(message) = topic_storage.get_retain_message(&topic_name).await? {
+            let msg = if let Some(message) = retain_storage.get(entry.offset).await? {
                 message
             } else {
                 continue;
@@ -213,7 +270,7 @@ async fn send_retain_message(
                 qos,
                 pkid,
                 retain,
-                topic: Bytes::from(topic_name.clone()),
+                topic: Bytes::from(tenant_topic_unmount(&topic_name)),
                 payload: msg.payload,
             };
 
@@ -226,7 +283,7 @@ async fn send_retain_message(
                 subscriber,
                 publish,
                 Some(properties),
-                msg.create_time as u128,
+                msg.create_time as u128 * 1000,
                 "".to_string(),
                 pkid,
             );
@@ -295,3 +352,264 @@ async fn send_retain_message(
     }
     Ok(())
 }
+
+// Number of retain message shard records after which a new snapshot of the index is saved
+const RETAIN_MESSAGE_SNAPSHOT_INTERVAL: u64 = 10000;
+
+// Keeps the local retained message index in line with the retain message shard
+// and drops the retained messages whose expiry time has passed. The index is
+// snapshotted regularly, so a restart only replays the records after the latest
+// snapshot instead of the whole shard.
+pub struct MessageExpire<S> {
+    cache_manager: Arc<CacheManager>,
+    client_pool: Arc<ClientPool>,
+    retain_storage: RetainMessageStorage<S>,
+    snapshot_offset: AtomicU64,
+    stop_send: broadcast::Sender<bool>,
+}
+
+impl<S> MessageExpire<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        client_pool: Arc<ClientPool>,
+        message_storage_adapter: Arc<S>,
+        stop_send: broadcast::Sender<bool>,
+    ) -> Self {
+        MessageExpire {
+            cache_manager,
+            client_pool,
+            retain_storage: RetainMessageStorage::new(message_storage_adapter),
+            snapshot_offset: AtomicU64::new(0),
+            stop_send,
+        }
+    }
+
+    pub async fn start(&self) {
+        if let Err(e) = self.retain_storage.init_shard().await {
+            error!(
+                "Failed to initialize the retain message shard, error message:{}",
+                e
+            );
+        }
+
+        if let Err(e) = self.load_snapshot().await {
+            error!(
+                "Failed to load the retain message snapshot, the shard is replayed from the start, error message:{}",
+                e
+            );
+        }
+
+        if let Err(e) = self.migrate_placement_retain_message().await {
+            error!(
+                "Failed to migrate retained messages from the placement center, error message:{}",
+                e
+            );
+        }
+
+        loop {
+            let mut stop_rx = self.stop_send.subscribe();
+            select! {
+                val = stop_rx.recv() =>{
+                    if let Ok(flag) = val {
+                        if flag {
+                            info!("{}","Retain message expire thread stopped successfully.");
+                            break;
+                        }
+                    }
+                }
+                _ = self.retain_message_expire()=>{
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    async fn retain_message_expire(&self) {
+        if let Err(e) = self.sync_retain_message_index().await {
+            error!(
+                "Failed to read the retain message shard, error message:{}",
+                e
+            );
+        }
+
+        let expired = self
+            .cache_manager
+            .retain_message_index
+            .remove_expired(now_second());
+        if !expired.is_empty() {
+            debug!("Expired retained messages: {:?}", expired);
+        }
+
+        if let Err(e) = self.try_save_snapshot().await {
+            error!(
+                "Failed to save the retain message snapshot, error message:{}",
+                e
+            );
+        }
+    }
+
+    async fn load_snapshot(&self) -> Result<(), MqttBrokerError> {
+        let Some(snapshot) = self.retain_storage.latest_snapshot().await? else {
+            return Ok(());
+        };
+        let entries = self.retain_storage.read_snapshot(&snapshot).await?;
+
+        let index = &self.cache_manager.retain_message_index;
+        let now = now_second();
+        for (topic_name, entry) in entries {
+            if !entry.is_expired(now) {
+                index.set(&topic_name, entry);
+            }
+        }
+        index.advance(snapshot.next_offset);
+        self.snapshot_offset
+            .store(snapshot.next_offset, Ordering::Relaxed);
+        info!(
+            "Retain message snapshot loaded, next offset: {}, retained messages: {}",
+            snapshot.next_offset,
+            index.len()
+        );
+        Ok(())
+    }
+
+    // Every broker follows the same shard, so the latest snapshot from any of them is
+    // checked first to avoid all of them saving one for the same records.
+    async fn try_save_snapshot(&self) -> Result<(), MqttBrokerError> {
+        let index = &self.cache_manager.retain_message_index;
+        let next_offset = index.next_offset();
+        if next_offset
+            < self.snapshot_offset.load(Ordering::Relaxed) + RETAIN_MESSAGE_SNAPSHOT_INTERVAL
+        {
+            return Ok(());
+        }
+
+        if let Some(snapshot) = self.retain_storage.latest_snapshot().await? {
+            if next_offset < snapshot.next_offset + RETAIN_MESSAGE_SNAPSHOT_INTERVAL {
+                self.snapshot_offset
+                    .store(snapshot.next_offset, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let now = now_second();
+        let entries: Vec<(String, RetainIndexEntry)> = index
+            .list()
+            .into_iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .collect();
+        let num = entries.len();
+        self.retain_storage
+            .save_snapshot(next_offset, entries)
+            .await?;
+        self.snapshot_offset.store(next_offset, Ordering::Relaxed);
+        info!(
+            "Retain message snapshot saved, next offset: {}, retained messages: {}",
+            next_offset, num
+        );
+        Ok(())
+    }
+
+    // Retained messages used to be kept on the topics in the placement center. They are
+    // copied to the retain message shard once, unless the shard already has a newer
+    // record for the topic, and the placement copy is cleared afterwards.
+    async fn migrate_placement_retain_message(&self) -> Result<(), MqttBrokerError> {
+        if self.retain_storage.is_migrated().await? {
+            return Ok(());
+        }
+
+        self.sync_retain_message_index().await?;
+        let written = self.shard_topics().await?;
+
+        let index = &self.cache_manager.retain_message_index;
+        let topic_storage = TopicStorage::new(self.client_pool.clone());
+        let now = now_second();
+        let mut migrated = 0;
+        for (topic_name, topic) in topic_storage.all().await? {
+            let Some(data) = topic.retain_message else {
+                continue;
+            };
+            if data.is_empty() {
+                continue;
+            }
+
+            if !written.contains(&topic_name) {
+                let message = serde_json::from_slice::<MqttMessage>(&data)?;
+                let entry = build_index_entry(0, &message);
+                if !entry.is_expired(now) {
+                    let offset = self.retain_storage.set(&topic_name, &message).await?;
+                    index.set(&topic_name, build_index_entry(offset, &message));
+                    migrated += 1;
+                }
+            }
+            topic_storage.delete_retain_message(topic_name).await?;
+        }
+
+        self.retain_storage.set_migrated().await?;
+        info!(
+            "Retained messages migrated from the placement center: {}",
+            migrated
+        );
+        Ok(())
+    }
+
+    // Topics with a record in the retain message shard, deleted ones included
+    async fn shard_topics(&self) -> Result<HashSet<String>, MqttBrokerError> {
+        let mut topics = HashSet::new();
+        let mut offset = 0;
+        loop {
+            let mut read_config = ReadConfig::new();
+            read_config.max_record_num = 100;
+            let records = self.retain_storage.read(offset, read_config).await?;
+            let start = offset;
+            for record in records {
+                if let Some(record_offset) = record.offset {
+                    offset = offset.max(record_offset + 1);
+                }
+                topics.insert(record.key);
+            }
+            if offset == start {
+                return Ok(topics);
+            }
+        }
+    }
+
+    async fn sync_retain_message_index(&self) -> Result<(), MqttBrokerError> {
+        let index = &self.cache_manager.retain_message_index;
+        let now = now_second();
+        loop {
+            let mut read_config = ReadConfig::new();
+            read_config.max_record_num = 100;
+            let records = self
+                .retain_storage
+                .read(index.next_offset(), read_config)
+                .await?;
+            if records.is_empty() {
+                return Ok(());
+            }
+
+            let mut next_offset = index.next_offset();
+            for record in records {
+                let Some(offset) = record.offset else {
+                    continue;
+                };
+                let topic_name = record.key.clone();
+                if record.data.is_empty() {
+                    index.delete(&topic_name, offset);
+                } else {
+                    let message = MqttMessage::decode_record(record)?;
+                    let entry = build_index_entry(offset, &message);
+                    if entry.is_expired(now) {
+                        index.delete(&topic_name, offset);
+                    } else {
+                        index.set(&topic_name, entry);
+                    }
+                }
+                next_offset = next_offset.max(offset + 1);
+            }
+            index.advance(next_offset);
+        }
+    }
+}
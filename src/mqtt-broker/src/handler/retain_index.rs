@@ -0,0 +1,329 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetainIndexEntry {
+    // Offset of the record in the retain message shard
+    pub offset: u64,
+    pub client_id: String,
+    pub qos: u8,
+    pub payload_size: usize,
+    pub create_time: u64,
+    // Absolute expiry time in seconds, 0 means the message never expires
+    pub expired_at: u64,
+}
+
+impl RetainIndexEntry {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expired_at > 0 && self.expired_at <= now
+    }
+}
+
+#[derive(Default)]
+struct TopicNode {
+    children: HashMap<String, TopicNode>,
+    retained: bool,
+}
+
+impl TopicNode {
+    fn insert(&mut self, levels: &[&str]) {
+        match levels.split_first() {
+            Some((level, rest)) => self
+                .children
+                .entry(level.to_string())
+                .or_default()
+                .insert(rest),
+            None => self.retained = true,
+        }
+    }
+
+    // Returns true when the node no longer holds anything and can be pruned.
+    fn remove(&mut self, levels: &[&str]) -> bool {
+        match levels.split_first() {
+            Some((level, rest)) => {
+                if let Some(child) = self.children.get_mut(*level) {
+                    if child.remove(rest) {
+                        self.children.remove(*level);
+                    }
+                }
+            }
+            None => self.retained = false,
+        }
+        !self.retained && self.children.is_empty()
+    }
+
+    fn collect_all(&self, path: &mut Vec<String>, results: &mut Vec<String>) {
+        if self.retained {
+            results.push(path.join("/"));
+        }
+        for (level, child) in self.children.iter() {
+            path.push(level.clone());
+            child.collect_all(path, results);
+            path.pop();
+        }
+    }
+
+    fn collect_match(&self, filter: &[&str], path: &mut Vec<String>, results: &mut Vec<String>) {
+        let Some((level, rest)) = filter.split_first() else {
+            if self.retained {
+                results.push(path.join("/"));
+            }
+            return;
+        };
+
+        match *level {
+            "#" if path.is_empty() => {
+                // Topics starting with $, tenant topics included, are not matched by a leading wildcard
+                for (name, child) in self.children.iter() {
+                    if name.starts_with('$') {
+                        continue;
+                    }
+                    path.push(name.clone());
+                    child.collect_all(path, results);
+                    path.pop();
+                }
+            }
+            "#" => self.collect_all(path, results),
+            "+" => {
+                for (name, child) in self.children.iter() {
+                    // Topics starting with $ are not matched by a leading wildcard
+                    if path.is_empty() && name.starts_with('$') {
+                        continue;
+                    }
+                    path.push(name.clone());
+                    child.collect_match(rest, path, results);
+                    path.pop();
+                }
+            }
+            name => {
+                if let Some(child) = self.children.get(name) {
+                    path.push(name.to_string());
+                    child.collect_match(rest, path, results);
+                    path.pop();
+                }
+            }
+        }
+    }
+}
+
+// In-memory index of the retain message shard. Every broker tails the shard, so
+// the index reflects retained messages written by any node of the cluster.
+#[derive(Default)]
+pub struct RetainMessageIndex {
+    entries: DashMap<String, RetainIndexEntry>,
+    tree: RwLock<TopicNode>,
+    // Deletes that were applied before the shard reader reached them, kept so
+    // that older records of the same topic are not resurrected.
+    tombstones: DashMap<String, u64>,
+    next_offset: AtomicU64,
+}
+
+impl RetainMessageIndex {
+    pub fn new() -> Self {
+        RetainMessageIndex::default()
+    }
+
+    pub fn set(&self, topic_name: &str, entry: RetainIndexEntry) {
+        if let Some(current) = self.entries.get(topic_name) {
+            if current.offset > entry.offset {
+                return;
+            }
+        }
+        if let Some(deleted_at) = self.tombstones.get(topic_name) {
+            if *deleted_at > entry.offset {
+                return;
+            }
+        }
+        self.tombstones.remove(topic_name);
+
+        if self.entries.insert(topic_name.to_string(), entry).is_none() {
+            let levels: Vec<&str> = topic_name.split('/').collect();
+            self.tree.write().unwrap().insert(&levels);
+        }
+    }
+
+    pub fn delete(&self, topic_name: &str, offset: u64) {
+        if let Some(current) = self.entries.get(topic_name) {
+            if current.offset > offset {
+                return;
+            }
+        }
+        if offset >= self.next_offset() {
+            self.tombstones.insert(topic_name.to_string(), offset);
+        }
+        self.remove(topic_name);
+    }
+
+    fn remove(&self, topic_name: &str) {
+        if self.entries.remove(topic_name).is_some() {
+            let levels: Vec<&str> = topic_name.split('/').collect();
+            self.tree.write().unwrap().remove(&levels);
+        }
+    }
+
+    pub fn get(&self, topic_name: &str) -> Option<RetainIndexEntry> {
+        self.entries.get(topic_name).map(|entry| entry.clone())
+    }
+
+    pub fn contains(&self, topic_name: &str) -> bool {
+        self.entries.contains_key(topic_name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn list(&self) -> Vec<(String, RetainIndexEntry)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    // Topic names of the retained messages matching a topic filter, wildcards included.
+    pub fn match_filter(&self, filter: &str) -> Vec<String> {
+        let levels: Vec<&str> = filter.split('/').collect();
+        let mut results = Vec::new();
+        self.tree
+            .read()
+            .unwrap()
+            .collect_match(&levels, &mut Vec::new(), &mut results);
+        results
+    }
+
+    pub fn remove_expired(&self, now: u64) -> Vec<String> {
+        let expired: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.value().is_expired(now))
+            .map(|entry| entry.key().clone())
+            .collect();
+        for topic_name in expired.iter() {
+            self.remove(topic_name);
+        }
+        expired
+    }
+
+    pub fn next_offset(&self) -> u64 {
+        self.next_offset.load(Ordering::Relaxed)
+    }
+
+    pub fn advance(&self, next_offset: u64) {
+        self.next_offset.store(next_offset, Ordering::Relaxed);
+        self.tombstones.retain(|_, offset| *offset >= next_offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetainIndexEntry, RetainMessageIndex};
+
+    fn entry(offset: u64) -> RetainIndexEntry {
+        RetainIndexEntry {
+            offset,
+            ..Default::default()
+        }
+    }
+
+    fn sorted(mut topics: Vec<String>) -> Vec<String> {
+        topics.sort();
+        topics
+    }
+
+    #[test]
+    fn match_filter_test() {
+        let index = RetainMessageIndex::new();
+        for (i, topic) in ["a/b/c", "a/b", "a/x/c", "b", "$SYS/uptime", "/lead"]
+            .iter()
+            .enumerate()
+        {
+            index.set(topic, entry(i as u64));
+        }
+
+        assert_eq!(index.match_filter("a/b"), vec!["a/b".to_string()]);
+        assert_eq!(
+            sorted(index.match_filter("a/+/c")),
+            vec!["a/b/c".to_string(), "a/x/c".to_string()]
+        );
+        assert_eq!(
+            sorted(index.match_filter("a/#")),
+            vec!["a/b".to_string(), "a/b/c".to_string(), "a/x/c".to_string()]
+        );
+        assert_eq!(
+            sorted(index.match_filter("#")),
+            vec![
+                "/lead".to_string(),
+                "a/b".to_string(),
+                "a/b/c".to_string(),
+                "a/x/c".to_string(),
+                "b".to_string()
+            ]
+        );
+        assert_eq!(index.match_filter("+/lead"), vec!["/lead".to_string()]);
+        assert_eq!(index.match_filter("+/uptime"), Vec::<String>::new());
+        assert_eq!(
+            index.match_filter("$SYS/#"),
+            vec!["$SYS/uptime".to_string()]
+        );
+        assert!(index.match_filter("c/#").is_empty());
+    }
+
+    #[test]
+    fn set_delete_order_test() {
+        let index = RetainMessageIndex::new();
+        index.set("t", entry(5));
+        index.set("t", entry(3));
+        assert_eq!(index.get("t").unwrap().offset, 5);
+
+        // a local delete is applied before the reader reaches older records
+        index.delete("t", 8);
+        assert!(!index.contains("t"));
+        index.set("t", entry(6));
+        assert!(!index.contains("t"));
+        assert!(index.match_filter("#").is_empty());
+
+        index.advance(9);
+        index.set("t", entry(10));
+        assert!(index.contains("t"));
+        assert_eq!(index.match_filter("#"), vec!["t".to_string()]);
+    }
+
+    #[test]
+    fn remove_expired_test() {
+        let index = RetainMessageIndex::new();
+        index.set(
+            "t1",
+            RetainIndexEntry {
+                offset: 1,
+                expired_at: 100,
+                ..Default::default()
+            },
+        );
+        index.set("t2", entry(2));
+        assert!(index.remove_expired(99).is_empty());
+        assert_eq!(index.remove_expired(100), vec!["t1".to_string()]);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.match_filter("+"), vec!["t2".to_string()]);
+    }
+}
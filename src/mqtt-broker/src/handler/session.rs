@@ -23,6 +23,9 @@
 use super::cache::CacheManager;
 use super::error::MqttBrokerError;
 use super::lastwill::last_will_delay_interval;
+use crate::observability::metrics::session::{
+    metrics_session_created, metrics_session_resumed, metrics_session_takenover,
+};
 use crate::storage::session::SessionStorage;
 
 #[allow(clippy::too_many_arguments)]
@@ -69,6 +72,14 @@ pub async fn build_session(
         )
     };
 
+    if new_session {
+        metrics_session_created();
+    } else if session.connection_id.is_some() {
+        metrics_session_takenover();
+    } else {
+        metrics_session_resumed();
+    }
+
     let conf = broker_mqtt_conf();
     session.update_connnction_id(Some(connect_id));
     session.update_broker_id(Some(conf.broker_id));
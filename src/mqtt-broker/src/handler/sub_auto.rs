@@ -20,10 +20,12 @@
 
 use crate::subscribe::subscribe_manager::SubscribeManager;
 
+use super::tenant::tenant_subscribe;
 use super::{cache::CacheManager, error::MqttBrokerError, subscribe::save_subscribe};
 
 pub async fn start_auto_subscribe(
     client_id: String,
+    tenant: &str,
     login: &Option<Login>,
     protocol: &MqttProtocol,
     client_pool: &Arc<ClientPool>,
@@ -64,6 +66,7 @@ pub async fn start_auto_subscribe(
             packet_identifier: 0,
             filters: filters.clone(),
         };
+        let subscribe = tenant_subscribe(tenant, &subscribe);
 
         match save_subscribe(
             &client_id,
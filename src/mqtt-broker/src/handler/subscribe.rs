@@ -17,8 +17,9 @@
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use grpc_clients::{placement::mqtt::call::placement_set_subscribe, pool::ClientPool};
 use log::error;
-use metadata_struct::mqtt::{
-    cluster::AvailableFlag, subscribe_data::MqttSubscribe, topic::MqttTopic,
+use metadata_struct::{
+    mqtt::{cluster::AvailableFlag, subscribe_data::MqttSubscribe, topic::MqttTopic},
+    schema::SchemaType,
 };
 use protocol::{
     mqtt::common::{Filter, MqttProtocol, Subscribe, SubscribeProperties},
@@ -28,8 +29,8 @@
 
 use crate::subscribe::{
     sub_common::{
-        decode_queue_info, decode_share_info, get_share_sub_leader, is_queue_sub, is_share_sub,
-        path_regex_match,
+        build_sub_transcode, decode_queue_info, decode_share_info, get_share_sub_leader,
+        is_queue_sub, is_share_sub, path_regex_match, strip_transcode_prefix,
     },
     subscribe_manager::{ShareSubShareSub, SubscribeManager},
     subscriber::Subscriber,
@@ -48,6 +49,8 @@ struct ParseShareQueueSubscribeRequest {
     sub_name: String,
     group_name: String,
     pkid: u16,
+    transcode: Option<SchemaType>,
+    user_properties: Vec<(String, String)>,
 }
 
 pub async fn save_subscribe(
@@ -127,10 +130,24 @@ pub async fn parse_subscribe(
     filter: &Filter,
     subscribe_properties: &Option<SubscribeProperties>,
 ) {
-    let sub_identifier = if let Some(properties) = subscribe_properties.clone() {
-        properties.subscription_identifier
+    let (sub_identifier, user_properties) = if let Some(properties) = subscribe_properties.clone() {
+        (
+            properties.subscription_identifier,
+            properties.user_properties,
+        )
     } else {
-        None
+        (None, Vec::new())
+    };
+
+    let transcode = match build_sub_transcode(&filter.path, subscribe_properties) {
+        Ok(transcode) => transcode,
+        Err(e) => {
+            error!(
+                "Failed to parse subscribe transcode, client_id [{}], sub_path [{}], error message: {}",
+                client_id, filter.path, e
+            );
+            return;
+        }
     };
 
     let enable_exclusive_sub = metadata_cache
@@ -143,7 +160,8 @@ pub async fn parse_subscribe(
         add_exclusive_subscribe(subscribe_manager, &filter.path, client_id);
     }
 
-    if is_share_sub(&filter.path) {
+    let sub_path = strip_transcode_prefix(&filter.path);
+    if is_share_sub(&sub_path) {
         parse_share_subscribe(
             client_pool,
             subscribe_manager,
@@ -157,10 +175,12 @@ pub async fn parse_subscribe(
                 pkid,
                 sub_name: "".to_string(),
                 group_name: "".to_string(),
+                transcode: transcode.clone(),
+                user_properties: user_properties.clone(),
             },
         )
         .await;
-    } else if is_queue_sub(&filter.path) {
+    } else if is_queue_sub(&sub_path) {
         parse_queue_subscribe(
             client_pool,
             subscribe_manager,
@@ -174,6 +194,8 @@ pub async fn parse_subscribe(
                 filter: filter.clone(),
                 sub_name: "".to_string(),
                 group_name: "".to_string(),
+                transcode: transcode.clone(),
+                user_properties: user_properties.clone(),
             },
         )
         .await;
@@ -185,6 +207,7 @@ pub async fn parse_subscribe(
             protocol,
             &sub_identifier,
             filter,
+            transcode,
         );
     }
 }
@@ -194,7 +217,7 @@ async fn parse_share_subscribe(
     subscribe_manager: &Arc<SubscribeManager>,
     req: &mut ParseShareQueueSubscribeRequest,
 ) {
-    let (group_name, sub_name) = decode_share_info(&req.filter.path);
+    let (group_name, sub_name) = decode_share_info(&strip_transcode_prefix(&req.filter.path));
     req.group_name = format!("{}_{}", group_name, sub_name);
     req.sub_name = sub_name;
     parse_share_queue_subscribe_common(client_pool, subscribe_manager, req).await;
@@ -205,7 +228,7 @@ async fn parse_queue_subscribe(
     subscribe_manager: &Arc<SubscribeManager>,
     req: &mut ParseShareQueueSubscribeRequest,
 ) {
-    let sub_name = decode_queue_info(&req.filter.path);
+    let sub_name = decode_queue_info(&strip_transcode_prefix(&req.filter.path));
     // queueSub is a special shareSub
     let group_name = format!("$queue_{}", sub_name);
     req.group_name = group_name;
@@ -254,6 +277,7 @@ async fn add_share_push_leader(
         retain_forward_rule: req.filter.retain_forward_rule.clone(),
         subscription_identifier: req.sub_identifier,
         sub_path: req.filter.path.clone(),
+        transcode: req.transcode.clone(),
     };
 
     subscribe_manager.add_topic_subscribe(&req.topic_name, &req.client_id, &req.filter.path);
@@ -272,6 +296,7 @@ async fn add_share_push_follower(
         group_name: req.group_name.clone(),
         sub_name: req.sub_name.clone(),
         subscription_identifier: req.sub_identifier,
+        user_properties: req.user_properties.clone(),
     };
 
     subscribe_manager.add_share_subscribe_follower(
@@ -289,6 +314,7 @@ fn add_exclusive_push(
     protocol: &MqttProtocol,
     sub_identifier: &Option<usize>,
     filter: &Filter,
+    transcode: Option<SchemaType>,
 ) {
     if path_regex_match(&topic.topic_name, &filter.path) {
         let sub = Subscriber {
@@ -303,6 +329,7 @@ fn add_exclusive_push(
             retain_forward_rule: filter.retain_forward_rule.to_owned(),
             subscription_identifier: sub_identifier.to_owned(),
             sub_path: filter.path.to_owned(),
+            transcode,
         };
         subscribe_manager.add_topic_subscribe(&topic.topic_name, client_id, &filter.path);
         subscribe_manager.add_exclusive_push(client_id, &filter.path, &topic.topic_id, sub);
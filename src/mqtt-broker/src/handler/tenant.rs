@@ -0,0 +1,556 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_base::tools::now_second;
+use metadata_struct::mqtt::cluster::{MqttClusterDynamicTenant, TenantSource};
+use metadata_struct::mqtt::tenant::MqttTenant;
+use protocol::mqtt::common::{Login, Subscribe};
+use regex::Regex;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::observability::metrics::tenant::{
+    record_tenant_message_received, record_tenant_quota_exceeded,
+};
+use crate::server::connection::NetworkConnection;
+
+// Topics of a tenant are stored as $tenant/{tenant}/{topic}, clients of the tenant
+// only ever see the {topic} part.
+pub const TENANT_TOPIC_PREFIX: &str = "$tenant";
+
+const TENANT_CLIENT_ID_SEPARATOR: &str = ":";
+
+// Prefixes that stay in front of the tenant mount point, with the number of levels they span
+const FILTER_PREFIXES: [(&str, usize); 4] = [
+    ("$share/", 2),
+    ("$transcode/", 2),
+    ("$queue/", 1),
+    ("$exclusive/", 1),
+];
+
+// Usage of a tenant across the cluster. The shares of the other brokers, the sessions and
+// the stored bytes are refreshed from the placement center and the storage adapter by
+// the tenant usage sync, what this broker admits in between is added on top.
+#[derive(Clone, Default)]
+pub struct TenantUsage {
+    pub rate_second: u64,
+    pub rate_count: u64,
+    // Messages accepted by this broker since its last usage report
+    pub report_message_count: u64,
+    pub remote_connections: u64,
+    pub remote_message_rate: u64,
+    pub session_num: u64,
+    pub storage_bytes: u64,
+}
+
+/// Returns the tenant of a connection, an empty tenant means the connection uses the
+/// shared namespace of the cluster.
+pub fn resolve_tenant(
+    config: &MqttClusterDynamicTenant,
+    login: &Option<Login>,
+    network_connection: &Option<NetworkConnection>,
+) -> String {
+    if !config.enable {
+        return "".to_string();
+    }
+    match config.source {
+        TenantSource::Username => {
+            if config.username_separator.is_empty() {
+                return "".to_string();
+            }
+            login
+                .as_ref()
+                .and_then(|login| login.username.split_once(&config.username_separator))
+                .map(|(tenant, _)| tenant.to_string())
+                .unwrap_or_default()
+        }
+        TenantSource::CertField => network_connection
+            .as_ref()
+            .and_then(|conn| conn.peer_cert_subject.get(&config.cert_field).cloned())
+            .unwrap_or_default(),
+        TenantSource::Listener => network_connection
+            .as_ref()
+            .and_then(|conn| {
+                config
+                    .listener_tenants
+                    .get(&conn.connection_type.to_string())
+                    .cloned()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+// CN, O and OU of the subject of a client certificate
+pub fn cert_subject_fields(der: &[u8]) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let Ok((_, cert)) = X509Certificate::from_der(der) else {
+        return fields;
+    };
+    let subject = cert.subject();
+    let values = [
+        ("CN", subject.iter_common_name().next()),
+        ("O", subject.iter_organization().next()),
+        ("OU", subject.iter_organizational_unit().next()),
+    ];
+    for (name, value) in values {
+        if let Some(value) = value.and_then(|attr| attr.as_str().ok()) {
+            fields.insert(name.to_string(), value.to_string());
+        }
+    }
+    fields
+}
+
+// Tenant names become a topic level, so they are limited to the characters of a topic name
+pub fn tenant_name_validator(tenant_name: &str) -> bool {
+    let regex = Regex::new(r"^[A-Za-z0-9_]+$").unwrap();
+    regex.is_match(tenant_name)
+}
+
+pub fn tenant_client_id(tenant: &str, client_id: &str) -> String {
+    if tenant.is_empty() {
+        return client_id.to_string();
+    }
+    format!("{}{}{}", tenant, TENANT_CLIENT_ID_SEPARATOR, client_id)
+}
+
+// While tenancy is enabled, a client of the shared namespace could otherwise pick an id that
+// equals the mounted id of a tenant client and take over its session
+pub fn is_reserved_client_id(
+    config: &MqttClusterDynamicTenant,
+    tenant: &str,
+    client_id: &str,
+) -> bool {
+    config.enable && tenant.is_empty() && client_id.contains(TENANT_CLIENT_ID_SEPARATOR)
+}
+
+pub fn is_tenant_client_id(tenant: &str, client_id: &str) -> bool {
+    client_id
+        .strip_prefix(tenant)
+        .is_some_and(|id| id.starts_with(TENANT_CLIENT_ID_SEPARATOR))
+}
+
+fn split_filter_prefix(path: &str) -> (&str, &str) {
+    let mut offset = 0;
+    'outer: loop {
+        let rest = &path[offset..];
+        for (prefix, levels) in FILTER_PREFIXES {
+            if !rest.starts_with(prefix) {
+                continue;
+            }
+            if let Some((index, _)) = rest.match_indices('/').nth(levels - 1) {
+                offset += index + 1;
+                continue 'outer;
+            }
+        }
+        break;
+    }
+    (&path[..offset], &path[offset..])
+}
+
+/// Moves a topic name or topic filter of a client into the namespace of its tenant.
+pub fn tenant_topic_name(tenant: &str, name: &str) -> String {
+    if tenant.is_empty() {
+        return name.to_string();
+    }
+    let (prefix, path) = split_filter_prefix(name);
+    format!("{}{}/{}/{}", prefix, TENANT_TOPIC_PREFIX, tenant, path)
+}
+
+/// Splits a mounted topic into its tenant and the topic seen by the clients. Paths
+/// decoded from shared subscriptions start with a "/", which is kept.
+pub fn split_tenant_mount(name: &str) -> Option<(String, String)> {
+    let (lead, name) = match name.strip_prefix('/') {
+        Some(name) => ("/", name),
+        None => ("", name),
+    };
+    let rest = name.strip_prefix(TENANT_TOPIC_PREFIX)?.strip_prefix('/')?;
+    let (tenant, topic) = rest.split_once('/')?;
+    Some((tenant.to_string(), format!("{}{}", lead, topic)))
+}
+
+pub fn tenant_of_topic(topic_name: &str) -> String {
+    split_tenant_mount(topic_name)
+        .map(|(tenant, _)| tenant)
+        .unwrap_or_default()
+}
+
+// The topic name delivered to the subscribers of a tenant topic
+pub fn tenant_topic_unmount(topic_name: &str) -> String {
+    split_tenant_mount(topic_name)
+        .map(|(_, topic)| topic)
+        .unwrap_or(topic_name.to_string())
+}
+
+// Clients can not address the tenant namespaces directly
+pub fn is_tenant_topic(name: &str) -> bool {
+    let (_, path) = split_filter_prefix(name);
+    let path = path.strip_prefix('/').unwrap_or(path);
+    path == TENANT_TOPIC_PREFIX || path.starts_with(&format!("{}/", TENANT_TOPIC_PREFIX))
+}
+
+pub fn tenant_subscribe(tenant: &str, subscribe: &Subscribe) -> Subscribe {
+    let mut subscribe = subscribe.clone();
+    for filter in subscribe.filters.iter_mut() {
+        filter.path = tenant_topic_name(tenant, &filter.path);
+    }
+    subscribe
+}
+
+impl CacheManager {
+    pub fn add_tenant(&self, tenant: MqttTenant) {
+        self.tenant_info.insert(tenant.tenant_name.clone(), tenant);
+    }
+
+    pub fn delete_tenant(&self, tenant_name: &str) {
+        self.tenant_info.remove(tenant_name);
+        self.tenant_usage.remove(tenant_name);
+    }
+
+    pub fn get_tenant(&self, tenant_name: &str) -> Option<MqttTenant> {
+        self.tenant_info
+            .get(tenant_name)
+            .map(|tenant| tenant.clone())
+    }
+
+    pub fn get_tenant_usage(&self, tenant_name: &str) -> TenantUsage {
+        self.tenant_usage
+            .get(tenant_name)
+            .map(|usage| usage.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn tenant_connection_num(&self, tenant_name: &str) -> u64 {
+        self.connection_info
+            .iter()
+            .filter(|conn| conn.tenant == tenant_name)
+            .count() as u64
+    }
+
+    pub fn tenant_cluster_connection_num(&self, tenant_name: &str) -> u64 {
+        self.tenant_connection_num(tenant_name)
+            + self.get_tenant_usage(tenant_name).remote_connections
+    }
+
+    pub fn tenant_session_num(&self, tenant_name: &str) -> u64 {
+        self.get_tenant_usage(tenant_name).session_num
+    }
+
+    pub fn tenant_topic_num(&self, tenant_name: &str) -> u64 {
+        self.topic_info
+            .iter()
+            .filter(|topic| tenant_of_topic(topic.key()) == tenant_name)
+            .count() as u64
+    }
+
+    pub fn check_tenant_connection_quota(&self, tenant_name: &str) -> Result<(), MqttBrokerError> {
+        let Some(tenant) = self.get_tenant(tenant_name) else {
+            return Ok(());
+        };
+        let max = tenant.quota.max_connections;
+        if max > 0 && self.tenant_cluster_connection_num(tenant_name) >= max {
+            return Err(quota_exceeded(tenant_name, "connections"));
+        }
+        Ok(())
+    }
+
+    // Checked before a new session is created, the session counts until the next sync
+    pub fn check_tenant_session_quota(&self, tenant_name: &str) -> Result<(), MqttBrokerError> {
+        let Some(tenant) = self.get_tenant(tenant_name) else {
+            return Ok(());
+        };
+        let mut usage = self
+            .tenant_usage
+            .entry(tenant_name.to_string())
+            .or_default();
+        let max = tenant.quota.max_sessions;
+        if max > 0 && usage.session_num >= max {
+            return Err(quota_exceeded(tenant_name, "sessions"));
+        }
+        usage.session_num += 1;
+        Ok(())
+    }
+
+    // Checked before a message is written to a topic that does not exist yet
+    pub fn check_tenant_topic_quota(
+        &self,
+        tenant_name: &str,
+        topic_name: &str,
+    ) -> Result<(), MqttBrokerError> {
+        if self.topic_exists(topic_name) {
+            return Ok(());
+        }
+        let Some(tenant) = self.get_tenant(tenant_name) else {
+            return Ok(());
+        };
+        let max = tenant.quota.max_topics;
+        if max > 0 && self.tenant_topic_num(tenant_name) >= max {
+            return Err(quota_exceeded(tenant_name, "topics"));
+        }
+        Ok(())
+    }
+
+    // Counts the message against the per second rate and the storage bytes of the tenant
+    pub fn check_tenant_message_quota(
+        &self,
+        tenant_name: &str,
+        payload_size: u64,
+    ) -> Result<(), MqttBrokerError> {
+        let Some(tenant) = self.get_tenant(tenant_name) else {
+            return Ok(());
+        };
+        let mut usage = self
+            .tenant_usage
+            .entry(tenant_name.to_string())
+            .or_default();
+
+        let now = now_second();
+        if usage.rate_second != now {
+            usage.rate_second = now;
+            usage.rate_count = 0;
+        }
+        let max_rate = tenant.quota.max_message_rate;
+        if max_rate > 0 && usage.rate_count + usage.remote_message_rate >= max_rate {
+            return Err(quota_exceeded(tenant_name, "message_rate"));
+        }
+
+        let max_storage_bytes = tenant.quota.max_storage_bytes;
+        if max_storage_bytes > 0 && usage.storage_bytes + payload_size > max_storage_bytes {
+            return Err(quota_exceeded(tenant_name, "storage_bytes"));
+        }
+
+        usage.rate_count += 1;
+        usage.report_message_count += 1;
+        usage.storage_bytes += payload_size;
+        Ok(())
+    }
+
+    // Returns the messages accepted since the last report and starts counting anew
+    pub fn take_tenant_report_message_count(&self, tenant_name: &str) -> u64 {
+        self.tenant_usage
+            .get_mut(tenant_name)
+            .map(|mut usage| std::mem::take(&mut usage.report_message_count))
+            .unwrap_or(0)
+    }
+
+    pub fn update_tenant_usage<F>(&self, tenant_name: &str, f: F)
+    where
+        F: FnOnce(&mut TenantUsage),
+    {
+        let mut usage = self
+            .tenant_usage
+            .entry(tenant_name.to_string())
+            .or_default();
+        f(&mut usage);
+    }
+
+    // Quotas and metrics of a message published into a tenant namespace, applied the same
+    // way whether it comes from a client or from the admin API
+    pub fn check_tenant_publish(
+        &self,
+        tenant_name: &str,
+        storage_topic_name: &str,
+        payload_size: usize,
+    ) -> Result<(), MqttBrokerError> {
+        if tenant_name.is_empty() {
+            return Ok(());
+        }
+        self.check_tenant_topic_quota(tenant_name, storage_topic_name)?;
+        self.check_tenant_message_quota(tenant_name, payload_size as u64)?;
+        record_tenant_message_received(tenant_name, payload_size);
+        Ok(())
+    }
+}
+
+fn quota_exceeded(tenant_name: &str, quota: &str) -> MqttBrokerError {
+    record_tenant_quota_exceeded(tenant_name, quota);
+    MqttBrokerError::TenantQuotaExceeded(tenant_name.to_string(), quota.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use grpc_clients::pool::ClientPool;
+    use metadata_struct::mqtt::cluster::{MqttClusterDynamicTenant, TenantSource};
+    use metadata_struct::mqtt::tenant::{MqttTenant, MqttTenantQuota};
+    use protocol::mqtt::common::Login;
+
+    use super::*;
+
+    #[test]
+    fn resolve_tenant_test() {
+        let mut config = MqttClusterDynamicTenant {
+            enable: true,
+            source: TenantSource::Username,
+            username_separator: "/".to_string(),
+            ..Default::default()
+        };
+        let login = Some(Login {
+            username: "acme/alice".to_string(),
+            password: "".to_string(),
+        });
+        assert_eq!(resolve_tenant(&config, &login, &None), "acme");
+
+        let login_without_tenant = Some(Login {
+            username: "alice".to_string(),
+            password: "".to_string(),
+        });
+        assert_eq!(resolve_tenant(&config, &login_without_tenant, &None), "");
+        assert_eq!(resolve_tenant(&config, &None, &None), "");
+
+        config.enable = false;
+        assert_eq!(resolve_tenant(&config, &login, &None), "");
+    }
+
+    #[test]
+    fn tenant_topic_name_test() {
+        assert_eq!(tenant_topic_name("", "a/b"), "a/b");
+        assert_eq!(tenant_topic_name("acme", "a/b"), "$tenant/acme/a/b");
+        assert_eq!(tenant_topic_name("acme", "/a/b"), "$tenant/acme//a/b");
+        assert_eq!(
+            tenant_topic_name("acme", "$share/g1/a/#"),
+            "$share/g1/$tenant/acme/a/#"
+        );
+        assert_eq!(
+            tenant_topic_name("acme", "$queue/a/b"),
+            "$queue/$tenant/acme/a/b"
+        );
+        assert_eq!(
+            tenant_topic_name("acme", "$transcode/json/$share/g1/a"),
+            "$transcode/json/$share/g1/$tenant/acme/a"
+        );
+        assert_eq!(
+            tenant_topic_name("acme", "$exclusive/a"),
+            "$exclusive/$tenant/acme/a"
+        );
+    }
+
+    #[test]
+    fn split_tenant_mount_test() {
+        assert_eq!(
+            split_tenant_mount("$tenant/acme/a/b"),
+            Some(("acme".to_string(), "a/b".to_string()))
+        );
+        assert_eq!(
+            split_tenant_mount("/$tenant/acme/a/b"),
+            Some(("acme".to_string(), "/a/b".to_string()))
+        );
+        assert_eq!(split_tenant_mount("a/b"), None);
+        assert_eq!(split_tenant_mount("$tenant/acme"), None);
+
+        assert_eq!(tenant_topic_unmount("$tenant/acme/a/b"), "a/b");
+        assert_eq!(tenant_topic_unmount("$tenant/acme//a/b"), "/a/b");
+        assert_eq!(tenant_topic_unmount("a/b"), "a/b");
+        assert_eq!(tenant_of_topic("$tenant/acme/a"), "acme");
+        assert_eq!(tenant_of_topic("a"), "");
+    }
+
+    #[test]
+    fn is_tenant_topic_test() {
+        assert!(is_tenant_topic("$tenant/acme/a"));
+        assert!(is_tenant_topic("$tenant/#"));
+        assert!(is_tenant_topic("$share/g1/$tenant/acme/a"));
+        assert!(is_tenant_topic("$queue//$tenant/acme/a"));
+        assert!(!is_tenant_topic("a/$tenant/b"));
+        assert!(!is_tenant_topic("$tenants/a"));
+        assert!(!is_tenant_topic("#"));
+    }
+
+    #[test]
+    fn tenant_client_id_test() {
+        assert_eq!(tenant_client_id("", "c1"), "c1");
+        assert_eq!(tenant_client_id("acme", "c1"), "acme:c1");
+        assert!(is_tenant_client_id("acme", "acme:c1"));
+        assert!(!is_tenant_client_id("acme", "acme2:c1"));
+        assert!(!is_tenant_client_id("acme", "c1"));
+
+        let mut config = MqttClusterDynamicTenant {
+            enable: true,
+            ..Default::default()
+        };
+        assert!(is_reserved_client_id(&config, "", "acme:c1"));
+        assert!(!is_reserved_client_id(&config, "", "c1"));
+        assert!(!is_reserved_client_id(&config, "acme", "b:c1"));
+        config.enable = false;
+        assert!(!is_reserved_client_id(&config, "", "acme:c1"));
+
+        assert!(tenant_name_validator("acme_1"));
+        assert!(!tenant_name_validator("acme/1"));
+        assert!(!tenant_name_validator(""));
+    }
+
+    #[test]
+    fn tenant_quota_test() {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = CacheManager::new(client_pool, "test".to_string());
+        cache_manager.add_tenant(MqttTenant {
+            tenant_name: "acme".to_string(),
+            quota: MqttTenantQuota {
+                max_topics: 1,
+                max_message_rate: 2,
+                max_storage_bytes: 100,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        cache_manager
+            .check_tenant_message_quota("acme", 10)
+            .unwrap();
+        cache_manager
+            .check_tenant_message_quota("acme", 10)
+            .unwrap();
+        assert!(cache_manager
+            .check_tenant_message_quota("acme", 10)
+            .is_err());
+        assert_eq!(cache_manager.get_tenant_usage("acme").storage_bytes, 20);
+        assert_eq!(cache_manager.take_tenant_report_message_count("acme"), 2);
+        assert_eq!(cache_manager.take_tenant_report_message_count("acme"), 0);
+
+        // Tenants without a definition are not limited
+        cache_manager
+            .check_tenant_message_quota("other", 1000)
+            .unwrap();
+
+        cache_manager
+            .check_tenant_topic_quota("acme", "$tenant/acme/a")
+            .unwrap();
+
+        // The shares of the other brokers count against the quotas
+        cache_manager.add_tenant(MqttTenant {
+            tenant_name: "beta".to_string(),
+            quota: MqttTenantQuota {
+                max_connections: 2,
+                max_sessions: 1,
+                max_message_rate: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        cache_manager.check_tenant_connection_quota("beta").unwrap();
+        cache_manager.update_tenant_usage("beta", |usage| {
+            usage.remote_connections = 2;
+            usage.remote_message_rate = 3;
+        });
+        assert!(cache_manager.check_tenant_connection_quota("beta").is_err());
+        assert!(cache_manager.check_tenant_message_quota("beta", 1).is_err());
+
+        cache_manager.check_tenant_session_quota("beta").unwrap();
+        assert!(cache_manager.check_tenant_session_quota("beta").is_err());
+        assert_eq!(cache_manager.tenant_session_num("beta"), 1);
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use common_base::tools::now_second;
+use grpc_clients::pool::ClientPool;
+use log::{error, info, warn};
+use storage_adapter::storage::StorageAdapter;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+use super::cache::CacheManager;
+use super::error::MqttBrokerError;
+use super::tenant::{is_tenant_client_id, tenant_of_topic};
+use crate::storage::message::MessageStorage;
+use crate::storage::session::SessionStorage;
+use crate::storage::tenant::{TenantBrokerUsage, TenantStorage, TenantUsageReport};
+
+const TENANT_USAGE_SYNC_INTERVAL_SEC: u64 = 5;
+
+// The report of a broker outlives a few missed syncs before it expires
+const TENANT_USAGE_LEASE_TTL_SEC: u64 = TENANT_USAGE_SYNC_INTERVAL_SEC * 3;
+
+// Keeps the tenant quotas cluster-wide: reports the connections and message rate of this
+// broker to the placement center, and refreshes the shares of the other brokers, the
+// session counts and the stored bytes of every tenant.
+pub struct TenantUsageSync<S> {
+    cache_manager: Arc<CacheManager>,
+    client_pool: Arc<ClientPool>,
+    message_storage_adapter: Arc<S>,
+    stop_send: broadcast::Sender<bool>,
+}
+
+impl<S> TenantUsageSync<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        client_pool: Arc<ClientPool>,
+        message_storage_adapter: Arc<S>,
+        stop_send: broadcast::Sender<bool>,
+    ) -> Self {
+        TenantUsageSync {
+            cache_manager,
+            client_pool,
+            message_storage_adapter,
+            stop_send,
+        }
+    }
+
+    pub async fn start(&self) {
+        let mut lease_id = 0;
+        let mut report_time = now_second();
+        loop {
+            let mut stop_rx = self.stop_send.subscribe();
+            select! {
+                val = stop_rx.recv() => {
+                    if let Ok(flag) = val {
+                        if flag {
+                            info!("{}", "Tenant usage sync thread stopped successfully.");
+                            break;
+                        }
+                    }
+                }
+                val = self.sync(&mut lease_id, &mut report_time) => {
+                    if let Err(e) = val {
+                        error!("Failed to sync the tenant usage, error message: {}", e);
+                    }
+                    sleep(Duration::from_secs(TENANT_USAGE_SYNC_INTERVAL_SEC)).await;
+                }
+            }
+        }
+    }
+
+    async fn sync(&self, lease_id: &mut u64, report_time: &mut u64) -> Result<(), MqttBrokerError> {
+        if !self.cache_manager.get_tenant_config().enable
+            || self.cache_manager.tenant_info.is_empty()
+        {
+            return Ok(());
+        }
+        let config = broker_mqtt_conf();
+        let tenant_storage = TenantStorage::new(self.client_pool.clone());
+        let tenants: Vec<String> = self
+            .cache_manager
+            .tenant_info
+            .iter()
+            .map(|tenant| tenant.key().clone())
+            .collect();
+
+        let now = now_second();
+        let elapsed = now.saturating_sub(*report_time).max(1);
+        *report_time = now;
+        let mut report = TenantUsageReport {
+            broker_id: config.broker_id,
+            ..Default::default()
+        };
+        for tenant in tenants.iter() {
+            let message_count = self.cache_manager.take_tenant_report_message_count(tenant);
+            report.tenants.insert(
+                tenant.clone(),
+                TenantBrokerUsage {
+                    connections: self.cache_manager.tenant_connection_num(tenant),
+                    message_rate: message_count / elapsed,
+                },
+            );
+        }
+        if *lease_id == 0
+            || tenant_storage
+                .keep_alive_usage_lease(*lease_id)
+                .await
+                .is_err()
+        {
+            *lease_id = tenant_storage
+                .grant_usage_lease(TENANT_USAGE_LEASE_TTL_SEC)
+                .await?;
+        }
+        tenant_storage.save_usage_report(*lease_id, &report).await?;
+
+        let remote = sum_remote_usage(config.broker_id, tenant_storage.list_usage_report().await?);
+        let sessions = SessionStorage::new(self.client_pool.clone())
+            .list_session()
+            .await?;
+        let storage_bytes = self.measure_storage_bytes(&tenants).await;
+
+        for tenant in tenants.iter() {
+            let remote_usage = remote.get(tenant).cloned().unwrap_or_default();
+            let session_num = sessions
+                .iter()
+                .filter(|session| is_tenant_client_id(tenant, session.key()))
+                .count() as u64;
+            let stored = storage_bytes.get(tenant).copied();
+            self.cache_manager.update_tenant_usage(tenant, |usage| {
+                usage.remote_connections = remote_usage.connections;
+                usage.remote_message_rate = remote_usage.message_rate;
+                usage.session_num = session_num;
+                if let Some(stored) = stored {
+                    usage.storage_bytes = stored;
+                }
+            });
+        }
+        Ok(())
+    }
+
+    // Payload bytes kept in the namespace of each tenant, a tenant is left out when one
+    // of its topics can not be measured so that its last value is kept
+    async fn measure_storage_bytes(&self, tenants: &[String]) -> HashMap<String, u64> {
+        let mut results: HashMap<String, u64> =
+            tenants.iter().map(|tenant| (tenant.clone(), 0)).collect();
+        let topics: Vec<(String, String)> = self
+            .cache_manager
+            .topic_info
+            .iter()
+            .map(|topic| (topic.topic_name.clone(), topic.topic_id.clone()))
+            .collect();
+        for (topic_name, topic_id) in topics {
+            let tenant = tenant_of_topic(&topic_name);
+            let Some(total) = results.get_mut(&tenant) else {
+                continue;
+            };
+            let message_storage =
+                MessageStorage::new_by_topic(self.message_storage_adapter.clone(), &topic_name);
+            match message_storage.get_topic_size(&topic_id).await {
+                Ok(size) => *total += size,
+                Err(e) => {
+                    warn!(
+                        "Failed to measure the stored bytes of topic {} of tenant {}, error message: {}",
+                        topic_name, tenant, e
+                    );
+                    results.remove(&tenant);
+                }
+            }
+        }
+        results
+    }
+}
+
+// (tenant_name, usage of the tenant on all brokers but this one)
+fn sum_remote_usage(
+    broker_id: u64,
+    reports: Vec<TenantUsageReport>,
+) -> HashMap<String, TenantBrokerUsage> {
+    let mut results: HashMap<String, TenantBrokerUsage> = HashMap::new();
+    for report in reports {
+        if report.broker_id == broker_id {
+            continue;
+        }
+        for (tenant, usage) in report.tenants {
+            let total = results.entry(tenant).or_default();
+            total.connections += usage.connections;
+            total.message_rate += usage.message_rate;
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum_remote_usage_test() {
+        let report = |broker_id: u64, connections: u64, message_rate: u64| TenantUsageReport {
+            broker_id,
+            tenants: HashMap::from([(
+                "acme".to_string(),
+                TenantBrokerUsage {
+                    connections,
+                    message_rate,
+                },
+            )]),
+        };
+        let results = sum_remote_usage(
+            1,
+            vec![report(1, 5, 50), report(2, 3, 30), report(3, 4, 40)],
+        );
+        assert_eq!(
+            results.get("acme"),
+            Some(&TenantBrokerUsage {
+                connections: 7,
+                message_rate: 70,
+            })
+        );
+        assert!(sum_remote_usage(1, vec![report(1, 5, 50)]).is_empty());
+    }
+}
@@ -26,7 +26,7 @@
 use super::error::MqttBrokerError;
 use crate::handler::cache::CacheManager;
 use crate::handler::topic_rewrite::process_publish_topic_rewrite;
-use crate::storage::message::cluster_name;
+use crate::storage::message::topic_storage_namespace;
 use crate::storage::topic::TopicStorage;
 use crate::subscribe::sub_common::{
     decode_queue_info, decode_share_info, is_queue_sub, is_share_sub,
@@ -126,7 +126,7 @@ pub async fn try_init_topic<S>(
     let topic = if let Some(tp) = metadata_cache.get_topic_by_name(topic_name) {
         tp
     } else {
-        let namespace = cluster_name();
+        let namespace = topic_storage_namespace(topic_name);
 
         // create Topic
         let topic_storage = TopicStorage::new(client_pool.clone());
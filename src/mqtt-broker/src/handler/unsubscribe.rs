@@ -16,7 +16,7 @@
     cache::CacheManager, error::MqttBrokerError, sub_exclusive::remove_exclusive_subscribe,
 };
 use crate::subscribe::{
-    sub_common::{decode_share_info, is_share_sub, path_regex_match},
+    sub_common::{decode_share_info, is_share_sub, path_regex_match, strip_transcode_prefix},
     subscribe_manager::SubscribeManager,
 };
 use common_base::config::broker_mqtt::broker_mqtt_conf;
@@ -70,8 +70,9 @@ fn unsubscribe_by_path(
                 continue;
             }
 
-            if is_share_sub(path) {
-                let (group_name, sub_name) = decode_share_info(path);
+            let share_path = strip_transcode_prefix(path);
+            if is_share_sub(&share_path) {
+                let (group_name, sub_name) = decode_share_info(&share_path);
                 // share leader
                 for (key, data) in subscribe_manager.share_leader_push.clone() {
                     let mut flag = false;
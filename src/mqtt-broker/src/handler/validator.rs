@@ -21,7 +21,8 @@
 use protocol::mqtt::common::{
     Connect, ConnectProperties, ConnectReturnCode, DisconnectReasonCode, LastWill,
     LastWillProperties, Login, MqttPacket, MqttProtocol, PubAckReason, PubRecReason, Publish,
-    PublishProperties, QoS, Subscribe, SubscribeReasonCode, UnsubAckReason, Unsubscribe,
+    PublishProperties, QoS, Subscribe, SubscribeProperties, SubscribeReasonCode, UnsubAckReason,
+    Unsubscribe,
 };
 use std::cmp::min;
 use std::net::SocketAddr;
@@ -42,10 +43,11 @@
     response_packet_mqtt_suback, response_packet_mqtt_unsuback,
 };
 use super::sub_exclusive::check_exclusive_subscribe;
+use super::tenant::{is_tenant_topic, tenant_subscribe, tenant_topic_name};
 use super::topic::topic_name_validator;
 use crate::security::AuthDriver;
 use crate::server::connection_manager::ConnectionManager;
-use crate::subscribe::sub_common::sub_path_validator;
+use crate::subscribe::sub_common::{build_sub_transcode, sub_path_validator};
 use crate::subscribe::subscribe_manager::SubscribeManager;
 
 pub async fn tcp_establish_connection_check(
@@ -412,13 +414,20 @@ pub async fn subscribe_validator(
     subscribe_manager: &Arc<SubscribeManager>,
     connection: &MQTTConnection,
     subscribe: &Subscribe,
+    subscribe_properties: &Option<SubscribeProperties>,
 ) -> Option<MqttPacket> {
     let mut return_codes: Vec<SubscribeReasonCode> = Vec::new();
+    let mut reason_string = None;
     for filter in subscribe.filters.clone() {
-        if !sub_path_validator(filter.path) {
+        if is_tenant_topic(&filter.path) || !sub_path_validator(filter.path.clone()) {
             return_codes.push(SubscribeReasonCode::TopicFilterInvalid);
             continue;
         }
+
+        if let Err(e) = build_sub_transcode(&filter.path, subscribe_properties) {
+            return_codes.push(SubscribeReasonCode::TopicFilterInvalid);
+            reason_string = Some(e.to_string());
+        }
     }
     if !return_codes.is_empty() {
         return Some(response_packet_mqtt_suback(
@@ -426,7 +435,7 @@ pub async fn subscribe_validator(
             connection,
             subscribe.packet_identifier,
             return_codes,
-            None,
+            reason_string,
         ));
     }
 
@@ -440,7 +449,8 @@ pub async fn subscribe_validator(
         ));
     }
 
-    if !check_exclusive_subscribe(metadata_cache, subscribe_manager, subscribe) {
+    let mounted_subscribe = tenant_subscribe(&connection.tenant, subscribe);
+    if !check_exclusive_subscribe(metadata_cache, subscribe_manager, &mounted_subscribe) {
         return Some(response_packet_mqtt_suback(
             protocol,
             connection,
@@ -486,7 +496,11 @@ pub async fn un_subscribe_validator(
     }
 
     for path in un_subscribe.filters.clone() {
-        if subscribe_manager.get_subscribe(client_id, &path).is_none() {
+        let tenant_path = tenant_topic_name(&connection.tenant, &path);
+        if subscribe_manager
+            .get_subscribe(client_id, &tenant_path)
+            .is_none()
+        {
             return Some(response_packet_mqtt_unsuback(
                 connection,
                 un_subscribe.pkid,
@@ -18,8 +18,11 @@
 
 use bridge::core::start_connector_thread;
 use bridge::manager::ConnectorManager;
-use common_base::config::broker_mqtt::broker_mqtt_conf;
-use common_base::metrics::register_prometheus_export;
+use common_base::config::broker_mqtt::{
+    broker_mqtt_conf, broker_mqtt_conf_path, reload_broker_mqtt_conf,
+};
+use common_base::config::reload::start_config_reload_watcher;
+use common_base::metrics::start_prometheus_export;
 use common_base::runtime::create_runtime;
 use common_base::tools::now_second;
 use delay_message::{start_build_delay_queue, start_delay_message_pop, DelayMessageManager};
@@ -27,17 +30,23 @@
 use handler::acl::UpdateAclCache;
 use handler::cache::CacheManager;
 use handler::cache_update::load_metadata_cache;
+use handler::cluster_config::apply_cluster_config_reload;
 use handler::heartbreat::{register_node, report_heartbeat};
 use handler::keep_alive::ClientKeepAlive;
+use handler::retain::MessageExpire;
 use handler::sub_parse_topic::start_parse_subscribe_by_new_topic_thread;
+use handler::tenant_usage::TenantUsageSync;
 use handler::user::{init_system_user, UpdateUserCache};
 use lazy_static::lazy_static;
 use log::{error, info};
 use observability::start_opservability;
+use rule_engine::RuleEngine;
 use schema_register::schema::SchemaRegisterManager;
 use security::AuthDriver;
+use server::cert_resolver::{start_cert_watcher, TlsCertResolver};
 use server::connection_manager::ConnectionManager;
 use server::grpc::server::GrpcServer;
+use server::http::server::{start_http_server, HttpServerState};
 use server::tcp::server::start_tcp_server;
 use server::websocket::server::{websocket_server, websockets_server, WebSocketServerState};
 use storage::cluster::ClusterStorage;
@@ -65,6 +74,7 @@
 pub mod bridge;
 pub mod handler;
 pub mod observability;
+pub mod rule_engine;
 pub mod security;
 pub mod server;
 pub mod storage;
@@ -123,7 +133,9 @@ pub struct MqttBroker<S> {
     connector_manager: Arc<ConnectorManager>,
     auth_driver: Arc<AuthDriver>,
     delay_message_manager: Arc<DelayMessageManager<S>>,
+    rule_engine: Arc<RuleEngine<S>>,
     schema_manager: Arc<SchemaRegisterManager>,
+    tls_cert_resolver: Arc<TlsCertResolver>,
 }
 
 impl<S> MqttBroker<S>
@@ -151,6 +163,18 @@ pub fn new(
             message_storage_adapter.clone(),
         ));
         let schema_manager = Arc::new(SchemaRegisterManager::new());
+        let tls_cert_resolver = match TlsCertResolver::new(&conf.network) {
+            Ok(resolver) => Arc::new(resolver),
+            Err(e) => {
+                panic!("{}", e);
+            }
+        };
+        let rule_engine = Arc::new(RuleEngine::new(
+            cache_manager.clone(),
+            connector_manager.clone(),
+            message_storage_adapter.clone(),
+            client_pool.clone(),
+        ));
         MqttBroker {
             runtime,
             cache_manager,
@@ -161,7 +185,9 @@ pub fn new(
             connection_manager,
             auth_driver,
             delay_message_manager,
+            rule_engine,
             schema_manager,
+            tls_cert_resolver,
         }
     }
 
@@ -178,18 +204,26 @@ pub fn start(&self, stop_send: broadcast::Sender<bool>) {
         self.start_mqtt_server(stop_send.clone());
         self.start_quic_server(stop_send.clone());
         self.start_websocket_server(stop_send.clone());
+        self.start_http_server(stop_send.clone());
         self.start_keep_alive_thread(stop_send.clone());
         self.start_delay_message_thread();
+        self.start_retain_message_thread(stop_send.clone());
         self.start_update_cache_thread(stop_send.clone());
         self.start_system_topic_thread(stop_send.clone());
         self.start_prometheus();
         self.start_connector_thread(stop_send.clone());
+        self.start_rule_engine_thread(stop_send.clone());
+        self.start_config_reload_thread(stop_send.clone());
         self.awaiting_stop(stop_send);
     }
 
     fn start_tracer_provider(&self) {
         self.runtime.spawn(async move {
-            common_base::telemetry::trace::init_tracer_provider(broker_mqtt_conf()).await;
+            common_base::telemetry::trace::init_tracer_provider(
+                &broker_mqtt_conf().telemetry,
+                "mqtt-broker",
+            )
+            .await;
         });
     }
     fn start_mqtt_server(&self, stop_send: broadcast::Sender<bool>) {
@@ -200,7 +234,9 @@ fn start_mqtt_server(&self, stop_send: broadcast::Sender<bool>) {
         let connection_manager = self.connection_manager.clone();
         let auth_driver = self.auth_driver.clone();
         let delay_message_manager = self.delay_message_manager.clone();
+        let rule_engine = self.rule_engine.clone();
         let schema_manager = self.schema_manager.clone();
+        let tls_cert_resolver = self.tls_cert_resolver.clone();
 
         self.runtime.spawn(async move {
             start_tcp_server(
@@ -209,20 +245,37 @@ fn start_mqtt_server(&self, stop_send: broadcast::Sender<bool>) {
                 connection_manager,
                 message_storage_adapter,
                 delay_message_manager,
+                rule_engine,
                 schema_manager,
                 client_pool,
                 stop_send,
                 auth_driver,
+                tls_cert_resolver,
             )
             .await
         });
     }
 
+    fn start_http_server(&self, stop_send: broadcast::Sender<bool>) {
+        let conf = broker_mqtt_conf();
+        if !conf.http_api.enable {
+            return;
+        }
+        let state = HttpServerState::new(
+            self.client_pool.clone(),
+            self.cache_manager.clone(),
+            self.connection_manager.clone(),
+            conf.http_api.api_keys.clone(),
+        );
+        self.runtime
+            .spawn(async move { start_http_server(state, stop_send).await });
+    }
+
     fn start_prometheus(&self) {
         let conf = broker_mqtt_conf();
         if conf.prometheus.enable {
             self.runtime.spawn(async move {
-                register_prometheus_export(conf.prometheus.port).await;
+                start_prometheus_export(conf.prometheus.clone(), "mqtt-broker").await;
             });
         }
     }
@@ -235,7 +288,9 @@ fn start_quic_server(&self, stop_send: broadcast::Sender<bool>) {
         let connection_manager = self.connection_manager.clone();
         let auth_driver = self.auth_driver.clone();
         let delay_message_manager = self.delay_message_manager.clone();
+        let rule_engine = self.rule_engine.clone();
         let schema_manager = self.schema_manager.clone();
+        let tls_cert_resolver = self.tls_cert_resolver.clone();
         self.runtime.spawn(async move {
             start_quic_server(
                 subscribe_manager,
@@ -243,10 +298,12 @@ fn start_quic_server(&self, stop_send: broadcast::Sender<bool>) {
                 connection_manager,
                 message_storage_adapter,
                 delay_message_manager,
+                rule_engine,
                 client_pool,
                 stop_send,
                 auth_driver,
                 schema_manager,
+                tls_cert_resolver,
             )
             .await
         });
@@ -263,6 +320,9 @@ fn start_grpc_server(&self) {
             self.schema_manager.clone(),
             self.client_pool.clone(),
             self.message_storage_adapter.clone(),
+            self.delay_message_manager.clone(),
+            self.rule_engine.clone(),
+            self.tls_cert_resolver.clone(),
         );
         self.runtime.spawn(async move {
             match server.start().await {
@@ -281,6 +341,7 @@ fn start_websocket_server(&self, stop_send: broadcast::Sender<bool>) {
             self.connection_manager.clone(),
             self.message_storage_adapter.clone(),
             self.delay_message_manager.clone(),
+            self.rule_engine.clone(),
             self.schema_manager.clone(),
             self.client_pool.clone(),
             self.auth_driver.clone(),
@@ -295,14 +356,16 @@ fn start_websocket_server(&self, stop_send: broadcast::Sender<bool>) {
             self.connection_manager.clone(),
             self.message_storage_adapter.clone(),
             self.delay_message_manager.clone(),
+            self.rule_engine.clone(),
             self.schema_manager.clone(),
             self.client_pool.clone(),
             self.auth_driver.clone(),
             stop_send.clone(),
         );
 
+        let tls_cert_resolver = self.tls_cert_resolver.clone();
         self.runtime
-            .spawn(async move { websockets_server(ws_state).await });
+            .spawn(async move { websockets_server(ws_state, tls_cert_resolver).await });
     }
 
     fn start_cluster_heartbeat_report(&self, stop_send: broadcast::Sender<bool>) {
@@ -315,8 +378,17 @@ fn start_cluster_heartbeat_report(&self, stop_send: broadcast::Sender<bool>) {
     fn start_connector_thread(&self, stop_send: broadcast::Sender<bool>) {
         let message_storage = self.message_storage_adapter.clone();
         let connector_manager = self.connector_manager.clone();
+        let cache_manager = self.cache_manager.clone();
+        let client_pool = self.client_pool.clone();
         self.runtime.spawn(async move {
-            start_connector_thread(message_storage, connector_manager, stop_send).await;
+            start_connector_thread(
+                message_storage,
+                connector_manager,
+                cache_manager,
+                client_pool,
+                stop_send,
+            )
+            .await;
         });
     }
 
@@ -340,6 +412,7 @@ fn start_push_server(&self, stop_send: broadcast::Sender<bool>) {
             self.cache_manager.clone(),
             self.subscribe_manager.clone(),
             self.connection_manager.clone(),
+            self.schema_manager.clone(),
         );
 
         self.runtime.spawn(async move {
@@ -351,6 +424,7 @@ fn start_push_server(&self, stop_send: broadcast::Sender<bool>) {
             self.message_storage_adapter.clone(),
             self.connection_manager.clone(),
             self.cache_manager.clone(),
+            self.schema_manager.clone(),
         );
 
         self.runtime.spawn(async move {
@@ -369,6 +443,13 @@ fn start_push_server(&self, stop_send: broadcast::Sender<bool>) {
         });
     }
 
+    fn start_rule_engine_thread(&self, stop_send: broadcast::Sender<bool>) {
+        let rule_engine = self.rule_engine.clone();
+        self.runtime.spawn(async move {
+            rule_engine.start(stop_send).await;
+        });
+    }
+
     fn start_keep_alive_thread(&self, stop_send: broadcast::Sender<bool>) {
         let mut keep_alive = ClientKeepAlive::new(
             self.client_pool.clone(),
@@ -393,7 +474,7 @@ fn start_delay_message_thread(&self) {
 
             // Start the delayed message index building thread
             let conf = broker_mqtt_conf();
-            let shard_num = 1;
+            let shard_num = delay_message_manager.get_shard_num();
             start_build_delay_queue(
                 conf.cluster_name.clone(),
                 delay_message_manager.clone(),
@@ -413,6 +494,18 @@ fn start_delay_message_thread(&self) {
         });
     }
 
+    fn start_retain_message_thread(&self, stop_send: broadcast::Sender<bool>) {
+        let message_expire = MessageExpire::new(
+            self.cache_manager.clone(),
+            self.client_pool.clone(),
+            self.message_storage_adapter.clone(),
+            stop_send,
+        );
+        self.runtime.spawn(async move {
+            message_expire.start().await;
+        });
+    }
+
     fn start_update_cache_thread(&self, stop_send: broadcast::Sender<bool>) {
         let update_user_cache = UpdateUserCache::new(stop_send.clone(), self.auth_driver.clone());
 
@@ -431,23 +524,55 @@ fn start_update_cache_thread(&self, stop_send: broadcast::Sender<bool>) {
         self.runtime.spawn(async move {
             update_flapping_detect_cache.start_update().await;
         });
+
+        let tenant_usage_sync = TenantUsageSync::new(
+            self.cache_manager.clone(),
+            self.client_pool.clone(),
+            self.message_storage_adapter.clone(),
+            stop_send.clone(),
+        );
+        self.runtime.spawn(async move {
+            tenant_usage_sync.start().await;
+        });
     }
 
     fn start_system_topic_thread(&self, stop_send: broadcast::Sender<bool>) {
         let cache_manager = self.cache_manager.clone();
         let message_storage_adapter = self.message_storage_adapter.clone();
         let client_pool = self.client_pool.clone();
+        let subscribe_manager = self.subscribe_manager.clone();
         self.runtime.spawn(async move {
             start_opservability(
                 cache_manager,
                 message_storage_adapter,
                 client_pool,
+                subscribe_manager,
                 stop_send,
             )
             .await;
         });
     }
 
+    fn start_config_reload_thread(&self, stop_send: broadcast::Sender<bool>) {
+        let cache_manager = self.cache_manager.clone();
+        let raw_stop_send = stop_send.clone();
+        self.runtime.spawn(async move {
+            apply_cluster_config_reload(cache_manager, raw_stop_send).await;
+        });
+
+        let tls_cert_resolver = self.tls_cert_resolver.clone();
+        let raw_stop_send = stop_send.clone();
+        self.runtime.spawn(async move {
+            start_cert_watcher(tls_cert_resolver, raw_stop_send).await;
+        });
+
+        if let Some(config_path) = broker_mqtt_conf_path() {
+            self.runtime.spawn(async move {
+                start_config_reload_watcher(config_path, reload_broker_mqtt_conf, stop_send).await;
+            });
+        }
+    }
+
     pub fn awaiting_stop(&self, stop_send: broadcast::Sender<bool>) {
         self.runtime.spawn(async move {
             sleep(Duration::from_millis(5)).await;
@@ -11,3 +11,63 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use prometheus_client::encoding::EncodeLabelSet;
+
+pub const AUTH_METHOD_SECRET_FREE: &str = "secret_free";
+pub const AUTH_METHOD_PLAINTEXT: &str = "plaintext";
+pub const AUTH_METHOD_ANONYMOUS: &str = "anonymous";
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct AuthLabels {
+    method: String,
+    result: String,
+}
+
+common_base::register_counter_metric!(
+    AUTH_COUNTER,
+    "authentications",
+    "The number of authentication attempts by method and result.",
+    AuthLabels
+);
+
+pub fn metrics_auth_success(method: &str) {
+    metrics_auth(method, "success")
+}
+
+pub fn metrics_auth_failure(method: &str) {
+    metrics_auth(method, "failure")
+}
+
+fn metrics_auth(method: &str, result: &str) {
+    let labels = AuthLabels {
+        method: method.to_string(),
+        result: result.to_string(),
+    };
+    common_base::counter_metric_inc!(AUTH_COUNTER, labels)
+}
+
+pub fn get_auth_counter(method: &str, result: &str) -> u64 {
+    let labels = AuthLabels {
+        method: method.to_string(),
+        result: result.to_string(),
+    };
+    let mut res = 0;
+    common_base::counter_metric_get!(AUTH_COUNTER, labels, res);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_counter_test() {
+        metrics_auth_success(AUTH_METHOD_PLAINTEXT);
+        metrics_auth_failure(AUTH_METHOD_PLAINTEXT);
+        metrics_auth_failure(AUTH_METHOD_PLAINTEXT);
+        assert_eq!(get_auth_counter(AUTH_METHOD_PLAINTEXT, "success"), 1);
+        assert_eq!(get_auth_counter(AUTH_METHOD_PLAINTEXT, "failure"), 2);
+        assert_eq!(get_auth_counter(AUTH_METHOD_ANONYMOUS, "failure"), 0);
+    }
+}
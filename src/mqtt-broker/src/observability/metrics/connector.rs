@@ -0,0 +1,57 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use prometheus_client::encoding::EncodeLabelSet;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct ConnectorLabel {
+    connector_name: String,
+}
+
+common_base::register_gauge_metric!(
+    CONNECTOR_LAG,
+    "connector_lag_seconds",
+    "Age in seconds of the oldest record a connector has not delivered yet.",
+    ConnectorLabel
+);
+
+common_base::register_counter_metric!(
+    CONNECTOR_MESSAGES_DROPPED,
+    "connector_messages_dropped",
+    "The number of messages a connector gave up on without a dead-letter topic.",
+    ConnectorLabel
+);
+
+pub fn metrics_connector_lag(connector_name: &str, lag: u64) {
+    let label = ConnectorLabel {
+        connector_name: connector_name.to_string(),
+    };
+    common_base::gauge_metric_set!(CONNECTOR_LAG, label, lag as i64)
+}
+
+pub fn incr_connector_messages_dropped(connector_name: &str, count: u64) {
+    let label = ConnectorLabel {
+        connector_name: connector_name.to_string(),
+    };
+    common_base::counter_metric_inc_by!(CONNECTOR_MESSAGES_DROPPED, label, count)
+}
+
+pub fn get_connector_messages_dropped(connector_name: &str) -> u64 {
+    let label = ConnectorLabel {
+        connector_name: connector_name.to_string(),
+    };
+    let mut res = 0;
+    common_base::counter_metric_get!(CONNECTOR_MESSAGES_DROPPED, label, res);
+    res
+}
@@ -38,6 +38,37 @@ pub fn get_client_connection_counter(client_id: String) -> u64 {
     res
 }
 
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct SubscribeTranscodeLabels {
+    client_id: String,
+    sub_path: String,
+}
+
+common_base::register_counter_metric!(
+    SUBSCRIBE_TRANSCODE_ERROR_COUNTER,
+    "subscribe_transcode_errors",
+    "The number of messages that failed to be transcoded for a subscriber.",
+    SubscribeTranscodeLabels
+);
+
+pub fn incr_subscribe_transcode_error_counter(client_id: String, sub_path: String) {
+    let labels = SubscribeTranscodeLabels {
+        client_id,
+        sub_path,
+    };
+    common_base::counter_metric_inc!(SUBSCRIBE_TRANSCODE_ERROR_COUNTER, labels)
+}
+
+pub fn get_subscribe_transcode_error_counter(client_id: String, sub_path: String) -> u64 {
+    let labels = SubscribeTranscodeLabels {
+        client_id,
+        sub_path,
+    };
+    let mut res = 0;
+    common_base::counter_metric_get!(SUBSCRIBE_TRANSCODE_ERROR_COUNTER, labels, res);
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use crate::observability::metrics::event_metrics;
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 pub mod auth;
+pub mod connector;
 pub mod event_metrics;
 pub mod packets;
 pub mod publish;
+pub mod rule;
 pub mod server;
 pub mod session;
+pub mod tenant;
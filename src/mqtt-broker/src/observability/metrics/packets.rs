@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::server::connection::{NetworkConnection, NetworkConnectionType};
+use common_base::metrics::registry::FamilyGauge;
 use prometheus_client::encoding::EncodeLabelSet;
 use protocol::mqtt::{
     codec::{calc_mqtt_packet_size, MqttPacketWrapper},
@@ -357,7 +358,7 @@ pub fn record_sent_metrics(packet_wrapper: &MqttPacketWrapper, network_type: Str
         MqttPacket::Disconnect(_, _) => {
             common_base::gauge_metric_inc!(PACKETS_DISCONNECT_SENT, label_qos)
         }
-        MqttPacket::Auth(_, _) => common_base::gauge_metric_inc!(PACKETS_CONNACK_SENT, label_qos),
+        MqttPacket::Auth(_, _) => common_base::gauge_metric_inc!(PACKETS_AUTH_SENT, label_qos),
         _ => unreachable!("This branch only matches for packets could not be sent"),
     }
 }
@@ -380,6 +381,209 @@ pub fn record_messages_dropped_no_subscribers_metrics(qos: QoS) {
     common_base::gauge_metric_inc!(MESSAGES_DROPPED_NO_SUBSCRIBERS, label);
 }
 
+const NETWORK_TYPES: [NetworkConnectionType; 5] = [
+    NetworkConnectionType::Tcp,
+    NetworkConnectionType::Tls,
+    NetworkConnectionType::WebSocket,
+    NetworkConnectionType::WebSockets,
+    NetworkConnectionType::Quic,
+];
+
+const SENT_QOS: [&str; 4] = ["-1", "0", "1", "2"];
+
+fn sum_network_gauge(family: &FamilyGauge<NetworkLabel>) -> i64 {
+    let family = family.read().unwrap();
+    NETWORK_TYPES
+        .iter()
+        .filter_map(|network| {
+            family.get(&NetworkLabel {
+                network: network.to_string(),
+            })
+        })
+        .map(|gauge| gauge.get())
+        .sum()
+}
+
+fn sum_network_qos_gauge(family: &FamilyGauge<NetworkQosLabel>, qos: Option<&str>) -> i64 {
+    let family = family.read().unwrap();
+    let mut total = 0;
+    for network in NETWORK_TYPES.iter() {
+        for label_qos in SENT_QOS.iter() {
+            if qos.is_some_and(|qos| qos != *label_qos) {
+                continue;
+            }
+            let label = NetworkQosLabel {
+                network: network.to_string(),
+                qos: label_qos.to_string(),
+            };
+            if let Some(gauge) = family.get(&label) {
+                total += gauge.get();
+            }
+        }
+    }
+    total
+}
+
+fn sum_qos_gauge(family: &FamilyGauge<QosLabel>) -> i64 {
+    let family = family.read().unwrap();
+    SENT_QOS
+        .iter()
+        .filter_map(|qos| {
+            family.get(&QosLabel {
+                qos: qos.to_string(),
+            })
+        })
+        .map(|gauge| gauge.get())
+        .sum()
+}
+
+// Totals across all networks and qos levels, named after their $SYS metrics topic.
+pub fn packet_metric_totals() -> Vec<(&'static str, i64)> {
+    vec![
+        ("packets/received", sum_network_gauge(&PACKETS_RECEIVED)),
+        ("packets/sent", sum_network_qos_gauge(&PACKETS_SENT, None)),
+        (
+            "packets/received/error",
+            sum_network_gauge(&PACKETS_RECEIVED_ERROR),
+        ),
+        (
+            "packets/connect/received",
+            sum_network_gauge(&PACKETS_CONNECT_RECEIVED),
+        ),
+        (
+            "packets/connack/sent",
+            sum_network_qos_gauge(&PACKETS_CONNACK_SENT, None),
+        ),
+        (
+            "packets/connack/error",
+            sum_network_gauge(&PACKETS_CONNACK_ERROR),
+        ),
+        (
+            "packets/connack/auth_error",
+            sum_network_gauge(&PACKETS_CONNACK_AUTH_ERROR),
+        ),
+        (
+            "packets/publish/received",
+            sum_network_gauge(&PACKETS_PUBLISH_RECEIVED),
+        ),
+        (
+            "packets/publish/sent",
+            sum_network_qos_gauge(&PACKETS_PUBLISH_SENT, None),
+        ),
+        (
+            "packets/puback/received",
+            sum_network_gauge(&PACKETS_PUBACK_RECEIVED),
+        ),
+        (
+            "packets/puback/sent",
+            sum_network_qos_gauge(&PACKETS_PUBACK_SENT, None),
+        ),
+        (
+            "packets/pubrec/received",
+            sum_network_gauge(&PACKETS_PUBREC_RECEIVED),
+        ),
+        (
+            "packets/pubrec/sent",
+            sum_network_qos_gauge(&PACKETS_PUBREC_SENT, None),
+        ),
+        (
+            "packets/pubrel/received",
+            sum_network_gauge(&PACKETS_PUBREL_RECEIVED),
+        ),
+        (
+            "packets/pubrel/sent",
+            sum_network_qos_gauge(&PACKETS_PUBREL_SENT, None),
+        ),
+        (
+            "packets/pubcomp/received",
+            sum_network_gauge(&PACKETS_PUBCOMP_RECEIVED),
+        ),
+        (
+            "packets/pubcomp/sent",
+            sum_network_qos_gauge(&PACKETS_PUBCOMP_SENT, None),
+        ),
+        (
+            "packets/subscribe/received",
+            sum_network_gauge(&PACKETS_SUBSCRIBLE_RECEIVED),
+        ),
+        (
+            "packets/suback/sent",
+            sum_network_qos_gauge(&PACKETS_SUBACK_SENT, None),
+        ),
+        (
+            "packets/unsubscribe/received",
+            sum_network_gauge(&PACKETS_UNSUBSCRIBLE_RECEIVED),
+        ),
+        (
+            "packets/unsuback/sent",
+            sum_network_qos_gauge(&PACKETS_UNSUBACK_SENT, None),
+        ),
+        (
+            "packets/pingreq/received",
+            sum_network_gauge(&PACKETS_PINGREQ_RECEIVED),
+        ),
+        (
+            "packets/pingresp/sent",
+            sum_network_qos_gauge(&PACKETS_PINGRESP_SENT, None),
+        ),
+        (
+            "packets/disconnect/received",
+            sum_network_gauge(&PACKETS_DISCONNECT_RECEIVED),
+        ),
+        (
+            "packets/disconnect/sent",
+            sum_network_qos_gauge(&PACKETS_DISCONNECT_SENT, None),
+        ),
+        (
+            "packets/auth/received",
+            sum_network_gauge(&PACKETS_AUTH_RECEIVED),
+        ),
+        (
+            "packets/auth/sent",
+            sum_network_qos_gauge(&PACKETS_AUTH_SENT, None),
+        ),
+        ("bytes/received", sum_network_gauge(&BYTES_RECEIVED)),
+        ("bytes/sent", sum_network_qos_gauge(&BYTES_SENT, None)),
+    ]
+}
+
+pub fn message_metric_totals() -> Vec<(&'static str, i64)> {
+    vec![
+        (
+            "messages/received",
+            sum_network_gauge(&PACKETS_PUBLISH_RECEIVED),
+        ),
+        (
+            "messages/sent",
+            sum_network_qos_gauge(&PACKETS_PUBLISH_SENT, None),
+        ),
+        (
+            "messages/qos0/sent",
+            sum_network_qos_gauge(&PACKETS_PUBLISH_SENT, Some("0")),
+        ),
+        (
+            "messages/qos1/sent",
+            sum_network_qos_gauge(&PACKETS_PUBLISH_SENT, Some("1")),
+        ),
+        (
+            "messages/qos2/sent",
+            sum_network_qos_gauge(&PACKETS_PUBLISH_SENT, Some("2")),
+        ),
+        (
+            "messages/retained/received",
+            sum_qos_gauge(&RETAIN_PACKETS_RECEIVED),
+        ),
+        (
+            "messages/retained/sent",
+            sum_qos_gauge(&RETAIN_PACKETS_SEND),
+        ),
+        (
+            "messages/dropped/no_subscribers",
+            sum_qos_gauge(&MESSAGES_DROPPED_NO_SUBSCRIBERS),
+        ),
+    ]
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -444,6 +648,26 @@ async fn test_gauge_metrics() {
         }
     }
 
+    #[test]
+    fn packet_metric_totals_test() {
+        record_received_error_metrics(NetworkConnectionType::Quic);
+        record_messages_dropped_no_subscribers_metrics(QoS::AtLeastOnce);
+
+        let packets = packet_metric_totals();
+        let received_error = packets
+            .iter()
+            .find(|(name, _)| *name == "packets/received/error")
+            .unwrap();
+        assert!(received_error.1 >= 1);
+
+        let messages = message_metric_totals();
+        let dropped = messages
+            .iter()
+            .find(|(name, _)| *name == "messages/dropped/no_subscribers")
+            .unwrap();
+        assert!(dropped.1 >= 1);
+    }
+
     use protocol::mqtt::codec::{calc_mqtt_packet_size, MqttPacketWrapper};
     use protocol::mqtt::common::{MqttPacket, MqttProtocol, Publish, UnsubAck};
 
@@ -483,6 +707,7 @@ fn calc_mqtt_packet_test() {
             connection_stop_sx: None,
             connection_id: 100,
             protocol: Some(MqttProtocol::Mqtt3),
+            peer_cert_subject: Default::default(),
         };
         let ty = NetworkConnectionType::Tcp;
         record_received_metrics(&nc, &mp, &ty);
@@ -11,3 +11,71 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use common_base::metrics::registry::latency_ms_buckets;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::histogram::exponential_buckets;
+use protocol::mqtt::common::QoS;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct QosLabel {
+    qos: String,
+}
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct EmptyLabel {}
+
+common_base::register_histogram_metric!(
+    PUBLISH_LATENCY_MS,
+    "publish_latency_ms",
+    "Time taken to handle an incoming publish until it is acknowledged, in milliseconds.",
+    QosLabel,
+    latency_ms_buckets()
+);
+
+common_base::register_histogram_metric!(
+    MESSAGE_DELIVERY_LATENCY_MS,
+    "message_delivery_latency_ms",
+    "Time from a message being received to it being pushed to a subscriber, in milliseconds.",
+    QosLabel,
+    latency_ms_buckets()
+);
+
+common_base::register_histogram_metric!(
+    INFLIGHT_WINDOW_SIZE,
+    "inflight_window_size",
+    "Size of the client's QoS 1/2 receive window each time a new QoS message arrives.",
+    EmptyLabel,
+    exponential_buckets(1.0, 2.0, 12)
+);
+
+common_base::register_gauge_metric!(
+    RETAINED_MESSAGES,
+    "retained_messages",
+    "The number of topics holding a retained message.",
+    EmptyLabel
+);
+
+pub fn metrics_publish_latency(qos: QoS, ms: f64) {
+    let label = QosLabel {
+        qos: (qos as u8).to_string(),
+    };
+    common_base::histogram_metric_observe!(PUBLISH_LATENCY_MS, label, ms)
+}
+
+pub fn metrics_message_delivery_latency(qos: QoS, ms: f64) {
+    let label = QosLabel {
+        qos: (qos as u8).to_string(),
+    };
+    common_base::histogram_metric_observe!(MESSAGE_DELIVERY_LATENCY_MS, label, ms)
+}
+
+pub fn metrics_inflight_window(size: isize) {
+    let label = EmptyLabel {};
+    common_base::histogram_metric_observe!(INFLIGHT_WINDOW_SIZE, label, size.max(0) as f64)
+}
+
+pub fn metrics_retained_count(count: usize) {
+    let label = EmptyLabel {};
+    common_base::gauge_metric_set!(RETAINED_MESSAGES, label, count as i64)
+}
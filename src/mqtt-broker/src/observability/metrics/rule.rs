@@ -0,0 +1,58 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use prometheus_client::encoding::EncodeLabelSet;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct RuleLabels {
+    rule_name: String,
+}
+
+common_base::register_counter_metric!(
+    RULE_MATCHED_COUNTER,
+    "rule_matched",
+    "The number of messages matched by a rule.",
+    RuleLabels
+);
+
+common_base::register_counter_metric!(
+    RULE_FAILED_COUNTER,
+    "rule_failed",
+    "The number of messages a rule failed to evaluate or deliver.",
+    RuleLabels
+);
+
+pub fn incr_rule_matched_counter(rule_name: String) {
+    let labels = RuleLabels { rule_name };
+    common_base::counter_metric_inc!(RULE_MATCHED_COUNTER, labels)
+}
+
+pub fn get_rule_matched_counter(rule_name: String) -> u64 {
+    let labels = RuleLabels { rule_name };
+    let mut res = 0;
+    common_base::counter_metric_get!(RULE_MATCHED_COUNTER, labels, res);
+    res
+}
+
+pub fn incr_rule_failed_counter(rule_name: String) {
+    let labels = RuleLabels { rule_name };
+    common_base::counter_metric_inc!(RULE_FAILED_COUNTER, labels)
+}
+
+pub fn get_rule_failed_counter(rule_name: String) -> u64 {
+    let labels = RuleLabels { rule_name };
+    let mut res = 0;
+    common_base::counter_metric_get!(RULE_FAILED_COUNTER, labels, res);
+    res
+}
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::LazyLock;
+
+use dashmap::DashMap;
 use prometheus_client::encoding::EncodeLabelSet;
 #[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
 struct LabelType {
@@ -26,12 +29,16 @@ struct LabelType {
     LabelType
 );
 
+// Latest observed length per queue, keyed by "{type}-{label}".
+static NETWORK_QUEUE_LEN: LazyLock<DashMap<String, usize>> = LazyLock::new(DashMap::new);
+
 pub fn metrics_request_queue(label: &str, len: usize) {
     let label_type = LabelType {
         label: label.to_string(),
         r#type: "request".to_string(),
     };
-    common_base::gauge_metric_inc_by!(BROKER_NETWORK_QUEUE_NUM, label_type, len as i64);
+    common_base::gauge_metric_set!(BROKER_NETWORK_QUEUE_NUM, label_type, len as i64);
+    NETWORK_QUEUE_LEN.insert(format!("request-{}", label), len);
 }
 
 pub fn metrics_response_queue(label: &str, len: usize) {
@@ -39,5 +46,44 @@ pub fn metrics_response_queue(label: &str, len: usize) {
         label: label.to_string(),
         r#type: "response".to_string(),
     };
-    common_base::gauge_metric_inc_by!(BROKER_NETWORK_QUEUE_NUM, label_type, len as i64);
+    common_base::gauge_metric_set!(BROKER_NETWORK_QUEUE_NUM, label_type, len as i64);
+    NETWORK_QUEUE_LEN.insert(format!("response-{}", label), len);
+}
+
+// Called when the worker owning a queue stops, so neither the gauge nor the
+// busy_port alarm keep reporting its last length.
+pub fn metrics_remove_network_queue(queue_type: &str, label: &str) {
+    let label_type = LabelType {
+        label: label.to_string(),
+        r#type: queue_type.to_string(),
+    };
+    BROKER_NETWORK_QUEUE_NUM
+        .write()
+        .unwrap()
+        .remove(&label_type);
+    NETWORK_QUEUE_LEN.remove(&format!("{}-{}", queue_type, label));
+}
+
+pub fn busy_network_queues(watermark: usize) -> Vec<(String, usize)> {
+    NETWORK_QUEUE_LEN
+        .iter()
+        .filter(|raw| *raw.value() >= watermark)
+        .map(|raw| (raw.key().clone(), *raw.value()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_network_queue_test() {
+        metrics_response_queue("handler-test", 900);
+        assert!(busy_network_queues(800).contains(&("response-handler-test".to_string(), 900)));
+
+        metrics_remove_network_queue("response", "handler-test");
+        assert!(!busy_network_queues(800)
+            .iter()
+            .any(|(key, _)| key == "response-handler-test"));
+    }
 }
@@ -11,3 +11,64 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use prometheus_client::encoding::EncodeLabelSet;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct SessionEventLabel {
+    event: String,
+}
+
+common_base::register_counter_metric!(
+    SESSION_EVENT_COUNTER,
+    "session_events",
+    "The number of sessions created, resumed, taken over or expired.",
+    SessionEventLabel
+);
+
+pub fn metrics_session_created() {
+    metrics_session_event("created")
+}
+
+pub fn metrics_session_resumed() {
+    metrics_session_event("resumed")
+}
+
+pub fn metrics_session_takenover() {
+    metrics_session_event("takenover")
+}
+
+pub fn metrics_session_expired() {
+    metrics_session_event("expired")
+}
+
+fn metrics_session_event(event: &str) {
+    let label = SessionEventLabel {
+        event: event.to_string(),
+    };
+    common_base::counter_metric_inc!(SESSION_EVENT_COUNTER, label)
+}
+
+pub fn get_session_event_counter(event: &str) -> u64 {
+    let label = SessionEventLabel {
+        event: event.to_string(),
+    };
+    let mut res = 0;
+    common_base::counter_metric_get!(SESSION_EVENT_COUNTER, label, res);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_event_counter_test() {
+        metrics_session_created();
+        metrics_session_expired();
+        metrics_session_expired();
+        assert_eq!(get_session_event_counter("created"), 1);
+        assert_eq!(get_session_event_counter("expired"), 2);
+        assert_eq!(get_session_event_counter("takenover"), 0);
+    }
+}
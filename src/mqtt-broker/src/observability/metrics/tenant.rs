@@ -0,0 +1,118 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use prometheus_client::encoding::EncodeLabelSet;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct TenantLabel {
+    tenant: String,
+}
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+struct TenantQuotaLabel {
+    tenant: String,
+    quota: String,
+}
+
+common_base::register_gauge_metric!(
+    TENANT_CONNECTIONS,
+    "tenant_connections",
+    "Number of connections of a tenant on this broker.",
+    TenantLabel
+);
+
+common_base::register_gauge_metric!(
+    TENANT_MESSAGES_RECEIVED,
+    "tenant_messages_received",
+    "Number of publish messages received from the clients of a tenant.",
+    TenantLabel
+);
+
+common_base::register_gauge_metric!(
+    TENANT_BYTES_RECEIVED,
+    "tenant_bytes_received",
+    "Number of payload bytes received from the clients of a tenant.",
+    TenantLabel
+);
+
+common_base::register_counter_metric!(
+    TENANT_QUOTA_EXCEEDED,
+    "tenant_quota_exceeded",
+    "Number of requests rejected because a tenant quota was exceeded.",
+    TenantQuotaLabel
+);
+
+fn tenant_label(tenant: &str) -> TenantLabel {
+    TenantLabel {
+        tenant: tenant.to_string(),
+    }
+}
+
+pub fn record_tenant_connection_inc(tenant: &str) {
+    let label = tenant_label(tenant);
+    common_base::gauge_metric_inc!(TENANT_CONNECTIONS, label)
+}
+
+pub fn record_tenant_connection_dec(tenant: &str) {
+    let label = tenant_label(tenant);
+    common_base::gauge_metric_inc_by!(TENANT_CONNECTIONS, label, -1)
+}
+
+pub fn record_tenant_message_received(tenant: &str, payload_size: usize) {
+    let label = tenant_label(tenant);
+    common_base::gauge_metric_inc!(TENANT_MESSAGES_RECEIVED, label);
+    common_base::gauge_metric_inc_by!(TENANT_BYTES_RECEIVED, label, payload_size as i64);
+}
+
+pub fn record_tenant_quota_exceeded(tenant: &str, quota: &str) {
+    let label = TenantQuotaLabel {
+        tenant: tenant.to_string(),
+        quota: quota.to_string(),
+    };
+    common_base::counter_metric_inc!(TENANT_QUOTA_EXCEEDED, label)
+}
+
+pub fn get_tenant_connections(tenant: &str) -> i64 {
+    let label = tenant_label(tenant);
+    let mut res = 0;
+    common_base::gauge_metric_get!(TENANT_CONNECTIONS, label, res);
+    res
+}
+
+pub fn get_tenant_quota_exceeded(tenant: &str, quota: &str) -> u64 {
+    let label = TenantQuotaLabel {
+        tenant: tenant.to_string(),
+        quota: quota.to_string(),
+    };
+    let mut res = 0;
+    common_base::counter_metric_get!(TENANT_QUOTA_EXCEEDED, label, res);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenant_metrics_test() {
+        record_tenant_connection_inc("metrics_tenant");
+        record_tenant_connection_inc("metrics_tenant");
+        record_tenant_connection_dec("metrics_tenant");
+        assert_eq!(get_tenant_connections("metrics_tenant"), 1);
+
+        record_tenant_quota_exceeded("metrics_tenant", "topics");
+        assert_eq!(get_tenant_quota_exceeded("metrics_tenant", "topics"), 1);
+        assert_eq!(get_tenant_quota_exceeded("metrics_tenant", "sessions"), 0);
+    }
+}
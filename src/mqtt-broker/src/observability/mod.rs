@@ -18,8 +18,10 @@
 use storage_adapter::storage::StorageAdapter;
 use system_topic::SystemTopic;
 use tokio::sync::broadcast;
+use warn::AlarmManager;
 
 use crate::handler::cache::CacheManager;
+use crate::subscribe::subscribe_manager::SubscribeManager;
 
 pub mod metrics;
 pub mod slow;
@@ -30,6 +32,7 @@ pub async fn start_opservability<S>(
     cache_manager: Arc<CacheManager>,
     message_storage_adapter: Arc<S>,
     client_pool: Arc<ClientPool>,
+    subscribe_manager: Arc<SubscribeManager>,
     stop_send: broadcast::Sender<bool>,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
@@ -38,8 +41,19 @@ pub async fn start_opservability<S>(
         cache_manager.clone(),
         message_storage_adapter.clone(),
         client_pool.clone(),
+        subscribe_manager.clone(),
     );
 
+    let mut alarm_manager = AlarmManager::new(
+        cache_manager.clone(),
+        message_storage_adapter.clone(),
+        client_pool.clone(),
+    );
+    let alarm_stop_send = stop_send.clone();
+    tokio::spawn(async move {
+        alarm_manager.start(alarm_stop_send).await;
+    });
+
     tokio::spawn(async move {
         system_topic.start_thread(stop_send).await;
     });
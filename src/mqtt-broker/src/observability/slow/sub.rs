@@ -14,6 +14,7 @@
 
 use crate::handler::cache::CacheManager;
 use crate::handler::error::MqttBrokerError;
+use crate::observability::warn::record_slow_subscription;
 use common_base::tools::{get_local_ip, now_second};
 use grep::matcher::Matcher;
 use grep::regex::RegexMatcher;
@@ -56,6 +57,7 @@ pub fn record_slow_sub_data(slow_data: SlowSubData, whole_ms: u64) -> Result<(),
     let data = serde_json::to_string(&slow_data)?;
 
     if slow_data.time_ms > whole_ms {
+        record_slow_subscription();
         info!("{}", data);
     }
 
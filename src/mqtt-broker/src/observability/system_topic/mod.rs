@@ -16,18 +16,24 @@
 use std::time::Duration;
 
 use broker::report_broker_info;
+use common_base::config::broker_mqtt::broker_mqtt_conf;
 use common_base::tools::get_local_ip;
 use grpc_clients::pool::ClientPool;
 use log::{debug, error};
 use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::message::MqttMessage;
+use packet::report_broker_metrics;
+use stats::report_broker_stat;
 use storage_adapter::storage::StorageAdapter;
+use sysmon::{start_schedule_probe, SystemMonitor};
 use tokio::select;
 use tokio::sync::broadcast;
-use tokio::time::sleep;
+use tokio::time::interval;
 
 use crate::handler::cache::CacheManager;
 use crate::handler::topic::try_init_topic;
 use crate::storage::message::MessageStorage;
+use crate::subscribe::subscribe_manager::SubscribeManager;
 
 // Cluster status information
 pub const SYSTEM_TOPIC_BROKERS: &str = "$SYS/brokers";
@@ -46,6 +52,26 @@
 pub const SYSTEM_TOPIC_BROKERS_UNSUBSCRIBED: &str =
     "$SYS/brokers/${node}/clients/${clientid}/unsubscribed";
 
+// Stats
+pub const SYSTEM_TOPIC_BROKERS_STATS_CONNECTIONS_COUNT: &str =
+    "$SYS/brokers/${node}/stats/connections/count";
+pub const SYSTEM_TOPIC_BROKERS_STATS_SESSIONS_COUNT: &str =
+    "$SYS/brokers/${node}/stats/sessions/count";
+pub const SYSTEM_TOPIC_BROKERS_STATS_TOPICS_COUNT: &str = "$SYS/brokers/${node}/stats/topics/count";
+pub const SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_COUNT: &str =
+    "$SYS/brokers/${node}/stats/subscriptions/count";
+pub const SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_SHARED_COUNT: &str =
+    "$SYS/brokers/${node}/stats/subscriptions/shared/count";
+pub const SYSTEM_TOPIC_BROKERS_STATS_RETAINED_COUNT: &str =
+    "$SYS/brokers/${node}/stats/retained/count";
+
+// Metrics, ${metric} is e.g. packets/publish/received or messages/sent
+pub const SYSTEM_TOPIC_BROKERS_METRICS: &str = "$SYS/brokers/${node}/metrics/${metric}";
+
+// Alarms
+pub const SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE: &str = "$SYS/brokers/${node}/alarms/activate";
+pub const SYSTEM_TOPIC_BROKERS_ALARMS_DEACTIVATE: &str = "$SYS/brokers/${node}/alarms/deactivate";
+
 pub mod broker;
 pub mod event;
 pub mod packet;
@@ -57,6 +83,7 @@ pub struct SystemTopic<S> {
     pub metadata_cache: Arc<CacheManager>,
     pub message_storage_adapter: Arc<S>,
     pub client_pool: Arc<ClientPool>,
+    pub subscribe_manager: Arc<SubscribeManager>,
 }
 
 impl<S> SystemTopic<S>
@@ -67,16 +94,26 @@ pub fn new(
         metadata_cache: Arc<CacheManager>,
         message_storage_adapter: Arc<S>,
         client_pool: Arc<ClientPool>,
+        subscribe_manager: Arc<SubscribeManager>,
     ) -> Self {
         SystemTopic {
             metadata_cache,
             message_storage_adapter,
             client_pool,
+            subscribe_manager,
         }
     }
 
     pub async fn start_thread(&self, stop_send: broadcast::Sender<bool>) {
         self.try_init_system_topic().await;
+        let conf = broker_mqtt_conf();
+        let config = &conf.system_monitor;
+        let mut sys_interval = interval(Duration::from_secs(config.sys_interval_secs.max(1)));
+        let mut sysmon_interval = interval(Duration::from_secs(config.sysmon_interval_secs.max(1)));
+        let mut system_monitor = SystemMonitor::new();
+        if config.enable {
+            start_schedule_probe(stop_send.clone());
+        }
         let mut stop_rx = stop_send.subscribe();
         loop {
             select! {
@@ -88,9 +125,13 @@ pub async fn start_thread(&self, stop_send: broadcast::Sender<bool>) {
                         }
                     }
                 }
-                _ = self.report_info()=>{}
+                _ = sys_interval.tick() => {
+                    self.report_info().await;
+                }
+                _ = sysmon_interval.tick(), if config.enable => {
+                    system_monitor.check();
+                }
             }
-            sleep(Duration::from_secs(60)).await;
         }
     }
 
@@ -101,6 +142,19 @@ pub async fn report_info(&self) {
             &self.message_storage_adapter,
         )
         .await;
+        report_broker_stat(
+            &self.client_pool,
+            &self.metadata_cache,
+            &self.message_storage_adapter,
+            &self.subscribe_manager,
+        )
+        .await;
+        report_broker_metrics(
+            &self.client_pool,
+            &self.metadata_cache,
+            &self.message_storage_adapter,
+        )
+        .await;
     }
 
     pub async fn try_init_system_topic(&self) {
@@ -133,6 +187,14 @@ fn get_all_system_topic(&self) -> Vec<String> {
             SYSTEM_TOPIC_BROKERS_UPTIME.to_string(),
             SYSTEM_TOPIC_BROKERS_DATETIME.to_string(),
             SYSTEM_TOPIC_BROKERS_SYSDESCR.to_string(),
+            SYSTEM_TOPIC_BROKERS_STATS_CONNECTIONS_COUNT.to_string(),
+            SYSTEM_TOPIC_BROKERS_STATS_SESSIONS_COUNT.to_string(),
+            SYSTEM_TOPIC_BROKERS_STATS_TOPICS_COUNT.to_string(),
+            SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_COUNT.to_string(),
+            SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_SHARED_COUNT.to_string(),
+            SYSTEM_TOPIC_BROKERS_STATS_RETAINED_COUNT.to_string(),
+            SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE.to_string(),
+            SYSTEM_TOPIC_BROKERS_ALARMS_DEACTIVATE.to_string(),
         ]
     }
 }
@@ -145,6 +207,27 @@ fn replace_topic_name(mut topic_name: String) -> String {
     topic_name
 }
 
+pub(crate) async fn report_system_data<S>(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    topic_name: String,
+    content: String,
+) where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    if let Some(record) = MqttMessage::build_system_topic_message(topic_name.clone(), content) {
+        write_topic_data(
+            message_storage_adapter,
+            metadata_cache,
+            client_pool,
+            topic_name,
+            record,
+        )
+        .await;
+    }
+}
+
 pub(crate) async fn write_topic_data<S>(
     message_storage_adapter: &Arc<S>,
     metadata_cache: &Arc<CacheManager>,
@@ -163,7 +246,8 @@ pub(crate) async fn write_topic_data<S>(
     .await
     {
         Ok(topic) => {
-            let message_storage = MessageStorage::new(message_storage_adapter.clone());
+            let message_storage =
+                MessageStorage::new_by_topic(message_storage_adapter.clone(), &topic.topic_name);
             match message_storage
                 .append_topic_message(&topic.topic_id, vec![record])
                 .await
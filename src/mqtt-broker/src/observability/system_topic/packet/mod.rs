@@ -11,3 +11,38 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use storage_adapter::storage::StorageAdapter;
+
+use super::{replace_topic_name, report_system_data, SYSTEM_TOPIC_BROKERS_METRICS};
+use crate::handler::cache::CacheManager;
+use crate::observability::metrics::packets::{message_metric_totals, packet_metric_totals};
+
+pub(crate) async fn report_broker_metrics<S>(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+) where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    let metrics = packet_metric_totals()
+        .into_iter()
+        .chain(message_metric_totals());
+    for (metric, value) in metrics {
+        report_system_data(
+            client_pool,
+            metadata_cache,
+            message_storage_adapter,
+            metric_topic_name(metric),
+            value.to_string(),
+        )
+        .await;
+    }
+}
+
+fn metric_topic_name(metric: &str) -> String {
+    replace_topic_name(SYSTEM_TOPIC_BROKERS_METRICS.replace("${metric}", metric))
+}
@@ -11,3 +11,76 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use storage_adapter::storage::StorageAdapter;
+
+use super::{
+    replace_topic_name, report_system_data, SYSTEM_TOPIC_BROKERS_STATS_CONNECTIONS_COUNT,
+    SYSTEM_TOPIC_BROKERS_STATS_RETAINED_COUNT, SYSTEM_TOPIC_BROKERS_STATS_SESSIONS_COUNT,
+    SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_COUNT,
+    SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_SHARED_COUNT, SYSTEM_TOPIC_BROKERS_STATS_TOPICS_COUNT,
+};
+use crate::handler::cache::CacheManager;
+use crate::observability::metrics::publish::metrics_retained_count;
+use crate::subscribe::sub_common::{is_share_sub, strip_transcode_prefix};
+use crate::subscribe::subscribe_manager::SubscribeManager;
+
+pub(crate) async fn report_broker_stat<S>(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    subscribe_manager: &Arc<SubscribeManager>,
+) where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    for (topic_name, value) in build_broker_stat(metadata_cache, subscribe_manager) {
+        report_system_data(
+            client_pool,
+            metadata_cache,
+            message_storage_adapter,
+            replace_topic_name(topic_name.to_string()),
+            value.to_string(),
+        )
+        .await;
+    }
+}
+
+fn build_broker_stat(
+    metadata_cache: &Arc<CacheManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+) -> Vec<(&'static str, usize)> {
+    let shared_subscriptions = subscribe_manager
+        .subscribe_list
+        .iter()
+        .filter(|raw| is_share_sub(&strip_transcode_prefix(&raw.filter.path)))
+        .count();
+    let retained = metadata_cache.retain_message_index.len();
+    metrics_retained_count(retained);
+
+    vec![
+        (
+            SYSTEM_TOPIC_BROKERS_STATS_CONNECTIONS_COUNT,
+            metadata_cache.connection_info.len(),
+        ),
+        (
+            SYSTEM_TOPIC_BROKERS_STATS_SESSIONS_COUNT,
+            metadata_cache.session_info.len(),
+        ),
+        (
+            SYSTEM_TOPIC_BROKERS_STATS_TOPICS_COUNT,
+            metadata_cache.topic_info.len(),
+        ),
+        (
+            SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_COUNT,
+            subscribe_manager.subscribe_list.len(),
+        ),
+        (
+            SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_SHARED_COUNT,
+            shared_subscriptions,
+        ),
+        (SYSTEM_TOPIC_BROKERS_STATS_RETAINED_COUNT, retained),
+    ]
+}
@@ -11,3 +11,174 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use log::debug;
+use sysinfo::System;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+
+use crate::observability::metrics::server::busy_network_queues;
+use crate::observability::warn::record_sysmon_result;
+
+const SCHEDULE_PROBE_INTERVAL_MS: u64 = 100;
+
+// The longest schedule delay seen since the last check.
+static MAX_SCHEDULE_DELAY_MS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SysmonAlarm {
+    HighCpuUsage,
+    HighMemoryUsage,
+    LongSchedule,
+    BusyPort,
+}
+
+impl SysmonAlarm {
+    fn name(&self) -> &'static str {
+        match self {
+            SysmonAlarm::HighCpuUsage => "high_cpu_usage",
+            SysmonAlarm::HighMemoryUsage => "high_system_memory_usage",
+            SysmonAlarm::LongSchedule => "long_schedule",
+            SysmonAlarm::BusyPort => "busy_port",
+        }
+    }
+}
+
+// Results are handed to the AlarmManager, which raises and clears the alarms
+// and keeps their history.
+pub struct SystemMonitor {
+    system: System,
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemMonitor {
+    pub fn new() -> Self {
+        SystemMonitor {
+            system: System::new(),
+        }
+    }
+
+    pub fn check(&mut self) {
+        let conf = broker_mqtt_conf();
+        let config = &conf.system_monitor;
+        let mut results = Vec::new();
+
+        self.system.refresh_cpu_usage();
+        let cpu_usage = self.system.global_cpu_usage();
+        results.push((
+            SysmonAlarm::HighCpuUsage,
+            cpu_usage >= config.cpu_high_watermark,
+            format!(
+                "cpu usage {:.2}%, watermark {:.2}%",
+                cpu_usage, config.cpu_high_watermark
+            ),
+        ));
+
+        self.system.refresh_memory();
+        let memory_usage = usage_percent(self.system.used_memory(), self.system.total_memory());
+        results.push((
+            SysmonAlarm::HighMemoryUsage,
+            memory_usage >= config.memory_high_watermark,
+            format!(
+                "memory usage {:.2}%, watermark {:.2}%",
+                memory_usage, config.memory_high_watermark
+            ),
+        ));
+
+        let schedule_delay = take_schedule_delay();
+        results.push((
+            SysmonAlarm::LongSchedule,
+            schedule_delay >= Duration::from_millis(config.long_schedule_ms),
+            format!(
+                "runtime schedule delay {}ms, watermark {}ms",
+                schedule_delay.as_millis(),
+                config.long_schedule_ms
+            ),
+        ));
+
+        let busy_queues = busy_network_queues(config.busy_queue_len);
+        results.push((
+            SysmonAlarm::BusyPort,
+            !busy_queues.is_empty(),
+            format!(
+                "network queues {:?}, watermark {}",
+                busy_queues, config.busy_queue_len
+            ),
+        ));
+
+        for (alarm, triggered, message) in results {
+            record_sysmon_result(alarm.name(), triggered, message);
+        }
+    }
+}
+
+// There is no GC to watch, so a saturated runtime shows up as timer ticks that
+// fire late. A task of its own samples that lateness all the time, and every
+// check reports the worst delay since the previous one.
+pub fn start_schedule_probe(stop_send: broadcast::Sender<bool>) {
+    let mut stop_rx = stop_send.subscribe();
+    tokio::spawn(async move {
+        let mut probe = interval(Duration::from_millis(SCHEDULE_PROBE_INTERVAL_MS));
+        probe.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            select! {
+                val = stop_rx.recv() => {
+                    if let Ok(flag) = val {
+                        if flag {
+                            debug!("Schedule probe thread stopped successfully");
+                            break;
+                        }
+                    }
+                }
+                scheduled = probe.tick() => {
+                    record_schedule_delay(Instant::now().saturating_duration_since(scheduled));
+                }
+            }
+        }
+    });
+}
+
+fn record_schedule_delay(delay: Duration) {
+    MAX_SCHEDULE_DELAY_MS.fetch_max(delay.as_millis() as u64, Ordering::Relaxed);
+}
+
+fn take_schedule_delay() -> Duration {
+    Duration::from_millis(MAX_SCHEDULE_DELAY_MS.swap(0, Ordering::Relaxed))
+}
+
+fn usage_percent(used: u64, total: u64) -> f32 {
+    if total == 0 {
+        return 0.0;
+    }
+    used as f32 / total as f32 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_delay_test() {
+        record_schedule_delay(Duration::from_millis(30));
+        record_schedule_delay(Duration::from_millis(300));
+        record_schedule_delay(Duration::from_millis(5));
+        assert_eq!(take_schedule_delay(), Duration::from_millis(300));
+        assert_eq!(take_schedule_delay(), Duration::ZERO);
+    }
+
+    #[test]
+    fn usage_percent_test() {
+        assert_eq!(usage_percent(1, 0), 0.0);
+        assert_eq!(usage_percent(50, 200), 25.0);
+    }
+}
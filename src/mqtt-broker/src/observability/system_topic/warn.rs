@@ -11,3 +11,98 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::tools::{get_local_ip, now_mills};
+use grpc_clients::pool::ClientPool;
+use log::error;
+use serde::{Deserialize, Serialize};
+use storage_adapter::storage::StorageAdapter;
+
+use super::{
+    replace_topic_name, report_system_data, SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE,
+    SYSTEM_TOPIC_BROKERS_ALARMS_DEACTIVATE,
+};
+use crate::handler::cache::CacheManager;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct SystemTopicAlarmEventMessage {
+    pub name: String,
+    pub message: String,
+    pub node: String,
+    pub ts: u128,
+}
+
+pub async fn st_report_alarm_activate<S>(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    name: &str,
+    message: String,
+) where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    report_alarm_event(
+        client_pool,
+        metadata_cache,
+        message_storage_adapter,
+        SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE,
+        name,
+        message,
+    )
+    .await;
+}
+
+pub async fn st_report_alarm_deactivate<S>(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    name: &str,
+    message: String,
+) where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    report_alarm_event(
+        client_pool,
+        metadata_cache,
+        message_storage_adapter,
+        SYSTEM_TOPIC_BROKERS_ALARMS_DEACTIVATE,
+        name,
+        message,
+    )
+    .await;
+}
+
+async fn report_alarm_event<S>(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    topic: &str,
+    name: &str,
+    message: String,
+) where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    let event_data = SystemTopicAlarmEventMessage {
+        name: name.to_string(),
+        message,
+        node: get_local_ip(),
+        ts: now_mills(),
+    };
+    match serde_json::to_string(&event_data) {
+        Ok(data) => {
+            report_system_data(
+                client_pool,
+                metadata_cache,
+                message_storage_adapter,
+                replace_topic_name(topic.to_string()),
+                data,
+            )
+            .await;
+        }
+        Err(e) => {
+            error!("Failed to serialize alarm event {}, error: {}", name, e);
+        }
+    }
+}
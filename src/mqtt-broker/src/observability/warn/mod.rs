@@ -11,3 +11,425 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use common_base::tools::{now_second, unique_id};
+use dashmap::DashMap;
+use grpc_clients::pool::ClientPool;
+use log::{debug, error, warn};
+use metadata_struct::mqtt::alarm::MqttAlarm;
+use metadata_struct::mqtt::cluster::MqttClusterDynamicAlarm;
+use storage_adapter::storage::StorageAdapter;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+use crate::handler::cache::CacheManager;
+use crate::observability::system_topic::warn::{
+    st_report_alarm_activate, st_report_alarm_deactivate,
+};
+use crate::storage::alarm::AlarmStorage;
+
+pub const ALARM_HIGH_CONNECTION_COUNT: &str = "high_connection_count";
+pub const ALARM_STORAGE_WRITE_FAILURE: &str = "storage_write_failure";
+pub const ALARM_CONNECTOR_DOWN: &str = "connector_down";
+pub const ALARM_FLAPPING_DETECT_BAN: &str = "flapping_detect_ban";
+pub const ALARM_SCHEMA_VALIDATION_SURGE: &str = "schema_validation_surge";
+pub const ALARM_SLOW_SUBSCRIPTION: &str = "slow_subscription";
+
+// Event counters since the last alarm check.
+static STORAGE_WRITE_FAILURES: AtomicU64 = AtomicU64::new(0);
+static FLAPPING_DETECT_BANS: AtomicU64 = AtomicU64::new(0);
+static SCHEMA_VALIDATION_FAILURES: AtomicU64 = AtomicU64::new(0);
+static SLOW_SUBSCRIPTIONS: AtomicU64 = AtomicU64::new(0);
+
+// Connector name -> last error.
+static DOWN_CONNECTORS: LazyLock<DashMap<String, String>> = LazyLock::new(DashMap::new);
+
+// Alarm name -> (triggered, message) from the last system monitor check.
+static SYSMON_RESULTS: LazyLock<DashMap<&'static str, (bool, String)>> =
+    LazyLock::new(DashMap::new);
+
+pub fn record_storage_write_failure() {
+    STORAGE_WRITE_FAILURES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_flapping_detect_ban() {
+    FLAPPING_DETECT_BANS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_schema_validation_failure() {
+    SCHEMA_VALIDATION_FAILURES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_slow_subscription() {
+    SLOW_SUBSCRIPTIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_connector_down(connector_name: &str, reason: String) {
+    DOWN_CONNECTORS.insert(connector_name.to_string(), reason);
+}
+
+pub fn record_connector_up(connector_name: &str) {
+    DOWN_CONNECTORS.remove(connector_name);
+}
+
+pub fn record_sysmon_result(name: &'static str, triggered: bool, message: String) {
+    SYSMON_RESULTS.insert(name, (triggered, message));
+}
+
+#[derive(Debug, Default)]
+struct AlarmSignals {
+    connections: u64,
+    storage_write_failures: u64,
+    flapping_detect_bans: u64,
+    schema_validation_failures: u64,
+    slow_subscriptions: u64,
+    down_connectors: Vec<(String, String)>,
+    sysmon_results: Vec<(&'static str, bool, String)>,
+}
+
+impl AlarmSignals {
+    fn take(connections: u64) -> Self {
+        AlarmSignals {
+            connections,
+            storage_write_failures: STORAGE_WRITE_FAILURES.swap(0, Ordering::Relaxed),
+            flapping_detect_bans: FLAPPING_DETECT_BANS.swap(0, Ordering::Relaxed),
+            schema_validation_failures: SCHEMA_VALIDATION_FAILURES.swap(0, Ordering::Relaxed),
+            slow_subscriptions: SLOW_SUBSCRIPTIONS.swap(0, Ordering::Relaxed),
+            down_connectors: DOWN_CONNECTORS
+                .iter()
+                .map(|raw| (raw.key().clone(), raw.value().clone()))
+                .collect(),
+            sysmon_results: SYSMON_RESULTS
+                .iter()
+                .map(|raw| (*raw.key(), raw.value().0, raw.value().1.clone()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct AlarmCheck {
+    name: &'static str,
+    resource: String,
+    triggered: bool,
+    message: String,
+}
+
+impl AlarmCheck {
+    fn threshold(name: &'static str, value: u64, watermark: u64, what: &str) -> Self {
+        AlarmCheck {
+            name,
+            resource: String::new(),
+            // A watermark of 0 disables the alarm.
+            triggered: watermark > 0 && value >= watermark,
+            message: format!("{} {}, watermark {}", what, value, watermark),
+        }
+    }
+}
+
+fn evaluate(
+    config: &MqttClusterDynamicAlarm,
+    signals: &AlarmSignals,
+    active: &HashMap<(String, String), MqttAlarm>,
+) -> Vec<AlarmCheck> {
+    let mut results = vec![
+        AlarmCheck::threshold(
+            ALARM_HIGH_CONNECTION_COUNT,
+            signals.connections,
+            config.connection_high_watermark,
+            "connections",
+        ),
+        AlarmCheck::threshold(
+            ALARM_STORAGE_WRITE_FAILURE,
+            signals.storage_write_failures,
+            config.storage_write_failures,
+            "storage write failures",
+        ),
+        AlarmCheck::threshold(
+            ALARM_FLAPPING_DETECT_BAN,
+            signals.flapping_detect_bans,
+            config.flapping_detect_bans,
+            "flapping detect bans",
+        ),
+        AlarmCheck::threshold(
+            ALARM_SCHEMA_VALIDATION_SURGE,
+            signals.schema_validation_failures,
+            config.schema_validation_failures,
+            "schema validation failures",
+        ),
+        AlarmCheck::threshold(
+            ALARM_SLOW_SUBSCRIPTION,
+            signals.slow_subscriptions,
+            config.slow_subscriptions,
+            "slow subscriptions",
+        ),
+    ];
+
+    for (connector_name, reason) in signals.down_connectors.iter() {
+        results.push(AlarmCheck {
+            name: ALARM_CONNECTOR_DOWN,
+            resource: connector_name.clone(),
+            triggered: true,
+            message: format!("connector {} is down, {}", connector_name, reason),
+        });
+    }
+
+    for (name, triggered, message) in signals.sysmon_results.iter() {
+        results.push(AlarmCheck {
+            name,
+            resource: String::new(),
+            triggered: *triggered,
+            message: message.clone(),
+        });
+    }
+
+    for (name, resource) in active.keys() {
+        if name == ALARM_CONNECTOR_DOWN
+            && !signals.down_connectors.iter().any(|(c, _)| c == resource)
+        {
+            results.push(AlarmCheck {
+                name: ALARM_CONNECTOR_DOWN,
+                resource: resource.clone(),
+                triggered: false,
+                message: format!("connector {} is running", resource),
+            });
+        }
+    }
+    results
+}
+
+pub struct AlarmManager<S> {
+    cache_manager: Arc<CacheManager>,
+    message_storage_adapter: Arc<S>,
+    client_pool: Arc<ClientPool>,
+    // (alarm name, resource) -> active alarm
+    active: HashMap<(String, String), MqttAlarm>,
+}
+
+impl<S> AlarmManager<S>
+where
+    S: StorageAdapter + Clone + Send + Sync + 'static,
+{
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        message_storage_adapter: Arc<S>,
+        client_pool: Arc<ClientPool>,
+    ) -> Self {
+        AlarmManager {
+            cache_manager,
+            message_storage_adapter,
+            client_pool,
+            active: HashMap::new(),
+        }
+    }
+
+    pub async fn start(&mut self, stop_send: broadcast::Sender<bool>) {
+        self.load_active().await;
+        let mut stop_rx = stop_send.subscribe();
+        loop {
+            let config = self.cache_manager.get_alarm_config();
+            select! {
+                val = stop_rx.recv() => {
+                    if let Ok(flag) = val {
+                        if flag {
+                            debug!("Alarm thread stopped successfully");
+                            break;
+                        }
+                    }
+                }
+                _ = sleep(Duration::from_secs(config.check_interval_secs.max(1))) => {
+                    if config.enable {
+                        self.check(&config).await;
+                    }
+                }
+            }
+        }
+    }
+
+    // Alarms this broker left open before a restart are taken over, so they are
+    // closed once their condition clears instead of staying active forever.
+    async fn load_active(&mut self) {
+        let conf = broker_mqtt_conf();
+        let storage = AlarmStorage::new(self.client_pool.clone());
+        let alarms = match storage.list_alarm().await {
+            Ok(alarms) => alarms,
+            Err(e) => {
+                error!(
+                    "Failed to load active alarms from placement center, error: {}",
+                    e
+                );
+                return;
+            }
+        };
+        for alarm in alarms {
+            if alarm.is_active()
+                && alarm.cluster_name == conf.cluster_name
+                && alarm.broker_id == conf.broker_id
+            {
+                self.active
+                    .insert((alarm.name.clone(), alarm.resource.clone()), alarm);
+            }
+        }
+    }
+
+    async fn check(&mut self, config: &MqttClusterDynamicAlarm) {
+        let signals = AlarmSignals::take(self.cache_manager.connection_info.len() as u64);
+        let results = evaluate(config, &signals, &self.active);
+        let stale: Vec<(String, String)> = self
+            .active
+            .keys()
+            .filter(|(name, resource)| {
+                !results
+                    .iter()
+                    .any(|r| r.name == name.as_str() && &r.resource == resource)
+            })
+            .cloned()
+            .collect();
+
+        for result in results {
+            let key = (result.name.to_string(), result.resource.clone());
+            if result.triggered && !self.active.contains_key(&key) {
+                self.activate(key, result.message).await;
+            } else if !result.triggered && self.active.contains_key(&key) {
+                self.deactivate(key, result.message).await;
+            }
+        }
+
+        // A loaded alarm whose condition is no longer checked, e.g. the system
+        // monitor was turned off, is closed here.
+        for key in stale {
+            let message = format!("alarm {} is no longer checked", key.0);
+            self.deactivate(key, message).await;
+        }
+    }
+
+    async fn activate(&mut self, key: (String, String), message: String) {
+        let conf = broker_mqtt_conf();
+        warn!("Alarm {} activated, {}", key.0, message);
+        let alarm = MqttAlarm {
+            cluster_name: conf.cluster_name.clone(),
+            broker_id: conf.broker_id,
+            alarm_id: unique_id(),
+            name: key.0.clone(),
+            resource: key.1.clone(),
+            message: message.clone(),
+            activate_at: now_second(),
+            deactivate_at: 0,
+        };
+        self.save_history(&alarm).await;
+        st_report_alarm_activate(
+            &self.client_pool,
+            &self.cache_manager,
+            &self.message_storage_adapter,
+            &key.0,
+            message,
+        )
+        .await;
+        self.active.insert(key, alarm);
+    }
+
+    async fn deactivate(&mut self, key: (String, String), message: String) {
+        let Some(mut alarm) = self.active.remove(&key) else {
+            return;
+        };
+        alarm.deactivate_at = now_second();
+        self.save_history(&alarm).await;
+        st_report_alarm_deactivate(
+            &self.client_pool,
+            &self.cache_manager,
+            &self.message_storage_adapter,
+            &key.0,
+            message,
+        )
+        .await;
+    }
+
+    async fn save_history(&self, alarm: &MqttAlarm) {
+        let storage = AlarmStorage::new(self.client_pool.clone());
+        if let Err(e) = storage.save_alarm(alarm).await {
+            error!(
+                "Failed to save alarm {} to placement center, error: {}",
+                alarm.name, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> MqttClusterDynamicAlarm {
+        MqttClusterDynamicAlarm {
+            enable: true,
+            check_interval_secs: 30,
+            connection_high_watermark: 100,
+            storage_write_failures: 10,
+            flapping_detect_bans: 0,
+            schema_validation_failures: 100,
+            slow_subscriptions: 100,
+        }
+    }
+
+    fn triggered(results: &[AlarmCheck], name: &str) -> Vec<String> {
+        results
+            .iter()
+            .filter(|r| r.name == name && r.triggered)
+            .map(|r| r.resource.clone())
+            .collect()
+    }
+
+    #[test]
+    fn evaluate_threshold_test() {
+        let signals = AlarmSignals {
+            connections: 100,
+            storage_write_failures: 9,
+            flapping_detect_bans: 1000,
+            ..Default::default()
+        };
+        let results = evaluate(&test_config(), &signals, &HashMap::new());
+        assert_eq!(triggered(&results, ALARM_HIGH_CONNECTION_COUNT).len(), 1);
+        assert!(triggered(&results, ALARM_STORAGE_WRITE_FAILURE).is_empty());
+        assert!(triggered(&results, ALARM_FLAPPING_DETECT_BAN).is_empty());
+    }
+
+    #[test]
+    fn evaluate_connector_test() {
+        let signals = AlarmSignals {
+            down_connectors: vec![("c1".to_string(), "timeout".to_string())],
+            ..Default::default()
+        };
+        let mut active = HashMap::new();
+        active.insert(
+            (ALARM_CONNECTOR_DOWN.to_string(), "c2".to_string()),
+            MqttAlarm::default(),
+        );
+        let results = evaluate(&test_config(), &signals, &active);
+        assert_eq!(triggered(&results, ALARM_CONNECTOR_DOWN), vec!["c1"]);
+        assert!(results
+            .iter()
+            .any(|r| r.name == ALARM_CONNECTOR_DOWN && r.resource == "c2" && !r.triggered));
+    }
+
+    #[test]
+    fn evaluate_sysmon_test() {
+        let signals = AlarmSignals {
+            sysmon_results: vec![
+                ("high_cpu_usage", true, "cpu usage 95.00%".to_string()),
+                ("busy_port", false, "network queues []".to_string()),
+            ],
+            ..Default::default()
+        };
+        let results = evaluate(&test_config(), &signals, &HashMap::new());
+        assert_eq!(triggered(&results, "high_cpu_usage").len(), 1);
+        assert!(triggered(&results, "busy_port").is_empty());
+        assert!(results.iter().any(|r| r.name == "busy_port"));
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+
+use serde_json::{Map, Number, Value};
+
+use super::sql::{BinaryOp, Expr, RuleStatement, SelectField, UnaryOp};
+use crate::handler::error::MqttBrokerError;
+use crate::subscribe::sub_common::path_regex_match;
+
+pub fn match_topic(statement: &RuleStatement, topic_name: &str) -> bool {
+    statement
+        .from
+        .iter()
+        .any(|filter| path_regex_match(topic_name, filter))
+}
+
+// Returns the selected fields when the WHERE clause holds, None otherwise.
+pub fn eval_statement(
+    statement: &RuleStatement,
+    context: &Value,
+) -> Result<Option<Value>, MqttBrokerError> {
+    if let Some(condition) = &statement.condition {
+        if !truthy(&eval_expr(condition, context)?)? {
+            return Ok(None);
+        }
+    }
+
+    let mut output = Map::new();
+    for field in &statement.fields {
+        match field {
+            SelectField::All => {
+                if let Value::Object(map) = context {
+                    output.extend(map.clone());
+                }
+            }
+            SelectField::Expr { expr, alias } => {
+                output.insert(alias.clone(), eval_expr(expr, context)?);
+            }
+        }
+    }
+    Ok(Some(Value::Object(output)))
+}
+
+fn eval_expr(expr: &Expr, context: &Value) -> Result<Value, MqttBrokerError> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Path(path) => {
+            let mut current = context;
+            for segment in path {
+                match current.get(segment) {
+                    Some(value) => current = value,
+                    None => return Ok(Value::Null),
+                }
+            }
+            Ok(current.clone())
+        }
+        Expr::Unary(UnaryOp::Not, expr) => Ok(Value::Bool(!truthy(&eval_expr(expr, context)?)?)),
+        Expr::Unary(UnaryOp::Neg, expr) => {
+            let value = eval_expr(expr, context)?;
+            arithmetic(&Value::from(0), BinaryOp::Sub, &value)
+        }
+        Expr::Binary(left, BinaryOp::And, right) => {
+            if !truthy(&eval_expr(left, context)?)? {
+                return Ok(Value::Bool(false));
+            }
+            Ok(Value::Bool(truthy(&eval_expr(right, context)?)?))
+        }
+        Expr::Binary(left, BinaryOp::Or, right) => {
+            if truthy(&eval_expr(left, context)?)? {
+                return Ok(Value::Bool(true));
+            }
+            Ok(Value::Bool(truthy(&eval_expr(right, context)?)?))
+        }
+        Expr::Binary(left, op, right) => {
+            let left = eval_expr(left, context)?;
+            let right = eval_expr(right, context)?;
+            match op {
+                BinaryOp::Eq => Ok(Value::Bool(values_equal(&left, &right))),
+                BinaryOp::NotEq => Ok(Value::Bool(!values_equal(&left, &right))),
+                BinaryOp::Gt | BinaryOp::Gte | BinaryOp::Lt | BinaryOp::Lte => {
+                    let result = match compare(&left, &right) {
+                        Some(ordering) => match op {
+                            BinaryOp::Gt => ordering == Ordering::Greater,
+                            BinaryOp::Gte => ordering != Ordering::Less,
+                            BinaryOp::Lt => ordering == Ordering::Less,
+                            _ => ordering != Ordering::Greater,
+                        },
+                        None => false,
+                    };
+                    Ok(Value::Bool(result))
+                }
+                _ => arithmetic(&left, *op, &right),
+            }
+        }
+    }
+}
+
+fn truthy(value: &Value) -> Result<bool, MqttBrokerError> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        Value::Null => Ok(false),
+        other => Err(MqttBrokerError::RuleEvalError(format!(
+            "expected a boolean, got {}",
+            other
+        ))),
+    }
+}
+
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(l), Some(r)) => l == r,
+        _ => left == right,
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64()?.partial_cmp(&r.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+fn arithmetic(left: &Value, op: BinaryOp, right: &Value) -> Result<Value, MqttBrokerError> {
+    if let (BinaryOp::Add, Value::String(l), Value::String(r)) = (op, left, right) {
+        return Ok(Value::String(format!("{}{}", l, r)));
+    }
+
+    let (Some(l), Some(r)) = (left.as_f64(), right.as_f64()) else {
+        return Err(MqttBrokerError::RuleEvalError(format!(
+            "cannot apply {:?} to {} and {}",
+            op, left, right
+        )));
+    };
+
+    if let (Some(l), Some(r)) = (left.as_i64(), right.as_i64()) {
+        let result = match op {
+            BinaryOp::Add => l.checked_add(r),
+            BinaryOp::Sub => l.checked_sub(r),
+            BinaryOp::Mul => l.checked_mul(r),
+            _ => None,
+        };
+        if let Some(result) = result {
+            return Ok(Value::from(result));
+        }
+    }
+
+    let result = match op {
+        BinaryOp::Add => l + r,
+        BinaryOp::Sub => l - r,
+        BinaryOp::Mul => l * r,
+        BinaryOp::Div => {
+            if r == 0.0 {
+                return Err(MqttBrokerError::RuleEvalError(
+                    "division by zero".to_string(),
+                ));
+            }
+            l / r
+        }
+        _ => unreachable!("non arithmetic operator {:?}", op),
+    };
+    Number::from_f64(result)
+        .map(Value::Number)
+        .ok_or_else(|| MqttBrokerError::RuleEvalError(format!("invalid number {}", result)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::rule_engine::sql::parse_rule_sql;
+
+    fn context() -> Value {
+        json!({
+            "payload": {"temp": 42.5, "unit": "C", "count": 3},
+            "clientid": "c1",
+            "topic": "sensors/room1",
+            "qos": 1,
+            "retain": false,
+            "user_properties": {"site": "a"},
+        })
+    }
+
+    #[test]
+    fn eval_select_test() {
+        let statement = parse_rule_sql(
+            r#"SELECT payload.temp AS t, clientid FROM "sensors/#" WHERE payload.temp > 40"#,
+        )
+        .unwrap();
+        assert!(match_topic(&statement, "sensors/room1"));
+        assert!(!match_topic(&statement, "other/room1"));
+
+        let result = eval_statement(&statement, &context()).unwrap();
+        assert_eq!(result, Some(json!({"t": 42.5, "clientid": "c1"})));
+
+        let statement =
+            parse_rule_sql(r#"SELECT * FROM "sensors/#" WHERE payload.temp < 40"#).unwrap();
+        assert_eq!(eval_statement(&statement, &context()).unwrap(), None);
+    }
+
+    #[test]
+    fn eval_expr_test() {
+        let statement = parse_rule_sql(
+            r##"SELECT payload.count * 2 + 1 AS n, -payload.count AS neg, payload.unit + 'elsius' AS u,
+               payload.missing AS m FROM "#"
+               WHERE (qos = 1.0 OR retain) AND NOT user_properties.site != 'a'"##,
+        )
+        .unwrap();
+        let result = eval_statement(&statement, &context()).unwrap();
+        assert_eq!(
+            result,
+            Some(json!({"n": 7, "neg": -3, "u": "Celsius", "m": null}))
+        );
+
+        let statement = parse_rule_sql(r##"SELECT payload.count / 0 AS x FROM "#""##).unwrap();
+        assert!(eval_statement(&statement, &context()).is_err());
+
+        let statement = parse_rule_sql(r##"SELECT * FROM "#" WHERE payload.unit"##).unwrap();
+        assert!(eval_statement(&statement, &context()).is_err());
+    }
+}
@@ -0,0 +1,474 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use common_base::tools::now_mills;
+use dashmap::DashSet;
+use grpc_clients::pool::ClientPool;
+use log::{error, info, warn};
+use metadata_struct::mqtt::message::MqttMessage;
+use metadata_struct::mqtt::rule::{MqttRule, MqttRuleAction};
+use protocol::mqtt::common::{qos, Publish, PublishProperties};
+use serde_json::{json, Map, Value};
+use sql::RuleStatement;
+use storage_adapter::storage::{ShardInfo, StorageAdapter};
+use tokio::select;
+use tokio::sync::{broadcast, mpsc, Mutex};
+
+use crate::bridge::manager::ConnectorManager;
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::topic::try_init_topic;
+use crate::observability::metrics::rule::{incr_rule_failed_counter, incr_rule_matched_counter};
+use crate::storage::message::{cluster_name, MessageStorage};
+
+pub mod eval;
+pub mod sql;
+
+const RULE_EVENT_QUEUE_SIZE: usize = 10000;
+
+#[derive(Clone, Debug)]
+pub struct RuleRuntime {
+    pub rule: MqttRule,
+    pub statement: RuleStatement,
+}
+
+pub struct RuleEvent {
+    pub client_id: String,
+    pub topic_name: String,
+    pub publish: Publish,
+    pub publish_properties: Option<PublishProperties>,
+    // time the message was published, in milliseconds
+    pub timestamp: u64,
+}
+
+// Results of a rule with connector actions are written to a shard of the rule, which
+// the target connectors read next to their own topic.
+pub fn rule_connector_shard_name(rule_name: &str) -> String {
+    format!("$rule_{}", rule_name)
+}
+
+pub struct RuleEngine<S> {
+    cache_manager: Arc<CacheManager>,
+    connector_manager: Arc<ConnectorManager>,
+    message_storage_adapter: Arc<S>,
+    client_pool: Arc<ClientPool>,
+    event_sender: mpsc::Sender<RuleEvent>,
+    event_receiver: Mutex<Option<mpsc::Receiver<RuleEvent>>>,
+    rule_shards: DashSet<String>,
+}
+
+impl<S> RuleEngine<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        connector_manager: Arc<ConnectorManager>,
+        message_storage_adapter: Arc<S>,
+        client_pool: Arc<ClientPool>,
+    ) -> Self {
+        let (event_sender, event_receiver) = mpsc::channel(RULE_EVENT_QUEUE_SIZE);
+        RuleEngine {
+            cache_manager,
+            connector_manager,
+            message_storage_adapter,
+            client_pool,
+            event_sender,
+            event_receiver: Mutex::new(Some(event_receiver)),
+            rule_shards: DashSet::new(),
+        }
+    }
+
+    // Queues a published message for rule evaluation so that the publisher is
+    // acknowledged without waiting for the rule actions. The message is skipped
+    // when the queue is full.
+    pub fn submit(
+        &self,
+        client_id: &str,
+        topic_name: &str,
+        publish: &Publish,
+        publish_properties: &Option<PublishProperties>,
+    ) {
+        if self.cache_manager.rule_info.is_empty() {
+            return;
+        }
+
+        let event = RuleEvent {
+            client_id: client_id.to_string(),
+            topic_name: topic_name.to_string(),
+            publish: publish.clone(),
+            publish_properties: publish_properties.clone(),
+            timestamp: now_mills() as u64,
+        };
+        if let Err(e) = self.event_sender.try_send(event) {
+            warn!(
+                "rule engine queue is full, skipping message on topic {}, error: {}",
+                topic_name, e
+            );
+        }
+    }
+
+    pub async fn start(&self, stop_send: broadcast::Sender<bool>) {
+        let Some(mut event_receiver) = self.event_receiver.lock().await.take() else {
+            error!("{}", "rule engine is already running");
+            return;
+        };
+        let mut stop_rx = stop_send.subscribe();
+        loop {
+            select! {
+                val = stop_rx.recv() => {
+                    if let Ok(true) = val {
+                        info!("{}", "Rule engine thread stopped successfully.");
+                        break;
+                    }
+                }
+                val = event_receiver.recv() => {
+                    let Some(event) = val else {
+                        break;
+                    };
+                    self.process(&event).await;
+                }
+            }
+        }
+    }
+
+    pub async fn process(&self, event: &RuleEvent) {
+        let rules: Vec<RuleRuntime> = self
+            .cache_manager
+            .rule_info
+            .iter()
+            .filter(|raw| raw.rule.enable && eval::match_topic(&raw.statement, &event.topic_name))
+            .map(|raw| raw.value().clone())
+            .collect();
+        if rules.is_empty() {
+            return;
+        }
+
+        let context = build_rule_context(event);
+        for runtime in rules {
+            let rule_name = runtime.rule.rule_name.clone();
+            let output = match eval::eval_statement(&runtime.statement, &context) {
+                Ok(Some(output)) => output,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("rule {} evaluation failed, error: {}", rule_name, e);
+                    incr_rule_failed_counter(rule_name);
+                    continue;
+                }
+            };
+
+            incr_rule_matched_counter(rule_name.clone());
+            let mut has_connector = false;
+            for action in runtime.rule.actions.iter() {
+                if let Err(e) = self.execute_action(event, action, &output).await {
+                    error!(
+                        "rule {} action {:?} failed, error: {}",
+                        rule_name, action, e
+                    );
+                    incr_rule_failed_counter(rule_name.clone());
+                    continue;
+                }
+                if matches!(action, MqttRuleAction::Connector { .. }) {
+                    has_connector = true;
+                }
+            }
+
+            // the result is stored once and read by every target connector
+            if has_connector {
+                if let Err(e) = self
+                    .write_connector_result(event, &rule_name, &output)
+                    .await
+                {
+                    error!(
+                        "rule {} failed to write the result for its connectors, error: {}",
+                        rule_name, e
+                    );
+                    incr_rule_failed_counter(rule_name.clone());
+                }
+            }
+        }
+    }
+
+    // Results are written straight to storage so that they do not re-enter the rule engine.
+    async fn execute_action(
+        &self,
+        event: &RuleEvent,
+        action: &MqttRuleAction,
+        output: &Value,
+    ) -> Result<(), MqttBrokerError> {
+        match action {
+            MqttRuleAction::Republish { topic, qos } => {
+                let mqtt_topic = try_init_topic(
+                    topic,
+                    &self.cache_manager,
+                    &self.message_storage_adapter,
+                    &self.client_pool,
+                )
+                .await?;
+                let message_storage =
+                    MessageStorage::new_by_topic(self.message_storage_adapter.clone(), topic);
+                append_rule_result(
+                    &message_storage,
+                    &event.client_id,
+                    topic,
+                    &mqtt_topic.topic_id,
+                    *qos,
+                    output,
+                )
+                .await
+            }
+            MqttRuleAction::Connector { connector_name } => {
+                if self
+                    .connector_manager
+                    .get_connector(connector_name)
+                    .is_none()
+                {
+                    return Err(MqttBrokerError::ConnectorNotFound(
+                        connector_name.to_owned(),
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn write_connector_result(
+        &self,
+        event: &RuleEvent,
+        rule_name: &str,
+        output: &Value,
+    ) -> Result<(), MqttBrokerError> {
+        let shard_name = rule_connector_shard_name(rule_name);
+        if !self.rule_shards.contains(&shard_name) {
+            let namespace = cluster_name();
+            let list = self
+                .message_storage_adapter
+                .list_shard(namespace.clone(), shard_name.clone())
+                .await?;
+            if list.is_empty() {
+                self.message_storage_adapter
+                    .create_shard(ShardInfo {
+                        namespace,
+                        shard_name: shard_name.clone(),
+                        replica_num: 1,
+                    })
+                    .await?;
+            }
+            self.rule_shards.insert(shard_name.clone());
+        }
+
+        let message_storage = MessageStorage::new(self.message_storage_adapter.clone());
+        append_rule_result(
+            &message_storage,
+            &event.client_id,
+            &event.topic_name,
+            &shard_name,
+            0,
+            output,
+        )
+        .await
+    }
+}
+
+async fn append_rule_result<S>(
+    message_storage: &MessageStorage<S>,
+    client_id: &str,
+    topic_name: &str,
+    shard_name: &str,
+    qos_num: u8,
+    output: &Value,
+) -> Result<(), MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let publish = Publish {
+        dup: false,
+        qos: qos(qos_num).unwrap_or_default(),
+        pkid: 0,
+        retain: false,
+        topic: Bytes::from(topic_name.to_owned()),
+        payload: Bytes::from(serde_json::to_vec(output)?),
+    };
+    if let Some(record) = MqttMessage::build_record(client_id, &publish, &None, 0) {
+        message_storage
+            .append_topic_message(shard_name, vec![record])
+            .await?;
+    }
+    Ok(())
+}
+
+pub fn build_rule_context(event: &RuleEvent) -> Value {
+    let publish = &event.publish;
+    let payload = serde_json::from_slice::<Value>(&publish.payload)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&publish.payload).to_string()));
+
+    let mut user_properties = Map::new();
+    if let Some(properties) = &event.publish_properties {
+        for (key, value) in properties.user_properties.iter() {
+            user_properties.insert(key.clone(), Value::String(value.clone()));
+        }
+    }
+
+    json!({
+        "payload": payload,
+        "clientid": event.client_id,
+        "topic": event.topic_name,
+        "qos": publish.qos as u8,
+        "retain": publish.retain,
+        "timestamp": event.timestamp,
+        "user_properties": user_properties,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use common_base::config::broker_mqtt::init_broker_mqtt_conf_by_path;
+    use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+    use metadata_struct::mqtt::rule::MqttRule;
+    use storage_adapter::memory::MemoryStorageAdapter;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn submit_test() {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(client_pool.clone(), "test".to_string()));
+        let rule_engine = RuleEngine::new(
+            cache_manager.clone(),
+            Arc::new(ConnectorManager::new()),
+            Arc::new(MemoryStorageAdapter::new()),
+            client_pool,
+        );
+        let publish = Publish {
+            payload: Bytes::from(r#"{"temp": 41}"#),
+            ..Default::default()
+        };
+
+        rule_engine.submit("c1", "sensors/1", &publish, &None);
+        let mut event_receiver = rule_engine.event_receiver.lock().await.take().unwrap();
+        assert!(event_receiver.try_recv().is_err());
+
+        cache_manager.add_rule(MqttRule {
+            rule_name: "r1".to_string(),
+            sql: r#"SELECT payload.temp FROM "sensors/#""#.to_string(),
+            enable: true,
+            ..Default::default()
+        });
+        rule_engine.submit("c1", "sensors/1", &publish, &None);
+        let event = event_receiver.try_recv().unwrap();
+        assert_eq!(event.client_id, "c1");
+        assert_eq!(event.topic_name, "sensors/1");
+        assert_eq!(event.publish.payload, publish.payload);
+        assert!(event.timestamp > 0);
+    }
+
+    #[tokio::test]
+    async fn connector_action_test() {
+        let path = format!(
+            "{}/../../config/mqtt-server.toml",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        init_broker_mqtt_conf_by_path(&path);
+
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(client_pool.clone(), "test".to_string()));
+        let connector_manager = Arc::new(ConnectorManager::new());
+        let storage_adapter = Arc::new(MemoryStorageAdapter::new());
+        let rule_engine = RuleEngine::new(
+            cache_manager.clone(),
+            connector_manager.clone(),
+            storage_adapter.clone(),
+            client_pool,
+        );
+        connector_manager.add_connector(&MQTTConnector {
+            connector_name: "k1".to_string(),
+            topic_id: "connector-topic".to_string(),
+            ..Default::default()
+        });
+        cache_manager.add_rule(MqttRule {
+            rule_name: "r1".to_string(),
+            sql: r#"SELECT payload.temp AS t FROM "sensors/#""#.to_string(),
+            enable: true,
+            actions: vec![MqttRuleAction::Connector {
+                connector_name: "k1".to_string(),
+            }],
+            ..Default::default()
+        });
+
+        let event = RuleEvent {
+            client_id: "c1".to_string(),
+            topic_name: "sensors/1".to_string(),
+            publish: Publish {
+                payload: Bytes::from(r#"{"temp": 41}"#),
+                ..Default::default()
+            },
+            publish_properties: None,
+            timestamp: 1000,
+        };
+        rule_engine.process(&event).await;
+
+        // the result goes to the rule shard, the connector topic is left alone
+        let message_storage = MessageStorage::new(storage_adapter);
+        let records = message_storage
+            .read_topic_message(&rule_connector_shard_name("r1"), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        let message = MqttMessage::decode_record(records[0].clone()).unwrap();
+        assert_eq!(message.topic, Bytes::from("sensors/1"));
+        assert_eq!(message.payload, Bytes::from(r#"{"t":41}"#));
+
+        let records = message_storage
+            .read_topic_message("connector-topic", 0, 10)
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn build_rule_context_test() {
+        let publish = Publish {
+            payload: Bytes::from(r#"{"temp": 41}"#),
+            ..Default::default()
+        };
+        let properties = Some(PublishProperties {
+            user_properties: vec![("site".to_string(), "a".to_string())],
+            ..Default::default()
+        });
+        let mut event = RuleEvent {
+            client_id: "c1".to_string(),
+            topic_name: "sensors/1".to_string(),
+            publish,
+            publish_properties: properties,
+            timestamp: 1000,
+        };
+        let context = build_rule_context(&event);
+        assert_eq!(context["payload"]["temp"], 41);
+        assert_eq!(context["clientid"], "c1");
+        assert_eq!(context["topic"], "sensors/1");
+        assert_eq!(context["user_properties"]["site"], "a");
+        assert_eq!(context["timestamp"], 1000);
+
+        event.publish = Publish {
+            payload: Bytes::from("plain text"),
+            ..Default::default()
+        };
+        event.publish_properties = None;
+        let context = build_rule_context(&event);
+        assert_eq!(context["payload"], "plain text");
+    }
+}
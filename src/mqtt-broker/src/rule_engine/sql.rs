@@ -0,0 +1,444 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde_json::{Number, Value};
+
+use crate::handler::error::MqttBrokerError;
+
+// SELECT <fields> FROM "<topic filter>"[, ...] [WHERE <expr>]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleStatement {
+    pub fields: Vec<SelectField>,
+    pub from: Vec<String>,
+    pub condition: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectField {
+    All,
+    Expr { expr: Expr, alias: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Path(Vec<String>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(Number),
+    Symbol(&'static str),
+}
+
+pub fn parse_rule_sql(sql: &str) -> Result<RuleStatement, MqttBrokerError> {
+    let tokens = tokenize(sql)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let statement = parser.statement()?;
+    if let Some(token) = parser.peek() {
+        return Err(parse_error(format!("unexpected token {:?}", token)));
+    }
+    Ok(statement)
+}
+
+fn parse_error(msg: String) -> MqttBrokerError {
+    MqttBrokerError::RuleSqlParseError(msg)
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>, MqttBrokerError> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c == '\'' || c == '"' {
+            let start = i + 1;
+            let mut end = start;
+            while end < chars.len() && chars[end] != c {
+                end += 1;
+            }
+            if end == chars.len() {
+                return Err(parse_error("unterminated string literal".to_string()));
+            }
+            tokens.push(Token::Str(chars[start..end].iter().collect()));
+            i = end + 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = if let Ok(n) = text.parse::<i64>() {
+                Number::from(n)
+            } else {
+                text.parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .ok_or_else(|| parse_error(format!("invalid number {}", text)))?
+            };
+            tokens.push(Token::Number(number));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        let (symbol, len) = match (c, chars.get(i + 1).copied()) {
+            ('!', Some('=')) | ('<', Some('>')) => ("!=", 2),
+            ('>', Some('=')) => (">=", 2),
+            ('<', Some('=')) => ("<=", 2),
+            ('=', _) => ("=", 1),
+            ('>', _) => (">", 1),
+            ('<', _) => ("<", 1),
+            ('+', _) => ("+", 1),
+            ('-', _) => ("-", 1),
+            ('*', _) => ("*", 1),
+            ('/', _) => ("/", 1),
+            (',', _) => (",", 1),
+            ('.', _) => (".", 1),
+            ('(', _) => ("(", 1),
+            (')', _) => (")", 1),
+            _ => return Err(parse_error(format!("unexpected character '{}'", c))),
+        };
+        i += len;
+        tokens.push(Token::Symbol(symbol));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), MqttBrokerError> {
+        if self.eat_keyword(keyword) {
+            return Ok(());
+        }
+        Err(parse_error(format!("expected keyword {}", keyword)))
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn statement(&mut self) -> Result<RuleStatement, MqttBrokerError> {
+        self.expect_keyword("SELECT")?;
+        let mut fields = Vec::new();
+        loop {
+            fields.push(self.select_field()?);
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+
+        self.expect_keyword("FROM")?;
+        let mut from = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token::Str(filter)) if !filter.is_empty() => from.push(filter),
+                _ => {
+                    return Err(parse_error(
+                        "FROM expects a quoted topic filter".to_string(),
+                    ))
+                }
+            }
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+
+        let condition = if self.eat_keyword("WHERE") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        Ok(RuleStatement {
+            fields,
+            from,
+            condition,
+        })
+    }
+
+    fn select_field(&mut self) -> Result<SelectField, MqttBrokerError> {
+        if self.eat_symbol("*") {
+            return Ok(SelectField::All);
+        }
+
+        let expr = self.expr()?;
+        let alias = if self.eat_keyword("AS") {
+            match self.next() {
+                Some(Token::Ident(alias)) | Some(Token::Str(alias)) => alias,
+                _ => return Err(parse_error("AS expects an alias name".to_string())),
+            }
+        } else if let Expr::Path(path) = &expr {
+            path.last().cloned().unwrap_or_default()
+        } else {
+            return Err(parse_error(
+                "computed fields must be named with AS".to_string(),
+            ));
+        };
+        Ok(SelectField::Expr { expr, alias })
+    }
+
+    fn expr(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            let right = self.and_expr()?;
+            left = Expr::Binary(Box::new(left), BinaryOp::Or, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            let right = self.not_expr()?;
+            left = Expr::Binary(Box::new(left), BinaryOp::And, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, MqttBrokerError> {
+        if self.eat_keyword("NOT") {
+            let expr = self.not_expr()?;
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(expr)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, MqttBrokerError> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => BinaryOp::Eq,
+            Some(Token::Symbol("!=")) => BinaryOp::NotEq,
+            Some(Token::Symbol(">")) => BinaryOp::Gt,
+            Some(Token::Symbol(">=")) => BinaryOp::Gte,
+            Some(Token::Symbol("<")) => BinaryOp::Lt,
+            Some(Token::Symbol("<=")) => BinaryOp::Lte,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(Expr::Binary(Box::new(left), op, Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                BinaryOp::Add
+            } else if self.eat_symbol("-") {
+                BinaryOp::Sub
+            } else {
+                return Ok(left);
+            };
+            let right = self.multiplicative()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                BinaryOp::Mul
+            } else if self.eat_symbol("/") {
+                BinaryOp::Div
+            } else {
+                return Ok(left);
+            };
+            let right = self.unary()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, MqttBrokerError> {
+        if self.eat_symbol("-") {
+            let expr = self.unary()?;
+            return Ok(Expr::Unary(UnaryOp::Neg, Box::new(expr)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, MqttBrokerError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(Value::Number(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Symbol("(")) => {
+                let expr = self.expr()?;
+                if !self.eat_symbol(")") {
+                    return Err(parse_error("missing closing parenthesis".to_string()));
+                }
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) => {
+                if ident.eq_ignore_ascii_case("true") {
+                    return Ok(Expr::Literal(Value::Bool(true)));
+                }
+                if ident.eq_ignore_ascii_case("false") {
+                    return Ok(Expr::Literal(Value::Bool(false)));
+                }
+                if ident.eq_ignore_ascii_case("null") {
+                    return Ok(Expr::Literal(Value::Null));
+                }
+                if is_reserved(&ident) {
+                    return Err(parse_error(format!("unexpected keyword {}", ident)));
+                }
+
+                let mut path = vec![ident];
+                while self.eat_symbol(".") {
+                    match self.next() {
+                        Some(Token::Ident(segment)) => path.push(segment),
+                        _ => return Err(parse_error("invalid field path".to_string())),
+                    }
+                }
+                Ok(Expr::Path(path))
+            }
+            Some(token) => Err(parse_error(format!("unexpected token {:?}", token))),
+            None => Err(parse_error("unexpected end of statement".to_string())),
+        }
+    }
+}
+
+fn is_reserved(ident: &str) -> bool {
+    ["SELECT", "FROM", "WHERE", "AS", "AND", "OR", "NOT"]
+        .iter()
+        .any(|keyword| ident.eq_ignore_ascii_case(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_select_test() {
+        let statement = parse_rule_sql(
+            r#"SELECT payload.temp AS t, clientid FROM "sensors/#" WHERE payload.temp > 40"#,
+        )
+        .unwrap();
+        assert_eq!(statement.from, vec!["sensors/#".to_string()]);
+        assert_eq!(
+            statement.fields,
+            vec![
+                SelectField::Expr {
+                    expr: Expr::Path(vec!["payload".to_string(), "temp".to_string()]),
+                    alias: "t".to_string(),
+                },
+                SelectField::Expr {
+                    expr: Expr::Path(vec!["clientid".to_string()]),
+                    alias: "clientid".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            statement.condition,
+            Some(Expr::Binary(
+                Box::new(Expr::Path(vec!["payload".to_string(), "temp".to_string()])),
+                BinaryOp::Gt,
+                Box::new(Expr::Literal(json!(40))),
+            ))
+        );
+
+        let statement =
+            parse_rule_sql("select * from 'a/+', 'b/#' where not qos <> 0 and retain = false")
+                .unwrap();
+        assert_eq!(statement.fields, vec![SelectField::All]);
+        assert_eq!(statement.from.len(), 2);
+        assert!(matches!(
+            statement.condition,
+            Some(Expr::Binary(_, BinaryOp::And, _))
+        ));
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert!(parse_rule_sql("SELECT FROM \"a\"").is_err());
+        assert!(parse_rule_sql("SELECT * FROM a").is_err());
+        assert!(parse_rule_sql("SELECT payload.a + 1 FROM \"a\"").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a\" WHERE (qos = 1").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a\" WHERE qos = 'x").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a\" extra").is_err());
+    }
+}
@@ -36,6 +36,10 @@
 
 use crate::handler::cache::CacheManager;
 use crate::handler::error::MqttBrokerError;
+use crate::observability::metrics::auth::{
+    metrics_auth_failure, metrics_auth_success, AUTH_METHOD_ANONYMOUS, AUTH_METHOD_PLAINTEXT,
+    AUTH_METHOD_SECRET_FREE,
+};
 use crate::security::acl::auth::is_blacklist;
 use crate::subscribe::sub_common::get_sub_topic_id_list;
 
@@ -148,15 +152,23 @@ pub async fn check_login_auth(
         let cluster = self.cache_manager.get_cluster_info();
 
         if cluster.security.secret_free_login {
+            metrics_auth_success(AUTH_METHOD_SECRET_FREE);
             return Ok(true);
         }
 
         if let Some(info) = login {
-            return self
+            let result = self
                 .plaintext_check_login(&info.username, &info.password)
                 .await;
+            if matches!(result, Ok(true)) {
+                metrics_auth_success(AUTH_METHOD_PLAINTEXT);
+            } else {
+                metrics_auth_failure(AUTH_METHOD_PLAINTEXT);
+            }
+            return result;
         }
 
+        metrics_auth_failure(AUTH_METHOD_ANONYMOUS);
         Ok(false)
     }
 
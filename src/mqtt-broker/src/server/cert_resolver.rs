@@ -0,0 +1,312 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use common_base::config::broker_mqtt::{broker_mqtt_conf, Network};
+use common_base::config::reload::{file_modified_time, subscribe_config_reload};
+use log::{error, info};
+use rustls::server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier};
+use rustls::sign::CertifiedKey;
+use rustls::{InconsistentKeys, RootCertStore, ServerConfig};
+use rustls_pemfile::{certs, private_key};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+use crate::handler::error::MqttBrokerError;
+
+const CERT_FILE_CHECK_INTERVAL_SECS: u64 = 10;
+
+#[derive(Default)]
+struct LoadedCerts {
+    default_cert: Option<Arc<CertifiedKey>>,
+    sni_certs: HashMap<String, Arc<CertifiedKey>>,
+    files: Vec<(String, Option<SystemTime>)>,
+}
+
+/// Certificates shared by the TCPS, WSS and QUIC listeners. Every handshake
+/// asks the resolver for a certificate, so a reload takes effect for new
+/// connections while established ones keep their session.
+#[derive(Default)]
+pub struct TlsCertResolver {
+    certs: RwLock<LoadedCerts>,
+}
+
+impl std::fmt::Debug for TlsCertResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsCertResolver")
+            .field("server_names", &self.server_names())
+            .finish()
+    }
+}
+
+impl TlsCertResolver {
+    pub fn new(network: &Network) -> Result<Self, MqttBrokerError> {
+        let resolver = TlsCertResolver::default();
+        resolver.reload(network)?;
+        Ok(resolver)
+    }
+
+    // All certificates are loaded before any is replaced, a broken file keeps
+    // the previous set in use.
+    pub fn reload(&self, network: &Network) -> Result<(), MqttBrokerError> {
+        let mut loaded = LoadedCerts {
+            default_cert: Some(load_certified_key(&network.tls_cert, &network.tls_key)?),
+            ..Default::default()
+        };
+        loaded.files.push(watch_file(&network.tls_cert));
+        loaded.files.push(watch_file(&network.tls_key));
+
+        for sni_cert in network.tls_sni_certs.iter() {
+            let key = load_certified_key(&sni_cert.tls_cert, &sni_cert.tls_key)?;
+            loaded
+                .sni_certs
+                .insert(sni_cert.server_name.to_lowercase(), key);
+            loaded.files.push(watch_file(&sni_cert.tls_cert));
+            loaded.files.push(watch_file(&sni_cert.tls_key));
+        }
+
+        *self.certs.write().unwrap() = loaded;
+        Ok(())
+    }
+
+    pub fn server_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .certs
+            .read()
+            .unwrap()
+            .sni_certs
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn files_changed(&self) -> bool {
+        self.certs
+            .read()
+            .unwrap()
+            .files
+            .iter()
+            .any(|(path, modified)| file_modified_time(path) != *modified)
+    }
+
+    pub fn server_config(self: &Arc<Self>, alpn_protocols: Vec<Vec<u8>>) -> ServerConfig {
+        let mut config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_cert_resolver(self.clone());
+        config.alpn_protocols = alpn_protocols;
+        config
+    }
+
+    // Client certificates are requested but optional, a certificate that is sent
+    // must be signed by the CA.
+    pub fn server_config_with_client_ca(
+        self: &Arc<Self>,
+        alpn_protocols: Vec<Vec<u8>>,
+        client_ca: &str,
+    ) -> Result<ServerConfig, MqttBrokerError> {
+        if client_ca.is_empty() {
+            return Ok(self.server_config(alpn_protocols));
+        }
+        let load_err = |e: String| MqttBrokerError::TlsCertLoadFailed(client_ca.to_string(), e);
+        let mut roots = RootCertStore::empty();
+        for cert in load_certs(Path::new(client_ca)).map_err(|e| load_err(e.to_string()))? {
+            roots.add(cert).map_err(|e| load_err(e.to_string()))?;
+        }
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+            .allow_unauthenticated()
+            .build()
+            .map_err(|e| load_err(e.to_string()))?;
+
+        let mut config = ServerConfig::builder()
+            .with_client_cert_verifier(verifier)
+            .with_cert_resolver(self.clone());
+        config.alpn_protocols = alpn_protocols;
+        Ok(config)
+    }
+
+    fn resolve_by_name(&self, server_name: Option<&str>) -> Option<Arc<CertifiedKey>> {
+        let certs = self.certs.read().unwrap();
+        if let Some(server_name) = server_name {
+            let server_name = server_name.to_lowercase();
+            if let Some(key) = certs.sni_certs.get(&server_name) {
+                return Some(key.clone());
+            }
+            if let Some((_, parent)) = server_name.split_once('.') {
+                if let Some(key) = certs.sni_certs.get(&format!("*.{}", parent)) {
+                    return Some(key.clone());
+                }
+            }
+        }
+        certs.default_cert.clone()
+    }
+}
+
+impl ResolvesServerCert for TlsCertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.resolve_by_name(client_hello.server_name())
+    }
+}
+
+fn watch_file(path: &str) -> (String, Option<SystemTime>) {
+    (path.to_string(), file_modified_time(path))
+}
+
+pub(crate) fn load_certs(path: &Path) -> io::Result<Vec<CertificateDer<'static>>> {
+    certs(&mut BufReader::new(File::open(path)?)).collect()
+}
+
+pub(crate) fn load_key(path: &Path) -> io::Result<PrivateKeyDer<'static>> {
+    private_key(&mut BufReader::new(File::open(path)?))?.ok_or(io::Error::new(
+        ErrorKind::Other,
+        "no private key found".to_string(),
+    ))
+}
+
+fn load_certified_key(cert: &str, key: &str) -> Result<Arc<CertifiedKey>, MqttBrokerError> {
+    let load_err = |e: String| MqttBrokerError::TlsCertLoadFailed(cert.to_string(), e);
+    let certs = load_certs(Path::new(cert)).map_err(|e| load_err(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(load_err("no certificate found".to_string()));
+    }
+    let key = load_key(Path::new(key)).map_err(|e| load_err(e.to_string()))?;
+    let signing_key = rustls::crypto::ring::sign::any_supported_type(&key)
+        .map_err(|e| load_err(e.to_string()))?;
+
+    let certified_key = CertifiedKey::new(certs, signing_key);
+    match certified_key.keys_match() {
+        Ok(()) | Err(rustls::Error::InconsistentKeys(InconsistentKeys::Unknown)) => {}
+        Err(e) => return Err(load_err(e.to_string())),
+    }
+    Ok(Arc::new(certified_key))
+}
+
+fn reload_from_conf(resolver: &TlsCertResolver) {
+    match resolver.reload(&broker_mqtt_conf().network) {
+        Ok(()) => info!("TLS certificates reloaded"),
+        Err(e) => error!(
+            "Failed to reload TLS certificates, keep using the previous ones. error message:{}",
+            e
+        ),
+    }
+}
+
+// Reloads the certificates when a file on disk changes or the config reload
+// changes which files are used.
+pub async fn start_cert_watcher(
+    resolver: Arc<TlsCertResolver>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let mut stop_rx = stop_send.subscribe();
+    let mut reload_rx = subscribe_config_reload();
+    loop {
+        select! {
+            val = stop_rx.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        break;
+                    }
+                }
+            }
+            val = reload_rx.recv() => {
+                if let Ok(report) = val {
+                    if report.is_applied("network.tls_cert")
+                        || report.is_applied("network.tls_key")
+                        || report.is_applied("network.tls_sni_certs")
+                    {
+                        reload_from_conf(&resolver);
+                    }
+                }
+            }
+            _ = sleep(Duration::from_secs(CERT_FILE_CHECK_INTERVAL_SECS)) => {
+                if resolver.files_changed() {
+                    reload_from_conf(&resolver);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rcgen::generate_simple_self_signed;
+    use rustls::crypto::ring::sign::any_supported_type;
+    use rustls::sign::CertifiedKey;
+    use rustls_pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+
+    use super::{LoadedCerts, TlsCertResolver};
+
+    fn certified_key(name: &str) -> Arc<CertifiedKey> {
+        let cert = generate_simple_self_signed(vec![name.to_string()]).unwrap();
+        let key = PrivatePkcs8KeyDer::from(cert.key_pair.serialize_der());
+        Arc::new(CertifiedKey::new(
+            vec![CertificateDer::from(cert.cert)],
+            any_supported_type(&key.into()).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn resolve_by_name_test() {
+        let default_cert = certified_key("localhost");
+        let tenant_cert = certified_key("tenant.example.com");
+        let wildcard_cert = certified_key("*.example.com");
+
+        let mut loaded = LoadedCerts {
+            default_cert: Some(default_cert.clone()),
+            ..Default::default()
+        };
+        loaded
+            .sni_certs
+            .insert("tenant.example.com".to_string(), tenant_cert.clone());
+        loaded
+            .sni_certs
+            .insert("*.example.com".to_string(), wildcard_cert.clone());
+        let resolver = TlsCertResolver {
+            certs: std::sync::RwLock::new(loaded),
+        };
+
+        let resolved = resolver
+            .resolve_by_name(Some("Tenant.Example.com"))
+            .unwrap();
+        assert!(Arc::ptr_eq(&resolved, &tenant_cert));
+
+        let resolved = resolver.resolve_by_name(Some("other.example.com")).unwrap();
+        assert!(Arc::ptr_eq(&resolved, &wildcard_cert));
+
+        let resolved = resolver.resolve_by_name(Some("a.b.example.com")).unwrap();
+        assert!(Arc::ptr_eq(&resolved, &default_cert));
+
+        let resolved = resolver.resolve_by_name(None).unwrap();
+        assert!(Arc::ptr_eq(&resolved, &default_cert));
+
+        assert_eq!(
+            resolver.server_names(),
+            vec![
+                "*.example.com".to_string(),
+                "tenant.example.com".to_string()
+            ]
+        );
+    }
+}
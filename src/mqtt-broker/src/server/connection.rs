@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt;
 use std::net::SocketAddr;
 use std::sync::atomic::AtomicU64;
@@ -53,6 +54,9 @@ pub struct NetworkConnection {
     pub connection_id: u64,
     pub protocol: Option<MqttProtocol>,
     pub addr: SocketAddr,
+    // (CN/O/OU, value) of the verified client certificate
+    #[serde(default)]
+    pub peer_cert_subject: HashMap<String, String>,
     #[serde(skip_serializing, skip_deserializing)]
     pub connection_stop_sx: Option<mpsc::Sender<bool>>,
 }
@@ -69,6 +73,7 @@ pub fn new(
             connection_id,
             protocol: None,
             addr,
+            peer_cert_subject: HashMap::new(),
             connection_stop_sx,
         }
     }
@@ -81,6 +86,10 @@ pub fn set_protocol(&mut self, protocol: MqttProtocol) {
         self.protocol = Some(protocol);
     }
 
+    pub fn set_peer_cert_subject(&mut self, peer_cert_subject: HashMap<String, String>) {
+        self.peer_cert_subject = peer_cert_subject;
+    }
+
     pub fn is_mqtt3(&self) -> bool {
         if let Some(protocol) = self.protocol.clone() {
             return protocol == MqttProtocol::Mqtt3;
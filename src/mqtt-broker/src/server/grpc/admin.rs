@@ -18,27 +18,52 @@
     create_acl_by_req, create_blacklist_by_req, delete_acl_by_req, delete_blacklist_by_req,
     list_acl_by_req, list_blacklist_by_req,
 };
+use crate::admin::alarm::{clear_alarm_by_req, list_alarm_by_req};
+use crate::admin::client::{
+    clean_session_by_req, inspect_client_by_req, kick_client_by_req, list_session_by_req,
+    list_subscribe_by_req,
+};
 use crate::admin::connector::{
     create_connector_by_req, delete_connector_by_req, list_connector_by_req,
     update_connector_by_req,
 };
+use crate::admin::delay_message::{
+    cancel_delay_message_by_req, inspect_delay_message_by_req, list_delay_message_by_req,
+};
+use crate::admin::message::{peek_message_by_req, publish_message_by_req};
+use crate::admin::retain::{
+    delete_retain_message_by_req, get_retain_message_by_req, list_retain_message_by_req,
+};
+use crate::admin::rule::{
+    create_rule_by_req, delete_rule_by_req, list_rule_by_req, update_rule_by_req,
+};
 use crate::admin::subscribe::{
     delete_auto_subscribe_rule, list_auto_subscribe_rule_by_req, set_auto_subscribe_rule,
 };
+use crate::admin::tenant::{
+    create_tenant_by_req, delete_tenant_by_req, list_tenant_by_req, set_tenant_config_by_req,
+    update_tenant_by_req,
+};
 use crate::admin::topic::{
     create_topic_rewrite_rule_by_req, delete_topic_rewrite_rule_by_req, list_topic_by_req,
 };
+use crate::admin::trace::{list_client_trace_by_req, set_client_trace_by_req};
 use crate::admin::user::{create_user_by_req, delete_user_by_req, list_user_by_req};
 use crate::admin::{
     cluster_status_by_req, enable_flapping_detect_by_req, enable_slow_subscribe_by_req,
-    list_connection_by_req, list_slow_subscribe_by_req,
+    list_connection_by_req, list_slow_subscribe_by_req, reload_config_by_req,
+    reload_tls_cert_by_req,
 };
 use crate::handler::cache::CacheManager;
+use crate::rule_engine::RuleEngine;
+use crate::server::cert_resolver::TlsCertResolver;
 use crate::server::connection_manager::ConnectionManager;
 use crate::storage::schema::{
     bind_schema_by_req, create_schema_by_req, delete_schema_by_req, list_bind_schema_by_req,
-    list_schema_by_req, unbind_schema_by_req, update_schema_by_req,
+    list_schema_by_req, list_schema_version_by_req, unbind_schema_by_req, update_schema_by_req,
 };
+use crate::subscribe::subscribe_manager::SubscribeManager;
+use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
 use protocol::broker_mqtt::broker_mqtt_admin::mqtt_broker_admin_service_server::MqttBrokerAdminService;
 use protocol::broker_mqtt::broker_mqtt_admin::{
@@ -53,38 +78,79 @@
     ListBlacklistReply, ListBlacklistRequest, ListConnectionReply, ListConnectionRequest,
     ListSlowSubscribeReply, ListSlowSubscribeRequest, ListTopicReply, ListTopicRequest,
     ListUserReply, ListUserRequest, MqttBindSchemaReply, MqttBindSchemaRequest,
-    MqttCreateConnectorReply, MqttCreateConnectorRequest, MqttCreateSchemaReply,
-    MqttCreateSchemaRequest, MqttDeleteConnectorReply, MqttDeleteConnectorRequest,
-    MqttDeleteSchemaReply, MqttDeleteSchemaRequest, MqttListBindSchemaReply,
-    MqttListBindSchemaRequest, MqttListConnectorReply, MqttListConnectorRequest,
-    MqttListSchemaReply, MqttListSchemaRequest, MqttUnbindSchemaReply, MqttUnbindSchemaRequest,
-    MqttUpdateConnectorReply, MqttUpdateConnectorRequest, MqttUpdateSchemaReply,
-    MqttUpdateSchemaRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
+    MqttCancelDelayMessageReply, MqttCancelDelayMessageRequest, MqttCleanSessionReply,
+    MqttCleanSessionRequest, MqttClearAlarmReply, MqttClearAlarmRequest, MqttCreateConnectorReply,
+    MqttCreateConnectorRequest, MqttCreateRuleReply, MqttCreateRuleRequest, MqttCreateSchemaReply,
+    MqttCreateSchemaRequest, MqttCreateTenantReply, MqttCreateTenantRequest,
+    MqttDeleteConnectorReply, MqttDeleteConnectorRequest, MqttDeleteRetainMessageReply,
+    MqttDeleteRetainMessageRequest, MqttDeleteRuleReply, MqttDeleteRuleRequest,
+    MqttDeleteSchemaReply, MqttDeleteSchemaRequest, MqttDeleteTenantReply, MqttDeleteTenantRequest,
+    MqttGetRetainMessageReply, MqttGetRetainMessageRequest, MqttInspectClientReply,
+    MqttInspectClientRequest, MqttInspectDelayMessageReply, MqttInspectDelayMessageRequest,
+    MqttKickClientReply, MqttKickClientRequest, MqttListAlarmReply, MqttListAlarmRequest,
+    MqttListBindSchemaReply, MqttListBindSchemaRequest, MqttListClientTraceReply,
+    MqttListClientTraceRequest, MqttListConnectorReply, MqttListConnectorRequest,
+    MqttListDelayMessageReply, MqttListDelayMessageRequest, MqttListRetainMessageReply,
+    MqttListRetainMessageRequest, MqttListRuleReply, MqttListRuleRequest, MqttListSchemaReply,
+    MqttListSchemaRequest, MqttListSchemaVersionReply, MqttListSchemaVersionRequest,
+    MqttListSessionReply, MqttListSessionRequest, MqttListSubscribeReply, MqttListSubscribeRequest,
+    MqttListTenantReply, MqttListTenantRequest, MqttPeekMessageReply, MqttPeekMessageRequest,
+    MqttPublishMessageReply, MqttPublishMessageRequest, MqttReloadConfigReply,
+    MqttReloadConfigRequest, MqttReloadTlsCertReply, MqttReloadTlsCertRequest,
+    MqttSetClientTraceReply, MqttSetClientTraceRequest, MqttSetTenantConfigReply,
+    MqttSetTenantConfigRequest, MqttUnbindSchemaReply, MqttUnbindSchemaRequest,
+    MqttUpdateConnectorReply, MqttUpdateConnectorRequest, MqttUpdateRuleReply,
+    MqttUpdateRuleRequest, MqttUpdateSchemaReply, MqttUpdateSchemaRequest, MqttUpdateTenantReply,
+    MqttUpdateTenantRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
 };
+use schema_register::schema::SchemaRegisterManager;
+use storage_adapter::storage::StorageAdapter;
 use tonic::{Request, Response, Status};
 
-pub struct GrpcAdminServices {
+pub struct GrpcAdminServices<S> {
     client_pool: Arc<ClientPool>,
     cache_manager: Arc<CacheManager>,
     connection_manager: Arc<ConnectionManager>,
+    subscribe_manager: Arc<SubscribeManager>,
+    delay_message_manager: Arc<DelayMessageManager<S>>,
+    message_storage_adapter: Arc<S>,
+    schema_manager: Arc<SchemaRegisterManager>,
+    rule_engine: Arc<RuleEngine<S>>,
+    tls_cert_resolver: Arc<TlsCertResolver>,
 }
 
-impl GrpcAdminServices {
+impl<S> GrpcAdminServices<S> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         client_pool: Arc<ClientPool>,
         cache_manager: Arc<CacheManager>,
         connection_manager: Arc<ConnectionManager>,
+        subscribe_manager: Arc<SubscribeManager>,
+        delay_message_manager: Arc<DelayMessageManager<S>>,
+        message_storage_adapter: Arc<S>,
+        schema_manager: Arc<SchemaRegisterManager>,
+        rule_engine: Arc<RuleEngine<S>>,
+        tls_cert_resolver: Arc<TlsCertResolver>,
     ) -> Self {
         GrpcAdminServices {
             client_pool,
             cache_manager,
             connection_manager,
+            subscribe_manager,
+            delay_message_manager,
+            message_storage_adapter,
+            schema_manager,
+            rule_engine,
+            tls_cert_resolver,
         }
     }
 }
 
 #[tonic::async_trait]
-impl MqttBrokerAdminService for GrpcAdminServices {
+impl<S> MqttBrokerAdminService for GrpcAdminServices<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
     // --- cluster ---
     async fn cluster_status(
         &self,
@@ -96,6 +162,26 @@ async fn cluster_status(
         }
     }
 
+    async fn mqtt_broker_reload_config(
+        &self,
+        _: Request<MqttReloadConfigRequest>,
+    ) -> Result<Response<MqttReloadConfigReply>, Status> {
+        match reload_config_by_req() {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_reload_tls_cert(
+        &self,
+        _: Request<MqttReloadTlsCertRequest>,
+    ) -> Result<Response<MqttReloadTlsCertReply>, Status> {
+        match reload_tls_cert_by_req(&self.tls_cert_resolver) {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
     // --- user ---
     async fn mqtt_broker_create_user(
         &self,
@@ -113,9 +199,9 @@ async fn mqtt_broker_delete_user(
 
     async fn mqtt_broker_list_user(
         &self,
-        _: Request<ListUserRequest>,
+        request: Request<ListUserRequest>,
     ) -> Result<Response<ListUserReply>, Status> {
-        list_user_by_req(&self.cache_manager, &self.client_pool).await
+        list_user_by_req(&self.cache_manager, &self.client_pool, request).await
     }
 
     async fn mqtt_broker_list_acl(
@@ -170,9 +256,9 @@ async fn mqtt_broker_enable_flapping_detect(
     // --- connection ---
     async fn mqtt_broker_list_connection(
         &self,
-        _: Request<ListConnectionRequest>,
+        request: Request<ListConnectionRequest>,
     ) -> Result<Response<ListConnectionReply>, Status> {
-        list_connection_by_req(&self.connection_manager, &self.cache_manager)
+        list_connection_by_req(&self.connection_manager, &self.cache_manager, request)
     }
 
     async fn mqtt_broker_enable_slow_subscribe(
@@ -238,6 +324,185 @@ async fn mqtt_broker_update_connector(
         update_connector_by_req(&self.client_pool, request).await
     }
 
+    // --- rule ---
+    async fn mqtt_broker_list_rule(
+        &self,
+        request: Request<MqttListRuleRequest>,
+    ) -> Result<Response<MqttListRuleReply>, Status> {
+        list_rule_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_create_rule(
+        &self,
+        request: Request<MqttCreateRuleRequest>,
+    ) -> Result<Response<MqttCreateRuleReply>, Status> {
+        create_rule_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_update_rule(
+        &self,
+        request: Request<MqttUpdateRuleRequest>,
+    ) -> Result<Response<MqttUpdateRuleReply>, Status> {
+        update_rule_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_delete_rule(
+        &self,
+        request: Request<MqttDeleteRuleRequest>,
+    ) -> Result<Response<MqttDeleteRuleReply>, Status> {
+        delete_rule_by_req(&self.client_pool, request).await
+    }
+
+    // --- delay message ---
+    async fn mqtt_broker_list_delay_message(
+        &self,
+        request: Request<MqttListDelayMessageRequest>,
+    ) -> Result<Response<MqttListDelayMessageReply>, Status> {
+        list_delay_message_by_req(&self.delay_message_manager, request).await
+    }
+
+    async fn mqtt_broker_inspect_delay_message(
+        &self,
+        request: Request<MqttInspectDelayMessageRequest>,
+    ) -> Result<Response<MqttInspectDelayMessageReply>, Status> {
+        inspect_delay_message_by_req(&self.delay_message_manager, request).await
+    }
+
+    async fn mqtt_broker_cancel_delay_message(
+        &self,
+        request: Request<MqttCancelDelayMessageRequest>,
+    ) -> Result<Response<MqttCancelDelayMessageReply>, Status> {
+        cancel_delay_message_by_req(&self.delay_message_manager, request).await
+    }
+
+    // --- message ---
+    async fn mqtt_broker_publish_message(
+        &self,
+        request: Request<MqttPublishMessageRequest>,
+    ) -> Result<Response<MqttPublishMessageReply>, Status> {
+        publish_message_by_req(
+            &self.cache_manager,
+            &self.client_pool,
+            &self.message_storage_adapter,
+            &self.delay_message_manager,
+            &self.subscribe_manager,
+            &self.schema_manager,
+            &self.rule_engine,
+            request,
+        )
+        .await
+    }
+
+    async fn mqtt_broker_peek_message(
+        &self,
+        request: Request<MqttPeekMessageRequest>,
+    ) -> Result<Response<MqttPeekMessageReply>, Status> {
+        peek_message_by_req(&self.cache_manager, &self.message_storage_adapter, request).await
+    }
+
+    // --- retain message ---
+    async fn mqtt_broker_list_retain_message(
+        &self,
+        request: Request<MqttListRetainMessageRequest>,
+    ) -> Result<Response<MqttListRetainMessageReply>, Status> {
+        list_retain_message_by_req(&self.cache_manager, request).await
+    }
+
+    async fn mqtt_broker_get_retain_message(
+        &self,
+        request: Request<MqttGetRetainMessageRequest>,
+    ) -> Result<Response<MqttGetRetainMessageReply>, Status> {
+        get_retain_message_by_req(&self.cache_manager, &self.message_storage_adapter, request).await
+    }
+
+    async fn mqtt_broker_delete_retain_message(
+        &self,
+        request: Request<MqttDeleteRetainMessageRequest>,
+    ) -> Result<Response<MqttDeleteRetainMessageReply>, Status> {
+        delete_retain_message_by_req(&self.cache_manager, &self.message_storage_adapter, request)
+            .await
+    }
+
+    // --- alarm ---
+    async fn mqtt_broker_list_alarm(
+        &self,
+        request: Request<MqttListAlarmRequest>,
+    ) -> Result<Response<MqttListAlarmReply>, Status> {
+        list_alarm_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_clear_alarm(
+        &self,
+        request: Request<MqttClearAlarmRequest>,
+    ) -> Result<Response<MqttClearAlarmReply>, Status> {
+        clear_alarm_by_req(&self.client_pool, request).await
+    }
+
+    // --- trace ---
+    async fn mqtt_broker_set_client_trace(
+        &self,
+        request: Request<MqttSetClientTraceRequest>,
+    ) -> Result<Response<MqttSetClientTraceReply>, Status> {
+        set_client_trace_by_req(request).await
+    }
+
+    async fn mqtt_broker_list_client_trace(
+        &self,
+        request: Request<MqttListClientTraceRequest>,
+    ) -> Result<Response<MqttListClientTraceReply>, Status> {
+        list_client_trace_by_req(request).await
+    }
+
+    // --- client & session ---
+    async fn mqtt_broker_list_session(
+        &self,
+        request: Request<MqttListSessionRequest>,
+    ) -> Result<Response<MqttListSessionReply>, Status> {
+        list_session_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_list_subscribe(
+        &self,
+        request: Request<MqttListSubscribeRequest>,
+    ) -> Result<Response<MqttListSubscribeReply>, Status> {
+        list_subscribe_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_inspect_client(
+        &self,
+        request: Request<MqttInspectClientRequest>,
+    ) -> Result<Response<MqttInspectClientReply>, Status> {
+        inspect_client_by_req(
+            &self.client_pool,
+            &self.cache_manager,
+            &self.connection_manager,
+            &self.subscribe_manager,
+            request,
+        )
+        .await
+    }
+
+    async fn mqtt_broker_kick_client(
+        &self,
+        request: Request<MqttKickClientRequest>,
+    ) -> Result<Response<MqttKickClientReply>, Status> {
+        kick_client_by_req(
+            &self.client_pool,
+            &self.cache_manager,
+            &self.connection_manager,
+            &self.subscribe_manager,
+            request,
+        )
+        .await
+    }
+
+    async fn mqtt_broker_clean_session(
+        &self,
+        request: Request<MqttCleanSessionRequest>,
+    ) -> Result<Response<MqttCleanSessionReply>, Status> {
+        clean_session_by_req(&self.client_pool, request).await
+    }
+
     // --- schema ---
     async fn mqtt_broker_list_schema(
         &self,
@@ -246,6 +511,13 @@ async fn mqtt_broker_list_schema(
         list_schema_by_req(&self.client_pool, request).await
     }
 
+    async fn mqtt_broker_list_schema_version(
+        &self,
+        request: Request<MqttListSchemaVersionRequest>,
+    ) -> Result<Response<MqttListSchemaVersionReply>, Status> {
+        list_schema_version_by_req(&self.client_pool, request).await
+    }
+
     async fn mqtt_broker_create_schema(
         &self,
         request: Request<MqttCreateSchemaRequest>,
@@ -308,4 +580,40 @@ async fn mqtt_broker_list_auto_subscribe_rule(
     ) -> Result<Response<ListAutoSubscribeRuleReply>, Status> {
         list_auto_subscribe_rule_by_req(&self.cache_manager)
     }
+
+    // --- tenant ---
+    async fn mqtt_broker_list_tenant(
+        &self,
+        request: Request<MqttListTenantRequest>,
+    ) -> Result<Response<MqttListTenantReply>, Status> {
+        list_tenant_by_req(&self.cache_manager, &self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_create_tenant(
+        &self,
+        request: Request<MqttCreateTenantRequest>,
+    ) -> Result<Response<MqttCreateTenantReply>, Status> {
+        create_tenant_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_update_tenant(
+        &self,
+        request: Request<MqttUpdateTenantRequest>,
+    ) -> Result<Response<MqttUpdateTenantReply>, Status> {
+        update_tenant_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_delete_tenant(
+        &self,
+        request: Request<MqttDeleteTenantRequest>,
+    ) -> Result<Response<MqttDeleteTenantReply>, Status> {
+        delete_tenant_by_req(&self.client_pool, request).await
+    }
+
+    async fn mqtt_broker_set_tenant_config(
+        &self,
+        request: Request<MqttSetTenantConfigRequest>,
+    ) -> Result<Response<MqttSetTenantConfigReply>, Status> {
+        set_tenant_config_by_req(&self.cache_manager, request).await
+    }
 }
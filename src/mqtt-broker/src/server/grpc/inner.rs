@@ -20,23 +20,28 @@
 use metadata_struct::mqtt::lastwill::LastWillData;
 use protocol::broker_mqtt::broker_mqtt_inner::mqtt_broker_inner_service_server::MqttBrokerInnerService;
 use protocol::broker_mqtt::broker_mqtt_inner::{
-    DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
+    DeleteSessionReply, DeleteSessionRequest, GetClientDetailReply, GetClientDetailRequest,
+    KickClientReply, KickClientRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
     UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 use schema_register::schema::SchemaRegisterManager;
 use storage_adapter::storage::StorageAdapter;
 use tonic::{Request, Response, Status};
 
+use crate::admin::client::{kick_local_client, local_client_detail};
 use crate::bridge::manager::ConnectorManager;
 use crate::handler::cache::CacheManager;
 use crate::handler::cache_update::update_cache_metadata;
 use crate::handler::lastwill::send_last_will_message;
+use crate::observability::metrics::session::metrics_session_expired;
+use crate::server::connection_manager::ConnectionManager;
 use crate::subscribe::subscribe_manager::SubscribeManager;
 
 pub struct GrpcInnerServices<S> {
     cache_manager: Arc<CacheManager>,
     connector_manager: Arc<ConnectorManager>,
     subscribe_manager: Arc<SubscribeManager>,
+    connection_manager: Arc<ConnectionManager>,
     schema_manager: Arc<SchemaRegisterManager>,
     client_pool: Arc<ClientPool>,
     message_storage_adapter: Arc<S>,
@@ -46,6 +51,7 @@ impl<S> GrpcInnerServices<S> {
     pub fn new(
         cache_manager: Arc<CacheManager>,
         subscribe_manager: Arc<SubscribeManager>,
+        connection_manager: Arc<ConnectionManager>,
         connector_manager: Arc<ConnectorManager>,
         schema_manager: Arc<SchemaRegisterManager>,
         client_pool: Arc<ClientPool>,
@@ -54,6 +60,7 @@ pub fn new(
         GrpcInnerServices {
             cache_manager,
             subscribe_manager,
+            connection_manager,
             connector_manager,
             client_pool,
             message_storage_adapter,
@@ -104,6 +111,7 @@ async fn delete_session(
         for client_id in req.client_id {
             self.subscribe_manager.remove_client_id(&client_id);
             self.cache_manager.remove_session(&client_id);
+            metrics_session_expired();
         }
 
         return Ok(Response::new(DeleteSessionReply::default()));
@@ -143,4 +151,43 @@ async fn send_last_will_message(
             }
         }
     }
+
+    async fn get_client_detail(
+        &self,
+        request: Request<GetClientDetailRequest>,
+    ) -> Result<Response<GetClientDetailReply>, Status> {
+        let req = request.into_inner();
+        if self.cache_manager.cluster_name != req.cluster_name {
+            return Err(Status::cancelled("Cluster name does not match".to_string()));
+        }
+        let detail = local_client_detail(
+            &self.cache_manager,
+            &self.connection_manager,
+            &self.subscribe_manager,
+            &req.client_id,
+        );
+        match serde_json::to_vec(&detail) {
+            Ok(detail) => Ok(Response::new(GetClientDetailReply { detail })),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn kick_client(
+        &self,
+        request: Request<KickClientRequest>,
+    ) -> Result<Response<KickClientReply>, Status> {
+        let req = request.into_inner();
+        if self.cache_manager.cluster_name != req.cluster_name {
+            return Err(Status::cancelled("Cluster name does not match".to_string()));
+        }
+        kick_local_client(
+            &self.cache_manager,
+            &self.client_pool,
+            &self.connection_manager,
+            &self.subscribe_manager,
+            &req.client_id,
+        )
+        .await?;
+        Ok(Response::new(KickClientReply::default()))
+    }
 }
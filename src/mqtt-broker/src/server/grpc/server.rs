@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
 use log::info;
 use protocol::broker_mqtt::broker_mqtt_admin::mqtt_broker_admin_service_server::MqttBrokerAdminServiceServer;
@@ -26,6 +27,8 @@
 use super::inner::GrpcInnerServices;
 use crate::bridge::manager::ConnectorManager;
 use crate::handler::cache::CacheManager;
+use crate::rule_engine::RuleEngine;
+use crate::server::cert_resolver::TlsCertResolver;
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::grpc::admin::GrpcAdminServices;
 use crate::subscribe::subscribe_manager::SubscribeManager;
@@ -39,6 +42,9 @@ pub struct GrpcServer<S> {
     schema_manager: Arc<SchemaRegisterManager>,
     client_pool: Arc<ClientPool>,
     message_storage_adapter: Arc<S>,
+    delay_message_manager: Arc<DelayMessageManager<S>>,
+    rule_engine: Arc<RuleEngine<S>>,
+    tls_cert_resolver: Arc<TlsCertResolver>,
 }
 
 impl<S> GrpcServer<S>
@@ -55,6 +61,9 @@ pub fn new(
         schema_manager: Arc<SchemaRegisterManager>,
         client_pool: Arc<ClientPool>,
         message_storage_adapter: Arc<S>,
+        delay_message_manager: Arc<DelayMessageManager<S>>,
+        rule_engine: Arc<RuleEngine<S>>,
+        tls_cert_resolver: Arc<TlsCertResolver>,
     ) -> Self {
         Self {
             port,
@@ -65,6 +74,9 @@ pub fn new(
             client_pool,
             message_storage_adapter,
             schema_manager,
+            delay_message_manager,
+            rule_engine,
+            tls_cert_resolver,
         }
     }
     pub async fn start(&self) -> Result<(), CommonError> {
@@ -73,6 +85,7 @@ pub async fn start(&self) -> Result<(), CommonError> {
         let inner_handler = GrpcInnerServices::new(
             self.metadata_cache.clone(),
             self.subscribe_manager.clone(),
+            self.connection_manager.clone(),
             self.connector_manager.clone(),
             self.schema_manager.clone(),
             self.client_pool.clone(),
@@ -82,6 +95,12 @@ pub async fn start(&self) -> Result<(), CommonError> {
             self.client_pool.clone(),
             self.metadata_cache.clone(),
             self.connection_manager.clone(),
+            self.subscribe_manager.clone(),
+            self.delay_message_manager.clone(),
+            self.message_storage_adapter.clone(),
+            self.schema_manager.clone(),
+            self.rule_engine.clone(),
+            self.tls_cert_resolver.clone(),
         );
         Server::builder()
             .accept_http1(true)
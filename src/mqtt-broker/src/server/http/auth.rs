@@ -0,0 +1,84 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use axum::extract::{Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::response::HttpError;
+use super::server::HttpServerState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+const BEARER_PREFIX: &str = "Bearer ";
+
+pub async fn api_key_auth(
+    State(state): State<HttpServerState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_authorized(request.headers(), &state.api_keys) {
+        return HttpError::unauthorized().into_response();
+    }
+    next.run(request).await
+}
+
+// No configured key means no request is accepted.
+pub fn is_authorized(headers: &HeaderMap, api_keys: &[String]) -> bool {
+    let Some(key) = request_api_key(headers) else {
+        return false;
+    };
+    api_keys.iter().any(|k| !k.is_empty() && k == key)
+}
+
+fn request_api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(value) = headers.get(AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+        return value.strip_prefix(BEARER_PREFIX).map(|k| k.trim());
+    }
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|k| k.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::AUTHORIZATION;
+    use axum::http::{HeaderMap, HeaderValue};
+
+    use super::{is_authorized, API_KEY_HEADER};
+
+    #[test]
+    fn is_authorized_test() {
+        let keys = vec!["k1".to_string(), "k2".to_string()];
+
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, &keys));
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer k2"));
+        assert!(is_authorized(&headers, &keys));
+        assert!(!is_authorized(&headers, &[]));
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic k2"));
+        assert!(!is_authorized(&headers, &keys));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("k1"));
+        assert!(is_authorized(&headers, &keys));
+
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("k3"));
+        assert!(!is_authorized(&headers, &keys));
+    }
+}
@@ -0,0 +1,550 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    CreateAclRequest, CreateBlacklistRequest, CreateTopicRewriteRuleRequest, CreateUserRequest,
+    DeleteAclRequest, DeleteAutoSubscribeRuleRequest, DeleteBlacklistRequest,
+    DeleteTopicRewriteRuleRequest, DeleteUserRequest, MqttConnectorType,
+    MqttCreateConnectorRequest, MqttCreateSchemaRequest, MqttDeleteConnectorRequest,
+    MqttDeleteSchemaRequest, MqttListConnectorRequest, MqttListSchemaRequest,
+    MqttUpdateConnectorRequest, MqttUpdateSchemaRequest, SetAutoSubscribeRuleRequest,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tonic::Request;
+
+use super::response::{
+    decode_json_list, success, success_empty, success_page, HttpError, HttpResult, PageQuery,
+};
+use super::server::HttpServerState;
+use crate::admin::acl::{
+    create_acl_by_req, create_blacklist_by_req, delete_acl_by_req, delete_blacklist_by_req,
+    list_acl_by_req, list_blacklist_by_req,
+};
+use crate::admin::connector::{
+    create_connector_by_req, delete_connector_by_req, list_connector_by_req,
+    update_connector_by_req,
+};
+use crate::admin::subscribe::{
+    delete_auto_subscribe_rule, list_auto_subscribe_rule_by_req, set_auto_subscribe_rule,
+};
+use crate::admin::topic::{
+    create_topic_rewrite_rule_by_req, delete_topic_rewrite_rule_by_req, search_topic,
+};
+use crate::admin::user::{create_user_by_req, delete_user_by_req, list_user_by_req};
+use crate::admin::{cluster_status_by_req, list_connection_by_req};
+use crate::storage::schema::{
+    create_schema_by_req, delete_schema_by_req, list_schema_by_req, update_schema_by_req,
+};
+
+// --- cluster ---
+pub async fn cluster_status(State(state): State<HttpServerState>) -> HttpResult {
+    let reply = cluster_status_by_req(&state.client_pool).await?;
+    success(json!({
+        "cluster_name": reply.cluster_name,
+        "nodes": reply.nodes,
+    }))
+}
+
+// --- user ---
+#[derive(Debug, Deserialize)]
+pub struct CreateUserBody {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub is_superuser: bool,
+}
+
+pub async fn list_user(
+    State(state): State<HttpServerState>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let reply = list_user_by_req(&state.cache_manager, &state.client_pool)
+        .await?
+        .into_inner();
+    let mut users = decode_json_list(&reply.users)?;
+    for user in users.iter_mut() {
+        if let Some(user) = user.as_object_mut() {
+            user.remove("password");
+        }
+    }
+    success_page(users, &query)
+}
+
+pub async fn create_user(
+    State(state): State<HttpServerState>,
+    Json(body): Json<CreateUserBody>,
+) -> HttpResult {
+    let request = CreateUserRequest {
+        username: body.username,
+        password: body.password,
+        is_superuser: body.is_superuser,
+    };
+    create_user_by_req(
+        &state.cache_manager,
+        &state.client_pool,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+pub async fn delete_user(
+    State(state): State<HttpServerState>,
+    Path(username): Path<String>,
+) -> HttpResult {
+    let request = DeleteUserRequest { username };
+    delete_user_by_req(
+        &state.cache_manager,
+        &state.client_pool,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+// --- acl ---
+pub async fn list_acl(
+    State(state): State<HttpServerState>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let reply = list_acl_by_req(&state.cache_manager, &state.client_pool)
+        .await?
+        .into_inner();
+    success_page(decode_json_list(&reply.acls)?, &query)
+}
+
+pub async fn create_acl(
+    State(state): State<HttpServerState>,
+    Json(acl): Json<Value>,
+) -> HttpResult {
+    let request = CreateAclRequest {
+        cluster_name: broker_mqtt_conf().cluster_name.clone(),
+        acl: serde_json::to_vec(&acl)?,
+    };
+    create_acl_by_req(
+        &state.cache_manager,
+        &state.client_pool,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+pub async fn delete_acl(
+    State(state): State<HttpServerState>,
+    Json(acl): Json<Value>,
+) -> HttpResult {
+    let request = DeleteAclRequest {
+        cluster_name: broker_mqtt_conf().cluster_name.clone(),
+        acl: serde_json::to_vec(&acl)?,
+    };
+    delete_acl_by_req(
+        &state.cache_manager,
+        &state.client_pool,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+// --- blacklist ---
+#[derive(Debug, Deserialize)]
+pub struct DeleteBlacklistBody {
+    pub blacklist_type: String,
+    pub resource_name: String,
+}
+
+pub async fn list_blacklist(
+    State(state): State<HttpServerState>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let reply = list_blacklist_by_req(&state.cache_manager, &state.client_pool)
+        .await?
+        .into_inner();
+    success_page(decode_json_list(&reply.blacklists)?, &query)
+}
+
+pub async fn create_blacklist(
+    State(state): State<HttpServerState>,
+    Json(blacklist): Json<Value>,
+) -> HttpResult {
+    let request = CreateBlacklistRequest {
+        cluster_name: broker_mqtt_conf().cluster_name.clone(),
+        blacklist: serde_json::to_vec(&blacklist)?,
+    };
+    create_blacklist_by_req(
+        &state.cache_manager,
+        &state.client_pool,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+pub async fn delete_blacklist(
+    State(state): State<HttpServerState>,
+    Json(body): Json<DeleteBlacklistBody>,
+) -> HttpResult {
+    let request = DeleteBlacklistRequest {
+        cluster_name: broker_mqtt_conf().cluster_name.clone(),
+        blacklist_type: body.blacklist_type,
+        resource_name: body.resource_name,
+    };
+    delete_blacklist_by_req(
+        &state.cache_manager,
+        &state.client_pool,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+// --- connection ---
+pub async fn list_connection(
+    State(state): State<HttpServerState>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let reply =
+        list_connection_by_req(&state.connection_manager, &state.cache_manager)?.into_inner();
+    let mut connections = Vec::new();
+    for raw in reply.list_connection_raw {
+        connections.push(json!({
+            "connection_id": raw.connection_id,
+            "connection_type": raw.connection_type,
+            "protocol": raw.protocol,
+            "source_addr": raw.source_addr,
+            "info": serde_json::from_str::<Value>(&raw.info).unwrap_or(Value::String(raw.info)),
+        }));
+    }
+    success_page(connections, &query)
+}
+
+// --- topic ---
+#[derive(Debug, Default, Deserialize)]
+pub struct TopicQuery {
+    #[serde(default)]
+    pub topic_name: String,
+    // 0 exact, 1 prefix, 2 contains
+    #[serde(default = "default_topic_match_option")]
+    pub match_option: i32,
+}
+
+fn default_topic_match_option() -> i32 {
+    2
+}
+
+pub async fn list_topic(
+    State(state): State<HttpServerState>,
+    Query(topic_query): Query<TopicQuery>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let topics = search_topic(
+        &state.cache_manager,
+        &topic_query.topic_name,
+        topic_query.match_option,
+    )
+    .into_iter()
+    .map(|topic| {
+        json!({
+            "topic_id": topic.topic_id,
+            "topic_name": topic.topic_name,
+            "cluster_name": topic.cluster_name,
+            "is_contain_retain_message": topic.is_contain_retain_message,
+        })
+    })
+    .collect();
+    success_page(topics, &query)
+}
+
+// --- topic rewrite rule ---
+#[derive(Debug, Deserialize)]
+pub struct CreateTopicRewriteRuleBody {
+    pub action: String,
+    pub source_topic: String,
+    pub dest_topic: String,
+    pub regex: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteTopicRewriteRuleBody {
+    pub action: String,
+    pub source_topic: String,
+}
+
+pub async fn list_topic_rewrite_rule(
+    State(state): State<HttpServerState>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let mut rules = Vec::new();
+    for entry in state.cache_manager.topic_rewrite_rule.iter() {
+        rules.push(serde_json::to_value(entry.value())?);
+    }
+    success_page(rules, &query)
+}
+
+pub async fn create_topic_rewrite_rule(
+    State(state): State<HttpServerState>,
+    Json(body): Json<CreateTopicRewriteRuleBody>,
+) -> HttpResult {
+    let request = CreateTopicRewriteRuleRequest {
+        action: body.action,
+        source_topic: body.source_topic,
+        dest_topic: body.dest_topic,
+        regex: body.regex,
+    };
+    create_topic_rewrite_rule_by_req(
+        &state.client_pool,
+        &state.cache_manager,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+pub async fn delete_topic_rewrite_rule(
+    State(state): State<HttpServerState>,
+    Json(body): Json<DeleteTopicRewriteRuleBody>,
+) -> HttpResult {
+    let request = DeleteTopicRewriteRuleRequest {
+        action: body.action,
+        source_topic: body.source_topic,
+    };
+    delete_topic_rewrite_rule_by_req(
+        &state.client_pool,
+        &state.cache_manager,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+// --- connector ---
+#[derive(Debug, Deserialize)]
+pub struct CreateConnectorBody {
+    pub connector_name: String,
+    pub connector_type: String,
+    // either the JSON encoded config string or the config object itself
+    pub config: Value,
+    pub topic_id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ConnectorQuery {
+    #[serde(default)]
+    pub connector_name: String,
+}
+
+pub async fn list_connector(
+    State(state): State<HttpServerState>,
+    Query(connector_query): Query<ConnectorQuery>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let request = MqttListConnectorRequest {
+        connector_name: connector_query.connector_name,
+    };
+    let reply = list_connector_by_req(&state.client_pool, Request::new(request))
+        .await?
+        .into_inner();
+    success_page(decode_json_list(&reply.connectors)?, &query)
+}
+
+pub async fn create_connector(
+    State(state): State<HttpServerState>,
+    Json(body): Json<CreateConnectorBody>,
+) -> HttpResult {
+    let connector_type = parse_connector_type(&body.connector_type)?;
+    let config = match body.config {
+        Value::String(config) => config,
+        config => config.to_string(),
+    };
+    let request = MqttCreateConnectorRequest {
+        connector_name: body.connector_name,
+        connector_type: connector_type as i32,
+        config,
+        topic_id: body.topic_id,
+    };
+    create_connector_by_req(&state.client_pool, Request::new(request)).await?;
+    success_empty()
+}
+
+pub async fn update_connector(
+    State(state): State<HttpServerState>,
+    Json(connector): Json<Value>,
+) -> HttpResult {
+    let request = MqttUpdateConnectorRequest {
+        connector: serde_json::to_vec(&connector)?,
+    };
+    update_connector_by_req(&state.client_pool, Request::new(request)).await?;
+    success_empty()
+}
+
+pub async fn delete_connector(
+    State(state): State<HttpServerState>,
+    Path(connector_name): Path<String>,
+) -> HttpResult {
+    let request = MqttDeleteConnectorRequest { connector_name };
+    delete_connector_by_req(&state.client_pool, Request::new(request)).await?;
+    success_empty()
+}
+
+fn parse_connector_type(connector_type: &str) -> Result<MqttConnectorType, HttpError> {
+    match connector_type.to_lowercase().as_str() {
+        "file" => Ok(MqttConnectorType::File),
+        "kafka" => Ok(MqttConnectorType::Kafka),
+        "mqtt" => Ok(MqttConnectorType::Mqtt),
+        "webhook" => Ok(MqttConnectorType::WebHook),
+        "rdb" => Ok(MqttConnectorType::Rdb),
+        "redis" => Ok(MqttConnectorType::Redis),
+        "influxdb" => Ok(MqttConnectorType::InfluxDb),
+        _ => Err(HttpError::bad_request(format!(
+            "invalid connector type: {}",
+            connector_type
+        ))),
+    }
+}
+
+// --- schema ---
+#[derive(Debug, Deserialize)]
+pub struct SchemaBody {
+    pub schema_name: String,
+    pub schema_type: String,
+    pub schema: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(default)]
+    pub compatibility: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SchemaQuery {
+    #[serde(default)]
+    pub schema_name: String,
+}
+
+pub async fn list_schema(
+    State(state): State<HttpServerState>,
+    Query(schema_query): Query<SchemaQuery>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let request = MqttListSchemaRequest {
+        schema_name: schema_query.schema_name,
+    };
+    let reply = list_schema_by_req(&state.client_pool, Request::new(request))
+        .await?
+        .into_inner();
+    success_page(decode_json_list(&reply.schemas)?, &query)
+}
+
+pub async fn create_schema(
+    State(state): State<HttpServerState>,
+    Json(body): Json<SchemaBody>,
+) -> HttpResult {
+    let request = MqttCreateSchemaRequest {
+        schema_name: body.schema_name,
+        schema_type: body.schema_type,
+        schema: body.schema,
+        desc: body.desc,
+        compatibility: body.compatibility,
+    };
+    create_schema_by_req(&state.client_pool, Request::new(request)).await?;
+    success_empty()
+}
+
+pub async fn update_schema(
+    State(state): State<HttpServerState>,
+    Json(body): Json<SchemaBody>,
+) -> HttpResult {
+    let request = MqttUpdateSchemaRequest {
+        schema_name: body.schema_name,
+        schema_type: body.schema_type,
+        schema: body.schema,
+        desc: body.desc,
+        compatibility: body.compatibility,
+    };
+    update_schema_by_req(&state.client_pool, Request::new(request)).await?;
+    success_empty()
+}
+
+pub async fn delete_schema(
+    State(state): State<HttpServerState>,
+    Path(schema_name): Path<String>,
+) -> HttpResult {
+    let request = MqttDeleteSchemaRequest { schema_name };
+    delete_schema_by_req(&state.client_pool, Request::new(request)).await?;
+    success_empty()
+}
+
+// --- auto subscribe rule ---
+#[derive(Debug, Deserialize)]
+pub struct SetAutoSubscribeRuleBody {
+    pub topic: String,
+    #[serde(default)]
+    pub qos: u32,
+    #[serde(default)]
+    pub no_local: bool,
+    #[serde(default)]
+    pub retain_as_published: bool,
+    #[serde(default)]
+    pub retained_handling: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteAutoSubscribeRuleBody {
+    pub topic: String,
+}
+
+pub async fn list_auto_subscribe_rule(
+    State(state): State<HttpServerState>,
+    Query(query): Query<PageQuery>,
+) -> HttpResult {
+    let reply = list_auto_subscribe_rule_by_req(&state.cache_manager)?.into_inner();
+    success_page(decode_json_list(&reply.auto_subscribe_rules)?, &query)
+}
+
+pub async fn set_auto_subscribe_rule_handler(
+    State(state): State<HttpServerState>,
+    Json(body): Json<SetAutoSubscribeRuleBody>,
+) -> HttpResult {
+    let request = SetAutoSubscribeRuleRequest {
+        topic: body.topic,
+        qos: body.qos,
+        no_local: body.no_local,
+        retain_as_published: body.retain_as_published,
+        retained_handling: body.retained_handling,
+    };
+    set_auto_subscribe_rule(
+        &state.client_pool,
+        &state.cache_manager,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
+
+pub async fn delete_auto_subscribe_rule_handler(
+    State(state): State<HttpServerState>,
+    Json(body): Json<DeleteAutoSubscribeRuleBody>,
+) -> HttpResult {
+    let request = DeleteAutoSubscribeRuleRequest { topic: body.topic };
+    delete_auto_subscribe_rule(
+        &state.client_pool,
+        &state.cache_manager,
+        Request::new(request),
+    )
+    .await?;
+    success_empty()
+}
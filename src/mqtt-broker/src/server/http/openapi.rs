@@ -0,0 +1,386 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde_json::{json, Map, Value};
+
+use super::server::{
+    ROUTE_ACL, ROUTE_AUTO_SUBSCRIBE_RULE, ROUTE_BLACKLIST, ROUTE_CLUSTER_STATUS, ROUTE_CONNECTION,
+    ROUTE_CONNECTOR, ROUTE_CONNECTOR_NAME, ROUTE_OPENAPI, ROUTE_SCHEMA, ROUTE_SCHEMA_NAME,
+    ROUTE_TOPIC, ROUTE_TOPIC_REWRITE_RULE, ROUTE_USER, ROUTE_USER_NAME,
+};
+
+pub fn openapi_document() -> Value {
+    let mut paths = Map::new();
+    let mut add = |route: &str, method: &str, operation: Value| {
+        let path = openapi_path(route);
+        let item = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[method] = operation;
+    };
+
+    add(
+        ROUTE_OPENAPI,
+        "get",
+        json!({"summary": "OpenAPI description of this API", "security": [], "responses": {"200": {"description": "OpenAPI document"}}}),
+    );
+    add(
+        ROUTE_CLUSTER_STATUS,
+        "get",
+        operation("Cluster name and broker nodes", None, false),
+    );
+
+    add(ROUTE_USER, "get", operation("List users", None, true));
+    add(
+        ROUTE_USER,
+        "post",
+        operation("Create a user", Some("CreateUser"), false),
+    );
+    add(
+        ROUTE_USER_NAME,
+        "delete",
+        operation("Delete a user", None, false),
+    );
+
+    add(ROUTE_ACL, "get", operation("List ACLs", None, true));
+    add(
+        ROUTE_ACL,
+        "post",
+        operation("Create an ACL", Some("Acl"), false),
+    );
+    add(
+        ROUTE_ACL,
+        "delete",
+        operation("Delete an ACL", Some("Acl"), false),
+    );
+
+    add(
+        ROUTE_BLACKLIST,
+        "get",
+        operation("List blacklist entries", None, true),
+    );
+    add(
+        ROUTE_BLACKLIST,
+        "post",
+        operation("Create a blacklist entry", Some("Blacklist"), false),
+    );
+    add(
+        ROUTE_BLACKLIST,
+        "delete",
+        operation("Delete a blacklist entry", Some("DeleteBlacklist"), false),
+    );
+
+    add(
+        ROUTE_CONNECTION,
+        "get",
+        operation("List connections", None, true),
+    );
+
+    let mut list_topic = operation("Search topics", None, true);
+    list_topic["parameters"].as_array_mut().unwrap().extend([
+        query_param("topic_name", "string", "Topic name to match"),
+        query_param(
+            "match_option",
+            "integer",
+            "0 exact, 1 prefix, 2 contains (default)",
+        ),
+    ]);
+    add(ROUTE_TOPIC, "get", list_topic);
+
+    add(
+        ROUTE_TOPIC_REWRITE_RULE,
+        "get",
+        operation("List topic rewrite rules", None, true),
+    );
+    add(
+        ROUTE_TOPIC_REWRITE_RULE,
+        "post",
+        operation(
+            "Create a topic rewrite rule",
+            Some("CreateTopicRewriteRule"),
+            false,
+        ),
+    );
+    add(
+        ROUTE_TOPIC_REWRITE_RULE,
+        "delete",
+        operation(
+            "Delete a topic rewrite rule",
+            Some("DeleteTopicRewriteRule"),
+            false,
+        ),
+    );
+
+    let mut list_connector = operation("List connectors", None, true);
+    list_connector["parameters"]
+        .as_array_mut()
+        .unwrap()
+        .push(query_param("connector_name", "string", "Connector name"));
+    add(ROUTE_CONNECTOR, "get", list_connector);
+    add(
+        ROUTE_CONNECTOR,
+        "post",
+        operation("Create a connector", Some("CreateConnector"), false),
+    );
+    add(
+        ROUTE_CONNECTOR,
+        "put",
+        operation("Update a connector", Some("Connector"), false),
+    );
+    add(
+        ROUTE_CONNECTOR_NAME,
+        "delete",
+        operation("Delete a connector", None, false),
+    );
+
+    let mut list_schema = operation("List schemas", None, true);
+    list_schema["parameters"]
+        .as_array_mut()
+        .unwrap()
+        .push(query_param("schema_name", "string", "Schema name"));
+    add(ROUTE_SCHEMA, "get", list_schema);
+    add(
+        ROUTE_SCHEMA,
+        "post",
+        operation("Create a schema", Some("Schema"), false),
+    );
+    add(
+        ROUTE_SCHEMA,
+        "put",
+        operation("Update a schema", Some("Schema"), false),
+    );
+    add(
+        ROUTE_SCHEMA_NAME,
+        "delete",
+        operation("Delete a schema", None, false),
+    );
+
+    add(
+        ROUTE_AUTO_SUBSCRIBE_RULE,
+        "get",
+        operation("List auto subscribe rules", None, true),
+    );
+    add(
+        ROUTE_AUTO_SUBSCRIBE_RULE,
+        "post",
+        operation(
+            "Set an auto subscribe rule",
+            Some("AutoSubscribeRule"),
+            false,
+        ),
+    );
+    add(
+        ROUTE_AUTO_SUBSCRIBE_RULE,
+        "delete",
+        operation(
+            "Delete an auto subscribe rule",
+            Some("DeleteAutoSubscribeRule"),
+            false,
+        ),
+    );
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "RobustMQ MQTT Broker HTTP API",
+            "version": "v1"
+        },
+        "paths": paths,
+        "security": [{"bearerAuth": []}, {"apiKey": []}],
+        "components": {
+            "securitySchemes": {
+                "bearerAuth": {"type": "http", "scheme": "bearer"},
+                "apiKey": {"type": "apiKey", "in": "header", "name": "X-API-Key"}
+            },
+            "schemas": schemas()
+        }
+    })
+}
+
+// Converts an axum route ("/users/:username") into an OpenAPI path ("/users/{username}").
+fn openapi_path(route: &str) -> String {
+    route
+        .split('/')
+        .map(|seg| match seg.strip_prefix(':') {
+            Some(name) => format!("{{{}}}", name),
+            None => seg.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+fn operation(summary: &str, body: Option<&str>, paged: bool) -> Value {
+    let mut parameters = Vec::new();
+    if paged {
+        parameters.extend([
+            query_param("page", "integer", "Page number, starting from 1"),
+            query_param("page_size", "integer", "Items per page, at most 1000"),
+            query_param("sort_field", "string", "Field to sort by"),
+            query_param("sort", "string", "asc or desc"),
+            query_param("filter_field", "string", "Field to filter on"),
+            query_param(
+                "filter_value",
+                "string",
+                "Substring the filter field must contain",
+            ),
+        ]);
+    }
+
+    let mut op = json!({
+        "summary": summary,
+        "parameters": parameters,
+        "responses": {
+            "200": {"description": "code 0 and the result in data"},
+            "400": {"description": "Invalid request"},
+            "401": {"description": "Missing or invalid API key"},
+            "500": {"description": "Internal error"}
+        }
+    });
+    if let Some(schema) = body {
+        op["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": {"$ref": format!("#/components/schemas/{}", schema)}
+                }
+            }
+        });
+    }
+    op
+}
+
+fn query_param(name: &str, ty: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "query",
+        "required": false,
+        "description": description,
+        "schema": {"type": ty}
+    })
+}
+
+fn object_schema(required: &[&str], properties: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": required,
+        "properties": properties
+    })
+}
+
+fn schemas() -> Value {
+    json!({
+        "CreateUser": object_schema(&["username", "password"], json!({
+            "username": {"type": "string"},
+            "password": {"type": "string"},
+            "is_superuser": {"type": "boolean"}
+        })),
+        "Acl": object_schema(&["resource_type", "resource_name", "topic", "ip", "action", "permission"], json!({
+            "resource_type": {"type": "string", "enum": ["ClientId", "User"]},
+            "resource_name": {"type": "string"},
+            "topic": {"type": "string"},
+            "ip": {"type": "string"},
+            "action": {"type": "string"},
+            "permission": {"type": "string", "enum": ["Allow", "Deny"]}
+        })),
+        "Blacklist": object_schema(&["blacklist_type", "resource_name", "end_time"], json!({
+            "blacklist_type": {"type": "string"},
+            "resource_name": {"type": "string"},
+            "end_time": {"type": "integer"},
+            "desc": {"type": "string"}
+        })),
+        "DeleteBlacklist": object_schema(&["blacklist_type", "resource_name"], json!({
+            "blacklist_type": {"type": "string"},
+            "resource_name": {"type": "string"}
+        })),
+        "CreateTopicRewriteRule": object_schema(&["action", "source_topic", "dest_topic", "regex"], json!({
+            "action": {"type": "string"},
+            "source_topic": {"type": "string"},
+            "dest_topic": {"type": "string"},
+            "regex": {"type": "string"}
+        })),
+        "DeleteTopicRewriteRule": object_schema(&["action", "source_topic"], json!({
+            "action": {"type": "string"},
+            "source_topic": {"type": "string"}
+        })),
+        "CreateConnector": object_schema(&["connector_name", "connector_type", "config", "topic_id"], json!({
+            "connector_name": {"type": "string"},
+            "connector_type": {"type": "string", "enum": ["file", "kafka", "mqtt", "webhook", "rdb", "redis", "influxdb"]},
+            "config": {"description": "Connector config, as an object or a JSON encoded string"},
+            "topic_id": {"type": "string"}
+        })),
+        "Connector": {"type": "object", "description": "A full connector as returned by GET"},
+        "Schema": object_schema(&["schema_name", "schema_type", "schema"], json!({
+            "schema_name": {"type": "string"},
+            "schema_type": {"type": "string"},
+            "schema": {"type": "string"},
+            "desc": {"type": "string"},
+            "compatibility": {"type": "string"}
+        })),
+        "AutoSubscribeRule": object_schema(&["topic"], json!({
+            "topic": {"type": "string"},
+            "qos": {"type": "integer"},
+            "no_local": {"type": "boolean"},
+            "retain_as_published": {"type": "boolean"},
+            "retained_handling": {"type": "integer"}
+        })),
+        "DeleteAutoSubscribeRule": object_schema(&["topic"], json!({
+            "topic": {"type": "string"}
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{openapi_document, openapi_path};
+    use crate::server::http::server::{
+        ROUTE_ACL, ROUTE_AUTO_SUBSCRIBE_RULE, ROUTE_BLACKLIST, ROUTE_CLUSTER_STATUS,
+        ROUTE_CONNECTION, ROUTE_CONNECTOR, ROUTE_CONNECTOR_NAME, ROUTE_OPENAPI, ROUTE_SCHEMA,
+        ROUTE_SCHEMA_NAME, ROUTE_TOPIC, ROUTE_TOPIC_REWRITE_RULE, ROUTE_USER, ROUTE_USER_NAME,
+    };
+
+    #[test]
+    fn openapi_document_test() {
+        assert_eq!(
+            openapi_path("/api/v1/users/:username"),
+            "/api/v1/users/{username}"
+        );
+
+        let doc = openapi_document();
+        for route in [
+            ROUTE_OPENAPI,
+            ROUTE_CLUSTER_STATUS,
+            ROUTE_USER,
+            ROUTE_USER_NAME,
+            ROUTE_ACL,
+            ROUTE_BLACKLIST,
+            ROUTE_CONNECTION,
+            ROUTE_TOPIC,
+            ROUTE_TOPIC_REWRITE_RULE,
+            ROUTE_CONNECTOR,
+            ROUTE_CONNECTOR_NAME,
+            ROUTE_SCHEMA,
+            ROUTE_SCHEMA_NAME,
+            ROUTE_AUTO_SUBSCRIBE_RULE,
+        ] {
+            assert!(doc["paths"].get(openapi_path(route)).is_some(), "{}", route);
+        }
+
+        let schemes = &doc["components"]["securitySchemes"];
+        assert!(schemes.get("bearerAuth").is_some());
+        assert!(schemes.get("apiKey").is_some());
+        assert_eq!(
+            doc["paths"]["/api/v1/openapi.json"]["get"]["security"],
+            serde_json::json!([])
+        );
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use common_base::enum_type::sort_type::SortType;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tonic::{Code, Status};
+
+use crate::handler::error::MqttBrokerError;
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+pub type HttpResult = Result<Json<Value>, HttpError>;
+
+pub fn success(data: Value) -> HttpResult {
+    Ok(Json(json!({ "code": 0, "data": data })))
+}
+
+pub fn success_empty() -> HttpResult {
+    success(Value::Null)
+}
+
+#[derive(Debug)]
+pub struct HttpError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl HttpError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        HttpError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "missing or invalid api key")
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        let body = json!({ "code": self.status.as_u16(), "message": self.message });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<Status> for HttpError {
+    fn from(status: Status) -> Self {
+        let code = match status.code() {
+            Code::InvalidArgument | Code::OutOfRange => StatusCode::BAD_REQUEST,
+            Code::NotFound => StatusCode::NOT_FOUND,
+            Code::AlreadyExists => StatusCode::CONFLICT,
+            Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+            Code::PermissionDenied => StatusCode::FORBIDDEN,
+            Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        HttpError::new(code, status.message())
+    }
+}
+
+impl From<MqttBrokerError> for HttpError {
+    fn from(e: MqttBrokerError) -> Self {
+        HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for HttpError {
+    fn from(e: serde_json::Error) -> Self {
+        HttpError::bad_request(e.to_string())
+    }
+}
+
+/// Query string shared by all list endpoints, e.g.
+/// `?page=2&page_size=20&sort_field=username&sort=desc&filter_field=username&filter_value=adm`
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct PageQuery {
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub sort_field: Option<String>,
+    pub sort: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_value: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Page {
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub items: Vec<Value>,
+}
+
+/// Filter, sort and slice a list of JSON objects. Filtering is a substring match
+/// on the string form of `filter_field`, pages start from 1.
+pub fn paginate(mut items: Vec<Value>, query: &PageQuery) -> Result<Page, HttpError> {
+    if let (Some(field), Some(value)) = (&query.filter_field, &query.filter_value) {
+        items.retain(|item| {
+            item.get(field)
+                .map(|v| value_to_string(v).contains(value.as_str()))
+                .unwrap_or(false)
+        });
+    }
+
+    if let Some(field) = &query.sort_field {
+        let sort_type = match &query.sort {
+            Some(sort) => SortType::from_str(sort).map_err(HttpError::bad_request)?,
+            None => SortType::ASC,
+        };
+        items.sort_by(|a, b| {
+            let ordering = compare_value(a.get(field), b.get(field));
+            match sort_type {
+                SortType::ASC => ordering,
+                SortType::DESC => ordering.reverse(),
+            }
+        });
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let total = items.len();
+    let items = items
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .collect();
+    Ok(Page {
+        total,
+        page,
+        page_size,
+        items,
+    })
+}
+
+pub fn success_page(items: Vec<Value>, query: &PageQuery) -> HttpResult {
+    let page = paginate(items, query)?;
+    success(serde_json::to_value(page)?)
+}
+
+/// Decode the JSON encoded entities carried in admin replies.
+pub fn decode_json_list(raw: &[Vec<u8>]) -> Result<Vec<Value>, HttpError> {
+    raw.iter()
+        .map(|data| {
+            serde_json::from_slice::<Value>(data)
+                .map_err(|e| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        })
+        .collect()
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn compare_value(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(x)), Some(Value::Number(y))) => x
+            .as_f64()
+            .unwrap_or_default()
+            .partial_cmp(&y.as_f64().unwrap_or_default())
+            .unwrap_or(Ordering::Equal),
+        (Some(x), Some(y)) => value_to_string(x).cmp(&value_to_string(y)),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{paginate, PageQuery};
+
+    fn users() -> Vec<serde_json::Value> {
+        vec![
+            json!({"username": "bob", "age": 9}),
+            json!({"username": "alice", "age": 30}),
+            json!({"username": "admin", "age": 12}),
+        ]
+    }
+
+    #[test]
+    fn paginate_sort_and_filter_test() {
+        let query = PageQuery {
+            sort_field: Some("age".to_string()),
+            sort: Some("desc".to_string()),
+            ..Default::default()
+        };
+        let page = paginate(users(), &query).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0]["username"], "alice");
+        assert_eq!(page.items[2]["username"], "bob");
+
+        let query = PageQuery {
+            filter_field: Some("username".to_string()),
+            filter_value: Some("a".to_string()),
+            sort_field: Some("username".to_string()),
+            ..Default::default()
+        };
+        let page = paginate(users(), &query).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0]["username"], "admin");
+
+        let query = PageQuery {
+            sort_field: Some("age".to_string()),
+            sort: Some("up".to_string()),
+            ..Default::default()
+        };
+        assert!(paginate(users(), &query).is_err());
+    }
+
+    #[test]
+    fn paginate_page_test() {
+        let query = PageQuery {
+            page: Some(2),
+            page_size: Some(2),
+            sort_field: Some("username".to_string()),
+            ..Default::default()
+        };
+        let page = paginate(users(), &query).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0]["username"], "bob");
+
+        let query = PageQuery {
+            page: Some(5),
+            ..Default::default()
+        };
+        assert!(paginate(users(), &query).unwrap().items.is_empty());
+    }
+}
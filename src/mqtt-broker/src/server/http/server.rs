@@ -0,0 +1,151 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::middleware::from_fn_with_state;
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::pool::ClientPool;
+use log::{error, info};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use super::auth::api_key_auth;
+use super::handler::*;
+use super::openapi::openapi_document;
+use crate::handler::cache::CacheManager;
+use crate::server::connection_manager::ConnectionManager;
+
+pub const ROUTE_OPENAPI: &str = "/api/v1/openapi.json";
+pub const ROUTE_CLUSTER_STATUS: &str = "/api/v1/cluster/status";
+pub const ROUTE_USER: &str = "/api/v1/users";
+pub const ROUTE_USER_NAME: &str = "/api/v1/users/:username";
+pub const ROUTE_ACL: &str = "/api/v1/acls";
+pub const ROUTE_BLACKLIST: &str = "/api/v1/blacklists";
+pub const ROUTE_CONNECTION: &str = "/api/v1/connections";
+pub const ROUTE_TOPIC: &str = "/api/v1/topics";
+pub const ROUTE_TOPIC_REWRITE_RULE: &str = "/api/v1/topic-rewrite-rules";
+pub const ROUTE_CONNECTOR: &str = "/api/v1/connectors";
+pub const ROUTE_CONNECTOR_NAME: &str = "/api/v1/connectors/:connector_name";
+pub const ROUTE_SCHEMA: &str = "/api/v1/schemas";
+pub const ROUTE_SCHEMA_NAME: &str = "/api/v1/schemas/:schema_name";
+pub const ROUTE_AUTO_SUBSCRIBE_RULE: &str = "/api/v1/auto-subscribe-rules";
+
+#[derive(Clone)]
+pub struct HttpServerState {
+    pub client_pool: Arc<ClientPool>,
+    pub cache_manager: Arc<CacheManager>,
+    pub connection_manager: Arc<ConnectionManager>,
+    pub api_keys: Arc<Vec<String>>,
+}
+
+impl HttpServerState {
+    pub fn new(
+        client_pool: Arc<ClientPool>,
+        cache_manager: Arc<CacheManager>,
+        connection_manager: Arc<ConnectionManager>,
+        api_keys: Vec<String>,
+    ) -> Self {
+        Self {
+            client_pool,
+            cache_manager,
+            connection_manager,
+            api_keys: Arc::new(api_keys),
+        }
+    }
+}
+
+pub async fn start_http_server(state: HttpServerState, stop_sx: broadcast::Sender<bool>) {
+    let config = broker_mqtt_conf();
+    let ip: SocketAddr = format!("0.0.0.0:{}", config.http_api.port).parse().unwrap();
+    let app = routes(state);
+
+    let handle = axum_server::Handle::new();
+    let shutdown_handle = handle.clone();
+    let mut stop_rx = stop_sx.subscribe();
+    tokio::spawn(async move {
+        if let Ok(true) = stop_rx.recv().await {
+            info!("Broker HTTP API Server stopped successfully");
+        }
+        shutdown_handle.graceful_shutdown(Some(Duration::from_secs(10)));
+    });
+
+    info!(
+        "Broker HTTP API Server start success. port:{}",
+        config.http_api.port
+    );
+    if let Err(e) = axum_server::bind(ip)
+        .handle(handle)
+        .serve(app.into_make_service())
+        .await
+    {
+        error!(
+            "Broker HTTP API Server failed to start, error message:{}",
+            e
+        );
+    }
+}
+
+pub fn routes(state: HttpServerState) -> Router {
+    let api = Router::new()
+        .route(ROUTE_CLUSTER_STATUS, get(cluster_status))
+        .route(ROUTE_USER, get(list_user).post(create_user))
+        .route(ROUTE_USER_NAME, delete(delete_user))
+        .route(ROUTE_ACL, get(list_acl).post(create_acl).delete(delete_acl))
+        .route(
+            ROUTE_BLACKLIST,
+            get(list_blacklist)
+                .post(create_blacklist)
+                .delete(delete_blacklist),
+        )
+        .route(ROUTE_CONNECTION, get(list_connection))
+        .route(ROUTE_TOPIC, get(list_topic))
+        .route(
+            ROUTE_TOPIC_REWRITE_RULE,
+            get(list_topic_rewrite_rule)
+                .post(create_topic_rewrite_rule)
+                .delete(delete_topic_rewrite_rule),
+        )
+        .route(
+            ROUTE_CONNECTOR,
+            get(list_connector)
+                .post(create_connector)
+                .put(update_connector),
+        )
+        .route(ROUTE_CONNECTOR_NAME, delete(delete_connector))
+        .route(
+            ROUTE_SCHEMA,
+            get(list_schema).post(create_schema).put(update_schema),
+        )
+        .route(ROUTE_SCHEMA_NAME, delete(delete_schema))
+        .route(
+            ROUTE_AUTO_SUBSCRIBE_RULE,
+            get(list_auto_subscribe_rule)
+                .post(set_auto_subscribe_rule_handler)
+                .delete(delete_auto_subscribe_rule_handler),
+        )
+        .route_layer(from_fn_with_state(state.clone(), api_key_auth));
+
+    Router::new()
+        .route(ROUTE_OPENAPI, get(openapi))
+        .merge(api)
+        .with_state(state)
+}
+
+async fn openapi() -> Json<Value> {
+    Json(openapi_document())
+}
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod cert_resolver;
 pub mod connection;
 pub mod connection_manager;
 pub mod grpc;
+pub mod http;
 pub mod packet;
 pub mod quic;
 pub mod tcp;
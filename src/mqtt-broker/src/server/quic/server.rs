@@ -15,7 +15,9 @@
 use crate::handler::cache::CacheManager;
 use crate::handler::command::Command;
 use crate::handler::error::MqttBrokerError;
+use crate::rule_engine::RuleEngine;
 use crate::security::AuthDriver;
+use crate::server::cert_resolver::TlsCertResolver;
 use crate::server::connection::NetworkConnectionType;
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::{RequestPackage, ResponsePackage};
@@ -27,6 +29,7 @@
 use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
 use log::info;
+use quinn::crypto::rustls::QuicServerConfig as QuicRustlsServerConfig;
 use quinn::{Connection, Endpoint, ServerConfig, VarInt};
 use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
 use rustls_pki_types::PrivateKeyDer;
@@ -49,10 +52,12 @@ pub async fn start_quic_server<S>(
     connection_manager: Arc<ConnectionManager>,
     message_storage_adapter: Arc<S>,
     delay_message_manager: Arc<DelayMessageManager<S>>,
+    rule_engine: Arc<RuleEngine<S>>,
     client_pool: Arc<ClientPool>,
     stop_sx: broadcast::Sender<bool>,
     auth_driver: Arc<AuthDriver>,
     schema_register_manager: Arc<SchemaRegisterManager>,
+    tls_cert_resolver: Arc<TlsCertResolver>,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
@@ -61,6 +66,7 @@ pub async fn start_quic_server<S>(
         cache_manager.clone(),
         message_storage_adapter.clone(),
         delay_message_manager.clone(),
+        rule_engine.clone(),
         subscribe_manager.clone(),
         client_pool.clone(),
         connection_manager.clone(),
@@ -68,10 +74,20 @@ pub async fn start_quic_server<S>(
         auth_driver.clone(),
     );
 
-    let mut server = QuicServer::new(SocketAddr::new(
-        IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
-        conf.network.quic_port as u16,
-    ));
+    let quic_crypto =
+        match QuicRustlsServerConfig::try_from(tls_cert_resolver.server_config(Vec::new())) {
+            Ok(crypto) => crypto,
+            Err(e) => {
+                panic!("Failed to create quic server config: {}", e)
+            }
+        };
+    let mut server = QuicServer::with_server_config(
+        SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            conf.network.quic_port as u16,
+        ),
+        ServerConfig::with_crypto(Arc::new(quic_crypto)),
+    );
     server.start();
 
     let quic_endpoint = server.get_endpoint();
@@ -176,6 +192,16 @@ pub fn new(addr: SocketAddr) -> Self {
         }
     }
 
+    pub fn with_server_config(addr: SocketAddr, server_config: ServerConfig) -> Self {
+        QuicServer {
+            quic_server_config: QuicServerConfig {
+                server_config,
+                bind_addr: addr,
+            },
+            endpoint: None,
+        }
+    }
+
     pub fn start(&mut self) {
         let endpoint = self.create_quinn_endpoint_as_a_quic_server();
         self.bind_address_for_quic_server_config(endpoint);
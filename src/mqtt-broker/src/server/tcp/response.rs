@@ -17,7 +17,9 @@
 
 use crate::handler::cache::CacheManager;
 use crate::handler::connection::disconnect_connection;
-use crate::observability::metrics::server::{metrics_request_queue, metrics_response_queue};
+use crate::observability::metrics::server::{
+    metrics_remove_network_queue, metrics_request_queue, metrics_response_queue,
+};
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::ResponsePackage;
 use crate::subscribe::subscribe_manager::SubscribeManager;
@@ -59,6 +61,7 @@ pub(crate) async fn response_process(
                     if let Ok(flag) = val {
                         if flag {
                             debug!("{}","TCP Server response process thread stopped successfully.");
+                            metrics_remove_network_queue("request", "response-total");
                             break;
                         }
                     }
@@ -127,6 +130,7 @@ pub(crate) fn response_child_process(
                         if let Ok(flag) = val {
                             if flag {
                                 info!("TCP Server response process thread {index} stopped successfully.");
+                                metrics_remove_network_queue("response", &format!("handler-{}", index));
                                 break;
                             }
                         }
@@ -22,10 +22,13 @@
 use storage_adapter::storage::StorageAdapter;
 use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc};
+use tokio_rustls::TlsAcceptor;
 
 use crate::handler::cache::CacheManager;
 use crate::handler::command::Command;
+use crate::rule_engine::RuleEngine;
 use crate::security::AuthDriver;
+use crate::server::cert_resolver::TlsCertResolver;
 use crate::server::connection::NetworkConnectionType;
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::{RequestPackage, ResponsePackage};
@@ -42,10 +45,12 @@ pub async fn start_tcp_server<S>(
     connection_manager: Arc<ConnectionManager>,
     message_storage_adapter: Arc<S>,
     delay_message_manager: Arc<DelayMessageManager<S>>,
+    rule_engine: Arc<RuleEngine<S>>,
     schema_manager: Arc<SchemaRegisterManager>,
     client_pool: Arc<ClientPool>,
     stop_sx: broadcast::Sender<bool>,
     auth_driver: Arc<AuthDriver>,
+    tls_cert_resolver: Arc<TlsCertResolver>,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
@@ -54,6 +59,7 @@ pub async fn start_tcp_server<S>(
         cache_manager.clone(),
         message_storage_adapter.clone(),
         delay_message_manager.clone(),
+        rule_engine.clone(),
         subscribe_manager.clone(),
         client_pool.clone(),
         connection_manager.clone(),
@@ -87,7 +93,9 @@ pub async fn start_tcp_server<S>(
         cache_manager,
         client_pool,
     );
-    server.start_tls(conf.network.tcps_port).await;
+    server
+        .start_tls(conf.network.tcps_port, tls_cert_resolver)
+        .await;
 }
 
 // U: codec: encoder + decoder
@@ -187,7 +195,7 @@ pub async fn start(&mut self, port: u32) {
         info!("MQTT TCP Server started successfully, listening port: {port}");
     }
 
-    pub async fn start_tls(&mut self, port: u32) {
+    pub async fn start_tls(&mut self, port: u32, tls_cert_resolver: Arc<TlsCertResolver>) {
         let listener = match TcpListener::bind(format!("0.0.0.0:{}", port)).await {
             Ok(tl) => tl,
             Err(e) => {
@@ -198,6 +206,15 @@ pub async fn start_tls(&mut self, port: u32) {
         let (response_queue_sx, response_queue_rx) = mpsc::channel::<ResponsePackage>(1000);
 
         let arc_listener = Arc::new(listener);
+        let client_ca = broker_mqtt_conf().network.tls_client_ca.clone();
+        let server_config =
+            match tls_cert_resolver.server_config_with_client_ca(Vec::new(), &client_ca) {
+                Ok(config) => config,
+                Err(e) => {
+                    panic!("{}", e.to_string());
+                }
+            };
+        let tls_acceptor = TlsAcceptor::from(Arc::new(server_config));
 
         acceptor_tls_process(
             self.accept_thread_num,
@@ -206,6 +223,7 @@ pub async fn start_tls(&mut self, port: u32) {
             self.network_connection_type.clone(),
             self.connection_manager.clone(),
             request_queue_sx,
+            tls_acceptor,
         )
         .await;
 
@@ -13,7 +13,6 @@
 // limitations under the License.
 
 use std::net::SocketAddr;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use axum::extract::ws::{Message, WebSocket};
@@ -39,7 +38,9 @@
 
 use crate::handler::cache::CacheManager;
 use crate::handler::command::Command;
+use crate::rule_engine::RuleEngine;
 use crate::security::AuthDriver;
+use crate::server::cert_resolver::TlsCertResolver;
 use crate::server::connection::NetworkConnection;
 use crate::server::connection_manager::ConnectionManager;
 use crate::subscribe::subscribe_manager::SubscribeManager;
@@ -52,6 +53,7 @@ pub struct WebSocketServerState<S> {
     cache_manager: Arc<CacheManager>,
     message_storage_adapter: Arc<S>,
     delay_message_manager: Arc<DelayMessageManager<S>>,
+    rule_engine: Arc<RuleEngine<S>>,
     client_pool: Arc<ClientPool>,
     stop_sx: broadcast::Sender<bool>,
     connection_manager: Arc<ConnectionManager>,
@@ -70,6 +72,7 @@ pub fn new(
         connection_manager: Arc<ConnectionManager>,
         message_storage_adapter: Arc<S>,
         delay_message_manager: Arc<DelayMessageManager<S>>,
+        rule_engine: Arc<RuleEngine<S>>,
         schema_manager: Arc<SchemaRegisterManager>,
         client_pool: Arc<ClientPool>,
         auth_driver: Arc<AuthDriver>,
@@ -81,6 +84,7 @@ pub fn new(
             connection_manager,
             message_storage_adapter,
             delay_message_manager,
+            rule_engine,
             schema_manager,
             client_pool,
             auth_driver,
@@ -111,8 +115,10 @@ pub async fn websocket_server<S>(state: WebSocketServerState<S>)
     }
 }
 
-pub async fn websockets_server<S>(state: WebSocketServerState<S>)
-where
+pub async fn websockets_server<S>(
+    state: WebSocketServerState<S>,
+    tls_cert_resolver: Arc<TlsCertResolver>,
+) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
     let config = broker_mqtt_conf();
@@ -121,17 +127,9 @@ pub async fn websockets_server<S>(state: WebSocketServerState<S>)
         .unwrap();
     let app = routes_v1(state);
 
-    let tls_config = match RustlsConfig::from_pem_file(
-        PathBuf::from(config.network.tls_cert.clone()),
-        PathBuf::from(config.network.tls_key.clone()),
-    )
-    .await
-    {
-        Ok(cf) => cf,
-        Err(e) => {
-            panic!("{}", e.to_string());
-        }
-    };
+    let tls_config = RustlsConfig::from_config(Arc::new(
+        tls_cert_resolver.server_config(vec![b"h2".to_vec(), b"http/1.1".to_vec()]),
+    ));
 
     info!(
         "Broker WebSocket TLS Server start success. port:{}",
@@ -175,6 +173,7 @@ async fn ws_handler<S>(
         state.cache_manager.clone(),
         state.message_storage_adapter.clone(),
         state.delay_message_manager.clone(),
+        state.rule_engine.clone(),
         state.sucscribe_manager.clone(),
         state.client_pool.clone(),
         state.connection_manager.clone(),
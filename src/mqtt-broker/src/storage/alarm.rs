@@ -0,0 +1,72 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::placement::mqtt::call::{
+    placement_delete_alarm, placement_list_alarm, placement_set_alarm,
+};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::alarm::MqttAlarm;
+use protocol::placement_center::placement_center_mqtt::{
+    DeleteAlarmRequest, ListAlarmRequest, SetAlarmRequest,
+};
+
+use crate::handler::error::MqttBrokerError;
+
+pub struct AlarmStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl AlarmStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        AlarmStorage { client_pool }
+    }
+
+    pub async fn save_alarm(&self, alarm: &MqttAlarm) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = SetAlarmRequest {
+            cluster_name: config.cluster_name.clone(),
+            alarm_id: alarm.alarm_id.clone(),
+            alarm: alarm.encode(),
+        };
+        placement_set_alarm(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn list_alarm(&self) -> Result<Vec<MqttAlarm>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = ListAlarmRequest {
+            cluster_name: config.cluster_name.clone(),
+        };
+        let reply =
+            placement_list_alarm(&self.client_pool, &config.placement_center, request).await?;
+        let mut list = Vec::new();
+        for raw in reply.alarms {
+            list.push(MqttAlarm::decode(&raw)?);
+        }
+        Ok(list)
+    }
+
+    pub async fn delete_alarm(&self, alarm_id: &str) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = DeleteAlarmRequest {
+            cluster_name: config.cluster_name.clone(),
+            alarm_id: alarm_id.to_owned(),
+        };
+        placement_delete_alarm(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+}
@@ -17,18 +17,37 @@
 
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use common_base::error::common::CommonError;
+use common_base::telemetry::trace::{inject_context, start_span};
 use metadata_struct::adapter::read_config::ReadConfig;
 use metadata_struct::adapter::record::Record;
+use opentelemetry::trace::{SpanKind, TraceContextExt};
+use opentelemetry::Context;
 use storage_adapter::storage::StorageAdapter;
 
+use crate::handler::tenant::split_tenant_mount;
+use crate::observability::warn::record_storage_write_failure;
+
 pub fn cluster_name() -> String {
     let conf = broker_mqtt_conf();
     conf.cluster_name.clone()
 }
 
+// Messages of a tenant topic are kept in a storage namespace of their own
+pub fn topic_storage_namespace(topic_name: &str) -> String {
+    if let Some((tenant, _)) = split_tenant_mount(topic_name) {
+        return tenant_storage_namespace(&tenant);
+    }
+    cluster_name()
+}
+
+pub fn tenant_storage_namespace(tenant: &str) -> String {
+    format!("{}_{}", cluster_name(), tenant)
+}
+
 #[derive(Clone)]
 pub struct MessageStorage<T> {
     storage_adapter: Arc<T>,
+    namespace: String,
 }
 
 impl<T> MessageStorage<T>
@@ -36,21 +55,56 @@ impl<T> MessageStorage<T>
     T: StorageAdapter + Send + Sync + 'static,
 {
     pub fn new(storage_adapter: Arc<T>) -> Self {
-        MessageStorage { storage_adapter }
+        MessageStorage {
+            storage_adapter,
+            namespace: cluster_name(),
+        }
+    }
+
+    pub fn new_by_topic(storage_adapter: Arc<T>, topic_name: &str) -> Self {
+        MessageStorage {
+            storage_adapter,
+            namespace: topic_storage_namespace(topic_name),
+        }
     }
 
     pub async fn append_topic_message(
         &self,
         topic_id: &str,
-        record: Vec<Record>,
+        mut record: Vec<Record>,
     ) -> Result<Vec<u64>, CommonError> {
         let shard_name = topic_id;
-        let namespace = cluster_name();
-        let results = self
+        let namespace = self.namespace.clone();
+
+        let trace_cx = start_span(
+            "robustmq/storage",
+            "storage/append",
+            SpanKind::Client,
+            &Context::current(),
+            "",
+        );
+        if let Some(cx) = &trace_cx {
+            let headers = inject_context(cx);
+            for raw in record.iter_mut() {
+                raw.merge_header(headers.clone());
+            }
+        }
+
+        let result = match self
             .storage_adapter
             .batch_write(namespace, shard_name.to_owned(), record)
-            .await?;
-        Ok(results)
+            .await
+        {
+            Ok(results) => Ok(results),
+            Err(e) => {
+                record_storage_write_failure();
+                Err(e)
+            }
+        };
+        if let Some(cx) = trace_cx {
+            cx.span().end();
+        }
+        result
     }
 
     pub async fn read_topic_message(
@@ -60,7 +114,7 @@ pub async fn read_topic_message(
         record_num: u64,
     ) -> Result<Vec<Record>, CommonError> {
         let shard_name = topic_id;
-        let namespace = cluster_name();
+        let namespace = self.namespace.clone();
         let mut read_config = ReadConfig::new();
         read_config.max_record_num = record_num;
 
@@ -76,6 +130,18 @@ pub async fn read_topic_message(
         Ok(records)
     }
 
+    pub async fn get_topic_end_offset(&self, topic_id: &str) -> Result<u64, CommonError> {
+        self.storage_adapter
+            .get_end_offset(self.namespace.clone(), topic_id.to_owned())
+            .await
+    }
+
+    pub async fn get_topic_size(&self, topic_id: &str) -> Result<u64, CommonError> {
+        self.storage_adapter
+            .get_shard_size(self.namespace.clone(), topic_id.to_owned())
+            .await
+    }
+
     pub async fn get_group_offset(&self, group_id: &str) -> Result<u64, CommonError> {
         let offset_data = self
             .storage_adapter
@@ -95,7 +161,7 @@ pub async fn commit_group_offset(
         offset: u64,
     ) -> Result<(), CommonError> {
         let shard_name = topic_id;
-        let namespace = cluster_name();
+        let namespace = self.namespace.clone();
 
         let mut offset_data = HashMap::new();
         offset_data.insert(shard_name.to_owned(), offset);
@@ -13,12 +13,16 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod alarm;
 pub mod auto_subscribe;
 pub mod blacklist;
 pub mod cluster;
 pub mod connector;
 pub mod message;
+pub mod retain;
+pub mod rule;
 pub mod schema;
 pub mod session;
+pub mod tenant;
 pub mod topic;
 pub mod user;
@@ -0,0 +1,315 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use log::info;
+use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::message::MqttMessage;
+use serde::{Deserialize, Serialize};
+use storage_adapter::storage::{ShardInfo, StorageAdapter};
+
+use super::message::cluster_name;
+use crate::handler::retain_index::RetainIndexEntry;
+
+pub const RETAIN_MESSAGE_SHARD_NAME: &str = "$retain-message";
+pub const RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME: &str = "$retain-message-snapshot";
+const RETAIN_MESSAGE_SNAPSHOT_GROUP: &str = "$retain-message-snapshot";
+const RETAIN_MESSAGE_MIGRATION_GROUP: &str = "$retain-message-migration";
+const RETAIN_MESSAGE_SNAPSHOT_CHUNK_SIZE: usize = 1000;
+
+// Snapshot of the retained message index, so that a broker does not have to replay
+// the retain message shard from the start. The entries are stored in chunk records
+// of the snapshot shard, and the offset of this header is committed as the latest
+// snapshot once every chunk has been written.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetainMessageSnapshot {
+    // The snapshot covers every record of the retain message shard before this offset
+    pub next_offset: u64,
+    pub chunks: Vec<u64>,
+}
+
+// Retained messages are appended to a dedicated shard keyed by topic name. The
+// latest record of a topic wins, and a record with an empty payload deletes it.
+pub struct RetainMessageStorage<S> {
+    message_storage_adapter: Arc<S>,
+}
+
+impl<S> RetainMessageStorage<S>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    pub fn new(message_storage_adapter: Arc<S>) -> Self {
+        RetainMessageStorage {
+            message_storage_adapter,
+        }
+    }
+
+    pub async fn init_shard(&self) -> Result<(), CommonError> {
+        let namespace = cluster_name();
+        for shard_name in [
+            RETAIN_MESSAGE_SHARD_NAME,
+            RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME,
+        ] {
+            let results = self
+                .message_storage_adapter
+                .list_shard(namespace.clone(), shard_name.to_string())
+                .await?;
+            if results.is_empty() {
+                let shard = ShardInfo {
+                    namespace: namespace.clone(),
+                    shard_name: shard_name.to_string(),
+                    replica_num: 1,
+                };
+                self.message_storage_adapter.create_shard(shard).await?;
+                info!("init shard:{}, {}", namespace, shard_name);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn set(&self, topic_name: &str, message: &MqttMessage) -> Result<u64, CommonError> {
+        let mut record = Record::build_byte(message.encode());
+        record.set_key(topic_name.to_string());
+        self.message_storage_adapter
+            .write(
+                cluster_name(),
+                RETAIN_MESSAGE_SHARD_NAME.to_string(),
+                record,
+            )
+            .await
+    }
+
+    pub async fn delete(&self, topic_name: &str) -> Result<u64, CommonError> {
+        let mut record = Record::build_byte(Vec::new());
+        record.set_key(topic_name.to_string());
+        self.message_storage_adapter
+            .write(
+                cluster_name(),
+                RETAIN_MESSAGE_SHARD_NAME.to_string(),
+                record,
+            )
+            .await
+    }
+
+    pub async fn get(&self, offset: u64) -> Result<Option<MqttMessage>, CommonError> {
+        let Some(record) = self.read_one(RETAIN_MESSAGE_SHARD_NAME, offset).await? else {
+            return Ok(None);
+        };
+        if record.data.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(MqttMessage::decode_record(record)?))
+    }
+
+    pub async fn save_snapshot(
+        &self,
+        next_offset: u64,
+        entries: Vec<(String, RetainIndexEntry)>,
+    ) -> Result<RetainMessageSnapshot, CommonError> {
+        let mut records = Vec::new();
+        for chunk in entries.chunks(RETAIN_MESSAGE_SNAPSHOT_CHUNK_SIZE) {
+            records.push(Record::build_byte(serde_json::to_vec(chunk)?));
+        }
+        let chunks = if records.is_empty() {
+            Vec::new()
+        } else {
+            self.message_storage_adapter
+                .batch_write(
+                    cluster_name(),
+                    RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME.to_string(),
+                    records,
+                )
+                .await?
+        };
+
+        let snapshot = RetainMessageSnapshot {
+            next_offset,
+            chunks,
+        };
+        let offset = self
+            .message_storage_adapter
+            .write(
+                cluster_name(),
+                RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME.to_string(),
+                Record::build_byte(serde_json::to_vec(&snapshot)?),
+            )
+            .await?;
+
+        let mut offsets = HashMap::new();
+        offsets.insert(RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME.to_string(), offset);
+        self.message_storage_adapter
+            .commit_offset(
+                RETAIN_MESSAGE_SNAPSHOT_GROUP.to_string(),
+                cluster_name(),
+                offsets,
+            )
+            .await?;
+        Ok(snapshot)
+    }
+
+    pub async fn latest_snapshot(&self) -> Result<Option<RetainMessageSnapshot>, CommonError> {
+        let offsets = self
+            .message_storage_adapter
+            .get_offset_by_group(RETAIN_MESSAGE_SNAPSHOT_GROUP.to_string())
+            .await?;
+        let Some(offset) = offsets.iter().map(|offset| offset.offset).max() else {
+            return Ok(None);
+        };
+        let Some(record) = self
+            .read_one(RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME, offset)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&record.data)?))
+    }
+
+    pub async fn read_snapshot(
+        &self,
+        snapshot: &RetainMessageSnapshot,
+    ) -> Result<Vec<(String, RetainIndexEntry)>, CommonError> {
+        let mut entries = Vec::new();
+        for offset in snapshot.chunks.iter() {
+            let Some(record) = self
+                .read_one(RETAIN_MESSAGE_SNAPSHOT_SHARD_NAME, *offset)
+                .await?
+            else {
+                return Err(CommonError::CommonError(format!(
+                    "Retain message snapshot chunk {} does not exist",
+                    offset
+                )));
+            };
+            entries.extend(serde_json::from_slice::<Vec<(String, RetainIndexEntry)>>(
+                &record.data,
+            )?);
+        }
+        Ok(entries)
+    }
+
+    pub async fn is_migrated(&self) -> Result<bool, CommonError> {
+        let offsets = self
+            .message_storage_adapter
+            .get_offset_by_group(RETAIN_MESSAGE_MIGRATION_GROUP.to_string())
+            .await?;
+        Ok(!offsets.is_empty())
+    }
+
+    pub async fn set_migrated(&self) -> Result<(), CommonError> {
+        let mut offsets = HashMap::new();
+        offsets.insert(RETAIN_MESSAGE_SHARD_NAME.to_string(), 1);
+        self.message_storage_adapter
+            .commit_offset(
+                RETAIN_MESSAGE_MIGRATION_GROUP.to_string(),
+                cluster_name(),
+                offsets,
+            )
+            .await
+    }
+
+    async fn read_one(&self, shard_name: &str, offset: u64) -> Result<Option<Record>, CommonError> {
+        let mut read_config = ReadConfig::new();
+        read_config.max_record_num = 1;
+        let records = self
+            .message_storage_adapter
+            .read_by_offset(cluster_name(), shard_name.to_string(), offset, read_config)
+            .await?;
+        let Some(record) = records.into_iter().next() else {
+            return Ok(None);
+        };
+        if record.offset != Some(offset) {
+            return Ok(None);
+        }
+        if !record.crc32_check() {
+            return Err(CommonError::CrcCheckByMessage);
+        }
+        Ok(Some(record))
+    }
+
+    pub async fn read(
+        &self,
+        offset: u64,
+        read_config: ReadConfig,
+    ) -> Result<Vec<Record>, CommonError> {
+        let records = self
+            .message_storage_adapter
+            .read_by_offset(
+                cluster_name(),
+                RETAIN_MESSAGE_SHARD_NAME.to_string(),
+                offset,
+                read_config,
+            )
+            .await?;
+        for record in records.iter() {
+            if !record.crc32_check() {
+                return Err(CommonError::CrcCheckByMessage);
+            }
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::broker_mqtt::init_broker_mqtt_conf_by_path;
+    use storage_adapter::memory::MemoryStorageAdapter;
+
+    use super::RetainMessageStorage;
+    use crate::handler::retain_index::RetainIndexEntry;
+
+    #[tokio::test]
+    async fn retain_message_snapshot_test() {
+        let path = format!(
+            "{}/../../config/mqtt-server.toml",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        init_broker_mqtt_conf_by_path(&path);
+
+        let storage = RetainMessageStorage::new(Arc::new(MemoryStorageAdapter::new()));
+        storage.init_shard().await.unwrap();
+        assert!(storage.latest_snapshot().await.unwrap().is_none());
+        assert!(!storage.is_migrated().await.unwrap());
+
+        let entries: Vec<(String, RetainIndexEntry)> = (0..2500)
+            .map(|i| {
+                (
+                    format!("t/{}", i),
+                    RetainIndexEntry {
+                        offset: i,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        storage.save_snapshot(2500, entries.clone()).await.unwrap();
+        storage
+            .save_snapshot(3000, entries[..10].to_vec())
+            .await
+            .unwrap();
+
+        let snapshot = storage.latest_snapshot().await.unwrap().unwrap();
+        assert_eq!(snapshot.next_offset, 3000);
+        assert_eq!(
+            storage.read_snapshot(&snapshot).await.unwrap(),
+            entries[..10].to_vec()
+        );
+
+        storage.set_migrated().await.unwrap();
+        assert!(storage.is_migrated().await.unwrap());
+    }
+}
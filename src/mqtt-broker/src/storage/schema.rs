@@ -16,25 +16,26 @@
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use grpc_clients::{
     placement::inner::call::{
-        bind_schema, create_schema, delete_schema, list_bind_schema, list_schema, un_bind_schema,
-        update_schema,
+        bind_schema, create_schema, delete_schema, get_schema, list_bind_schema, list_schema,
+        list_schema_version, un_bind_schema, update_schema,
     },
     pool::ClientPool,
 };
-use metadata_struct::schema::{SchemaData, SchemaType};
+use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
 use protocol::broker_mqtt::broker_mqtt_admin::{
     MqttBindSchemaReply, MqttCreateSchemaReply, MqttDeleteSchemaReply, MqttListBindSchemaReply,
-    MqttListSchemaReply, MqttUnbindSchemaReply, MqttUpdateSchemaReply,
+    MqttListSchemaReply, MqttListSchemaVersionReply, MqttUnbindSchemaReply, MqttUpdateSchemaReply,
 };
 use protocol::{
     broker_mqtt::broker_mqtt_admin::{
         MqttBindSchemaRequest, MqttCreateSchemaRequest, MqttDeleteSchemaRequest,
-        MqttListBindSchemaRequest, MqttListSchemaRequest, MqttUnbindSchemaRequest,
-        MqttUpdateSchemaRequest,
+        MqttListBindSchemaRequest, MqttListSchemaRequest, MqttListSchemaVersionRequest,
+        MqttUnbindSchemaRequest, MqttUpdateSchemaRequest,
     },
     placement_center::placement_center_inner::{
-        BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, ListBindSchemaRequest,
-        ListSchemaRequest, UnBindSchemaRequest, UpdateSchemaRequest,
+        BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, GetSchemaRequest,
+        ListBindSchemaRequest, ListSchemaRequest, ListSchemaVersionRequest, UnBindSchemaRequest,
+        UpdateSchemaRequest,
     },
 };
 use std::sync::Arc;
@@ -56,6 +57,24 @@ pub async fn list_schema_by_req(
     Ok(Response::new(MqttListSchemaReply { schemas }))
 }
 
+pub async fn list_schema_version_by_req(
+    client_pool: &Arc<ClientPool>,
+    request: Request<MqttListSchemaVersionRequest>,
+) -> Result<Response<MqttListSchemaVersionReply>, Status> {
+    let req = request.into_inner();
+    let config = broker_mqtt_conf();
+    let request = ListSchemaVersionRequest {
+        cluster_name: config.cluster_name.clone(),
+        schema_name: req.schema_name.clone(),
+        version: req.version,
+    };
+
+    let schemas = list_schema_version(client_pool, &config.placement_center, request)
+        .await?
+        .schemas;
+    Ok(Response::new(MqttListSchemaVersionReply { schemas }))
+}
+
 pub async fn create_schema_by_req(
     client_pool: &Arc<ClientPool>,
     request: Request<MqttCreateSchemaRequest>,
@@ -74,12 +93,20 @@ pub async fn create_schema_by_req(
         }
     };
 
+    let compatibility = match parse_schema_compatibility(&req.compatibility) {
+        Ok(compatibility) => compatibility,
+        Err(e) => return Err(Status::cancelled(e.to_string())),
+    };
+
+    // the version is assigned by the placement center
     let schema_data = SchemaData {
         cluster_name: config.cluster_name.clone(),
         name: req.schema_name.clone(),
         schema_type,
         schema: req.schema.clone(),
         desc: req.desc.clone(),
+        version: 0,
+        compatibility,
     };
 
     let request = CreateSchemaRequest {
@@ -110,12 +137,25 @@ pub async fn update_schema_by_req(
         }
     };
 
+    // an update keeps the level stored on the schema unless it sets one
+    let compatibility = if req.compatibility.is_empty() {
+        current_schema_compatibility(client_pool, &req.schema_name).await?
+    } else {
+        match parse_schema_compatibility(&req.compatibility) {
+            Ok(compatibility) => compatibility,
+            Err(e) => return Err(Status::cancelled(e.to_string())),
+        }
+    };
+
+    // the version is assigned by the placement center
     let schema_data = SchemaData {
         cluster_name: config.cluster_name.clone(),
         name: req.schema_name.clone(),
         schema_type,
         schema: req.schema.clone(),
         desc: req.desc.clone(),
+        version: 0,
+        compatibility,
     };
 
     let request = UpdateSchemaRequest {
@@ -173,6 +213,7 @@ pub async fn bind_schema_by_req(
         cluster_name: config.cluster_name.clone(),
         schema_name: req.schema_name.clone(),
         resource_name: req.resource_name.clone(),
+        schema_version: req.schema_version,
     };
 
     bind_schema(client_pool, &config.placement_center, request).await?;
@@ -195,3 +236,34 @@ pub async fn unbind_schema_by_req(
     un_bind_schema(client_pool, &config.placement_center, request).await?;
     Ok(Response::new(MqttUnbindSchemaReply::default()))
 }
+
+async fn current_schema_compatibility(
+    client_pool: &Arc<ClientPool>,
+    schema_name: &str,
+) -> Result<SchemaCompatibility, Status> {
+    let config = broker_mqtt_conf();
+    let request = GetSchemaRequest {
+        cluster_name: config.cluster_name.clone(),
+        schema_name: schema_name.to_string(),
+    };
+
+    let raw = get_schema(client_pool, &config.placement_center, request)
+        .await?
+        .schema;
+    match serde_json::from_slice::<SchemaData>(&raw) {
+        Ok(schema) => Ok(schema.compatibility),
+        Err(e) => Err(Status::cancelled(e.to_string())),
+    }
+}
+
+fn parse_schema_compatibility(compatibility: &str) -> Result<SchemaCompatibility, MqttBrokerError> {
+    match compatibility {
+        "" | "none" => Ok(SchemaCompatibility::NONE),
+        "backward" => Ok(SchemaCompatibility::BACKWARD),
+        "forward" => Ok(SchemaCompatibility::FORWARD),
+        "full" => Ok(SchemaCompatibility::FULL),
+        _ => Err(MqttBrokerError::InvalidSchemaCompatibility(
+            compatibility.to_string(),
+        )),
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::placement::kv::call::{
+    placement_get_prefix, placement_lease_grant, placement_lease_keep_alive, placement_set,
+};
+use grpc_clients::placement::mqtt::call::{
+    placement_create_tenant, placement_delete_tenant, placement_list_tenant,
+    placement_update_tenant,
+};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::tenant::MqttTenant;
+use protocol::placement_center::placement_center_kv::{
+    GetPrefixRequest, LeaseGrantRequest, LeaseKeepAliveRequest, SetRequest,
+};
+use protocol::placement_center::placement_center_mqtt::{
+    CreateTenantRequest, DeleteTenantRequest, ListTenantRequest, UpdateTenantRequest,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::handler::error::MqttBrokerError;
+
+// Tenant usage of one broker, the report is attached to a lease of the broker so that
+// the share of a broker that stopped expires with it
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct TenantUsageReport {
+    pub broker_id: u64,
+    // (tenant_name, TenantBrokerUsage)
+    pub tenants: HashMap<String, TenantBrokerUsage>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct TenantBrokerUsage {
+    pub connections: u64,
+    // Messages per second accepted over the last report interval
+    pub message_rate: u64,
+}
+
+fn tenant_usage_key(cluster_name: &str, broker_id: u64) -> String {
+    format!("{}{}", tenant_usage_key_prefix(cluster_name), broker_id)
+}
+
+fn tenant_usage_key_prefix(cluster_name: &str) -> String {
+    format!("/tenant_usage/{}/", cluster_name)
+}
+
+pub struct TenantStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl TenantStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        TenantStorage { client_pool }
+    }
+
+    pub async fn list_tenant(&self, tenant_name: &str) -> Result<Vec<MqttTenant>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = ListTenantRequest {
+            cluster_name: config.cluster_name.clone(),
+            tenant_name: tenant_name.to_owned(),
+        };
+        let reply =
+            placement_list_tenant(&self.client_pool, &config.placement_center, request).await?;
+        let mut list = Vec::new();
+        for raw in reply.tenants {
+            list.push(MqttTenant::decode(&raw)?);
+        }
+        Ok(list)
+    }
+
+    pub async fn list_all_tenants(&self) -> Result<Vec<MqttTenant>, MqttBrokerError> {
+        self.list_tenant("").await
+    }
+
+    pub async fn create_tenant(&self, tenant: MqttTenant) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = CreateTenantRequest {
+            cluster_name: config.cluster_name.clone(),
+            tenant_name: tenant.tenant_name.clone(),
+            tenant: tenant.encode(),
+        };
+        placement_create_tenant(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn update_tenant(&self, tenant: MqttTenant) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = UpdateTenantRequest {
+            cluster_name: config.cluster_name.clone(),
+            tenant_name: tenant.tenant_name.clone(),
+            tenant: tenant.encode(),
+        };
+        placement_update_tenant(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn delete_tenant(&self, tenant_name: &str) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = DeleteTenantRequest {
+            cluster_name: config.cluster_name.clone(),
+            tenant_name: tenant_name.to_owned(),
+        };
+        placement_delete_tenant(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn grant_usage_lease(&self, ttl: u64) -> Result<u64, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let reply = placement_lease_grant(
+            &self.client_pool,
+            &config.placement_center,
+            LeaseGrantRequest { ttl },
+        )
+        .await?;
+        Ok(reply.lease_id)
+    }
+
+    pub async fn keep_alive_usage_lease(&self, lease_id: u64) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        placement_lease_keep_alive(
+            &self.client_pool,
+            &config.placement_center,
+            LeaseKeepAliveRequest { lease_id },
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn save_usage_report(
+        &self,
+        lease_id: u64,
+        report: &TenantUsageReport,
+    ) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = SetRequest {
+            key: tenant_usage_key(&config.cluster_name, report.broker_id),
+            value: serde_json::to_string(report)?,
+            lease_id,
+        };
+        placement_set(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn list_usage_report(&self) -> Result<Vec<TenantUsageReport>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = GetPrefixRequest {
+            prefix: tenant_usage_key_prefix(&config.cluster_name),
+        };
+        let reply =
+            placement_get_prefix(&self.client_pool, &config.placement_center, request).await?;
+        let mut list = Vec::new();
+        for raw in reply.values {
+            list.push(serde_json::from_str::<TenantUsageReport>(&raw)?);
+        }
+        Ok(list)
+    }
+}
@@ -21,20 +21,22 @@
 use metadata_struct::adapter::record::Record;
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::{Publish, PublishProperties, QoS};
+use schema_register::schema::SchemaRegisterManager;
 use storage_adapter::storage::StorageAdapter;
 use tokio::select;
 use tokio::sync::broadcast::{self};
 use tokio::time::sleep;
 
 use super::sub_common::{
-    loop_commit_offset, min_qos, publish_message_qos, qos2_send_pubrel, wait_pub_ack,
-    wait_pub_comp, wait_pub_rec,
+    build_transcode_payload, loop_commit_offset, merge_record_trace_context, min_qos,
+    publish_message_qos, qos2_send_pubrel, wait_pub_ack, wait_pub_comp, wait_pub_rec,
 };
 use super::subscribe_manager::SubscribeManager;
 use super::subscriber::Subscriber;
 use crate::handler::cache::{CacheManager, QosAckPackageData, QosAckPacketInfo};
 use crate::handler::error::MqttBrokerError;
 use crate::handler::message::is_message_expire;
+use crate::handler::tenant::tenant_topic_unmount;
 use crate::server::connection_manager::ConnectionManager;
 use crate::storage::message::MessageStorage;
 use crate::subscribe::subscriber::SubPublishParam;
@@ -43,6 +45,7 @@ pub struct ExclusivePush<S> {
     cache_manager: Arc<CacheManager>,
     subscribe_manager: Arc<SubscribeManager>,
     connection_manager: Arc<ConnectionManager>,
+    schema_manager: Arc<SchemaRegisterManager>,
     message_storage: Arc<S>,
 }
 
@@ -55,12 +58,14 @@ pub fn new(
         cache_manager: Arc<CacheManager>,
         subscribe_manager: Arc<SubscribeManager>,
         connection_manager: Arc<ConnectionManager>,
+        schema_manager: Arc<SchemaRegisterManager>,
     ) -> Self {
         ExclusivePush {
             message_storage,
             cache_manager,
             subscribe_manager,
             connection_manager,
+            schema_manager,
         }
     }
 
@@ -112,10 +117,12 @@ async fn start_push_thread(&self) {
 
             let (sub_thread_stop_sx, mut sub_thread_stop_rx) = broadcast::channel(1);
 
-            let message_storage = MessageStorage::new(self.message_storage.clone());
+            let message_storage =
+                MessageStorage::new_by_topic(self.message_storage.clone(), &subscriber.topic_name);
             let cache_manager = self.cache_manager.clone();
             let connection_manager = self.connection_manager.clone();
             let subscribe_manager = self.subscribe_manager.clone();
+            let schema_manager = self.schema_manager.clone();
 
             // Subscribe to the data push thread
             self.subscribe_manager
@@ -162,6 +169,7 @@ async fn start_push_thread(&self) {
                                 &connection_manager,
                                 &message_storage,
                                 &cache_manager,
+                                &schema_manager,
                                 &subscriber,
                                 &group_id,
                                 &qos,
@@ -200,6 +208,7 @@ async fn pub_message<S>(
     connection_manager: &Arc<ConnectionManager>,
     message_storage: &MessageStorage<S>,
     cache_manager: &Arc<CacheManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
     subscriber: &Subscriber,
     group_id: &str,
     qos: &QoS,
@@ -231,6 +240,7 @@ async fn pub_message<S>(
             qos,
             subscriber,
             cache_manager,
+            schema_manager,
             sub_ids,
         )
         .await?
@@ -320,6 +330,7 @@ async fn build_pub_message(
     qos: &QoS,
     subscriber: &Subscriber,
     cache_manager: &Arc<CacheManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
     sub_ids: &[usize],
 ) -> Result<Option<SubPublishParam>, MqttBrokerError> {
     let msg = MqttMessage::decode_record(record.clone())?;
@@ -337,6 +348,13 @@ async fn build_pub_message(
         return Ok(None);
     }
 
+    let payload =
+        if let Some(payload) = build_transcode_payload(schema_manager, subscriber, msg.payload) {
+            payload
+        } else {
+            return Ok(None);
+        };
+
     let retain = if subscriber.preserve_retain {
         msg.retain
     } else {
@@ -348,17 +366,20 @@ async fn build_pub_message(
         qos: qos.to_owned(),
         pkid: 0,
         retain,
-        topic: Bytes::from(subscriber.topic_name.clone()),
-        payload: msg.payload,
+        topic: Bytes::from(tenant_topic_unmount(&subscriber.topic_name)),
+        payload,
     };
 
+    let mut user_properties = msg.user_properties;
+    merge_record_trace_context(&record, &mut user_properties);
+
     let properties = PublishProperties {
         payload_format_indicator: msg.format_indicator,
         message_expiry_interval: Some(msg.expiry_interval as u32),
         topic_alias: None,
         response_topic: msg.response_topic,
         correlation_data: msg.correlation_data,
-        user_properties: msg.user_properties,
+        user_properties,
         subscription_identifiers: sub_ids.into(),
         content_type: msg.content_type,
     };
@@ -374,7 +395,7 @@ async fn build_pub_message(
         subscriber.clone(),
         publish,
         Some(properties),
-        record.timestamp as u128,
+        record.timestamp_ms(),
         group_id.to_string(),
         pkid,
     );
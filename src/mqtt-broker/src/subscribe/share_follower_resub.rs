@@ -100,7 +100,7 @@ async fn start_resub_thread(&self) {
                         };
                         let _subscribe_properties = SubscribeProperties {
                             subscription_identifier: share_sub.subscription_identifier,
-                            user_properties: Vec::new(),
+                            user_properties: share_sub.user_properties,
                         };
                         // self.subscribe_manager
                         //     .add_subscribe0(
@@ -748,7 +748,7 @@ fn build_resub_subscribe_pkg(
 
     let subscribe_properties = SubscribeProperties {
         subscription_identifier: share_sub.subscription_identifier,
-        user_properties: Vec::new(),
+        user_properties: share_sub.user_properties,
     };
 
     MqttPacket::Subscribe(subscribe, Some(subscribe_properties))
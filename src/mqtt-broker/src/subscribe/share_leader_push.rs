@@ -20,19 +20,21 @@
 use log::{error, info};
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::{MqttPacket, MqttProtocol, Publish, PublishProperties, QoS};
+use schema_register::schema::SchemaRegisterManager;
 use storage_adapter::storage::StorageAdapter;
 use tokio::select;
 use tokio::sync::broadcast::{self, Sender};
 use tokio::time::sleep;
 
 use super::sub_common::{
-    loop_commit_offset, min_qos, publish_message_qos, publish_message_to_client, qos2_send_pubrel,
-    wait_packet_ack,
+    build_transcode_payload, loop_commit_offset, merge_record_trace_context, min_qos,
+    publish_message_qos, publish_message_to_client, qos2_send_pubrel, wait_packet_ack,
 };
 use super::subscribe_manager::{ShareLeaderSubscribeData, SubscribeManager};
 use crate::handler::cache::{CacheManager, QosAckPackageData, QosAckPackageType, QosAckPacketInfo};
 use crate::handler::error::MqttBrokerError;
 use crate::handler::message::is_message_expire;
+use crate::handler::tenant::tenant_topic_unmount;
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::ResponsePackage;
 use crate::storage::message::MessageStorage;
@@ -44,6 +46,7 @@ pub struct ShareLeaderPush<S> {
     message_storage: Arc<S>,
     connection_manager: Arc<ConnectionManager>,
     cache_manager: Arc<CacheManager>,
+    schema_manager: Arc<SchemaRegisterManager>,
 }
 
 impl<S> ShareLeaderPush<S>
@@ -55,12 +58,14 @@ pub fn new(
         message_storage: Arc<S>,
         connection_manager: Arc<ConnectionManager>,
         cache_manager: Arc<CacheManager>,
+        schema_manager: Arc<SchemaRegisterManager>,
     ) -> Self {
         ShareLeaderPush {
             subscribe_manager,
             message_storage,
             connection_manager,
             cache_manager,
+            schema_manager,
         }
     }
 
@@ -142,7 +147,8 @@ async fn push_by_round_robin(
         );
         let cursor_point = 0;
 
-        let message_storage = MessageStorage::new(self.message_storage.clone());
+        let message_storage =
+            MessageStorage::new_by_topic(self.message_storage.clone(), &sub_data.topic_name);
 
         // get current offset by group
         let mut offset = match message_storage.get_group_offset(&group_id).await {
@@ -160,6 +166,7 @@ async fn push_by_round_robin(
 
         let connection_manager = self.connection_manager.clone();
         let cache_manager = self.cache_manager.clone();
+        let schema_manager = self.schema_manager.clone();
 
         tokio::spawn(async move {
             info!(
@@ -186,6 +193,7 @@ async fn push_by_round_robin(
                     res = read_message_process(
                         &connection_manager,
                         &cache_manager,
+                        &schema_manager,
                         &message_storage,
                         &sub_data,
                         &sub_list,
@@ -232,6 +240,7 @@ async fn push_by_round_robin(
 async fn read_message_process<S>(
     connection_manager: &Arc<ConnectionManager>,
     cache_manager: &Arc<CacheManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
     message_storage: &MessageStorage<S>,
     sub_data: &ShareLeaderSubscribeData,
     sub_list: &[Subscriber],
@@ -274,9 +283,15 @@ async fn read_message_process<S>(
                 continue;
             };
 
-            if let Some((mut publish, properties)) =
-                build_publish(cache_manager, &subscribe, &sub_data.topic_name, &msg)
-            {
+            if let Some((mut publish, mut properties)) = build_publish(
+                cache_manager,
+                schema_manager,
+                &subscribe,
+                &sub_data.topic_name,
+                &msg,
+            ) {
+                merge_record_trace_context(record, &mut properties.user_properties);
+
                 let pkid = if publish.qos != QoS::AtMostOnce {
                     cache_manager.get_pkid(&subscribe.client_id).await
                 } else {
@@ -289,7 +304,7 @@ async fn read_message_process<S>(
                     subscribe.clone(),
                     publish,
                     Some(properties),
-                    record.timestamp as u128,
+                    record.timestamp_ms(),
                     group_id.to_owned(),
                     pkid,
                 );
@@ -424,6 +439,7 @@ async fn qos_publish<S>(
 
 fn build_publish(
     metadata_cache: &Arc<CacheManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
     subscribe: &Subscriber,
     topic_name: &str,
     msg: &MqttMessage,
@@ -441,13 +457,15 @@ fn build_publish(
         return None;
     }
 
+    let payload = build_transcode_payload(schema_manager, subscribe, msg.payload.clone())?;
+
     let publish = Publish {
         dup: false,
         qos,
         pkid: 0,
         retain,
-        topic: Bytes::from(topic_name.to_owned()),
-        payload: msg.payload.clone(),
+        topic: Bytes::from(tenant_topic_unmount(topic_name)),
+        payload,
     };
 
     let mut sub_ids = Vec::new();
@@ -12,31 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::ws::Message;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use common_base::error::common::CommonError;
+use common_base::telemetry::trace::{
+    extract_context, inject_context, merge_trace_context, start_span,
+};
 use common_base::tools::now_mills;
 use grpc_clients::placement::mqtt::call::placement_get_share_sub_leader;
 use grpc_clients::pool::ClientPool;
 use log::{error, warn};
+use metadata_struct::adapter::record::Record;
+use metadata_struct::schema::SchemaType;
+use opentelemetry::trace::{SpanKind, TraceContextExt};
+use opentelemetry::Context;
 use protocol::mqtt::codec::{MqttCodec, MqttPacketWrapper};
-use protocol::mqtt::common::{MqttPacket, MqttProtocol, PubRel, QoS};
+use protocol::mqtt::common::{MqttPacket, MqttProtocol, PubRel, QoS, SubscribeProperties};
 use protocol::placement_center::placement_center_mqtt::{
     GetShareSubLeaderReply, GetShareSubLeaderRequest,
 };
 use regex::Regex;
+use schema_register::schema::SchemaRegisterManager;
 use storage_adapter::storage::StorageAdapter;
 use tokio::select;
 use tokio::sync::broadcast::{self, Sender};
 use tokio::time::{sleep, timeout};
 
-use super::subscriber::SubPublishParam;
+use super::subscriber::{SubPublishParam, Subscriber};
 use crate::handler::cache::{CacheManager, QosAckPackageData, QosAckPackageType};
 use crate::handler::error::MqttBrokerError;
+use crate::handler::tenant::split_tenant_mount;
+use crate::observability::metrics::event_metrics::incr_subscribe_transcode_error_counter;
+use crate::observability::metrics::publish::metrics_message_delivery_latency;
 use crate::observability::slow::sub::{record_slow_sub_data, SlowSubData};
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::ResponsePackage;
@@ -46,6 +58,10 @@
 
 const QUEUE_SUB_PREFIX: &str = "$queue";
 
+const TRANSCODE_SUB_PREFIX: &str = "$transcode/";
+
+const TRANSCODE_USER_PROPERTY: &str = "transcode";
+
 pub fn path_contain_sub(_: &str) -> bool {
     true
 }
@@ -70,6 +86,9 @@ pub fn sub_path_validator(sub_path: String) -> bool {
 }
 
 pub fn path_regex_match(topic_name: &str, sub_path: &str) -> bool {
+    let sub_path = strip_transcode_prefix(sub_path);
+    let sub_path = sub_path.as_str();
+
     let path = if is_share_sub(sub_path) {
         let (_, group_path) = decode_share_info(sub_path);
         group_path
@@ -88,6 +107,15 @@ pub fn path_regex_match(topic_name: &str, sub_path: &str) -> bool {
         topic_name.to_owned()
     };
 
+    // Topics and filters only match within the same tenant
+    let (topic, path) = match (split_tenant_mount(&topic), split_tenant_mount(&path)) {
+        (None, None) => (topic, path),
+        (Some((topic_tenant, topic)), Some((path_tenant, path))) if topic_tenant == path_tenant => {
+            (topic, path)
+        }
+        _ => return false,
+    };
+
     // Path perfect matching
     if topic == path {
         return true;
@@ -159,6 +187,98 @@ pub fn decode_queue_info(sub_name: &str) -> String {
     format!("/{}", str_slice.join("/"))
 }
 
+pub fn is_transcode_sub(sub_name: &str) -> bool {
+    sub_name.starts_with(TRANSCODE_SUB_PREFIX)
+}
+
+// $transcode/{format}/{path}, the path keeps a $share or $queue prefix as it is
+pub fn decode_transcode_info(sub_name: &str) -> (String, String) {
+    let mut str_slice: Vec<&str> = sub_name.split("/").collect();
+    str_slice.remove(0);
+    let format = if str_slice.is_empty() {
+        "".to_string()
+    } else {
+        str_slice.remove(0).to_string()
+    };
+    let path = str_slice.join("/");
+    if is_share_sub(&path) || is_queue_sub(&path) {
+        return (format, path);
+    }
+    (format, format!("/{}", path))
+}
+
+pub fn strip_transcode_prefix(sub_name: &str) -> String {
+    if is_transcode_sub(sub_name) {
+        return decode_transcode_info(sub_name).1;
+    }
+    sub_name.to_string()
+}
+
+pub fn parse_transcode_format(format: &str) -> Option<SchemaType> {
+    match format.to_lowercase().as_str() {
+        "json" => Some(SchemaType::JSON),
+        "avro" => Some(SchemaType::AVRO),
+        "protobuf" => Some(SchemaType::PROTOBUF),
+        _ => None,
+    }
+}
+
+// The target format is taken from the $transcode prefix of the filter, or from the
+// "transcode" user property of the subscribe packet. An unknown format is an error so
+// that the subscription can be rejected instead of silently receiving raw payloads.
+pub fn build_sub_transcode(
+    sub_path: &str,
+    subscribe_properties: &Option<SubscribeProperties>,
+) -> Result<Option<SchemaType>, MqttBrokerError> {
+    let format = if is_transcode_sub(sub_path) {
+        Some(decode_transcode_info(sub_path).0)
+    } else if let Some(properties) = subscribe_properties {
+        properties
+            .user_properties
+            .iter()
+            .find(|(key, _)| key == TRANSCODE_USER_PROPERTY)
+            .map(|(_, value)| value.clone())
+    } else {
+        None
+    };
+
+    if let Some(format) = format {
+        return parse_transcode_format(&format)
+            .map(Some)
+            .ok_or(MqttBrokerError::InvalidSchemaType(format));
+    }
+    Ok(None)
+}
+
+// Returns None when the payload can not be transcoded for the subscriber, the error is
+// logged and counted per subscriber and the message is skipped for it.
+pub fn build_transcode_payload(
+    schema_manager: &Arc<SchemaRegisterManager>,
+    subscriber: &Subscriber,
+    payload: Bytes,
+) -> Option<Bytes> {
+    let target = if let Some(target) = &subscriber.transcode {
+        target
+    } else {
+        return Some(payload);
+    };
+
+    match schema_manager.transcode(&subscriber.topic_name, &payload, target) {
+        Ok(data) => Some(Bytes::from(data)),
+        Err(e) => {
+            incr_subscribe_transcode_error_counter(
+                subscriber.client_id.clone(),
+                subscriber.sub_path.clone(),
+            );
+            error!(
+                "Failed to transcode message to {} for client_id [{}], sub_path [{}], topic [{}], error message: {}",
+                target, subscriber.client_id, subscriber.sub_path, subscriber.topic_name, e
+            );
+            None
+        }
+    }
+}
+
 pub async fn get_share_sub_leader(
     client_pool: &Arc<ClientPool>,
     group_name: &String,
@@ -192,9 +312,11 @@ pub async fn publish_message_to_client(
     metadata_cache: &Arc<CacheManager>,
 ) -> Result<(), MqttBrokerError> {
     if let Some(protocol) = connection_manager.get_connect_protocol(resp.connection_id) {
+        let mut packet = resp.packet;
+        let trace_cx = start_push_span(sub_pub_param, &mut packet);
         let response: MqttPacketWrapper = MqttPacketWrapper {
             protocol_version: protocol.clone().into(),
-            packet: resp.packet,
+            packet,
         };
 
         if connection_manager.is_websocket(resp.connection_id) {
@@ -215,21 +337,57 @@ pub async fn publish_message_to_client(
                 .await?
         }
 
-        // record slow sub data
-        if metadata_cache.get_slow_sub_config().enable && sub_pub_param.create_time > 0 {
-            let slow_data = SlowSubData::build(
-                sub_pub_param.subscribe.sub_path.clone(),
-                sub_pub_param.subscribe.client_id.clone(),
-                sub_pub_param.subscribe.topic_name.clone(),
-                (now_mills() - sub_pub_param.create_time) as u64,
-            );
-            record_slow_sub_data(slow_data, metadata_cache.get_slow_sub_config().whole_ms)?;
+        if let Some(cx) = trace_cx {
+            cx.span().end();
+        }
+
+        if sub_pub_param.create_time_ms > 0 {
+            let latency_ms = now_mills().saturating_sub(sub_pub_param.create_time_ms);
+            metrics_message_delivery_latency(sub_pub_param.publish.qos, latency_ms as f64);
+
+            // record slow sub data
+            if metadata_cache.get_slow_sub_config().enable {
+                let slow_data = SlowSubData::build(
+                    sub_pub_param.subscribe.sub_path.clone(),
+                    sub_pub_param.subscribe.client_id.clone(),
+                    sub_pub_param.subscribe.topic_name.clone(),
+                    latency_ms as u64,
+                );
+                record_slow_sub_data(slow_data, metadata_cache.get_slow_sub_config().whole_ms)?;
+            }
         }
     }
 
     Ok(())
 }
 
+// Records written under a traced publish carry the storage span in their headers.
+pub fn merge_record_trace_context(record: &Record, user_properties: &mut Vec<(String, String)>) {
+    let cx = extract_context(&record.header_map());
+    merge_trace_context(user_properties, inject_context(&cx));
+}
+
+// Continue the trace carried in the message user properties, and hand the
+// delivery span on to the subscriber when the packet carries properties.
+fn start_push_span(sub_pub_param: &SubPublishParam, packet: &mut MqttPacket) -> Option<Context> {
+    let carrier: HashMap<String, String> = if let Some(properties) = &sub_pub_param.properties {
+        properties.user_properties.iter().cloned().collect()
+    } else {
+        HashMap::new()
+    };
+    let cx = start_span(
+        "robustmq/subscribe",
+        "subscribe/push",
+        SpanKind::Producer,
+        &extract_context(&carrier),
+        &sub_pub_param.subscribe.client_id,
+    )?;
+    if let MqttPacket::Publish(_, Some(properties)) = packet {
+        merge_trace_context(&mut properties.user_properties, inject_context(&cx));
+    }
+    Some(cx)
+}
+
 pub async fn wait_pub_ack(
     metadata_cache: &Arc<CacheManager>,
     connection_manager: &Arc<ConnectionManager>,
@@ -569,16 +727,22 @@ pub async fn publish_message_qos(
 mod tests {
     use std::sync::Arc;
 
+    use bytes::Bytes;
     use common_base::tools::unique_id;
     use grpc_clients::pool::ClientPool;
     use metadata_struct::mqtt::topic::MqttTopic;
-    use protocol::mqtt::common::QoS;
+    use metadata_struct::schema::SchemaType;
+    use protocol::mqtt::common::{QoS, SubscribeProperties};
+    use schema_register::schema::SchemaRegisterManager;
 
     use crate::handler::cache::CacheManager;
+    use crate::observability::metrics::event_metrics::get_subscribe_transcode_error_counter;
     use crate::subscribe::sub_common::{
-        decode_share_info, get_sub_topic_id_list, is_share_sub, min_qos, path_regex_match,
-        sub_path_validator,
+        build_sub_transcode, build_transcode_payload, decode_share_info, decode_transcode_info,
+        get_sub_topic_id_list, is_queue_sub, is_share_sub, is_transcode_sub, min_qos,
+        path_regex_match, strip_transcode_prefix, sub_path_validator,
     };
+    use crate::subscribe::subscriber::Subscriber;
 
     #[tokio::test]
     async fn is_share_sub_test() {
@@ -680,6 +844,19 @@ fn path_regex_match_test() {
         let topic_name = r"y/a/z/b".to_string(); 
         let sub_regex = r"y/+/z/#".to_string();
         assert!(path_regex_match(&topic_name, &sub_regex));
+
+        let topic_name = "$tenant/acme/sensor/1".to_string();
+        assert!(path_regex_match(&topic_name, "$tenant/acme/sensor/+"));
+        assert!(path_regex_match(&topic_name, "$tenant/acme/#"));
+        assert!(!path_regex_match(&topic_name, "$tenant/other/sensor/+"));
+        assert!(!path_regex_match(&topic_name, "#"));
+        assert!(!path_regex_match("sensor/1", "$tenant/acme/#"));
+
+        let topic_name = "$tenant/acme//sensor/1".to_string();
+        assert!(path_regex_match(
+            &topic_name,
+            "$share/groupname/$tenant/acme/sensor/+"
+        ));
     }
 
     #[test]
@@ -707,6 +884,95 @@ async fn get_sub_topic_list_test() {
         assert_eq!(result.first().unwrap().clone(), topic.topic_id);
     }
 
+    #[test]
+    fn transcode_sub_test() {
+        let (format, path) = decode_transcode_info("$transcode/json/sensors/#");
+        assert_eq!(format, "json".to_string());
+        assert_eq!(path, "/sensors/#".to_string());
+
+        assert!(path_regex_match("/sensors/1", "$transcode/json/sensors/#"));
+        assert!(!path_regex_match("/devices/1", "$transcode/json/sensors/#"));
+
+        assert_eq!(
+            build_sub_transcode("$transcode/json/sensors/#", &None).unwrap(),
+            Some(SchemaType::JSON)
+        );
+        assert!(build_sub_transcode("$transcode/xml/sensors/#", &None).is_err());
+        assert_eq!(build_sub_transcode("/sensors/#", &None).unwrap(), None);
+
+        assert!(!is_transcode_sub("$transcodejson/sensors/#"));
+        assert_eq!(
+            build_sub_transcode("$transcodejson/sensors/#", &None).unwrap(),
+            None
+        );
+
+        let share_path = "$transcode/json/$share/g1/sensors/#";
+        let (format, path) = decode_transcode_info(share_path);
+        assert_eq!(format, "json".to_string());
+        assert_eq!(path, "$share/g1/sensors/#".to_string());
+        assert!(is_share_sub(&strip_transcode_prefix(share_path)));
+        assert_eq!(
+            decode_share_info(&strip_transcode_prefix(share_path)),
+            ("g1".to_string(), "/sensors/#".to_string())
+        );
+        assert!(path_regex_match("/sensors/1", share_path));
+        assert!(!path_regex_match("/devices/1", share_path));
+        assert_eq!(
+            build_sub_transcode(share_path, &None).unwrap(),
+            Some(SchemaType::JSON)
+        );
+
+        let queue_path = "$transcode/avro/$queue/sensors/#";
+        assert!(is_queue_sub(&strip_transcode_prefix(queue_path)));
+        assert!(path_regex_match("/sensors/1", queue_path));
+        assert_eq!(
+            strip_transcode_prefix("/sensors/#"),
+            "/sensors/#".to_string()
+        );
+
+        let properties = SubscribeProperties {
+            subscription_identifier: None,
+            user_properties: vec![("transcode".to_string(), "avro".to_string())],
+        };
+        assert_eq!(
+            build_sub_transcode("/sensors/#", &Some(properties)).unwrap(),
+            Some(SchemaType::AVRO)
+        );
+
+        let properties = SubscribeProperties {
+            subscription_identifier: None,
+            user_properties: vec![("transcode".to_string(), "xml".to_string())],
+        };
+        assert!(build_sub_transcode("/sensors/#", &Some(properties)).is_err());
+    }
+
+    #[test]
+    fn transcode_payload_test() {
+        let schema_manager = Arc::new(SchemaRegisterManager::new());
+        let payload = Bytes::from(r#"{"a": 1}"#);
+
+        let mut subscriber = Subscriber {
+            client_id: "c1".to_string(),
+            topic_name: "/sensors/1".to_string(),
+            sub_path: "/sensors/#".to_string(),
+            ..Default::default()
+        };
+        let res = build_transcode_payload(&schema_manager, &subscriber, payload.clone());
+        assert_eq!(res, Some(payload.clone()));
+
+        subscriber.transcode = Some(SchemaType::JSON);
+        let res = build_transcode_payload(&schema_manager, &subscriber, payload.clone());
+        assert_eq!(res, Some(Bytes::from(r#"{"a":1}"#)));
+
+        subscriber.transcode = Some(SchemaType::AVRO);
+        let res = build_transcode_payload(&schema_manager, &subscriber, payload);
+        assert!(res.is_none());
+        assert_eq!(
+            get_subscribe_transcode_error_counter("c1".to_string(), "/sensors/#".to_string()),
+            1
+        );
+    }
+
     #[tokio::test]
     async fn path_validator_test() {
         let path = "/loboxu/test".to_string();
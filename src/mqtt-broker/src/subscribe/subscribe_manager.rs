@@ -28,6 +28,7 @@ pub struct ShareSubShareSub {
     pub packet_identifier: u16,
     pub filter: Filter,
     pub subscription_identifier: Option<usize>,
+    pub user_properties: Vec<(String, String)>,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use metadata_struct::schema::SchemaType;
 use protocol::mqtt::common::{Filter, MqttProtocol, QoS, RetainForwardRule, SubscribeProperties};
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +31,9 @@ pub struct Subscriber {
     pub preserve_retain: bool,
     pub retain_forward_rule: RetainForwardRule,
     pub subscription_identifier: Option<usize>,
+    // Target format of the payload, None means the payload is delivered as stored
+    #[serde(default)]
+    pub transcode: Option<SchemaType>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -44,7 +48,8 @@ pub(crate) struct SubPublishParam {
     pub subscribe: Subscriber,
     pub publish: Publish,
     pub properties: Option<PublishProperties>,
-    pub create_time: u128,
+    // Time the message was stored in milliseconds, 0 when it is unknown
+    pub create_time_ms: u128,
     pub pkid: u16,
     pub group_id: String,
 }
@@ -54,7 +59,7 @@ pub fn new(
         subscribe: Subscriber,
         publish: Publish,
         properties: Option<PublishProperties>,
-        create_time: u128,
+        create_time_ms: u128,
         group_id: String,
         pkid: u16,
     ) -> Self {
@@ -62,7 +67,7 @@ pub fn new(
             subscribe,
             publish,
             properties,
-            create_time,
+            create_time_ms,
             pkid,
             group_id,
         }
@@ -0,0 +1,516 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use common_base::tools::{now_second, unique_id};
+use log::info;
+use rocksdb::{BoundColumnFamily, WriteBatch};
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::PlacementCenterError;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::route::{read_record_field, write_record_field};
+use crate::storage::keys::{cluster_name_of_key, key_backup_staging_prefix};
+use crate::storage::rocksdb::{RocksDBEngine, DB_COLUMN_FAMILY_CLUSTER};
+
+pub const BACKUP_FORMAT_VERSION: u32 = 2;
+pub const BACKUP_IMPORT_BATCH_SIZE: usize = 500;
+const BACKUP_FILE_MAGIC: &[u8; 8] = b"RMQBAK01";
+const BACKUP_WRITE_BATCH_SIZE: usize = 1000;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BackupHeader {
+    pub version: u32,
+    // Name of the placement center cluster the backup was taken from
+    pub source_cluster: String,
+    // Set when only the metadata of one broker cluster was exported
+    pub cluster_name: Option<String>,
+    pub last_applied_index: Option<u64>,
+    pub create_time: u64,
+}
+
+impl BackupHeader {
+    pub fn new(
+        source_cluster: String,
+        cluster_name: Option<String>,
+        last_applied_index: Option<u64>,
+    ) -> Self {
+        BackupHeader {
+            version: BACKUP_FORMAT_VERSION,
+            source_cluster,
+            cluster_name,
+            last_applied_index,
+            create_time: now_second(),
+        }
+    }
+}
+
+// A batch of backup records imported into a running cluster through the raft log. Batches
+// are staged under a key prefix of their import, the last one swaps the staged records in
+// for the keys in the scope of the import with a single write. The first batch drops what
+// earlier imports that never finished left behind.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BackupImportBatch {
+    pub import_id: String,
+    pub records: Vec<(Vec<u8>, Vec<u8>)>,
+    pub finished: bool,
+    pub clear: bool,
+    pub cluster_name: Option<String>,
+    // Number of records the import stages in total, checked before the swap
+    pub total: u64,
+}
+
+/// Writes the backup file header followed by every record of the cluster column family,
+/// read from a RocksDB snapshot so the backup is a consistent view of the state machine.
+/// The file ends with a trailer holding the number of records, an empty key followed by
+/// the count, which lets readers detect a truncated file.
+pub fn write_backup(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    header: &BackupHeader,
+    writer: &mut impl Write,
+) -> Result<u64, PlacementCenterError> {
+    let cf = cluster_cf(rocksdb_engine_handler)?;
+    writer.write_all(BACKUP_FILE_MAGIC)?;
+    write_record_field(writer, &serde_json::to_vec(header)?)?;
+
+    let db_snapshot = rocksdb_engine_handler.db.snapshot();
+    let mut iter = db_snapshot.raw_iterator_cf(&cf);
+    iter.seek_to_first();
+    let mut records = 0;
+    while iter.valid() {
+        if let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+            if record_in_scope(key, header.cluster_name.as_deref()) {
+                write_record_field(writer, key)?;
+                write_record_field(writer, value)?;
+                records += 1;
+            }
+        }
+        iter.next();
+    }
+    iter.status()?;
+    write_record_field(writer, &[])?;
+    write_record_field(writer, &records.to_be_bytes())?;
+    writer.flush()?;
+    Ok(records)
+}
+
+/// Builds a backup into an anonymous temporary file and returns it rewound to the start,
+/// ready to be streamed to the caller.
+pub async fn build_backup_file(
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    header: BackupHeader,
+) -> Result<File, PlacementCenterError> {
+    tokio::task::spawn_blocking(move || {
+        let mut writer = BufWriter::new(tempfile::tempfile()?);
+        let records = write_backup(&rocksdb_engine_handler, &header, &mut writer)?;
+        let mut file = writer
+            .into_inner()
+            .map_err(|e| PlacementCenterError::IoError(e.into_error()))?;
+        file.seek(SeekFrom::Start(0))?;
+        info!(
+            "Backup built successfully, records: {}, header: {:?}",
+            records, header
+        );
+        Ok(file)
+    })
+    .await
+    .map_err(|e| PlacementCenterError::CommonError(e.to_string()))?
+}
+
+/// Imports a backup file into the running cluster, replacing the data in the scope of the
+/// import. The whole file is validated before anything is written, then the records are
+/// staged through the raft log in batches so that every node applies the same data, and
+/// the last batch swaps them in.
+pub async fn import_backup_file(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mut file: File,
+    cluster_name: Option<&str>,
+) -> Result<u64, PlacementCenterError> {
+    file.seek(SeekFrom::Start(0))?;
+    let total = validate_backup(BufReader::new(&file), cluster_name)?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = BackupReader::new(BufReader::new(file))?;
+    info!(
+        "Start importing backup, records: {}, header: {:?}",
+        total,
+        reader.header()
+    );
+
+    let import_id = unique_id();
+    let mut records = 0;
+    let mut clear = true;
+    loop {
+        let batch = reader.next_batch(BACKUP_IMPORT_BATCH_SIZE, cluster_name)?;
+        let finished = batch.len() < BACKUP_IMPORT_BATCH_SIZE;
+        let size = batch.len() as u64;
+        let import = BackupImportBatch {
+            import_id: import_id.clone(),
+            records: batch,
+            finished,
+            clear,
+            cluster_name: cluster_name.map(|name| name.to_string()),
+            total,
+        };
+        let data = StorageData::new(
+            StorageDataType::ClusterImportBackup,
+            bincode::serialize(&import)?,
+        );
+        // Nothing outside the staging area changes until the last batch, so a failed
+        // import leaves the cluster as it was and the restore can simply run again.
+        let resp = match raft_machine_apply.client_write(data).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                return Err(PlacementCenterError::BackupImportInterrupted(
+                    records,
+                    total,
+                    e.to_string(),
+                ));
+            }
+        };
+        records += size;
+        clear = false;
+        if finished {
+            if resp.and_then(|resp| resp.data.value).is_none() {
+                return Err(PlacementCenterError::BackupImportInterrupted(
+                    records,
+                    total,
+                    "the staged records were not swapped in".to_string(),
+                ));
+            }
+            return Ok(records);
+        }
+    }
+}
+
+/// Reads the whole backup once and returns the number of records in scope, so that a
+/// truncated or corrupt file is rejected before any of it is written.
+pub fn validate_backup(
+    reader: impl Read,
+    cluster_name: Option<&str>,
+) -> Result<u64, PlacementCenterError> {
+    let mut reader = BackupReader::new(reader)?;
+    let mut records = 0;
+    while let Some((key, _)) = reader.next_record()? {
+        if record_in_scope(&key, cluster_name) {
+            records += 1;
+        }
+    }
+    Ok(records)
+}
+
+// Staged records of an import are never part of a backup or of the scope of an import
+pub fn record_in_scope(key: &[u8], cluster_name: Option<&str>) -> bool {
+    if key.starts_with(key_backup_staging_prefix().as_bytes()) {
+        return false;
+    }
+    match cluster_name {
+        Some(cluster_name) => {
+            std::str::from_utf8(key).ok().and_then(cluster_name_of_key) == Some(cluster_name)
+        }
+        None => true,
+    }
+}
+
+pub struct BackupReader<R> {
+    reader: R,
+    header: BackupHeader,
+    records: u64,
+    finished: bool,
+}
+
+impl<R: Read> BackupReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, PlacementCenterError> {
+        let mut magic = [0u8; 8];
+        if reader.read_exact(&mut magic).is_err() || &magic != BACKUP_FILE_MAGIC {
+            return Err(PlacementCenterError::BackupFileInvalid(
+                "unknown file format".to_string(),
+            ));
+        }
+
+        let Some(data) = read_record_field(&mut reader)? else {
+            return Err(PlacementCenterError::BackupFileInvalid(
+                "missing backup header".to_string(),
+            ));
+        };
+        let header: BackupHeader = serde_json::from_slice(&data)?;
+        if header.version > BACKUP_FORMAT_VERSION {
+            return Err(PlacementCenterError::BackupVersionNotSupported(
+                header.version,
+                BACKUP_FORMAT_VERSION,
+            ));
+        }
+        Ok(BackupReader {
+            reader,
+            header,
+            records: 0,
+            finished: false,
+        })
+    }
+
+    pub fn header(&self) -> &BackupHeader {
+        &self.header
+    }
+
+    pub fn next_record(&mut self) -> Result<Option<(Vec<u8>, Vec<u8>)>, PlacementCenterError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let Some(key) = read_record_field(&mut self.reader)? else {
+            // Version 1 files have no trailer and simply end after the last record
+            if self.header.version < 2 {
+                self.finished = true;
+                return Ok(None);
+            }
+            return Err(PlacementCenterError::BackupFileInvalid(
+                "file is truncated".to_string(),
+            ));
+        };
+        let Some(value) = read_record_field(&mut self.reader)? else {
+            return Err(PlacementCenterError::BackupFileInvalid(
+                "file is truncated".to_string(),
+            ));
+        };
+
+        if key.is_empty() {
+            let count = <[u8; 8]>::try_from(value.as_slice())
+                .map(u64::from_be_bytes)
+                .map_err(|_| {
+                    PlacementCenterError::BackupFileInvalid("invalid trailer".to_string())
+                })?;
+            if count != self.records {
+                return Err(PlacementCenterError::BackupFileInvalid(format!(
+                    "expected {} records, found {}",
+                    count, self.records
+                )));
+            }
+            self.finished = true;
+            return Ok(None);
+        }
+
+        self.records += 1;
+        Ok(Some((key, value)))
+    }
+
+    /// Reads up to `size` records that belong to `cluster_name`, an empty batch means the
+    /// end of the file has been reached.
+    pub fn next_batch(
+        &mut self,
+        size: usize,
+        cluster_name: Option<&str>,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, PlacementCenterError> {
+        let mut records = Vec::with_capacity(size);
+        while records.len() < size {
+            let Some((key, value)) = self.next_record()? else {
+                break;
+            };
+            if record_in_scope(&key, cluster_name) {
+                records.push((key, value));
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Loads a backup file straight into the cluster column family. Only used to bootstrap a
+/// new node, so the column family is required to be empty. The file is validated first so
+/// that a bad file leaves the node untouched.
+pub fn restore_backup_file(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    path: &Path,
+    cluster_name: Option<&str>,
+) -> Result<u64, PlacementCenterError> {
+    let cf = cluster_cf(rocksdb_engine_handler)?;
+    let db = &rocksdb_engine_handler.db;
+
+    let mut iter = db.raw_iterator_cf(&cf);
+    iter.seek_to_first();
+    if iter.valid() {
+        return Err(PlacementCenterError::RestoreTargetNotEmpty(
+            DB_COLUMN_FAMILY_CLUSTER.to_string(),
+        ));
+    }
+    drop(iter);
+
+    validate_backup(BufReader::new(File::open(path)?), cluster_name)?;
+    let mut reader = BackupReader::new(BufReader::new(File::open(path)?))?;
+    info!(
+        "Start restoring backup {:?}, header: {:?}",
+        path,
+        reader.header()
+    );
+    let mut batch = WriteBatch::default();
+    let mut records = 0;
+    while let Some((key, value)) = reader.next_record()? {
+        if !record_in_scope(&key, cluster_name) {
+            continue;
+        }
+        batch.put_cf(&cf, key, value);
+        records += 1;
+        if batch.len() >= BACKUP_WRITE_BATCH_SIZE {
+            db.write(std::mem::take(&mut batch))?;
+        }
+    }
+    db.write(batch)?;
+    Ok(records)
+}
+
+fn cluster_cf(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+) -> Result<Arc<BoundColumnFamily>, PlacementCenterError> {
+    rocksdb_engine_handler
+        .cf_handle(DB_COLUMN_FAMILY_CLUSTER)
+        .ok_or_else(|| {
+            PlacementCenterError::RocksDBFamilyNotAvailable(DB_COLUMN_FAMILY_CLUSTER.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::BufWriter;
+    use std::sync::Arc;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::storage::keys::{key_cluster, storage_key_mqtt_user};
+
+    fn test_engine() -> Arc<RocksDBEngine> {
+        Arc::new(RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            vec![DB_COLUMN_FAMILY_CLUSTER.to_string()],
+        ))
+    }
+
+    #[test]
+    fn backup_and_restore_test() {
+        let rocksdb_engine = test_engine();
+        let cf = rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_CLUSTER).unwrap();
+        for cluster_name in ["c1", "c2"] {
+            rocksdb_engine
+                .write(
+                    cf.clone(),
+                    &key_cluster("MqttBrokerServer", cluster_name),
+                    &cluster_name.to_string(),
+                )
+                .unwrap();
+            rocksdb_engine
+                .write(
+                    cf.clone(),
+                    &storage_key_mqtt_user(cluster_name, "u1"),
+                    &"u1".to_string(),
+                )
+                .unwrap();
+        }
+        rocksdb_engine.write(cf.clone(), "kv-key", &1).unwrap();
+
+        let dir = tempdir().unwrap();
+        let full = dir.path().join("full.bak");
+        let header = BackupHeader::new("pc".to_string(), None, Some(10));
+        let mut writer = BufWriter::new(File::create(&full).unwrap());
+        assert_eq!(
+            write_backup(&rocksdb_engine, &header, &mut writer).unwrap(),
+            5
+        );
+        drop(writer);
+
+        let partial = dir.path().join("c1.bak");
+        let header = BackupHeader::new("pc".to_string(), Some("c1".to_string()), Some(10));
+        let mut writer = BufWriter::new(File::create(&partial).unwrap());
+        assert_eq!(
+            write_backup(&rocksdb_engine, &header, &mut writer).unwrap(),
+            2
+        );
+        drop(writer);
+
+        let reader = BackupReader::new(File::open(&partial).unwrap()).unwrap();
+        assert_eq!(reader.header(), &header);
+
+        let new_engine = test_engine();
+        assert_eq!(restore_backup_file(&new_engine, &full, None).unwrap(), 5);
+        let new_cf = new_engine.cf_handle(DB_COLUMN_FAMILY_CLUSTER).unwrap();
+        assert_eq!(
+            new_engine
+                .read::<String>(new_cf.clone(), &storage_key_mqtt_user("c2", "u1"))
+                .unwrap(),
+            Some("u1".to_string())
+        );
+
+        // A node that already holds data is never overwritten
+        assert!(restore_backup_file(&new_engine, &full, None).is_err());
+
+        let new_engine = test_engine();
+        assert_eq!(
+            restore_backup_file(&new_engine, &full, Some("c2")).unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn backup_truncated_test() {
+        let rocksdb_engine = test_engine();
+        let cf = rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_CLUSTER).unwrap();
+        for i in 0..3 {
+            rocksdb_engine
+                .write(cf.clone(), &storage_key_mqtt_user("c1", &i.to_string()), &i)
+                .unwrap();
+        }
+
+        let mut data = Vec::new();
+        let header = BackupHeader::new("pc".to_string(), None, None);
+        write_backup(&rocksdb_engine, &header, &mut data).unwrap();
+        assert_eq!(validate_backup(&data[..], None).unwrap(), 3);
+        assert_eq!(validate_backup(&data[..], Some("c2")).unwrap(), 0);
+
+        // Cut right after a record, before the trailer, and in the middle of a record
+        let trailer_len = 4 + 4 + 8;
+        let cut = data.len() - trailer_len;
+        assert!(validate_backup(&data[..cut], None).is_err());
+        assert!(validate_backup(&data[..cut - 3], None).is_err());
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("truncated.bak");
+        std::fs::write(&path, &data[..cut]).unwrap();
+        let new_engine = test_engine();
+        assert!(restore_backup_file(&new_engine, &path, None).is_err());
+        let new_cf = new_engine.cf_handle(DB_COLUMN_FAMILY_CLUSTER).unwrap();
+        let mut iter = new_engine.db.raw_iterator_cf(&new_cf);
+        iter.seek_to_first();
+        assert!(!iter.valid());
+    }
+
+    #[test]
+    fn backup_version_test() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("future.bak");
+        let mut header = BackupHeader::new("pc".to_string(), None, None);
+        header.version = BACKUP_FORMAT_VERSION + 1;
+        let mut file = File::create(&path).unwrap();
+        file.write_all(BACKUP_FILE_MAGIC).unwrap();
+        write_record_field(&mut file, &serde_json::to_vec(&header).unwrap()).unwrap();
+        drop(file);
+
+        assert!(matches!(
+            BackupReader::new(File::open(&path).unwrap()),
+            Err(PlacementCenterError::BackupVersionNotSupported(_, _))
+        ));
+        assert!(BackupReader::new(&b"not a backup"[..]).is_err());
+    }
+}
@@ -39,7 +39,7 @@ pub struct PlacementCacheManager {
 
 impl PlacementCacheManager {
     pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> PlacementCacheManager {
-        let mut cache = PlacementCacheManager {
+        let cache = PlacementCacheManager {
             cluster_list: DashMap::with_capacity(2),
             node_heartbeat: DashMap::with_capacity(2),
             node_list: DashMap::with_capacity(2),
@@ -155,7 +155,7 @@ pub fn get_broker_heart(&self, cluster_name: &str, node_id: u64) -> Option<NodeH
         None
     }
 
-    pub fn load_cache(&mut self, rocksdb_engine_handler: Arc<RocksDBEngine>) {
+    pub fn load_cache(&self, rocksdb_engine_handler: Arc<RocksDBEngine>) {
         let cluster = ClusterStorage::new(rocksdb_engine_handler.clone());
         if let Ok(result) = cluster.list(None) {
             for cluster in result {
@@ -131,6 +131,18 @@ pub enum PlacementCenterError {
     #[error("Connector [{0}] already exist")]
     ConnectorAlreadyExist(String),
 
+    #[error("Rule {0} Not found")]
+    RuleNotFound(String),
+
+    #[error("Rule [{0}] already exist")]
+    RuleAlreadyExist(String),
+
+    #[error("Tenant {0} Not found")]
+    TenantNotFound(String),
+
+    #[error("Tenant [{0}] already exist")]
+    TenantAlreadyExist(String),
+
     #[error("Schema [{0}] does not exist")]
     SchemaDoesNotExist(String),
 
@@ -145,4 +157,34 @@ pub enum PlacementCenterError {
 
     #[error("Schema [{0}] already exist")]
     SchemaAlreadyExist(String),
+
+    #[error("Schema {0} version {1} does not exist")]
+    SchemaVersionDoesNotExist(String, u64),
+
+    #[error("{0}")]
+    SchemaIncompatible(String),
+
+    #[error("Lease {0} does not exist")]
+    KvLeaseDoesNotExist(u64),
+
+    #[error("Key {0} is reserved for the KV service and can not be used")]
+    KvReservedKey(String),
+
+    #[error("Watch start revision {0} has been compacted, compacted revision is {1}")]
+    KvWatchRevisionCompacted(u64, u64),
+
+    #[error("Backup file is invalid, {0}")]
+    BackupFileInvalid(String),
+
+    #[error("Backup format version {0} is not supported, the newest supported version is {1}")]
+    BackupVersionNotSupported(u32, u32),
+
+    #[error("Restore requires an empty data directory, but {0} already contains data")]
+    RestoreTargetNotEmpty(String),
+
+    #[error("Backup import stopped after {0} of {1} records, run the restore again: {2}")]
+    BackupImportInterrupted(u64, u64, String),
+
+    #[error("Backup import {0} staged {1} of {2} records, run the restore again")]
+    BackupImportIncomplete(String, u64, u64),
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_base::metrics::registry::latency_ms_buckets;
 use prometheus_client::encoding::EncodeLabelSet;
 
 #[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
@@ -19,6 +20,11 @@ pub struct GrpcMethodLabel {
     pub method: String,
 }
 
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+pub struct RaftDataTypeLabel {
+    pub data_type: String,
+}
+
 common_base::register_counter_metric!(
     GRPC_REQUEST_NUM,
     "grpc_request_num",
@@ -33,4 +39,32 @@ pub fn metrics_grpc_request_incr(method: &str) {
     common_base::gauge_metric_inc!(GRPC_REQUEST_NUM, label)
 }
 
-pub fn metrics_grpc_request_ms(_: u128) {}
+common_base::register_histogram_metric!(
+    GRPC_REQUEST_LATENCY_MS,
+    "grpc_request_latency_ms",
+    "Latency of grpc requests in milliseconds",
+    GrpcMethodLabel,
+    latency_ms_buckets()
+);
+
+common_base::register_histogram_metric!(
+    RAFT_APPLY_LATENCY_MS,
+    "raft_apply_latency_ms",
+    "Latency of applying a committed raft entry to the state machine in milliseconds",
+    RaftDataTypeLabel,
+    latency_ms_buckets()
+);
+
+pub fn metrics_grpc_request_ms(ms: u128) {
+    let label = GrpcMethodLabel {
+        method: "all".to_string(),
+    };
+    common_base::histogram_metric_observe!(GRPC_REQUEST_LATENCY_MS, label, ms as f64)
+}
+
+pub fn metrics_raft_apply_ms(data_type: &str, ms: f64) {
+    let label = RaftDataTypeLabel {
+        data_type: data_type.to_string(),
+    };
+    common_base::histogram_metric_observe!(RAFT_APPLY_LATENCY_MS, label, ms)
+}
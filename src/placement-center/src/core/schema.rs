@@ -20,6 +20,7 @@
     },
     route::{
         apply::RaftMachineApply,
+        common::SchemaUpdateResult,
         data::{StorageData, StorageDataType},
     },
     storage::placement::schema::SchemaStorage,
@@ -29,8 +30,9 @@
 use prost::Message;
 use prost_validate::Result;
 use protocol::placement_center::placement_center_inner::{
-    BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, ListBindSchemaRequest,
-    ListSchemaRequest, UnBindSchemaRequest, UpdateSchemaRequest,
+    BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, GetSchemaRequest,
+    ListBindSchemaRequest, ListSchemaRequest, ListSchemaVersionRequest, UnBindSchemaRequest,
+    UpdateSchemaRequest,
 };
 use rocksdb_engine::RocksDBEngine;
 use std::sync::Arc;
@@ -61,6 +63,68 @@ pub fn list_schema_req(
     Ok(results)
 }
 
+pub fn get_schema_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &GetSchemaRequest,
+) -> Result<Vec<u8>, PlacementCenterError> {
+    if req.cluster_name.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "cluster_name".to_string(),
+        ));
+    }
+
+    if req.schema_name.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "schema_name".to_string(),
+        ));
+    }
+
+    let schema_storage = SchemaStorage::new(rocksdb_engine_handler.clone());
+    if let Some(data) = schema_storage.get(&req.cluster_name, &req.schema_name)? {
+        Ok(data.encode())
+    } else {
+        Err(PlacementCenterError::SchemaNotFound(
+            req.schema_name.clone(),
+        ))
+    }
+}
+
+pub fn list_schema_version_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &ListSchemaVersionRequest,
+) -> Result<Vec<Vec<u8>>, PlacementCenterError> {
+    if req.cluster_name.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "cluster_name".to_string(),
+        ));
+    }
+
+    if req.schema_name.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "schema_name".to_string(),
+        ));
+    }
+
+    let schema_storage = SchemaStorage::new(rocksdb_engine_handler.clone());
+    let list = if req.version > 0 {
+        if let Some(data) =
+            schema_storage.get_version(&req.cluster_name, &req.schema_name, req.version)?
+        {
+            vec![data]
+        } else {
+            vec![]
+        }
+    } else {
+        schema_storage.list_version(&req.cluster_name, &req.schema_name)?
+    };
+
+    let mut results = Vec::new();
+    for data in list {
+        results.push(data.encode());
+    }
+    Ok(results)
+}
+
 pub async fn create_schema_req(
     raft_machine_apply: &Arc<RaftMachineApply>,
     call_manager: &Arc<MQTTInnerCallManager>,
@@ -91,32 +155,31 @@ pub async fn create_schema_req(
             "schema_name".to_string(),
         ))
     } else {
+        let mut schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
+        schema.version = 1;
+
+        let request = CreateSchemaRequest {
+            cluster_name: req.cluster_name.clone(),
+            schema_name: req.schema_name.clone(),
+            schema: schema.encode(),
+        };
         let data = StorageData::new(
             StorageDataType::SchemaSet,
-            CreateSchemaRequest::encode_to_vec(req),
+            CreateSchemaRequest::encode_to_vec(&request),
         );
         raft_machine_apply.client_write(data).await?;
 
-        let schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
         update_cache_by_add_schema(&req.cluster_name, call_manager, client_pool, schema).await?;
         Ok(())
     }
 }
 
 pub async fn update_schema_req(
-    rocksdb_engine_handler: &Arc<RocksDBEngine>,
     raft_machine_apply: &Arc<RaftMachineApply>,
     call_manager: &Arc<MQTTInnerCallManager>,
     client_pool: &Arc<ClientPool>,
     req: &UpdateSchemaRequest,
 ) -> Result<(), PlacementCenterError> {
-    let storage = SchemaStorage::new(rocksdb_engine_handler.clone());
-    if storage.get(&req.cluster_name, &req.schema_name)?.is_none() {
-        return Err(PlacementCenterError::SchemaNotFound(
-            req.schema_name.clone(),
-        ));
-    };
-
     if req.cluster_name.is_empty() {
         return Err(PlacementCenterError::RequestParamsNotEmpty(
             "cluster_name".to_string(),
@@ -134,16 +197,35 @@ pub async fn update_schema_req(
             "schema".to_string(),
         ));
     }
+    serde_json::from_slice::<SchemaData>(&req.schema)?;
 
+    // every update creates a new version, the version and the compatibility check
+    // against the latest version are decided by the state machine
     let data = StorageData::new(
-        StorageDataType::SchemaSet,
+        StorageDataType::SchemaUpdate,
         UpdateSchemaRequest::encode_to_vec(req),
     );
-    raft_machine_apply.client_write(data).await?;
+    let value = if let Some(value) = raft_machine_apply
+        .client_write(data)
+        .await?
+        .and_then(|resp| resp.data.value)
+    {
+        value
+    } else {
+        return Err(PlacementCenterError::ExecutionResultIsEmpty);
+    };
 
-    let schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
-    update_cache_by_add_schema(&req.cluster_name, call_manager, client_pool, schema).await?;
-    Ok(())
+    match serde_json::from_slice::<SchemaUpdateResult>(&value)? {
+        SchemaUpdateResult::Updated(schema) => {
+            update_cache_by_add_schema(&req.cluster_name, call_manager, client_pool, schema)
+                .await?;
+            Ok(())
+        }
+        SchemaUpdateResult::NotFound => Err(PlacementCenterError::SchemaNotFound(
+            req.schema_name.clone(),
+        )),
+        SchemaUpdateResult::Incompatible(e) => Err(PlacementCenterError::SchemaIncompatible(e)),
+    }
 }
 
 pub async fn delete_schema_req(
@@ -223,6 +305,7 @@ pub async fn list_bind_schema_req(
 }
 
 pub async fn bind_schema_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
     raft_machine_apply: &Arc<RaftMachineApply>,
     call_manager: &Arc<MQTTInnerCallManager>,
     client_pool: &Arc<ClientPool>,
@@ -244,6 +327,26 @@ pub async fn bind_schema_req(
         ));
     }
 
+    // a binding pinned to a version needs that version to be cached on the broker,
+    // the latest version is already there
+    let schema_version = if req.schema_version > 0 {
+        let storage = SchemaStorage::new(rocksdb_engine_handler.clone());
+        let schema = if let Some(schema) =
+            storage.get_version(&req.cluster_name, &req.schema_name, req.schema_version)?
+        {
+            schema
+        } else {
+            return Err(PlacementCenterError::SchemaVersionDoesNotExist(
+                req.schema_name.clone(),
+                req.schema_version,
+            ));
+        };
+        update_cache_by_add_schema(&req.cluster_name, call_manager, client_pool, schema).await?;
+        Some(req.schema_version)
+    } else {
+        None
+    };
+
     let data = StorageData::new(
         StorageDataType::SchemaBindSet,
         BindSchemaRequest::encode_to_vec(req),
@@ -254,6 +357,7 @@ pub async fn bind_schema_req(
         cluster_name: req.cluster_name.clone(),
         schema_name: req.schema_name.clone(),
         resource_name: req.resource_name.clone(),
+        schema_version,
     };
 
     update_cache_by_add_schema_bind(&req.cluster_name, call_manager, client_pool, schema_data)
@@ -293,6 +397,7 @@ pub async fn un_bind_schema_req(
         cluster_name: req.cluster_name.clone(),
         schema_name: req.schema_name.clone(),
         resource_name: req.resource_name.clone(),
+        schema_version: None,
     };
 
     update_cache_by_delete_schema_bind(&req.cluster_name, call_manager, client_pool, schema_data)
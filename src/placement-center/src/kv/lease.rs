@@ -0,0 +1,177 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::tools::now_mills;
+use dashmap::DashMap;
+use log::{error, info};
+use prost::Message;
+use protocol::placement_center::placement_center_kv::LeaseRevokeRequest;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+use crate::core::error::PlacementCenterError;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::placement::kv::{KvLease, KvStorage};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub fn lease_deadline(ttl: u64) -> u128 {
+    now_mills() + ttl as u128 * 1000
+}
+
+// Deadlines extended by keep-alives. They only live on the leader and are not written
+// to the raft log, a new leader gives every lease a full ttl when it takes over.
+#[derive(Default)]
+pub struct KvLeaseManager {
+    deadlines: DashMap<u64, u128>,
+}
+
+impl KvLeaseManager {
+    pub fn keep_alive(&self, lease: &KvLease) {
+        self.deadlines.insert(lease.id, lease_deadline(lease.ttl));
+    }
+
+    pub fn expire_at(&self, lease: &KvLease) -> u128 {
+        match self.deadlines.get(&lease.id) {
+            Some(deadline) => lease.expire_at_ms.max(*deadline),
+            None => lease.expire_at_ms,
+        }
+    }
+
+    pub fn remove(&self, lease_id: u64) {
+        self.deadlines.remove(&lease_id);
+    }
+
+    pub fn reset(&self, leases: &[KvLease]) {
+        self.deadlines.clear();
+        for lease in leases {
+            self.keep_alive(lease);
+        }
+    }
+}
+
+pub struct KvLeaseController {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    raft_machine_apply: Arc<RaftMachineApply>,
+    kv_lease_manager: Arc<KvLeaseManager>,
+    stop_send: broadcast::Sender<bool>,
+}
+
+impl KvLeaseController {
+    pub fn new(
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+        raft_machine_apply: Arc<RaftMachineApply>,
+        kv_lease_manager: Arc<KvLeaseManager>,
+        stop_send: broadcast::Sender<bool>,
+    ) -> Self {
+        KvLeaseController {
+            rocksdb_engine_handler,
+            raft_machine_apply,
+            kv_lease_manager,
+            stop_send,
+        }
+    }
+
+    pub async fn start(&self) {
+        let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
+        match kv_storage.list_lease() {
+            Ok(leases) => self.kv_lease_manager.reset(&leases),
+            Err(e) => error!("Failed to list kv leases, error message: {}", e),
+        }
+
+        let mut stop_recv = self.stop_send.subscribe();
+        loop {
+            select! {
+                val = stop_recv.recv() =>{
+                    if let Ok(flag) = val {
+                        if flag {
+                            info!("Kv lease controller thread stopped successfully");
+                            break;
+                        }
+                    }
+                }
+                _ = self.revoke_expired_lease() => {
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    // Expired leases are revoked through the raft state machine so that every node
+    // deletes the attached keys and notifies its watchers.
+    async fn revoke_expired_lease(&self) {
+        let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
+        let leases = match kv_storage.list_lease() {
+            Ok(leases) => leases,
+            Err(e) => {
+                error!("Failed to list kv leases, error message: {}", e);
+                return;
+            }
+        };
+
+        let now = now_mills();
+        for lease in leases {
+            if self.kv_lease_manager.expire_at(&lease) > now {
+                continue;
+            }
+            match self.revoke_lease(lease.id).await {
+                Ok(()) => self.kv_lease_manager.remove(lease.id),
+                Err(e) => error!(
+                    "Failed to revoke kv lease {}, error message: {}",
+                    lease.id, e
+                ),
+            }
+        }
+    }
+
+    async fn revoke_lease(&self, lease_id: u64) -> Result<(), PlacementCenterError> {
+        let req = LeaseRevokeRequest { lease_id };
+        let data = StorageData::new(
+            StorageDataType::KvLeaseRevoke,
+            LeaseRevokeRequest::encode_to_vec(&req),
+        );
+        self.raft_machine_apply.client_write(data).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KvLeaseManager;
+    use crate::storage::placement::kv::KvLease;
+
+    #[test]
+    fn keep_alive_extends_stored_deadline() {
+        let manager = KvLeaseManager::default();
+        let lease = KvLease {
+            id: 1,
+            ttl: 30,
+            expire_at_ms: 0,
+        };
+        assert_eq!(manager.expire_at(&lease), 0);
+
+        manager.keep_alive(&lease);
+        assert!(manager.expire_at(&lease) > 0);
+
+        manager.remove(1);
+        assert_eq!(manager.expire_at(&lease), 0);
+
+        manager.reset(&[lease.clone()]);
+        assert!(manager.expire_at(&lease) > 0);
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use dashmap::DashMap;
+use protocol::placement_center::placement_center_kv::WatchEvent;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+use crate::core::error::PlacementCenterError;
+
+pub const KV_WATCH_HISTORY_CAPACITY: usize = 10000;
+const KV_WATCH_CHANNEL_SIZE: usize = 1000;
+
+struct KvWatcher {
+    key: String,
+    prefix: bool,
+    sender: Sender<Vec<WatchEvent>>,
+}
+
+impl KvWatcher {
+    fn matches(&self, key: &str) -> bool {
+        if self.prefix {
+            key.starts_with(&self.key)
+        } else {
+            key == self.key
+        }
+    }
+}
+
+struct KvWatchHistory {
+    events: VecDeque<WatchEvent>,
+    // Events with a revision less than or equal to this one are no longer available for replay
+    compacted_revision: u64,
+}
+
+pub struct KvWatchManager {
+    watchers: DashMap<u64, KvWatcher>,
+    history: Mutex<KvWatchHistory>,
+    history_capacity: usize,
+    watch_id: AtomicU64,
+}
+
+impl KvWatchManager {
+    pub fn new(history_capacity: usize, compacted_revision: u64) -> Self {
+        KvWatchManager {
+            watchers: DashMap::with_capacity(8),
+            history: Mutex::new(KvWatchHistory {
+                events: VecDeque::with_capacity(history_capacity),
+                compacted_revision,
+            }),
+            history_capacity,
+            watch_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn publish(&self, events: Vec<WatchEvent>) {
+        if events.is_empty() {
+            return;
+        }
+
+        // The history lock is held while fanning out so that a watcher registered
+        // concurrently neither misses nor receives an event twice.
+        let mut history = self.history.lock().unwrap();
+        history.events.extend(events.iter().cloned());
+        while history.events.len() > self.history_capacity {
+            if let Some(event) = history.events.pop_front() {
+                history.compacted_revision = event.mod_revision;
+            }
+        }
+
+        let mut closed = Vec::new();
+        for watcher in self.watchers.iter() {
+            let matched: Vec<WatchEvent> = events
+                .iter()
+                .filter(|event| watcher.matches(&event.key))
+                .cloned()
+                .collect();
+            if matched.is_empty() {
+                continue;
+            }
+
+            // A watcher that is gone or too slow to drain its channel is dropped,
+            // the client is expected to watch again from its last seen revision.
+            if watcher.sender.try_send(matched).is_err() {
+                closed.push(*watcher.key());
+            }
+        }
+
+        for id in closed {
+            self.watchers.remove(&id);
+        }
+    }
+
+    /// Drops the history and every watcher after the KV data was replaced as a whole, as
+    /// done by a backup import. Clients have to read the keys again and watch from there.
+    pub fn reset(&self, compacted_revision: u64) {
+        let mut history = self.history.lock().unwrap();
+        history.events.clear();
+        history.compacted_revision = compacted_revision;
+        self.watchers.clear();
+    }
+
+    pub fn watch(
+        &self,
+        key: String,
+        prefix: bool,
+        start_revision: u64,
+    ) -> Result<Receiver<Vec<WatchEvent>>, PlacementCenterError> {
+        let (sender, receiver) = mpsc::channel(KV_WATCH_CHANNEL_SIZE);
+        let watcher = KvWatcher {
+            key,
+            prefix,
+            sender,
+        };
+
+        let history = self.history.lock().unwrap();
+        if start_revision > 0 {
+            if start_revision <= history.compacted_revision {
+                return Err(PlacementCenterError::KvWatchRevisionCompacted(
+                    start_revision,
+                    history.compacted_revision,
+                ));
+            }
+
+            let replay: Vec<WatchEvent> = history
+                .events
+                .iter()
+                .filter(|event| event.mod_revision >= start_revision && watcher.matches(&event.key))
+                .cloned()
+                .collect();
+            if !replay.is_empty() {
+                let _ = watcher.sender.try_send(replay);
+            }
+        }
+
+        let id = self.watch_id.fetch_add(1, Ordering::Relaxed);
+        self.watchers.insert(id, watcher);
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::placement_center::placement_center_kv::WatchEvent;
+
+    use super::KvWatchManager;
+
+    fn event(key: &str, revision: u64) -> WatchEvent {
+        WatchEvent {
+            key: key.to_string(),
+            mod_revision: revision,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn watch_key_and_prefix_test() {
+        let manager = KvWatchManager::new(10, 0);
+        let mut key_recv = manager.watch("/a/1".to_string(), false, 0).unwrap();
+        let mut prefix_recv = manager.watch("/a/".to_string(), true, 0).unwrap();
+
+        manager.publish(vec![event("/a/1", 1), event("/a/2", 1)]);
+        manager.publish(vec![event("/b/1", 2)]);
+
+        let events = key_recv.recv().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].key, "/a/1");
+
+        let events = prefix_recv.recv().await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(prefix_recv.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn watch_reset_test() {
+        let manager = KvWatchManager::new(10, 0);
+        let mut recv = manager.watch("/a".to_string(), true, 0).unwrap();
+        manager.publish(vec![event("/a/1", 1)]);
+        manager.reset(5);
+
+        assert_eq!(recv.recv().await.unwrap().len(), 1);
+        assert!(recv.recv().await.is_none());
+        assert!(manager.watch("/a".to_string(), true, 5).is_err());
+        assert!(manager.watch("/a".to_string(), true, 6).is_ok());
+    }
+
+    #[tokio::test]
+    async fn watch_replay_and_compacted_test() {
+        let manager = KvWatchManager::new(2, 0);
+        for revision in 1..=3 {
+            manager.publish(vec![event("/a", revision)]);
+        }
+
+        assert!(manager.watch("/a".to_string(), false, 1).is_err());
+
+        let mut recv = manager.watch("/a".to_string(), false, 2).unwrap();
+        let events = recv.recv().await.unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.mod_revision).collect::<Vec<u64>>(),
+            vec![2, 3]
+        );
+    }
+}
@@ -12,10 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
-use common_base::config::placement_center::placement_center_conf;
+use common_base::config::placement_center::{
+    placement_center_conf, placement_center_conf_path, reload_placement_center_conf,
+};
+use common_base::config::reload::start_config_reload_watcher;
+use common_base::metrics::start_prometheus_export;
 use grpc_clients::pool::ClientPool;
 use log::info;
 use mqtt::cache::load_mqtt_cache;
@@ -29,18 +34,25 @@
 use tokio::sync::broadcast::Sender;
 use tokio::time::sleep;
 
+use crate::core::backup::restore_backup_file;
 use crate::core::cache::PlacementCacheManager;
 use crate::core::controller::ClusterController;
+use crate::core::error::PlacementCenterError;
 use crate::journal::cache::{load_journal_cache, JournalCacheManager};
 use crate::journal::controller::call_node::{journal_call_thread_manager, JournalInnerCallManager};
+use crate::kv::lease::KvLeaseManager;
+use crate::kv::watch::{KvWatchManager, KV_WATCH_HISTORY_CAPACITY};
 use crate::mqtt::cache::MqttCacheManager;
 use crate::raft::raft_node::{create_raft_node, start_openraft_node};
+use crate::raft::store::snapshot::SnapshotProgress;
 use crate::raft::typeconfig::TypeConfig;
 use crate::route::apply::RaftMachineApply;
 use crate::route::DataRoute;
+use crate::storage::placement::kv::KvStorage;
 
 mod core;
 mod journal;
+mod kv;
 mod mqtt;
 mod raft;
 mod route;
@@ -62,6 +74,12 @@ pub struct PlacementCenter {
     journal_call_manager: Arc<JournalInnerCallManager>,
     // Global call thread manager
     mqtt_call_manager: Arc<MQTTInnerCallManager>,
+    // Fan out of KV changes to watchers
+    kv_watch_manager: Arc<KvWatchManager>,
+    // KV lease deadlines extended by keep-alives, only used on the leader
+    kv_lease_manager: Arc<KvLeaseManager>,
+    // Progress of the raft snapshot being built
+    snapshot_progress: Arc<SnapshotProgress>,
 }
 
 impl Default for PlacementCenter {
@@ -87,6 +105,13 @@ pub fn new() -> PlacementCenter {
 
         let journal_call_manager = Arc::new(JournalInnerCallManager::new(cluster_cache.clone()));
         let mqtt_call_manager = Arc::new(MQTTInnerCallManager::new(cluster_cache.clone()));
+
+        // History from before a restart is not kept, so watches can only resume after the current revision
+        let kv_revision = KvStorage::new(rocksdb_engine_handler.clone())
+            .get_revision()
+            .unwrap_or(0);
+        let kv_watch_manager =
+            Arc::new(KvWatchManager::new(KV_WATCH_HISTORY_CAPACITY, kv_revision));
         PlacementCenter {
             cluster_cache,
             engine_cache,
@@ -95,7 +120,38 @@ pub fn new() -> PlacementCenter {
             client_pool,
             journal_call_manager,
             mqtt_call_manager,
+            kv_watch_manager,
+            kv_lease_manager: Arc::new(KvLeaseManager::default()),
+            snapshot_progress: Arc::new(SnapshotProgress::default()),
+        }
+    }
+
+    /// Bootstraps this node from a backup file before it starts. The node must be the only
+    /// member of a new cluster and its data directory must not contain any metadata yet.
+    pub fn restore_from_backup(
+        &mut self,
+        path: &str,
+        cluster_name: Option<&str>,
+    ) -> Result<u64, PlacementCenterError> {
+        let config = placement_center_conf();
+        if config.node.nodes.len() > 1 {
+            return Err(PlacementCenterError::CommonError(format!(
+                "Restoring a backup bootstraps a single node cluster, but {} nodes are configured",
+                config.node.nodes.len()
+            )));
         }
+
+        let records =
+            restore_backup_file(&self.rocksdb_engine_handler, Path::new(path), cluster_name)?;
+        self.cluster_cache
+            .load_cache(self.rocksdb_engine_handler.clone());
+
+        let kv_revision = KvStorage::new(self.rocksdb_engine_handler.clone())
+            .get_revision()
+            .unwrap_or(0);
+        self.kv_watch_manager =
+            Arc::new(KvWatchManager::new(KV_WATCH_HISTORY_CAPACITY, kv_revision));
+        Ok(records)
     }
 
     pub async fn start(&mut self, stop_send: Sender<bool>) {
@@ -106,11 +162,22 @@ pub async fn start(&mut self, stop_send: Sender<bool>) {
             self.cluster_cache.clone(),
             self.engine_cache.clone(),
             self.mqtt_cache.clone(),
+            self.kv_watch_manager.clone(),
+            self.kv_lease_manager.clone(),
         ));
 
         self.start_call_thread();
 
-        let openraft_node = create_raft_node(self.client_pool.clone(), data_route).await;
+        self.start_prometheus();
+
+        self.start_config_reload_thread(stop_send.clone());
+
+        let openraft_node = create_raft_node(
+            self.client_pool.clone(),
+            data_route,
+            self.snapshot_progress.clone(),
+        )
+        .await;
 
         let placement_center_storage = Arc::new(RaftMachineApply::new(openraft_node.clone()));
 
@@ -138,6 +205,7 @@ pub fn monitoring_leader_transition(
             self.mqtt_cache.clone(),
             self.engine_cache.clone(),
             self.client_pool.clone(),
+            self.kv_lease_manager.clone(),
             raft_machine_apply,
         );
     }
@@ -151,6 +219,9 @@ pub fn start_grpc_server(&self, raft_machine_apply: Arc<RaftMachineApply>) {
         let client_pool = self.client_pool.clone();
         let journal_call_manager = self.journal_call_manager.clone();
         let mqtt_call_manager = self.mqtt_call_manager.clone();
+        let kv_watch_manager = self.kv_watch_manager.clone();
+        let kv_lease_manager = self.kv_lease_manager.clone();
+        let snapshot_progress = self.snapshot_progress.clone();
         tokio::spawn(async move {
             if let Err(e) = start_grpc_server(
                 raft_machine_apply,
@@ -161,6 +232,9 @@ pub fn start_grpc_server(&self, raft_machine_apply: Arc<RaftMachineApply>) {
                 client_pool,
                 journal_call_manager,
                 mqtt_call_manager,
+                kv_watch_manager,
+                kv_lease_manager,
+                snapshot_progress,
             )
             .await
             {
@@ -226,6 +300,24 @@ fn start_call_thread(&self) {
         });
     }
 
+    fn start_prometheus(&self) {
+        let conf = placement_center_conf();
+        if conf.prometheus.enable {
+            tokio::spawn(async move {
+                start_prometheus_export(conf.prometheus.clone(), "placement-center").await;
+            });
+        }
+    }
+
+    fn start_config_reload_thread(&self, stop_send: Sender<bool>) {
+        if let Some(config_path) = placement_center_conf_path() {
+            tokio::spawn(async move {
+                start_config_reload_watcher(config_path, reload_placement_center_conf, stop_send)
+                    .await;
+            });
+        }
+    }
+
     // Wait Stop Signal
     pub async fn awaiting_stop(&self, stop_send: Sender<bool>) {
         tokio::spawn(async move {
@@ -16,6 +16,7 @@
 
 use dashmap::DashMap;
 use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+use metadata_struct::mqtt::bridge::status::MQTTConnectorStats;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::user::MqttUser;
 use protocol::placement_center::placement_center_inner::ClusterType;
@@ -151,6 +152,19 @@ pub fn get_connector(&self, cluster_name: &str, connector_name: &str) -> Option<
         None
     }
 
+    pub fn update_connector_stats(
+        &self,
+        cluster_name: &str,
+        connector_name: &str,
+        stats: MQTTConnectorStats,
+    ) {
+        if let Some(data) = self.connector_list.get(cluster_name) {
+            if let Some(mut val) = data.get_mut(connector_name) {
+                val.stats = stats;
+            }
+        }
+    }
+
     pub fn get_all_connector(&self) -> Vec<MQTTConnector> {
         let mut results = Vec::new();
         for (_, raw) in self.connector_list.clone() {
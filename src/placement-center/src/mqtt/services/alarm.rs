@@ -0,0 +1,71 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use prost::Message;
+use protocol::placement_center::placement_center_mqtt::{
+    DeleteAlarmReply, DeleteAlarmRequest, ListAlarmReply, ListAlarmRequest, SetAlarmReply,
+    SetAlarmRequest,
+};
+use rocksdb_engine::RocksDBEngine;
+use tonic::{Request, Response, Status};
+
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::mqtt::alarm::MqttAlarmStorage;
+
+pub fn list_alarm_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    request: Request<ListAlarmRequest>,
+) -> Result<Response<ListAlarmReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttAlarmStorage::new(rocksdb_engine_handler.clone());
+    let alarms = storage
+        .list(&req.cluster_name)?
+        .iter()
+        .map(|alarm| alarm.encode())
+        .collect();
+    Ok(Response::new(ListAlarmReply { alarms }))
+}
+
+pub async fn set_alarm_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    request: Request<SetAlarmRequest>,
+) -> Result<Response<SetAlarmReply>, Status> {
+    let req = request.into_inner();
+    let data = StorageData::new(
+        StorageDataType::MqttSetAlarm,
+        SetAlarmRequest::encode_to_vec(&req),
+    );
+    if let Err(e) = raft_machine_apply.client_write(data).await {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(SetAlarmReply::default()))
+}
+
+pub async fn delete_alarm_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    request: Request<DeleteAlarmRequest>,
+) -> Result<Response<DeleteAlarmReply>, Status> {
+    let req = request.into_inner();
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteAlarm,
+        DeleteAlarmRequest::encode_to_vec(&req),
+    );
+    if let Err(e) = raft_machine_apply.client_write(data).await {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(DeleteAlarmReply::default()))
+}
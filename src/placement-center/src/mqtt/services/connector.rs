@@ -23,6 +23,8 @@
 use crate::storage::mqtt::connector::MqttConnectorStorage;
 use grpc_clients::pool::ClientPool;
 use log::warn;
+use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+use metadata_struct::mqtt::bridge::status::MQTTConnectorStats;
 use prost::Message;
 use protocol::placement_center::placement_center_mqtt::{
     ConnectorHeartbeatReply, ConnectorHeartbeatRequest, CreateConnectorReply,
@@ -62,6 +64,17 @@ pub fn connector_heartbeat_by_req(
                 &raw.connector_name,
                 raw.heartbeat_time,
             );
+
+            if !raw.stats.is_empty() {
+                match serde_json::from_str::<MQTTConnectorStats>(&raw.stats) {
+                    Ok(stats) => mqtt_cache.update_connector_stats(
+                        &req.cluster_name,
+                        &raw.connector_name,
+                        stats,
+                    ),
+                    Err(e) => warn!("connector:{} invalid stats, {}", raw.connector_name, e),
+                }
+            }
         }
     }
     Ok(Response::new(ConnectorHeartbeatReply::default()))
@@ -69,21 +82,31 @@ pub fn connector_heartbeat_by_req(
 
 pub fn list_connectors_by_req(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    mqtt_cache: &Arc<MqttCacheManager>,
     request: Request<ListConnectorRequest>,
 ) -> Result<Response<ListConnectorReply>, Status> {
     let req = request.into_inner();
     let storage = MqttConnectorStorage::new(rocksdb_engine_handler.clone());
 
+    // Delivery stats are only reported through heartbeats, so they live in the cache.
+    let with_stats = |mut connector: MQTTConnector| {
+        if let Some(cached) = mqtt_cache.get_connector(&req.cluster_name, &connector.connector_name)
+        {
+            connector.stats = cached.stats;
+        }
+        connector.encode()
+    };
+
     if !req.connector_name.is_empty() {
         if let Some(data) = storage.get(&req.cluster_name, &req.connector_name)? {
-            let data = vec![data.encode()];
+            let data = vec![with_stats(data)];
             return Ok(Response::new(ListConnectorReply { connectors: data }));
         }
     } else {
         let data = storage.list(&req.cluster_name)?;
         let mut result = Vec::new();
         for raw in data {
-            result.push(raw.encode());
+            result.push(with_stats(raw));
         }
         return Ok(Response::new(ListConnectorReply { connectors: result }));
     }
@@ -13,9 +13,12 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod alarm;
 pub mod connector;
+pub mod rule;
 pub mod session;
 pub mod share_sub;
 pub mod subscribe;
+pub mod tenant;
 pub mod topic;
 pub mod user;
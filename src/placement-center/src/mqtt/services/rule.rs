@@ -0,0 +1,159 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::rule::MqttRule;
+use prost::Message;
+use protocol::placement_center::placement_center_mqtt::{
+    CreateRuleReply, CreateRuleRequest, DeleteRuleReply, DeleteRuleRequest, ListRuleReply,
+    ListRuleRequest, UpdateRuleReply, UpdateRuleRequest,
+};
+use rocksdb_engine::RocksDBEngine;
+use tonic::{Request, Response, Status};
+
+use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::{
+    update_cache_by_add_rule, update_cache_by_delete_rule, MQTTInnerCallManager,
+};
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::mqtt::rule::MqttRuleStorage;
+
+pub fn list_rule_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    request: Request<ListRuleRequest>,
+) -> Result<Response<ListRuleReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttRuleStorage::new(rocksdb_engine_handler.clone());
+
+    if !req.rule_name.is_empty() {
+        let rules = match storage.get(&req.cluster_name, &req.rule_name)? {
+            Some(rule) => vec![rule.encode()],
+            None => Vec::new(),
+        };
+        return Ok(Response::new(ListRuleReply { rules }));
+    }
+
+    let rules = storage
+        .list(&req.cluster_name)?
+        .iter()
+        .map(|rule| rule.encode())
+        .collect();
+    Ok(Response::new(ListRuleReply { rules }))
+}
+
+pub async fn create_rule_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<CreateRuleRequest>,
+) -> Result<Response<CreateRuleReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttRuleStorage::new(rocksdb_engine_handler.clone());
+    if storage.get(&req.cluster_name, &req.rule_name)?.is_some() {
+        return Err(Status::cancelled(
+            PlacementCenterError::RuleAlreadyExist(req.rule_name).to_string(),
+        ));
+    }
+
+    if let Err(e) = save_rule(raft_machine_apply, mqtt_call_manager, client_pool, req).await {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(CreateRuleReply::default()))
+}
+
+pub async fn update_rule_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<UpdateRuleRequest>,
+) -> Result<Response<UpdateRuleReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttRuleStorage::new(rocksdb_engine_handler.clone());
+    if storage.get(&req.cluster_name, &req.rule_name)?.is_none() {
+        return Err(Status::cancelled(
+            PlacementCenterError::RuleNotFound(req.rule_name).to_string(),
+        ));
+    }
+
+    let create_req = CreateRuleRequest {
+        cluster_name: req.cluster_name,
+        rule_name: req.rule_name,
+        rule: req.rule,
+    };
+    if let Err(e) = save_rule(
+        raft_machine_apply,
+        mqtt_call_manager,
+        client_pool,
+        create_req,
+    )
+    .await
+    {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(UpdateRuleReply::default()))
+}
+
+pub async fn delete_rule_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<DeleteRuleRequest>,
+) -> Result<Response<DeleteRuleReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttRuleStorage::new(rocksdb_engine_handler.clone());
+    let rule = match storage.get(&req.cluster_name, &req.rule_name)? {
+        Some(rule) => rule,
+        None => {
+            return Err(Status::cancelled(
+                PlacementCenterError::RuleNotFound(req.rule_name).to_string(),
+            ));
+        }
+    };
+
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteRule,
+        DeleteRuleRequest::encode_to_vec(&req),
+    );
+    if let Err(e) = raft_machine_apply.client_write(data).await {
+        return Err(Status::cancelled(e.to_string()));
+    }
+
+    if let Err(e) =
+        update_cache_by_delete_rule(&req.cluster_name, mqtt_call_manager, client_pool, rule).await
+    {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(DeleteRuleReply::default()))
+}
+
+async fn save_rule(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    req: CreateRuleRequest,
+) -> Result<(), PlacementCenterError> {
+    let rule = MqttRule::decode(&req.rule)?;
+    let data = StorageData::new(
+        StorageDataType::MqttSetRule,
+        CreateRuleRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+    update_cache_by_add_rule(&req.cluster_name, mqtt_call_manager, client_pool, rule).await
+}
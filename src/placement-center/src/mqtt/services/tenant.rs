@@ -0,0 +1,160 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::tenant::MqttTenant;
+use prost::Message;
+use protocol::placement_center::placement_center_mqtt::{
+    CreateTenantReply, CreateTenantRequest, DeleteTenantReply, DeleteTenantRequest,
+    ListTenantReply, ListTenantRequest, UpdateTenantReply, UpdateTenantRequest,
+};
+use rocksdb_engine::RocksDBEngine;
+use tonic::{Request, Response, Status};
+
+use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::{
+    update_cache_by_add_tenant, update_cache_by_delete_tenant, MQTTInnerCallManager,
+};
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::mqtt::tenant::MqttTenantStorage;
+
+pub fn list_tenant_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    request: Request<ListTenantRequest>,
+) -> Result<Response<ListTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttTenantStorage::new(rocksdb_engine_handler.clone());
+
+    if !req.tenant_name.is_empty() {
+        let tenants = match storage.get(&req.cluster_name, &req.tenant_name)? {
+            Some(tenant) => vec![tenant.encode()],
+            None => Vec::new(),
+        };
+        return Ok(Response::new(ListTenantReply { tenants }));
+    }
+
+    let tenants = storage
+        .list(&req.cluster_name)?
+        .iter()
+        .map(|tenant| tenant.encode())
+        .collect();
+    Ok(Response::new(ListTenantReply { tenants }))
+}
+
+pub async fn create_tenant_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<CreateTenantRequest>,
+) -> Result<Response<CreateTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttTenantStorage::new(rocksdb_engine_handler.clone());
+    if storage.get(&req.cluster_name, &req.tenant_name)?.is_some() {
+        return Err(Status::cancelled(
+            PlacementCenterError::TenantAlreadyExist(req.tenant_name).to_string(),
+        ));
+    }
+
+    if let Err(e) = save_tenant(raft_machine_apply, mqtt_call_manager, client_pool, req).await {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(CreateTenantReply::default()))
+}
+
+pub async fn update_tenant_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<UpdateTenantRequest>,
+) -> Result<Response<UpdateTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttTenantStorage::new(rocksdb_engine_handler.clone());
+    if storage.get(&req.cluster_name, &req.tenant_name)?.is_none() {
+        return Err(Status::cancelled(
+            PlacementCenterError::TenantNotFound(req.tenant_name).to_string(),
+        ));
+    }
+
+    let create_req = CreateTenantRequest {
+        cluster_name: req.cluster_name,
+        tenant_name: req.tenant_name,
+        tenant: req.tenant,
+    };
+    if let Err(e) = save_tenant(
+        raft_machine_apply,
+        mqtt_call_manager,
+        client_pool,
+        create_req,
+    )
+    .await
+    {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(UpdateTenantReply::default()))
+}
+
+pub async fn delete_tenant_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    request: Request<DeleteTenantRequest>,
+) -> Result<Response<DeleteTenantReply>, Status> {
+    let req = request.into_inner();
+    let storage = MqttTenantStorage::new(rocksdb_engine_handler.clone());
+    let tenant = match storage.get(&req.cluster_name, &req.tenant_name)? {
+        Some(tenant) => tenant,
+        None => {
+            return Err(Status::cancelled(
+                PlacementCenterError::TenantNotFound(req.tenant_name).to_string(),
+            ));
+        }
+    };
+
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteTenant,
+        DeleteTenantRequest::encode_to_vec(&req),
+    );
+    if let Err(e) = raft_machine_apply.client_write(data).await {
+        return Err(Status::cancelled(e.to_string()));
+    }
+
+    if let Err(e) =
+        update_cache_by_delete_tenant(&req.cluster_name, mqtt_call_manager, client_pool, tenant)
+            .await
+    {
+        return Err(Status::cancelled(e.to_string()));
+    }
+    Ok(Response::new(DeleteTenantReply::default()))
+}
+
+async fn save_tenant(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    mqtt_call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    req: CreateTenantRequest,
+) -> Result<(), PlacementCenterError> {
+    let tenant = MqttTenant::decode(&req.tenant)?;
+    let data = StorageData::new(
+        StorageDataType::MqttSetTenant,
+        CreateTenantRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+    update_cache_by_add_tenant(&req.cluster_name, mqtt_call_manager, client_pool, tenant).await
+}
@@ -17,6 +17,7 @@
 use crate::{
     core::cache::PlacementCacheManager,
     journal::{cache::JournalCacheManager, controller::StorageEngineController},
+    kv::lease::{KvLeaseController, KvLeaseManager},
     mqtt::{cache::MqttCacheManager, controller::MqttController},
     route::apply::RaftMachineApply,
 };
@@ -28,6 +29,7 @@
 use rocksdb_engine::RocksDBEngine;
 use tokio::sync::broadcast::{self, Sender};
 
+#[allow(clippy::too_many_arguments)]
 pub fn monitoring_leader_transition(
     raft: &Raft<TypeConfig>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
@@ -35,6 +37,7 @@ pub fn monitoring_leader_transition(
     mqtt_cache: Arc<MqttCacheManager>,
     engine_cache: Arc<JournalCacheManager>,
     client_pool: Arc<ClientPool>,
+    kv_lease_manager: Arc<KvLeaseManager>,
     raft_machine_apply: Arc<RaftMachineApply>,
 ) {
     let mut metrics_rx = raft.metrics();
@@ -60,6 +63,7 @@ pub fn monitoring_leader_transition(
                                     &mqtt_cache,
                                     &engine_cache,
                                     &client_pool,
+                                    &kv_lease_manager,
                                     &raft_machine_apply,
                                     stop_send.clone(),
                                 );
@@ -82,12 +86,14 @@ pub fn monitoring_leader_transition(
     });
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn start_controller(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
     cluster_cache: &Arc<PlacementCacheManager>,
     mqtt_cache: &Arc<MqttCacheManager>,
     engine_cache: &Arc<JournalCacheManager>,
     client_pool: &Arc<ClientPool>,
+    kv_lease_manager: &Arc<KvLeaseManager>,
     raft_machine_apply: &Arc<RaftMachineApply>,
     stop_send: Sender<bool>,
 ) {
@@ -111,6 +117,16 @@ pub fn start_controller(
     tokio::spawn(async move {
         journal_controller.start().await;
     });
+
+    let kv_lease_controller = KvLeaseController::new(
+        rocksdb_engine_handler.clone(),
+        raft_machine_apply.clone(),
+        kv_lease_manager.clone(),
+        stop_send.clone(),
+    );
+    tokio::spawn(async move {
+        kv_lease_controller.start().await;
+    });
 }
 
 pub fn stop_controller(stop_send: Sender<bool>) {
@@ -14,19 +14,20 @@
 
 use std::collections::BTreeMap;
 use std::fmt::Display;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use common_base::config::placement_center::placement_center_conf;
 use grpc_clients::pool::ClientPool;
 use log::info;
-use openraft::{Config, Raft};
+use openraft::{Config, Raft, SnapshotPolicy};
 
 use super::network::network::Network;
 use super::store::new_storage;
+use super::store::snapshot::SnapshotProgress;
 use super::typeconfig::TypeConfig;
 use crate::route::DataRoute;
-use crate::storage::rocksdb::storage_raft_fold;
+use crate::storage::rocksdb::{storage_raft_fold, storage_snapshot_fold};
 pub type NodeId = u64;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, Default)]
@@ -104,18 +105,24 @@ pub async fn start_openraft_node(raft_node: Raft<TypeConfig>) {
 pub async fn create_raft_node(
     client_pool: Arc<ClientPool>,
     route: Arc<DataRoute>,
+    snapshot_progress: Arc<SnapshotProgress>,
 ) -> Raft<TypeConfig> {
+    let conf = placement_center_conf();
     let config = Config {
         heartbeat_interval: 250,
         election_timeout_min: 299,
+        snapshot_max_chunk_size: conf.raft.snapshot_max_chunk_size,
+        snapshot_policy: SnapshotPolicy::LogsSinceLast(conf.raft.snapshot_logs_since_last),
+        max_in_snapshot_log_to_keep: conf.raft.max_in_snapshot_log_to_keep,
         ..Default::default()
     };
 
     let config = Arc::new(config.validate().unwrap());
-    let conf = placement_center_conf();
     let path = storage_raft_fold(&conf.rocksdb.data_path);
     let dir = Path::new(&path);
-    let (log_store, state_machine_store) = new_storage(&dir, route).await;
+    let snapshot_dir = PathBuf::from(storage_snapshot_fold(&conf.rocksdb.data_path));
+    let (log_store, state_machine_store) =
+        new_storage(&dir, snapshot_dir, route, snapshot_progress).await;
 
     let network = Network::new(client_pool);
 
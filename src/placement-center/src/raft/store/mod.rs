@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
@@ -20,21 +20,21 @@
 use openraft::{SnapshotMeta, StorageError};
 use rocksdb::{ColumnFamilyDescriptor, Options, DB};
 use serde::{Deserialize, Serialize};
+use snapshot::SnapshotProgress;
 use state_machine_store::StateMachineStore;
 
 use super::typeconfig::TypeConfig;
 use crate::route::DataRoute;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StoredSnapshot {
+    /// The data of the state machine lives in the snapshot file named after `meta.snapshot_id`.
     pub meta: SnapshotMeta<TypeConfig>,
-
-    /// The data of the state machine at the time of this snapshot.
-    pub data: Vec<u8>,
 }
 
 type StorageResult<T> = Result<T, StorageError<TypeConfig>>;
 
 pub mod log_store;
+pub mod snapshot;
 pub mod state_machine_store;
 
 /// converts an id to a byte vector for storing in the database.
@@ -51,7 +51,9 @@ fn bin_to_id(buf: &[u8]) -> u64 {
 
 pub(crate) async fn new_storage<P: AsRef<Path>>(
     db_path: P,
+    snapshot_dir: PathBuf,
     route: Arc<DataRoute>,
+    snapshot_progress: Arc<SnapshotProgress>,
 ) -> (LogStore, StateMachineStore) {
     let mut db_opts = Options::default();
     db_opts.create_missing_column_families(true);
@@ -64,7 +66,10 @@ pub(crate) async fn new_storage<P: AsRef<Path>>(
     let db = Arc::new(db);
 
     let log_store = LogStore { db: db.clone() };
-    let sm_store = StateMachineStore::new(db, route).await.unwrap();
+    std::fs::create_dir_all(&snapshot_dir).unwrap();
+    let sm_store = StateMachineStore::new(db, snapshot_dir, route, snapshot_progress)
+        .await
+        .unwrap();
 
     (log_store, sm_store)
 }
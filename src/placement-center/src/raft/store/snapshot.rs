@@ -0,0 +1,172 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use common_base::tools::now_second;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+const SNAPSHOT_FILE_SUFFIX: &str = ".snap";
+const SNAPSHOT_CHECKPOINT_SUFFIX: &str = ".checkpoint";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotBuildStatus {
+    pub building: bool,
+    pub snapshot_id: String,
+    pub records: u64,
+    pub bytes: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub error: Option<String>,
+}
+
+/// Progress of the snapshot currently being built, or of the last one if none is running.
+#[derive(Default)]
+pub struct SnapshotProgress {
+    status: RwLock<SnapshotBuildStatus>,
+    records: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl SnapshotProgress {
+    pub fn start(&self, snapshot_id: &str) {
+        self.records.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+        let mut status = self.status.write().unwrap();
+        *status = SnapshotBuildStatus {
+            building: true,
+            snapshot_id: snapshot_id.to_string(),
+            start_time: now_second(),
+            ..Default::default()
+        };
+    }
+
+    pub fn record_written(&self, bytes: u64) {
+        self.records.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self, error: Option<String>) {
+        let mut status = self.status.write().unwrap();
+        status.building = false;
+        status.end_time = now_second();
+        status.error = error;
+    }
+
+    pub fn status(&self) -> SnapshotBuildStatus {
+        let mut status = self.status.read().unwrap().clone();
+        status.records = self.records.load(Ordering::Relaxed);
+        status.bytes = self.bytes.load(Ordering::Relaxed);
+        status
+    }
+}
+
+pub fn snapshot_file_path(snapshot_dir: &Path, snapshot_id: &str) -> PathBuf {
+    snapshot_dir.join(format!("{}{}", snapshot_id, SNAPSHOT_FILE_SUFFIX))
+}
+
+pub fn snapshot_checkpoint_path(snapshot_dir: &Path, snapshot_id: &str) -> PathBuf {
+    snapshot_dir.join(format!("{}{}", snapshot_id, SNAPSHOT_CHECKPOINT_SUFFIX))
+}
+
+/// Only the current snapshot is needed, older files and checkpoints left behind by an
+/// interrupted build are removed once a new one is in place.
+pub fn remove_stale_snapshot_files(snapshot_dir: &Path, current_snapshot_id: &str) {
+    let current = snapshot_file_path(snapshot_dir, current_snapshot_id);
+    let entries = match fs::read_dir(snapshot_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!(
+                "Failed to read snapshot directory {:?}: {}",
+                snapshot_dir, e
+            );
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = path.file_name().and_then(|name| name.to_str());
+        if name.is_some_and(|name| name.ends_with(SNAPSHOT_CHECKPOINT_SUFFIX)) {
+            match fs::remove_dir_all(&path) {
+                Ok(_) => info!("Removed stale snapshot checkpoint {:?}", path),
+                Err(e) => warn!(
+                    "Failed to remove stale snapshot checkpoint {:?}: {}",
+                    path, e
+                ),
+            }
+            continue;
+        }
+        let is_snapshot = name.is_some_and(|name| name.ends_with(SNAPSHOT_FILE_SUFFIX));
+        if !is_snapshot || path == current {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(_) => info!("Removed stale snapshot file {:?}", path),
+            Err(e) => warn!("Failed to remove stale snapshot file {:?}: {}", path, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{
+        remove_stale_snapshot_files, snapshot_checkpoint_path, snapshot_file_path, SnapshotProgress,
+    };
+
+    #[test]
+    fn snapshot_progress_test() {
+        let progress = SnapshotProgress::default();
+        progress.start("1-10-1");
+        progress.record_written(10);
+        progress.record_written(20);
+
+        let status = progress.status();
+        assert!(status.building);
+        assert_eq!(status.records, 2);
+        assert_eq!(status.bytes, 30);
+
+        progress.finish(None);
+        let status = progress.status();
+        assert!(!status.building);
+        assert_eq!(status.snapshot_id, "1-10-1");
+        assert_eq!(status.records, 2);
+    }
+
+    #[test]
+    fn remove_stale_snapshot_files_test() {
+        let dir = tempdir().unwrap();
+        for id in ["1-10-1", "1-20-2"] {
+            fs::write(snapshot_file_path(dir.path(), id), b"data").unwrap();
+        }
+        fs::write(dir.path().join("other"), b"data").unwrap();
+        let checkpoint = snapshot_checkpoint_path(dir.path(), "1-10-1");
+        fs::create_dir_all(&checkpoint).unwrap();
+
+        remove_stale_snapshot_files(dir.path(), "1-20-2");
+
+        assert!(!checkpoint.exists());
+        assert!(!snapshot_file_path(dir.path(), "1-10-1").exists());
+        assert!(snapshot_file_path(dir.path(), "1-20-2").exists());
+        assert!(dir.path().join("other").exists());
+    }
+}
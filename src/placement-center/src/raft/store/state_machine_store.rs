@@ -12,34 +12,48 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::Cursor;
+use std::io::SeekFrom;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
-use log::warn;
+use log::{error, warn};
 use openraft::storage::RaftStateMachine;
 use openraft::{
     AnyError, EntryPayload, ErrorSubject, ErrorVerb, LogId, OptionalSend, RaftSnapshotBuilder,
     Snapshot, SnapshotMeta, StorageError, StoredMembership,
 };
 use rocksdb::{BoundColumnFamily, DB};
+use tokio::fs::File;
+use tokio::io::AsyncSeekExt;
 
-use super::{cf_raft_store, StorageResult, StoredSnapshot};
+use super::snapshot::{
+    remove_stale_snapshot_files, snapshot_checkpoint_path, snapshot_file_path, SnapshotProgress,
+};
+use super::{bin_to_id, cf_raft_store, id_to_bin, StorageResult, StoredSnapshot};
+use crate::core::error::PlacementCenterError;
+use crate::core::metrics::metrics_raft_apply_ms;
 use crate::raft::raft_node::{typ, NodeId};
 use crate::raft::route::AppResponseData;
 use crate::raft::typeconfig::{SnapshotData, TypeConfig};
 use crate::route::DataRoute;
 
+const SNAPSHOT_CURRENT_KEY: &[u8] = b"snapshot";
+const SNAPSHOT_INSTALLING_KEY: &[u8] = b"snapshot_installing";
+
 #[derive(Clone)]
 pub struct StateMachineStore {
     pub data: StateMachineData,
 
-    /// snapshot index is not persisted in this example.
-    ///
-    /// It is only used as a suffix of snapshot id, and should be globally unique.
-    /// In practice, using a timestamp in micro-second would be good enough.
+    /// Suffix of the snapshot id, persisted so that ids stay unique across restarts.
     snapshot_idx: u64,
 
-    /// State machine stores snapshot in db.
+    /// Directory holding the snapshot files.
+    snapshot_dir: PathBuf,
+
+    snapshot_progress: Arc<SnapshotProgress>,
+
+    /// State machine stores snapshot meta in db.
     db: Arc<DB>,
 }
 
@@ -53,36 +67,61 @@ pub struct StateMachineData {
     pub route: Arc<DataRoute>,
 }
 
-impl RaftSnapshotBuilder<TypeConfig> for StateMachineStore {
-    async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<TypeConfig>> {
-        let last_applied_log = self.data.last_applied_log_id;
-        let last_membership = self.data.last_membership.clone();
-
-        // todo
-        let kv_json = self.data.route.build_snapshot();
+/// Builds a snapshot from a checkpoint taken when the builder was created, together with
+/// the last applied log id it is labelled with.
+pub struct StateMachineSnapshotBuilder {
+    store: StateMachineStore,
+    meta: SnapshotMeta<TypeConfig>,
+    checkpoint: Result<PathBuf, PlacementCenterError>,
+}
 
-        let snapshot_id = if let Some(last) = last_applied_log {
-            format!("{}-{}-{}", last.leader_id, last.index, self.snapshot_idx)
-        } else {
-            format!("--{}", self.snapshot_idx)
+impl RaftSnapshotBuilder<TypeConfig> for StateMachineSnapshotBuilder {
+    async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<TypeConfig>> {
+        let meta = self.meta.clone();
+        let checkpoint_dir = match &self.checkpoint {
+            Ok(dir) => dir.clone(),
+            Err(e) => return Err(StorageError::write_snapshot(Some(meta.signature()), e)),
         };
 
-        let meta = SnapshotMeta {
-            last_log_id: last_applied_log,
-            last_membership,
-            snapshot_id,
+        let store = &self.store;
+        let path = snapshot_file_path(&store.snapshot_dir, &meta.snapshot_id);
+        store.snapshot_progress.start(&meta.snapshot_id);
+
+        // Building walks the whole column family, keep it off the async runtime
+        let route = store.data.route.clone();
+        let progress = store.snapshot_progress.clone();
+        let build_path = path.clone();
+        let result = match tokio::task::spawn_blocking(move || {
+            let result = route.build_snapshot(&checkpoint_dir, &build_path, &progress);
+            if let Err(e) = std::fs::remove_dir_all(&checkpoint_dir) {
+                warn!(
+                    "Failed to remove snapshot checkpoint {:?}: {}",
+                    checkpoint_dir, e
+                );
+            }
+            result
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => Err(PlacementCenterError::CommonError(e.to_string())),
         };
 
-        let snapshot = StoredSnapshot {
-            meta: meta.clone(),
-            data: kv_json.clone(),
-        };
+        if let Err(e) = result {
+            store.snapshot_progress.finish(Some(e.to_string()));
+            return Err(StorageError::write_snapshot(Some(meta.signature()), &e));
+        }
+        store.snapshot_progress.finish(None);
 
-        self.set_current_snapshot_(snapshot)?;
+        store.set_current_snapshot_(StoredSnapshot { meta: meta.clone() })?;
+        remove_stale_snapshot_files(&store.snapshot_dir, &meta.snapshot_id);
 
+        let file = File::open(&path)
+            .await
+            .map_err(|e| StorageError::read_snapshot(Some(meta.signature()), &e))?;
         Ok(Snapshot {
             meta,
-            snapshot: Box::new(Cursor::new(kv_json)),
+            snapshot: Box::new(file),
         })
     }
 }
@@ -90,7 +129,9 @@ async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<
 impl StateMachineStore {
     pub async fn new(
         db: Arc<DB>,
+        snapshot_dir: PathBuf,
         route: Arc<DataRoute>,
+        snapshot_progress: Arc<SnapshotProgress>,
     ) -> Result<StateMachineStore, StorageError<TypeConfig>> {
         let mut sm = Self {
             data: StateMachineData {
@@ -99,11 +140,19 @@ pub async fn new(
                 route,
             },
             snapshot_idx: 0,
+            snapshot_dir,
+            snapshot_progress,
             db,
         };
 
-        let snapshot = sm.get_current_snapshot_()?;
-        if let Some(snap) = snapshot {
+        sm.snapshot_idx = sm.get_snapshot_idx_()?;
+
+        // An install that was interrupted is redone from the start before anything else
+        if let Some(snap) = sm.get_stored_snapshot_(SNAPSHOT_INSTALLING_KEY)? {
+            sm.update_state_machine_(snap.clone()).await?;
+            sm.set_current_snapshot_(snap)?;
+            sm.clear_installing_snapshot_()?;
+        } else if let Some(snap) = sm.get_stored_snapshot_(SNAPSHOT_CURRENT_KEY)? {
             sm.update_state_machine_(snap).await?;
         }
 
@@ -114,29 +163,71 @@ async fn update_state_machine_(
         &mut self,
         snapshot: StoredSnapshot,
     ) -> Result<(), StorageError<TypeConfig>> {
+        let path = snapshot_file_path(&self.snapshot_dir, &snapshot.meta.snapshot_id);
+        let route = self.data.route.clone();
+        let result = match tokio::task::spawn_blocking(move || route.recover_snapshot(&path)).await
+        {
+            Ok(result) => result,
+            Err(e) => Err(PlacementCenterError::CommonError(e.to_string())),
+        };
+        if let Err(e) = result {
+            return Err(StorageError::read_snapshot(
+                Some(snapshot.meta.signature()),
+                &e,
+            ));
+        }
+
         self.data.last_applied_log_id = snapshot.meta.last_log_id;
         self.data.last_membership = snapshot.meta.last_membership.clone();
+        Ok(())
+    }
 
-        match self.data.route.recover_snapshot(snapshot.data) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(StorageError::read(&e)),
+    fn get_stored_snapshot_(&self, key: &[u8]) -> StorageResult<Option<StoredSnapshot>> {
+        let snapshot: Option<StoredSnapshot> = self
+            .db
+            .get_cf(&self.store(), key)
+            .map_err(|e| StorageError::read(&e))?
+            .and_then(|v| serde_json::from_slice(&v).ok());
+
+        if let Some(snap) = &snapshot {
+            let path = snapshot_file_path(&self.snapshot_dir, &snap.meta.snapshot_id);
+            if !path.exists() {
+                warn!(
+                    "Snapshot file {:?} of snapshot {} does not exist, ignoring it",
+                    path, snap.meta.snapshot_id
+                );
+                return Ok(None);
+            }
         }
+        Ok(snapshot)
     }
 
-    fn get_current_snapshot_(&self) -> StorageResult<Option<StoredSnapshot>> {
+    fn get_snapshot_idx_(&self) -> StorageResult<u64> {
         Ok(self
             .db
-            .get_cf(&self.store(), b"snapshot")
+            .get_cf(&self.store(), b"snapshot_idx")
             .map_err(|e| StorageError::read(&e))?
-            .and_then(|v| serde_json::from_slice(&v).ok()))
+            .map(|v| bin_to_id(&v))
+            .unwrap_or(0))
+    }
+
+    fn set_snapshot_idx_(&self, snapshot_idx: u64) -> StorageResult<()> {
+        self.db
+            .put_cf(&self.store(), b"snapshot_idx", id_to_bin(snapshot_idx))
+            .map_err(|e| StorageError::write(&e))?;
+        Ok(())
     }
 
     fn set_current_snapshot_(&self, snap: StoredSnapshot) -> StorageResult<()> {
+        self.set_stored_snapshot_(SNAPSHOT_CURRENT_KEY, &snap)
+    }
+
+    fn set_stored_snapshot_(&self, key: &[u8], snap: &StoredSnapshot) -> StorageResult<()> {
         self.db
             .put_cf(
                 &self.store(),
-                b"snapshot",
-                serde_json::to_vec(&snap).unwrap().as_slice(),
+                key,
+                serde_json::to_vec(snap).unwrap().as_slice(),
             )
             .map_err(|e| StorageError::write_snapshot(Some(snap.meta.signature()), &e))?;
         self.flush(
@@ -146,6 +237,13 @@ fn set_current_snapshot_(&self, snap: StoredSnapshot) -> StorageResult<()> {
         Ok(())
     }
 
+    fn clear_installing_snapshot_(&self) -> StorageResult<()> {
+        self.db
+            .delete_cf(&self.store(), SNAPSHOT_INSTALLING_KEY)
+            .map_err(|e| StorageError::write(&e))?;
+        self.flush(ErrorSubject::Snapshot(None), ErrorVerb::Write)
+    }
+
     fn flush(
         &self,
         subject: ErrorSubject<TypeConfig>,
@@ -163,7 +261,7 @@ fn store(&self) -> Arc<BoundColumnFamily> {
 }
 
 impl RaftStateMachine<TypeConfig> for StateMachineStore {
-    type SnapshotBuilder = Self;
+    type SnapshotBuilder = StateMachineSnapshotBuilder;
 
     async fn applied_state(
         &mut self,
@@ -193,17 +291,24 @@ async fn apply<I>(
 
             match ent.payload {
                 EntryPayload::Blank => {}
-                EntryPayload::Normal(req) => match self.data.route.route(req.clone()).await {
-                    Ok(data) => {
-                        resp_value = data;
+                EntryPayload::Normal(req) => {
+                    let start = Instant::now();
+                    match self.data.route.route(req.clone()).await {
+                        Ok(data) => {
+                            resp_value = data;
+                        }
+                        Err(e) => {
+                            warn!(
+                                "Raft route failed to process message with error message: {},req:{:?}",
+                                e, req.data_type
+                            );
+                        }
                     }
-                    Err(e) => {
-                        warn!(
-                            "Raft route failed to process message with error message: {},req:{:?}",
-                            e, req.data_type
-                        );
-                    }
-                },
+                    metrics_raft_apply_ms(
+                        &format!("{:?}", req.data_type),
+                        start.elapsed().as_secs_f64() * 1000.0,
+                    );
+                }
                 EntryPayload::Membership(mem) => {
                     self.data.last_membership = StoredMembership::new(Some(ent.log_id), mem);
                 }
@@ -216,28 +321,75 @@ async fn apply<I>(
 
     async fn get_snapshot_builder(&mut self) -> Self::SnapshotBuilder {
         self.snapshot_idx += 1;
-        self.clone()
+        if let Err(e) = self.set_snapshot_idx_(self.snapshot_idx) {
+            error!("Failed to persist snapshot index, error message: {}", e);
+        }
+
+        let last_applied_log = self.data.last_applied_log_id;
+        let snapshot_id = if let Some(last) = last_applied_log {
+            format!("{}-{}-{}", last.leader_id, last.index, self.snapshot_idx)
+        } else {
+            format!("--{}", self.snapshot_idx)
+        };
+        let meta = SnapshotMeta {
+            last_log_id: last_applied_log,
+            last_membership: self.data.last_membership.clone(),
+            snapshot_id,
+        };
+
+        // No log is applied while the builder is created, so the checkpoint holds exactly
+        // the state up to last_log_id
+        let checkpoint_dir = snapshot_checkpoint_path(&self.snapshot_dir, &meta.snapshot_id);
+        let checkpoint = self
+            .data
+            .route
+            .checkpoint(&checkpoint_dir)
+            .map(|_| checkpoint_dir);
+
+        StateMachineSnapshotBuilder {
+            store: self.clone(),
+            meta,
+            checkpoint,
+        }
     }
 
     async fn begin_receiving_snapshot(
         &mut self,
-    ) -> Result<Box<Cursor<Vec<u8>>>, StorageError<TypeConfig>> {
-        Ok(Box::new(Cursor::new(Vec::new())))
+    ) -> Result<Box<SnapshotData>, StorageError<TypeConfig>> {
+        // Chunks from the leader are written to an anonymous file that is removed once dropped
+        let file = tempfile::tempfile_in(&self.snapshot_dir)
+            .map_err(|e| StorageError::write_snapshot(None, &e))?;
+        Ok(Box::new(File::from_std(file)))
     }
 
     async fn install_snapshot(
         &mut self,
         meta: &SnapshotMeta<TypeConfig>,
-        snapshot: Box<SnapshotData>,
+        mut snapshot: Box<SnapshotData>,
     ) -> Result<(), StorageError<TypeConfig>> {
-        let new_snapshot = StoredSnapshot {
-            meta: meta.clone(),
-            data: snapshot.into_inner(),
-        };
+        let path = snapshot_file_path(&self.snapshot_dir, &meta.snapshot_id);
+        let copied: std::io::Result<()> = async {
+            snapshot.seek(SeekFrom::Start(0)).await?;
+            let mut target = File::create(&path).await?;
+            tokio::io::copy(&mut snapshot, &mut target).await?;
+            target.sync_all().await
+        }
+        .await;
+        if let Err(e) = copied {
+            return Err(StorageError::write_snapshot(Some(meta.signature()), &e));
+        }
+
+        let new_snapshot = StoredSnapshot { meta: meta.clone() };
+
+        // Recorded before the data is replaced, a crash in the middle is redone on startup
+        self.set_stored_snapshot_(SNAPSHOT_INSTALLING_KEY, &new_snapshot)?;
 
         self.update_state_machine_(new_snapshot.clone()).await?;
 
         self.set_current_snapshot_(new_snapshot)?;
+        self.clear_installing_snapshot_()?;
+
+        remove_stale_snapshot_files(&self.snapshot_dir, &meta.snapshot_id);
 
         Ok(())
     }
@@ -245,10 +397,18 @@ async fn install_snapshot(
     async fn get_current_snapshot(
         &mut self,
     ) -> Result<Option<Snapshot<TypeConfig>>, StorageError<TypeConfig>> {
-        let x = self.get_current_snapshot_()?;
-        Ok(x.map(|s| Snapshot {
-            meta: s.meta.clone(),
-            snapshot: Box::new(Cursor::new(s.data.clone())),
+        let snapshot = match self.get_stored_snapshot_(SNAPSHOT_CURRENT_KEY)? {
+            Some(snapshot) => snapshot,
+            None => return Ok(None),
+        };
+
+        let path = snapshot_file_path(&self.snapshot_dir, &snapshot.meta.snapshot_id);
+        let file = File::open(&path)
+            .await
+            .map_err(|e| StorageError::read_snapshot(Some(snapshot.meta.signature()), &e))?;
+        Ok(Some(Snapshot {
+            meta: snapshot.meta,
+            snapshot: Box::new(file),
         }))
     }
 }
@@ -12,15 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::Cursor;
-
 use serde::{Deserialize, Serialize};
 
 use crate::raft::raft_node::Node;
 use crate::raft::route::AppResponseData;
 use crate::route::data::StorageData;
 
-pub type SnapshotData = Cursor<Vec<u8>>;
+// Snapshots are kept on disk and streamed to followers in chunks instead of being held in memory
+pub type SnapshotData = tokio::fs::File;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct TypeConfig {}
@@ -31,7 +30,7 @@ impl openraft::RaftTypeConfig for TypeConfig {
     type Node = Node;
     type NodeId = u64;
     type Entry = openraft::impls::Entry<Self>;
-    type SnapshotData = std::io::Cursor<Vec<u8>>;
+    type SnapshotData = SnapshotData;
     type Responder = openraft::impls::OneshotResponder<Self>;
     type AsyncRuntime = openraft::impls::TokioRuntime;
 }
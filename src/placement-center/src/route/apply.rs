@@ -31,6 +31,26 @@ pub fn new(openraft_node: Raft<TypeConfig>) -> Self {
         RaftMachineApply { openraft_node }
     }
 
+    // For requests that only change state held on the leader and are not written to the
+    // raft log. Followers answer the same way a write that has to be forwarded does.
+    pub fn ensure_leader(&self) -> Result<(), PlacementCenterError> {
+        let metrics = self.openraft_node.metrics().borrow().clone();
+        if metrics.current_leader == Some(metrics.id) {
+            return Ok(());
+        }
+        let leader_node = metrics.current_leader.and_then(|id| {
+            metrics
+                .membership_config
+                .membership()
+                .get_node(&id)
+                .cloned()
+        });
+        Err(PlacementCenterError::CommonError(format!(
+            "has to forward request to: {:?}, {:?}",
+            metrics.current_leader, leader_node
+        )))
+    }
+
     pub async fn client_write(
         &self,
         data: StorageData,
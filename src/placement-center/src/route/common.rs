@@ -20,7 +20,10 @@
     BindSchemaRequest, CreateSchemaRequest, DeleteIdempotentDataRequest,
     DeleteResourceConfigRequest, DeleteSchemaRequest, SaveOffsetDataRequest,
     SetIdempotentDataRequest, SetResourceConfigRequest, UnBindSchemaRequest, UnRegisterNodeRequest,
+    UpdateSchemaRequest,
 };
+use schema_register::compatibility::check_compatibility;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::core::cache::PlacementCacheManager;
@@ -33,6 +36,13 @@
 use crate::storage::placement::schema::SchemaStorage;
 use crate::storage::rocksdb::RocksDBEngine;
 
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum SchemaUpdateResult {
+    Updated(SchemaData),
+    NotFound,
+    Incompatible(String),
+}
+
 #[derive(Clone)]
 pub struct DataRouteCluster {
     rocksdb_engine_handler: Arc<RocksDBEngine>,
@@ -132,6 +142,32 @@ pub fn set_schema(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
         Ok(())
     }
 
+    // The version and the compatibility check are decided here, so that concurrent
+    // updates are checked against the version stored before them. The check uses the
+    // level stored on the schema, the level carried by the request only takes effect
+    // for the updates after this one.
+    pub fn update_schema(&self, value: Vec<u8>) -> Result<Vec<u8>, PlacementCenterError> {
+        let req = UpdateSchemaRequest::decode(value.as_ref())?;
+        let schema_storage = SchemaStorage::new(self.rocksdb_engine_handler.clone());
+        let result =
+            if let Some(current) = schema_storage.get(&req.cluster_name, &req.schema_name)? {
+                let mut schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
+                schema.version = current.version + 1;
+                let mut checked = schema.clone();
+                checked.compatibility = current.compatibility.clone();
+                match check_compatibility(&current, &checked) {
+                    Ok(()) => {
+                        schema_storage.save(&req.cluster_name, &req.schema_name, &schema)?;
+                        SchemaUpdateResult::Updated(schema)
+                    }
+                    Err(e) => SchemaUpdateResult::Incompatible(e.to_string()),
+                }
+            } else {
+                SchemaUpdateResult::NotFound
+            };
+        Ok(serde_json::to_vec(&result)?)
+    }
+
     pub fn delete_schema(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
         let req = DeleteSchemaRequest::decode(value.as_ref())?;
         let schema_storage = SchemaStorage::new(self.rocksdb_engine_handler.clone());
@@ -147,6 +183,11 @@ pub fn set_schema_bind(&self, value: Vec<u8>) -> Result<(), PlacementCenterError
             cluster_name: req.cluster_name.clone(),
             resource_name: req.resource_name.clone(),
             schema_name: req.schema_name.clone(),
+            schema_version: if req.schema_version > 0 {
+                Some(req.schema_version)
+            } else {
+                None
+            },
         };
         schema_storage.save_bind(&req.cluster_name, bind_data)?;
         Ok(())
@@ -172,11 +213,14 @@ mod tests {
     use common_base::tools::unique_id;
     use common_base::utils::file_utils::test_temp_dir;
     use metadata_struct::placement::node::BrokerNode;
-    use protocol::placement_center::placement_center_inner::ClusterType;
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+    use prost::Message;
+    use protocol::placement_center::placement_center_inner::{ClusterType, UpdateSchemaRequest};
 
     use crate::core::cache::PlacementCacheManager;
-    use crate::route::common::DataRouteCluster;
+    use crate::route::common::{DataRouteCluster, SchemaUpdateResult};
     use crate::storage::placement::node::NodeStorage;
+    use crate::storage::placement::schema::SchemaStorage;
     use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
 
     #[tokio::test]
@@ -214,4 +258,63 @@ async fn register_unregister_node() {
         let res = node_storage.get(&cluster_name, node_id).unwrap();
         assert!(res.is_none());
     }
+
+    #[tokio::test]
+    async fn update_schema_assigns_version_in_apply() {
+        let config = placement_center_test_conf();
+        let rocksdb_engine = Arc::new(RocksDBEngine::new(
+            &test_temp_dir(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let cluster_cache = Arc::new(PlacementCacheManager::new(rocksdb_engine.clone()));
+        let route = DataRouteCluster::new(rocksdb_engine.clone(), cluster_cache);
+
+        let cluster_name = unique_id();
+        let schema = SchemaData {
+            cluster_name: cluster_name.clone(),
+            name: "s1".to_string(),
+            schema_type: SchemaType::JSON,
+            desc: "".to_string(),
+            schema: r#"{"type":"object","properties":{"a":{"type":"string"}}}"#.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::BACKWARD,
+        };
+        let update = |schema: &SchemaData| {
+            UpdateSchemaRequest {
+                cluster_name: cluster_name.clone(),
+                schema_name: "s1".to_string(),
+                schema: serde_json::to_vec(schema).unwrap(),
+            }
+            .encode_to_vec()
+        };
+
+        let res: SchemaUpdateResult =
+            serde_json::from_slice(&route.update_schema(update(&schema)).unwrap()).unwrap();
+        assert_eq!(res, SchemaUpdateResult::NotFound);
+
+        let schema_storage = SchemaStorage::new(rocksdb_engine.clone());
+        schema_storage.save(&cluster_name, "s1", &schema).unwrap();
+
+        // the version in the request is ignored, apply assigns the next one
+        let mut next = schema.clone();
+        next.version = 0;
+        next.desc = "v2".to_string();
+        let res: SchemaUpdateResult =
+            serde_json::from_slice(&route.update_schema(update(&next)).unwrap()).unwrap();
+        let SchemaUpdateResult::Updated(updated) = res else {
+            panic!("unexpected result {:?}", res);
+        };
+        assert_eq!(updated.version, 2);
+
+        let mut changed_type = schema.clone();
+        changed_type.schema_type = SchemaType::AVRO;
+        let res: SchemaUpdateResult =
+            serde_json::from_slice(&route.update_schema(update(&changed_type)).unwrap()).unwrap();
+        assert!(matches!(res, SchemaUpdateResult::Incompatible(_)));
+
+        let stored = schema_storage.get(&cluster_name, "s1").unwrap().unwrap();
+        assert_eq!(stored.version, 2);
+        assert_eq!(stored.desc, "v2");
+    }
 }
@@ -87,4 +87,24 @@ pub enum StorageDataType {
     MqttDeleteConnector,
     MqttSetAutoSubscribeRule,
     MqttDeleteAutoSubscribeRule,
+    MqttSetRule,
+    MqttDeleteRule,
+    MqttSetAlarm,
+    MqttDeleteAlarm,
+
+    // KV, appended to keep the serialized variant indexes of existing log entries stable
+    KvTxn,
+    KvLeaseGrant,
+    KvLeaseKeepAlive,
+    KvLeaseRevoke,
+
+    // Backup
+    ClusterImportBackup,
+
+    // Tenant
+    MqttSetTenant,
+    MqttDeleteTenant,
+
+    // Schema
+    SchemaUpdate,
 }
@@ -12,32 +12,317 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
 use std::sync::Arc;
 
 use prost::Message as _;
-use protocol::placement_center::placement_center_kv::{DeleteRequest, SetRequest};
+use protocol::placement_center::placement_center_kv::request_op::Op;
+use protocol::placement_center::placement_center_kv::{
+    Compare, CompareResult, CompareTarget, DeleteRequest, LeaseGrantReply, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeRequest, SetRequest, TxnReply, TxnRequest, WatchEvent,
+    WatchEventType,
+};
 
 use crate::core::error::PlacementCenterError;
-use crate::storage::placement::kv::KvStorage;
+use crate::kv::watch::KvWatchManager;
+use crate::storage::keys::is_reserved_kv_key;
+use crate::storage::placement::kv::{KvLease, KvMeta, KvStorage};
 use crate::storage::rocksdb::RocksDBEngine;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct DataRouteKv {
     kv_storage: KvStorage,
+    watch_manager: Arc<KvWatchManager>,
 }
 
 impl DataRouteKv {
-    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+    pub fn new(
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+        watch_manager: Arc<KvWatchManager>,
+    ) -> Self {
         let kv_storage = KvStorage::new(rocksdb_engine_handler.clone());
-        DataRouteKv { kv_storage }
+        DataRouteKv {
+            kv_storage,
+            watch_manager,
+        }
     }
+
     pub fn set(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
         let req: SetRequest = SetRequest::decode(value.as_ref())?;
-        Ok(self.kv_storage.set(req.key, req.value)?)
+        check_user_key(&req.key)?;
+        self.check_lease(req.lease_id)?;
+        let revision = self.kv_storage.get_revision()? + 1;
+        let event = self.put(req, revision)?;
+        self.commit(revision, vec![event])
     }
 
     pub fn delete(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
         let req: DeleteRequest = DeleteRequest::decode(value.as_ref())?;
-        Ok(self.kv_storage.delete(req.key)?)
+        check_user_key(&req.key)?;
+        let revision = self.kv_storage.get_revision()? + 1;
+        if let Some(event) = self.remove(&req.key, revision)? {
+            self.commit(revision, vec![event])?;
+        }
+        Ok(())
+    }
+
+    pub fn txn(&self, value: Vec<u8>) -> Result<Vec<u8>, PlacementCenterError> {
+        let req: TxnRequest = TxnRequest::decode(value.as_ref())?;
+
+        let mut succeeded = true;
+        for compare in req.compares.iter() {
+            let value = self.kv_storage.get(compare.key.clone())?;
+            let meta = self.kv_storage.get_meta(&compare.key)?;
+            if !compare_kv(compare, value.as_deref(), meta.as_ref()) {
+                succeeded = false;
+                break;
+            }
+        }
+
+        let ops = if succeeded { req.success } else { req.failure };
+
+        // Validate every operation up front so that a transaction is either applied
+        // as a whole or not at all.
+        for op in ops.iter() {
+            match &op.op {
+                Some(Op::Put(put)) => {
+                    check_user_key(&put.key)?;
+                    self.check_lease(put.lease_id)?;
+                }
+                Some(Op::Delete(delete)) => check_user_key(&delete.key)?,
+                None => {}
+            }
+        }
+
+        let mut revision = self.kv_storage.get_revision()?;
+        let next_revision = revision + 1;
+        let mut events = Vec::new();
+        for op in ops {
+            match op.op {
+                Some(Op::Put(put)) => events.push(self.put(put, next_revision)?),
+                Some(Op::Delete(delete)) => {
+                    if let Some(event) = self.remove(&delete.key, next_revision)? {
+                        events.push(event);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if !events.is_empty() {
+            revision = next_revision;
+            self.commit(revision, events)?;
+        }
+
+        Ok(TxnReply {
+            succeeded,
+            revision,
+        }
+        .encode_to_vec())
+    }
+
+    // The deadline is stamped by the proposer, apply only assigns the id so that every
+    // replica stores the same lease
+    pub fn lease_grant(&self, value: Vec<u8>) -> Result<Vec<u8>, PlacementCenterError> {
+        let mut lease = serde_json::from_slice::<KvLease>(&value)?;
+        lease.id = self.kv_storage.next_lease_id()?;
+        self.kv_storage.save_lease(&lease)?;
+        Ok(LeaseGrantReply {
+            lease_id: lease.id,
+            ttl: lease.ttl,
+        }
+        .encode_to_vec())
+    }
+
+    // Keep-alives are tracked on the leader only. Entries written before that still decode,
+    // but no longer move the stored deadline.
+    pub fn lease_keep_alive(&self, value: Vec<u8>) -> Result<Vec<u8>, PlacementCenterError> {
+        let req: LeaseKeepAliveRequest = LeaseKeepAliveRequest::decode(value.as_ref())?;
+        let lease = if let Some(lease) = self.kv_storage.get_lease(req.lease_id)? {
+            lease
+        } else {
+            return Err(PlacementCenterError::KvLeaseDoesNotExist(req.lease_id));
+        };
+        Ok(LeaseKeepAliveReply {
+            lease_id: lease.id,
+            ttl: lease.ttl,
+        }
+        .encode_to_vec())
+    }
+
+    pub fn lease_revoke(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req: LeaseRevokeRequest = LeaseRevokeRequest::decode(value.as_ref())?;
+        if self.kv_storage.get_lease(req.lease_id)?.is_none() {
+            return Ok(());
+        }
+
+        let revision = self.kv_storage.get_revision()? + 1;
+        let mut events = Vec::new();
+        for meta in self.kv_storage.list_meta_by_lease(req.lease_id)? {
+            if let Some(event) = self.remove(&meta.key, revision)? {
+                events.push(event);
+            }
+        }
+        self.kv_storage.delete_lease(req.lease_id)?;
+
+        if !events.is_empty() {
+            self.commit(revision, events)?;
+        }
+        Ok(())
+    }
+
+    fn check_lease(&self, lease_id: u64) -> Result<(), PlacementCenterError> {
+        if lease_id > 0 && self.kv_storage.get_lease(lease_id)?.is_none() {
+            return Err(PlacementCenterError::KvLeaseDoesNotExist(lease_id));
+        }
+        Ok(())
+    }
+
+    fn put(&self, req: SetRequest, revision: u64) -> Result<WatchEvent, PlacementCenterError> {
+        let previous = self.kv_storage.get_meta(&req.key)?;
+        let meta = match previous.clone() {
+            Some(meta) => KvMeta {
+                mod_revision: revision,
+                version: meta.version + 1,
+                lease_id: req.lease_id,
+                ..meta
+            },
+            None => KvMeta {
+                key: req.key.clone(),
+                create_revision: revision,
+                mod_revision: revision,
+                version: 1,
+                lease_id: req.lease_id,
+            },
+        };
+        self.kv_storage.set(req.key.clone(), req.value.clone())?;
+        self.kv_storage.save_meta(&meta)?;
+        let previous_lease_id = previous.map_or(0, |meta| meta.lease_id);
+        if previous_lease_id != meta.lease_id {
+            if previous_lease_id > 0 {
+                self.kv_storage
+                    .delete_lease_key(previous_lease_id, &req.key)?;
+            }
+            if meta.lease_id > 0 {
+                self.kv_storage.save_lease_key(meta.lease_id, &req.key)?;
+            }
+        }
+        Ok(WatchEvent {
+            event_type: WatchEventType::Put.into(),
+            key: req.key,
+            value: req.value,
+            create_revision: meta.create_revision,
+            mod_revision: revision,
+            version: meta.version,
+        })
+    }
+
+    fn remove(&self, key: &str, revision: u64) -> Result<Option<WatchEvent>, PlacementCenterError> {
+        if !self.kv_storage.exists(key.to_string())? {
+            return Ok(None);
+        }
+        let meta = self.kv_storage.get_meta(key)?.unwrap_or_default();
+        self.kv_storage.delete(key.to_string())?;
+        self.kv_storage.delete_meta(key)?;
+        if meta.lease_id > 0 {
+            self.kv_storage.delete_lease_key(meta.lease_id, key)?;
+        }
+        Ok(Some(WatchEvent {
+            event_type: WatchEventType::Delete.into(),
+            key: key.to_string(),
+            value: String::new(),
+            create_revision: meta.create_revision,
+            mod_revision: revision,
+            version: 0,
+        }))
+    }
+
+    fn commit(&self, revision: u64, events: Vec<WatchEvent>) -> Result<(), PlacementCenterError> {
+        self.kv_storage.save_revision(revision)?;
+        self.watch_manager.publish(events);
+        Ok(())
+    }
+}
+
+pub fn check_user_key(key: &str) -> Result<(), PlacementCenterError> {
+    if is_reserved_kv_key(key) {
+        return Err(PlacementCenterError::KvReservedKey(key.to_string()));
+    }
+    Ok(())
+}
+
+pub fn compare_kv(compare: &Compare, value: Option<&str>, meta: Option<&KvMeta>) -> bool {
+    let ordering = match compare.target() {
+        CompareTarget::Value => match value {
+            Some(value) => value.cmp(compare.value.as_str()),
+            None => return false,
+        },
+        CompareTarget::Version => meta.map_or(0, |m| m.version).cmp(&compare.number),
+        CompareTarget::CreateRevision => meta.map_or(0, |m| m.create_revision).cmp(&compare.number),
+        CompareTarget::ModRevision => meta.map_or(0, |m| m.mod_revision).cmp(&compare.number),
+        CompareTarget::Lease => meta.map_or(0, |m| m.lease_id).cmp(&compare.number),
+    };
+
+    match compare.result() {
+        CompareResult::Equal => ordering == Ordering::Equal,
+        CompareResult::NotEqual => ordering != Ordering::Equal,
+        CompareResult::Greater => ordering == Ordering::Greater,
+        CompareResult::Less => ordering == Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::placement_center::placement_center_kv::{Compare, CompareResult, CompareTarget};
+
+    use super::{check_user_key, compare_kv};
+    use crate::storage::placement::kv::KvMeta;
+
+    fn compare(target: CompareTarget, result: CompareResult, value: &str, number: u64) -> Compare {
+        Compare {
+            key: "k".to_string(),
+            target: target.into(),
+            result: result.into(),
+            value: value.to_string(),
+            number,
+        }
+    }
+
+    #[test]
+    fn check_user_key_test() {
+        assert!(check_user_key("a/b").is_ok());
+        assert!(check_user_key("/kv").is_ok());
+        assert!(check_user_key("/kv/revision").is_err());
+        assert!(check_user_key("/kv/meta/a").is_err());
+    }
+
+    #[test]
+    fn compare_kv_test() {
+        let meta = KvMeta {
+            key: "k".to_string(),
+            create_revision: 3,
+            mod_revision: 5,
+            version: 2,
+            lease_id: 0,
+        };
+
+        let cmp = compare(CompareTarget::Value, CompareResult::Equal, "v1", 0);
+        assert!(compare_kv(&cmp, Some("v1"), Some(&meta)));
+        assert!(!compare_kv(&cmp, Some("v2"), Some(&meta)));
+        assert!(!compare_kv(&cmp, None, None));
+
+        // Version 0 means the key does not exist, which is the create-if-absent idiom
+        let cmp = compare(CompareTarget::Version, CompareResult::Equal, "", 0);
+        assert!(compare_kv(&cmp, None, None));
+        assert!(!compare_kv(&cmp, Some("v1"), Some(&meta)));
+
+        let cmp = compare(CompareTarget::ModRevision, CompareResult::Less, "", 6);
+        assert!(compare_kv(&cmp, Some("v1"), Some(&meta)));
+
+        let cmp = compare(CompareTarget::CreateRevision, CompareResult::Greater, "", 3);
+        assert!(!compare_kv(&cmp, Some("v1"), Some(&meta)));
+
+        let cmp = compare(CompareTarget::Lease, CompareResult::NotEqual, "", 7);
+        assert!(compare_kv(&cmp, Some("v1"), Some(&meta)));
     }
 }
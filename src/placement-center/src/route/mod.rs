@@ -19,23 +19,37 @@
 pub mod kv;
 pub mod mqtt;
 
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 
-use bincode::{deserialize, serialize};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use data::{StorageData, StorageDataType};
-use log::{error, info};
+use log::info;
+use rocksdb::checkpoint::Checkpoint;
+use rocksdb::{BoundColumnFamily, Options, WriteBatch, DB};
 
+use crate::core::backup::{record_in_scope, BackupImportBatch};
 use crate::core::cache::PlacementCacheManager;
 use crate::core::error::PlacementCenterError;
-use crate::journal::cache::JournalCacheManager;
-use crate::mqtt::cache::MqttCacheManager;
+use crate::journal::cache::{load_journal_cache, JournalCacheManager};
+use crate::kv::lease::KvLeaseManager;
+use crate::kv::watch::KvWatchManager;
+use crate::mqtt::cache::{load_mqtt_cache, MqttCacheManager};
+use crate::raft::store::snapshot::SnapshotProgress;
 use crate::route::common::DataRouteCluster;
 use crate::route::journal::DataRouteJournal;
 use crate::route::kv::DataRouteKv;
 use crate::route::mqtt::DataRouteMqtt;
+use crate::storage::keys::{key_backup_staging, key_backup_staging_prefix};
+use crate::storage::placement::kv::KvStorage;
 use crate::storage::rocksdb::{RocksDBEngine, DB_COLUMN_FAMILY_CLUSTER};
 
+const SNAPSHOT_FILE_MAGIC: &[u8; 8] = b"RMQSNAP1";
+const SNAPSHOT_WRITE_BATCH_SIZE: usize = 1000;
+
 #[derive(Clone)]
 pub struct DataRoute {
     route_kv: DataRouteKv,
@@ -43,6 +57,11 @@ pub struct DataRoute {
     route_journal: DataRouteJournal,
     route_cluster: DataRouteCluster,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    cluster_cache: Arc<PlacementCacheManager>,
+    engine_cache: Arc<JournalCacheManager>,
+    mqtt_cache: Arc<MqttCacheManager>,
+    kv_watch_manager: Arc<KvWatchManager>,
+    kv_lease_manager: Arc<KvLeaseManager>,
 }
 
 impl DataRoute {
@@ -51,8 +70,10 @@ pub fn new(
         cluster_cache: Arc<PlacementCacheManager>,
         engine_cache: Arc<JournalCacheManager>,
         mqtt_cache: Arc<MqttCacheManager>,
+        kv_watch_manager: Arc<KvWatchManager>,
+        kv_lease_manager: Arc<KvLeaseManager>,
     ) -> DataRoute {
-        let route_kv = DataRouteKv::new(rocksdb_engine_handler.clone());
+        let route_kv = DataRouteKv::new(rocksdb_engine_handler.clone(), kv_watch_manager.clone());
         let route_mqtt = DataRouteMqtt::new(rocksdb_engine_handler.clone(), mqtt_cache.clone());
         let route_cluster =
             DataRouteCluster::new(rocksdb_engine_handler.clone(), cluster_cache.clone());
@@ -64,6 +85,11 @@ pub fn new(
             route_journal,
             route_cluster,
             rocksdb_engine_handler,
+            cluster_cache,
+            engine_cache,
+            mqtt_cache,
+            kv_watch_manager,
+            kv_lease_manager,
         }
     }
 
@@ -82,6 +108,17 @@ pub async fn route(
                 self.route_kv.delete(storage_data.value)?;
                 Ok(None)
             }
+            StorageDataType::KvTxn => Ok(Some(self.route_kv.txn(storage_data.value)?)),
+            StorageDataType::KvLeaseGrant => {
+                Ok(Some(self.route_kv.lease_grant(storage_data.value)?))
+            }
+            StorageDataType::KvLeaseKeepAlive => {
+                Ok(Some(self.route_kv.lease_keep_alive(storage_data.value)?))
+            }
+            StorageDataType::KvLeaseRevoke => {
+                self.route_kv.lease_revoke(storage_data.value)?;
+                Ok(None)
+            }
             StorageDataType::ClusterAddNode => {
                 self.route_cluster.add_node(storage_data.value).await?;
                 Ok(None)
@@ -127,6 +164,9 @@ pub async fn route(
                 self.route_cluster.set_schema(storage_data.value)?;
                 Ok(None)
             }
+            StorageDataType::SchemaUpdate => {
+                Ok(Some(self.route_cluster.update_schema(storage_data.value)?))
+            }
             StorageDataType::SchemaDelete => {
                 self.route_cluster.delete_schema(storage_data.value)?;
                 Ok(None)
@@ -246,6 +286,30 @@ pub async fn route(
                 self.route_mqtt.delete_connector(storage_data.value)?;
                 Ok(None)
             }
+            StorageDataType::MqttSetRule => {
+                self.route_mqtt.set_rule(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttDeleteRule => {
+                self.route_mqtt.delete_rule(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttSetTenant => {
+                self.route_mqtt.set_tenant(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttDeleteTenant => {
+                self.route_mqtt.delete_tenant(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttSetAlarm => {
+                self.route_mqtt.set_alarm(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttDeleteAlarm => {
+                self.route_mqtt.delete_alarm(storage_data.value)?;
+                Ok(None)
+            }
 
             // auto subscribe
             StorageDataType::MqttSetAutoSubscribeRule => {
@@ -258,78 +322,258 @@ pub async fn route(
                     .delete_auto_subscribe_rule(storage_data.value)?;
                 Ok(None)
             }
+
+            // Backup
+            StorageDataType::ClusterImportBackup => self.import_backup(storage_data.value),
         }
     }
 
-    pub fn build_snapshot(&self) -> Vec<u8> {
-        info!("Start building snapshots");
-        let cf = if let Some(cf) = self
-            .rocksdb_engine_handler
-            .cf_handle(DB_COLUMN_FAMILY_CLUSTER)
-        {
-            cf
-        } else {
-            error!(
-                "{}",
-                PlacementCenterError::RocksDBFamilyNotAvailable(
-                    DB_COLUMN_FAMILY_CLUSTER.to_string(),
-                )
-            );
-            return Vec::new();
-        };
+    /// Stages a batch of records from a backup file. The last batch of the import swaps the
+    /// staged records in, rebuilds the caches and answers with the number of records swapped
+    /// in, failed applies are only logged so the proposer needs that answer.
+    pub fn import_backup(&self, value: Vec<u8>) -> Result<Option<Vec<u8>>, PlacementCenterError> {
+        let import: BackupImportBatch = bincode::deserialize(&value)?;
+        let cf = self.cluster_cf()?;
+        let staging = key_backup_staging(&import.import_id);
+        let mut batch = WriteBatch::default();
+        if import.clear {
+            let prefix = key_backup_staging_prefix();
+            let mut iter = self.rocksdb_engine_handler.db.raw_iterator_cf(&cf);
+            iter.seek(prefix.as_bytes());
+            while iter.valid() {
+                match iter.key() {
+                    Some(key) if key.starts_with(prefix.as_bytes()) => batch.delete_cf(&cf, key),
+                    _ => break,
+                }
+                iter.next();
+            }
+            iter.status()?;
+        }
+        for (key, value) in import.records {
+            let mut staged_key = staging.as_bytes().to_vec();
+            staged_key.extend_from_slice(&key);
+            batch.put_cf(&cf, staged_key, value);
+        }
+        self.rocksdb_engine_handler.db.write(batch)?;
 
-        let res = match self.rocksdb_engine_handler.read_all_by_cf(cf) {
-            Ok(data) => data,
-            Err(e) => {
-                error!("{}", e.to_string());
-                return Vec::new();
-            }
-        };
+        if !import.finished {
+            return Ok(None);
+        }
+        self.commit_backup_import(&import)?;
+        self.reload_cache()?;
+        Ok(Some(import.total.to_be_bytes().to_vec()))
+    }
 
-        let res = match serialize(&res) {
-            Ok(data) => data,
-            Err(e) => {
-                error!("{}", e.to_string());
-                return Vec::new();
-            }
-        };
-        info!("Snapshot built successfully, snapshot size :{}", res.len());
-        res
+    // Replaces the keys in the scope of the import with the staged records in one write, so
+    // readers see either the old data or the whole backup.
+    fn commit_backup_import(&self, import: &BackupImportBatch) -> Result<(), PlacementCenterError> {
+        let cf = self.cluster_cf()?;
+        let staging_prefix = key_backup_staging_prefix();
+        let staging = key_backup_staging(&import.import_id);
+        let mut batch = WriteBatch::default();
+        let mut staged = Vec::new();
+        let mut iter = self.rocksdb_engine_handler.db.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+        while iter.valid() {
+            if let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+                if key.starts_with(staging_prefix.as_bytes()) {
+                    batch.delete_cf(&cf, key);
+                    if let Some(original) = key.strip_prefix(staging.as_bytes()) {
+                        staged.push((original.to_vec(), value.to_vec()));
+                    }
+                } else if record_in_scope(key, import.cluster_name.as_deref()) {
+                    batch.delete_cf(&cf, key);
+                }
+            }
+            iter.next();
+        }
+        iter.status()?;
+        drop(iter);
+
+        if staged.len() as u64 != import.total {
+            return Err(PlacementCenterError::BackupImportIncomplete(
+                import.import_id.clone(),
+                staged.len() as u64,
+                import.total,
+            ));
+        }
+        // Deletes come first in the batch, the puts win for keys that are in both
+        for (key, value) in staged {
+            batch.put_cf(&cf, key, value);
+        }
+        self.rocksdb_engine_handler.db.write(batch)?;
+        Ok(())
     }
 
-    pub fn recover_snapshot(&self, data: Vec<u8>) -> Result<(), PlacementCenterError> {
-        info!("Start restoring snapshot, snapshot length :{}", data.len());
+    fn reload_cache(&self) -> Result<(), PlacementCenterError> {
+        self.cluster_cache
+            .load_cache(self.rocksdb_engine_handler.clone());
+        load_journal_cache(&self.engine_cache, &self.rocksdb_engine_handler)?;
+        load_mqtt_cache(
+            &self.mqtt_cache,
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+        )?;
+
+        // The KV data was replaced without watch events, watchers have to start over and
+        // leases get a full ttl from now on.
+        let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
+        self.kv_watch_manager.reset(kv_storage.get_revision()?);
+        self.kv_lease_manager.reset(&kv_storage.list_lease()?);
+        Ok(())
+    }
+
+    /// Takes a RocksDB checkpoint of the current data into `dir`. It has to be called from
+    /// the state machine while no log is being applied, so that the checkpoint matches the
+    /// last applied log id exactly.
+    pub fn checkpoint(&self, dir: &Path) -> Result<(), PlacementCenterError> {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        Checkpoint::new(&self.rocksdb_engine_handler.db)?.create_checkpoint(dir)?;
+        Ok(())
+    }
+
+    /// Streams the cluster column family of the checkpoint in `checkpoint_dir` into `path`.
+    /// The checkpoint is a separate database, so logs keep being applied meanwhile.
+    pub fn build_snapshot(
+        &self,
+        checkpoint_dir: &Path,
+        path: &Path,
+        progress: &SnapshotProgress,
+    ) -> Result<(), PlacementCenterError> {
+        info!("Start building snapshot {:?}", path);
         let now = Instant::now();
-        let records = match deserialize::<Vec<(String, Vec<u8>)>>(&data) {
-            Ok(data) => data,
-            Err(e) => {
-                return Err(PlacementCenterError::CommonError(e.to_string()));
-            }
-        };
+        let db = DB::open_cf_for_read_only(
+            &Options::default(),
+            checkpoint_dir,
+            [DB_COLUMN_FAMILY_CLUSTER],
+            false,
+        )?;
+        let cf = db.cf_handle(DB_COLUMN_FAMILY_CLUSTER).ok_or_else(|| {
+            PlacementCenterError::RocksDBFamilyNotAvailable(DB_COLUMN_FAMILY_CLUSTER.to_string())
+        })?;
+        let mut iter = db.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(SNAPSHOT_FILE_MAGIC)?;
+        while iter.valid() {
+            if let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+                write_record_field(&mut writer, key)?;
+                write_record_field(&mut writer, value)?;
+                progress.record_written((key.len() + value.len()) as u64);
+            }
+            iter.next();
+        }
+        iter.status()?;
 
-        let cf = if let Some(cf) = self
-            .rocksdb_engine_handler
-            .cf_handle(DB_COLUMN_FAMILY_CLUSTER)
-        {
-            cf
-        } else {
-            return Err(PlacementCenterError::RocksDBFamilyNotAvailable(
-                DB_COLUMN_FAMILY_CLUSTER.to_string(),
-            ));
-        };
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+
+        let status = progress.status();
+        info!(
+            "Snapshot built successfully, records: {}, size: {}, time: {}",
+            status.records,
+            status.bytes,
+            now.elapsed().as_millis()
+        );
+        Ok(())
+    }
 
-        for raw in records {
-            self.rocksdb_engine_handler
-                .write_raw(cf.clone(), &raw.0, &raw.1)?;
+    /// Replaces the cluster column family with the content of the snapshot file at `path`.
+    /// The data is written in several batches, callers must be able to run it again from
+    /// the start if it is interrupted.
+    pub fn recover_snapshot(&self, path: &Path) -> Result<(), PlacementCenterError> {
+        info!("Start restoring snapshot {:?}", path);
+        let now = Instant::now();
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != SNAPSHOT_FILE_MAGIC {
+            return Err(PlacementCenterError::CommonError(format!(
+                "Snapshot file {:?} has an unknown format",
+                path
+            )));
+        }
+
+        let cf = self.cluster_cf()?;
+        let db = &self.rocksdb_engine_handler.db;
+
+        let mut batch = WriteBatch::default();
+        let mut iter = db.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+        while iter.valid() {
+            if let Some(key) = iter.key() {
+                batch.delete_cf(&cf, key);
+            }
+            if batch.len() >= SNAPSHOT_WRITE_BATCH_SIZE {
+                db.write(std::mem::take(&mut batch))?;
+            }
+            iter.next();
         }
+        iter.status()?;
+        db.write(std::mem::take(&mut batch))?;
+
+        let mut records = 0;
+        while let Some(key) = read_record_field(&mut reader)? {
+            let value = match read_record_field(&mut reader)? {
+                Some(value) => value,
+                None => {
+                    return Err(PlacementCenterError::CommonError(format!(
+                        "Snapshot file {:?} is truncated",
+                        path
+                    )));
+                }
+            };
+            batch.put_cf(&cf, key, value);
+            records += 1;
+            if batch.len() >= SNAPSHOT_WRITE_BATCH_SIZE {
+                db.write(std::mem::take(&mut batch))?;
+            }
+        }
+        db.write(batch)?;
 
         info!(
-            "Snapshot recovery was successful, snapshot size {}, time: {}",
-            data.len(),
+            "Snapshot recovery was successful, records: {}, time: {}",
+            records,
             now.elapsed().as_millis()
         );
         Ok(())
     }
+
+    fn cluster_cf(&self) -> Result<Arc<BoundColumnFamily>, PlacementCenterError> {
+        self.rocksdb_engine_handler
+            .cf_handle(DB_COLUMN_FAMILY_CLUSTER)
+            .ok_or_else(|| {
+                PlacementCenterError::RocksDBFamilyNotAvailable(
+                    DB_COLUMN_FAMILY_CLUSTER.to_string(),
+                )
+            })
+    }
+}
+
+// Snapshot and backup files store records as length prefixed key and value pairs.
+pub(crate) fn write_record_field(
+    writer: &mut impl Write,
+    data: &[u8],
+) -> Result<(), PlacementCenterError> {
+    writer.write_u32::<BigEndian>(data.len() as u32)?;
+    writer.write_all(data)?;
+    Ok(())
+}
+
+pub(crate) fn read_record_field(
+    reader: &mut impl Read,
+) -> Result<Option<Vec<u8>>, PlacementCenterError> {
+    let len = match reader.read_u32::<BigEndian>() {
+        Ok(len) => len,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut data = vec![0u8; len as usize];
+    reader.read_exact(&mut data)?;
+    Ok(Some(data))
 }
 
 #[cfg(test)]
@@ -339,12 +583,99 @@ mod test {
     use tempfile::tempdir;
 
     use crate::{
-        core::cache::PlacementCacheManager, journal::cache::JournalCacheManager,
-        mqtt::cache::MqttCacheManager, storage::rocksdb::DB_COLUMN_FAMILY_CLUSTER,
+        core::backup::BackupImportBatch,
+        core::cache::PlacementCacheManager,
+        journal::cache::JournalCacheManager,
+        kv::lease::KvLeaseManager,
+        kv::watch::{KvWatchManager, KV_WATCH_HISTORY_CAPACITY},
+        mqtt::cache::MqttCacheManager,
+        raft::store::snapshot::SnapshotProgress,
+        storage::keys::{key_backup_staging_prefix, key_resource_config},
+        storage::rocksdb::DB_COLUMN_FAMILY_CLUSTER,
     };
 
     use super::DataRoute;
 
+    fn test_data_route(rocksdb_engine: Arc<RocksDBEngine>) -> DataRoute {
+        DataRoute::new(
+            rocksdb_engine.clone(),
+            Arc::new(PlacementCacheManager::new(rocksdb_engine)),
+            Arc::new(JournalCacheManager::new()),
+            Arc::new(MqttCacheManager::new()),
+            Arc::new(KvWatchManager::new(KV_WATCH_HISTORY_CAPACITY, 0)),
+            Arc::new(KvLeaseManager::default()),
+        )
+    }
+
+    #[test]
+    pub fn import_backup_staging_test() {
+        let rocksdb_engine = Arc::new(RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            vec![DB_COLUMN_FAMILY_CLUSTER.to_string()],
+        ));
+        let cf = rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_CLUSTER).unwrap();
+        let key = |cluster: &str, name: &str| key_resource_config(cluster.into(), name.into());
+        let read = |key: &str| rocksdb_engine.read::<String>(cf.clone(), key).unwrap();
+        for (cluster, name) in [("c1", "a"), ("c1", "old"), ("c2", "a")] {
+            rocksdb_engine
+                .write(cf.clone(), &key(cluster, name), &"before".to_string())
+                .unwrap();
+        }
+        let data_route = test_data_route(rocksdb_engine.clone());
+
+        let batch = |import_id: &str, names: &[&str], clear: bool, finished: bool, total: u64| {
+            let records = names
+                .iter()
+                .map(|name| {
+                    let value = serde_json::to_vec(&"after".to_string()).unwrap();
+                    (key("c1", name).into_bytes(), value)
+                })
+                .collect();
+            bincode::serialize(&BackupImportBatch {
+                import_id: import_id.to_string(),
+                records,
+                finished,
+                clear,
+                cluster_name: Some("c1".to_string()),
+                total,
+            })
+            .unwrap()
+        };
+
+        // Staged batches leave the cluster data untouched
+        assert!(data_route
+            .import_backup(batch("i1", &["a"], true, false, 2))
+            .unwrap()
+            .is_none());
+        assert_eq!(read(&key("c1", "a")), Some("before".to_string()));
+        assert_eq!(read(&key("c1", "old")), Some("before".to_string()));
+
+        // A last batch that does not find every staged record changes nothing
+        assert!(data_route
+            .import_backup(batch("i1", &["b"], false, true, 3))
+            .is_err());
+        assert_eq!(read(&key("c1", "a")), Some("before".to_string()));
+
+        // Starting over drops the staged records of the unfinished import
+        data_route
+            .import_backup(batch("i2", &["a"], true, false, 2))
+            .unwrap();
+        let reply = data_route
+            .import_backup(batch("i2", &["b"], false, true, 2))
+            .unwrap();
+        assert_eq!(reply, Some(2u64.to_be_bytes().to_vec()));
+        assert_eq!(read(&key("c1", "a")), Some("after".to_string()));
+        assert_eq!(read(&key("c1", "b")), Some("after".to_string()));
+        assert_eq!(read(&key("c1", "old")), None);
+        assert_eq!(read(&key("c2", "a")), Some("before".to_string()));
+
+        let prefix = key_backup_staging_prefix();
+        let mut iter = rocksdb_engine.db.raw_iterator_cf(&cf);
+        iter.seek(prefix.as_bytes());
+        assert!(!iter.valid() || !iter.key().unwrap().starts_with(prefix.as_bytes()));
+    }
+
     #[test]
     pub fn snapshot_test() {
         let rocksdb_engine = Arc::new(RocksDBEngine::new(
@@ -364,15 +695,31 @@ pub fn snapshot_test() {
         let cluster_cache = Arc::new(PlacementCacheManager::new(rocksdb_engine.clone()));
         let engine_cache = Arc::new(JournalCacheManager::new());
         let mqtt_cache = Arc::new(MqttCacheManager::new());
+        let kv_watch_manager = Arc::new(KvWatchManager::new(KV_WATCH_HISTORY_CAPACITY, 0));
+        let kv_lease_manager = Arc::new(KvLeaseManager::default());
 
         let data_route = DataRoute::new(
             rocksdb_engine.clone(),
             cluster_cache.clone(),
             engine_cache.clone(),
             mqtt_cache.clone(),
+            kv_watch_manager.clone(),
+            kv_lease_manager.clone(),
         );
 
-        let snapshot = data_route.build_snapshot();
+        let snapshot_dir = tempdir().unwrap();
+        let checkpoint = snapshot_dir.path().join("1-10-1.checkpoint");
+        data_route.checkpoint(&checkpoint).unwrap();
+
+        // Writes after the checkpoint are not part of the snapshot
+        rocksdb_engine.write(cf.clone(), "key-10", &10).unwrap();
+
+        let snapshot = snapshot_dir.path().join("1-10-1.snap");
+        let progress = SnapshotProgress::default();
+        data_route
+            .build_snapshot(&checkpoint, &snapshot, &progress)
+            .unwrap();
+        assert_eq!(progress.status().records, 10);
 
         // GET A NEW ONE
 
@@ -382,14 +729,21 @@ pub fn snapshot_test() {
             vec![DB_COLUMN_FAMILY_CLUSTER.to_string()],
         ));
 
+        let new_cf = new_rocksdb_engine
+            .cf_handle(DB_COLUMN_FAMILY_CLUSTER)
+            .unwrap();
+        new_rocksdb_engine.write(new_cf, "stale-key", &100).unwrap();
+
         let new_data_route = DataRoute::new(
             new_rocksdb_engine.clone(),
             cluster_cache,
             engine_cache,
             mqtt_cache,
+            kv_watch_manager,
+            kv_lease_manager,
         );
 
-        new_data_route.recover_snapshot(snapshot).unwrap();
+        new_data_route.recover_snapshot(&snapshot).unwrap();
 
         let cf = new_rocksdb_engine
             .cf_handle(DB_COLUMN_FAMILY_CLUSTER)
@@ -404,5 +758,15 @@ pub fn snapshot_test() {
 
             assert_eq!(i, value);
         }
+
+        // Recovering replaces the existing content instead of merging into it
+        assert!(new_rocksdb_engine
+            .read::<i32>(cf.clone(), "stale-key")
+            .unwrap()
+            .is_none());
+        assert!(new_rocksdb_engine
+            .read::<i32>(cf.clone(), "key-10")
+            .unwrap()
+            .is_none());
     }
 }
@@ -17,32 +17,39 @@
 use common_base::tools::now_mills;
 use metadata_struct::acl::mqtt_acl::MqttAcl;
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
+use metadata_struct::mqtt::alarm::MqttAlarm;
 use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
 use metadata_struct::mqtt::bridge::connector::MQTTConnector;
+use metadata_struct::mqtt::rule::MqttRule;
 use metadata_struct::mqtt::session::MqttSession;
 use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
+use metadata_struct::mqtt::tenant::MqttTenant;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
 use metadata_struct::mqtt::user::MqttUser;
 use prost::Message as _;
 use protocol::mqtt::common::{qos, retain_forward_rule, Error, QoS, RetainForwardRule};
 use protocol::placement_center::placement_center_mqtt::{
-    CreateAclRequest, CreateBlacklistRequest, CreateConnectorRequest, CreateSessionRequest,
-    CreateTopicRequest, CreateTopicRewriteRuleRequest, CreateUserRequest, DeleteAclRequest,
-    DeleteAutoSubscribeRuleRequest, DeleteBlacklistRequest, DeleteConnectorRequest,
-    DeleteSessionRequest, DeleteSubscribeRequest, DeleteTopicRequest,
-    DeleteTopicRewriteRuleRequest, DeleteUserRequest, SaveLastWillMessageRequest,
-    SetAutoSubscribeRuleRequest, SetSubscribeRequest, UpdateSessionRequest,
+    CreateAclRequest, CreateBlacklistRequest, CreateConnectorRequest, CreateRuleRequest,
+    CreateSessionRequest, CreateTenantRequest, CreateTopicRequest, CreateTopicRewriteRuleRequest,
+    CreateUserRequest, DeleteAclRequest, DeleteAlarmRequest, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistRequest, DeleteConnectorRequest, DeleteRuleRequest, DeleteSessionRequest,
+    DeleteSubscribeRequest, DeleteTenantRequest, DeleteTopicRequest, DeleteTopicRewriteRuleRequest,
+    DeleteUserRequest, SaveLastWillMessageRequest, SetAlarmRequest, SetAutoSubscribeRuleRequest,
+    SetSubscribeRequest, UpdateSessionRequest,
 };
 
 use crate::core::error::PlacementCenterError;
 use crate::mqtt::cache::MqttCacheManager;
 use crate::storage::mqtt::acl::AclStorage;
+use crate::storage::mqtt::alarm::MqttAlarmStorage;
 use crate::storage::mqtt::blacklist::MqttBlackListStorage;
 use crate::storage::mqtt::connector::MqttConnectorStorage;
 use crate::storage::mqtt::lastwill::MqttLastWillStorage;
+use crate::storage::mqtt::rule::MqttRuleStorage;
 use crate::storage::mqtt::session::MqttSessionStorage;
 use crate::storage::mqtt::subscribe::MqttSubscribeStorage;
+use crate::storage::mqtt::tenant::MqttTenantStorage;
 use crate::storage::mqtt::topic::MqttTopicStorage;
 use crate::storage::mqtt::user::MqttUserStorage;
 use crate::storage::rocksdb::RocksDBEngine;
@@ -225,6 +232,54 @@ pub fn delete_connector(&self, value: Vec<u8>) -> Result<(), PlacementCenterErro
         Ok(())
     }
 
+    // Rule
+    pub fn set_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let storage = MqttRuleStorage::new(self.rocksdb_engine_handler.clone());
+        let req = CreateRuleRequest::decode(value.as_ref())?;
+        let rule = serde_json::from_slice::<MqttRule>(&req.rule)?;
+        storage.save(&req.cluster_name, &req.rule_name, &rule)?;
+        Ok(())
+    }
+
+    pub fn delete_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let storage = MqttRuleStorage::new(self.rocksdb_engine_handler.clone());
+        let req = DeleteRuleRequest::decode(value.as_ref())?;
+        storage.delete(&req.cluster_name, &req.rule_name)?;
+        Ok(())
+    }
+
+    // Tenant
+    pub fn set_tenant(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let storage = MqttTenantStorage::new(self.rocksdb_engine_handler.clone());
+        let req = CreateTenantRequest::decode(value.as_ref())?;
+        let tenant = serde_json::from_slice::<MqttTenant>(&req.tenant)?;
+        storage.save(&req.cluster_name, &req.tenant_name, &tenant)?;
+        Ok(())
+    }
+
+    pub fn delete_tenant(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let storage = MqttTenantStorage::new(self.rocksdb_engine_handler.clone());
+        let req = DeleteTenantRequest::decode(value.as_ref())?;
+        storage.delete(&req.cluster_name, &req.tenant_name)?;
+        Ok(())
+    }
+
+    // Alarm
+    pub fn set_alarm(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let storage = MqttAlarmStorage::new(self.rocksdb_engine_handler.clone());
+        let req = SetAlarmRequest::decode(value.as_ref())?;
+        let alarm = serde_json::from_slice::<MqttAlarm>(&req.alarm)?;
+        storage.save(&req.cluster_name, &req.alarm_id, &alarm)?;
+        Ok(())
+    }
+
+    pub fn delete_alarm(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let storage = MqttAlarmStorage::new(self.rocksdb_engine_handler.clone());
+        let req = DeleteAlarmRequest::decode(value.as_ref())?;
+        storage.delete(&req.cluster_name, &req.alarm_id)?;
+        Ok(())
+    }
+
     // ACL
     pub fn create_acl(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
         let req = CreateAclRequest::decode(value.as_ref())?;
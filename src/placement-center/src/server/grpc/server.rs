@@ -27,8 +27,11 @@
 use crate::core::metrics::{metrics_grpc_request_incr, metrics_grpc_request_ms};
 use crate::journal::cache::JournalCacheManager;
 use crate::journal::controller::call_node::JournalInnerCallManager;
+use crate::kv::lease::KvLeaseManager;
+use crate::kv::watch::KvWatchManager;
 use crate::mqtt::cache::MqttCacheManager;
 use crate::mqtt::controller::call_broker::MQTTInnerCallManager;
+use crate::raft::store::snapshot::SnapshotProgress;
 use crate::route::apply::RaftMachineApply;
 use crate::server::grpc::service_inner::GrpcPlacementService;
 use crate::server::grpc::service_journal::GrpcEngineService;
@@ -55,6 +58,9 @@ pub async fn start_grpc_server(
     client_pool: Arc<ClientPool>,
     journal_call_manager: Arc<JournalInnerCallManager>,
     mqtt_call_manager: Arc<MQTTInnerCallManager>,
+    kv_watch_manager: Arc<KvWatchManager>,
+    kv_lease_manager: Arc<KvLeaseManager>,
+    snapshot_progress: Arc<SnapshotProgress>,
 ) -> Result<(), PlacementCenterError> {
     let config = placement_center_conf();
     let ip = format!("{}:{}", config.network.local_ip, config.network.grpc_port).parse()?;
@@ -68,7 +74,12 @@ pub async fn start_grpc_server(
         mqtt_call_manager.clone(),
     );
 
-    let kv_handler = GrpcKvService::new(raft_machine_apply.clone(), rocksdb_engine_handler.clone());
+    let kv_handler = GrpcKvService::new(
+        raft_machine_apply.clone(),
+        rocksdb_engine_handler.clone(),
+        kv_watch_manager,
+        kv_lease_manager,
+    );
 
     let engine_handler = GrpcEngineService::new(
         raft_machine_apply.clone(),
@@ -79,7 +90,8 @@ pub async fn start_grpc_server(
         client_pool.clone(),
     );
 
-    let openraft_handler = GrpcOpenRaftServices::new(raft_machine_apply.openraft_node.clone());
+    let openraft_handler =
+        GrpcOpenRaftServices::new(raft_machine_apply.openraft_node.clone(), snapshot_progress);
 
     let mqtt_handler = GrpcMqttService::new(
         cluster_cache.clone(),
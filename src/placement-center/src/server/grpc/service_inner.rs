@@ -12,37 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::pin::Pin;
 use std::sync::Arc;
 
+use common_base::config::placement_center::placement_center_conf;
 use common_base::error::common::CommonError;
 use common_base::tools::now_second;
+use futures::Stream;
 use grpc_clients::pool::ClientPool;
 use log::{debug, info};
 use prost::Message;
 use prost_validate::Validator;
 use protocol::placement_center::placement_center_inner::placement_center_service_server::PlacementCenterService;
 use protocol::placement_center::placement_center_inner::{
-    BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest,
-    CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
-    DeleteResourceConfigReply, DeleteResourceConfigRequest, DeleteSchemaReply, DeleteSchemaRequest,
-    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, GetOffsetDataReply,
-    GetOffsetDataReplyOffset, GetOffsetDataRequest, GetResourceConfigReply,
-    GetResourceConfigRequest, HeartbeatReply, HeartbeatRequest, ListBindSchemaReply,
-    ListBindSchemaRequest, ListSchemaReply, ListSchemaRequest, NodeListReply, NodeListRequest,
-    RegisterNodeReply, RegisterNodeRequest, ReportMonitorReply, ReportMonitorRequest,
-    SaveOffsetDataReply, SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
-    SetResourceConfigReply, SetResourceConfigRequest, UnBindSchemaReply, UnBindSchemaRequest,
-    UnRegisterNodeReply, UnRegisterNodeRequest, UpdateSchemaReply, UpdateSchemaRequest,
+    BackupReply, BackupRequest, BindSchemaReply, BindSchemaRequest, ClusterStatusReply,
+    ClusterStatusRequest, CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply,
+    DeleteIdempotentDataRequest, DeleteResourceConfigReply, DeleteResourceConfigRequest,
+    DeleteSchemaReply, DeleteSchemaRequest, ExistsIdempotentDataReply, ExistsIdempotentDataRequest,
+    GetOffsetDataReply, GetOffsetDataReplyOffset, GetOffsetDataRequest, GetResourceConfigReply,
+    GetResourceConfigRequest, GetSchemaReply, GetSchemaRequest, HeartbeatReply, HeartbeatRequest,
+    ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply, ListSchemaRequest,
+    ListSchemaVersionReply, ListSchemaVersionRequest, NodeListReply, NodeListRequest,
+    RegisterNodeReply, RegisterNodeRequest, ReportMonitorReply, ReportMonitorRequest, RestoreReply,
+    RestoreRequest, SaveOffsetDataReply, SaveOffsetDataRequest, SetIdempotentDataReply,
+    SetIdempotentDataRequest, SetResourceConfigReply, SetResourceConfigRequest, UnBindSchemaReply,
+    UnBindSchemaRequest, UnRegisterNodeReply, UnRegisterNodeRequest, UpdateSchemaReply,
+    UpdateSchemaRequest,
 };
-use tonic::{Request, Response, Status};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tonic::{Request, Response, Status, Streaming};
 
 use super::validate::ValidateExt;
+use crate::core::backup::{build_backup_file, import_backup_file, BackupHeader};
 use crate::core::cache::PlacementCacheManager;
 use crate::core::cluster::{register_node_by_req, un_register_node_by_req};
 use crate::core::error::PlacementCenterError;
 use crate::core::schema::{
-    bind_schema_req, create_schema_req, delete_schema_req, list_bind_schema_req, list_schema_req,
-    un_bind_schema_req, update_schema_req,
+    bind_schema_req, create_schema_req, delete_schema_req, get_schema_req, list_bind_schema_req,
+    list_schema_req, list_schema_version_req, un_bind_schema_req, update_schema_req,
 };
 use crate::journal::controller::call_node::JournalInnerCallManager;
 use crate::mqtt::controller::call_broker::MQTTInnerCallManager;
@@ -53,6 +60,8 @@
 use crate::storage::placement::offset::OffsetStorage;
 use crate::storage::rocksdb::RocksDBEngine;
 
+const BACKUP_CHUNK_SIZE: usize = 1024 * 1024;
+
 pub struct GrpcPlacementService {
     raft_machine_apply: Arc<RaftMachineApply>,
     cluster_cache: Arc<PlacementCacheManager>,
@@ -84,6 +93,8 @@ pub fn new(
 
 #[tonic::async_trait]
 impl PlacementCenterService for GrpcPlacementService {
+    type BackupStream = Pin<Box<dyn Stream<Item = Result<BackupReply, Status>> + Send>>;
+
     async fn cluster_status(
         &self,
         _: Request<ClusterStatusRequest>,
@@ -388,6 +399,36 @@ async fn list_schema(
         }
     }
 
+    async fn get_schema(
+        &self,
+        request: Request<GetSchemaRequest>,
+    ) -> Result<Response<GetSchemaReply>, Status> {
+        let req = request.into_inner();
+        match get_schema_req(&self.rocksdb_engine_handler, &req) {
+            Ok(data) => {
+                return Ok(Response::new(GetSchemaReply { schema: data }));
+            }
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn list_schema_version(
+        &self,
+        request: Request<ListSchemaVersionRequest>,
+    ) -> Result<Response<ListSchemaVersionReply>, Status> {
+        let req = request.into_inner();
+        match list_schema_version_req(&self.rocksdb_engine_handler, &req) {
+            Ok(data) => {
+                return Ok(Response::new(ListSchemaVersionReply { schemas: data }));
+            }
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
     async fn create_schema(
         &self,
         request: Request<CreateSchemaRequest>,
@@ -417,7 +458,6 @@ async fn update_schema(
     ) -> Result<Response<UpdateSchemaReply>, Status> {
         let req = request.into_inner();
         match update_schema_req(
-            &self.rocksdb_engine_handler,
             &self.raft_machine_apply,
             &self.mqtt_call_manager,
             &self.client_pool,
@@ -478,6 +518,7 @@ async fn bind_schema(
     ) -> Result<Response<BindSchemaReply>, Status> {
         let req = request.into_inner();
         match bind_schema_req(
+            &self.rocksdb_engine_handler,
             &self.raft_machine_apply,
             &self.mqtt_call_manager,
             &self.client_pool,
@@ -515,4 +556,86 @@ async fn un_bind_schema(
             }
         }
     }
+
+    async fn backup(
+        &self,
+        request: Request<BackupRequest>,
+    ) -> Result<Response<Self::BackupStream>, Status> {
+        let req = request.into_inner();
+        let cluster_name = if req.cluster_name.is_empty() {
+            None
+        } else {
+            if self.cluster_cache.get_cluster(&req.cluster_name).is_none() {
+                return Err(Status::cancelled(
+                    PlacementCenterError::ClusterDoesNotExist(req.cluster_name).to_string(),
+                ));
+            }
+            Some(req.cluster_name)
+        };
+
+        let last_applied_index = self
+            .raft_machine_apply
+            .openraft_node
+            .metrics()
+            .borrow()
+            .last_applied
+            .as_ref()
+            .map(|log_id| log_id.index);
+        let header = BackupHeader::new(
+            placement_center_conf().cluster_name.clone(),
+            cluster_name,
+            last_applied_index,
+        );
+        let file = build_backup_file(self.rocksdb_engine_handler.clone(), header)
+            .await
+            .map_err(|e| Status::cancelled(e.to_string()))?;
+
+        let stream =
+            futures::stream::unfold(Some(tokio::fs::File::from_std(file)), |file| async move {
+                let mut file = file?;
+                let mut data = vec![0u8; BACKUP_CHUNK_SIZE];
+                match file.read(&mut data).await {
+                    Ok(0) => None,
+                    Ok(len) => {
+                        data.truncate(len);
+                        Some((Ok(BackupReply { data }), Some(file)))
+                    }
+                    Err(e) => Some((Err(Status::cancelled(e.to_string())), None)),
+                }
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn restore(
+        &self,
+        request: Request<Streaming<RestoreRequest>>,
+    ) -> Result<Response<RestoreReply>, Status> {
+        let mut stream = request.into_inner();
+        let mut file = tokio::fs::File::from_std(
+            tempfile::tempfile().map_err(|e| Status::cancelled(e.to_string()))?,
+        );
+        let mut cluster_name = None;
+        while let Some(req) = stream.message().await? {
+            if !req.cluster_name.is_empty() {
+                cluster_name = Some(req.cluster_name);
+            }
+            file.write_all(&req.data)
+                .await
+                .map_err(|e| Status::cancelled(e.to_string()))?;
+        }
+        file.flush()
+            .await
+            .map_err(|e| Status::cancelled(e.to_string()))?;
+
+        match import_backup_file(
+            &self.raft_machine_apply,
+            file.into_std().await,
+            cluster_name.as_deref(),
+        )
+        .await
+        {
+            Ok(records) => Ok(Response::new(RestoreReply { records })),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
 }
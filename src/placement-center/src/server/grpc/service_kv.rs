@@ -12,41 +12,87 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::pin::Pin;
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use futures::Stream;
+use openraft::raft::ClientWriteResponse;
 use prost::Message;
 use protocol::placement_center::placement_center_kv::kv_service_server::KvService;
+use protocol::placement_center::placement_center_kv::request_op::Op;
 use protocol::placement_center::placement_center_kv::{
     DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest, ListShardReply, ListShardRequest,
+    SetReply, SetRequest, TxnReply, TxnRequest, WatchReply, WatchRequest,
 };
 use tonic::{Request, Response, Status};
 
+use crate::core::error::PlacementCenterError;
+use crate::kv::lease::{lease_deadline, KvLeaseManager};
+use crate::kv::watch::KvWatchManager;
+use crate::raft::typeconfig::TypeConfig;
 use crate::route::apply::RaftMachineApply;
 use crate::route::data::{StorageData, StorageDataType};
-use crate::storage::placement::kv::KvStorage;
+use crate::route::kv::check_user_key;
+use crate::storage::placement::kv::{KvLease, KvStorage};
 use crate::storage::rocksdb::RocksDBEngine;
 
 pub struct GrpcKvService {
     raft_machine_apply: Arc<RaftMachineApply>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    kv_watch_manager: Arc<KvWatchManager>,
+    kv_lease_manager: Arc<KvLeaseManager>,
 }
 
 impl GrpcKvService {
     pub fn new(
         raft_machine_apply: Arc<RaftMachineApply>,
         rocksdb_engine_handler: Arc<RocksDBEngine>,
+        kv_watch_manager: Arc<KvWatchManager>,
+        kv_lease_manager: Arc<KvLeaseManager>,
     ) -> Self {
         GrpcKvService {
             raft_machine_apply,
             rocksdb_engine_handler,
+            kv_watch_manager,
+            kv_lease_manager,
         }
     }
+
+    fn check_lease(&self, lease_id: u64) -> Result<(), Status> {
+        if lease_id == 0 {
+            return Ok(());
+        }
+        let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
+        match kv_storage.get_lease(lease_id) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(Status::cancelled(
+                PlacementCenterError::KvLeaseDoesNotExist(lease_id).to_string(),
+            )),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+}
+
+fn check_request_key(key: &str) -> Result<(), Status> {
+    check_user_key(key).map_err(|e| Status::cancelled(e.to_string()))
+}
+
+fn decode_write_reply<T: Message + Default>(
+    resp: Option<ClientWriteResponse<TypeConfig>>,
+) -> Result<T, PlacementCenterError> {
+    if let Some(value) = resp.and_then(|resp| resp.data.value) {
+        return Ok(T::decode(value.as_ref())?);
+    }
+    Err(PlacementCenterError::ExecutionResultIsEmpty)
 }
 
 #[tonic::async_trait]
 impl KvService for GrpcKvService {
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<WatchReply, Status>> + Send>>;
+
     async fn set(&self, request: Request<SetRequest>) -> Result<Response<SetReply>, Status> {
         let req = request.into_inner();
 
@@ -55,7 +101,9 @@ async fn set(&self, request: Request<SetRequest>) -> Result<Response<SetReply>,
                 CommonError::ParameterCannotBeNull("key or value".to_string()).to_string(),
             ));
         }
-        println!("ffff set");
+        check_request_key(&req.key)?;
+        self.check_lease(req.lease_id)?;
+
         // Raft state machine is used to store Node data
         let data = StorageData::new(StorageDataType::KvSet, SetRequest::encode_to_vec(&req));
         match self.raft_machine_apply.client_write(data).await {
@@ -74,13 +122,24 @@ async fn get(&self, request: Request<GetRequest>) -> Result<Response<GetReply>,
                 CommonError::ParameterCannotBeNull("key".to_string()).to_string(),
             ));
         }
+        check_request_key(&req.key)?;
 
         let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
         let mut reply = GetReply::default();
-        match kv_storage.get(req.key) {
+        match kv_storage.get(req.key.clone()) {
             Ok(Some(data)) => {
                 reply.value = data;
-                return Ok(Response::new(reply));
+            }
+            Ok(None) => return Ok(Response::new(reply)),
+            Err(e) => return Err(Status::cancelled(e.to_string())),
+        }
+
+        match kv_storage.get_meta(&req.key) {
+            Ok(Some(meta)) => {
+                reply.create_revision = meta.create_revision;
+                reply.mod_revision = meta.mod_revision;
+                reply.version = meta.version;
+                reply.lease_id = meta.lease_id;
             }
             Ok(None) => {}
             Err(e) => return Err(Status::cancelled(e.to_string())),
@@ -100,6 +159,7 @@ async fn delete(
                 CommonError::ParameterCannotBeNull("key".to_string()).to_string(),
             ));
         }
+        check_request_key(&req.key)?;
 
         // Raft state machine is used to store Node data
         let data = StorageData::new(
@@ -125,6 +185,7 @@ async fn exists(
                 CommonError::ParameterCannotBeNull("key".to_string()).to_string(),
             ));
         }
+        check_request_key(&req.key)?;
 
         let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
         match kv_storage.exists(req.key) {
@@ -168,6 +229,7 @@ async fn get_prefix(
                 CommonError::ParameterCannotBeNull("prefix".to_string()).to_string(),
             ));
         }
+        check_request_key(&req.prefix)?;
 
         let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
 
@@ -176,4 +238,162 @@ async fn get_prefix(
             Err(e) => Err(Status::cancelled(e.to_string())),
         }
     }
+
+    async fn watch(
+        &self,
+        request: Request<WatchRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let req = request.into_inner();
+
+        if req.key.is_empty() {
+            return Err(Status::cancelled(
+                CommonError::ParameterCannotBeNull("key".to_string()).to_string(),
+            ));
+        }
+        check_request_key(&req.key)?;
+
+        let receiver = self
+            .kv_watch_manager
+            .watch(req.key, req.prefix, req.start_revision)
+            .map_err(|e| Status::cancelled(e.to_string()))?;
+
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver
+                .recv()
+                .await
+                .map(|events| (Ok(WatchReply { events }), receiver))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn lease_grant(
+        &self,
+        request: Request<LeaseGrantRequest>,
+    ) -> Result<Response<LeaseGrantReply>, Status> {
+        let req = request.into_inner();
+
+        if req.ttl == 0 {
+            return Err(Status::cancelled(
+                CommonError::ParameterCannotBeNull("ttl".to_string()).to_string(),
+            ));
+        }
+
+        let lease = KvLease {
+            id: 0,
+            ttl: req.ttl,
+            expire_at_ms: lease_deadline(req.ttl),
+        };
+        let data = StorageData::new(
+            StorageDataType::KvLeaseGrant,
+            serde_json::to_vec(&lease).map_err(|e| Status::cancelled(e.to_string()))?,
+        );
+        match self.raft_machine_apply.client_write(data).await {
+            Ok(resp) => decode_write_reply::<LeaseGrantReply>(resp)
+                .map(Response::new)
+                .map_err(|e| Status::cancelled(e.to_string())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn lease_keep_alive(
+        &self,
+        request: Request<LeaseKeepAliveRequest>,
+    ) -> Result<Response<LeaseKeepAliveReply>, Status> {
+        let req = request.into_inner();
+
+        if req.lease_id == 0 {
+            return Err(Status::cancelled(
+                CommonError::ParameterCannotBeNull("lease_id".to_string()).to_string(),
+            ));
+        }
+        self.raft_machine_apply
+            .ensure_leader()
+            .map_err(|e| Status::cancelled(e.to_string()))?;
+
+        let kv_storage = KvStorage::new(self.rocksdb_engine_handler.clone());
+        let lease = match kv_storage.get_lease(req.lease_id) {
+            Ok(Some(lease)) => lease,
+            Ok(None) => {
+                return Err(Status::cancelled(
+                    PlacementCenterError::KvLeaseDoesNotExist(req.lease_id).to_string(),
+                ))
+            }
+            Err(e) => return Err(Status::cancelled(e.to_string())),
+        };
+        self.kv_lease_manager.keep_alive(&lease);
+        Ok(Response::new(LeaseKeepAliveReply {
+            lease_id: lease.id,
+            ttl: lease.ttl,
+        }))
+    }
+
+    async fn lease_revoke(
+        &self,
+        request: Request<LeaseRevokeRequest>,
+    ) -> Result<Response<LeaseRevokeReply>, Status> {
+        let req = request.into_inner();
+
+        if req.lease_id == 0 {
+            return Err(Status::cancelled(
+                CommonError::ParameterCannotBeNull("lease_id".to_string()).to_string(),
+            ));
+        }
+
+        let data = StorageData::new(
+            StorageDataType::KvLeaseRevoke,
+            LeaseRevokeRequest::encode_to_vec(&req),
+        );
+        match self.raft_machine_apply.client_write(data).await {
+            Ok(_) => {
+                self.kv_lease_manager.remove(req.lease_id);
+                Ok(Response::new(LeaseRevokeReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn txn(&self, request: Request<TxnRequest>) -> Result<Response<TxnReply>, Status> {
+        let req = request.into_inner();
+
+        if req.compares.iter().any(|compare| compare.key.is_empty()) {
+            return Err(Status::cancelled(
+                CommonError::ParameterCannotBeNull("compare key".to_string()).to_string(),
+            ));
+        }
+        for compare in req.compares.iter() {
+            check_request_key(&compare.key)?;
+        }
+
+        for op in req.success.iter().chain(req.failure.iter()) {
+            match &op.op {
+                Some(Op::Put(put)) => {
+                    if put.key.is_empty() || put.value.is_empty() {
+                        return Err(Status::cancelled(
+                            CommonError::ParameterCannotBeNull("key or value".to_string())
+                                .to_string(),
+                        ));
+                    }
+                    check_request_key(&put.key)?;
+                    self.check_lease(put.lease_id)?;
+                }
+                Some(Op::Delete(delete)) => {
+                    if delete.key.is_empty() {
+                        return Err(Status::cancelled(
+                            CommonError::ParameterCannotBeNull("key".to_string()).to_string(),
+                        ));
+                    }
+                    check_request_key(&delete.key)?;
+                }
+                None => {}
+            }
+        }
+
+        let data = StorageData::new(StorageDataType::KvTxn, TxnRequest::encode_to_vec(&req));
+        match self.raft_machine_apply.client_write(data).await {
+            Ok(resp) => decode_write_reply::<TxnReply>(resp)
+                .map(Response::new)
+                .map_err(|e| Status::cancelled(e.to_string())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
 }
@@ -19,10 +19,14 @@
     create_acl_by_req, create_blacklist_by_req, delete_acl_by_req, delete_blacklist_by_req,
     list_acl_by_req, list_blacklist_by_req,
 };
+use crate::mqtt::services::alarm::{delete_alarm_by_req, list_alarm_by_req, set_alarm_by_req};
 use crate::mqtt::services::connector::{
     connector_heartbeat_by_req, create_connector_by_req, delete_connector_by_req,
     list_connectors_by_req, update_connector_by_req,
 };
+use crate::mqtt::services::rule::{
+    create_rule_by_req, delete_rule_by_req, list_rule_by_req, update_rule_by_req,
+};
 use crate::mqtt::services::session::{
     create_session_by_req, delete_session_by_req, list_session_by_req, update_session_by_req,
 };
@@ -31,6 +35,9 @@
     delete_auto_subscribe_rule_by_req, delete_subscribe_by_req, list_auto_subscribe_rule_by_req,
     list_subscribe_by_req, set_auto_subscribe_rule_by_req, set_subscribe_by_req,
 };
+use crate::mqtt::services::tenant::{
+    create_tenant_by_req, delete_tenant_by_req, list_tenant_by_req, update_tenant_by_req,
+};
 use crate::mqtt::services::topic::{
     create_topic_by_req, create_topic_rewrite_rule_by_req, delete_topic_by_req,
     delete_topic_rewrite_rule_by_req, list_topic_by_req, list_topic_rewrite_rule_by_req,
@@ -44,22 +51,26 @@
 use protocol::placement_center::placement_center_mqtt::{
     ConnectorHeartbeatReply, ConnectorHeartbeatRequest, CreateAclReply, CreateAclRequest,
     CreateBlacklistReply, CreateBlacklistRequest, CreateConnectorReply, CreateConnectorRequest,
-    CreateSessionReply, CreateSessionRequest, CreateTopicReply, CreateTopicRequest,
+    CreateRuleReply, CreateRuleRequest, CreateSessionReply, CreateSessionRequest,
+    CreateTenantReply, CreateTenantRequest, CreateTopicReply, CreateTopicRequest,
     CreateTopicRewriteRuleReply, CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest,
-    DeleteAclReply, DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
-    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteConnectorReply, DeleteConnectorRequest,
-    DeleteSessionReply, DeleteSessionRequest, DeleteSubscribeReply, DeleteSubscribeRequest,
-    DeleteTopicReply, DeleteTopicRequest, DeleteTopicRewriteRuleReply,
-    DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply,
-    GetShareSubLeaderRequest, ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply,
+    DeleteAclReply, DeleteAclRequest, DeleteAlarmReply, DeleteAlarmRequest,
+    DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest, DeleteBlacklistReply,
+    DeleteBlacklistRequest, DeleteConnectorReply, DeleteConnectorRequest, DeleteRuleReply,
+    DeleteRuleRequest, DeleteSessionReply, DeleteSessionRequest, DeleteSubscribeReply,
+    DeleteSubscribeRequest, DeleteTenantReply, DeleteTenantRequest, DeleteTopicReply,
+    DeleteTopicRequest, DeleteTopicRewriteRuleReply, DeleteTopicRewriteRuleRequest,
+    DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply, GetShareSubLeaderRequest,
+    ListAclReply, ListAclRequest, ListAlarmReply, ListAlarmRequest, ListAutoSubscribeRuleReply,
     ListAutoSubscribeRuleRequest, ListBlacklistReply, ListBlacklistRequest, ListConnectorReply,
-    ListConnectorRequest, ListSessionReply, ListSessionRequest, ListSubscribeReply,
-    ListSubscribeRequest, ListTopicReply, ListTopicRequest, ListTopicRewriteRuleReply,
-    ListTopicRewriteRuleRequest, ListUserReply, ListUserRequest, SaveLastWillMessageReply,
-    SaveLastWillMessageRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
-    SetSubscribeReply, SetSubscribeRequest, SetTopicRetainMessageReply,
-    SetTopicRetainMessageRequest, UpdateConnectorReply, UpdateConnectorRequest, UpdateSessionReply,
-    UpdateSessionRequest,
+    ListConnectorRequest, ListRuleReply, ListRuleRequest, ListSessionReply, ListSessionRequest,
+    ListSubscribeReply, ListSubscribeRequest, ListTenantReply, ListTenantRequest, ListTopicReply,
+    ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply,
+    ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest, SetAlarmReply,
+    SetAlarmRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetSubscribeReply,
+    SetSubscribeRequest, SetTopicRetainMessageReply, SetTopicRetainMessageRequest,
+    UpdateConnectorReply, UpdateConnectorRequest, UpdateRuleReply, UpdateRuleRequest,
+    UpdateSessionReply, UpdateSessionRequest, UpdateTenantReply, UpdateTenantRequest,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
@@ -348,7 +359,7 @@ async fn list_connectors(
         &self,
         request: Request<ListConnectorRequest>,
     ) -> Result<Response<ListConnectorReply>, Status> {
-        list_connectors_by_req(&self.rocksdb_engine_handler, request)
+        list_connectors_by_req(&self.rocksdb_engine_handler, &self.mqtt_cache, request)
     }
 
     async fn create_connector(
@@ -400,6 +411,106 @@ async fn connector_heartbeat(
         connector_heartbeat_by_req(&self.mqtt_cache, request)
     }
 
+    // Rule
+    async fn list_rule(
+        &self,
+        request: Request<ListRuleRequest>,
+    ) -> Result<Response<ListRuleReply>, Status> {
+        list_rule_by_req(&self.rocksdb_engine_handler, request)
+    }
+
+    async fn create_rule(
+        &self,
+        request: Request<CreateRuleRequest>,
+    ) -> Result<Response<CreateRuleReply>, Status> {
+        create_rule_by_req(
+            &self.rocksdb_engine_handler,
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            request,
+        )
+        .await
+    }
+
+    async fn update_rule(
+        &self,
+        request: Request<UpdateRuleRequest>,
+    ) -> Result<Response<UpdateRuleReply>, Status> {
+        update_rule_by_req(
+            &self.rocksdb_engine_handler,
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            request,
+        )
+        .await
+    }
+
+    async fn delete_rule(
+        &self,
+        request: Request<DeleteRuleRequest>,
+    ) -> Result<Response<DeleteRuleReply>, Status> {
+        delete_rule_by_req(
+            &self.rocksdb_engine_handler,
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            request,
+        )
+        .await
+    }
+
+    // Tenant
+    async fn list_tenant(
+        &self,
+        request: Request<ListTenantRequest>,
+    ) -> Result<Response<ListTenantReply>, Status> {
+        list_tenant_by_req(&self.rocksdb_engine_handler, request)
+    }
+
+    async fn create_tenant(
+        &self,
+        request: Request<CreateTenantRequest>,
+    ) -> Result<Response<CreateTenantReply>, Status> {
+        create_tenant_by_req(
+            &self.rocksdb_engine_handler,
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            request,
+        )
+        .await
+    }
+
+    async fn update_tenant(
+        &self,
+        request: Request<UpdateTenantRequest>,
+    ) -> Result<Response<UpdateTenantReply>, Status> {
+        update_tenant_by_req(
+            &self.rocksdb_engine_handler,
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            request,
+        )
+        .await
+    }
+
+    async fn delete_tenant(
+        &self,
+        request: Request<DeleteTenantRequest>,
+    ) -> Result<Response<DeleteTenantReply>, Status> {
+        delete_tenant_by_req(
+            &self.rocksdb_engine_handler,
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            request,
+        )
+        .await
+    }
+
     // AutoSubscribeRule
     async fn set_auto_subscribe_rule(
         &self,
@@ -421,4 +532,25 @@ async fn list_auto_subscribe_rule(
     ) -> Result<Response<ListAutoSubscribeRuleReply>, Status> {
         list_auto_subscribe_rule_by_req(&self.rocksdb_engine_handler, request)
     }
+
+    async fn set_alarm(
+        &self,
+        request: Request<SetAlarmRequest>,
+    ) -> Result<Response<SetAlarmReply>, Status> {
+        set_alarm_by_req(&self.raft_machine_apply, request).await
+    }
+
+    async fn list_alarm(
+        &self,
+        request: Request<ListAlarmRequest>,
+    ) -> Result<Response<ListAlarmReply>, Status> {
+        list_alarm_by_req(&self.rocksdb_engine_handler, request)
+    }
+
+    async fn delete_alarm(
+        &self,
+        request: Request<DeleteAlarmRequest>,
+    ) -> Result<Response<DeleteAlarmReply>, Status> {
+        delete_alarm_by_req(&self.raft_machine_apply, request).await
+    }
 }
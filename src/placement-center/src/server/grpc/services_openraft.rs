@@ -12,28 +12,45 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use bincode::{deserialize, serialize};
-use openraft::Raft;
+use openraft::{LogId, Raft};
 use protocol::placement_center::placement_center_openraft::open_raft_service_server::OpenRaftService;
 use protocol::placement_center::placement_center_openraft::{
     AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, SnapshotStatusReply,
+    SnapshotStatusRequest, TriggerSnapshotReply, TriggerSnapshotRequest, VoteReply, VoteRequest,
 };
+use serde::Serialize;
 use tonic::{Request, Response, Status};
 
-use crate::raft::raft_node::Node;
+use crate::raft::raft_node::{Node, NodeId};
+use crate::raft::store::snapshot::{SnapshotBuildStatus, SnapshotProgress};
 use crate::raft::typeconfig::TypeConfig;
 
 pub struct GrpcOpenRaftServices {
     raft_node: Raft<TypeConfig>,
+    snapshot_progress: Arc<SnapshotProgress>,
 }
 
 impl GrpcOpenRaftServices {
-    pub fn new(raft_node: Raft<TypeConfig>) -> Self {
-        GrpcOpenRaftServices { raft_node }
+    pub fn new(raft_node: Raft<TypeConfig>, snapshot_progress: Arc<SnapshotProgress>) -> Self {
+        GrpcOpenRaftServices {
+            raft_node,
+            snapshot_progress,
+        }
     }
 }
 
+#[derive(Serialize)]
+struct SnapshotStatus {
+    build: SnapshotBuildStatus,
+    last_applied: Option<LogId<NodeId>>,
+    snapshot: Option<LogId<NodeId>>,
+    purged: Option<LogId<NodeId>>,
+}
+
 #[tonic::async_trait]
 impl OpenRaftService for GrpcOpenRaftServices {
     async fn vote(&self, request: Request<VoteRequest>) -> Result<Response<VoteReply>, Status> {
@@ -136,4 +153,45 @@ async fn change_membership(
         let reply = ChangeMembershipReply { value };
         return Ok(Response::new(reply));
     }
+
+    async fn trigger_snapshot(
+        &self,
+        request: Request<TriggerSnapshotRequest>,
+    ) -> Result<Response<TriggerSnapshotReply>, Status> {
+        let req = request.into_inner();
+
+        if let Err(e) = self.raft_node.trigger().snapshot().await {
+            return Err(Status::cancelled(e.to_string()));
+        }
+
+        // Only logs already covered by a snapshot can be purged; the snapshot triggered
+        // above is built in the background, so this compacts up to the previous one.
+        if req.purge_log {
+            let snapshot = self.raft_node.metrics().borrow().snapshot;
+            if let Some(log_id) = snapshot {
+                if let Err(e) = self.raft_node.trigger().purge_log(log_id.index).await {
+                    return Err(Status::cancelled(e.to_string()));
+                }
+            }
+        }
+
+        return Ok(Response::new(TriggerSnapshotReply::default()));
+    }
+
+    async fn snapshot_status(
+        &self,
+        _: Request<SnapshotStatusRequest>,
+    ) -> Result<Response<SnapshotStatusReply>, Status> {
+        let metrics = self.raft_node.metrics().borrow().clone();
+        let status = SnapshotStatus {
+            build: self.snapshot_progress.status(),
+            last_applied: metrics.last_applied,
+            snapshot: metrics.snapshot,
+            purged: metrics.purged,
+        };
+
+        let content =
+            serde_json::to_string(&status).map_err(|e| Status::cancelled(e.to_string()))?;
+        return Ok(Response::new(SnapshotStatusReply { content }));
+    }
 }
@@ -56,6 +56,42 @@ pub fn key_offset_by_group(cluster_name: &str, group: &str) -> String {
     format!("/offset/{}/{}", cluster_name, group)
 }
 
+/** ===========KV========== */
+// Bookkeeping of the KV service. Keys of KV users can not be written under it.
+pub const KV_RESERVED_PREFIX: &str = "/kv/";
+
+pub fn is_reserved_kv_key(key: &str) -> bool {
+    key.starts_with(KV_RESERVED_PREFIX)
+}
+
+pub fn key_kv_revision() -> String {
+    format!("{}revision", KV_RESERVED_PREFIX)
+}
+
+pub fn key_kv_meta(key: &str) -> String {
+    format!("{}meta/{}", KV_RESERVED_PREFIX, key)
+}
+
+pub fn key_kv_lease(lease_id: u64) -> String {
+    format!("{}lease/{}", KV_RESERVED_PREFIX, lease_id)
+}
+
+pub fn key_kv_lease_prefix() -> String {
+    format!("{}lease/", KV_RESERVED_PREFIX)
+}
+
+pub fn key_kv_lease_id() -> String {
+    format!("{}lease_id", KV_RESERVED_PREFIX)
+}
+
+pub fn key_kv_lease_key(lease_id: u64, key: &str) -> String {
+    format!("{}{}", key_kv_lease_key_prefix(lease_id), key)
+}
+
+pub fn key_kv_lease_key_prefix(lease_id: u64) -> String {
+    format!("{}lease_keys/{}/", KV_RESERVED_PREFIX, lease_id)
+}
+
 /** ===========Journal========== */
 pub fn key_shard(cluster_name: &str, namespace: &str, shard_name: &str) -> String {
     format!(
@@ -198,6 +234,30 @@ pub fn storage_key_mqtt_connector_prefix(cluster_name: &str) -> String {
     format!("/mqtt/connector/{}", cluster_name)
 }
 
+pub fn storage_key_mqtt_rule(cluster_name: &str, rule_name: &str) -> String {
+    format!("/mqtt/rule/{}/{}", cluster_name, rule_name)
+}
+
+pub fn storage_key_mqtt_rule_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/rule/{}/", cluster_name)
+}
+
+pub fn storage_key_mqtt_tenant(cluster_name: &str, tenant_name: &str) -> String {
+    format!("/mqtt/tenant/{}/{}", cluster_name, tenant_name)
+}
+
+pub fn storage_key_mqtt_tenant_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/tenant/{}/", cluster_name)
+}
+
+pub fn storage_key_mqtt_alarm(cluster_name: &str, alarm_id: &str) -> String {
+    format!("/mqtt/alarm/{}/{}", cluster_name, alarm_id)
+}
+
+pub fn storage_key_mqtt_alarm_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/alarm/{}/", cluster_name)
+}
+
 pub fn storage_key_mqtt_schema(cluster_name: &str, schema_name: &str) -> String {
     format!("/mqtt/schema/{}/{}", cluster_name, schema_name)
 }
@@ -206,6 +266,21 @@ pub fn storage_key_mqtt_schema_prefix(cluster_name: &str) -> String {
     format!("/mqtt/schema/{}", cluster_name)
 }
 
+pub fn storage_key_mqtt_schema_version(
+    cluster_name: &str,
+    schema_name: &str,
+    version: u64,
+) -> String {
+    format!(
+        "/mqtt/schema_version/{}/{}/{:020}",
+        cluster_name, schema_name, version
+    )
+}
+
+pub fn storage_key_mqtt_schema_version_prefix(cluster_name: &str, schema_name: &str) -> String {
+    format!("/mqtt/schema_version/{}/{}/", cluster_name, schema_name)
+}
+
 pub fn storage_key_mqtt_schema_bind(
     cluster_name: &str,
     resource_name: &str,
@@ -280,3 +355,74 @@ pub fn storage_key_mqtt_auto_subscribe_rule(cluster_name: &str, topic: &str) ->
 pub fn storage_key_mqtt_auto_subscribe_rule_prefix(cluster_name: &str) -> String {
     format!("/mqtt/auto_subscribe_rule/{}/", cluster_name)
 }
+
+/** ===========Backup========== */
+// Records of a restore that is still being imported. They are moved to their own keys by
+// the last batch of the import.
+pub fn key_backup_staging_prefix() -> String {
+    "/backup_staging/".to_string()
+}
+
+pub fn key_backup_staging(import_id: &str) -> String {
+    format!("{}{}/", key_backup_staging_prefix(), import_id)
+}
+
+/// Returns the broker cluster a metadata key belongs to, or None for keys that are not
+/// scoped to a single cluster such as the placement KV data.
+pub fn cluster_name_of_key(key: &str) -> Option<&str> {
+    let parts: Vec<&str> = key.split('/').collect();
+    let index = match parts.get(1).copied() {
+        Some("clusters") if parts.get(2) == Some(&"node") => 3,
+        Some("clusters") | Some("config") | Some("idempotent") | Some("offset") => 2,
+        Some("journal") | Some("mqtt") => 3,
+        _ => return None,
+    };
+    parts.get(index).copied().filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_kv_key_test() {
+        for key in [
+            key_kv_revision(),
+            key_kv_meta("a"),
+            key_kv_lease(1),
+            key_kv_lease_id(),
+            key_kv_lease_key(1, "a"),
+        ] {
+            assert!(is_reserved_kv_key(&key), "{}", key);
+        }
+        assert!(!is_reserved_kv_key("kv/revision"));
+        assert!(!is_reserved_kv_key("/kvx/a"));
+        assert!(!key_kv_lease_id().starts_with(&key_kv_lease_prefix()));
+        assert!(!key_kv_lease_key(1, "a").starts_with(&key_kv_lease_prefix()));
+    }
+
+    #[test]
+    fn cluster_name_of_key_test() {
+        assert_eq!(
+            cluster_name_of_key(&key_cluster("MqttBrokerServer", "c1")),
+            Some("c1")
+        );
+        assert_eq!(cluster_name_of_key(&key_node("c1", 1)), Some("c1"));
+        assert_eq!(
+            cluster_name_of_key(&key_resource_config("c1".to_string(), "k".to_string())),
+            Some("c1")
+        );
+        assert_eq!(
+            cluster_name_of_key(&key_offset("c1", "g", "n", "s")),
+            Some("c1")
+        );
+        assert_eq!(cluster_name_of_key(&key_shard("c1", "n", "s")), Some("c1"));
+        assert_eq!(
+            cluster_name_of_key(&storage_key_mqtt_user("c1", "u")),
+            Some("c1")
+        );
+        assert_eq!(cluster_name_of_key(&key_kv_lease(1)), None);
+        assert_eq!(cluster_name_of_key(&key_kv_lease_key(1, "k")), None);
+        assert_eq!(cluster_name_of_key("user-key"), None);
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use metadata_struct::mqtt::alarm::MqttAlarm;
+
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{storage_key_mqtt_alarm, storage_key_mqtt_alarm_prefix};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttAlarmStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttAlarmStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttAlarmStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        alarm_id: &str,
+        alarm: &MqttAlarm,
+    ) -> Result<(), CommonError> {
+        let key = storage_key_mqtt_alarm(cluster_name, alarm_id);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, alarm)
+    }
+
+    pub fn list(&self, cluster_name: &str) -> Result<Vec<MqttAlarm>, CommonError> {
+        let prefix_key = storage_key_mqtt_alarm_prefix(cluster_name);
+        let mut results = Vec::new();
+        for raw in engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)? {
+            results.push(serde_json::from_str::<MqttAlarm>(&raw.data)?);
+        }
+        Ok(results)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        alarm_id: &str,
+    ) -> Result<Option<MqttAlarm>, CommonError> {
+        let key = storage_key_mqtt_alarm(cluster_name, alarm_id);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_str::<MqttAlarm>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete(&self, cluster_name: &str, alarm_id: &str) -> Result<(), CommonError> {
+        let key = storage_key_mqtt_alarm(cluster_name, alarm_id);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use common_base::utils::file_utils::test_temp_dir;
+    use metadata_struct::mqtt::alarm::MqttAlarm;
+
+    use crate::storage::mqtt::alarm::MqttAlarmStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn alarm_storage_test() {
+        let config = placement_center_test_conf();
+        let rs = Arc::new(RocksDBEngine::new(
+            &test_temp_dir(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let alarm_storage = MqttAlarmStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for alarm_id in ["a1", "a2"] {
+            let alarm = MqttAlarm {
+                cluster_name: cluster_name.clone(),
+                alarm_id: alarm_id.to_string(),
+                name: "connector_down".to_string(),
+                activate_at: 1,
+                ..Default::default()
+            };
+            alarm_storage.save(&cluster_name, alarm_id, &alarm).unwrap();
+        }
+        assert_eq!(alarm_storage.list(&cluster_name).unwrap().len(), 2);
+
+        let mut alarm = alarm_storage.get(&cluster_name, "a1").unwrap().unwrap();
+        assert!(alarm.is_active());
+        alarm.deactivate_at = 2;
+        alarm_storage.save(&cluster_name, "a1", &alarm).unwrap();
+        let alarm = alarm_storage.get(&cluster_name, "a1").unwrap().unwrap();
+        assert!(!alarm.is_active());
+
+        alarm_storage.delete(&cluster_name, "a1").unwrap();
+        assert!(alarm_storage.get(&cluster_name, "a1").unwrap().is_none());
+        assert_eq!(alarm_storage.list(&cluster_name).unwrap().len(), 1);
+    }
+}
@@ -25,10 +25,13 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod alarm;
 pub mod blacklist;
 pub mod connector;
 pub mod lastwill;
+pub mod rule;
 pub mod session;
 pub mod subscribe;
+pub mod tenant;
 pub mod topic;
 pub mod user;
@@ -0,0 +1,123 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use metadata_struct::mqtt::rule::MqttRule;
+
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{storage_key_mqtt_rule, storage_key_mqtt_rule_prefix};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttRuleStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttRuleStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttRuleStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        rule_name: &str,
+        rule: &MqttRule,
+    ) -> Result<(), CommonError> {
+        let key = storage_key_mqtt_rule(cluster_name, rule_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, rule)
+    }
+
+    pub fn list(&self, cluster_name: &str) -> Result<Vec<MqttRule>, CommonError> {
+        let prefix_key = storage_key_mqtt_rule_prefix(cluster_name);
+        let mut results = Vec::new();
+        for raw in engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)? {
+            results.push(serde_json::from_str::<MqttRule>(&raw.data)?);
+        }
+        Ok(results)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        rule_name: &str,
+    ) -> Result<Option<MqttRule>, CommonError> {
+        let key = storage_key_mqtt_rule(cluster_name, rule_name);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_str::<MqttRule>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete(&self, cluster_name: &str, rule_name: &str) -> Result<(), CommonError> {
+        let key = storage_key_mqtt_rule(cluster_name, rule_name);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use common_base::utils::file_utils::test_temp_dir;
+    use metadata_struct::mqtt::rule::{MqttRule, MqttRuleAction};
+
+    use crate::storage::mqtt::rule::MqttRuleStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn rule_storage_test() {
+        let config = placement_center_test_conf();
+        let rs = Arc::new(RocksDBEngine::new(
+            &test_temp_dir(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let rule_storage = MqttRuleStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for rule_name in ["r1", "r2"] {
+            let rule = MqttRule {
+                cluster_name: cluster_name.clone(),
+                rule_name: rule_name.to_string(),
+                sql: "SELECT * FROM \"sensors/#\"".to_string(),
+                actions: vec![MqttRuleAction::Republish {
+                    topic: "alarm".to_string(),
+                    qos: 1,
+                }],
+                enable: true,
+                ..Default::default()
+            };
+            rule_storage.save(&cluster_name, rule_name, &rule).unwrap();
+        }
+
+        let res = rule_storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 2);
+
+        let res = rule_storage.get(&cluster_name, "r1").unwrap().unwrap();
+        assert_eq!(res.rule_name, "r1");
+        assert_eq!(res.actions.len(), 1);
+
+        rule_storage.delete(&cluster_name, "r1").unwrap();
+        assert!(rule_storage.get(&cluster_name, "r1").unwrap().is_none());
+        assert_eq!(rule_storage.list(&cluster_name).unwrap().len(), 1);
+    }
+}
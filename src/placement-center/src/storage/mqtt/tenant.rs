@@ -0,0 +1,123 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use metadata_struct::mqtt::tenant::MqttTenant;
+
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{storage_key_mqtt_tenant, storage_key_mqtt_tenant_prefix};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttTenantStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttTenantStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttTenantStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        tenant_name: &str,
+        tenant: &MqttTenant,
+    ) -> Result<(), CommonError> {
+        let key = storage_key_mqtt_tenant(cluster_name, tenant_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, tenant)
+    }
+
+    pub fn list(&self, cluster_name: &str) -> Result<Vec<MqttTenant>, CommonError> {
+        let prefix_key = storage_key_mqtt_tenant_prefix(cluster_name);
+        let mut results = Vec::new();
+        for raw in engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)? {
+            results.push(serde_json::from_str::<MqttTenant>(&raw.data)?);
+        }
+        Ok(results)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        tenant_name: &str,
+    ) -> Result<Option<MqttTenant>, CommonError> {
+        let key = storage_key_mqtt_tenant(cluster_name, tenant_name);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_str::<MqttTenant>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete(&self, cluster_name: &str, tenant_name: &str) -> Result<(), CommonError> {
+        let key = storage_key_mqtt_tenant(cluster_name, tenant_name);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use common_base::utils::file_utils::test_temp_dir;
+    use metadata_struct::mqtt::tenant::{MqttTenant, MqttTenantQuota};
+
+    use crate::storage::mqtt::tenant::MqttTenantStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn tenant_storage_test() {
+        let config = placement_center_test_conf();
+        let rs = Arc::new(RocksDBEngine::new(
+            &test_temp_dir(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let tenant_storage = MqttTenantStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for tenant_name in ["t1", "t2"] {
+            let tenant = MqttTenant {
+                cluster_name: cluster_name.clone(),
+                tenant_name: tenant_name.to_string(),
+                quota: MqttTenantQuota {
+                    max_connections: 100,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            tenant_storage
+                .save(&cluster_name, tenant_name, &tenant)
+                .unwrap();
+        }
+
+        let res = tenant_storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 2);
+
+        let res = tenant_storage.get(&cluster_name, "t1").unwrap().unwrap();
+        assert_eq!(res.tenant_name, "t1");
+        assert_eq!(res.quota.max_connections, 100);
+
+        tenant_storage.delete(&cluster_name, "t1").unwrap();
+        assert!(tenant_storage.get(&cluster_name, "t1").unwrap().is_none());
+        assert_eq!(tenant_storage.list(&cluster_name).unwrap().len(), 1);
+    }
+}
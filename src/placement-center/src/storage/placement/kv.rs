@@ -15,13 +15,34 @@
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use serde::{Deserialize, Serialize};
 
 use crate::storage::engine::{
     engine_delete_by_cluster, engine_exists_by_cluster, engine_get_by_cluster,
     engine_prefix_list_by_cluster, engine_save_by_cluster,
 };
+use crate::storage::keys::{
+    key_kv_lease, key_kv_lease_id, key_kv_lease_key, key_kv_lease_key_prefix, key_kv_lease_prefix,
+    key_kv_meta, key_kv_revision,
+};
 use crate::storage::rocksdb::RocksDBEngine;
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvMeta {
+    pub key: String,
+    pub create_revision: u64,
+    pub mod_revision: u64,
+    pub version: u64,
+    pub lease_id: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvLease {
+    pub id: u64,
+    pub ttl: u64,
+    pub expire_at_ms: u128,
+}
+
 #[derive(Debug, Clone)]
 pub struct KvStorage {
     rocksdb_engine_handler: Arc<RocksDBEngine>,
@@ -65,6 +86,120 @@ pub fn get_prefix(&self, prefix: String) -> Result<Vec<String>, CommonError> {
             Err(e) => Err(e),
         }
     }
+
+    pub fn get_revision(&self) -> Result<u64, CommonError> {
+        if let Some(data) =
+            engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_revision())?
+        {
+            return Ok(serde_json::from_str::<u64>(&data.data)?);
+        }
+        Ok(0)
+    }
+
+    pub fn save_revision(&self, revision: u64) -> Result<(), CommonError> {
+        engine_save_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_revision(),
+            revision,
+        )
+    }
+
+    pub fn get_meta(&self, key: &str) -> Result<Option<KvMeta>, CommonError> {
+        if let Some(data) =
+            engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_meta(key))?
+        {
+            return Ok(Some(serde_json::from_str::<KvMeta>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn save_meta(&self, meta: &KvMeta) -> Result<(), CommonError> {
+        engine_save_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_meta(&meta.key),
+            meta,
+        )
+    }
+
+    pub fn delete_meta(&self, key: &str) -> Result<(), CommonError> {
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_meta(key))
+    }
+
+    // Keys attached to a lease are indexed by lease, so revoking a lease does not
+    // have to scan the metadata of every key
+    pub fn save_lease_key(&self, lease_id: u64, key: &str) -> Result<(), CommonError> {
+        engine_save_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_lease_key(lease_id, key),
+            key,
+        )
+    }
+
+    pub fn delete_lease_key(&self, lease_id: u64, key: &str) -> Result<(), CommonError> {
+        engine_delete_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_lease_key(lease_id, key),
+        )
+    }
+
+    pub fn list_meta_by_lease(&self, lease_id: u64) -> Result<Vec<KvMeta>, CommonError> {
+        let data = engine_prefix_list_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_lease_key_prefix(lease_id),
+        )?;
+        let mut result = Vec::new();
+        for item in data {
+            let key = serde_json::from_str::<String>(&item.data)?;
+            if let Some(meta) = self.get_meta(&key)? {
+                result.push(meta);
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn next_lease_id(&self) -> Result<u64, CommonError> {
+        let current =
+            match engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_lease_id())? {
+                Some(data) => serde_json::from_str::<u64>(&data.data)?,
+                None => 0,
+            };
+        let next = current + 1;
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_lease_id(), next)?;
+        Ok(next)
+    }
+
+    pub fn get_lease(&self, lease_id: u64) -> Result<Option<KvLease>, CommonError> {
+        if let Some(data) =
+            engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_lease(lease_id))?
+        {
+            return Ok(Some(serde_json::from_str::<KvLease>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn save_lease(&self, lease: &KvLease) -> Result<(), CommonError> {
+        engine_save_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_lease(lease.id),
+            lease,
+        )
+    }
+
+    pub fn delete_lease(&self, lease_id: u64) -> Result<(), CommonError> {
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key_kv_lease(lease_id))
+    }
+
+    pub fn list_lease(&self) -> Result<Vec<KvLease>, CommonError> {
+        let data = engine_prefix_list_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_kv_lease_prefix(),
+        )?;
+        let mut result = Vec::new();
+        for item in data {
+            result.push(serde_json::from_str::<KvLease>(&item.data)?);
+        }
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -146,6 +281,54 @@ fn test_get_prefix() {
         assert_eq!(result, vec!["value1".to_string(), "value2".to_string()]);
     }
 
+    #[test]
+    fn test_lease_and_meta() {
+        let kv = setup_kv_storage();
+        assert_eq!(kv.next_lease_id().unwrap(), 1);
+        assert_eq!(kv.next_lease_id().unwrap(), 2);
+
+        let lease = KvLease {
+            id: 2,
+            ttl: 10,
+            expire_at_ms: 1000,
+        };
+        kv.save_lease(&lease).unwrap();
+        assert_eq!(kv.get_lease(2).unwrap(), Some(lease));
+        assert_eq!(kv.list_lease().unwrap().len(), 1);
+
+        for (key, lease_id) in [("a", 2), ("b", 0), ("c", 2), ("d", 12)] {
+            kv.save_meta(&KvMeta {
+                key: key.to_string(),
+                lease_id,
+                ..Default::default()
+            })
+            .unwrap();
+            if lease_id > 0 {
+                kv.save_lease_key(lease_id, key).unwrap();
+            }
+        }
+        let mut keys: Vec<String> = kv
+            .list_meta_by_lease(2)
+            .unwrap()
+            .into_iter()
+            .map(|meta| meta.key)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, vec!["a".to_string(), "c".to_string()]);
+
+        kv.delete_lease_key(2, "a").unwrap();
+        let keys: Vec<String> = kv
+            .list_meta_by_lease(2)
+            .unwrap()
+            .into_iter()
+            .map(|meta| meta.key)
+            .collect();
+        assert_eq!(keys, vec!["c".to_string()]);
+
+        kv.delete_lease(2).unwrap();
+        assert!(kv.get_lease(2).unwrap().is_none());
+    }
+
     #[test]
     fn test_get_prefix_non_existent() {
         let kv = setup_kv_storage();
@@ -21,6 +21,7 @@
     storage_key_mqtt_schema, storage_key_mqtt_schema_bind,
     storage_key_mqtt_schema_bind_prefix_by_cluster,
     storage_key_mqtt_schema_bind_prefix_by_resource, storage_key_mqtt_schema_prefix,
+    storage_key_mqtt_schema_version, storage_key_mqtt_schema_version_prefix,
 };
 use crate::storage::rocksdb::RocksDBEngine;
 use metadata_struct::schema::{SchemaData, SchemaResourceBind};
@@ -45,9 +46,43 @@ pub fn save(
     ) -> Result<(), PlacementCenterError> {
         let key = storage_key_mqtt_schema(cluster_name, schema_name);
         engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, schema)?;
+
+        let version_key =
+            storage_key_mqtt_schema_version(cluster_name, schema_name, schema.version);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), version_key, schema)?;
         Ok(())
     }
 
+    pub fn list_version(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+    ) -> Result<Vec<SchemaData>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_schema_version_prefix(cluster_name, schema_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let schema = serde_json::from_str::<SchemaData>(&raw.data)?;
+            results.push(schema);
+        }
+        results.sort_by_key(|schema| schema.version);
+        Ok(results)
+    }
+
+    pub fn get_version(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+        version: u64,
+    ) -> Result<Option<SchemaData>, PlacementCenterError> {
+        let key = storage_key_mqtt_schema_version(cluster_name, schema_name, version);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            let schema = serde_json::from_str::<SchemaData>(&data.data)?;
+            return Ok(Some(schema));
+        }
+        Ok(None)
+    }
+
     pub fn list(&self, cluster_name: &str) -> Result<Vec<SchemaData>, PlacementCenterError> {
         let prefix_key = storage_key_mqtt_schema_prefix(cluster_name);
         let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
@@ -80,6 +115,12 @@ pub fn delete(
     ) -> Result<(), PlacementCenterError> {
         let key: String = storage_key_mqtt_schema(cluster_name, schema_name);
         engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+
+        for schema in self.list_version(cluster_name, schema_name)? {
+            let version_key =
+                storage_key_mqtt_schema_version(cluster_name, schema_name, schema.version);
+            engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), version_key)?;
+        }
         Ok(())
     }
 
@@ -156,9 +197,72 @@ pub fn delete_bind(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use common_base::utils::file_utils::test_temp_dir;
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+
+    use crate::storage::placement::schema::SchemaStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
 
     #[tokio::test]
-    async fn schema_storage_test() {}
+    async fn schema_storage_test() {
+        let config = placement_center_test_conf();
+        let rs = Arc::new(RocksDBEngine::new(
+            &test_temp_dir(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let schema_storage = SchemaStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+        let schema_name = "schema1".to_string();
+
+        for version in 1..=3 {
+            let schema = SchemaData {
+                cluster_name: cluster_name.clone(),
+                name: schema_name.clone(),
+                schema_type: SchemaType::JSON,
+                desc: format!("v{}", version),
+                schema: r#"{"type": "object"}"#.to_string(),
+                version,
+                compatibility: SchemaCompatibility::NONE,
+            };
+            schema_storage
+                .save(&cluster_name, &schema_name, &schema)
+                .unwrap();
+        }
+
+        let latest = schema_storage
+            .get(&cluster_name, &schema_name)
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.version, 3);
+
+        let versions = schema_storage
+            .list_version(&cluster_name, &schema_name)
+            .unwrap();
+        assert_eq!(
+            versions.iter().map(|s| s.version).collect::<Vec<u64>>(),
+            vec![1, 2, 3]
+        );
+
+        let v2 = schema_storage
+            .get_version(&cluster_name, &schema_name, 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(v2.desc, "v2");
+
+        schema_storage.delete(&cluster_name, &schema_name).unwrap();
+        assert!(schema_storage
+            .get(&cluster_name, &schema_name)
+            .unwrap()
+            .is_none());
+        assert!(schema_storage
+            .list_version(&cluster_name, &schema_name)
+            .unwrap()
+            .is_empty());
+    }
 
     #[tokio::test]
     async fn schema_bind_storage_test() {}
@@ -28,9 +28,15 @@ pub fn storage_raft_fold(path: &str) -> String {
     format!("{}/_raft", path)
 }
 
+pub fn storage_snapshot_fold(path: &str) -> String {
+    format!("{}/_snapshot", path)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::storage::rocksdb::{column_family_list, storage_data_fold, storage_raft_fold};
+    use crate::storage::rocksdb::{
+        column_family_list, storage_data_fold, storage_raft_fold, storage_snapshot_fold,
+    };
 
     #[tokio::test]
     async fn column_family_list_test() {
@@ -52,4 +58,11 @@ async fn storage_raft_fold_test() {
         let fold = storage_raft_fold(path);
         assert_eq!(fold, "/tmp/test/_raft");
     }
+
+    #[tokio::test]
+    async fn storage_snapshot_fold_test() {
+        let path = "/tmp/test";
+        let fold = storage_snapshot_fold(path);
+        assert_eq!(fold, "/tmp/test/_snapshot");
+    }
 }
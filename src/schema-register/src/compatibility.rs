@@ -0,0 +1,359 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use apache_avro::schema_compatibility::SchemaCompatibility as AvroCompatibility;
+use apache_avro::Schema;
+use common_base::error::common::CommonError;
+use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+use serde_json::Value;
+
+/// Check whether `new_schema` can replace `old_schema` under the compatibility level of
+/// `new_schema`.
+///
+/// BACKWARD: consumers using the new schema can read data produced with the old schema.
+/// FORWARD: consumers using the old schema can read data produced with the new schema.
+/// FULL: both of the above.
+pub fn check_compatibility(
+    old_schema: &SchemaData,
+    new_schema: &SchemaData,
+) -> Result<(), CommonError> {
+    if new_schema.compatibility == SchemaCompatibility::NONE {
+        return Ok(());
+    }
+
+    if old_schema.schema_type != new_schema.schema_type {
+        return Err(CommonError::CommonError(format!(
+            "Schema {} can not change type from {} to {} with compatibility {}",
+            new_schema.name,
+            old_schema.schema_type,
+            new_schema.schema_type,
+            new_schema.compatibility
+        )));
+    }
+
+    let (backward, forward) = match new_schema.compatibility {
+        SchemaCompatibility::NONE => (false, false),
+        SchemaCompatibility::BACKWARD => (true, false),
+        SchemaCompatibility::FORWARD => (false, true),
+        SchemaCompatibility::FULL => (true, true),
+    };
+
+    let res = match new_schema.schema_type {
+        SchemaType::AVRO => {
+            avro_compatibility(&old_schema.schema, &new_schema.schema, backward, forward)
+        }
+        SchemaType::JSON => {
+            json_compatibility(&old_schema.schema, &new_schema.schema, backward, forward)
+        }
+        SchemaType::PROTOBUF => Err(CommonError::CommonError(
+            "compatibility check is not supported for protobuf schemas".to_string(),
+        )),
+    };
+
+    res.map_err(|e| {
+        CommonError::CommonError(format!(
+            "Schema {} version {} is not {} compatible with version {}: {}",
+            new_schema.name, new_schema.version, new_schema.compatibility, old_schema.version, e
+        ))
+    })
+}
+
+fn avro_compatibility(
+    old_schema: &str,
+    new_schema: &str,
+    backward: bool,
+    forward: bool,
+) -> Result<(), CommonError> {
+    let old = Schema::parse_str(old_schema)?;
+    let new = Schema::parse_str(new_schema)?;
+
+    if backward {
+        AvroCompatibility::can_read(&old, &new)
+            .map_err(|e| CommonError::CommonError(e.to_string()))?;
+    }
+
+    if forward {
+        AvroCompatibility::can_read(&new, &old)
+            .map_err(|e| CommonError::CommonError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn json_compatibility(
+    old_schema: &str,
+    new_schema: &str,
+    backward: bool,
+    forward: bool,
+) -> Result<(), CommonError> {
+    let old: Value = serde_json::from_str(old_schema)?;
+    let new: Value = serde_json::from_str(new_schema)?;
+
+    if backward {
+        json_can_read(&old, &new, "$")?;
+    }
+
+    if forward {
+        json_can_read(&new, &old, "$")?;
+    }
+    Ok(())
+}
+
+// Checks that every document accepted by `writer` is structurally accepted by `reader`.
+fn json_can_read(writer: &Value, reader: &Value, path: &str) -> Result<(), CommonError> {
+    let writer_type = writer.get("type");
+    let reader_type = reader.get("type");
+    if let (Some(w), Some(r)) = (writer_type, reader_type) {
+        if !json_type_compatible(w, r) {
+            return Err(CommonError::CommonError(format!(
+                "type of {} changed from {} to {}",
+                path, w, r
+            )));
+        }
+    }
+
+    let writer_required = json_required(writer);
+    for field in json_required(reader) {
+        if !writer_required.contains(&field) {
+            return Err(CommonError::CommonError(format!(
+                "field {}.{} is required by the reader but optional for the writer",
+                path, field
+            )));
+        }
+    }
+
+    let empty = serde_json::Map::new();
+    let writer_props = writer
+        .get("properties")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty);
+    let reader_props = reader
+        .get("properties")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty);
+
+    let reader_closed = reader
+        .get("additionalProperties")
+        .and_then(|v| v.as_bool())
+        .is_some_and(|v| !v);
+
+    for (name, writer_prop) in writer_props.iter() {
+        let field_path = format!("{}.{}", path, name);
+        match reader_props.get(name) {
+            Some(reader_prop) => json_can_read(writer_prop, reader_prop, &field_path)?,
+            None => {
+                if reader_closed {
+                    return Err(CommonError::CommonError(format!(
+                        "field {} is not allowed by the reader",
+                        field_path
+                    )));
+                }
+            }
+        }
+    }
+
+    if let (Some(w), Some(r)) = (writer.get("items"), reader.get("items")) {
+        json_can_read(w, r, &format!("{}[]", path))?;
+    }
+    Ok(())
+}
+
+fn json_type_compatible(writer: &Value, reader: &Value) -> bool {
+    let writer_types = json_types(writer);
+    let reader_types = json_types(reader);
+    writer_types.iter().all(|w| {
+        reader_types
+            .iter()
+            .any(|r| r == w || (r == "number" && w == "integer"))
+    })
+}
+
+fn json_types(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(list) => list
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn json_required(value: &Value) -> Vec<String> {
+    value
+        .get("required")
+        .and_then(|v| v.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+
+    use super::check_compatibility;
+
+    fn build_schema(
+        schema_type: SchemaType,
+        version: u64,
+        compatibility: SchemaCompatibility,
+        schema: &str,
+    ) -> SchemaData {
+        SchemaData {
+            cluster_name: "test_cluster".to_string(),
+            name: "schema1".to_string(),
+            schema_type,
+            desc: "".to_string(),
+            schema: schema.to_string(),
+            version,
+            compatibility,
+        }
+    }
+
+    #[test]
+    pub fn avro_compatibility_test() {
+        let v1 = r#"
+        {
+            "type": "record",
+            "name": "test",
+            "fields": [
+                {"name": "a", "type": "long"}
+            ]
+        }
+        "#;
+
+        // add a field with default value
+        let v2 = r#"
+        {
+            "type": "record",
+            "name": "test",
+            "fields": [
+                {"name": "a", "type": "long"},
+                {"name": "b", "type": "string", "default": ""}
+            ]
+        }
+        "#;
+
+        // add a field without default value
+        let v3 = r#"
+        {
+            "type": "record",
+            "name": "test",
+            "fields": [
+                {"name": "a", "type": "long"},
+                {"name": "b", "type": "string"}
+            ]
+        }
+        "#;
+
+        let old = build_schema(SchemaType::AVRO, 1, SchemaCompatibility::NONE, v1);
+
+        let new = build_schema(SchemaType::AVRO, 2, SchemaCompatibility::FULL, v2);
+        assert!(check_compatibility(&old, &new).is_ok());
+
+        let new = build_schema(SchemaType::AVRO, 2, SchemaCompatibility::BACKWARD, v3);
+        assert!(check_compatibility(&old, &new).is_err());
+
+        let new = build_schema(SchemaType::AVRO, 2, SchemaCompatibility::FORWARD, v3);
+        assert!(check_compatibility(&old, &new).is_ok());
+
+        let new = build_schema(SchemaType::AVRO, 2, SchemaCompatibility::NONE, v3);
+        assert!(check_compatibility(&old, &new).is_ok());
+    }
+
+    #[test]
+    pub fn json_compatibility_test() {
+        let v1 = r#"{
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" }
+            },
+            "required": ["name"]
+        }"#;
+
+        // add an optional field
+        let v2 = r#"{
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" },
+                "city": { "type": "string" }
+            },
+            "required": ["name"]
+        }"#;
+
+        // make an existing field required
+        let v3 = r#"{
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" }
+            },
+            "required": ["name", "age"]
+        }"#;
+
+        // change the type of an existing field
+        let v4 = r#"{
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "string" }
+            },
+            "required": ["name"]
+        }"#;
+
+        let old = build_schema(SchemaType::JSON, 1, SchemaCompatibility::NONE, v1);
+
+        let new = build_schema(SchemaType::JSON, 2, SchemaCompatibility::FULL, v2);
+        assert!(check_compatibility(&old, &new).is_ok());
+
+        let new = build_schema(SchemaType::JSON, 2, SchemaCompatibility::BACKWARD, v3);
+        assert!(check_compatibility(&old, &new).is_err());
+
+        let new = build_schema(SchemaType::JSON, 2, SchemaCompatibility::FORWARD, v3);
+        assert!(check_compatibility(&old, &new).is_ok());
+
+        let new = build_schema(SchemaType::JSON, 2, SchemaCompatibility::BACKWARD, v4);
+        assert!(check_compatibility(&old, &new).is_err());
+    }
+
+    #[test]
+    pub fn schema_type_change_test() {
+        let old = build_schema(
+            SchemaType::JSON,
+            1,
+            SchemaCompatibility::NONE,
+            r#"{"type": "object"}"#,
+        );
+        let new = build_schema(
+            SchemaType::AVRO,
+            2,
+            SchemaCompatibility::BACKWARD,
+            r#"{"type": "record", "name": "test", "fields": []}"#,
+        );
+        assert!(check_compatibility(&old, &new).is_err());
+
+        let new = build_schema(
+            SchemaType::AVRO,
+            2,
+            SchemaCompatibility::NONE,
+            r#"{"type": "record", "name": "test", "fields": []}"#,
+        );
+        assert!(check_compatibility(&old, &new).is_ok());
+    }
+}
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 pub mod avro;
+pub mod compatibility;
 pub mod json;
 pub mod protobuf;
 pub mod schema;
+pub mod transcode;
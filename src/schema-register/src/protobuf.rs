@@ -14,7 +14,12 @@
 
 use common_base::error::common::CommonError;
 use metadata_struct::schema::SchemaData;
-use protofish::{decode::Value, prelude::Context};
+use protofish::{
+    context::{MessageInfo, ValueType},
+    decode::{MessageValue, PackedArray, Value},
+    prelude::Context,
+};
+use serde_json::{Map, Number, Value as JsonValue};
 
 pub fn protobuf_validate(
     schema_data: &SchemaData,
@@ -52,10 +57,410 @@ pub fn protobuf_validate(
     Ok(true)
 }
 
+pub fn protobuf_decode(
+    schema_data: &SchemaData,
+    data: &[u8],
+    message_name: &str,
+) -> Result<JsonValue, CommonError> {
+    let context = Context::parse([schema_data.schema.as_str()]).map_err(|err| {
+        CommonError::CommonError(format!(
+            "Failed to parse schema {}: {}",
+            schema_data.name.as_str(),
+            err
+        ))
+    })?;
+
+    let message = context.get_message(message_name).ok_or_else(|| {
+        CommonError::CommonError(format!(
+            "Message {} not found in schema {}",
+            message_name,
+            schema_data.name.as_str()
+        ))
+    })?;
+
+    let decoded = message.decode(data, &context);
+    message_to_json(&context, &decoded)
+}
+
+pub fn protobuf_encode(
+    schema_data: &SchemaData,
+    value: &JsonValue,
+    message_name: &str,
+) -> Result<Vec<u8>, CommonError> {
+    let context = Context::parse([schema_data.schema.as_str()]).map_err(|err| {
+        CommonError::CommonError(format!(
+            "Failed to parse schema {}: {}",
+            schema_data.name.as_str(),
+            err
+        ))
+    })?;
+
+    let message = context.get_message(message_name).ok_or_else(|| {
+        CommonError::CommonError(format!(
+            "Message {} not found in schema {}",
+            message_name,
+            schema_data.name.as_str()
+        ))
+    })?;
+
+    let mut buf = Vec::new();
+    encode_message(&context, message, value, &mut buf)?;
+    Ok(buf)
+}
+
+// Returns the full name of the first message declared in the schema, which is used
+// when the caller does not specify the message to decode.
+pub fn protobuf_default_message(schema_data: &SchemaData) -> Option<String> {
+    let mut package = None;
+    for line in schema_data.schema.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("package ") {
+            package = Some(name.trim_end_matches(';').trim().to_string());
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix("message ") {
+            let name = name.trim_end_matches('{').split_whitespace().next()?;
+            return Some(if let Some(package) = package {
+                format!("{}.{}", package, name)
+            } else {
+                name.to_string()
+            });
+        }
+    }
+    None
+}
+
+fn message_to_json(context: &Context, message: &MessageValue) -> Result<JsonValue, CommonError> {
+    let info = context.resolve_message(message.msg_ref);
+    let mut result = Map::new();
+    for field in message.fields.iter() {
+        let name = if let Some(field_info) = info.get_field(field.number) {
+            field_info.name.clone()
+        } else {
+            field.number.to_string()
+        };
+
+        let value = value_to_json(context, &field.value)?;
+
+        // repeated fields are decoded as one entry per element
+        match result.remove(&name) {
+            Some(JsonValue::Array(mut list)) => {
+                match value {
+                    JsonValue::Array(values) => list.extend(values),
+                    value => list.push(value),
+                }
+                result.insert(name, JsonValue::Array(list));
+            }
+            Some(exist) => {
+                result.insert(name, JsonValue::Array(vec![exist, value]));
+            }
+            None => {
+                result.insert(name, value);
+            }
+        }
+    }
+    Ok(JsonValue::Object(result))
+}
+
+fn value_to_json(context: &Context, value: &Value) -> Result<JsonValue, CommonError> {
+    let res = match value {
+        Value::Double(v) => float_to_json(*v),
+        Value::Float(v) => float_to_json(*v as f64),
+        Value::Int32(v) | Value::SInt32(v) | Value::SFixed32(v) => JsonValue::from(*v),
+        Value::Int64(v) | Value::SInt64(v) | Value::SFixed64(v) => JsonValue::from(*v),
+        Value::UInt32(v) | Value::Fixed32(v) => JsonValue::from(*v),
+        Value::UInt64(v) | Value::Fixed64(v) => JsonValue::from(*v),
+        Value::Bool(v) => JsonValue::Bool(*v),
+        Value::String(v) => JsonValue::String(v.clone()),
+        Value::Bytes(v) => JsonValue::Array(v.iter().map(|b| JsonValue::from(*b)).collect()),
+        Value::Enum(v) => JsonValue::from(v.value),
+        Value::Message(v) => message_to_json(context, v)?,
+        Value::Packed(v) => packed_to_json(v),
+        Value::Unknown(_) | Value::Incomplete(_, _) => {
+            return Err(CommonError::CommonError(
+                "protobuf payload contains unknown or incomplete fields".to_string(),
+            ));
+        }
+    };
+    Ok(res)
+}
+
+fn encode_message(
+    context: &Context,
+    info: &MessageInfo,
+    value: &JsonValue,
+    buf: &mut Vec<u8>,
+) -> Result<(), CommonError> {
+    let JsonValue::Object(map) = value else {
+        return Err(CommonError::CommonError(format!(
+            "Message {} must be encoded from a json object",
+            info.full_name
+        )));
+    };
+
+    for (name, value) in map.iter() {
+        let field = info.get_field_by_name(name).ok_or_else(|| {
+            CommonError::CommonError(format!(
+                "Field {} not found in message {}",
+                name, info.full_name
+            ))
+        })?;
+
+        // json arrays are encoded as repeated fields, one entry per element
+        let values = match value {
+            JsonValue::Array(list) if !matches!(field.field_type, ValueType::Bytes) => {
+                list.iter().collect()
+            }
+            JsonValue::Null => Vec::new(),
+            value => vec![value],
+        };
+        for value in values {
+            encode_field(context, field.number, &field.field_type, value, buf)?;
+        }
+    }
+    Ok(())
+}
+
+fn encode_field(
+    context: &Context,
+    number: u64,
+    field_type: &ValueType,
+    value: &JsonValue,
+    buf: &mut Vec<u8>,
+) -> Result<(), CommonError> {
+    let invalid = || {
+        CommonError::CommonError(format!(
+            "Value {} can not be encoded as protobuf field {}",
+            value, number
+        ))
+    };
+
+    match field_type {
+        ValueType::Int32 | ValueType::Int64 | ValueType::Enum(_) => {
+            let v = value.as_i64().ok_or_else(invalid)?;
+            write_varint(buf, number << 3);
+            write_varint(buf, v as u64);
+        }
+        ValueType::UInt32 | ValueType::UInt64 => {
+            let v = value.as_u64().ok_or_else(invalid)?;
+            write_varint(buf, number << 3);
+            write_varint(buf, v);
+        }
+        ValueType::SInt32 | ValueType::SInt64 => {
+            let v = value.as_i64().ok_or_else(invalid)?;
+            write_varint(buf, number << 3);
+            write_varint(buf, ((v << 1) ^ (v >> 63)) as u64);
+        }
+        ValueType::Bool => {
+            let v = value.as_bool().ok_or_else(invalid)?;
+            write_varint(buf, number << 3);
+            write_varint(buf, v as u64);
+        }
+        ValueType::Fixed64 => {
+            let v = value.as_u64().ok_or_else(invalid)?;
+            write_varint(buf, (number << 3) | 1);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueType::SFixed64 => {
+            let v = value.as_i64().ok_or_else(invalid)?;
+            write_varint(buf, (number << 3) | 1);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueType::Double => {
+            let v = value.as_f64().ok_or_else(invalid)?;
+            write_varint(buf, (number << 3) | 1);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueType::Fixed32 => {
+            let v = u32::try_from(value.as_u64().ok_or_else(invalid)?).map_err(|_| invalid())?;
+            write_varint(buf, (number << 3) | 5);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueType::SFixed32 => {
+            let v = i32::try_from(value.as_i64().ok_or_else(invalid)?).map_err(|_| invalid())?;
+            write_varint(buf, (number << 3) | 5);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueType::Float => {
+            let v = value.as_f64().ok_or_else(invalid)? as f32;
+            write_varint(buf, (number << 3) | 5);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueType::String => {
+            let v = value.as_str().ok_or_else(invalid)?;
+            write_bytes(buf, number, v.as_bytes());
+        }
+        ValueType::Bytes => {
+            let v = match value {
+                JsonValue::String(s) => s.as_bytes().to_vec(),
+                JsonValue::Array(list) => list
+                    .iter()
+                    .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(invalid)?,
+                _ => return Err(invalid()),
+            };
+            write_bytes(buf, number, &v);
+        }
+        ValueType::Message(msg_ref) => {
+            let mut inner = Vec::new();
+            encode_message(
+                context,
+                context.resolve_message(*msg_ref),
+                value,
+                &mut inner,
+            )?;
+            write_bytes(buf, number, &inner);
+        }
+    }
+    Ok(())
+}
+
+fn write_bytes(buf: &mut Vec<u8>, number: u64, data: &[u8]) {
+    write_varint(buf, (number << 3) | 2);
+    write_varint(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+fn packed_to_json(value: &PackedArray) -> JsonValue {
+    let list: Vec<JsonValue> = match value {
+        PackedArray::Double(v) => v.iter().map(|x| float_to_json(*x)).collect(),
+        PackedArray::Float(v) => v.iter().map(|x| float_to_json(*x as f64)).collect(),
+        PackedArray::Int32(v) | PackedArray::SInt32(v) | PackedArray::SFixed32(v) => {
+            v.iter().map(|x| JsonValue::from(*x)).collect()
+        }
+        PackedArray::Int64(v) | PackedArray::SInt64(v) | PackedArray::SFixed64(v) => {
+            v.iter().map(|x| JsonValue::from(*x)).collect()
+        }
+        PackedArray::UInt32(v) | PackedArray::Fixed32(v) => {
+            v.iter().map(|x| JsonValue::from(*x)).collect()
+        }
+        PackedArray::UInt64(v) | PackedArray::Fixed64(v) => {
+            v.iter().map(|x| JsonValue::from(*x)).collect()
+        }
+        PackedArray::Bool(v) => v.iter().map(|x| JsonValue::Bool(*x)).collect(),
+    };
+    JsonValue::Array(list)
+}
+
+fn float_to_json(v: f64) -> JsonValue {
+    Number::from_f64(v)
+        .map(JsonValue::Number)
+        .unwrap_or(JsonValue::Null)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::protobuf::protobuf_validate;
-    use metadata_struct::schema::{SchemaData, SchemaType};
+    use crate::protobuf::{
+        protobuf_decode, protobuf_default_message, protobuf_encode, protobuf_validate,
+    };
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+
+    #[test]
+    pub fn protobuf_decode_test() {
+        let schema = r#"
+            syntax = "proto3";
+            package MyPackage;
+
+            message Person {
+                string name = 1;
+                uint32 age = 2;
+            }
+        "#;
+
+        let schema_data = SchemaData {
+            cluster_name: "test_cluster".to_string(),
+            name: "MyPackage".to_string(),
+            schema_type: SchemaType::PROTOBUF,
+            desc: "".to_string(),
+            schema: schema.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
+        };
+
+        let message_name = protobuf_default_message(&schema_data).unwrap();
+        assert_eq!(message_name, "MyPackage.Person");
+
+        let res = protobuf_decode(&schema_data, b"\x0a\x04John\x10\x1e", &message_name).unwrap();
+        assert_eq!(res, serde_json::json!({"name": "John", "age": 30}));
+
+        let res = protobuf_decode(&schema_data, b"\x0a\x04John", "MyPackage.Unknown");
+        assert!(res.is_err());
+    }
+
+    #[test]
+    pub fn protobuf_encode_test() {
+        let schema = r#"
+            syntax = "proto3";
+            package MyPackage;
+
+            message Experience {
+                string company = 1;
+                repeated string skills = 5;
+            }
+
+            message Person {
+                string name = 1;
+                uint32 age = 2;
+                repeated Experience experience = 3;
+                sint32 offset = 4;
+            }
+        "#;
+
+        let schema_data = SchemaData {
+            cluster_name: "test_cluster".to_string(),
+            name: "MyPackage".to_string(),
+            schema_type: SchemaType::PROTOBUF,
+            desc: "".to_string(),
+            schema: schema.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
+        };
+
+        let data = protobuf_encode(
+            &schema_data,
+            &serde_json::json!({"name": "John", "age": 30}),
+            "MyPackage.Person",
+        )
+        .unwrap();
+        assert_eq!(data, b"\x0a\x04John\x10\x1e".to_vec());
+
+        let value = serde_json::json!({
+            "name": "John",
+            "age": 300,
+            "experience": [
+                {"company": "Google", "skills": ["Java", "Rust"]},
+                {"company": "RobustMQ", "skills": ["Rust", "Go"]}
+            ],
+            "offset": -2
+        });
+        let data = protobuf_encode(&schema_data, &value, "MyPackage.Person").unwrap();
+        assert!(protobuf_validate(&schema_data, &data, "MyPackage.Person").unwrap());
+        let res = protobuf_decode(&schema_data, &data, "MyPackage.Person").unwrap();
+        assert_eq!(res, value);
+
+        let res = protobuf_encode(
+            &schema_data,
+            &serde_json::json!({"unknown": 1}),
+            "MyPackage.Person",
+        );
+        assert!(res.is_err());
+        let res = protobuf_encode(
+            &schema_data,
+            &serde_json::json!({"age": "30"}),
+            "MyPackage.Person",
+        );
+        assert!(res.is_err());
+    }
 
     #[test]
     pub fn protobuf_validate_test() {
@@ -81,6 +486,8 @@ pub fn protofish_example_test() {
             schema_type: SchemaType::PROTOBUF,
             desc: "".to_string(),
             schema: schema.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
         };
 
         let res = protobuf_validate(&schema_data, b"\x0a\x05Perch", "Proto.Request");
@@ -126,6 +533,8 @@ pub fn protofish_schema_test() {
             schema_type: SchemaType::PROTOBUF,
             desc: "".to_string(),
             schema: schema.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
         };
 
         // ----- Experience -----
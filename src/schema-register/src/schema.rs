@@ -16,20 +16,27 @@
 use dashmap::DashMap;
 use metadata_struct::schema::{SchemaData, SchemaResourceBind, SchemaType};
 
-use crate::{avro::avro_validate, json::json_validate};
+use crate::{
+    avro::avro_validate,
+    json::json_validate,
+    transcode::{decode_json_payload, decode_to_json, encode_from_json},
+};
 
 #[derive(Default)]
 pub struct SchemaRegisterManager {
-    // (SchemaName, SchemaData)
+    // (SchemaName, SchemaData), the latest version of each schema
     schema_list: DashMap<String, SchemaData>,
-    // (Resource, Vec<SchemaName>)
-    schema_resource_list: DashMap<String, Vec<String>>,
+    // (SchemaName, (Version, SchemaData))
+    schema_version_list: DashMap<String, DashMap<u64, SchemaData>>,
+    // (Resource, Vec<SchemaResourceBind>)
+    schema_resource_list: DashMap<String, Vec<SchemaResourceBind>>,
 }
 
 impl SchemaRegisterManager {
     pub fn new() -> Self {
         SchemaRegisterManager {
             schema_list: DashMap::with_capacity(2),
+            schema_version_list: DashMap::with_capacity(2),
             schema_resource_list: DashMap::with_capacity(2),
         }
     }
@@ -42,9 +49,9 @@ pub fn is_check_schema(&self, topic: &str) -> bool {
     }
 
     pub fn validate(&self, resource: &str, data: &[u8]) -> Result<bool, CommonError> {
-        if let Some(schemc_list) = self.schema_resource_list.get(resource) {
-            for schema_name in schemc_list.iter() {
-                if let Some(schema) = self.schema_list.get(schema_name) {
+        if let Some(bind_list) = self.schema_resource_list.get(resource) {
+            for bind in bind_list.iter() {
+                if let Some(schema) = self.get_bind_schema(bind) {
                     match schema.schema_type {
                         SchemaType::JSON => {
                             let raw = serde_json::from_slice::<String>(data)?;
@@ -61,13 +68,62 @@ pub fn validate(&self, resource: &str, data: &[u8]) -> Result<bool, CommonError>
         Ok(true)
     }
 
+    // Convert a payload stored in `resource` into the `target` format. The payload is decoded
+    // with the first schema bound to the resource that can decode it, in bind order, and
+    // encoded with a bound schema of the target type.
+    pub fn transcode(
+        &self,
+        resource: &str,
+        data: &[u8],
+        target: &SchemaType,
+    ) -> Result<Vec<u8>, CommonError> {
+        let schemas = self.get_schema_resource(resource);
+
+        let value = if schemas.is_empty() {
+            decode_json_payload(data)?
+        } else {
+            let (source, value) = decode_source_payload(resource, &schemas, data)?;
+            if source.schema_type == *target {
+                return Ok(data.to_vec());
+            }
+            value
+        };
+
+        if *target == SchemaType::JSON {
+            return Ok(serde_json::to_vec(&value)?);
+        }
+
+        if let Some(schema) = schemas.iter().find(|s| s.schema_type == *target) {
+            return encode_from_json(schema, value);
+        }
+
+        Err(CommonError::CommonError(format!(
+            "Resource {} has no {} schema bound, payload can not be transcoded",
+            resource, target
+        )))
+    }
+
     // Schema
     pub fn add_schema(&self, schema: SchemaData) {
-        self.schema_list.insert(schema.name.clone(), schema);
+        let is_latest = if let Some(current) = self.schema_list.get(&schema.name) {
+            current.version <= schema.version
+        } else {
+            true
+        };
+
+        self.schema_version_list
+            .entry(schema.name.clone())
+            .or_default()
+            .insert(schema.version, schema.clone());
+
+        if is_latest {
+            self.schema_list.insert(schema.name.clone(), schema);
+        }
     }
 
     pub fn remove_schema(&self, schema_name: &str) {
         self.schema_list.remove(schema_name);
+        self.schema_version_list.remove(schema_name);
     }
 
     pub fn get_schema(&self, schema_name: &str) -> Option<SchemaData> {
@@ -77,6 +133,15 @@ pub fn get_schema(&self, schema_name: &str) -> Option<SchemaData> {
         None
     }
 
+    pub fn get_schema_by_version(&self, schema_name: &str, version: u64) -> Option<SchemaData> {
+        if let Some(list) = self.schema_version_list.get(schema_name) {
+            if let Some(schema) = list.get(&version) {
+                return Some(schema.clone());
+            }
+        }
+        None
+    }
+
     pub fn get_all_schema(&self) -> Vec<SchemaData> {
         let mut list = Vec::new();
         for schema in self.schema_list.iter() {
@@ -87,16 +152,14 @@ pub fn get_all_schema(&self) -> Vec<SchemaData> {
 
     // Schema Resource
     pub fn add_schema_resource(&self, schema_resource: &SchemaResourceBind) {
-        let schema_name = &schema_resource.schema_name;
         let resource = schema_resource.resource_name.clone();
 
-        if let Some(mut list) = self.schema_resource_list.get_mut(schema_name) {
-            if !list.contains(&schema_name.to_owned()) {
-                list.push(schema_name.to_owned());
-            }
+        if let Some(mut list) = self.schema_resource_list.get_mut(&resource) {
+            list.retain(|x| x.schema_name != schema_resource.schema_name);
+            list.push(schema_resource.clone());
         } else {
             self.schema_resource_list
-                .insert(resource, vec![schema_name.to_owned()]);
+                .insert(resource, vec![schema_resource.clone()]);
         }
     }
 
@@ -106,16 +169,16 @@ pub fn remove_resource(&self, resource: &str) {
 
     pub fn remove_resource_schema(&self, resource: &str, schema_name: &str) {
         if let Some(mut list) = self.schema_resource_list.get_mut(resource) {
-            list.retain(|x| x != schema_name);
+            list.retain(|x| x.schema_name != schema_name);
         }
     }
 
     pub fn get_schema_resource(&self, resource: &str) -> Vec<SchemaData> {
         if let Some(list) = self.schema_resource_list.get(resource) {
             let mut res = Vec::new();
-            for schema_name in list.iter() {
-                if let Some(schema) = self.schema_list.get(schema_name) {
-                    res.push(schema.clone());
+            for bind in list.iter() {
+                if let Some(schema) = self.get_bind_schema(bind) {
+                    res.push(schema);
                 }
             }
             res
@@ -123,13 +186,42 @@ pub fn get_schema_resource(&self, resource: &str) -> Vec<SchemaData> {
             vec![]
         }
     }
+
+    fn get_bind_schema(&self, bind: &SchemaResourceBind) -> Option<SchemaData> {
+        if let Some(version) = bind.schema_version {
+            return self.get_schema_by_version(&bind.schema_name, version);
+        }
+        self.get_schema(&bind.schema_name)
+    }
+}
+
+fn decode_source_payload<'a>(
+    resource: &str,
+    schemas: &'a [SchemaData],
+    data: &[u8],
+) -> Result<(&'a SchemaData, serde_json::Value), CommonError> {
+    let mut errors = Vec::new();
+    for schema in schemas {
+        match decode_to_json(schema, data) {
+            Ok(value) => return Ok((schema, value)),
+            Err(e) => errors.push(format!("{}: {}", schema.name, e)),
+        }
+    }
+    Err(CommonError::CommonError(format!(
+        "Payload of resource {} can not be decoded with any bound schema, {}",
+        resource,
+        errors.join(", ")
+    )))
 }
 
 #[cfg(test)]
 mod test {
     use super::SchemaRegisterManager;
+    use crate::avro::avro_validate;
     use apache_avro::{Schema, Writer};
-    use metadata_struct::schema::{SchemaData, SchemaResourceBind, SchemaType};
+    use metadata_struct::schema::{
+        SchemaCompatibility, SchemaData, SchemaResourceBind, SchemaType,
+    };
     use serde::{Deserialize, Serialize};
 
     #[test]
@@ -151,6 +243,8 @@ pub fn json_schema_test() {
             schema: schema_json_content.to_string(),
             schema_type: SchemaType::JSON,
             desc: "test".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
         });
 
         let topic_name = "t1".to_string();
@@ -158,6 +252,7 @@ pub fn json_schema_test() {
             cluster_name: cluster_name.clone(),
             resource_name: topic_name.clone(),
             schema_name: schema_name.clone(),
+            schema_version: None,
         };
         schema_manager.add_schema_resource(&bind_schema);
 
@@ -198,6 +293,191 @@ pub fn json_schema_test() {
         assert!(result.unwrap());
     }
 
+    #[test]
+    pub fn schema_version_test() {
+        let schema_manager = SchemaRegisterManager::new();
+        let cluster_name = "test1".to_string();
+        let schema_name = "schema1".to_string();
+        let v1 = r#"{
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            },
+            "required": ["name"]
+        }"#;
+        let v2 = r#"{
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" }
+            },
+            "required": ["name", "age"]
+        }"#;
+
+        for (version, schema) in [(1, v1), (2, v2)] {
+            schema_manager.add_schema(SchemaData {
+                cluster_name: cluster_name.clone(),
+                name: schema_name.clone(),
+                schema: schema.to_string(),
+                schema_type: SchemaType::JSON,
+                desc: "test".to_string(),
+                version,
+                compatibility: SchemaCompatibility::NONE,
+            });
+        }
+
+        // an older version arriving late must not replace the latest one
+        schema_manager.add_schema(
+            schema_manager
+                .get_schema_by_version(&schema_name, 1)
+                .unwrap(),
+        );
+        assert_eq!(schema_manager.get_schema(&schema_name).unwrap().version, 2);
+
+        let pinned_topic = "t1".to_string();
+        schema_manager.add_schema_resource(&SchemaResourceBind {
+            cluster_name: cluster_name.clone(),
+            resource_name: pinned_topic.clone(),
+            schema_name: schema_name.clone(),
+            schema_version: Some(1),
+        });
+
+        let latest_topic = "t2".to_string();
+        schema_manager.add_schema_resource(&SchemaResourceBind {
+            cluster_name: cluster_name.clone(),
+            resource_name: latest_topic.clone(),
+            schema_name: schema_name.clone(),
+            schema_version: None,
+        });
+
+        let data = serde_json::to_vec(r#"{"name": "John Doe"}"#).unwrap();
+        assert!(schema_manager.validate(&pinned_topic, &data).unwrap());
+        assert!(!schema_manager.validate(&latest_topic, &data).unwrap());
+
+        schema_manager.remove_schema(&schema_name);
+        assert!(schema_manager
+            .get_schema_by_version(&schema_name, 1)
+            .is_none());
+    }
+
+    #[test]
+    pub fn transcode_test() {
+        let schema_manager = SchemaRegisterManager::new();
+        let cluster_name = "test1".to_string();
+        let avro_content = r#"
+        {
+            "type": "record",
+            "name": "test",
+            "fields": [
+                {"name": "a", "type": "long"},
+                {"name": "b", "type": "string"}
+            ]
+        }
+        "#;
+
+        schema_manager.add_schema(SchemaData {
+            cluster_name: cluster_name.clone(),
+            name: "avro_schema".to_string(),
+            schema: avro_content.to_string(),
+            schema_type: SchemaType::AVRO,
+            desc: "test".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
+        });
+
+        let topic_name = "t1".to_string();
+        schema_manager.add_schema_resource(&SchemaResourceBind {
+            cluster_name: cluster_name.clone(),
+            resource_name: topic_name.clone(),
+            schema_name: "avro_schema".to_string(),
+            schema_version: None,
+        });
+
+        let schema = Schema::parse_str(avro_content).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+        writer
+            .append_ser(TestData {
+                a: 1,
+                b: "test".to_string(),
+            })
+            .unwrap();
+        let encoded_data = writer.into_inner().unwrap();
+
+        // avro -> json
+        let json_data = schema_manager
+            .transcode(&topic_name, &encoded_data, &SchemaType::JSON)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json_data).unwrap();
+        assert_eq!(value, serde_json::json!({"a": 1, "b": "test"}));
+
+        // same format is passed through
+        let res = schema_manager
+            .transcode(&topic_name, &encoded_data, &SchemaType::AVRO)
+            .unwrap();
+        assert_eq!(res, encoded_data);
+
+        // no protobuf schema bound to the topic
+        assert!(schema_manager
+            .transcode(&topic_name, &encoded_data, &SchemaType::PROTOBUF)
+            .is_err());
+
+        // json -> avro, decoded with the json schema and encoded with the avro schema
+        schema_manager.add_schema(SchemaData {
+            cluster_name: cluster_name.clone(),
+            name: "json_schema".to_string(),
+            schema: r#"{"type": "object"}"#.to_string(),
+            schema_type: SchemaType::JSON,
+            desc: "test".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
+        });
+
+        let json_topic = "t2".to_string();
+        for schema_name in ["json_schema", "avro_schema"] {
+            schema_manager.add_schema_resource(&SchemaResourceBind {
+                cluster_name: cluster_name.clone(),
+                resource_name: json_topic.clone(),
+                schema_name: schema_name.to_string(),
+                schema_version: None,
+            });
+        }
+
+        let data = serde_json::to_vec(r#"{"a": 1, "b": "test"}"#).unwrap();
+        let avro_data = schema_manager
+            .transcode(&json_topic, &data, &SchemaType::AVRO)
+            .unwrap();
+        assert!(avro_validate(avro_content, &avro_data).unwrap());
+
+        // the source schema is the one that decodes the payload, not the first bound one
+        let avro_first_topic = "t4".to_string();
+        for schema_name in ["avro_schema", "json_schema"] {
+            schema_manager.add_schema_resource(&SchemaResourceBind {
+                cluster_name: cluster_name.clone(),
+                resource_name: avro_first_topic.clone(),
+                schema_name: schema_name.to_string(),
+                schema_version: None,
+            });
+        }
+        let avro_data = schema_manager
+            .transcode(&avro_first_topic, &data, &SchemaType::AVRO)
+            .unwrap();
+        assert!(avro_validate(avro_content, &avro_data).unwrap());
+        let json_data = schema_manager
+            .transcode(&avro_first_topic, &encoded_data, &SchemaType::JSON)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json_data).unwrap();
+        assert_eq!(value, serde_json::json!({"a": 1, "b": "test"}));
+
+        // topics without schema only support plain json
+        let res = schema_manager
+            .transcode("t3", br#"{"a": 1}"#, &SchemaType::JSON)
+            .unwrap();
+        assert_eq!(res, br#"{"a":1}"#.to_vec());
+        assert!(schema_manager
+            .transcode("t3", br#"{"a": 1}"#, &SchemaType::AVRO)
+            .is_err());
+    }
+
     #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
     struct TestData {
         a: u64,
@@ -232,6 +512,8 @@ pub fn avro_schema_test() {
             schema: schema_avro_content.to_string(),
             schema_type: SchemaType::AVRO,
             desc: "test".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
         });
 
         let topic_name = "t1".to_string();
@@ -239,6 +521,7 @@ pub fn avro_schema_test() {
             cluster_name: cluster_name.clone(),
             resource_name: topic_name.clone(),
             schema_name: schema_name.clone(),
+            schema_version: None,
         };
         schema_manager.add_schema_resource(&bind_schema);
 
@@ -0,0 +1,189 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use apache_avro::{types::Value as AvroValue, Reader, Schema, Writer};
+use common_base::error::common::CommonError;
+use metadata_struct::schema::{SchemaData, SchemaType};
+use serde_json::Value;
+
+use crate::protobuf::{protobuf_decode, protobuf_default_message, protobuf_encode};
+
+/// Decode a payload that was encoded with `schema` into a JSON value.
+pub fn decode_to_json(schema: &SchemaData, data: &[u8]) -> Result<Value, CommonError> {
+    match schema.schema_type {
+        SchemaType::JSON => decode_json_payload(data),
+        SchemaType::AVRO => avro_to_json(&schema.schema, data),
+        SchemaType::PROTOBUF => protobuf_decode(schema, data, &default_message(schema)?),
+    }
+}
+
+/// Encode a JSON value with `schema`.
+pub fn encode_from_json(schema: &SchemaData, value: Value) -> Result<Vec<u8>, CommonError> {
+    match schema.schema_type {
+        SchemaType::JSON => Ok(serde_json::to_vec(&value)?),
+        SchemaType::AVRO => json_to_avro(&schema.schema, value),
+        SchemaType::PROTOBUF => protobuf_encode(schema, &value, &default_message(schema)?),
+    }
+}
+
+fn default_message(schema: &SchemaData) -> Result<String, CommonError> {
+    protobuf_default_message(schema).ok_or_else(|| {
+        CommonError::CommonError(format!(
+            "No message is defined in protobuf schema {}",
+            schema.name
+        ))
+    })
+}
+
+// JSON payloads validated by the broker are JSON strings that wrap the document,
+// plain JSON documents are accepted as well.
+pub fn decode_json_payload(data: &[u8]) -> Result<Value, CommonError> {
+    let value = serde_json::from_slice::<Value>(data)?;
+    if let Value::String(raw) = &value {
+        if let Ok(inner) = serde_json::from_str::<Value>(raw) {
+            return Ok(inner);
+        }
+    }
+    Ok(value)
+}
+
+fn avro_to_json(schema: &str, data: &[u8]) -> Result<Value, CommonError> {
+    let schema = Schema::parse_str(schema)?;
+    let reader = Reader::with_schema(&schema, data)?;
+    let mut records = Vec::new();
+    for record in reader {
+        let value: Value = record?.try_into()?;
+        records.push(value);
+    }
+
+    if records.len() == 1 {
+        return Ok(records.remove(0));
+    }
+    Ok(Value::Array(records))
+}
+
+fn json_to_avro(schema: &str, value: Value) -> Result<Vec<u8>, CommonError> {
+    let schema = Schema::parse_str(schema)?;
+    let mut writer = Writer::new(&schema, Vec::new());
+    let records = match value {
+        Value::Array(list) if !matches!(schema, Schema::Array(_)) => list,
+        value => vec![value],
+    };
+
+    for record in records {
+        let record = AvroValue::from(record).resolve(&schema)?;
+        writer.append(record)?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+#[cfg(test)]
+mod test {
+    use apache_avro::{Schema, Writer};
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::{decode_to_json, encode_from_json};
+    use crate::avro::avro_validate;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct TestData {
+        a: i64,
+        b: String,
+    }
+
+    fn build_schema(schema_type: SchemaType, schema: &str) -> SchemaData {
+        SchemaData {
+            cluster_name: "test_cluster".to_string(),
+            name: "schema1".to_string(),
+            schema_type,
+            desc: "".to_string(),
+            schema: schema.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::NONE,
+        }
+    }
+
+    #[test]
+    pub fn avro_json_transcode_test() {
+        let raw_schema = r#"
+        {
+            "type": "record",
+            "name": "test",
+            "fields": [
+                {"name": "a", "type": "long"},
+                {"name": "b", "type": "string"}
+            ]
+        }
+        "#;
+        let schema_data = build_schema(SchemaType::AVRO, raw_schema);
+
+        let schema = Schema::parse_str(raw_schema).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+        writer
+            .append_ser(TestData {
+                a: 1,
+                b: "test".to_string(),
+            })
+            .unwrap();
+        let encoded_data = writer.into_inner().unwrap();
+
+        let value = decode_to_json(&schema_data, &encoded_data).unwrap();
+        assert_eq!(value, json!({"a": 1, "b": "test"}));
+
+        let encoded_data = encode_from_json(&schema_data, value).unwrap();
+        assert!(avro_validate(raw_schema, &encoded_data).unwrap());
+
+        let res = encode_from_json(&schema_data, json!({"a": "1"}));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    pub fn json_protobuf_transcode_test() {
+        let schema_data = build_schema(
+            SchemaType::PROTOBUF,
+            r#"
+            syntax = "proto3";
+            package MyPackage;
+
+            message Person {
+                string name = 1;
+                uint32 age = 2;
+            }
+            "#,
+        );
+
+        let value = json!({"name": "John", "age": 30});
+        let encoded_data = encode_from_json(&schema_data, value.clone()).unwrap();
+        assert_eq!(encoded_data, b"\x0a\x04John\x10\x1e".to_vec());
+        assert_eq!(decode_to_json(&schema_data, &encoded_data).unwrap(), value);
+
+        assert!(encode_from_json(&schema_data, json!({"name": 1})).is_err());
+    }
+
+    #[test]
+    pub fn json_payload_transcode_test() {
+        let schema_data = build_schema(SchemaType::JSON, r#"{"type": "object"}"#);
+
+        let data = serde_json::to_vec(r#"{"name": "John Doe"}"#).unwrap();
+        let value = decode_to_json(&schema_data, &data).unwrap();
+        assert_eq!(value, json!({"name": "John Doe"}));
+
+        let value = decode_to_json(&schema_data, br#"{"name": "John Doe"}"#).unwrap();
+        assert_eq!(value, json!({"name": "John Doe"}));
+
+        assert!(decode_to_json(&schema_data, b"not json").is_err());
+    }
+}
@@ -107,6 +107,7 @@ async fn write(
         record: Record,
     ) -> Result<u64, CommonError> {
         let data = JournalClientWriteData {
+            headers: record.header_map(),
             key: record.key,
             content: record.data,
             tags: record.tags,
@@ -134,6 +135,7 @@ async fn batch_write(
         let mut data = Vec::new();
         for record in records {
             data.push(JournalClientWriteData {
+                headers: record.header_map(),
                 key: record.key,
                 content: record.data,
                 tags: record.tags,
@@ -216,6 +218,28 @@ async fn read_by_key(
         }
     }
 
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        Err(CommonError::CommonError(format!(
+            "The journal engine does not report the end offset of shard {} under {}",
+            shard_name, namespace
+        )))
+    }
+
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        Err(CommonError::CommonError(format!(
+            "The journal engine does not report the size of shard {} under {}",
+            shard_name, namespace
+        )))
+    }
+
     async fn get_offset_by_group(&self, group: String) -> Result<Vec<ShardOffset>, CommonError> {
         self.offset_manager
             .get_shard_offset(&self.cluster_name, &group)
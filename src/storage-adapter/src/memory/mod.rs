@@ -252,6 +252,37 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let shard_key = self.shard_key(&namespace, &shard_name);
+        Ok(self
+            .shard_data
+            .get(&shard_key)
+            .map(|data_list| data_list.len() as u64)
+            .unwrap_or(0))
+    }
+
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let shard_key = self.shard_key(&namespace, &shard_name);
+        Ok(self
+            .shard_data
+            .get(&shard_key)
+            .map(|data_list| {
+                data_list
+                    .iter()
+                    .map(|record| record.data.len() as u64)
+                    .sum()
+            })
+            .unwrap_or(0))
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
@@ -344,6 +375,27 @@ async fn stream_read_write() {
         assert_eq!(result.get(1).unwrap().clone(), 3);
         assert!(storage_adapter.shard_data.contains_key(&shard_key));
         assert_eq!(storage_adapter.shard_data.get(&shard_key).unwrap().len(), 4);
+        assert_eq!(
+            storage_adapter
+                .get_end_offset(namespace.clone(), shard_name.clone())
+                .await
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            storage_adapter
+                .get_end_offset(namespace.clone(), "not-exists".to_string())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            storage_adapter
+                .get_shard_size(namespace.clone(), shard_name.clone())
+                .await
+                .unwrap(),
+            20
+        );
 
         let group_id = "test_group_id".to_string();
         let mut read_config = ReadConfig::new();
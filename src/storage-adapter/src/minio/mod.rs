@@ -100,6 +100,11 @@ pub fn records_path(
         )
     }
 
+    #[inline(always)]
+    pub fn records_path_prefix(namespace: impl AsRef<str>, shard_name: impl AsRef<str>) -> String {
+        format!("records/{}/{}/", namespace.as_ref(), shard_name.as_ref())
+    }
+
     #[inline(always)]
     pub fn offsets_path(namespace: impl AsRef<str>, shard_name: impl AsRef<str>) -> String {
         format!(
@@ -511,6 +516,41 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let offset_bytes = self
+            .op
+            .read(&Self::offsets_path(&namespace, &shard_name))
+            .await?
+            .to_vec();
+        Ok(serde_json::from_slice::<u64>(&offset_bytes)?)
+    }
+
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let mut lister = self
+            .op
+            .lister_with(&Self::records_path_prefix(&namespace, &shard_name))
+            .await?;
+
+        let mut size = 0;
+        while let Some(entry) = lister.try_next().await? {
+            if entry.metadata().mode() != EntryMode::FILE {
+                continue;
+            }
+            let record_bytes = self.op.read(entry.path()).await?.to_vec();
+            let record = serde_json::from_slice::<Record>(&record_bytes)?;
+            size += record.data.len() as u64;
+        }
+        Ok(size)
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
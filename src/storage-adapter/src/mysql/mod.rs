@@ -580,6 +580,42 @@ async fn get_offset_by_timestamp(
         .map_err(|e| CommonError::CommonError(format!("Failed to get offset by timestamp: {}", e)))
     }
 
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let mut conn = self.pool.get_conn()?;
+
+        let sql = format!(
+            "SELECT IFNULL(MAX(`offset`) + 1, 0) FROM `{}`",
+            Self::record_table_name(&namespace, &shard_name)
+        );
+
+        let offset: Option<u64> = conn
+            .query_first(sql)
+            .map_err(|e| CommonError::CommonError(format!("Failed to get end offset: {}", e)))?;
+        Ok(offset.unwrap_or(0))
+    }
+
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let mut conn = self.pool.get_conn()?;
+
+        let sql = format!(
+            "SELECT CAST(IFNULL(SUM(LENGTH(`data`)), 0) AS UNSIGNED) FROM `{}`",
+            Self::record_table_name(&namespace, &shard_name)
+        );
+
+        let size: Option<u64> = conn
+            .query_first(sql)
+            .map_err(|e| CommonError::CommonError(format!("Failed to get shard size: {}", e)))?;
+        Ok(size.unwrap_or(0))
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
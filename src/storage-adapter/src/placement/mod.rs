@@ -291,6 +291,7 @@ async fn thread_batch_write(
                 SetRequest {
                     key: Self::shard_record_key(&namespace, &shard_name, start_offset),
                     value: serde_json::to_string(&msg)?,
+                    lease_id: 0,
                 },
             )
             .await?;
@@ -303,6 +304,7 @@ async fn thread_batch_write(
                     SetRequest {
                         key: Self::key_offset_key(&namespace, &shard_name, &msg.key),
                         value: serde_json::to_string(&start_offset)?,
+                        lease_id: 0,
                     },
                 )
                 .await?;
@@ -316,6 +318,7 @@ async fn thread_batch_write(
                     SetRequest {
                         key: Self::tag_offsets_key(&namespace, &shard_name, tag, start_offset),
                         value: serde_json::to_string(&start_offset)?,
+                        lease_id: 0,
                     },
                 )
                 .await?;
@@ -331,6 +334,7 @@ async fn thread_batch_write(
             SetRequest {
                 key: Self::shard_offset_key(&namespace, &shard_name),
                 value: serde_json::to_string(&start_offset)?,
+                lease_id: 0,
             },
         )
         .await?;
@@ -367,6 +371,7 @@ async fn create_shard(&self, shard: ShardInfo) -> Result<(), CommonError> {
             SetRequest {
                 key: Self::shard_offset_key(&namespace, &shard_name),
                 value: serde_json::to_string(&0_u64)?,
+                lease_id: 0,
             },
         )
         .await?;
@@ -377,6 +382,7 @@ async fn create_shard(&self, shard: ShardInfo) -> Result<(), CommonError> {
             SetRequest {
                 key: Self::shard_info_key(&namespace, &shard_name),
                 value: serde_json::to_string(&shard)?,
+                lease_id: 0,
             },
         )
         .await?;
@@ -641,6 +647,44 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let reply = placement_get(
+            &self.client_pool,
+            &self.addrs,
+            GetRequest {
+                key: Self::shard_offset_key(&namespace, &shard_name),
+            },
+        )
+        .await?;
+        Ok(reply.value.parse::<u64>()?)
+    }
+
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let reply = placement_get_prefix(
+            &self.client_pool,
+            &self.addrs,
+            GetPrefixRequest {
+                prefix: Self::shard_record_key_prefix(&namespace, &shard_name),
+            },
+        )
+        .await?;
+
+        let mut size = 0;
+        for val in reply.values {
+            let record = serde_json::from_str::<Record>(&val)?;
+            size += record.data.len() as u64;
+        }
+        Ok(size)
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
@@ -681,6 +725,7 @@ async fn commit_offset(
                 SetRequest {
                     key: Self::group_record_offsets_key(&group_name, &namespace, &shard_name),
                     value: serde_json::to_string(&offset)?,
+                    lease_id: 0,
                 },
             )
             .await?;
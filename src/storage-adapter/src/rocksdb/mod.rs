@@ -574,6 +574,39 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        self.ensure_shard_exists(&namespace, &shard_name)?;
+
+        let cf = self.db.cf_handle(DB_COLUMN_FAMILY).unwrap();
+        let shard_offset_key = Self::shard_offset_key(&namespace, &shard_name);
+        Ok(self
+            .db
+            .read::<u64>(cf, shard_offset_key.as_str())?
+            .unwrap_or(0))
+    }
+
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        self.ensure_shard_exists(&namespace, &shard_name)?;
+
+        let cf = self.db.cf_handle(DB_COLUMN_FAMILY).unwrap();
+        let shard_record_key_prefix = Self::shard_record_key_prefix(&namespace, &shard_name);
+
+        let mut size = 0;
+        for (_, v) in self.db.read_prefix(cf, &shard_record_key_prefix)? {
+            let record = serde_json::from_slice::<Record>(&v)?;
+            size += record.data.len() as u64;
+        }
+        Ok(size)
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
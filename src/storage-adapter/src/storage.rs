@@ -94,6 +94,20 @@ async fn get_offset_by_timestamp(
         timestamp: u64,
     ) -> Result<Option<ShardOffset>, CommonError>;
 
+    // The offset the next record written to the shard will get.
+    async fn get_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError>;
+
+    // Payload bytes of the records kept in the shard.
+    async fn get_shard_size(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError>;
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
@@ -125,6 +125,7 @@ async fn write_data(client: &JournalClient, namespace: &str, shard_name: &str) {
             key: "k0".to_string(),
             content: "ccccc0".as_bytes().to_vec(),
             tags: vec!["tag0".to_string()],
+            ..Default::default()
         };
 
         let res_opt = client
@@ -141,6 +142,7 @@ async fn write_data(client: &JournalClient, namespace: &str, shard_name: &str) {
             key: "k1".to_string(),
             content: "ccccc1".as_bytes().to_vec(),
             tags: vec!["tag1".to_string()],
+            ..Default::default()
         };
         let res_opt = client
             .write(namespace.to_owned(), shard_name.to_owned(), data)
@@ -157,16 +159,19 @@ async fn write_data(client: &JournalClient, namespace: &str, shard_name: &str) {
                 key: "k2".to_string(),
                 content: "ccccc2".as_bytes().to_vec(),
                 tags: vec!["tag2".to_string()],
+                ..Default::default()
             },
             JournalClientWriteData {
                 key: "k3".to_string(),
                 content: "ccccc3".as_bytes().to_vec(),
                 tags: vec!["tag3".to_string()],
+                ..Default::default()
             },
             JournalClientWriteData {
                 key: "k4".to_string(),
                 content: "ccccc4".as_bytes().to_vec(),
                 tags: vec!["tag4".to_string()],
+                ..Default::default()
             },
         ];
         let res_opt = client
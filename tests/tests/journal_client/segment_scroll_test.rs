@@ -41,6 +41,7 @@ async fn write_scroll_test() {
                 key: format!("key-{}", i),
                 content: content.as_bytes().to_vec(),
                 tags: vec![format!("tag-{}", i)],
+                ..Default::default()
             };
 
             let res_opt = client
@@ -37,6 +37,7 @@ pub struct ClientTestProperties {
 pub mod auth_test;
 pub mod keep_alive_test;
 pub mod lastwill_message_test;
+pub mod mqtt_bridge_test;
 pub mod qos_test;
 pub mod req_resp_test;
 pub mod retain_message_test;
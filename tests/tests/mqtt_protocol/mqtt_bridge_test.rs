@@ -0,0 +1,568 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use common_base::tools::unique_id;
+    use dashmap::DashMap;
+    use futures::{SinkExt, StreamExt};
+    use grpc_clients::mqtt::admin::call::{
+        mqtt_broker_create_connector, mqtt_broker_delete_connector,
+    };
+    use grpc_clients::pool::ClientPool;
+    use metadata_struct::mqtt::bridge::config_mqtt::{
+        MqttBridgeConnectorConfig, MqttBridgeDirection,
+    };
+    use mqtt_broker::bridge::mqtt::BRIDGE_ORIGIN_PROPERTY;
+    use paho_mqtt::{Client, Message, MessageBuilder, Properties, PropertyCode, Receiver};
+    use protocol::broker_mqtt::broker_mqtt_admin::{
+        MqttConnectorType, MqttCreateConnectorRequest, MqttDeleteConnectorRequest,
+    };
+    use protocol::mqtt::codec::{MqttCodec, MqttPacketWrapper};
+    use protocol::mqtt::common::{
+        ConnAck, ConnAckProperties, ConnectReturnCode, MqttPacket, PingResp, PubAck, PubAckReason,
+        Publish, QoS, SubAck, SubAckProperties, SubscribeReasonCode,
+    };
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::select;
+    use tokio::sync::mpsc;
+    use tokio_util::codec::Framed;
+
+    use crate::mqtt_protocol::{
+        common::{
+            broker_addr, broker_grpc_addr, build_client_id, connect_server, distinct_conn,
+            password, publish_data, username,
+        },
+        ClientTestProperties,
+    };
+
+    // The bridge connects back to the test broker, which plays the remote site.
+    #[tokio::test]
+    async fn mqtt_bridge_ingress_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let grpc_addr = vec![broker_grpc_addr()];
+
+        let id = unique_id();
+        let src_topic = format!("/bridge_src/{}", id);
+        let dst_topic = format!("/bridge_dst{}", src_topic);
+        let connector_name = format!("mqtt_bridge_{}", id);
+
+        let config = MqttBridgeConnectorConfig {
+            server: broker_addr(),
+            client_id: build_client_id("mqtt_bridge_ingress"),
+            username: username(),
+            password: password(),
+            direction: MqttBridgeDirection::Ingress,
+            subscribe_topics: vec![src_topic.clone()],
+            local_topic_prefix: "/bridge_dst".to_string(),
+            ..Default::default()
+        };
+        create_bridge(
+            &client_pool,
+            &grpc_addr,
+            &connector_name,
+            &src_topic,
+            &config,
+        )
+        .await;
+
+        let client_properties = ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_ingress_sub"),
+            addr: broker_addr(),
+            ..Default::default()
+        };
+        let sub_cli = connect_server(&client_properties);
+        let rx = sub_cli.start_consuming();
+        sub_cli.subscribe(&dst_topic, 1).unwrap();
+
+        let client_properties = ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_ingress_pub"),
+            addr: broker_addr(),
+            ..Default::default()
+        };
+        let pub_cli = connect_server(&client_properties);
+
+        // Keep publishing until the connector has been scheduled and subscribed.
+        let mut received = None;
+        for _ in 0..30 {
+            let msg = MessageBuilder::new()
+                .payload("bridged message")
+                .topic(src_topic.clone())
+                .qos(1)
+                .finalize();
+            publish_data(&pub_cli, msg, false);
+
+            if let Ok(Some(msg)) = rx.recv_timeout(Duration::from_secs(1)) {
+                received = Some(msg);
+                break;
+            }
+        }
+        let msg = received.expect("bridged message was not received");
+        assert_eq!(msg.topic(), dst_topic);
+        assert_eq!(msg.payload_str(), "bridged message");
+
+        distinct_conn(pub_cli);
+        distinct_conn(sub_cli);
+
+        delete_bridge(&client_pool, &grpc_addr, connector_name).await;
+    }
+
+    #[tokio::test]
+    async fn mqtt_bridge_egress_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let grpc_addr = vec![broker_grpc_addr()];
+
+        let id = unique_id();
+        let src_topic = format!("/bridge_egress_src/{}", id);
+        let remote_topic = format!("/bridge_remote{}", src_topic);
+        let connector_name = format!("mqtt_bridge_{}", id);
+
+        let config = MqttBridgeConnectorConfig {
+            server: broker_addr(),
+            client_id: build_client_id("mqtt_bridge_egress"),
+            username: username(),
+            password: password(),
+            direction: MqttBridgeDirection::Egress,
+            remote_topic_prefix: "/bridge_remote".to_string(),
+            ..Default::default()
+        };
+        create_bridge(
+            &client_pool,
+            &grpc_addr,
+            &connector_name,
+            &src_topic,
+            &config,
+        )
+        .await;
+
+        let sub_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_egress_sub"),
+            addr: broker_addr(),
+            ..Default::default()
+        });
+        let rx = sub_cli.start_consuming();
+        sub_cli.subscribe(&remote_topic, 1).unwrap();
+
+        let pub_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_egress_pub"),
+            addr: broker_addr(),
+            ..Default::default()
+        });
+
+        let msg = publish_until_received(&pub_cli, &rx, &src_topic, "egress message", None);
+        assert_eq!(msg.topic(), remote_topic);
+        assert_eq!(msg.payload_str(), "egress message");
+        assert!(msg
+            .properties()
+            .user_iter()
+            .any(|(key, _)| key == BRIDGE_ORIGIN_PROPERTY));
+
+        distinct_conn(pub_cli);
+        distinct_conn(sub_cli);
+        delete_bridge(&client_pool, &grpc_addr, connector_name).await;
+    }
+
+    // The bridge points back at this broker and subscribes to the very topic it
+    // forwards to, so every forwarded message comes straight back on ingress.
+    #[tokio::test]
+    async fn mqtt_bridge_loop_prevention_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let grpc_addr = vec![broker_grpc_addr()];
+
+        let id = unique_id();
+        let src_topic = format!("/bridge_loop_src/{}", id);
+        let remote_topic = format!("/bridge_loop_remote{}", src_topic);
+        let local_topic = format!("/bridge_loop_local{}", remote_topic);
+        let connector_name = format!("mqtt_bridge_{}", id);
+
+        let config = MqttBridgeConnectorConfig {
+            server: broker_addr(),
+            client_id: build_client_id("mqtt_bridge_loop"),
+            username: username(),
+            password: password(),
+            direction: MqttBridgeDirection::Both,
+            remote_topic_prefix: "/bridge_loop_remote".to_string(),
+            subscribe_topics: vec![remote_topic.clone()],
+            local_topic_prefix: "/bridge_loop_local".to_string(),
+            ..Default::default()
+        };
+        create_bridge(
+            &client_pool,
+            &grpc_addr,
+            &connector_name,
+            &src_topic,
+            &config,
+        )
+        .await;
+
+        let remote_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_loop_remote_sub"),
+            addr: broker_addr(),
+            ..Default::default()
+        });
+        let remote_rx = remote_cli.start_consuming();
+        remote_cli.subscribe(&remote_topic, 1).unwrap();
+
+        let local_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_loop_local_sub"),
+            addr: broker_addr(),
+            ..Default::default()
+        });
+        let local_rx = local_cli.start_consuming();
+        local_cli.subscribe(&local_topic, 1).unwrap();
+
+        let pub_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_loop_pub"),
+            addr: broker_addr(),
+            ..Default::default()
+        });
+
+        // Wait until both directions are running.
+        publish_until_received(&pub_cli, &remote_rx, &src_topic, "egress ready", None);
+        publish_until_received(&pub_cli, &local_rx, &remote_topic, "ingress ready", None);
+
+        // Egress stamps this cluster as origin, so ingress has to drop the
+        // message when it comes back.
+        let msg = MessageBuilder::new()
+            .payload("looped")
+            .topic(src_topic.clone())
+            .qos(1)
+            .finalize();
+        publish_data(&pub_cli, msg, false);
+
+        // Egress must not forward messages that were already bridged.
+        let mut props = Properties::new();
+        props
+            .push_string_pair(PropertyCode::UserProperty, BRIDGE_ORIGIN_PROPERTY, "other")
+            .unwrap();
+        let msg = MessageBuilder::new()
+            .payload("bridged")
+            .topic(src_topic.clone())
+            .properties(props)
+            .qos(1)
+            .finalize();
+        publish_data(&pub_cli, msg, false);
+
+        let msg = MessageBuilder::new()
+            .payload("last")
+            .topic(src_topic.clone())
+            .qos(1)
+            .finalize();
+        publish_data(&pub_cli, msg, false);
+
+        let mut remote_payloads = Vec::new();
+        while let Ok(Some(msg)) = remote_rx.recv_timeout(Duration::from_secs(3)) {
+            remote_payloads.push(msg.payload_str().to_string());
+        }
+        assert!(remote_payloads.iter().any(|payload| payload == "looped"));
+        assert!(remote_payloads.iter().any(|payload| payload == "last"));
+        assert!(!remote_payloads.iter().any(|payload| payload == "bridged"));
+
+        while let Ok(Some(msg)) = local_rx.recv_timeout(Duration::from_secs(1)) {
+            assert_eq!(msg.payload_str(), "ingress ready");
+        }
+
+        distinct_conn(pub_cli);
+        distinct_conn(remote_cli);
+        distinct_conn(local_cli);
+        delete_bridge(&client_pool, &grpc_addr, connector_name).await;
+    }
+
+    // Two remote sites run as brokers inside the test process. Site A bridges into
+    // this cluster and this cluster bridges the same topic on to site B, so a
+    // message that already went through a bridge must stop here.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn mqtt_bridge_two_sites_loop_prevention_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let grpc_addr = vec![broker_grpc_addr()];
+        let site_a = start_site_broker().await;
+        let site_b = start_site_broker().await;
+
+        let id = unique_id();
+        let site_a_topic = format!("/bridge_site_a/{}", id);
+        let local_topic = format!("/bridge_from_a{}", site_a_topic);
+        let site_b_topic = format!("/bridge_to_b{}", local_topic);
+        let ingress_name = format!("mqtt_bridge_a_{}", id);
+        let egress_name = format!("mqtt_bridge_b_{}", id);
+
+        let ingress_config = MqttBridgeConnectorConfig {
+            server: site_a.clone(),
+            client_id: build_client_id("mqtt_bridge_site_a"),
+            direction: MqttBridgeDirection::Ingress,
+            subscribe_topics: vec![site_a_topic.clone()],
+            local_topic_prefix: "/bridge_from_a".to_string(),
+            ..Default::default()
+        };
+        create_bridge(
+            &client_pool,
+            &grpc_addr,
+            &ingress_name,
+            &local_topic,
+            &ingress_config,
+        )
+        .await;
+
+        let egress_config = MqttBridgeConnectorConfig {
+            server: site_b.clone(),
+            client_id: build_client_id("mqtt_bridge_site_b"),
+            direction: MqttBridgeDirection::Egress,
+            remote_topic_prefix: "/bridge_to_b".to_string(),
+            ..Default::default()
+        };
+        create_bridge(
+            &client_pool,
+            &grpc_addr,
+            &egress_name,
+            &local_topic,
+            &egress_config,
+        )
+        .await;
+
+        let site_a_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_site_a_pub"),
+            addr: site_a.clone(),
+            ..Default::default()
+        });
+        let site_b_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_site_b_sub"),
+            addr: site_b.clone(),
+            ..Default::default()
+        });
+        let site_b_rx = site_b_cli.start_consuming();
+        site_b_cli.subscribe(&site_b_topic, 1).unwrap();
+
+        let local_cli = connect_server(&ClientTestProperties {
+            mqtt_version: 5,
+            client_id: build_client_id("mqtt_bridge_site_local"),
+            addr: broker_addr(),
+            ..Default::default()
+        });
+        let local_rx = local_cli.start_consuming();
+        local_cli.subscribe(&local_topic, 1).unwrap();
+
+        // Wait until both bridges are running, the local message reaching site B
+        // also shows the egress bridge is live.
+        let msg = publish_until_received(&site_a_cli, &local_rx, &site_a_topic, "from a", None);
+        assert!(msg
+            .properties()
+            .user_iter()
+            .any(|(key, value)| key == BRIDGE_ORIGIN_PROPERTY && value == site_a));
+        publish_until_received(&local_cli, &site_b_rx, &local_topic, "local", None);
+
+        // Already bridged by a third site, ingress has to drop it.
+        let mut props = Properties::new();
+        props
+            .push_string_pair(PropertyCode::UserProperty, BRIDGE_ORIGIN_PROPERTY, "site-c")
+            .unwrap();
+        let msg = MessageBuilder::new()
+            .payload("from c")
+            .topic(site_a_topic.clone())
+            .properties(props)
+            .qos(1)
+            .finalize();
+        publish_data(&site_a_cli, msg, false);
+
+        // Bridged in from site A, egress must not pass it on to site B.
+        let msg = MessageBuilder::new()
+            .payload("from a again")
+            .topic(site_a_topic.clone())
+            .qos(1)
+            .finalize();
+        publish_data(&site_a_cli, msg, false);
+
+        let mut local_payloads = Vec::new();
+        while let Ok(Some(msg)) = local_rx.recv_timeout(Duration::from_secs(3)) {
+            local_payloads.push(msg.payload_str().to_string());
+        }
+        assert!(local_payloads
+            .iter()
+            .any(|payload| payload == "from a again"));
+        assert!(!local_payloads.iter().any(|payload| payload == "from c"));
+
+        while let Ok(Some(msg)) = site_b_rx.recv_timeout(Duration::from_secs(1)) {
+            assert_eq!(msg.payload_str(), "local");
+        }
+
+        distinct_conn(site_a_cli);
+        distinct_conn(site_b_cli);
+        distinct_conn(local_cli);
+        delete_bridge(&client_pool, &grpc_addr, ingress_name).await;
+        delete_bridge(&client_pool, &grpc_addr, egress_name).await;
+    }
+
+    type SiteSubscribers = Arc<DashMap<String, Vec<mpsc::UnboundedSender<MqttPacket>>>>;
+
+    // A minimal MQTT broker standing in for a remote site. Publishes are fanned out
+    // with QoS 0 to the clients subscribed to exactly that topic.
+    async fn start_site_broker() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("tcp://{}", listener.local_addr().unwrap());
+        let subscribers: SiteSubscribers = Arc::new(DashMap::new());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_site_connection(stream, subscribers.clone()));
+            }
+        });
+        addr
+    }
+
+    async fn serve_site_connection(stream: TcpStream, subscribers: SiteSubscribers) {
+        let (mut write, mut read) = Framed::new(stream, MqttCodec::new(None)).split();
+        let (sx, mut rx) = mpsc::unbounded_channel::<MqttPacket>();
+        let mut protocol_version = 5;
+        loop {
+            let packet = select! {
+                val = rx.recv() => {
+                    let Some(packet) = val else {
+                        break;
+                    };
+                    packet
+                }
+                val = read.next() => {
+                    let Some(Ok(packet)) = val else {
+                        break;
+                    };
+                    match packet {
+                        MqttPacket::Connect(version, ..) => {
+                            protocol_version = version;
+                            MqttPacket::ConnAck(
+                                ConnAck {
+                                    session_present: false,
+                                    code: ConnectReturnCode::Success,
+                                },
+                                Some(ConnAckProperties::default()),
+                            )
+                        }
+                        MqttPacket::Subscribe(subscribe, _) => {
+                            for filter in subscribe.filters.iter() {
+                                subscribers
+                                    .entry(filter.path.clone())
+                                    .or_default()
+                                    .push(sx.clone());
+                            }
+                            MqttPacket::SubAck(
+                                SubAck {
+                                    pkid: subscribe.packet_identifier,
+                                    return_codes: vec![
+                                        SubscribeReasonCode::QoS0;
+                                        subscribe.filters.len()
+                                    ],
+                                },
+                                Some(SubAckProperties::default()),
+                            )
+                        }
+                        MqttPacket::Publish(publish, properties) => {
+                            let topic = String::from_utf8_lossy(&publish.topic).to_string();
+                            if let Some(list) = subscribers.get(&topic) {
+                                for sub in list.iter() {
+                                    let forward = Publish {
+                                        qos: QoS::AtMostOnce,
+                                        pkid: 0,
+                                        ..publish.clone()
+                                    };
+                                    let _ = sub.send(MqttPacket::Publish(forward, properties.clone()));
+                                }
+                            }
+                            if publish.qos == QoS::AtMostOnce {
+                                continue;
+                            }
+                            MqttPacket::PubAck(
+                                PubAck {
+                                    pkid: publish.pkid,
+                                    reason: Some(PubAckReason::Success),
+                                },
+                                None,
+                            )
+                        }
+                        MqttPacket::PingReq(_) => MqttPacket::PingResp(PingResp {}),
+                        MqttPacket::Disconnect(..) => break,
+                        _ => continue,
+                    }
+                }
+            };
+
+            let wrapper = MqttPacketWrapper {
+                protocol_version,
+                packet,
+            };
+            if write.send(wrapper).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    async fn create_bridge(
+        client_pool: &Arc<ClientPool>,
+        grpc_addr: &[String],
+        connector_name: &str,
+        topic_id: &str,
+        config: &MqttBridgeConnectorConfig,
+    ) {
+        let request = MqttCreateConnectorRequest {
+            connector_name: connector_name.to_string(),
+            connector_type: MqttConnectorType::Mqtt as i32,
+            config: serde_json::to_string(config).unwrap(),
+            topic_id: topic_id.to_string(),
+        };
+        mqtt_broker_create_connector(client_pool, grpc_addr, request)
+            .await
+            .unwrap();
+    }
+
+    async fn delete_bridge(
+        client_pool: &Arc<ClientPool>,
+        grpc_addr: &[String],
+        connector_name: String,
+    ) {
+        let request = MqttDeleteConnectorRequest { connector_name };
+        mqtt_broker_delete_connector(client_pool, grpc_addr, request)
+            .await
+            .unwrap();
+    }
+
+    // Keeps publishing until the connector has been scheduled and the message
+    // shows up on rx.
+    fn publish_until_received(
+        cli: &Client,
+        rx: &Receiver<Option<Message>>,
+        topic: &str,
+        payload: &str,
+        props: Option<Properties>,
+    ) -> Message {
+        for _ in 0..30 {
+            let mut builder = MessageBuilder::new().payload(payload).topic(topic).qos(1);
+            if let Some(props) = &props {
+                builder = builder.properties(props.clone());
+            }
+            publish_data(cli, builder.finalize(), false);
+
+            if let Ok(Some(msg)) = rx.recv_timeout(Duration::from_secs(1)) {
+                return msg;
+            }
+        }
+        panic!("message on {} was not received", topic);
+    }
+}
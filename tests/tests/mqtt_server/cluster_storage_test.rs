@@ -31,7 +31,7 @@ async fn cluster_node_test() {
         let client_pool: Arc<ClientPool> = Arc::new(ClientPool::new(10));
         let cluster_storage = ClusterStorage::new(client_pool);
 
-        let mut config = broker_mqtt_conf().clone();
+        let mut config = (*broker_mqtt_conf()).clone();
         config.broker_id = 1234u64;
         cluster_storage.register_node(&config).await.unwrap();
 